@@ -130,10 +130,9 @@ impl Party {
         println!(
             "Party #{}: {}",
             self.party_id,
-            if signature.is_some() {
-                format!("lottery #{:?} won", signature.as_ref().unwrap().indexes)
-            } else {
-                "lost all lotteries".to_string()
+            match &signature {
+                Some(signature) => format!("lottery #{:?} won", signature.indexes),
+                None => "lost all lotteries".to_string(),
             }
         );
         signature
@@ -12,7 +12,7 @@ pub type FileContent = String;
 
 /// In memory representation of a folder containing data imported using the `scripts/import.sh` script
 /// of the fake aggregator.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct FakeAggregatorData {
     epoch_settings: FileContent,
 
@@ -28,15 +28,21 @@ pub struct FakeAggregatorData {
     ctx_snapshots_list: FileContent,
     individual_ctx_snapshots: BTreeMap<ArtifactId, FileContent>,
     ctx_proofs: BTreeMap<ArtifactId, FileContent>,
+
+    cardano_blocks_list: FileContent,
+    individual_cardano_blocks: BTreeMap<ArtifactId, FileContent>,
 }
 
+/// Prefix of the files containing a single Cardano block, the rest of the file name is the block hash.
+const CARDANO_BLOCK_FILE_PREFIX: &str = "cardano-block-";
+
 impl FakeAggregatorData {
     pub fn load_from_folder(folder: &Path) -> Self {
         let mut data = FakeAggregatorData::default();
 
         for entry in list_json_files_in_folder(folder) {
             let filename = entry.file_name().to_string_lossy().to_string();
-            let file_content = fs::read_to_string(&entry.path()).unwrap_or_else(|_| {
+            let file_content = fs::read_to_string(entry.path()).unwrap_or_else(|_| {
                 panic!(
                     "Could not read file content, file_path: {}",
                     entry.path().display()
@@ -74,6 +80,17 @@ impl FakeAggregatorData {
                 "ctx-proofs.json" => {
                     data.ctx_proofs = Self::read_artifacts_json_file(&entry.path());
                 }
+                "cardano-blocks.json" => {
+                    data.cardano_blocks_list = file_content;
+                }
+                _ if filename.starts_with(CARDANO_BLOCK_FILE_PREFIX) => {
+                    let block_hash = filename
+                        .trim_start_matches(CARDANO_BLOCK_FILE_PREFIX)
+                        .trim_end_matches(".json")
+                        .to_string();
+                    data.individual_cardano_blocks
+                        .insert(block_hash, file_content);
+                }
                 // unknown file
                 _ => {}
             }
@@ -105,6 +122,10 @@ impl FakeAggregatorData {
                     "proof_transaction_hashes",
                     BTreeSet::from_iter(self.ctx_proofs.keys().cloned()),
                 ),
+                generate_ids_array(
+                    "cardano_block_hashes",
+                    BTreeSet::from_iter(self.individual_cardano_blocks.keys().cloned()),
+                ),
             ],
             false,
         )
@@ -143,11 +164,88 @@ impl FakeAggregatorData {
                     BTreeSet::from_iter(self.ctx_proofs.keys().cloned()),
                 ),
                 generate_artifact_getter("ctx_proofs", self.ctx_proofs),
+                generate_ids_array(
+                    "cardano_block_hashes",
+                    BTreeSet::from_iter(self.individual_cardano_blocks.keys().cloned()),
+                ),
+                generate_artifact_getter("cardano_blocks", self.individual_cardano_blocks),
+                generate_list_getter("cardano_blocks_list", self.cardano_blocks_list),
             ],
             true,
         )
     }
 
+    /// Check that the given code, generated by this type, can be included as is in a Rust
+    /// source file.
+    ///
+    /// Each raw string literal must be closed by its delimiter right before the end of its
+    /// artifact tuple or getter function: an embedded json containing the delimiter (`"#` for a
+    /// `r#"` literal) would close it earlier. Each function signature must be one of the
+    /// signatures of the generated getters.
+    ///
+    /// All the problems found are returned.
+    pub fn verify_generated_code(code: &str) -> Result<(), Vec<String>> {
+        let mut errors = vec![];
+        let mut code_outside_literals = String::new();
+        let mut cursor = 0;
+        let line_of = |offset: usize| code[..offset].matches('\n').count() + 1;
+
+        while let Some((opening, nb_hashes)) = find_raw_string_opening(&code[cursor..]) {
+            let opening = cursor + opening;
+            let content_start = opening + nb_hashes + 2;
+            let delimiter = format!("\"{}", "#".repeat(nb_hashes));
+            code_outside_literals.push_str(&code[cursor..content_start]);
+
+            let mut closings = code[content_start..]
+                .match_indices(&delimiter)
+                .map(|(index, _)| content_start + index + delimiter.len());
+            let Some(first_closing) = closings.next() else {
+                errors.push(format!(
+                    "Raw string literal opened at line {} is never closed",
+                    line_of(opening)
+                ));
+                cursor = code.len();
+                break;
+            };
+            let is_literal_end =
+                |closing: &usize| code[*closing..].trim_start().starts_with([')', '}']);
+
+            cursor = if is_literal_end(&first_closing) {
+                first_closing
+            } else {
+                errors.push(format!(
+                    "Raw string literal opened at line {} contains its delimiter `{delimiter}` at line {}",
+                    line_of(opening),
+                    line_of(first_closing),
+                ));
+                match closings.find(is_literal_end) {
+                    Some(closing) => closing,
+                    None => {
+                        cursor = code.len();
+                        break;
+                    }
+                }
+            };
+            code_outside_literals.push_str(&delimiter);
+        }
+        code_outside_literals.push_str(&code[cursor..]);
+
+        for line in code_outside_literals
+            .lines()
+            .filter(|line| line.contains("fn "))
+        {
+            if !is_generated_getter_signature(line) {
+                errors.push(format!("Unexpected function signature: `{line}`"));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     fn assemble_code(functions_code: &[String], include_use_btree_map: bool) -> String {
         format!(
             "{}{}
@@ -189,6 +287,47 @@ fn extract_artifact_id_and_content(
     Ok((key.to_owned(), json_content))
 }
 
+/// Find the first raw string literal opening (`r#"`, `r##"`, ...) in the given code, return its
+/// position and its number of `#`.
+fn find_raw_string_opening(code: &str) -> Option<(usize, usize)> {
+    code.match_indices('r').find_map(|(index, _)| {
+        let after_r = &code[index + 1..];
+        let nb_hashes = after_r.len() - after_r.trim_start_matches('#').len();
+
+        (nb_hashes > 0 && after_r[nb_hashes..].starts_with('"')).then_some((index, nb_hashes))
+    })
+}
+
+fn is_generated_getter_signature(line: &str) -> bool {
+    let is_identifier = |name: &str| {
+        name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+
+    if let Some(name) = line.strip_prefix("pub(crate) fn ").and_then(|rest| {
+        rest.strip_suffix("() -> BTreeMap<String, String> {")
+            .or_else(|| rest.strip_suffix("() -> &'static str {"))
+    }) {
+        return is_identifier(name);
+    }
+
+    line.strip_prefix("pub(crate) const fn ")
+        .and_then(|rest| rest.strip_suffix("] {"))
+        .and_then(|rest| rest.split_once("<'a>() -> [&'a str; "))
+        .is_some_and(|(name, len)| is_identifier(name) && len.parse::<usize>().is_ok())
+}
+
+/// Raw string literal of the given content, with enough `#` so the content can't contain its
+/// closing delimiter.
+fn raw_string_literal(content: &str) -> String {
+    let nb_hashes = (1..)
+        .find(|nb_hashes| !content.contains(&format!("\"{}", "#".repeat(*nb_hashes))))
+        .unwrap();
+    let hashes = "#".repeat(nb_hashes);
+
+    format!("r{hashes}\"{content}\"{hashes}")
+}
+
 pub fn list_json_files_in_folder(folder: &Path) -> impl Iterator<Item = fs::DirEntry> + '_ {
     crate::list_files_in_folder(folder)
         .filter(|e| e.file_name().to_string_lossy().ends_with(".json"))
@@ -207,9 +346,10 @@ pub fn generate_artifact_getter(
             r###"
         (
             "{}",
-            r#"{}"#
+            {}
         ),"###,
-            artifact_id, file_content
+            artifact_id,
+            raw_string_literal(&file_content)
         )
         .unwrap();
     }
@@ -219,7 +359,7 @@ pub fn generate_artifact_getter(
     [{}
     ]
     .into_iter()
-    .map(|(k, v)| (k.to_owned(), v.to_owned()))
+    .map(|(k, v): (&str, &str)| (k.to_owned(), v.to_owned()))
     .collect()
 }}"###,
         fun_name, artifacts_list
@@ -230,9 +370,10 @@ pub fn generate_artifact_getter(
 pub fn generate_list_getter(fun_name: &str, source_json: FileContent) -> String {
     format!(
         r###"pub(crate) fn {}() -> &'static str {{
-    r#"{}"#
+    {}
 }}"###,
-        fun_name, source_json
+        fun_name,
+        raw_string_literal(&source_json)
     )
 }
 
@@ -263,6 +404,8 @@ pub fn generate_ids_array(array_name: &str, ids: BTreeSet<ArtifactId>) -> String
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use crate::get_temp_dir;
 
     use super::*;
@@ -358,4 +501,252 @@ fn b() {}
         ]);
         assert_eq!(expected, id_per_json);
     }
+
+    #[test]
+    fn load_cardano_block_files_into_individual_cardano_blocks() {
+        let dir = get_temp_dir("load_cardano_block_files_into_individual_cardano_blocks");
+        fs::write(
+            dir.join("cardano-block-hash1.json"),
+            r#"{ "hash": "hash1" }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("cardano-block-hash2.json"),
+            r#"{ "hash": "hash2" }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("cardano-blocks.json"), r#"[ "hash1", "hash2" ]"#).unwrap();
+
+        let data = FakeAggregatorData::load_from_folder(&dir);
+
+        assert_eq!(
+            BTreeMap::from([
+                ("hash1".to_string(), r#"{ "hash": "hash1" }"#.to_string()),
+                ("hash2".to_string(), r#"{ "hash": "hash2" }"#.to_string()),
+            ]),
+            data.individual_cardano_blocks
+        );
+        assert_eq!(r#"[ "hash1", "hash2" ]"#, data.cardano_blocks_list);
+    }
+
+    #[test]
+    fn generate_code_for_ids_include_cardano_block_hashes() {
+        let dir = get_temp_dir("generate_code_for_ids_include_cardano_block_hashes");
+        fs::write(
+            dir.join("cardano-block-hash1.json"),
+            r#"{ "hash": "hash1" }"#,
+        )
+        .unwrap();
+
+        let code = FakeAggregatorData::load_from_folder(&dir).generate_code_for_ids();
+
+        assert!(code.contains(
+            r#"pub(crate) const fn cardano_block_hashes<'a>() -> [&'a str; 1] {
+    [
+        "hash1",
+    ]
+}"#
+        ));
+    }
+
+    fn artifacts_strategy() -> impl Strategy<Value = BTreeMap<ArtifactId, FileContent>> {
+        prop::collection::btree_map("[a-f0-9]{1,16}", "[ -~]{0,32}", 0..5)
+    }
+
+    fn fake_aggregator_data_strategy() -> impl Strategy<Value = FakeAggregatorData> {
+        (
+            ("[ -~]{0,32}", "[ -~]{0,32}", "[ -~]{0,32}", "[ -~]{0,32}"),
+            ("[ -~]{0,32}", "[ -~]{0,32}"),
+            (
+                artifacts_strategy(),
+                artifacts_strategy(),
+                artifacts_strategy(),
+                artifacts_strategy(),
+                artifacts_strategy(),
+                artifacts_strategy(),
+            ),
+        )
+            .prop_map(
+                |(
+                    (epoch_settings, certificates_list, snapshots_list, msds_list),
+                    (ctx_snapshots_list, cardano_blocks_list),
+                    (
+                        individual_certificates,
+                        individual_snapshots,
+                        individual_msds,
+                        individual_ctx_snapshots,
+                        ctx_proofs,
+                        individual_cardano_blocks,
+                    ),
+                )| FakeAggregatorData {
+                    epoch_settings,
+                    certificates_list,
+                    individual_certificates,
+                    snapshots_list,
+                    individual_snapshots,
+                    msds_list,
+                    individual_msds,
+                    ctx_snapshots_list,
+                    individual_ctx_snapshots,
+                    ctx_proofs,
+                    cardano_blocks_list,
+                    individual_cardano_blocks,
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn generate_code_for_all_data_is_idempotent(data in fake_aggregator_data_strategy()) {
+            prop_assert_eq!(
+                data.clone().generate_code_for_all_data(),
+                data.generate_code_for_all_data()
+            );
+        }
+
+        #[test]
+        fn generated_code_for_any_json_content_is_valid(
+            data in fake_aggregator_data_strategy(),
+            json in r##"[ -~]{0,16}("#{1,3}[ -~]{0,16}){0,3}"##,
+        ) {
+            let mut data = data;
+            data.epoch_settings = json.clone();
+            data.individual_certificates.insert("abc".to_string(), json);
+
+            prop_assert_eq!(
+                Ok(()),
+                FakeAggregatorData::verify_generated_code(&data.generate_code_for_all_data())
+            );
+        }
+    }
+
+    #[test]
+    fn raw_string_literal_use_more_hashes_than_the_content_delimiters() {
+        assert_eq!(
+            r###"r#"{ "a": 1 }"#"###,
+            raw_string_literal(r#"{ "a": 1 }"#)
+        );
+        assert_eq!(
+            r####"r##"{ "a": "#1" }"##"####,
+            raw_string_literal(r##"{ "a": "#1" }"##)
+        );
+        assert_eq!(
+            r#####"r###"{ "a": "##1", "b": "#2" }"###"#####,
+            raw_string_literal(r###"{ "a": "##1", "b": "#2" }"###)
+        );
+    }
+
+    #[test]
+    fn verify_generated_code_accept_the_generated_code() {
+        let mut data = FakeAggregatorData {
+            certificates_list: r#"[{ "hash": "hash1" }]"#.to_string(),
+            ..FakeAggregatorData::default()
+        };
+        data.individual_certificates.insert(
+            "hash1".to_string(),
+            r##"{ "hash": "hash1", "comment": "#1" }"##.to_string(),
+        );
+
+        assert_eq!(
+            Ok(()),
+            FakeAggregatorData::verify_generated_code(&data.clone().generate_code_for_ids())
+        );
+        assert_eq!(
+            Ok(()),
+            FakeAggregatorData::verify_generated_code(&data.generate_code_for_all_data())
+        );
+    }
+
+    #[test]
+    fn verify_generated_code_reject_a_json_containing_the_literal_delimiter() {
+        let code = r###"pub(crate) fn certificates() -> BTreeMap<String, String> {
+    [
+        (
+            "hash1",
+            r#"{ "comment": "#1" }"#
+        ),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_owned(), v.to_owned()))
+    .collect()
+}"###;
+
+        let errors = FakeAggregatorData::verify_generated_code(code).unwrap_err();
+
+        assert_eq!(
+            vec![
+                r##"Raw string literal opened at line 5 contains its delimiter `"#` at line 5"##
+                    .to_string()
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn verify_generated_code_reject_a_raw_string_literal_never_closed() {
+        let code = r###"pub(crate) fn epoch_settings() -> &'static str {
+    r#"{ "epoch": 1 }
+}"###;
+
+        let errors = FakeAggregatorData::verify_generated_code(code).unwrap_err();
+
+        assert_eq!(
+            vec!["Raw string literal opened at line 2 is never closed".to_string()],
+            errors
+        );
+    }
+
+    #[test]
+    fn verify_generated_code_reject_unexpected_function_signatures() {
+        let code = r###"pub(crate) fn epoch_settings() -> String {
+    r#"{ "text": "fn main() {}" }"#
+}
+
+pub(crate) const fn certificate_hashes<'a>() -> [&'a str; 0] {
+    [
+    ]
+}"###;
+
+        let errors = FakeAggregatorData::verify_generated_code(code).unwrap_err();
+
+        assert_eq!(
+            vec![
+                "Unexpected function signature: `pub(crate) fn epoch_settings() -> String {`"
+                    .to_string()
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn generate_code_for_all_data_sort_artifacts_by_id_whatever_the_insertion_order() {
+        let mut data = FakeAggregatorData::default();
+        for id in ["hash3", "hash1", "hash2"] {
+            data.individual_certificates
+                .insert(id.to_string(), format!(r#"{{ "hash": "{id}" }}"#));
+        }
+
+        let code = data.generate_code_for_all_data();
+
+        assert!(code.contains(
+            r#"pub(crate) const fn certificate_hashes<'a>() -> [&'a str; 3] {
+    [
+        "hash1",
+        "hash2",
+        "hash3",
+    ]
+}"#
+        ));
+        let certificates_positions: Vec<usize> = ["hash1", "hash2", "hash3"]
+            .iter()
+            .map(|id| {
+                code.find(&format!(r##"r#"{{ "hash": "{id}" }}"#"##))
+                    .unwrap()
+            })
+            .collect();
+        assert!(
+            certificates_positions.windows(2).all(|w| w[0] < w[1]),
+            "certificates should be generated sorted by hash, positions: {certificates_positions:?}"
+        );
+    }
 }
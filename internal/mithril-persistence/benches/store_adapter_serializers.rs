@@ -0,0 +1,59 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use mithril_common::messages::CertificateMessage;
+use mithril_common::test_utils::fake_data;
+use mithril_persistence::store::adapter::{
+    BincodeSerializer, JsonSerializer, MessagePackSerializer, StoreAdapterSerializer,
+};
+
+const NB_CERTIFICATES: usize = 1000;
+
+fn certificate_list() -> Vec<CertificateMessage> {
+    (0..NB_CERTIFICATES)
+        .map(|index| {
+            fake_data::certificate(format!("certificate-hash-{index}"))
+                .try_into()
+                .unwrap()
+        })
+        .collect()
+}
+
+type CertificateListSerializer = Box<dyn StoreAdapterSerializer<Vec<CertificateMessage>>>;
+
+fn serializers() -> Vec<(&'static str, CertificateListSerializer)> {
+    vec![
+        ("json", Box::new(JsonSerializer::new())),
+        ("message_pack", Box::new(MessagePackSerializer::new())),
+        ("bincode", Box::new(BincodeSerializer::new())),
+    ]
+}
+
+fn serialize_certificate_list(c: &mut Criterion) {
+    let certificates = certificate_list();
+    let mut group = c.benchmark_group(format!("serialize {NB_CERTIFICATES} certificates"));
+    for (name, serializer) in serializers() {
+        group.bench_function(name, |b| {
+            b.iter(|| serializer.serialize(&certificates).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn deserialize_certificate_list(c: &mut Criterion) {
+    let certificates = certificate_list();
+    let mut group = c.benchmark_group(format!("deserialize {NB_CERTIFICATES} certificates"));
+    for (name, serializer) in serializers() {
+        let bytes = serializer.serialize(&certificates).unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| serializer.deserialize(&bytes).unwrap());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = serialize_certificate_list, deserialize_certificate_list
+);
+criterion_main!(benches);
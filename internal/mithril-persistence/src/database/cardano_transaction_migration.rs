@@ -83,6 +83,29 @@ create index block_number_transaction_hash_index on cardano_tx(block_number, tra
 -- remove all data from the block_range_root table since the order used to create them has changed
 delete from block_range_root;
 
+vacuum;
+"#,
+        ),
+        // Migration 7
+        // Add index on `slot_number` column of `cardano_tx` table
+        SqlMigration::new(
+            7,
+            r#"
+create index slot_number_index on cardano_tx(slot_number);
+"#,
+        ),
+        // Migration 8
+        // Add index on `block_hash` column of `cardano_tx` table
+        // Truncate `block_range_root` table since the block hash is now part of the leaves of
+        // the transactions Merkle trees
+        SqlMigration::new(
+            8,
+            r#"
+create index block_hash_index on cardano_tx(block_hash);
+
+-- remove all data from the block_range_root table since the leaves used to create them have changed
+delete from block_range_root;
+
 vacuum;
 "#,
         ),
@@ -1,6 +1,7 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use mithril_common::StdResult;
+use semver::Version;
 use sqlite::{Row, Value};
 use std::{
     cmp::Ordering,
@@ -54,6 +55,63 @@ pub struct DatabaseVersion {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Compatibility of a [DatabaseVersion] with another one, see [DatabaseVersion::is_compatible_with].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionCompatibility {
+    /// Both versions share the same major version
+    Compatible,
+
+    /// The other version is older: it can be read by upgrading it
+    BackwardCompatible,
+
+    /// The other version is newer: the caller may need to be upgraded
+    ForwardCompatible,
+
+    /// The versions belong to different applications
+    Incompatible,
+}
+
+impl DatabaseVersion {
+    /// Semver representation of the database version.
+    ///
+    /// The database version being an integer, it's the major part of the semver version
+    /// (ie: the version `3` is `3.0.0`).
+    pub fn semver_version(&self) -> StdResult<Version> {
+        let major = self
+            .version
+            .try_into()
+            .with_context(|| format!("Invalid database version: '{}'", self.version))?;
+
+        Ok(Version::new(major, 0, 0))
+    }
+
+    /// Check the compatibility of the other version with this one, following the semver rules
+    /// on the major version, without reading the database.
+    pub fn is_compatible_with(&self, other: &DatabaseVersion) -> VersionCompatibility {
+        if self.application_type != other.application_type {
+            return VersionCompatibility::Incompatible;
+        }
+
+        match other.version.cmp(&self.version) {
+            Ordering::Equal => VersionCompatibility::Compatible,
+            Ordering::Greater => VersionCompatibility::ForwardCompatible,
+            Ordering::Less => VersionCompatibility::BackwardCompatible,
+        }
+    }
+
+    /// Check if both versions share the same major and minor versions.
+    pub fn is_same_minor(&self, other: &DatabaseVersion) -> bool {
+        match (self.semver_version(), other.semver_version()) {
+            (Ok(version), Ok(other_version)) => {
+                self.application_type == other.application_type
+                    && version.major == other_version.major
+                    && version.minor == other_version.minor
+            }
+            _ => false,
+        }
+    }
+}
+
 impl SqLiteEntity for DatabaseVersion {
     fn hydrate(row: Row) -> Result<Self, HydrationError> {
         let version = row.read::<i64, _>(0);
@@ -230,4 +288,48 @@ returning db_version.version as version, db_version.application_type as applicat
             query.get_definition("true")
         )
     }
+
+    fn database_version(
+        application_type: ApplicationNodeType,
+        version: DbVersion,
+    ) -> DatabaseVersion {
+        DatabaseVersion {
+            version,
+            application_type,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn is_compatible_with_follows_major_version_rules() {
+        let version = database_version(ApplicationNodeType::Aggregator, 3);
+
+        assert_eq!(
+            VersionCompatibility::Compatible,
+            version.is_compatible_with(&database_version(ApplicationNodeType::Aggregator, 3))
+        );
+        assert_eq!(
+            VersionCompatibility::ForwardCompatible,
+            version.is_compatible_with(&database_version(ApplicationNodeType::Aggregator, 4))
+        );
+        assert_eq!(
+            VersionCompatibility::BackwardCompatible,
+            version.is_compatible_with(&database_version(ApplicationNodeType::Aggregator, 2))
+        );
+        assert_eq!(
+            VersionCompatibility::Incompatible,
+            version.is_compatible_with(&database_version(ApplicationNodeType::Signer, 3))
+        );
+    }
+
+    #[test]
+    fn is_same_minor_compares_major_and_minor_versions() {
+        let version = database_version(ApplicationNodeType::Signer, 3);
+
+        assert!(version.is_same_minor(&database_version(ApplicationNodeType::Signer, 3)));
+        assert!(!version.is_same_minor(&database_version(ApplicationNodeType::Signer, 4)));
+        assert!(!version.is_same_minor(&database_version(ApplicationNodeType::Aggregator, 3)));
+        assert!(!database_version(ApplicationNodeType::Signer, -1)
+            .is_same_minor(&database_version(ApplicationNodeType::Signer, -1)));
+    }
 }
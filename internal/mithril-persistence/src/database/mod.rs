@@ -11,11 +11,15 @@ mod version_checker;
 
 pub use db_version::*;
 pub use hydrator::Hydrator;
-pub use version_checker::{DatabaseVersionChecker, SqlMigration};
+pub use version_checker::{
+    DatabaseVersionChecker, MigrationAuditEntry, MigrationError, ReadonlyDatabaseVersionProvider,
+    ShadowMigrationConfig, SqlMigration,
+};
 
 /// Database version.
 pub type DbVersion = i64;
 
+/// Database helpers for the tests
 #[cfg(test)]
 pub mod test_helper {
     use sqlite::ConnectionThreadSafe;
@@ -2,7 +2,7 @@ use std::ops::Range;
 
 use sqlite::Value;
 
-use mithril_common::entities::{BlockNumber, BlockRange, TransactionHash};
+use mithril_common::entities::{BlockHash, BlockNumber, BlockRange, SlotNumber, TransactionHash};
 
 use crate::database::record::CardanoTransactionRecord;
 use crate::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
@@ -44,6 +44,15 @@ impl GetCardanoTransactionQuery {
         Self { condition }
     }
 
+    pub fn by_block_hash(block_hash: &BlockHash) -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "block_hash = ?*",
+                vec![Value::String(block_hash.to_owned())],
+            ),
+        }
+    }
+
     pub fn by_block_ranges(block_ranges: Vec<BlockRange>) -> Self {
         let mut condition = WhereCondition::default();
         for block_range in block_ranges {
@@ -72,6 +81,18 @@ impl GetCardanoTransactionQuery {
         Self { condition }
     }
 
+    pub fn between_slots(from_slot: SlotNumber, to_slot: SlotNumber) -> Self {
+        let condition = WhereCondition::new(
+            "slot_number between ?* and ?*",
+            vec![
+                Value::Integer(from_slot as i64),
+                Value::Integer(to_slot as i64),
+            ],
+        );
+
+        Self { condition }
+    }
+
     pub fn with_highest_block_number() -> Self {
         Self {
             condition: WhereCondition::new(
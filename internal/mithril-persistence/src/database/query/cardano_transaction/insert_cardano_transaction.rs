@@ -1,4 +1,4 @@
-use std::iter::repeat;
+use std::iter::repeat_n;
 
 use sqlite::Value;
 
@@ -22,9 +22,8 @@ impl InsertCardanoTransactionQuery {
     pub fn insert_many(transactions_records: Vec<CardanoTransactionRecord>) -> StdResult<Self> {
         let columns =
             "(transaction_hash, block_number, slot_number, block_hash, immutable_file_number)";
-        let values_columns: Vec<&str> = repeat("(?*, ?*, ?*, ?*, ?*)")
-            .take(transactions_records.len())
-            .collect();
+        let values_columns: Vec<&str> =
+            repeat_n("(?*, ?*, ?*, ?*, ?*)", transactions_records.len()).collect();
 
         let values: StdResult<Vec<Value>> =
             transactions_records
@@ -53,6 +53,33 @@ impl CardanoTransactionRepository {
             .fetch_collect(GetCardanoTransactionQuery::between_blocks(range))
     }
 
+    /// Return all the [CardanoTransactionRecord]s in the database where slot number is in the
+    /// given inclusive range.
+    pub async fn get_transactions_in_slot_range(
+        &self,
+        from_slot: SlotNumber,
+        to_slot: SlotNumber,
+    ) -> StdResult<Vec<CardanoTransactionRecord>> {
+        self.connection_pool
+            .connection()?
+            .fetch_collect(GetCardanoTransactionQuery::between_slots(
+                from_slot, to_slot,
+            ))
+    }
+
+    /// Return all the [CardanoTransactionRecord]s in the database included in the block with
+    /// the given hash.
+    pub async fn get_transactions_by_block_hash<T: Into<BlockHash>>(
+        &self,
+        block_hash: T,
+    ) -> StdResult<Vec<CardanoTransactionRecord>> {
+        self.connection_pool
+            .connection()?
+            .fetch_collect(GetCardanoTransactionQuery::by_block_hash(
+                &block_hash.into(),
+            ))
+    }
+
     /// Return the [CardanoTransactionRecord] for the given transaction hash.
     pub async fn get_transaction<T: Into<TransactionHash>>(
         &self,
@@ -743,6 +770,73 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn repository_get_transactions_in_slot_range() {
+        let connection = cardano_tx_db_connection().unwrap();
+        let repository = CardanoTransactionRepository::new(Arc::new(
+            SqliteConnectionPool::build_from_connection(connection),
+        ));
+
+        let transactions = vec![
+            CardanoTransactionRecord::new("tx-hash-1", 10, 1, "block-hash-1", 99),
+            CardanoTransactionRecord::new("tx-hash-2", 11, 5, "block-hash-2", 100),
+            CardanoTransactionRecord::new("tx-hash-3", 12, 10, "block-hash-3", 101),
+            CardanoTransactionRecord::new("tx-hash-4", 13, 20, "block-hash-4", 102),
+        ];
+        repository
+            .create_transactions(transactions.clone())
+            .await
+            .unwrap();
+
+        let transaction_result = repository
+            .get_transactions_in_slot_range(3, 12)
+            .await
+            .unwrap();
+        assert_eq!(transactions[1..=2].to_vec(), transaction_result);
+
+        let transaction_result = repository
+            .get_transactions_in_slot_range(5, 10)
+            .await
+            .unwrap();
+        assert_eq!(transactions[1..=2].to_vec(), transaction_result);
+
+        let transaction_result = repository
+            .get_transactions_in_slot_range(21, 30)
+            .await
+            .unwrap();
+        assert_eq!(Vec::<CardanoTransactionRecord>::new(), transaction_result);
+    }
+
+    #[tokio::test]
+    async fn repository_get_transactions_by_block_hash() {
+        let connection = cardano_tx_db_connection().unwrap();
+        let repository = CardanoTransactionRepository::new(Arc::new(
+            SqliteConnectionPool::build_from_connection(connection),
+        ));
+
+        let transactions = vec![
+            CardanoTransactionRecord::new("tx-hash-1", 10, 50, "block-hash-10", 99),
+            CardanoTransactionRecord::new("tx-hash-2", 10, 50, "block-hash-10", 99),
+            CardanoTransactionRecord::new("tx-hash-3", 11, 51, "block-hash-11", 100),
+        ];
+        repository
+            .create_transactions(transactions.clone())
+            .await
+            .unwrap();
+
+        let transaction_result = repository
+            .get_transactions_by_block_hash("block-hash-10")
+            .await
+            .unwrap();
+        assert_eq!(transactions[0..=1].to_vec(), transaction_result);
+
+        let transaction_result = repository
+            .get_transactions_by_block_hash("block-hash-unknown")
+            .await
+            .unwrap();
+        assert_eq!(Vec::<CardanoTransactionRecord>::new(), transaction_result);
+    }
+
     #[tokio::test]
     async fn repository_get_block_interval_without_block_range_root() {
         let connection = cardano_tx_db_connection().unwrap();
@@ -1,15 +1,54 @@
 use anyhow::{anyhow, Context};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use mithril_common::StdResult;
+use semver::{Version, VersionReq};
 use slog::{debug, error, info, Logger};
-use std::{cmp::Ordering, collections::BTreeSet};
+use sqlite::{State, Value};
+use std::{
+    cmp::Ordering,
+    collections::BTreeSet,
+    time::{Duration, Instant},
+};
+use thiserror::Error;
 
 use super::{
     ApplicationNodeType, DatabaseVersion, DbVersion, GetDatabaseVersionQuery,
     UpdateDatabaseVersionQuery,
 };
 
-use crate::sqlite::{ConnectionExtensions, SqliteConnection};
+use crate::sqlite::{ConnectionExtensions, HydrationError, SqliteConnection};
+
+/// Error raised when checking the database version of an application.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MigrationError {
+    /// The application has no database version stored
+    #[error("No database version stored for application '{0}'")]
+    VersionNotFound(ApplicationNodeType),
+
+    /// The stored database version does not satisfy the version requirement
+    #[error("Database version '{stored}' does not satisfy the requirement '{requirement}'")]
+    VersionOutOfRange {
+        /// Stored database version
+        stored: Version,
+
+        /// Version requirement that was checked
+        requirement: VersionReq,
+    },
+
+    /// A [pre][SqlMigration::with_pre_hook] or [post][SqlMigration::with_post_hook] migration
+    /// hook failed
+    #[error("The {phase}-hook of migration '{migration_version}' failed: {error}")]
+    HookFailed {
+        /// Phase of the failing hook, `pre` or `post`
+        phase: &'static str,
+
+        /// Version of the migration of the failing hook
+        migration_version: DbVersion,
+
+        /// Error raised by the hook
+        error: String,
+    },
+}
 
 /// Struct to perform application version check in the database.
 pub struct DatabaseVersionChecker<'conn> {
@@ -24,6 +63,54 @@ pub struct DatabaseVersionChecker<'conn> {
 
     /// known migrations
     migrations: BTreeSet<SqlMigration>,
+
+    /// if set, the queries are run with the `query_only` pragma enabled
+    read_only: bool,
+
+    /// active feature flags, migrations with a flag that is not in this set are skipped
+    feature_flags: BTreeSet<String>,
+
+    /// settings of the copy of the rows of the [shadow migrations][SqlMigration::as_shadow_migration]
+    shadow_config: ShadowMigrationConfig,
+
+    /// who triggered the migrations, recorded in the migrations audit log
+    triggered_by: String,
+}
+
+/// Entry of the audit log of the migrations applied to the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationAuditEntry {
+    /// Version of the applied migration
+    pub migration_version: DbVersion,
+
+    /// Date and time at which the migration was applied
+    pub run_at: DateTime<Utc>,
+
+    /// Who triggered the migration
+    pub triggered_by: String,
+
+    /// Duration of the migration, in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Settings of the copy of the rows of a table rewritten by a
+/// [shadow migration][SqlMigration::as_shadow_migration].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShadowMigrationConfig {
+    /// Number of rows copied at once to the shadow table
+    pub batch_size: usize,
+
+    /// Pause between two batches, to let the other connections access the database
+    pub sleep_between_batches: Duration,
+}
+
+impl Default for ShadowMigrationConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 1000,
+            sleep_between_batches: Duration::ZERO,
+        }
+    }
 }
 
 impl<'conn> DatabaseVersionChecker<'conn> {
@@ -37,10 +124,143 @@ impl<'conn> DatabaseVersionChecker<'conn> {
 
         Self {
             connection,
+            triggered_by: application_type.to_string(),
             application_type,
             logger,
             migrations,
+            read_only: false,
+            feature_flags: BTreeSet::new(),
+            shadow_config: ShadowMigrationConfig::default(),
+        }
+    }
+
+    /// Set who triggers the migrations, recorded in the migrations audit log.
+    ///
+    /// Defaults to the application type.
+    pub fn with_triggered_by(mut self, triggered_by: &str) -> Self {
+        self.triggered_by = triggered_by.to_string();
+        self
+    }
+
+    /// Set the settings used to copy the rows of the tables rewritten by the
+    /// [shadow migrations][SqlMigration::as_shadow_migration].
+    pub fn with_shadow_config(mut self, config: ShadowMigrationConfig) -> Self {
+        self.shadow_config = config;
+        self
+    }
+
+    /// Create a checker that also applies the migrations guarded by one of the given
+    /// feature flags.
+    ///
+    /// A guarded migration is only applied if its flag is active when the database reaches its
+    /// version: activating the flag once the database is at a higher version won't apply it.
+    pub fn new_with_flags(
+        logger: Logger,
+        application_type: ApplicationNodeType,
+        connection: &'conn SqliteConnection,
+        feature_flags: BTreeSet<String>,
+    ) -> Self {
+        Self {
+            feature_flags,
+            ..Self::new(logger, application_type, connection)
+        }
+    }
+
+    /// Create a checker that never writes to the database.
+    ///
+    /// The `query_only` pragma is enabled before each operation and restored afterward, so any
+    /// attempt to alter the database (ie: [applying migrations][Self::apply]) fails.
+    pub fn new_readonly(
+        logger: Logger,
+        application_type: ApplicationNodeType,
+        connection: &'conn SqliteConnection,
+    ) -> Self {
+        Self {
+            read_only: true,
+            ..Self::new(logger, application_type, connection)
+        }
+    }
+
+    /// Read the database version of the application, `None` if it was never set.
+    pub fn get_application_version(&self) -> StdResult<Option<DatabaseVersion>> {
+        self.run(|| {
+            if !db_version_table_exists(self.connection)? {
+                return Ok(None);
+            }
+
+            self.connection
+                .fetch_first(GetDatabaseVersionQuery::get_application_version(
+                    &self.application_type,
+                ))
+                .with_context(|| "Can not get application version")
+        })
+    }
+
+    /// List the migrations applied to the database, in the order they were applied.
+    pub fn list_audit_entries(&self) -> StdResult<Vec<MigrationAuditEntry>> {
+        self.run(|| {
+            if !migration_audit_table_exists(self.connection)? {
+                return Ok(vec![]);
+            }
+
+            let mut statement = self.connection.prepare(
+                "select migration_version, run_at, triggered_by, duration_ms from migration_audit order by rowid",
+            )?;
+            let mut entries = vec![];
+            while let State::Row = statement.next()? {
+                let run_at = statement.read::<String, _>(1)?;
+                entries.push(MigrationAuditEntry {
+                    migration_version: statement.read::<i64, _>(0)?,
+                    run_at: DateTime::parse_from_rfc3339(&run_at)
+                        .with_context(|| {
+                            format!("Could not parse the migration audit date '{run_at}'")
+                        })?
+                        .with_timezone(&Utc),
+                    triggered_by: statement.read::<String, _>(2)?,
+                    duration_ms: statement.read::<i64, _>(3)? as u64,
+                });
+            }
+
+            Ok(entries)
+        })
+    }
+
+    /// Check that the database version of the application satisfies the given requirement.
+    ///
+    /// The database version being an integer, it's compared as the major part of a semver
+    /// version (ie: the version `3` is checked as `3.0.0`).
+    pub fn check_range(&self, range: &VersionReq) -> StdResult<()> {
+        let db_version = self
+            .get_application_version()?
+            .ok_or_else(|| MigrationError::VersionNotFound(self.application_type.clone()))?;
+        let stored = db_version.semver_version()?;
+
+        if !range.matches(&stored) {
+            return Err(MigrationError::VersionOutOfRange {
+                stored,
+                requirement: range.clone(),
+            }
+            .into());
         }
+
+        Ok(())
+    }
+
+    /// Run the given operation, enabling the `query_only` pragma first if this checker is
+    /// read-only.
+    fn run<T>(&self, operation: impl FnOnce() -> StdResult<T>) -> StdResult<T> {
+        if !self.read_only {
+            return operation();
+        }
+
+        let was_query_only = is_query_only(self.connection)?;
+        self.connection.execute("pragma query_only = on")?;
+        let result = operation();
+        if !was_query_only {
+            self.connection.execute("pragma query_only = off")?;
+        }
+
+        result
     }
 
     /// Register a migration.
@@ -52,6 +272,10 @@ impl<'conn> DatabaseVersionChecker<'conn> {
 
     /// Apply migrations
     pub fn apply(&self) -> StdResult<()> {
+        self.run(|| self.apply_unchecked())
+    }
+
+    fn apply_unchecked(&self) -> StdResult<()> {
         debug!(&self.logger, "check database version",);
         self.create_table_if_not_exists(&self.application_type)
             .with_context(|| "Can not create table 'db_version' while applying migrations")?;
@@ -66,15 +290,32 @@ impl<'conn> DatabaseVersionChecker<'conn> {
         // the current database version is equal to the maximum migration
         // version present in this software.
         // If no migration registered then version = 0.
-        let migration_version = self.migrations.iter().map(|m| m.version).max().unwrap_or(0);
+        let known_version = self.migrations.iter().map(|m| m.version).max().unwrap_or(0);
+        // Skipped migrations don't upgrade the database version, so it may be lower than the
+        // known version once all the active migrations are applied.
+        let migration_version = self
+            .active_migrations()
+            .map(|m| m.version)
+            .max()
+            .unwrap_or(0);
 
-        match migration_version.cmp(&db_version.version) {
+        let ordering = match known_version.cmp(&db_version.version) {
+            Ordering::Less => Ordering::Less,
+            _ => migration_version
+                .cmp(&db_version.version)
+                .max(Ordering::Equal),
+        };
+
+        match ordering {
             Ordering::Greater => {
                 debug!(
                     &self.logger,
                     "Database needs upgrade from version '{}' to version '{}', applying new migrations…",
                     db_version.version, migration_version
                 );
+                self.create_audit_table_if_not_exists().with_context(|| {
+                    "Can not create table 'migration_audit' while applying migrations"
+                })?;
                 self.apply_migrations(&db_version, self.connection)?;
                 info!(
                     &self.logger,
@@ -86,7 +327,7 @@ impl<'conn> DatabaseVersionChecker<'conn> {
                     &self.logger,
                     "Software version '{}' is older than database structure version '{}'.",
                     db_version.version,
-                    migration_version,
+                    known_version,
                 );
 
                 Err(anyhow!("This software version is older than the database structure. Aborting launch to prevent possible data corruption."))?;
@@ -105,30 +346,215 @@ impl<'conn> DatabaseVersionChecker<'conn> {
         connection: &SqliteConnection,
     ) -> StdResult<()> {
         for migration in &self
-            .migrations
-            .iter()
+            .active_migrations()
             .filter(|&m| m.version > starting_version.version)
             .collect::<Vec<&SqlMigration>>()
         {
-            connection.execute(&migration.alterations)?;
-            let db_version = DatabaseVersion {
-                version: migration.version,
-                application_type: self.application_type.clone(),
-                updated_at: Utc::now(),
-            };
-            let _ = connection
-                .fetch_first(UpdateDatabaseVersionQuery::one(db_version))
+            match (&migration.pre_hook, &migration.shadow_table) {
+                // The shadow migrations handle their own transactions
+                (Some(pre_hook), None) => {
+                    let transaction = connection.begin_transaction()?;
+                    run_hook(connection, "pre", migration.version, pre_hook)?;
+                    self.apply_migration(migration, connection)?;
+                    transaction.commit()?;
+                }
+                (Some(pre_hook), Some(_)) => {
+                    run_hook(connection, "pre", migration.version, pre_hook)?;
+                    self.apply_migration(migration, connection)?;
+                }
+                (None, _) => self.apply_migration(migration, connection)?,
+            }
+            if let Some(post_hook) = &migration.post_hook {
+                run_hook(connection, "post", migration.version, post_hook)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_migration(
+        &self,
+        migration: &SqlMigration,
+        connection: &SqliteConnection,
+    ) -> StdResult<()> {
+        let started_at = Instant::now();
+        match &migration.shadow_table {
+            Some(table_name) => self
+                .apply_shadow_migration(migration, table_name, connection, started_at)
                 .with_context(|| {
                     format!(
-                        "Can not save database version when applying migration: '{}'",
+                        "Can not apply shadow migration '{}' of table '{table_name}'",
                         migration.version
                     )
-                })?;
+                })?,
+            None => {
+                connection.execute(&migration.alterations)?;
+                self.save_applied_migration(migration, connection, started_at)?;
+            }
         }
 
         Ok(())
     }
 
+    fn save_applied_migration(
+        &self,
+        migration: &SqlMigration,
+        connection: &SqliteConnection,
+        started_at: Instant,
+    ) -> StdResult<()> {
+        let db_version = DatabaseVersion {
+            version: migration.version,
+            application_type: self.application_type.clone(),
+            updated_at: Utc::now(),
+        };
+        let _ = connection
+            .fetch_first(UpdateDatabaseVersionQuery::one(db_version))
+            .with_context(|| {
+                format!(
+                    "Can not save database version when applying migration: '{}'",
+                    migration.version
+                )
+            })?;
+        self.record_audit_entry(connection, migration.version, started_at.elapsed())
+            .with_context(|| {
+                format!(
+                    "Can not record the audit entry of migration: '{}'",
+                    migration.version
+                )
+            })?;
+
+        Ok(())
+    }
+
+    fn record_audit_entry(
+        &self,
+        connection: &SqliteConnection,
+        migration_version: DbVersion,
+        duration: Duration,
+    ) -> StdResult<()> {
+        let mut statement = connection.prepare(
+            "insert into migration_audit (migration_version, run_at, triggered_by, duration_ms) values (?1, ?2, ?3, ?4)",
+        )?;
+        statement.bind(
+            &[
+                Value::Integer(migration_version),
+                Value::String(Utc::now().to_rfc3339()),
+                Value::String(self.triggered_by.clone()),
+                Value::Integer(duration.as_millis() as i64),
+            ][..],
+        )?;
+        statement.next()?;
+
+        Ok(())
+    }
+
+    fn create_audit_table_if_not_exists(&self) -> StdResult<()> {
+        self.connection.execute(
+            "create table if not exists migration_audit (migration_version integer not null, run_at text not null, triggered_by text not null, duration_ms integer not null);",
+        )?;
+
+        Ok(())
+    }
+
+    /// Rewrite a table using a shadow table created by the migration alterations.
+    ///
+    /// The rows are copied in batches so the table stays available between two batches. The
+    /// rows written meanwhile are mirrored to the shadow table by triggers, and the remaining
+    /// rows are copied in the transaction that swaps the tables and saves the database version.
+    fn apply_shadow_migration(
+        &self,
+        migration: &SqlMigration,
+        table_name: &str,
+        connection: &SqliteConnection,
+        started_at: Instant,
+    ) -> StdResult<()> {
+        let shadow_table_name = SqlMigration::shadow_table_name(table_name);
+        // The shadow table and triggers left by an interrupted migration are created again, the
+        // table itself is only replaced once all its rows are copied
+        connection.execute(format!(
+            r#"
+drop trigger if exists {shadow_table_name}_sync_insert;
+drop trigger if exists {shadow_table_name}_sync_update;
+drop trigger if exists {shadow_table_name}_sync_delete;
+drop table if exists {shadow_table_name};
+"#
+        ))?;
+        connection.execute(&migration.alterations)?;
+        let columns = shared_columns(connection, table_name, &shadow_table_name)?;
+        let new_values = columns
+            .iter()
+            .map(|column| format!("new.{column}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let columns = columns.join(", ");
+        connection.execute(format!(
+            r#"
+create trigger {shadow_table_name}_sync_insert after insert on {table_name} begin
+    insert or replace into {shadow_table_name} (rowid, {columns}) values (new.rowid, {new_values});
+end;
+create trigger {shadow_table_name}_sync_update after update on {table_name} begin
+    delete from {shadow_table_name} where rowid = old.rowid;
+    insert or replace into {shadow_table_name} (rowid, {columns}) values (new.rowid, {new_values});
+end;
+create trigger {shadow_table_name}_sync_delete after delete on {table_name} begin
+    delete from {shadow_table_name} where rowid = old.rowid;
+end;
+"#
+        ))?;
+        let copy_rows_sql = format!(
+            "insert or replace into {shadow_table_name} (rowid, {columns}) select rowid, {columns} from {table_name} where rowid > ?1 and rowid <= ?2"
+        );
+        let batch_end_rowid_sql = format!(
+            "select rowid from {table_name} where rowid > ?1 order by rowid limit 1 offset ?2"
+        );
+        let batch_size = self.shadow_config.batch_size.max(1) as i64;
+
+        let mut last_copied_rowid = 0;
+        loop {
+            // The batch is incomplete: the remaining rows are copied while swapping the tables
+            let Some(batch_end_rowid) = batch_end_rowid(
+                connection,
+                &batch_end_rowid_sql,
+                last_copied_rowid,
+                batch_size,
+            )?
+            else {
+                break;
+            };
+
+            copy_rows(
+                connection,
+                &copy_rows_sql,
+                last_copied_rowid,
+                batch_end_rowid,
+            )?;
+            debug!(
+                &self.logger,
+                "Shadow migration of table '{table_name}': rows copied up to rowid '{batch_end_rowid}'"
+            );
+            last_copied_rowid = batch_end_rowid;
+            std::thread::sleep(self.shadow_config.sleep_between_batches);
+        }
+
+        // Dropping the table also drops the triggers that mirror its rows
+        let transaction = connection.begin_transaction()?;
+        copy_rows(connection, &copy_rows_sql, last_copied_rowid, i64::MAX)?;
+        connection.execute(format!(
+            "drop table {table_name}; alter table {shadow_table_name} rename to {table_name};"
+        ))?;
+        self.save_applied_migration(migration, connection, started_at)?;
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    fn active_migrations(&self) -> impl Iterator<Item = &SqlMigration> + '_ {
+        self.migrations.iter().filter(|m| match &m.feature_flag {
+            Some(flag) => self.feature_flags.contains(flag),
+            None => true,
+        })
+    }
+
     /// Method to create the table at the beginning of the migration procedure.
     /// This code is temporary and should not last.
     pub fn create_table_if_not_exists(
@@ -136,12 +562,8 @@ impl<'conn> DatabaseVersionChecker<'conn> {
         application_type: &ApplicationNodeType,
     ) -> StdResult<()> {
         let connection = self.connection;
-        let table_exists = connection.query_single_cell::<_, i64>(
-            "select exists(select name from sqlite_master where type='table' and name='db_version') as table_exists",
-            &[],
-        )? == 1;
 
-        if !table_exists {
+        if !db_version_table_exists(connection)? {
             let sql = format!("
 create table db_version (application_type text not null primary key, version integer not null, updated_at text not null);
 insert into db_version (application_type, version, updated_at) values ('{application_type}', 0, '{}');
@@ -153,6 +575,135 @@ insert into db_version (application_type, version, updated_at) values ('{applica
     }
 }
 
+/// Read the database version of an application through a connection that can't write to the
+/// database.
+pub struct ReadonlyDatabaseVersionProvider<'conn> {
+    connection: &'conn SqliteConnection,
+    application_type: ApplicationNodeType,
+}
+
+impl<'conn> ReadonlyDatabaseVersionProvider<'conn> {
+    /// Create a new provider, fails if the `query_only` pragma is not set on the connection.
+    pub fn new(
+        application_type: ApplicationNodeType,
+        connection: &'conn SqliteConnection,
+    ) -> Result<Self, HydrationError> {
+        match is_query_only(connection) {
+            Ok(true) => Ok(Self {
+                connection,
+                application_type,
+            }),
+            _ => Err(HydrationError::ReadOnlyConnectionRequired),
+        }
+    }
+
+    /// Read the database version of the application, `None` if it was never set.
+    pub fn get_application_version(&self) -> StdResult<Option<DatabaseVersion>> {
+        if !db_version_table_exists(self.connection)? {
+            return Ok(None);
+        }
+
+        self.connection
+            .fetch_first(GetDatabaseVersionQuery::get_application_version(
+                &self.application_type,
+            ))
+            .with_context(|| "Can not get application version")
+    }
+}
+
+fn run_hook(
+    connection: &SqliteConnection,
+    phase: &'static str,
+    migration_version: DbVersion,
+    hook: &str,
+) -> StdResult<()> {
+    connection
+        .execute(hook)
+        .map_err(|e| MigrationError::HookFailed {
+            phase,
+            migration_version,
+            error: e.to_string(),
+        })?;
+
+    Ok(())
+}
+
+fn is_query_only(connection: &SqliteConnection) -> StdResult<bool> {
+    Ok(connection.query_single_cell::<_, i64>("pragma query_only", &[])? == 1)
+}
+
+/// Comma separated names of the columns of the shadow table that also exist in the table.
+fn shared_columns(
+    connection: &SqliteConnection,
+    table_name: &str,
+    shadow_table_name: &str,
+) -> StdResult<Vec<String>> {
+    let sql = "select name from pragma_table_info(?1) where name in (select name from pragma_table_info(?2))";
+    let mut statement = connection.prepare(sql)?;
+    statement.bind(
+        &[
+            Value::String(shadow_table_name.to_string()),
+            Value::String(table_name.to_string()),
+        ][..],
+    )?;
+    let mut columns = vec![];
+    while let State::Row = statement.next()? {
+        columns.push(statement.read::<String, _>(0)?);
+    }
+
+    if columns.is_empty() {
+        return Err(anyhow!(
+            "Table '{shadow_table_name}' has no column in common with table '{table_name}'"
+        ));
+    }
+
+    Ok(columns)
+}
+
+fn copy_rows(
+    connection: &SqliteConnection,
+    copy_rows_sql: &str,
+    from_rowid: i64,
+    to_rowid: i64,
+) -> StdResult<()> {
+    let mut statement = connection.prepare(copy_rows_sql)?;
+    statement.bind(&[Value::Integer(from_rowid), Value::Integer(to_rowid)][..])?;
+    statement.next()?;
+
+    Ok(())
+}
+
+/// Rowid of the last row of the batch starting after the given rowid, `None` if the batch
+/// is incomplete.
+fn batch_end_rowid(
+    connection: &SqliteConnection,
+    batch_end_rowid_sql: &str,
+    after_rowid: i64,
+    batch_size: i64,
+) -> StdResult<Option<i64>> {
+    let mut statement = connection.prepare(batch_end_rowid_sql)?;
+    statement.bind(&[Value::Integer(after_rowid), Value::Integer(batch_size - 1)][..])?;
+
+    match statement.next()? {
+        State::Row => Ok(Some(statement.read::<i64, _>(0)?)),
+        State::Done => Ok(None),
+    }
+}
+
+fn migration_audit_table_exists(connection: &SqliteConnection) -> StdResult<bool> {
+    Ok(connection.query_single_cell::<_, i64>(
+        "select exists(select name from sqlite_master where type='table' and name='migration_audit') as table_exists",
+        &[],
+    )? == 1)
+}
+
+fn db_version_table_exists(connection: &SqliteConnection) -> StdResult<bool> {
+    Ok(connection.query_single_cell::<_, i64>(
+        "select exists(select name from sqlite_master where type='table' and name='db_version') as table_exists",
+        &[],
+    )? == 1)
+}
+
 /// Represent a file containing SQL structure or data alterations.
 #[derive(Debug)]
 pub struct SqlMigration {
@@ -161,6 +712,18 @@ pub struct SqlMigration {
 
     /// SQL statements to alter the database.
     pub alterations: String,
+
+    /// If set, the migration is only applied when this feature flag is active.
+    pub feature_flag: Option<String>,
+
+    /// If set, the migration rewrites this table using a shadow table.
+    pub shadow_table: Option<String>,
+
+    /// SQL statements run before the alterations, in the same transaction.
+    pub pre_hook: Option<String>,
+
+    /// SQL statements run after the alterations are committed.
+    pub post_hook: Option<String>,
 }
 
 impl SqlMigration {
@@ -169,8 +732,56 @@ impl SqlMigration {
         Self {
             version,
             alterations: alteration.into(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         }
     }
+
+    /// Only apply this migration when the given feature flag is active.
+    pub fn with_feature_flag(mut self, flag: &str) -> Self {
+        self.feature_flag = Some(flag.to_string());
+        self
+    }
+
+    /// Run the given SQL before the alterations, in the same transaction (ie: to analyse a
+    /// table).
+    ///
+    /// The transaction is rolled back if the hook fails, the migration is then not applied.
+    pub fn with_pre_hook(mut self, sql: &str) -> Self {
+        self.pre_hook = Some(sql.to_string());
+        self
+    }
+
+    /// Run the given SQL once the alterations are committed, outside of any transaction (ie:
+    /// to vacuum the database).
+    ///
+    /// The migration stays applied if the hook fails.
+    pub fn with_post_hook(mut self, sql: &str) -> Self {
+        self.post_hook = Some(sql.to_string());
+        self
+    }
+
+    /// Rewrite the given table without blocking it, for the alterations that can't be done in
+    /// place (ie: adding a `not null` column).
+    ///
+    /// The alterations must create the table with its target schema under the
+    /// [shadow name][Self::shadow_table_name] of the table. The rows of the columns shared by both
+    /// tables are then copied in batches to the shadow table, which finally replaces the table.
+    /// The table must have a rowid, the rows keep their rowid in the shadow table.
+    ///
+    /// The indexes and triggers of the table must be created again by the alterations on the
+    /// shadow table.
+    pub fn as_shadow_migration(mut self, new_table_name: &str) -> Self {
+        self.shadow_table = Some(new_table_name.to_string());
+        self
+    }
+
+    /// Name of the shadow table used to rewrite the given table.
+    pub fn shadow_table_name(table_name: &str) -> String {
+        format!("{table_name}_shadow")
+    }
 }
 
 impl PartialOrd for SqlMigration {
@@ -258,6 +869,10 @@ mod tests {
         let migration = SqlMigration {
             version: 1,
             alterations: alterations.to_string(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         };
         db_checker.add_migration(migration);
         db_checker.apply().unwrap();
@@ -272,6 +887,10 @@ mod tests {
         let migration = SqlMigration {
             version: 2,
             alterations: alterations.to_string(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         };
         db_checker.add_migration(migration);
         db_checker.apply().unwrap();
@@ -285,12 +904,20 @@ mod tests {
         let migration = SqlMigration {
             version: 4,
             alterations: alterations.to_string(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         };
         db_checker.add_migration(migration);
         let alterations = "alter table whatever add column more_thing text; update whatever set more_thing = 'more thing'";
         let migration = SqlMigration {
             version: 3,
             alterations: alterations.to_string(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         };
         db_checker.add_migration(migration);
         db_checker.apply().unwrap();
@@ -311,6 +938,10 @@ mod tests {
         let migration = SqlMigration {
             version: 1,
             alterations: alterations.to_string(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         };
         db_checker.add_migration(migration);
         db_checker.apply().unwrap();
@@ -334,24 +965,477 @@ mod tests {
         let migration = SqlMigration {
             version: 1,
             alterations: alterations.to_string(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         };
         db_checker.add_migration(migration);
         let alterations = "alter table wrong add column thing_content text; update whatever set thing_content = 'some content'";
         let migration = SqlMigration {
             version: 2,
             alterations: alterations.to_string(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         };
         db_checker.add_migration(migration);
         let alterations = "alter table whatever add column thing_content text; update whatever set thing_content = 'some content'";
         let migration = SqlMigration {
             version: 3,
             alterations: alterations.to_string(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         };
         db_checker.add_migration(migration);
         db_checker.apply().unwrap_err();
         check_database_version(&connection, 1);
     }
 
+    #[test]
+    fn readonly_checker_cannot_apply_migrations() {
+        let (_filepath, connection) =
+            create_sqlite_file("readonly_checker_cannot_apply_migrations").unwrap();
+        let mut db_checker = DatabaseVersionChecker::new_readonly(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        db_checker.add_migration(SqlMigration::new(
+            1,
+            "create table whatever (thing_id integer);",
+        ));
+
+        let error = db_checker
+            .apply()
+            .expect_err("Applying migrations with a read-only checker should fail");
+        assert!(
+            error.downcast_ref::<sqlite::Error>().is_some(),
+            "Expected an SQLite error but got: '{error:?}'"
+        );
+        assert_eq!(None, db_checker.get_application_version().unwrap());
+
+        // The pragma is restored after the operation
+        assert!(!is_query_only(&connection).unwrap());
+    }
+
+    #[test]
+    fn readonly_checker_can_read_application_version() {
+        let (_filepath, connection) =
+            create_sqlite_file("readonly_checker_can_read_application_version").unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        db_checker.add_migration(SqlMigration::new(
+            1,
+            "create table whatever (thing_id integer);",
+        ));
+        db_checker.apply().unwrap();
+
+        let readonly_checker = DatabaseVersionChecker::new_readonly(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+
+        let version = readonly_checker.get_application_version().unwrap();
+        assert_eq!(Some(1), version.map(|v| v.version));
+    }
+
+    fn database_at_version(test_name: &str, version: DbVersion) -> SqliteConnection {
+        let (_filepath, connection) = create_sqlite_file(test_name).unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        for migration_version in 1..=version {
+            db_checker.add_migration(SqlMigration::new(
+                migration_version,
+                format!("create table whatever_{migration_version} (thing_id integer);"),
+            ));
+        }
+        db_checker.apply().unwrap();
+
+        connection
+    }
+
+    #[test]
+    fn check_range_passes_if_the_database_version_satisfies_the_requirement() {
+        let connection = database_at_version("check_range_passes_if_satisfied", 3);
+        let db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+
+        db_checker
+            .check_range(&VersionReq::parse(">=2.0.0, <4.0.0").unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn check_range_fails_if_the_database_version_does_not_satisfy_the_requirement() {
+        let connection = database_at_version("check_range_fails_if_not_satisfied", 3);
+        let db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        let requirement = VersionReq::parse(">=4.0.0").unwrap();
+
+        let error = db_checker.check_range(&requirement).unwrap_err();
+
+        assert_eq!(
+            Some(&MigrationError::VersionOutOfRange {
+                stored: Version::new(3, 0, 0),
+                requirement,
+            }),
+            error.downcast_ref::<MigrationError>()
+        );
+    }
+
+    #[test]
+    fn check_range_fails_if_no_database_version_is_stored() {
+        let (_filepath, connection) =
+            create_sqlite_file("check_range_fails_if_no_version").unwrap();
+        let db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+
+        let error = db_checker
+            .check_range(&VersionReq::parse(">=1.0.0").unwrap())
+            .unwrap_err();
+
+        assert_eq!(
+            Some(&MigrationError::VersionNotFound(
+                ApplicationNodeType::Aggregator
+            )),
+            error.downcast_ref::<MigrationError>()
+        );
+    }
+
+    #[test]
+    fn readonly_provider_requires_a_query_only_connection() {
+        let (_filepath, connection) =
+            create_sqlite_file("readonly_provider_requires_a_query_only_connection").unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        db_checker.add_migration(SqlMigration::new(
+            1,
+            "create table whatever (thing_id integer);",
+        ));
+        db_checker.apply().unwrap();
+
+        let error =
+            ReadonlyDatabaseVersionProvider::new(ApplicationNodeType::Aggregator, &connection)
+                .err()
+                .expect("Creating a read-only provider on a writable connection should fail");
+        assert!(matches!(error, HydrationError::ReadOnlyConnectionRequired));
+
+        connection.execute("pragma query_only = on").unwrap();
+        let provider =
+            ReadonlyDatabaseVersionProvider::new(ApplicationNodeType::Aggregator, &connection)
+                .unwrap();
+        let version = provider.get_application_version().unwrap();
+        assert_eq!(Some(1), version.map(|v| v.version));
+    }
+
+    fn index_exists(connection: &SqliteConnection, index_name: &str) -> bool {
+        connection
+            .prepare(format!(
+                "select exists(select 1 from sqlite_master where type='index' and name='{index_name}')"
+            ))
+            .unwrap()
+            .iter()
+            .next()
+            .unwrap()
+            .unwrap()
+            .read::<i64, _>(0)
+            == 1
+    }
+
+    fn migrations_with_feature_flag() -> Vec<SqlMigration> {
+        vec![
+            SqlMigration::new(1, "create table whatever (thing_id integer);"),
+            SqlMigration::new(2, "create index whatever_index on whatever(thing_id);")
+                .with_feature_flag("whatever_index"),
+        ]
+    }
+
+    #[test]
+    fn migration_with_inactive_feature_flag_is_skipped() {
+        let (_filepath, connection) =
+            create_sqlite_file("migration_with_inactive_feature_flag_is_skipped").unwrap();
+        let mut db_checker = DatabaseVersionChecker::new_with_flags(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+            BTreeSet::from(["another_flag".to_string()]),
+        );
+        for migration in migrations_with_feature_flag() {
+            db_checker.add_migration(migration);
+        }
+
+        db_checker.apply().unwrap();
+
+        assert_eq!(1, get_table_whatever_column_count(&connection));
+        assert!(!index_exists(&connection, "whatever_index"));
+        check_database_version(&connection, 1);
+    }
+
+    #[test]
+    fn migration_with_active_feature_flag_is_applied() {
+        let (_filepath, connection) =
+            create_sqlite_file("migration_with_active_feature_flag_is_applied").unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        for migration in migrations_with_feature_flag() {
+            db_checker.add_migration(migration);
+        }
+        db_checker.apply().unwrap();
+        check_database_version(&connection, 1);
+
+        let mut db_checker = DatabaseVersionChecker::new_with_flags(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+            BTreeSet::from(["whatever_index".to_string()]),
+        );
+        for migration in migrations_with_feature_flag() {
+            db_checker.add_migration(migration);
+        }
+        db_checker.apply().unwrap();
+        assert!(index_exists(&connection, "whatever_index"));
+        check_database_version(&connection, 2);
+
+        // Deactivating the flag once the migration is applied is not a downgrade
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        for migration in migrations_with_feature_flag() {
+            db_checker.add_migration(migration);
+        }
+        db_checker.apply().unwrap();
+        check_database_version(&connection, 2);
+    }
+
+    #[test]
+    fn shadow_migration_copies_all_the_rows_to_the_rewritten_table() {
+        let (_filepath, connection) =
+            create_sqlite_file("shadow_migration_copies_all_the_rows_to_the_rewritten_table")
+                .unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        )
+        .with_shadow_config(ShadowMigrationConfig {
+            batch_size: 768,
+            sleep_between_batches: Duration::ZERO,
+        });
+        db_checker.add_migration(SqlMigration::new(
+            1,
+            r#"
+create table whatever (thing_id integer primary key, thing_content text);
+with recursive thing(id) as (select 1 union all select id + 1 from thing where id < 10000)
+insert into whatever (thing_id, thing_content) select id, 'content ' || id from thing;
+"#,
+        ));
+        db_checker.add_migration(
+            SqlMigration::new(
+                2,
+                r#"
+create table whatever_shadow (
+    thing_id integer primary key,
+    thing_content text not null,
+    thing_status text not null default 'active'
+);
+"#,
+            )
+            .as_shadow_migration("whatever"),
+        );
+
+        db_checker.apply().unwrap();
+
+        check_database_version(&connection, 2);
+        assert_eq!(3, get_table_whatever_column_count(&connection));
+        assert_eq!(
+            (10000, 50005000),
+            connection
+                .prepare("select count(*), sum(thing_id) from whatever where thing_content = 'content ' || thing_id and thing_status = 'active'")
+                .unwrap()
+                .iter()
+                .next()
+                .map(|row| {
+                    let row = row.unwrap();
+                    (row.read::<i64, _>(0), row.read::<i64, _>(1))
+                })
+                .unwrap()
+        );
+        assert_eq!(
+            0,
+            connection
+                .query_single_cell::<_, i64>(
+                    "select count(*) from sqlite_master where name = 'whatever_shadow'",
+                    &[]
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn shadow_migration_mirrors_the_rows_written_while_copying_the_batches() {
+        let (filepath, connection) = create_sqlite_file(
+            "shadow_migration_mirrors_the_rows_written_while_copying_the_batches",
+        )
+        .unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        )
+        .with_shadow_config(ShadowMigrationConfig {
+            batch_size: 5000,
+            sleep_between_batches: Duration::from_millis(300),
+        });
+        db_checker.add_migration(SqlMigration::new(
+            1,
+            r#"
+create table whatever (thing_id integer primary key, thing_content text);
+with recursive thing(id) as (select 1 union all select id + 1 from thing where id < 10000)
+insert into whatever (thing_id, thing_content) select id, 'content ' || id from thing;
+"#,
+        ));
+        db_checker.add_migration(
+            SqlMigration::new(
+                2,
+                r#"
+create table whatever_shadow (
+    thing_id integer primary key,
+    thing_content text not null,
+    thing_status text not null default 'active'
+);
+"#,
+            )
+            .as_shadow_migration("whatever"),
+        );
+
+        // Rows already copied by the first batch are written while the migration sleeps
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            let mut connection = Connection::open_thread_safe(&filepath).unwrap();
+            connection.set_busy_timeout(5000).unwrap();
+            connection
+                .execute(
+                    r#"
+update whatever set thing_content = 'updated' where thing_id = 1;
+delete from whatever where thing_id = 2;
+insert into whatever (thing_id, thing_content) values (10001, 'content 10001');
+"#,
+                )
+                .unwrap();
+        });
+        db_checker.apply().unwrap();
+        writer.join().unwrap();
+
+        check_database_version(&connection, 2);
+        let thing_content = |thing_id: i64| {
+            connection
+                .query_single_cell::<_, String>(
+                    "select coalesce(max(thing_content), 'missing') from whatever where thing_id = ?1",
+                    &[Value::Integer(thing_id)],
+                )
+                .unwrap()
+        };
+        assert_eq!("updated", thing_content(1));
+        assert_eq!("missing", thing_content(2));
+        assert_eq!("content 10001", thing_content(10001));
+        assert_eq!(
+            10000,
+            connection
+                .query_single_cell::<_, i64>("select count(*) from whatever", &[])
+                .unwrap()
+        );
+        assert_eq!(
+            0,
+            connection
+                .query_single_cell::<_, i64>(
+                    "select count(*) from sqlite_master where type = 'trigger'",
+                    &[]
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn shadow_migration_replaces_the_shadow_table_left_by_an_interrupted_migration() {
+        let (_filepath, connection) = create_sqlite_file(
+            "shadow_migration_replaces_the_shadow_table_left_by_an_interrupted_migration",
+        )
+        .unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        db_checker.add_migration(SqlMigration::new(
+            1,
+            r#"
+create table whatever (thing_id integer primary key, thing_content text);
+insert into whatever (thing_id, thing_content) values (1, 'content 1'), (2, 'content 2');
+create table whatever_shadow (thing_id integer primary key);
+insert into whatever_shadow (thing_id) values (1), (3);
+"#,
+        ));
+        db_checker.add_migration(
+            SqlMigration::new(
+                2,
+                r#"
+create table whatever_shadow (
+    thing_id integer primary key,
+    thing_content text not null,
+    thing_status text not null default 'active'
+);
+"#,
+            )
+            .as_shadow_migration("whatever"),
+        );
+
+        db_checker.apply().unwrap();
+
+        check_database_version(&connection, 2);
+        assert_eq!(3, get_table_whatever_column_count(&connection));
+        assert_eq!(
+            (2, 3),
+            connection
+                .prepare("select count(*), sum(thing_id) from whatever where thing_content = 'content ' || thing_id")
+                .unwrap()
+                .iter()
+                .next()
+                .map(|row| {
+                    let row = row.unwrap();
+                    (row.read::<i64, _>(0), row.read::<i64, _>(1))
+                })
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_fail_downgrading() {
         let (_filepath, connection) = create_sqlite_file("test_fail_downgrading").unwrap();
@@ -364,6 +1448,10 @@ mod tests {
         let migration = SqlMigration {
             version: 1,
             alterations: alterations.to_string(),
+            feature_flag: None,
+            shadow_table: None,
+            pre_hook: None,
+            post_hook: None,
         };
         db_checker.add_migration(migration);
         db_checker.apply().unwrap();
@@ -381,4 +1469,134 @@ mod tests {
         );
         check_database_version(&connection, 1);
     }
+
+    fn migration_log_entries(connection: &SqliteConnection) -> Vec<String> {
+        let mut statement = connection
+            .prepare("select entry from migration_log order by rowid")
+            .unwrap();
+        let mut entries = vec![];
+        while let State::Row = statement.next().unwrap() {
+            entries.push(statement.read::<String, _>(0).unwrap());
+        }
+
+        entries
+    }
+
+    #[test]
+    fn post_hook_runs_after_the_migration() {
+        let (_filepath, connection) = create_sqlite_file("post_hook_runs_after_migration").unwrap();
+        connection
+            .execute("create table migration_log (entry text not null)")
+            .unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        db_checker.add_migration(
+            SqlMigration::new(1, "create table whatever (thing_id integer);")
+                .with_post_hook(
+                    "insert into migration_log (entry) select 'whatever columns: ' || count(*) from pragma_table_info('whatever');",
+                ),
+        );
+
+        db_checker.apply().unwrap();
+
+        check_database_version(&connection, 1);
+        assert_eq!(
+            vec!["whatever columns: 1".to_string()],
+            migration_log_entries(&connection)
+        );
+    }
+
+    #[test]
+    fn failing_pre_hook_rolls_back_the_migration() {
+        let (_filepath, connection) = create_sqlite_file("failing_pre_hook").unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        db_checker.add_migration(
+            SqlMigration::new(1, "create table whatever (thing_id integer);")
+                .with_pre_hook("insert into missing_table (entry) values ('pre');"),
+        );
+
+        let error = db_checker
+            .apply()
+            .expect_err("the failing pre-hook should fail");
+
+        assert!(matches!(
+            error.downcast_ref::<MigrationError>(),
+            Some(MigrationError::HookFailed {
+                phase: "pre",
+                migration_version: 1,
+                ..
+            })
+        ));
+        check_database_version(&connection, 0);
+        assert_eq!(0, get_table_whatever_column_count(&connection));
+    }
+
+    #[test]
+    fn failing_post_hook_keeps_the_migration_applied() {
+        let (_filepath, connection) = create_sqlite_file("failing_post_hook").unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        );
+        db_checker.add_migration(
+            SqlMigration::new(1, "create table whatever (thing_id integer);")
+                .with_post_hook("insert into missing_table (entry) values ('post');"),
+        );
+
+        let error = db_checker
+            .apply()
+            .expect_err("the failing post-hook should fail");
+
+        assert!(matches!(
+            error.downcast_ref::<MigrationError>(),
+            Some(MigrationError::HookFailed {
+                phase: "post",
+                migration_version: 1,
+                ..
+            })
+        ));
+        check_database_version(&connection, 1);
+        assert_eq!(1, get_table_whatever_column_count(&connection));
+    }
+
+    #[test]
+    fn applied_migrations_are_recorded_in_the_audit_log() {
+        let (_filepath, connection) = create_sqlite_file("migrations_audit_log").unwrap();
+        let mut db_checker = DatabaseVersionChecker::new(
+            slog_scope::logger(),
+            ApplicationNodeType::Aggregator,
+            &connection,
+        )
+        .with_triggered_by("operator");
+        assert!(db_checker.list_audit_entries().unwrap().is_empty());
+
+        db_checker
+            .add_migration(SqlMigration::new(
+                1,
+                "create table whatever (thing_id integer);",
+            ))
+            .add_migration(SqlMigration::new(
+                2,
+                "alter table whatever add column thing_content text;",
+            ));
+        db_checker.apply().unwrap();
+        db_checker.apply().unwrap();
+
+        let entries = db_checker.list_audit_entries().unwrap();
+        assert_eq!(
+            vec![(1, "operator".to_string()), (2, "operator".to_string())],
+            entries
+                .into_iter()
+                .map(|entry| (entry.migration_version, entry.triggered_by))
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,5 +1,5 @@
 use sqlite::Value;
-use std::iter::repeat;
+use std::iter::repeat_n;
 
 /// Internal Boolean representation
 #[derive(Debug, Clone)]
@@ -90,7 +90,7 @@ impl WhereCondition {
 
     /// Instanciate a condition with a `IN` statement.
     pub fn where_in(field: &str, parameters: Vec<Value>) -> Self {
-        let params: Vec<&str> = repeat("?*").take(parameters.len()).collect();
+        let params: Vec<&str> = repeat_n("?*", parameters.len()).collect();
         let expression = format!("{} in ({})", field, params.join(", "));
 
         Self {
@@ -360,10 +360,10 @@ mod tests {
         assert_eq!(1, params.len());
     }
 
+    impl GetAllCondition for String {}
+
     #[test]
     fn expression_get_all_default() {
-        impl GetAllCondition for String {}
-
         let expression = String::get_all_condition();
         let (sql, params) = expression.expand();
 
@@ -247,9 +247,11 @@ mod tests {
 
         let tables_list = execute_single_cell_query(
             &connection,
-            // Note: exclude sqlite system tables and migration system `db_version` table
+            // Note: exclude sqlite system tables and migration system `db_version` and
+            // `migration_audit` tables
             "SELECT group_concat(name) FROM sqlite_schema \
-            WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != 'db_version' \
+            WHERE type = 'table' AND name NOT LIKE 'sqlite_%' \
+            AND name NOT IN ('db_version', 'migration_audit') \
             ORDER BY name;",
         );
 
@@ -9,6 +9,10 @@ pub enum HydrationError {
     /// data do not conform to expectations
     #[error("data do not conform to expectations: {0}")]
     InvalidData(String),
+
+    /// the connection must be read-only (`query_only` pragma set)
+    #[error("a read-only connection is required, the 'query_only' pragma must be set")]
+    ReadOnlyConnectionRequired,
 }
 
 /// How to hydrate an entity from a SQLite result row
@@ -78,6 +78,10 @@ where
         &self,
         how_many: usize,
     ) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
+        if let Some(error) = &self.error {
+            return Err(AdapterError::GeneralError(anyhow!(error.clone())));
+        }
+
         if how_many > 0 {
             match &self.last_key {
                 Some(_key) => Ok(vec![(
@@ -103,6 +107,10 @@ where
     }
 
     async fn get_iter(&self) -> Result<Box<dyn Iterator<Item = Self::Record> + '_>, AdapterError> {
+        if let Some(error) = &self.error {
+            return Err(AdapterError::GeneralError(anyhow!(error.clone())));
+        }
+
         let mut values = vec![];
         if let Some(value) = &self.last_value {
             values.push(value.clone());
@@ -233,4 +241,13 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_return_error_calling_get_last_n_records_and_get_iter() {
+        let adapter: DumbStoreAdapter<String, String> =
+            DumbStoreAdapter::new_failing_adapter("error");
+
+        assert!(adapter.get_last_n_records(1).await.is_err());
+        assert!(adapter.get_iter().await.is_err());
+    }
 }
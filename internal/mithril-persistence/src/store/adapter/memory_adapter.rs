@@ -1,13 +1,20 @@
 use anyhow::anyhow;
 use async_trait::async_trait;
+use serde::Serialize;
 use std::{collections::HashMap, hash::Hash};
+use tokio::sync::broadcast;
 
-use super::{AdapterError, StoreAdapter};
+use super::{
+    broadcast_changes_stream, AdapterError, IndexableStoreAdapter, StoreAdapter, StoreChange,
+    StoreChangeStream, STORE_CHANGES_CHANNEL_CAPACITY,
+};
 
 /// A [StoreAdapter] that store data in memory.
 pub struct MemoryAdapter<K, V> {
     index: Vec<K>,
     values: HashMap<K, V>,
+    versions: HashMap<K, u64>,
+    changes: broadcast::Sender<StoreChange<K, V>>,
 }
 
 impl<K, V> MemoryAdapter<K, V>
@@ -19,6 +26,7 @@ where
     pub fn new(data: Option<Vec<(K, V)>>) -> Result<Self, AdapterError> {
         let data = data.unwrap_or_default();
         let mut values = HashMap::new();
+        let mut versions = HashMap::new();
         let mut index = Vec::new();
 
         for (idx, elt) in data.into_iter() {
@@ -27,18 +35,47 @@ where
                     "duplicate key found"
                 )));
             }
+            versions.insert(idx.clone(), 1);
             index.push(idx);
         }
 
-        Ok(Self { index, values })
+        let (changes, _) = broadcast::channel(STORE_CHANGES_CHANNEL_CAPACITY);
+
+        Ok(Self {
+            index,
+            values,
+            versions,
+            changes,
+        })
+    }
+
+    fn notify(&self, change: StoreChange<K, V>) {
+        // An error only means that there is no subscriber at the moment.
+        let _ = self.changes.send(change);
+    }
+
+    /// Store the record and increment its version, returns the new version
+    fn insert_record(&mut self, key: K, record: V) -> u64 {
+        let version = self.versions.entry(key.clone()).or_insert(0);
+        *version += 1;
+        let version = *version;
+
+        if self.values.insert(key.clone(), record.clone()).is_none() {
+            self.index.push(key.clone());
+            self.notify(StoreChange::Inserted(key, record));
+        } else {
+            self.notify(StoreChange::Updated(key, record));
+        }
+
+        version
     }
 }
 
 #[async_trait]
 impl<K, V> StoreAdapter for MemoryAdapter<K, V>
 where
-    K: Hash + Eq + Send + Sync + Clone,
-    V: Send + Sync + Clone,
+    K: Hash + Eq + Send + Sync + Clone + 'static,
+    V: Send + Sync + Clone + 'static,
 {
     type Key = K;
     type Record = V;
@@ -48,12 +85,7 @@ where
         key: &Self::Key,
         record: &Self::Record,
     ) -> Result<(), AdapterError> {
-        let key = (*key).clone();
-        let record = (*record).clone();
-
-        if self.values.insert(key.clone(), record).is_none() {
-            self.index.push(key);
-        }
+        self.insert_record(key.clone(), record.clone());
 
         Ok(())
     }
@@ -84,8 +116,14 @@ where
 
     async fn remove(&mut self, key: &Self::Key) -> Result<Option<Self::Record>, AdapterError> {
         self.index.retain(|k| *k != *key);
+        self.versions.remove(key);
+        let removed = self.values.remove(key);
 
-        Ok(self.values.remove(key))
+        if removed.is_some() {
+            self.notify(StoreChange::Deleted(key.clone()));
+        }
+
+        Ok(removed)
     }
 
     async fn get_iter(&self) -> Result<Box<dyn Iterator<Item = Self::Record> + '_>, AdapterError> {
@@ -96,10 +134,106 @@ where
                 .map(|k| self.values.get(k).unwrap().clone()),
         ))
     }
+
+    /// Records are not indexed: the search is a substring match on their JSON serialization.
+    async fn search(&self, query: &str) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError>
+    where
+        Self::Record: Serialize,
+    {
+        let mut records = vec![];
+        for key in self.index.iter().rev() {
+            let value = self.values.get(key).unwrap();
+            let serialized_value =
+                serde_json::to_string(value).map_err(|e| AdapterError::GeneralError(e.into()))?;
+            if serialized_value.contains(query) {
+                records.push((key.clone(), value.clone()));
+            }
+        }
+
+        Ok(records)
+    }
+
+    async fn delete_range(&mut self, from: &Self::Key, to: &Self::Key) -> Result<u64, AdapterError>
+    where
+        Self::Key: PartialOrd,
+    {
+        let (deleted_keys, kept_keys): (Vec<K>, Vec<K>) = self
+            .index
+            .drain(..)
+            .partition(|key| from <= key && key <= to);
+        self.index = kept_keys;
+
+        for key in &deleted_keys {
+            self.versions.remove(key);
+            self.values.remove(key);
+            self.notify(StoreChange::Deleted(key.clone()));
+        }
+
+        Ok(deleted_keys.len() as u64)
+    }
+
+    async fn get_record_version(&self, key: &Self::Key) -> Result<u64, AdapterError> {
+        Ok(self.versions.get(key).copied().unwrap_or_default())
+    }
+
+    async fn get_versioned(
+        &self,
+        key: &Self::Key,
+    ) -> Result<Option<(Self::Record, u64)>, AdapterError> {
+        Ok(self.values.get(key).map(|value| {
+            (
+                value.clone(),
+                self.versions.get(key).copied().unwrap_or_default(),
+            )
+        }))
+    }
+
+    async fn store_if_version(
+        &mut self,
+        key: &Self::Key,
+        record: &Self::Record,
+        expected_version: u64,
+    ) -> Result<u64, AdapterError> {
+        let actual_version = self.get_record_version(key).await?;
+        if actual_version != expected_version {
+            return Err(AdapterError::VersionConflict {
+                expected: expected_version,
+                actual: actual_version,
+            });
+        }
+
+        Ok(self.insert_record(key.clone(), record.clone()))
+    }
+
+    fn changes(&self) -> StoreChangeStream<Self::Key, Self::Record> {
+        broadcast_changes_stream(self.changes.subscribe())
+    }
+}
+
+/// Records are kept in memory so there's no index to create.
+#[async_trait]
+impl<K, V> IndexableStoreAdapter for MemoryAdapter<K, V>
+where
+    K: Hash + Eq + Send + Sync + Clone + 'static,
+    V: Send + Sync + Clone + 'static,
+{
+    async fn create_compound_index(
+        &self,
+        _columns: &[&str],
+        _unique: bool,
+    ) -> Result<(), AdapterError> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::StreamExt;
+    use mithril_common::entities::StakeDistributionParty;
+    use serde::Deserialize;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
     use super::*;
 
     fn init_adapter(nb: u64) -> MemoryAdapter<u64, String> {
@@ -237,4 +371,168 @@ mod tests {
             values
         );
     }
+
+    #[tokio::test]
+    async fn changes_stream_yields_every_mutation_in_order() {
+        let mut adapter = init_adapter(1);
+        let changes = adapter.changes();
+
+        adapter.store_record(&2, &"two".to_string()).await.unwrap();
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+        adapter.remove(&2).await.unwrap();
+        adapter.remove(&42).await.unwrap();
+        drop(adapter);
+
+        let changes: Vec<StoreChange<u64, String>> = changes.collect().await;
+        assert_eq!(
+            vec![
+                StoreChange::Inserted(2, "two".to_string()),
+                StoreChange::Updated(1, "one".to_string()),
+                StoreChange::Deleted(2),
+            ],
+            changes
+        );
+    }
+
+    #[tokio::test]
+    async fn storing_without_subscriber_does_not_fail() {
+        let mut adapter = init_adapter(0);
+
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+        adapter.remove(&1).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn store_if_version_increments_the_record_version() {
+        let mut adapter = init_adapter(1);
+
+        assert_eq!(1, adapter.get_record_version(&1).await.unwrap());
+        assert_eq!(
+            2,
+            adapter
+                .store_if_version(&1, &"one".to_string(), 1)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            1,
+            adapter
+                .store_if_version(&2, &"two".to_string(), 0)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some("one".to_string()),
+            adapter.get_record(&1).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn get_versioned_returns_the_record_with_its_version() {
+        let mut adapter = init_adapter(1);
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+
+        assert_eq!(
+            Some(("one".to_string(), 2)),
+            adapter.get_versioned(&1).await.unwrap()
+        );
+        assert_eq!(None, adapter.get_versioned(&2).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn store_if_version_fails_on_a_version_mismatch() {
+        let mut adapter = init_adapter(1);
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+
+        let error = adapter
+            .store_if_version(&1, &"uno".to_string(), 1)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                error,
+                AdapterError::VersionConflict {
+                    expected: 1,
+                    actual: 2
+                }
+            ),
+            "unexpected error: {error:?}"
+        );
+        assert_eq!(
+            Some("one".to_string()),
+            adapter.get_record(&1).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn only_one_of_two_concurrent_versioned_writes_succeeds() {
+        let adapter = Arc::new(Mutex::new(init_adapter(1)));
+        let writers: Vec<_> = ["first", "second"]
+            .into_iter()
+            .map(|value| {
+                let adapter = adapter.clone();
+                tokio::spawn(async move {
+                    adapter
+                        .lock()
+                        .await
+                        .store_if_version(&1, &value.to_string(), 1)
+                        .await
+                })
+            })
+            .collect();
+
+        let mut results = vec![];
+        for writer in writers {
+            results.push(writer.await.unwrap());
+        }
+
+        assert_eq!(1, results.iter().filter(|result| result.is_ok()).count());
+        assert_eq!(
+            2,
+            adapter.lock().await.get_record_version(&1).await.unwrap()
+        );
+    }
+
+    /// Certificate metadata searchable by the party ids of its signers
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct CertificateSigners {
+        hash: String,
+        signers: Vec<StakeDistributionParty>,
+    }
+
+    fn certificates_with_signers(signers: &[&[&str]]) -> Vec<(String, CertificateSigners)> {
+        signers
+            .iter()
+            .enumerate()
+            .map(|(index, party_ids)| {
+                let certificate = CertificateSigners {
+                    hash: format!("certificate-{index}"),
+                    signers: party_ids
+                        .iter()
+                        .map(|party_id| StakeDistributionParty {
+                            party_id: party_id.to_string(),
+                            stake: 100 * (index as u64 + 1),
+                        })
+                        .collect(),
+                };
+
+                (certificate.hash.clone(), certificate)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn search_returns_the_records_containing_the_query() {
+        let certificates = certificates_with_signers(&[
+            &["pool1aaa", "pool1bbb"],
+            &["pool1ccc", "pool1ddd"],
+            &["pool1aaa", "pool1eee"],
+        ]);
+        let adapter = MemoryAdapter::new(Some(certificates.clone())).unwrap();
+
+        let records = adapter.search("pool1ddd").await.unwrap();
+
+        assert_eq!(vec![certificates[1].clone()], records);
+    }
 }
@@ -2,12 +2,22 @@
 //! an adapter [in memory][MemoryAdapter] and another [sqlite][SQLiteAdapter].
 
 mod memory_adapter;
+mod query_filter;
+mod serializer;
 mod sqlite_adapter;
 mod store_adapter;
+mod time_series_adapter;
 
 pub use memory_adapter::MemoryAdapter;
+pub use query_filter::QueryFilter;
+#[cfg(feature = "bincode")]
+pub use serializer::BincodeSerializer;
+#[cfg(feature = "message_pack")]
+pub use serializer::MessagePackSerializer;
+pub use serializer::{JsonSerializer, StoreAdapterSerializer};
 pub use sqlite_adapter::{SQLiteAdapter, SQLiteResultIterator};
 pub use store_adapter::*;
+pub use time_series_adapter::TimeSeriesStoreAdapter;
 
 mod dumb_adapter;
 pub use dumb_adapter::DumbStoreAdapter;
@@ -0,0 +1,189 @@
+use anyhow::anyhow;
+use serde::Serialize;
+use sqlite::Value;
+
+use super::AdapterError;
+
+/// Type-safe filter on the keys of the records of a [StoreAdapter][super::StoreAdapter].
+///
+/// Keys are compared using their JSON representation, which means that numeric keys are
+/// compared as numbers and string keys are compared lexicographically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryFilter<K> {
+    /// The key is equal to the given key
+    Equal(K),
+
+    /// The key is strictly greater than the given key
+    GreaterThan(K),
+
+    /// The key is strictly lower than the given key
+    LessThan(K),
+
+    /// The key is between the two given keys, bounds included
+    Between(K, K),
+
+    /// Both filters must match
+    And(Box<QueryFilter<K>>, Box<QueryFilter<K>>),
+
+    /// At least one of the filters must match
+    Or(Box<QueryFilter<K>>, Box<QueryFilter<K>>),
+
+    /// The filter must not match
+    Not(Box<QueryFilter<K>>),
+}
+
+impl<K> QueryFilter<K> {
+    /// Combine this filter with another one, both must match.
+    pub fn and(self, other: QueryFilter<K>) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    /// Combine this filter with another one, at least one must match.
+    pub fn or(self, other: QueryFilter<K>) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+}
+
+impl<K: Serialize> QueryFilter<K> {
+    /// Build the parameterised SQL condition of this filter on the `key` column with its
+    /// parameters.
+    ///
+    /// The parameters are bound using anonymous `?` placeholders, in the order of the
+    /// returned values.
+    pub fn to_sql(&self) -> Result<(String, Vec<Value>), AdapterError> {
+        let mut parameters = Vec::new();
+        let condition = self.expand(&mut parameters)?;
+
+        Ok((condition, parameters))
+    }
+
+    fn expand(&self, parameters: &mut Vec<Value>) -> Result<String, AdapterError> {
+        let condition = match self {
+            Self::Equal(key) => Self::comparison("=", key, parameters)?,
+            Self::GreaterThan(key) => Self::comparison(">", key, parameters)?,
+            Self::LessThan(key) => Self::comparison("<", key, parameters)?,
+            Self::Between(from, to) => {
+                let from = Self::comparison("between", from, parameters)?;
+                let to = Self::serialize_key(to)?;
+                parameters.push(Value::String(to));
+
+                format!("{from} and ?")
+            }
+            Self::And(left, right) => format!(
+                "({} and {})",
+                left.expand(parameters)?,
+                right.expand(parameters)?
+            ),
+            Self::Or(left, right) => format!(
+                "({} or {})",
+                left.expand(parameters)?,
+                right.expand(parameters)?
+            ),
+            Self::Not(filter) => format!("not ({})", filter.expand(parameters)?),
+        };
+
+        Ok(condition)
+    }
+
+    fn comparison(
+        operator: &str,
+        key: &K,
+        parameters: &mut Vec<Value>,
+    ) -> Result<String, AdapterError> {
+        let key = Self::serialize_key(key)?;
+        parameters.push(Value::String(key));
+
+        Ok(format!("key {operator} ?"))
+    }
+
+    fn serialize_key(key: &K) -> Result<String, AdapterError> {
+        serde_json::to_string(key).map_err(|e| {
+            AdapterError::GeneralError(
+                anyhow!(e).context("Query filter: Serde error while serializing store key"),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comparison_generate_parameterised_sql() {
+        assert_eq!(
+            ("key = ?".to_string(), vec![Value::String("1".to_string())]),
+            QueryFilter::Equal(1).to_sql().unwrap()
+        );
+        assert_eq!(
+            ("key > ?".to_string(), vec![Value::String("1".to_string())]),
+            QueryFilter::GreaterThan(1).to_sql().unwrap()
+        );
+        assert_eq!(
+            ("key < ?".to_string(), vec![Value::String("1".to_string())]),
+            QueryFilter::LessThan(1).to_sql().unwrap()
+        );
+    }
+
+    #[test]
+    fn between_generate_parameterised_sql() {
+        assert_eq!(
+            (
+                "key between ? and ?".to_string(),
+                vec![
+                    Value::String("400".to_string()),
+                    Value::String("600".to_string())
+                ]
+            ),
+            QueryFilter::Between(400, 600).to_sql().unwrap()
+        );
+    }
+
+    #[test]
+    fn and_generate_parameterised_sql() {
+        let filter = QueryFilter::Equal("a").and(QueryFilter::GreaterThan("b"));
+
+        assert_eq!(
+            (
+                "(key = ? and key > ?)".to_string(),
+                vec![
+                    Value::String("\"a\"".to_string()),
+                    Value::String("\"b\"".to_string())
+                ]
+            ),
+            filter.to_sql().unwrap()
+        );
+    }
+
+    #[test]
+    fn nested_filters_generate_parameterised_sql() {
+        let filter = QueryFilter::Not(Box::new(
+            QueryFilter::LessThan(1).or(QueryFilter::Equal(5).and(QueryFilter::GreaterThan(3))),
+        ));
+
+        assert_eq!(
+            (
+                "not ((key < ? or (key = ? and key > ?)))".to_string(),
+                vec![
+                    Value::String("1".to_string()),
+                    Value::String("5".to_string()),
+                    Value::String("3".to_string()),
+                ]
+            ),
+            filter.to_sql().unwrap()
+        );
+    }
+
+    #[test]
+    fn key_values_are_never_interpolated_in_sql() {
+        let (sql, parameters) = QueryFilter::Equal("'; drop table store; --")
+            .to_sql()
+            .unwrap();
+
+        assert_eq!("key = ?", sql);
+        assert_eq!(
+            vec![Value::String("\"'; drop table store; --\"".to_string())],
+            parameters
+        );
+    }
+}
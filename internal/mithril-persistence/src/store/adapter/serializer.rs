@@ -0,0 +1,172 @@
+use anyhow::Context;
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+
+use mithril_common::StdResult;
+
+/// Serialization format of the records of a [StoreAdapter][super::StoreAdapter].
+pub trait StoreAdapterSerializer<V>: Sync + Send {
+    /// Serialize the given value to bytes
+    fn serialize(&self, value: &V) -> StdResult<Vec<u8>>;
+
+    /// Deserialize a value from the given bytes
+    fn deserialize(&self, bytes: &[u8]) -> StdResult<V>;
+}
+
+/// [StoreAdapterSerializer] to JSON, readable but verbose.
+pub struct JsonSerializer<V> {
+    value: PhantomData<fn() -> V>,
+}
+
+impl<V> JsonSerializer<V> {
+    /// JsonSerializer factory
+    pub fn new() -> Self {
+        Self { value: PhantomData }
+    }
+}
+
+impl<V> Default for JsonSerializer<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> StoreAdapterSerializer<V> for JsonSerializer<V>
+where
+    V: Serialize + DeserializeOwned,
+{
+    fn serialize(&self, value: &V) -> StdResult<Vec<u8>> {
+        serde_json::to_vec(value).with_context(|| "Could not serialize value to JSON")
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> StdResult<V> {
+        serde_json::from_slice(bytes).with_context(|| "Could not deserialize value from JSON")
+    }
+}
+
+/// [StoreAdapterSerializer] to [MessagePack](https://msgpack.org), a compact self-describing
+/// binary format.
+///
+/// The structs are serialized as maps so their fields are read back by name.
+#[cfg(feature = "message_pack")]
+pub struct MessagePackSerializer<V> {
+    value: PhantomData<fn() -> V>,
+}
+
+#[cfg(feature = "message_pack")]
+impl<V> MessagePackSerializer<V> {
+    /// MessagePackSerializer factory
+    pub fn new() -> Self {
+        Self { value: PhantomData }
+    }
+}
+
+#[cfg(feature = "message_pack")]
+impl<V> Default for MessagePackSerializer<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "message_pack")]
+impl<V> StoreAdapterSerializer<V> for MessagePackSerializer<V>
+where
+    V: Serialize + DeserializeOwned,
+{
+    fn serialize(&self, value: &V) -> StdResult<Vec<u8>> {
+        rmp_serde::to_vec_named(value).with_context(|| "Could not serialize value to MessagePack")
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> StdResult<V> {
+        rmp_serde::from_slice(bytes).with_context(|| "Could not deserialize value from MessagePack")
+    }
+}
+
+/// [StoreAdapterSerializer] to [bincode](https://github.com/bincode-org/bincode), the most
+/// compact and fastest of the formats.
+///
+/// The format is not self-describing: the values are read with the exact layout of their type,
+/// so it's not suited for types which serialization skips some fields.
+#[cfg(feature = "bincode")]
+pub struct BincodeSerializer<V> {
+    value: PhantomData<fn() -> V>,
+}
+
+#[cfg(feature = "bincode")]
+impl<V> BincodeSerializer<V> {
+    /// BincodeSerializer factory
+    pub fn new() -> Self {
+        Self { value: PhantomData }
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<V> Default for BincodeSerializer<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<V> StoreAdapterSerializer<V> for BincodeSerializer<V>
+where
+    V: Serialize + DeserializeOwned,
+{
+    fn serialize(&self, value: &V) -> StdResult<Vec<u8>> {
+        bincode::serialize(value).with_context(|| "Could not serialize value to bincode")
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> StdResult<V> {
+        bincode::deserialize(bytes).with_context(|| "Could not deserialize value from bincode")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::Certificate;
+    use mithril_common::messages::CertificateMessage;
+    use mithril_common::test_utils::fake_data;
+
+    use super::*;
+
+    fn assert_certificate_round_trip(serializer: &dyn StoreAdapterSerializer<CertificateMessage>) {
+        let certificate = fake_data::certificate("certificate-hash".to_string());
+        let message: CertificateMessage = certificate.clone().try_into().unwrap();
+
+        let bytes = serializer.serialize(&message).unwrap();
+        let deserialized_message = serializer.deserialize(&bytes).unwrap();
+
+        assert_eq!(message, deserialized_message);
+        assert_eq!(
+            certificate,
+            Certificate::try_from(deserialized_message).unwrap()
+        );
+    }
+
+    #[test]
+    fn json_serializer_round_trip_a_certificate() {
+        assert_certificate_round_trip(&JsonSerializer::new());
+    }
+
+    #[cfg(feature = "message_pack")]
+    #[test]
+    fn message_pack_serializer_round_trip_a_certificate() {
+        assert_certificate_round_trip(&MessagePackSerializer::new());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bincode_serializer_round_trip_a_certificate() {
+        assert_certificate_round_trip(&BincodeSerializer::new());
+    }
+
+    #[test]
+    fn deserializing_invalid_bytes_fails() {
+        let serializer: Box<dyn StoreAdapterSerializer<CertificateMessage>> =
+            Box::new(JsonSerializer::new());
+
+        serializer
+            .deserialize(b"invalid")
+            .expect_err("deserializing invalid bytes should fail");
+    }
+}
@@ -3,10 +3,22 @@ use async_trait::async_trait;
 use serde::{de::DeserializeOwned, Serialize};
 use sha2::{Digest, Sha256};
 use sqlite::{Connection, State, Statement};
-use std::{marker::PhantomData, sync::Arc, thread::sleep, time::Duration};
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+    thread::sleep,
+    time::Duration,
+};
+use tokio::sync::broadcast;
 
-use super::{AdapterError, StoreAdapter};
-use crate::sqlite::SqliteConnection;
+use mithril_common::StdResult;
+
+use super::{
+    broadcast_changes_stream, import_batches, AdapterError, ImportMode, ImportReport,
+    IndexableStoreAdapter, QueryFilter, RecordBatchIterator, StoreAdapter, StoreChange,
+    StoreChangeStream, STORE_CHANGES_CHANNEL_CAPACITY,
+};
+use crate::sqlite::{ConnectionExtensions, SqliteConnection};
 
 type Result<T> = std::result::Result<T, AdapterError>;
 
@@ -14,17 +26,27 @@ const DELAY_MS_ON_LOCK: u32 = 50;
 const NB_RETRIES_ON_LOCK: u32 = 3;
 
 /// Store adapter for SQLite3
+///
+/// **important:** only the changes made through this adapter are sent to the
+/// [changes][StoreAdapter::changes] subscribers, changes made directly in the database are not.
+///
+/// The [full-text search][StoreAdapter::search] requires a FTS5 virtual table named after the
+/// table of the adapter with a `_fts` suffix, indexing its `value` column with the table as
+/// external content. It must be created by a migration.
 pub struct SQLiteAdapter<K, V> {
     connection: Arc<SqliteConnection>,
     table: String,
+    changes: broadcast::Sender<StoreChange<K, V>>,
+    /// Changes held back until the transaction of an import is committed
+    pending_changes: Mutex<Option<Vec<StoreChange<K, V>>>>,
     key: PhantomData<K>,
     value: PhantomData<V>,
 }
 
 impl<K, V> SQLiteAdapter<K, V>
 where
-    K: Serialize,
-    V: DeserializeOwned,
+    K: Serialize + Clone,
+    V: DeserializeOwned + Clone,
 {
     /// Create a new SQLiteAdapter instance.
     pub fn new(table_name: &str, connection: Arc<SqliteConnection>) -> Result<Self> {
@@ -32,9 +54,13 @@ where
             Self::check_table_exists(&connection, table_name)?;
         }
 
+        let (changes, _) = broadcast::channel(STORE_CHANGES_CHANNEL_CAPACITY);
+
         Ok(Self {
             connection,
             table: table_name.to_owned(),
+            changes,
+            pending_changes: Mutex::new(None),
             key: PhantomData,
             value: PhantomData,
         })
@@ -56,14 +82,49 @@ where
 
         if table_exists != 1 {
             Self::create_table(connection, table_name)?;
+        } else {
+            Self::add_version_column_if_missing(connection, table_name)?;
+        }
+
+        Self::create_key_index_if_missing(connection, table_name)
+    }
+
+    fn check_fts_table_exists(connection: &Connection, fts_table: &str) -> Result<()> {
+        let sql = format!(
+            "select exists(select 1 from sqlite_master where type='table' and name='{fts_table}')"
+        );
+        let mut statement = connection
+            .prepare(sql)
+            .map_err(|e| AdapterError::OpeningStreamError(e.into()))?;
+        statement
+            .next()
+            .map_err(|e| AdapterError::QueryError(e.into()))?;
+        let table_exists = statement
+            .read::<i64, _>(0)
+            .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+
+        if table_exists != 1 {
+            return Err(AdapterError::QueryError(anyhow!(
+                "no full-text search table '{fts_table}' found"
+            )));
         }
 
         Ok(())
     }
 
+    /// The keys are indexed so the [queries][StoreAdapter::query] on a range of keys are
+    /// index range scans.
+    fn create_key_index_if_missing(connection: &Connection, table_name: &str) -> Result<()> {
+        connection
+            .execute(format!(
+                "create index if not exists {table_name}_key_range_index on {table_name}(key)"
+            ))
+            .map_err(|e| AdapterError::InitializationError(e.into()))
+    }
+
     fn create_table(connection: &Connection, table_name: &str) -> Result<()> {
         let sql = format!(
-            "create table {table_name} (key_hash text primary key, key json not null, value json not null)"
+            "create table {table_name} (key_hash text primary key, key json not null, value json not null, version integer not null default 1)"
         );
         connection
             .execute(sql)
@@ -72,6 +133,45 @@ where
         Ok(())
     }
 
+    /// Tables created before the records were versioned don't have a `version` column, their
+    /// existing records start at version 1.
+    fn add_version_column_if_missing(connection: &Connection, table_name: &str) -> Result<()> {
+        let sql = format!(
+            "select exists(select 1 from pragma_table_info('{table_name}') where name = 'version')"
+        );
+        let mut statement = connection
+            .prepare(sql)
+            .map_err(|e| AdapterError::OpeningStreamError(e.into()))?;
+        statement
+            .next()
+            .map_err(|e| AdapterError::QueryError(e.into()))?;
+        let column_exists = statement
+            .read::<i64, _>(0)
+            .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+
+        if column_exists != 1 {
+            connection
+                .execute(format!(
+                    "alter table {table_name} add column version integer not null default 1"
+                ))
+                .map_err(|e| AdapterError::QueryError(e.into()))?;
+        }
+
+        Ok(())
+    }
+
+    fn serialize_value(&self, record: &V) -> Result<String>
+    where
+        V: Serialize,
+    {
+        serde_json::to_string(record).map_err(|e| {
+            AdapterError::GeneralError(
+                anyhow!(e)
+                    .context("SQLite adapter error: could not serialize value before insertion"),
+            )
+        })
+    }
+
     fn get_hash_from_key(&self, key: &K) -> Result<String> {
         let mut hasher = Sha256::new();
         hasher.update(self.serialize_key(key)?);
@@ -88,6 +188,50 @@ where
         })
     }
 
+    /// Run a versioned write statement, returns the new version of the record or `None` if the
+    /// statement did not write anything because of a version mismatch.
+    fn execute_versioned_write(
+        &self,
+        sql: String,
+        key: &K,
+        record: &V,
+        expected_version: u64,
+    ) -> Result<Option<u64>>
+    where
+        V: Serialize,
+    {
+        let value = self.serialize_value(record)?;
+        let mut statement = self
+            .connection
+            .prepare(sql)
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        statement
+            .bind((1, self.get_hash_from_key(key)?.as_str()))
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        statement
+            .bind((2, self.serialize_key(key)?.as_str()))
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        statement
+            .bind((3, value.as_str()))
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        if expected_version > 0 {
+            statement
+                .bind((4, expected_version as i64))
+                .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        }
+
+        match statement
+            .next()
+            .map_err(|e| AdapterError::QueryError(e.into()))?
+        {
+            State::Row => statement
+                .read::<i64, _>(0)
+                .map(|version| Some(version as u64))
+                .map_err(|e| AdapterError::ParsingDataError(e.into())),
+            State::Done => Ok(None),
+        }
+    }
+
     // Connection must be locked from the calling function to be able to return
     // a Statement that references this connection.
     fn get_statement_for_key<'conn>(
@@ -95,7 +239,7 @@ where
         connection: &'conn Connection,
         sql: String,
         key: &K,
-    ) -> Result<Statement> {
+    ) -> Result<Statement<'conn>> {
         let mut statement = connection
             .prepare(sql)
             .map_err(|e| AdapterError::InitializationError(e.into()))?;
@@ -136,28 +280,86 @@ where
 
         Ok(maybe_value)
     }
+
+    fn has_changes_subscribers(&self) -> bool {
+        self.changes.receiver_count() > 0
+    }
+
+    fn notify(&self, change: StoreChange<K, V>) {
+        if let Some(pending_changes) = self.pending_changes.lock().unwrap().as_mut() {
+            pending_changes.push(change);
+            return;
+        }
+        // An error only means that there is no subscriber at the moment.
+        let _ = self.changes.send(change);
+    }
+}
+
+impl<K, V> SQLiteAdapter<K, V>
+where
+    K: Serialize + DeserializeOwned,
+    V: DeserializeOwned,
+{
+    /// Get the records which keys match the given `filter`, sorted by the given `order by`
+    /// clause.
+    fn query_ordered_by(
+        &self,
+        filter: QueryFilter<K>,
+        order_by: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<(K, V)>> {
+        let (condition, mut parameters) = filter.to_sql()?;
+        let mut sql = format!(
+            "select cast(key as text) as key, cast(value as text) as value from {} where {condition} order by {order_by}",
+            self.table
+        );
+        if let Some(limit) = limit {
+            sql.push_str(" limit ?");
+            parameters.push(sqlite::Value::Integer(limit as i64));
+        }
+        let mut statement = self
+            .connection
+            .prepare(sql)
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        statement
+            .bind(&parameters[..])
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+
+        statement
+            .iter()
+            .map(|row| {
+                let row = row.map_err(|e| AdapterError::QueryError(e.into()))?;
+                let key: K = serde_json::from_str(row.read::<&str, _>(0))
+                    .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+                let value: V = serde_json::from_str(row.read::<&str, _>(1))
+                    .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+
+                Ok((key, value))
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
 impl<K, V> StoreAdapter for SQLiteAdapter<K, V>
 where
-    K: Send + Sync + Serialize + DeserializeOwned,
-    V: Send + Sync + Serialize + DeserializeOwned,
+    K: Send + Sync + Serialize + DeserializeOwned + Clone + 'static,
+    V: Send + Sync + Serialize + DeserializeOwned + Clone + 'static,
 {
     type Key = K;
     type Record = V;
 
     async fn store_record(&mut self, key: &Self::Key, record: &Self::Record) -> Result<()> {
+        // Only check for an existing record when someone listens to the changes
+        let maybe_existed = match self.has_changes_subscribers() {
+            true => Some(self.record_exists(key).await?),
+            false => None,
+        };
         let sql = format!(
-            "insert into {} (key_hash, key, value) values (?1, ?2, ?3) on conflict (key_hash) do update set value = excluded.value",
+            "insert into {0} (key_hash, key, value) values (?1, ?2, ?3) on conflict (key_hash) do update set value = excluded.value, version = {0}.version + 1",
             self.table
         );
-        let value = serde_json::to_string(record).map_err(|e| {
-            AdapterError::GeneralError(
-                anyhow!(e)
-                    .context("SQLite adapter error: could not serialize value before insertion"),
-            )
-        })?;
+        let value = self.serialize_value(record)?;
         let mut statement = self
             .connection
             .prepare(sql)
@@ -175,6 +377,12 @@ where
             .next()
             .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
 
+        match maybe_existed {
+            Some(true) => self.notify(StoreChange::Updated(key.clone(), record.clone())),
+            Some(false) => self.notify(StoreChange::Inserted(key.clone(), record.clone())),
+            None => {}
+        }
+
         Ok(())
     }
 
@@ -238,8 +446,13 @@ where
             self.table
         );
         let statement = self.get_statement_for_key(&self.connection, sql, key)?;
+        let removed = self.fetch_maybe_one_value(statement)?;
 
-        self.fetch_maybe_one_value(statement)
+        if removed.is_some() {
+            self.notify(StoreChange::Deleted(key.clone()));
+        }
+
+        Ok(removed)
     }
 
     async fn get_iter(&self) -> Result<Box<dyn Iterator<Item = Self::Record> + '_>> {
@@ -247,6 +460,287 @@ where
 
         Ok(Box::new(iterator))
     }
+
+    async fn query(
+        &self,
+        filter: QueryFilter<Self::Key>,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Self::Key, Self::Record)>> {
+        self.query_ordered_by(filter, "ROWID desc", limit)
+    }
+
+    async fn query_by_key_desc(
+        &self,
+        filter: QueryFilter<Self::Key>,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Self::Key, Self::Record)>> {
+        // The column is qualified, `key` alone would be the key cast as text of the projection
+        self.query_ordered_by(filter, &format!("{}.key desc", self.table), limit)
+    }
+
+    async fn count(&self, filter: QueryFilter<Self::Key>) -> Result<u64> {
+        let (condition, parameters) = filter.to_sql()?;
+        let sql = format!("select count(*) from {} where {condition}", self.table);
+        let mut statement = self
+            .connection
+            .prepare(sql)
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        statement
+            .bind(&parameters[..])
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        statement
+            .next()
+            .map_err(|e| AdapterError::QueryError(e.into()))?;
+
+        statement
+            .read::<i64, _>(0)
+            .map(|count| count as u64)
+            .map_err(|e| AdapterError::ParsingDataError(e.into()))
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<(Self::Key, Self::Record)>> {
+        let fts_table = format!("{}_fts", self.table);
+        Self::check_fts_table_exists(&self.connection, &fts_table)?;
+        let sql = format!(
+            "select cast(t.key as text) as key, cast(t.value as text) as value from {table} as t join {fts_table} on {fts_table}.rowid = t.ROWID where {fts_table} match ?1 order by t.ROWID desc",
+            table = self.table
+        );
+        let mut statement = self
+            .connection
+            .prepare(sql)
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        // The query is searched as a phrase so its characters are not parsed as FTS5 operators
+        statement
+            .bind((1, format!("\"{}\"", query.replace('"', "\"\"")).as_str()))
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+
+        statement
+            .iter()
+            .map(|row| {
+                let row = row.map_err(|e| AdapterError::QueryError(e.into()))?;
+                let key: K = serde_json::from_str(row.read::<&str, _>(0))
+                    .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+                let value: V = serde_json::from_str(row.read::<&str, _>(1))
+                    .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    async fn delete_range(&mut self, from: &Self::Key, to: &Self::Key) -> Result<u64> {
+        let (condition, parameters) = QueryFilter::Between(from.clone(), to.clone()).to_sql()?;
+        let sql = format!(
+            "delete from {} where {condition} returning cast(key as text) as key",
+            self.table
+        );
+        let mut statement = self
+            .connection
+            .prepare(sql)
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        statement
+            .bind(&parameters[..])
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+
+        let mut deleted = 0;
+        for row in statement.iter() {
+            let row = row.map_err(|e| AdapterError::QueryError(e.into()))?;
+            deleted += 1;
+            if self.has_changes_subscribers() {
+                let key: K = serde_json::from_str(row.read::<&str, _>(0))
+                    .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+                self.notify(StoreChange::Deleted(key));
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    async fn get_record_version(&self, key: &Self::Key) -> Result<u64> {
+        let sql = format!("select version from {} where key_hash = ?1", self.table);
+        let mut statement = self.get_statement_for_key(&self.connection, sql, key)?;
+
+        match statement
+            .next()
+            .map_err(|e| AdapterError::QueryError(e.into()))?
+        {
+            State::Done => Ok(0),
+            State::Row => statement
+                .read::<i64, _>(0)
+                .map(|version| version as u64)
+                .map_err(|e| AdapterError::ParsingDataError(e.into())),
+        }
+    }
+
+    async fn get_versioned(&self, key: &Self::Key) -> Result<Option<(Self::Record, u64)>> {
+        // Read in a single statement so the version is the one of the returned value
+        let sql = format!(
+            "select value, version from {} where key_hash = ?1",
+            self.table
+        );
+        let mut statement = self.get_statement_for_key(&self.connection, sql, key)?;
+
+        match statement
+            .next()
+            .map_err(|e| AdapterError::QueryError(e.into()))?
+        {
+            State::Done => Ok(None),
+            State::Row => {
+                let value = statement
+                    .read::<String, _>(0)
+                    .map_err(|e| AdapterError::QueryError(e.into()))?;
+                let record = serde_json::from_str(&value)
+                    .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+                let version = statement
+                    .read::<i64, _>(1)
+                    .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+
+                Ok(Some((record, version as u64)))
+            }
+        }
+    }
+
+    async fn store_if_version(
+        &mut self,
+        key: &Self::Key,
+        record: &Self::Record,
+        expected_version: u64,
+    ) -> Result<u64> {
+        let sql = match expected_version {
+            0 => format!(
+                "insert into {} (key_hash, key, value) values (?1, ?2, ?3) on conflict (key_hash) do nothing returning version",
+                self.table
+            ),
+            _ => format!(
+                "update {} set key = ?2, value = ?3, version = version + 1 where key_hash = ?1 and version = ?4 returning version",
+                self.table
+            ),
+        };
+        let new_version = self.execute_versioned_write(sql, key, record, expected_version)?;
+        let Some(new_version) = new_version else {
+            return Err(AdapterError::VersionConflict {
+                expected: expected_version,
+                actual: self.get_record_version(key).await?,
+            });
+        };
+
+        match expected_version {
+            0 => self.notify(StoreChange::Inserted(key.clone(), record.clone())),
+            _ => self.notify(StoreChange::Updated(key.clone(), record.clone())),
+        }
+
+        Ok(new_version)
+    }
+
+    async fn cursor_export<'a>(
+        &'a self,
+        batch_size: usize,
+    ) -> Result<RecordBatchIterator<'a, Self::Key, Self::Record>> {
+        Ok(Box::new(SQLiteBatchIterator::new(
+            &self.connection,
+            &self.table,
+            batch_size,
+        )))
+    }
+
+    async fn import(
+        &mut self,
+        records: Vec<(Self::Key, Self::Record)>,
+        mode: ImportMode,
+    ) -> Result<ImportReport>
+    where
+        Self::Key: Serialize + Send + Sync,
+        Self::Record: Send + Sync,
+    {
+        self.cursor_import_from(std::iter::once(Ok(records)), mode)
+            .await
+    }
+
+    /// The records are imported in a transaction: the content of the store is kept if a batch
+    /// fails to be read or stored, and the changes are sent to the subscribers once committed.
+    async fn cursor_import_from<I>(&mut self, batches: I, mode: ImportMode) -> Result<ImportReport>
+    where
+        Self: Sized,
+        I: Iterator<Item = StdResult<Vec<(Self::Key, Self::Record)>>> + Send,
+        Self::Key: Serialize + Send + Sync,
+        Self::Record: Send + Sync,
+    {
+        let connection = self.connection.clone();
+        let transaction = connection
+            .begin_transaction()
+            .map_err(AdapterError::QueryError)?;
+        *self.pending_changes.lock().unwrap() = Some(vec![]);
+
+        let report = import_batches(self, batches, mode).await;
+        let pending_changes = self.pending_changes.lock().unwrap().take();
+        let report = report?;
+        transaction
+            .commit()
+            .map_err(|e| AdapterError::QueryError(e.into()))?;
+
+        for change in pending_changes.unwrap_or_default() {
+            self.notify(change);
+        }
+
+        Ok(report)
+    }
+
+    fn changes(&self) -> StoreChangeStream<Self::Key, Self::Record> {
+        broadcast_changes_stream(self.changes.subscribe())
+    }
+}
+
+#[async_trait]
+impl<K, V> IndexableStoreAdapter for SQLiteAdapter<K, V>
+where
+    K: Send + Sync + Serialize + DeserializeOwned + Clone + 'static,
+    V: Send + Sync + Serialize + DeserializeOwned + Clone + 'static,
+{
+    /// Create an index on the given fields of the JSON records.
+    ///
+    /// Fields are JSON paths relative to the record (ie: `epoch` or `beacon.epoch`), queries must
+    /// filter on `json_extract(value, '$.<field>')` to use the index.
+    async fn create_compound_index(&self, columns: &[&str], unique: bool) -> Result<()> {
+        if columns.is_empty() {
+            return Err(AdapterError::InitializationError(anyhow!(
+                "SQLite adapter error: can not create an index without column"
+            )));
+        }
+        if let Some(column) = columns.iter().find(|c| !Self::is_valid_index_column(c)) {
+            return Err(AdapterError::InitializationError(anyhow!(
+                "SQLite adapter error: invalid index column '{column}'"
+            )));
+        }
+
+        let index_name = format!(
+            "{}_{}_index",
+            self.table,
+            columns.join("_").replace('.', "_")
+        );
+        let expressions = columns
+            .iter()
+            .map(|column| format!("json_extract(value, '$.{column}')"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "create {}index if not exists {index_name} on {}({expressions})",
+            if unique { "unique " } else { "" },
+            self.table
+        );
+
+        self.connection
+            .execute(sql)
+            .map_err(|e| AdapterError::InitializationError(e.into()))
+    }
+}
+
+impl<K, V> SQLiteAdapter<K, V> {
+    fn is_valid_index_column(column: &str) -> bool {
+        !column.is_empty()
+            && column
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    }
 }
 
 /// Iterator over SQLite adapter results.
@@ -291,9 +785,98 @@ impl<V> Iterator for SQLiteResultIterator<V> {
     }
 }
 
+/// Iterator over the batches of records of a [SQLiteAdapter] table, from the oldest to the
+/// newest.
+///
+/// Each batch is fetched when it's read, with an `OFFSET` on the records ordered by `ROWID`.
+struct SQLiteBatchIterator<'conn, K, V> {
+    connection: &'conn SqliteConnection,
+    table: String,
+    batch_size: usize,
+    offset: usize,
+    is_exhausted: bool,
+    key: PhantomData<K>,
+    value: PhantomData<V>,
+}
+
+impl<'conn, K, V> SQLiteBatchIterator<'conn, K, V>
+where
+    K: DeserializeOwned,
+    V: DeserializeOwned,
+{
+    fn new(connection: &'conn SqliteConnection, table_name: &str, batch_size: usize) -> Self {
+        Self {
+            connection,
+            table: table_name.to_owned(),
+            batch_size: batch_size.max(1),
+            offset: 0,
+            is_exhausted: false,
+            key: PhantomData,
+            value: PhantomData,
+        }
+    }
+
+    fn fetch_batch(&self) -> StdResult<Vec<(K, V)>> {
+        let sql = format!(
+            "select cast(key as text) as key, cast(value as text) as value from {} order by ROWID asc limit ?1 offset ?2",
+            self.table
+        );
+        let mut statement = self.connection.prepare(sql)?;
+        statement.bind((1, self.batch_size as i64))?;
+        statement.bind((2, self.offset as i64))?;
+
+        statement
+            .iter()
+            .map(|row| {
+                let row = row?;
+                let key: K = serde_json::from_str(row.read::<&str, _>(0))?;
+                let value: V = serde_json::from_str(row.read::<&str, _>(1))?;
+
+                Ok((key, value))
+            })
+            .collect()
+    }
+}
+
+impl<K, V> Iterator for SQLiteBatchIterator<'_, K, V>
+where
+    K: DeserializeOwned,
+    V: DeserializeOwned,
+{
+    type Item = StdResult<Vec<(K, V)>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_exhausted {
+            return None;
+        }
+
+        match self.fetch_batch() {
+            Ok(batch) if batch.is_empty() => {
+                self.is_exhausted = true;
+                None
+            }
+            Ok(batch) => {
+                self.offset += batch.len();
+                self.is_exhausted = batch.len() < self.batch_size;
+                Some(Ok(batch))
+            }
+            Err(error) => {
+                self.is_exhausted = true;
+                Some(Err(error.context(format!(
+                    "Could not fetch the records of table '{}' from offset {}",
+                    self.table, self.offset
+                ))))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use futures::StreamExt;
+    use mithril_common::entities::StakeDistributionParty;
     use mithril_common::test_utils::TempDir;
+    use serde::Deserialize;
     use sqlite::Value;
     use std::path::{Path, PathBuf};
 
@@ -458,6 +1041,219 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_query() {
+        let test_name = "test_query";
+        let mut adapter = init_db(&get_file_path(test_name), None);
+        for (key, value) in [(1, "one"), (2, "two"), (3, "three"), (10, "ten")] {
+            adapter
+                .store_record(&key, &value.to_string())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            vec![(2_u64, "two".to_string())],
+            adapter
+                .query(QueryFilter::Equal(2), None)
+                .await
+                .expect("query should not fail")
+        );
+        assert_eq!(
+            vec![(10_u64, "ten".to_string()), (3_u64, "three".to_string())],
+            adapter
+                .query(QueryFilter::GreaterThan(2), None)
+                .await
+                .expect("query should not fail")
+        );
+        assert_eq!(
+            vec![(10_u64, "ten".to_string()), (1_u64, "one".to_string())],
+            adapter
+                .query(
+                    QueryFilter::Not(Box::new(
+                        QueryFilter::GreaterThan(1).and(QueryFilter::LessThan(10))
+                    )),
+                    None
+                )
+                .await
+                .expect("query should not fail")
+        );
+        assert_eq!(
+            vec![(10_u64, "ten".to_string())],
+            adapter
+                .query(
+                    QueryFilter::LessThan(2).or(QueryFilter::GreaterThan(2)),
+                    Some(1)
+                )
+                .await
+                .expect("query should not fail")
+        );
+    }
+
+    #[tokio::test]
+    async fn query_on_a_key_range_uses_the_key_index() {
+        let test_name = "query_on_a_key_range_uses_the_key_index";
+        let connection = Arc::new(Connection::open_thread_safe(get_file_path(test_name)).unwrap());
+        let mut adapter: SQLiteAdapter<u64, String> =
+            SQLiteAdapter::new(TABLE_NAME, connection.clone()).unwrap();
+        for key in [1, 5, 10, 50, 100] {
+            adapter.store_record(&key, &key.to_string()).await.unwrap();
+        }
+
+        assert_eq!(
+            vec![(50_u64, "50".to_string()), (10_u64, "10".to_string())],
+            adapter
+                .query(QueryFilter::Between(6, 50), None)
+                .await
+                .unwrap()
+        );
+        let query_plan = explain_query_plan(
+            &connection,
+            &format!("select value from {TABLE_NAME} where key between '6' and '50'"),
+        );
+        assert!(
+            query_plan
+                .iter()
+                .any(|detail| detail.contains(&format!("{TABLE_NAME}_key_range_index"))),
+            "query plan should use the key index: {query_plan:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn count_and_query_by_key_desc_on_a_key_range() {
+        let test_name = "count_and_query_by_key_desc_on_a_key_range";
+        let connection = Arc::new(Connection::open_thread_safe(get_file_path(test_name)).unwrap());
+        let mut adapter: SQLiteAdapter<u64, String> =
+            SQLiteAdapter::new(TABLE_NAME, connection).unwrap();
+        // Written out of order to check that the keys, not the write order, are used
+        for key in [50, 1, 100, 5, 10] {
+            adapter.store_record(&key, &key.to_string()).await.unwrap();
+        }
+
+        assert_eq!(3, adapter.count(QueryFilter::Between(5, 50)).await.unwrap());
+        assert_eq!(
+            0,
+            adapter.count(QueryFilter::GreaterThan(100)).await.unwrap()
+        );
+        assert_eq!(
+            vec![(50_u64, "50".to_string()), (10_u64, "10".to_string())],
+            adapter
+                .query_by_key_desc(QueryFilter::Between(5, 50), Some(2))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            vec![100, 50, 10, 5, 1],
+            adapter
+                .query_by_key_desc(QueryFilter::Between(0, u64::MAX), None)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    fn explain_query_plan(connection: &Connection, sql: &str) -> Vec<String> {
+        connection
+            .prepare(format!("explain query plan {sql}"))
+            .unwrap()
+            .iter()
+            .map(|row| row.unwrap().read::<&str, _>("detail").to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn created_index_is_used_by_queries_on_its_field() {
+        let test_name = "created_index_is_used_by_queries_on_its_field";
+        let connection = Arc::new(Connection::open_thread_safe(get_file_path(test_name)).unwrap());
+        let mut adapter: SQLiteAdapter<u64, serde_json::Value> =
+            SQLiteAdapter::new(TABLE_NAME, connection.clone()).unwrap();
+
+        adapter.create_index("epoch", false).await.unwrap();
+        for key in 0..1000_u64 {
+            adapter
+                .store_record(
+                    &key,
+                    &serde_json::json!({ "epoch": key / 10, "digest": format!("digest-{key}") }),
+                )
+                .await
+                .unwrap();
+        }
+
+        let query_plan = explain_query_plan(
+            &connection,
+            &format!("select value from {TABLE_NAME} where json_extract(value, '$.epoch') = 5"),
+        );
+        assert!(
+            query_plan
+                .iter()
+                .any(|detail| detail.contains(&format!("USING INDEX {TABLE_NAME}_epoch_index"))),
+            "query plan should use the index: {query_plan:?}"
+        );
+        let query_plan = explain_query_plan(
+            &connection,
+            &format!("select value from {TABLE_NAME} where json_extract(value, '$.digest') = 'a'"),
+        );
+        assert!(
+            query_plan
+                .iter()
+                .all(|detail| !detail.contains("USING INDEX")),
+            "query plan should not use any index: {query_plan:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn create_compound_and_unique_indexes() {
+        let test_name = "create_compound_and_unique_indexes";
+        let connection = Arc::new(Connection::open_thread_safe(get_file_path(test_name)).unwrap());
+        let mut adapter: SQLiteAdapter<u64, serde_json::Value> =
+            SQLiteAdapter::new(TABLE_NAME, connection.clone()).unwrap();
+
+        adapter
+            .create_compound_index(&["epoch", "beacon.digest"], false)
+            .await
+            .unwrap();
+        adapter.create_index("digest", true).await.unwrap();
+        // creating an existing index is a no-op
+        adapter.create_index("digest", true).await.unwrap();
+
+        let query_plan = explain_query_plan(
+            &connection,
+            &format!("select value from {TABLE_NAME} where json_extract(value, '$.epoch') = 5 and json_extract(value, '$.beacon.digest') = 'a'"),
+        );
+        assert!(
+            query_plan.iter().any(|detail| detail.contains(&format!(
+                "USING INDEX {TABLE_NAME}_epoch_beacon_digest_index"
+            ))),
+            "query plan should use the compound index: {query_plan:?}"
+        );
+
+        adapter
+            .store_record(&1, &serde_json::json!({ "digest": "digest-1" }))
+            .await
+            .unwrap();
+        adapter
+            .store_record(&2, &serde_json::json!({ "digest": "digest-1" }))
+            .await
+            .expect_err("unique index should prevent storing two records with the same digest");
+    }
+
+    #[tokio::test]
+    async fn create_index_with_invalid_column_fails() {
+        let test_name = "create_index_with_invalid_column_fails";
+        let adapter = init_db(&get_file_path(test_name), None);
+
+        adapter
+            .create_index("epoch'); drop table key_value_store; --", false)
+            .await
+            .expect_err("invalid column should be rejected");
+        adapter
+            .create_compound_index(&[], false)
+            .await
+            .expect_err("index without column should be rejected");
+    }
+
     #[tokio::test]
     async fn check_get_last_n_modified_records() {
         let test_name = "check_get_last_n_modified_records";
@@ -478,4 +1274,275 @@ mod tests {
             values
         );
     }
+
+    #[tokio::test]
+    async fn changes_stream_yields_mutations_made_through_the_adapter() {
+        let test_name = "changes_stream_yields_mutations_made_through_the_adapter";
+        let mut adapter = init_db(&get_file_path(test_name), None);
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+        let changes = adapter.changes();
+
+        adapter.store_record(&2, &"two".to_string()).await.unwrap();
+        adapter
+            .store_record(&1, &"updated record".to_string())
+            .await
+            .unwrap();
+        adapter.remove(&2).await.unwrap();
+        adapter.remove(&42).await.unwrap();
+        drop(adapter);
+
+        let changes: Vec<StoreChange<u64, String>> = changes.collect().await;
+        assert_eq!(
+            vec![
+                StoreChange::Inserted(2, "two".to_string()),
+                StoreChange::Updated(1, "updated record".to_string()),
+                StoreChange::Deleted(2),
+            ],
+            changes
+        );
+    }
+
+    #[tokio::test]
+    async fn store_if_version_increments_the_record_version() {
+        let filepath = get_file_path("store_if_version_increments_the_record_version");
+        let mut adapter = init_db(&filepath, None);
+
+        assert_eq!(0, adapter.get_record_version(&1).await.unwrap());
+        assert_eq!(
+            1,
+            adapter
+                .store_if_version(&1, &"one".to_string(), 0)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            2,
+            adapter
+                .store_if_version(&1, &"uno".to_string(), 1)
+                .await
+                .unwrap()
+        );
+        adapter.store_record(&1, &"eins".to_string()).await.unwrap();
+
+        assert_eq!(3, adapter.get_record_version(&1).await.unwrap());
+        assert_eq!(
+            Some("eins".to_string()),
+            adapter.get_record(&1).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn store_if_version_fails_on_a_version_mismatch() {
+        let filepath = get_file_path("store_if_version_fails_on_a_version_mismatch");
+        let mut adapter = init_db(&filepath, None);
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+
+        let error = adapter
+            .store_if_version(&1, &"uno".to_string(), 0)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                error,
+                AdapterError::VersionConflict {
+                    expected: 0,
+                    actual: 1
+                }
+            ),
+            "unexpected error: {error:?}"
+        );
+        assert_eq!(
+            Some("one".to_string()),
+            adapter.get_record(&1).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn get_versioned_returns_the_record_with_its_version() {
+        let filepath = get_file_path("get_versioned_returns_the_record_with_its_version");
+        let mut adapter = init_db(&filepath, None);
+
+        assert_eq!(None, adapter.get_versioned(&1).await.unwrap());
+
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+        adapter.store_record(&1, &"uno".to_string()).await.unwrap();
+
+        assert_eq!(
+            Some(("uno".to_string(), 2)),
+            adapter.get_versioned(&1).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn update_with_the_version_of_a_stale_read_fails() {
+        let filepath = get_file_path("update_with_the_version_of_a_stale_read_fails");
+        let mut adapter = init_db(&filepath, None);
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+
+        let (_, read_version) = adapter.get_versioned(&1).await.unwrap().unwrap();
+        assert_eq!(1, read_version);
+        // Another writer updates the record between the read and the update
+        let (record, version) = adapter.get_versioned(&1).await.unwrap().unwrap();
+        adapter
+            .store_if_version(&1, &format!("{record} updated"), version)
+            .await
+            .unwrap();
+
+        let error = adapter
+            .store_if_version(&1, &"stale update".to_string(), read_version)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                error,
+                AdapterError::VersionConflict {
+                    expected: 1,
+                    actual: 2
+                }
+            ),
+            "unexpected error: {error:?}"
+        );
+        assert_eq!(
+            Some(("one updated".to_string(), 2)),
+            adapter.get_versioned(&1).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn only_one_of_two_concurrent_versioned_writes_succeeds() {
+        let filepath = get_file_path("only_one_of_two_concurrent_versioned_writes_succeeds");
+        let connection = Arc::new(Connection::open_thread_safe(&filepath).unwrap());
+        let mut adapter: SQLiteAdapter<u64, String> =
+            SQLiteAdapter::new(TABLE_NAME, connection.clone()).unwrap();
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+
+        let writers: Vec<_> = ["first", "second"]
+            .into_iter()
+            .map(|value| {
+                let mut writer: SQLiteAdapter<u64, String> =
+                    SQLiteAdapter::new(TABLE_NAME, connection.clone()).unwrap();
+                tokio::spawn(
+                    async move { writer.store_if_version(&1, &value.to_string(), 1).await },
+                )
+            })
+            .collect();
+
+        let mut results = vec![];
+        for writer in writers {
+            results.push(writer.await.unwrap());
+        }
+
+        assert_eq!(1, results.iter().filter(|result| result.is_ok()).count());
+        assert!(results.iter().any(|result| matches!(
+            result,
+            Err(AdapterError::VersionConflict {
+                expected: 1,
+                actual: 2
+            })
+        )));
+        assert_eq!(2, adapter.get_record_version(&1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn opening_a_table_without_version_column_adds_it() {
+        let filepath = get_file_path("opening_a_table_without_version_column_adds_it");
+        let connection = Connection::open_thread_safe(&filepath).unwrap();
+        connection
+            .execute(format!(
+                "create table {TABLE_NAME} (key_hash text primary key, key json not null, value json not null)"
+            ))
+            .unwrap();
+        let connection = Arc::new(connection);
+        let mut legacy_adapter: SQLiteAdapter<u64, String> =
+            SQLiteAdapter::new(TABLE_NAME, connection).unwrap();
+
+        legacy_adapter
+            .store_record(&1, &"one".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(1, legacy_adapter.get_record_version(&1).await.unwrap());
+    }
+
+    /// Certificate metadata searchable by the party ids of its signers
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct CertificateSigners {
+        hash: String,
+        signers: Vec<StakeDistributionParty>,
+    }
+
+    fn certificates_with_signers(signers: &[&[&str]]) -> Vec<(String, CertificateSigners)> {
+        signers
+            .iter()
+            .enumerate()
+            .map(|(index, party_ids)| {
+                let certificate = CertificateSigners {
+                    hash: format!("certificate-{index}"),
+                    signers: party_ids
+                        .iter()
+                        .map(|party_id| StakeDistributionParty {
+                            party_id: party_id.to_string(),
+                            stake: 100 * (index as u64 + 1),
+                        })
+                        .collect(),
+                };
+
+                (certificate.hash.clone(), certificate)
+            })
+            .collect()
+    }
+
+    fn create_fts_table(connection: &Connection, table_name: &str) {
+        connection
+            .execute(format!(
+                r#"
+create virtual table {table_name}_fts using fts5(value, content='{table_name}', content_rowid='rowid');
+create trigger {table_name}_fts_insert after insert on {table_name} begin
+    insert into {table_name}_fts(rowid, value) values (new.rowid, new.value);
+end;
+create trigger {table_name}_fts_delete after delete on {table_name} begin
+    insert into {table_name}_fts({table_name}_fts, rowid, value) values ('delete', old.rowid, old.value);
+end;
+create trigger {table_name}_fts_update after update on {table_name} begin
+    insert into {table_name}_fts({table_name}_fts, rowid, value) values ('delete', old.rowid, old.value);
+    insert into {table_name}_fts(rowid, value) values (new.rowid, new.value);
+end;
+"#
+            ))
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn search_returns_the_records_containing_the_query() {
+        let connection = Arc::new(Connection::open_thread_safe(":memory:").unwrap());
+        let mut adapter: SQLiteAdapter<String, CertificateSigners> =
+            SQLiteAdapter::new("certificate_store", connection.clone()).unwrap();
+        create_fts_table(&connection, "certificate_store");
+        let certificates = certificates_with_signers(&[
+            &["pool1aaa", "pool1bbb"],
+            &["pool1ccc", "pool1ddd"],
+            &["pool1aaa", "pool1eee"],
+        ]);
+        for (key, certificate) in &certificates {
+            adapter.store_record(key, certificate).await.unwrap();
+        }
+
+        let records = adapter.search("pool1ddd").await.unwrap();
+
+        assert_eq!(vec![certificates[1].clone()], records);
+    }
+
+    #[tokio::test]
+    async fn search_without_fts_table_fails() {
+        let adapter = init_db(&get_file_path("search_without_fts_table_fails"), None);
+
+        let error = adapter
+            .search("value")
+            .await
+            .expect_err("search without a full-text search table should fail");
+
+        assert!(matches!(error, AdapterError::QueryError(_)));
+    }
 }
@@ -1,6 +1,15 @@
+use anyhow::anyhow;
 use async_trait::async_trait;
-use mithril_common::StdError;
+use futures::stream::{self, BoxStream, StreamExt};
+use mithril_common::{StdError, StdResult};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use thiserror::Error;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use super::QueryFilter;
 
 /// [StoreAdapter] related errors
 #[derive(Debug, Error)]
@@ -24,8 +33,118 @@ pub enum AdapterError {
     /// Error while querying the subsystem.
     #[error("problem when querying the adapter")]
     QueryError(#[source] StdError),
+
+    /// The version of the stored record is not the one expected by a
+    /// [versioned write][StoreAdapter::store_if_version].
+    #[error("record version conflict: expected version {expected}, actual version {actual}")]
+    VersionConflict {
+        /// Version expected by the writer
+        expected: u64,
+
+        /// Actual version of the stored record
+        actual: u64,
+    },
+}
+
+/// A mutation applied to the content of a [StoreAdapter].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreChange<K, V> {
+    /// A record was stored with a key that did not exist yet.
+    Inserted(K, V),
+
+    /// The record stored with an existing key was replaced.
+    Updated(K, V),
+
+    /// The record stored with the key was removed.
+    Deleted(K),
+}
+
+/// Stream of the [changes][StoreChange] applied to a [StoreAdapter].
+pub type StoreChangeStream<K, V> = BoxStream<'static, StoreChange<K, V>>;
+
+/// Number of changes kept for a subscriber that is late at reading its stream.
+///
+/// A lagging subscriber skips the oldest changes instead of blocking the writers.
+pub(crate) const STORE_CHANGES_CHANNEL_CAPACITY: usize = 1024;
+
+/// Turn a broadcast receiver of changes into a [StoreChangeStream].
+pub(crate) fn broadcast_changes_stream<K, V>(
+    receiver: broadcast::Receiver<StoreChange<K, V>>,
+) -> StoreChangeStream<K, V>
+where
+    K: Clone + Send + 'static,
+    V: Clone + Send + 'static,
+{
+    stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(change) => return Some((change, receiver)),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .boxed()
+}
+
+/// How the [imported][StoreAdapter::import] records are merged with the records already in the
+/// store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// The content of the store is replaced by the imported records, the stored records that
+    /// are not imported are removed.
+    Replace,
+
+    /// The imported records replace the stored records with the same key, the other stored
+    /// records are kept.
+    Merge,
+
+    /// The imported records with a key that is already stored are skipped.
+    SkipExisting,
 }
 
+impl FromStr for ImportMode {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "replace" => Ok(Self::Replace),
+            "merge" => Ok(Self::Merge),
+            "skip-existing" => Ok(Self::SkipExisting),
+            _ => Err(anyhow!(
+                "unknown import mode '{s}', expected one of: replace, merge, skip-existing"
+            )),
+        }
+    }
+}
+
+impl Display for ImportMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Replace => write!(f, "replace"),
+            Self::Merge => write!(f, "merge"),
+            Self::SkipExisting => write!(f, "skip-existing"),
+        }
+    }
+}
+
+/// Number of records written or skipped by an [import][StoreAdapter::import].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Records stored with a key that did not exist yet
+    pub inserted: u64,
+
+    /// Records not stored because their key already existed
+    pub skipped: u64,
+
+    /// Records that replaced a stored record with the same key
+    pub replaced: u64,
+}
+
+/// Iterator over the batches of records of a [cursor export][StoreAdapter::cursor_export].
+pub type RecordBatchIterator<'a, K, V> =
+    Box<dyn Iterator<Item = StdResult<Vec<(K, V)>>> + Send + 'a>;
+
 /// Represent a way to store Key/Value pair data.
 #[async_trait]
 pub trait StoreAdapter: Sync + Send {
@@ -61,4 +180,639 @@ pub trait StoreAdapter: Sync + Send {
 
     /// Get an iterator over the stored values, from the latest to the oldest.
     async fn get_iter(&self) -> Result<Box<dyn Iterator<Item = Self::Record> + '_>, AdapterError>;
+
+    /// Get the records which keys match the given `filter`, from the latest to the oldest.
+    ///
+    /// Adapters that do not support queries return a [AdapterError::QueryError].
+    async fn query(
+        &self,
+        _filter: QueryFilter<Self::Key>,
+        _limit: Option<usize>,
+    ) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError>
+    where
+        Self::Key: Send + 'static,
+    {
+        Err(AdapterError::QueryError(anyhow!(
+            "this adapter does not support queries"
+        )))
+    }
+
+    /// Get the records which keys match the given `filter`, from the highest to the lowest key.
+    ///
+    /// Adapters that do not support queries return a [AdapterError::QueryError].
+    async fn query_by_key_desc(
+        &self,
+        _filter: QueryFilter<Self::Key>,
+        _limit: Option<usize>,
+    ) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError>
+    where
+        Self::Key: Send + 'static,
+    {
+        Err(AdapterError::QueryError(anyhow!(
+            "this adapter does not support queries"
+        )))
+    }
+
+    /// Count the records which keys match the given `filter`.
+    ///
+    /// Adapters that do not support queries return a [AdapterError::QueryError].
+    async fn count(&self, _filter: QueryFilter<Self::Key>) -> Result<u64, AdapterError>
+    where
+        Self::Key: Send + 'static,
+    {
+        Err(AdapterError::QueryError(anyhow!(
+            "this adapter does not support queries"
+        )))
+    }
+
+    /// Get the records which serialized value contains the given text, from the latest to the
+    /// oldest.
+    ///
+    /// Adapters that do not support full-text search return a [AdapterError::QueryError].
+    async fn search(&self, _query: &str) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError>
+    where
+        Self::Record: Serialize,
+    {
+        Err(AdapterError::QueryError(anyhow!(
+            "this adapter does not support full-text search"
+        )))
+    }
+
+    /// Remove the records which keys are between `from` and `to`, bounds included, and return
+    /// the number of removed records.
+    ///
+    /// Adapters that do not support range deletion return a [AdapterError::QueryError].
+    async fn delete_range(
+        &mut self,
+        _from: &Self::Key,
+        _to: &Self::Key,
+    ) -> Result<u64, AdapterError>
+    where
+        Self::Key: PartialOrd,
+    {
+        Err(AdapterError::QueryError(anyhow!(
+            "this adapter does not support range deletion"
+        )))
+    }
+
+    /// Get the version of the record stored using the given `key`, `0` if there's none.
+    ///
+    /// The version of a record is incremented each time it is stored.
+    ///
+    /// Adapters that do not support versioned records return a [AdapterError::QueryError].
+    async fn get_record_version(&self, _key: &Self::Key) -> Result<u64, AdapterError> {
+        Err(AdapterError::QueryError(anyhow!(
+            "this adapter does not support versioned records"
+        )))
+    }
+
+    /// Get the record stored using the given `key` with its version, to
+    /// [update it][Self::store_if_version] only if it was not updated in the meantime.
+    ///
+    /// Adapters that do not support versioned records return a [AdapterError::QueryError].
+    async fn get_versioned(
+        &self,
+        _key: &Self::Key,
+    ) -> Result<Option<(Self::Record, u64)>, AdapterError> {
+        Err(AdapterError::QueryError(anyhow!(
+            "this adapter does not support versioned records"
+        )))
+    }
+
+    /// Store the given `record` only if the version of the stored record is still the
+    /// `expected_version`, use `0` to store a record that must not exist yet.
+    ///
+    /// Returns the new version of the record, or a [AdapterError::VersionConflict] if the
+    /// record was updated in the meantime.
+    ///
+    /// Adapters that do not support versioned records return a [AdapterError::QueryError].
+    async fn store_if_version(
+        &mut self,
+        _key: &Self::Key,
+        _record: &Self::Record,
+        _expected_version: u64,
+    ) -> Result<u64, AdapterError> {
+        Err(AdapterError::QueryError(anyhow!(
+            "this adapter does not support versioned records"
+        )))
+    }
+
+    /// Export all the stored records, from the oldest to the newest.
+    ///
+    /// Importing the exported records in an empty store keeps their order.
+    async fn export(&self) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
+        let mut records = self.get_last_n_records(usize::MAX).await?;
+        records.reverse();
+
+        Ok(records)
+    }
+
+    /// Import the given records, in order, merging them with the stored records according to
+    /// the given [ImportMode].
+    async fn import(
+        &mut self,
+        records: Vec<(Self::Key, Self::Record)>,
+        mode: ImportMode,
+    ) -> Result<ImportReport, AdapterError>
+    where
+        Self::Key: Serialize + Send + Sync,
+        Self::Record: Send + Sync,
+    {
+        import_batches(self, std::iter::once(Ok(records)), mode).await
+    }
+
+    /// Export all the stored records, from the oldest to the newest, in batches of at most
+    /// `batch_size` records.
+    ///
+    /// Adapters backed by a database fetch each batch when it's read instead of loading all
+    /// the records at once.
+    async fn cursor_export<'a>(
+        &'a self,
+        batch_size: usize,
+    ) -> Result<RecordBatchIterator<'a, Self::Key, Self::Record>, AdapterError>
+    where
+        Self::Key: Send + 'static,
+        Self::Record: Send + 'static,
+    {
+        let mut records = self.export().await?.into_iter();
+        let batch_size = batch_size.max(1);
+
+        Ok(Box::new(std::iter::from_fn(move || {
+            let batch: Vec<_> = records.by_ref().take(batch_size).collect();
+            (!batch.is_empty()).then_some(Ok(batch))
+        })))
+    }
+
+    /// Import the records of the given batches, such as the ones of a
+    /// [cursor export][Self::cursor_export], in order, merging them with the stored records
+    /// according to the given [ImportMode].
+    ///
+    /// The import stops at the first batch that fails to be read. In [ImportMode::Replace] all
+    /// the batches are read before the stored records are removed, so the content of the store
+    /// is kept if one of them fails.
+    async fn cursor_import_from<I>(
+        &mut self,
+        batches: I,
+        mode: ImportMode,
+    ) -> Result<ImportReport, AdapterError>
+    where
+        Self: Sized,
+        I: Iterator<Item = StdResult<Vec<(Self::Key, Self::Record)>>> + Send,
+        Self::Key: Serialize + Send + Sync,
+        Self::Record: Send + Sync,
+    {
+        match mode {
+            ImportMode::Replace => {
+                let staged_batches = batches
+                    .collect::<StdResult<Vec<_>>>()
+                    .map_err(AdapterError::GeneralError)?;
+                import_batches(self, staged_batches.into_iter().map(Ok), mode).await
+            }
+            ImportMode::Merge | ImportMode::SkipExisting => {
+                import_batches(self, batches, mode).await
+            }
+        }
+    }
+
+    /// Subscribe to the changes applied to the store from now on.
+    ///
+    /// Dropping the returned stream cancels the subscription.
+    /// Adapters that do not support change notifications return an empty stream.
+    fn changes(&self) -> StoreChangeStream<Self::Key, Self::Record>
+    where
+        Self::Key: Send + 'static,
+        Self::Record: Send + 'static,
+    {
+        stream::empty().boxed()
+    }
+}
+
+/// Import the records of the given batches, in order, stopping at the first batch that fails
+/// to be read.
+pub(crate) async fn import_batches<A, I>(
+    adapter: &mut A,
+    batches: I,
+    mode: ImportMode,
+) -> Result<ImportReport, AdapterError>
+where
+    A: StoreAdapter + ?Sized,
+    I: Iterator<Item = StdResult<Vec<(A::Key, A::Record)>>> + Send,
+    A::Key: Serialize + Send + Sync,
+    A::Record: Send + Sync,
+{
+    let replaced_keys = remove_replaced_records(adapter, mode).await?;
+    let mut report = ImportReport::default();
+    for batch in batches {
+        let records = batch.map_err(AdapterError::GeneralError)?;
+        import_records(adapter, &records, mode, &replaced_keys, &mut report).await?;
+    }
+
+    Ok(report)
+}
+
+/// Serialized form of a key, used to compare the keys of the stored and the imported records.
+fn serialized_key<K: Serialize>(key: &K) -> Result<String, AdapterError> {
+    serde_json::to_string(key).map_err(|e| AdapterError::GeneralError(e.into()))
+}
+
+/// In [ImportMode::Replace], remove all the stored records, so the imported records keep the
+/// import order, and return their serialized keys.
+async fn remove_replaced_records<A>(
+    adapter: &mut A,
+    mode: ImportMode,
+) -> Result<HashSet<String>, AdapterError>
+where
+    A: StoreAdapter + ?Sized,
+    A::Key: Serialize,
+{
+    let mut replaced_keys = HashSet::new();
+    if mode == ImportMode::Replace {
+        for (key, _) in adapter.export().await? {
+            adapter.remove(&key).await?;
+            replaced_keys.insert(serialized_key(&key)?);
+        }
+    }
+
+    Ok(replaced_keys)
+}
+
+/// Store the given records according to the import `mode`, counting them in the `report`.
+async fn import_records<A>(
+    adapter: &mut A,
+    records: &[(A::Key, A::Record)],
+    mode: ImportMode,
+    replaced_keys: &HashSet<String>,
+    report: &mut ImportReport,
+) -> Result<(), AdapterError>
+where
+    A: StoreAdapter + ?Sized,
+    A::Key: Serialize + Send + Sync,
+    A::Record: Send + Sync,
+{
+    for (key, record) in records {
+        let exists = match mode {
+            ImportMode::Replace => replaced_keys.contains(&serialized_key(key)?),
+            ImportMode::Merge | ImportMode::SkipExisting => adapter.record_exists(key).await?,
+        };
+        match (exists, mode) {
+            (true, ImportMode::SkipExisting) => {
+                report.skipped += 1;
+                continue;
+            }
+            (true, _) => report.replaced += 1,
+            (false, _) => report.inserted += 1,
+        }
+        adapter.store_record(key, record).await?;
+    }
+
+    Ok(())
+}
+
+/// A [StoreAdapter] able to create secondary indexes on the fields of its records.
+#[async_trait]
+pub trait IndexableStoreAdapter: StoreAdapter {
+    /// Create an index on the given field of the records if it does not exist yet.
+    async fn create_index(&self, column: &str, unique: bool) -> Result<(), AdapterError> {
+        self.create_compound_index(&[column], unique).await
+    }
+
+    /// Create an index on the given fields of the records if it does not exist yet.
+    async fn create_compound_index(
+        &self,
+        columns: &[&str],
+        unique: bool,
+    ) -> Result<(), AdapterError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+    use mithril_common::test_utils::TempDir;
+    use sqlite::Connection;
+    use std::sync::Arc;
+
+    use crate::store::adapter::{MemoryAdapter, SQLiteAdapter};
+
+    use super::*;
+
+    fn records(range: std::ops::RangeInclusive<u64>, prefix: &str) -> Vec<(u64, String)> {
+        range.map(|i| (i, format!("{prefix} {i}"))).collect()
+    }
+
+    fn sqlite_adapter(test_name: &str) -> SQLiteAdapter<u64, String> {
+        let db_path = TempDir::create("store_adapter_import", test_name).join("db.sqlite3");
+        let connection = Connection::open_thread_safe(db_path).unwrap();
+
+        SQLiteAdapter::new("key_value_store", Arc::new(connection)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_returns_the_records_from_the_oldest_to_the_newest() {
+        let adapter = MemoryAdapter::new(Some(records(1..=200, "value"))).unwrap();
+
+        assert_eq!(records(1..=200, "value"), adapter.export().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn import_exported_records_in_an_empty_store_with_each_mode() {
+        let source = MemoryAdapter::new(Some(records(1..=200, "value"))).unwrap();
+        let exported = source.export().await.unwrap();
+
+        for mode in [
+            ImportMode::Replace,
+            ImportMode::Merge,
+            ImportMode::SkipExisting,
+        ] {
+            let mut target = sqlite_adapter(&format!("import_in_empty_store_{mode}"));
+
+            let report = target.import(exported.clone(), mode).await.unwrap();
+
+            assert_eq!(
+                ImportReport {
+                    inserted: 200,
+                    skipped: 0,
+                    replaced: 0,
+                },
+                report,
+                "mode: {mode}"
+            );
+            assert_eq!(exported, target.export().await.unwrap(), "mode: {mode}");
+        }
+    }
+
+    #[tokio::test]
+    async fn import_with_replace_mode_replaces_the_store_content() {
+        let mut adapter = MemoryAdapter::new(Some(records(151..=250, "stored"))).unwrap();
+
+        let report = adapter
+            .import(records(1..=200, "imported"), ImportMode::Replace)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ImportReport {
+                inserted: 150,
+                skipped: 0,
+                replaced: 50,
+            },
+            report
+        );
+        assert_eq!(
+            records(1..=200, "imported"),
+            adapter.export().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn import_with_merge_mode_replaces_the_existing_records_and_keep_the_others() {
+        let mut adapter = MemoryAdapter::new(Some(records(151..=250, "stored"))).unwrap();
+
+        let report = adapter
+            .import(records(1..=200, "imported"), ImportMode::Merge)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ImportReport {
+                inserted: 150,
+                skipped: 0,
+                replaced: 50,
+            },
+            report
+        );
+        let exported = adapter.export().await.unwrap();
+        assert_eq!(250, exported.len());
+        assert_eq!(
+            Some("imported 151".to_string()),
+            adapter.get_record(&151).await.unwrap()
+        );
+        assert_eq!(
+            Some("stored 250".to_string()),
+            adapter.get_record(&250).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn import_with_skip_existing_mode_keeps_the_existing_records() {
+        let mut adapter = MemoryAdapter::new(Some(records(151..=250, "stored"))).unwrap();
+
+        let report = adapter
+            .import(records(1..=200, "imported"), ImportMode::SkipExisting)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ImportReport {
+                inserted: 150,
+                skipped: 50,
+                replaced: 0,
+            },
+            report
+        );
+        assert_eq!(250, adapter.export().await.unwrap().len());
+        assert_eq!(
+            Some("stored 151".to_string()),
+            adapter.get_record(&151).await.unwrap()
+        );
+        assert_eq!(
+            Some("imported 150".to_string()),
+            adapter.get_record(&150).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn cursor_export_yields_all_the_records_in_batches_in_order() {
+        let connection = Connection::open_thread_safe(":memory:").unwrap();
+        let mut adapter: SQLiteAdapter<u64, String> =
+            SQLiteAdapter::new("key_value_store", Arc::new(connection)).unwrap();
+        for (key, record) in records(1..=10_000, "value") {
+            adapter.store_record(&key, &record).await.unwrap();
+        }
+
+        let batches = adapter
+            .cursor_export(100)
+            .await
+            .unwrap()
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(100, batches.len());
+        assert!(batches.iter().all(|batch| batch.len() == 100));
+        assert_eq!(
+            adapter.export().await.unwrap(),
+            batches.into_iter().flatten().collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn cursor_import_from_a_cursor_export_keeps_the_records_order() {
+        let source = MemoryAdapter::new(Some(records(1..=1_000, "imported"))).unwrap();
+        let mut target = MemoryAdapter::new(Some(records(901..=1_100, "stored"))).unwrap();
+
+        let report = target
+            .cursor_import_from(source.cursor_export(64).await.unwrap(), ImportMode::Replace)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ImportReport {
+                inserted: 900,
+                skipped: 0,
+                replaced: 100,
+            },
+            report
+        );
+        assert_eq!(
+            records(1..=1_000, "imported"),
+            target.export().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn cursor_import_from_stops_at_the_first_batch_that_fails() {
+        let mut adapter = MemoryAdapter::new(None).unwrap();
+        let batches = vec![
+            Ok(records(1..=10, "value")),
+            Err(anyhow!("batch could not be read")),
+            Ok(records(11..=20, "value")),
+        ];
+
+        adapter
+            .cursor_import_from(batches.into_iter(), ImportMode::Merge)
+            .await
+            .expect_err("cursor_import_from should fail when a batch fails");
+
+        assert_eq!(records(1..=10, "value"), adapter.export().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn cursor_import_from_with_replace_mode_keeps_the_store_content_if_a_batch_fails() {
+        let mut adapter = MemoryAdapter::new(Some(records(1..=10, "stored"))).unwrap();
+        let batches = vec![
+            Ok(records(1..=5, "imported")),
+            Err(anyhow!("batch could not be read")),
+        ];
+
+        adapter
+            .cursor_import_from(batches.into_iter(), ImportMode::Replace)
+            .await
+            .expect_err("cursor_import_from should fail when a batch fails");
+
+        assert_eq!(records(1..=10, "stored"), adapter.export().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn sqlite_cursor_import_from_rolls_back_the_imported_batches_if_a_batch_fails() {
+        for mode in [
+            ImportMode::Replace,
+            ImportMode::Merge,
+            ImportMode::SkipExisting,
+        ] {
+            let mut adapter = sqlite_adapter(&format!("import_rolled_back_{mode}"));
+            adapter
+                .import(records(1..=10, "stored"), ImportMode::Merge)
+                .await
+                .unwrap();
+            let mut changes = adapter.changes();
+            let batches = vec![
+                Ok(records(5..=15, "imported")),
+                Err(anyhow!("batch could not be read")),
+            ];
+
+            adapter
+                .cursor_import_from(batches.into_iter(), mode)
+                .await
+                .expect_err("cursor_import_from should fail when a batch fails");
+
+            assert_eq!(
+                records(1..=10, "stored"),
+                adapter.export().await.unwrap(),
+                "mode: {mode}"
+            );
+            assert!(
+                changes.next().now_or_never().is_none(),
+                "no change should be notified for a rolled back import, mode: {mode}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn sqlite_import_notifies_the_changes_once_committed() {
+        let mut adapter = sqlite_adapter("import_notifies_once_committed");
+        adapter
+            .import(records(1..=2, "stored"), ImportMode::Merge)
+            .await
+            .unwrap();
+        let mut changes = adapter.changes();
+
+        adapter
+            .import(records(2..=3, "imported"), ImportMode::Replace)
+            .await
+            .unwrap();
+
+        let mut notified = vec![];
+        while let Some(Some(change)) = changes.next().now_or_never() {
+            notified.push(change);
+        }
+        assert_eq!(
+            vec![
+                StoreChange::Deleted(1),
+                StoreChange::Deleted(2),
+                StoreChange::Inserted(2, "imported 2".to_string()),
+                StoreChange::Inserted(3, "imported 3".to_string()),
+            ],
+            notified
+        );
+    }
+
+    fn letter_records() -> Vec<(String, String)> {
+        ["a", "b", "c", "d", "e"]
+            .into_iter()
+            .map(|key| (key.to_string(), format!("value {key}")))
+            .collect()
+    }
+
+    fn stored_keys(records: Vec<(String, String)>) -> Vec<String> {
+        records.into_iter().map(|(key, _)| key).collect()
+    }
+
+    #[tokio::test]
+    async fn delete_range_removes_the_records_between_the_bounds() {
+        let mut memory_adapter = MemoryAdapter::new(Some(letter_records())).unwrap();
+        let db_path = TempDir::create("store_adapter_delete_range", "sqlite").join("db.sqlite3");
+        let connection = Connection::open_thread_safe(db_path).unwrap();
+        let mut sqlite_adapter: SQLiteAdapter<String, String> =
+            SQLiteAdapter::new("key_value_store", Arc::new(connection)).unwrap();
+        sqlite_adapter
+            .import(letter_records(), ImportMode::Replace)
+            .await
+            .unwrap();
+
+        let adapters: [&mut dyn StoreAdapter<Key = String, Record = String>; 2] =
+            [&mut memory_adapter, &mut sqlite_adapter];
+        for adapter in adapters {
+            let deleted = adapter
+                .delete_range(&"b".to_string(), &"d".to_string())
+                .await
+                .unwrap();
+
+            assert_eq!(3, deleted);
+            assert_eq!(
+                vec!["a".to_string(), "e".to_string()],
+                stored_keys(adapter.export().await.unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn import_mode_can_be_parsed_from_its_display() {
+        for mode in [
+            ImportMode::Replace,
+            ImportMode::Merge,
+            ImportMode::SkipExisting,
+        ] {
+            assert_eq!(mode, mode.to_string().parse::<ImportMode>().unwrap());
+        }
+        "unknown".parse::<ImportMode>().unwrap_err();
+    }
 }
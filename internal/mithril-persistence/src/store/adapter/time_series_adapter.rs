@@ -0,0 +1,181 @@
+use mithril_common::entities::SlotNumber;
+
+use super::{AdapterError, QueryFilter, StoreAdapter};
+
+type Result<T> = std::result::Result<T, AdapterError>;
+
+/// Access to the records of a [StoreAdapter] keyed by [SlotNumber] as a time series.
+///
+/// The records are read with [range queries][QueryFilter::Between], the wrapped adapter must
+/// support [queries][StoreAdapter::query] (ie: a [SQLiteAdapter][super::SQLiteAdapter] which
+/// scans its key index).
+pub struct TimeSeriesStoreAdapter<V> {
+    adapter: Box<dyn StoreAdapter<Key = SlotNumber, Record = V>>,
+}
+
+impl<V> TimeSeriesStoreAdapter<V>
+where
+    V: Send + Sync,
+{
+    /// TimeSeriesStoreAdapter factory
+    pub fn new(adapter: Box<dyn StoreAdapter<Key = SlotNumber, Record = V>>) -> Self {
+        Self { adapter }
+    }
+
+    /// Store the given `record` at the given `slot`, replacing the record of the slot if any.
+    pub async fn store_record(&mut self, slot: SlotNumber, record: &V) -> Result<()> {
+        self.adapter.store_record(&slot, record).await
+    }
+
+    /// Get the records stored between the `from` and `to` slots, bounds included, ordered by
+    /// slot.
+    pub async fn get_range(&self, from: SlotNumber, to: SlotNumber) -> Result<Vec<V>> {
+        Ok(self
+            .get_range_with_slots(from, to)
+            .await?
+            .into_iter()
+            .map(|(_, record)| record)
+            .collect())
+    }
+
+    /// Count the records stored at or after the given `slot`.
+    pub async fn count_since(&self, slot: SlotNumber) -> Result<u64> {
+        self.adapter
+            .count(QueryFilter::Between(slot, SlotNumber::MAX))
+            .await
+    }
+
+    /// Get the `n` records stored at the highest slots, from the most recent to the oldest.
+    pub async fn latest_n(&self, n: usize) -> Result<Vec<V>> {
+        let records = self
+            .adapter
+            .query_by_key_desc(QueryFilter::Between(0, SlotNumber::MAX), Some(n))
+            .await?;
+
+        Ok(records.into_iter().map(|(_, record)| record).collect())
+    }
+
+    /// Remove the records stored strictly before the given `slot` with a single range
+    /// deletion, and return the number of removed records.
+    pub async fn prune_before(&mut self, slot: SlotNumber) -> Result<u64> {
+        if slot == 0 {
+            return Ok(0);
+        }
+
+        self.adapter.delete_range(&0, &(slot - 1)).await
+    }
+
+    async fn get_range_with_slots(
+        &self,
+        from: SlotNumber,
+        to: SlotNumber,
+    ) -> Result<Vec<(SlotNumber, V)>> {
+        // The adapter returns the records in their write order
+        let mut records = self
+            .adapter
+            .query(QueryFilter::Between(from, to), None)
+            .await?;
+        records.sort_by_key(|(slot, _)| *slot);
+
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::TempDir;
+    use sqlite::Connection;
+    use std::sync::Arc;
+
+    use crate::store::adapter::{MemoryAdapter, SQLiteAdapter};
+
+    use super::*;
+
+    fn transaction_hash(slot: SlotNumber) -> String {
+        format!("tx-hash-{slot}")
+    }
+
+    async fn time_series_adapter_with_transactions(
+        test_name: &str,
+        slots: impl Iterator<Item = SlotNumber>,
+    ) -> TimeSeriesStoreAdapter<String> {
+        let db_path = TempDir::create("time_series_adapter", test_name).join("db.sqlite3");
+        let connection = Arc::new(Connection::open_thread_safe(db_path).unwrap());
+        let mut adapter = TimeSeriesStoreAdapter::new(Box::new(
+            SQLiteAdapter::new("cardano_transaction", connection).unwrap(),
+        ));
+        for slot in slots {
+            adapter
+                .store_record(slot, &transaction_hash(slot))
+                .await
+                .unwrap();
+        }
+
+        adapter
+    }
+
+    #[tokio::test]
+    async fn get_range_returns_the_records_between_the_bounds_ordered_by_slot() {
+        let adapter = time_series_adapter_with_transactions("get_range", 1..=1000).await;
+
+        let records = adapter.get_range(400, 600).await.unwrap();
+
+        assert_eq!(201, records.len());
+        assert_eq!(
+            (400..=600).map(transaction_hash).collect::<Vec<_>>(),
+            records
+        );
+    }
+
+    #[tokio::test]
+    async fn count_since_counts_the_records_at_or_after_the_slot() {
+        let adapter = time_series_adapter_with_transactions("count_since", 1..=1000).await;
+
+        assert_eq!(1000, adapter.count_since(0).await.unwrap());
+        assert_eq!(101, adapter.count_since(900).await.unwrap());
+        assert_eq!(0, adapter.count_since(1001).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn latest_n_returns_the_records_of_the_highest_slots() {
+        // Written out of order to check that the slots, not the write order, are used
+        let adapter =
+            time_series_adapter_with_transactions("latest_n", (1..=500).rev().chain(501..=1000))
+                .await;
+
+        assert_eq!(
+            vec![
+                transaction_hash(1000),
+                transaction_hash(999),
+                transaction_hash(998)
+            ],
+            adapter.latest_n(3).await.unwrap()
+        );
+        assert_eq!(1000, adapter.latest_n(2000).await.unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn prune_before_removes_the_records_of_the_previous_slots() {
+        let mut adapter = time_series_adapter_with_transactions("prune_before", 1..=100).await;
+
+        assert_eq!(49, adapter.prune_before(50).await.unwrap());
+        assert_eq!(0, adapter.prune_before(0).await.unwrap());
+
+        assert_eq!(51, adapter.count_since(0).await.unwrap());
+        assert_eq!(
+            vec![transaction_hash(50)],
+            adapter.get_range(0, 50).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn reading_from_an_adapter_without_queries_support_fails() {
+        let adapter: TimeSeriesStoreAdapter<String> =
+            TimeSeriesStoreAdapter::new(Box::new(MemoryAdapter::new(None).unwrap()));
+
+        adapter
+            .get_range(1, 10)
+            .await
+            .expect_err("memory adapter does not support queries");
+    }
+}
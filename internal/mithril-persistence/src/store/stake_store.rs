@@ -35,6 +35,18 @@ impl StakeStore {
             retention_limit,
         }
     }
+
+    /// Get the highest epoch for which stakes are stored.
+    pub async fn get_last_stakes_epoch(&self) -> StdResult<Option<Epoch>> {
+        let records = self
+            .adapter
+            .read()
+            .await
+            .get_last_n_records(usize::MAX)
+            .await?;
+
+        Ok(records.into_iter().map(|(epoch, _)| epoch).max())
+    }
 }
 
 #[async_trait]
@@ -158,6 +170,24 @@ mod tests {
         assert_eq!(2, res.expect("Query result should not be empty.").len());
     }
 
+    #[tokio::test]
+    async fn get_last_stakes_epoch() {
+        assert_eq!(
+            None,
+            init_store(0, 0, None)
+                .get_last_stakes_epoch()
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(Epoch(3)),
+            init_store(3, 2, None)
+                .get_last_stakes_epoch()
+                .await
+                .unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn check_retention_limit() {
         let store = init_store(2, 2, Some(2));
@@ -1,13 +1,13 @@
 use anyhow::Context;
 use async_trait::async_trait;
 use semver::Version;
-use slog_scope::{debug, warn};
+use slog_scope::{debug, info, warn};
 use std::sync::Arc;
 use thiserror::Error;
 
 use crate::{
-    snapshot_uploaders::SnapshotLocation, snapshotter::OngoingSnapshot, SnapshotUploader,
-    Snapshotter,
+    snapshot_uploaders::SnapshotLocation, snapshotter::OngoingSnapshot, SnapshotStore,
+    SnapshotUploader, Snapshotter,
 };
 
 use super::ArtifactBuilder;
@@ -33,6 +33,8 @@ pub struct CardanoImmutableFilesFullArtifactBuilder {
     snapshotter: Arc<dyn Snapshotter>,
     snapshot_uploader: Arc<dyn SnapshotUploader>,
     compression_algorithm: CompressionAlgorithm,
+    size_budget: Option<(Arc<dyn SnapshotStore>, u64)>,
+    deduplication_store: Option<Arc<dyn SnapshotStore>>,
 }
 
 impl CardanoImmutableFilesFullArtifactBuilder {
@@ -48,9 +50,48 @@ impl CardanoImmutableFilesFullArtifactBuilder {
             snapshotter,
             snapshot_uploader,
             compression_algorithm,
+            size_budget: None,
+            deduplication_store: None,
         }
     }
 
+    /// Refuse to upload a new snapshot if the archives of the snapshots of the given store
+    /// would exceed `max_bytes` with it.
+    pub fn with_size_budget(
+        mut self,
+        snapshot_store: Arc<dyn SnapshotStore>,
+        max_bytes: u64,
+    ) -> Self {
+        self.size_budget = Some((snapshot_store, max_bytes));
+        self
+    }
+
+    /// Reuse the snapshot of the given store that has the same digest and beacon as a new
+    /// snapshot instead of creating and uploading a new archive.
+    pub fn with_deduplication(mut self, snapshot_store: Arc<dyn SnapshotStore>) -> Self {
+        self.deduplication_store = Some(snapshot_store);
+        self
+    }
+
+    /// Find a stored snapshot with the given digest and beacon.
+    ///
+    /// The beacon must match too so the reused snapshot is the one already stored for this
+    /// digest, always returns `None` if the deduplication is not enabled.
+    async fn find_stored_snapshot(
+        &self,
+        beacon: &CardanoDbBeacon,
+        snapshot_digest: &str,
+    ) -> StdResult<Option<Snapshot>> {
+        let Some(snapshot_store) = &self.deduplication_store else {
+            return Ok(None);
+        };
+
+        Ok(snapshot_store
+            .find_by_digest(snapshot_digest)
+            .await?
+            .filter(|snapshot| &snapshot.beacon == beacon))
+    }
+
     async fn create_snapshot_archive(
         &self,
         beacon: &CardanoDbBeacon,
@@ -79,6 +120,29 @@ impl CardanoImmutableFilesFullArtifactBuilder {
         Ok(ongoing_snapshot)
     }
 
+    /// Check that the given new snapshot archive fits in the size budget, if any.
+    ///
+    /// The archive is removed if it doesn't since it will never be uploaded.
+    async fn enforce_size_budget(&self, ongoing_snapshot: &OngoingSnapshot) -> StdResult<()> {
+        let Some((snapshot_store, max_bytes)) = &self.size_budget else {
+            return Ok(());
+        };
+
+        let result = snapshot_store
+            .enforce_budget(*max_bytes, *ongoing_snapshot.get_file_size())
+            .await;
+        if result.is_err() {
+            if let Err(error) = tokio::fs::remove_file(ongoing_snapshot.get_file_path()).await {
+                warn!(
+                    " > Over budget ongoing snapshot file removal failure: {}",
+                    error
+                );
+            }
+        }
+
+        result
+    }
+
     async fn upload_snapshot_archive(
         &self,
         ongoing_snapshot: &OngoingSnapshot,
@@ -136,12 +200,31 @@ impl ArtifactBuilder<CardanoDbBeacon, Snapshot> for CardanoImmutableFilesFullArt
             })?
             .to_owned();
 
+        if let Some(stored_snapshot) = self
+            .find_stored_snapshot(&beacon, &snapshot_digest)
+            .await
+            .with_context(|| {
+                "Cardano Immutable Files Full Artifact Builder can not search the stored snapshots"
+            })?
+        {
+            info!(
+                "CardanoImmutableFilesFullArtifactBuilder: snapshot deduplicated, reusing the stored archive";
+                "digest" => &stored_snapshot.digest
+            );
+            return Ok(stored_snapshot);
+        }
+
         let ongoing_snapshot = self
             .create_snapshot_archive(&beacon, &snapshot_digest)
             .await
             .with_context(|| {
                 "Cardano Immutable Files Full Artifact Builder can not create snapshot archive"
             })?;
+        self.enforce_size_budget(&ongoing_snapshot)
+            .await
+            .with_context(|| {
+                "Cardano Immutable Files Full Artifact Builder can not store the snapshot archive within the size budget"
+            })?;
         let locations = self
             .upload_snapshot_archive(&ongoing_snapshot)
             .await
@@ -160,6 +243,7 @@ impl ArtifactBuilder<CardanoDbBeacon, Snapshot> for CardanoImmutableFilesFullArt
 #[cfg(test)]
 mod tests {
     use anyhow::anyhow;
+    use chrono::Utc;
     use std::path::Path;
     use tempfile::NamedTempFile;
 
@@ -167,7 +251,16 @@ mod tests {
 
     use super::*;
 
-    use crate::{snapshot_uploaders::MockSnapshotUploader, DumbSnapshotUploader, DumbSnapshotter};
+    use crate::{
+        database::{
+            record::SignedEntityRecord,
+            repository::SignedEntityStore,
+            test_helper::{insert_signed_entities, main_db_connection},
+        },
+        snapshot_uploaders::MockSnapshotUploader,
+        store::MockSnapshotStore,
+        DumbSnapshotUploader, DumbSnapshotter, SnapshotError,
+    };
 
     #[tokio::test]
     async fn should_compute_valid_artifact() {
@@ -328,4 +421,155 @@ mod tests {
             "Ongoing snapshot file should have been removed even after upload failure"
         );
     }
+
+    #[tokio::test]
+    async fn snapshot_with_a_stored_digest_and_beacon_reuses_the_stored_archive() {
+        let beacon = fake_data::beacon();
+        let certificate = fake_data::certificate("certificate-123".to_string());
+        let connection = Arc::new(main_db_connection().unwrap());
+        let snapshot_store = Arc::new(SignedEntityStore::new(connection.clone()));
+        let first_snapshotter = Arc::new(DumbSnapshotter::new());
+        let first_snapshot = CardanoImmutableFilesFullArtifactBuilder::new(
+            &Version::parse("1.0.0").unwrap(),
+            first_snapshotter.clone(),
+            Arc::new(DumbSnapshotUploader::new()),
+            CompressionAlgorithm::Zstandard,
+        )
+        .with_deduplication(snapshot_store.clone())
+        .compute_artifact(beacon.clone(), &certificate)
+        .await
+        .unwrap();
+        insert_signed_entities(
+            &connection,
+            vec![SignedEntityRecord::from_snapshot(
+                first_snapshot.clone(),
+                certificate.hash.clone(),
+                Utc::now(),
+            )],
+        )
+        .unwrap();
+
+        let second_snapshotter = Arc::new(DumbSnapshotter::new());
+        let second_snapshot = CardanoImmutableFilesFullArtifactBuilder::new(
+            &Version::parse("1.0.0").unwrap(),
+            second_snapshotter.clone(),
+            Arc::new(DumbSnapshotUploader::new()),
+            CompressionAlgorithm::Zstandard,
+        )
+        .with_deduplication(snapshot_store)
+        .compute_artifact(beacon, &certificate)
+        .await
+        .unwrap();
+
+        assert!(first_snapshotter.get_last_snapshot().unwrap().is_some());
+        assert!(
+            second_snapshotter.get_last_snapshot().unwrap().is_none(),
+            "Only one archive should have been created"
+        );
+        assert_eq!(first_snapshot, second_snapshot);
+    }
+
+    #[tokio::test]
+    async fn snapshot_with_a_stored_digest_but_another_beacon_creates_a_new_archive() {
+        let beacon = fake_data::beacon();
+        let certificate = fake_data::certificate("certificate-123".to_string());
+        let snapshot_digest = certificate
+            .protocol_message
+            .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+            .unwrap()
+            .to_owned();
+        let mut snapshot_store = MockSnapshotStore::new();
+        snapshot_store
+            .expect_find_by_digest()
+            .withf(move |digest| digest == snapshot_digest)
+            .returning(|digest| {
+                Ok(Some(Snapshot {
+                    digest: digest.to_string(),
+                    beacon: CardanoDbBeacon {
+                        epoch: fake_data::beacon().epoch - 1,
+                        ..fake_data::beacon()
+                    },
+                    ..fake_data::snapshots(1).remove(0)
+                }))
+            });
+        let dumb_snapshotter = Arc::new(DumbSnapshotter::new());
+        let builder = CardanoImmutableFilesFullArtifactBuilder::new(
+            &Version::parse("1.0.0").unwrap(),
+            dumb_snapshotter.clone(),
+            Arc::new(DumbSnapshotUploader::new()),
+            CompressionAlgorithm::Zstandard,
+        )
+        .with_deduplication(Arc::new(snapshot_store));
+
+        let snapshot = builder
+            .compute_artifact(beacon.clone(), &certificate)
+            .await
+            .unwrap();
+
+        assert!(dumb_snapshotter.get_last_snapshot().unwrap().is_some());
+        assert_eq!(beacon, snapshot.beacon);
+    }
+
+    /// Snapshotter creating archives of the given size
+    struct FixedSizeSnapshotter(u64);
+
+    impl Snapshotter for FixedSizeSnapshotter {
+        fn snapshot(&self, archive_name: &str) -> StdResult<OngoingSnapshot> {
+            Ok(OngoingSnapshot::new(
+                Path::new(archive_name).to_path_buf(),
+                self.0,
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn snapshot_exceeding_the_size_budget_is_not_uploaded() {
+        const GB: u64 = 1024 * 1024 * 1024;
+        let connection = main_db_connection().unwrap();
+        let stored_records: Vec<SignedEntityRecord> = fake_data::snapshots(3)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, snapshot)| {
+                SignedEntityRecord::from_snapshot(
+                    Snapshot {
+                        size: 3 * GB,
+                        ..snapshot
+                    },
+                    format!("certificate-{idx}"),
+                    Utc::now(),
+                )
+            })
+            .collect();
+        insert_signed_entities(&connection, stored_records).unwrap();
+        let snapshot_store = Arc::new(SignedEntityStore::new(Arc::new(connection)));
+        let dumb_snapshot_uploader = Arc::new(DumbSnapshotUploader::new());
+        let builder = CardanoImmutableFilesFullArtifactBuilder::new(
+            &Version::parse("1.0.0").unwrap(),
+            Arc::new(FixedSizeSnapshotter(2 * GB)),
+            dumb_snapshot_uploader.clone(),
+            CompressionAlgorithm::default(),
+        )
+        .with_size_budget(snapshot_store, 10 * GB);
+
+        let error = builder
+            .compute_artifact(
+                fake_data::beacon(),
+                &fake_data::certificate("certificate-123".to_string()),
+            )
+            .await
+            .expect_err("a snapshot exceeding the size budget should fail");
+
+        assert!(
+            matches!(
+                error.downcast_ref::<SnapshotError>(),
+                Some(SnapshotError::BudgetExceeded { current, max })
+                    if *current == 11 * GB && *max == 10 * GB
+            ),
+            "unexpected error: {error:?}"
+        );
+        assert!(
+            dumb_snapshot_uploader.get_last_upload().unwrap().is_none(),
+            "No snapshot should have been uploaded"
+        );
+    }
 }
@@ -81,7 +81,7 @@ mod tests {
                 &ProtocolParameters {
                     k: 1,
                     m: 1,
-                    phi_f: 0.5
+                    phi_f: 0.5,
                 }
                 .into(),
             ),
@@ -91,7 +91,7 @@ mod tests {
                 &ProtocolParameters {
                     k: 1,
                     m: 1,
-                    phi_f: 0.5
+                    phi_f: 0.5,
                 }
                 .into(),
             )
@@ -1,7 +1,9 @@
 mod era_command;
 mod genesis_command;
 mod serve_command;
+mod store_command;
 mod tools_command;
+mod verify_certificate_command;
 
 use anyhow::anyhow;
 use clap::{CommandFactory, Parser, Subcommand};
@@ -21,7 +23,9 @@ pub enum MainCommand {
     Genesis(genesis_command::GenesisCommand),
     Era(era_command::EraCommand),
     Serve(serve_command::ServeCommand),
+    Store(store_command::StoreCommand),
     Tools(tools_command::ToolsCommand),
+    VerifyCertificate(verify_certificate_command::VerifyCertificateCommand),
     #[clap(alias("doc"), hide(true))]
     GenerateDoc(GenerateDocCommands),
 }
@@ -40,7 +44,9 @@ impl MainCommand {
             Self::Genesis(cmd) => cmd.execute(config_builder).await,
             Self::Era(cmd) => cmd.execute(config_builder).await,
             Self::Serve(cmd) => cmd.execute(config_builder).await,
+            Self::Store(cmd) => cmd.execute(config_builder).await,
             Self::Tools(cmd) => cmd.execute(config_builder).await,
+            Self::VerifyCertificate(cmd) => cmd.execute(config_builder).await,
             Self::GenerateDoc(cmd) => {
                 let config_infos = vec![Configuration::extract(), DefaultConfiguration::extract()];
                 cmd.execute_with_configurations(&mut MainOpts::command(), &config_infos)
@@ -54,7 +60,9 @@ impl MainCommand {
             MainCommand::Serve(_) => CommandType::Server,
             MainCommand::Genesis(_) => CommandType::CommandLine,
             MainCommand::Era(_) => CommandType::CommandLine,
+            MainCommand::Store(_) => CommandType::CommandLine,
             MainCommand::Tools(_) => CommandType::CommandLine,
+            MainCommand::VerifyCertificate(_) => CommandType::CommandLine,
             MainCommand::GenerateDoc(_) => CommandType::CommandLine,
         }
     }
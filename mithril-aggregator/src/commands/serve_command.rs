@@ -7,9 +7,10 @@ use std::time::Duration;
 use std::{net::IpAddr, path::PathBuf};
 use tokio::{sync::oneshot, task::JoinSet};
 
-use crate::{dependency_injection::DependenciesBuilder, Configuration};
+use crate::{dependency_injection::DependenciesBuilder, Configuration, DependencyContainer};
 
 const SQLITE_MONITORING_FILE: &str = "monitoring.sqlite3";
+const SIGNATURE_CHECKPOINT_FILE: &str = "signature_checkpoint.json";
 
 /// Server runtime mode
 #[derive(Parser, Debug, Clone)]
@@ -111,13 +112,35 @@ impl ServeCommand {
                 .unwrap()
         });
 
+        // warm up the dependencies, the runtime still recovers if one of the steps fails
+        if let Err(error) = DependencyContainer::build_async(&mut dependencies_builder).await {
+            warn!("Failed to run the startup initialisation steps of the dependencies: {error:?}");
+        }
+
         // start the aggregator runtime
+        let checkpoint_path = config.get_sqlite_dir().join(SIGNATURE_CHECKPOINT_FILE);
         let mut runtime = dependencies_builder
             .create_aggregator_runner()
             .await
-            .with_context(|| "Dependencies Builder can not create aggregator runner")?;
+            .with_context(|| "Dependencies Builder can not create aggregator runner")?
+            .with_checkpoint_path(checkpoint_path.clone());
+        if checkpoint_path.exists() {
+            runtime = runtime.restore_from_checkpoint(&checkpoint_path).await;
+        }
         let mut join_set = JoinSet::new();
-        join_set.spawn(async move { runtime.run().await.map_err(|e| e.to_string()) });
+        join_set.spawn(async move {
+            tokio::select! {
+                result = runtime.run() => return result.map_err(|e| e.to_string()),
+                result = tokio::signal::ctrl_c() => result.map_err(|e| e.to_string())?,
+            }
+
+            // The signing round in progress is saved so it's resumed on the next startup
+            if let Err(error) = runtime.save_checkpoint().await {
+                warn!("Failed to save the signature checkpoint: {error:?}");
+            }
+
+            Ok(())
+        });
 
         // start the cardano transactions preloader
         let cardano_transactions_preloader = dependencies_builder
@@ -129,6 +152,17 @@ impl ServeCommand {
         let preload_task =
             tokio::spawn(async move { cardano_transactions_preloader.preload().await });
 
+        // resume the snapshot uploads interrupted by a previous run
+        let snapshot_uploader = dependencies_builder
+            .get_snapshot_uploader()
+            .await
+            .with_context(|| "Dependencies Builder can not create snapshot uploader")?;
+        let resume_uploads_task = tokio::spawn(async move {
+            if let Err(error) = snapshot_uploader.resume_pending_uploads().await {
+                warn!("Failed to resume the pending snapshot uploads: {error:?}");
+            }
+        });
+
         // start the HTTP server
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
         let routes = dependencies_builder
@@ -179,7 +213,11 @@ impl ServeCommand {
             }
         }
 
-        join_set.spawn(async { tokio::signal::ctrl_c().await.map_err(|e| e.to_string()) });
+        let transaction_store = dependencies_builder
+            .get_transaction_store()
+            .await
+            .with_context(|| "Dependencies Builder can not create transaction store")?;
+
         dependencies_builder.vanish().await;
 
         if let Err(e) = join_set.join_next().await.unwrap()? {
@@ -193,6 +231,13 @@ impl ServeCommand {
         if !preload_task.is_finished() {
             preload_task.abort();
         }
+        if !resume_uploads_task.is_finished() {
+            resume_uploads_task.abort();
+        }
+
+        if let Err(error) = transaction_store.flush().await {
+            warn!("Failed to flush the buffered Cardano transactions: {error:?}");
+        }
 
         info!("Event store is finishing...");
         event_store_thread.await.unwrap();
@@ -0,0 +1,221 @@
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use config::{builder::DefaultState, ConfigBuilder};
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{ConnectionBuilder, SqliteConnection};
+use mithril_persistence::store::adapter::{ImportMode, SQLiteAdapter, StoreAdapter};
+use serde_json::Value;
+use slog_scope::debug;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Stores of the aggregator backed by a [SQLiteAdapter], identified by their table name.
+const ADAPTER_STORES_TABLES: [&str; 2] = ["pending_certificate", "upload_session"];
+
+/// Records of a store, copied as raw JSON.
+///
+/// The keys of the stores are plain values so their serialization, and so their hash, is the
+/// same as with their typed representation.
+type StoreRecords = Vec<(Value, Value)>;
+
+/// Content of a JSON file dump of the stores: the records of each store by table name.
+type StoresDump = BTreeMap<String, StoreRecords>;
+
+/// Open the SQLite adapter of the store of the given table, the records are read as raw JSON.
+fn open_store(
+    connection: Arc<SqliteConnection>,
+    table: &str,
+) -> StdResult<SQLiteAdapter<Value, Value>> {
+    SQLiteAdapter::<Value, Value>::new(table, connection)
+        .with_context(|| format!("Can not open store '{table}'"))
+}
+
+fn open_database(path: &Path) -> StdResult<Arc<SqliteConnection>> {
+    let connection = ConnectionBuilder::open_file(path)
+        .build()
+        .with_context(|| format!("Can not open database '{path:?}'"))?;
+
+    Ok(Arc::new(connection))
+}
+
+/// Source of the records of a [MigrateStoreCommand].
+enum StoresSource {
+    /// JSON file dump written by an [ExportStoreCommand]
+    Dump(StoresDump),
+
+    /// SQLite database
+    Database(Arc<SqliteConnection>),
+}
+
+impl StoresSource {
+    /// Take the records of the store of the given table, a store missing from a dump is empty.
+    async fn take_records(&mut self, table: &str) -> StdResult<StoreRecords> {
+        match self {
+            Self::Dump(dump) => Ok(dump.remove(table).unwrap_or_default()),
+            Self::Database(connection) => open_store(connection.clone(), table)
+                .with_context(|| format!("Can not open store '{table}' in source database"))?
+                .export()
+                .await
+                .with_context(|| format!("Can not export store '{table}'")),
+        }
+    }
+}
+
+fn is_json_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+/// The given tables, or all the stores tables if none is given.
+fn tables_or_all(tables: &[String]) -> Vec<String> {
+    if tables.is_empty() {
+        ADAPTER_STORES_TABLES
+            .iter()
+            .map(|t| t.to_string())
+            .collect()
+    } else {
+        tables.to_vec()
+    }
+}
+
+/// Backup and migration of the aggregator stores
+#[derive(Parser, Debug, Clone)]
+pub struct StoreCommand {
+    /// commands
+    #[clap(subcommand)]
+    pub store_subcommand: StoreSubCommand,
+}
+
+impl StoreCommand {
+    pub async fn execute(&self, config_builder: ConfigBuilder<DefaultState>) -> StdResult<()> {
+        self.store_subcommand.execute(config_builder).await
+    }
+}
+
+/// Store subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum StoreSubCommand {
+    /// Copy the records of the stores of a SQLite database, or of a JSON file dump, to another
+    /// SQLite database.
+    ///
+    /// Since it will modify the target database it's strongly recommended to backup it before
+    /// running this command.
+    Migrate(MigrateStoreCommand),
+
+    /// Dump the records of the stores of a SQLite database to a JSON file.
+    Export(ExportStoreCommand),
+}
+
+impl StoreSubCommand {
+    pub async fn execute(&self, config_builder: ConfigBuilder<DefaultState>) -> StdResult<()> {
+        match self {
+            Self::Migrate(cmd) => cmd.execute(config_builder).await,
+            Self::Export(cmd) => cmd.execute(config_builder).await,
+        }
+    }
+}
+
+/// Migrate stores command.
+#[derive(Parser, Debug, Clone)]
+pub struct MigrateStoreCommand {
+    /// Path of the SQLite database, or of the JSON file dump (`.json` extension), to read the
+    /// records from
+    #[clap(long)]
+    from: PathBuf,
+
+    /// Path of the SQLite database to write the records to
+    #[clap(long)]
+    to: PathBuf,
+
+    /// How the records are merged with the records of the target database:
+    /// `replace`, `merge` or `skip-existing`
+    #[clap(long, default_value_t = ImportMode::Merge)]
+    mode: ImportMode,
+
+    /// Tables of the stores to migrate, all the stores if not set
+    #[clap(long)]
+    table: Vec<String>,
+}
+
+impl MigrateStoreCommand {
+    pub async fn execute(&self, _config_builder: ConfigBuilder<DefaultState>) -> StdResult<()> {
+        debug!("MIGRATE STORE command"; "from" => ?self.from, "to" => ?self.to, "mode" => %self.mode);
+        let mut source = if is_json_file(&self.from) {
+            StoresSource::Dump(self.read_dump()?)
+        } else {
+            StoresSource::Database(
+                open_database(&self.from)
+                    .with_context(|| format!("Can not open source database '{:?}'", self.from))?,
+            )
+        };
+        let target_connection = open_database(&self.to)
+            .with_context(|| format!("Can not open target database '{:?}'", self.to))?;
+
+        for table in tables_or_all(&self.table) {
+            let records = source.take_records(&table).await?;
+            let report = open_store(target_connection.clone(), &table)
+                .with_context(|| format!("Can not open store '{table}' in target database"))?
+                .import(records, self.mode)
+                .await
+                .with_context(|| format!("Can not import store '{table}'"))?;
+
+            println!(
+                "Store '{table}' migrated: {} inserted, {} replaced, {} skipped",
+                report.inserted, report.replaced, report.skipped
+            );
+        }
+
+        Ok(())
+    }
+
+    fn read_dump(&self) -> StdResult<StoresDump> {
+        let file = File::open(&self.from)
+            .with_context(|| format!("Can not open stores dump '{:?}'", self.from))?;
+
+        serde_json::from_reader(std::io::BufReader::new(file))
+            .with_context(|| format!("Can not read stores dump '{:?}'", self.from))
+    }
+}
+
+/// Export stores command.
+#[derive(Parser, Debug, Clone)]
+pub struct ExportStoreCommand {
+    /// Path of the SQLite database to read the records from
+    #[clap(long)]
+    from: PathBuf,
+
+    /// Path of the JSON file to write the records to, it's overwritten if it exists
+    #[clap(long)]
+    to: PathBuf,
+
+    /// Tables of the stores to export, all the stores if not set
+    #[clap(long)]
+    table: Vec<String>,
+}
+
+impl ExportStoreCommand {
+    pub async fn execute(&self, _config_builder: ConfigBuilder<DefaultState>) -> StdResult<()> {
+        debug!("EXPORT STORE command"; "from" => ?self.from, "to" => ?self.to);
+        let source_connection = open_database(&self.from)
+            .with_context(|| format!("Can not open source database '{:?}'", self.from))?;
+
+        let mut dump = StoresDump::new();
+        for table in tables_or_all(&self.table) {
+            let records = open_store(source_connection.clone(), &table)?
+                .export()
+                .await
+                .with_context(|| format!("Can not export store '{table}'"))?;
+            println!("Store '{table}' exported: {} records", records.len());
+            dump.insert(table, records);
+        }
+
+        let file = File::create(&self.to)
+            .with_context(|| format!("Can not create stores dump '{:?}'", self.to))?;
+        serde_json::to_writer(std::io::BufWriter::new(file), &dump)
+            .with_context(|| format!("Can not write stores dump '{:?}'", self.to))?;
+
+        Ok(())
+    }
+}
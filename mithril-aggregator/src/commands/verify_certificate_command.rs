@@ -0,0 +1,60 @@
+use anyhow::{anyhow, Context};
+use clap::Parser;
+use config::{builder::DefaultState, ConfigBuilder};
+use mithril_common::{entities::Certificate, StdResult};
+use slog_scope::debug;
+
+use crate::{dependency_injection::DependenciesBuilder, Configuration, VerificationError};
+
+/// Verify the multi-signature of a certificate stored by the aggregator
+#[derive(Parser, Debug, Clone)]
+pub struct VerifyCertificateCommand {
+    /// Hash of the certificate to verify
+    #[clap(long)]
+    hash: String,
+}
+
+impl VerifyCertificateCommand {
+    pub async fn execute(&self, config_builder: ConfigBuilder<DefaultState>) -> StdResult<()> {
+        let config: Configuration = config_builder
+            .build()
+            .with_context(|| "configuration build error")?
+            .try_deserialize()
+            .with_context(|| "configuration deserialize error")?;
+        debug!("VERIFY CERTIFICATE command"; "config" => format!("{config:?}"));
+        println!("Verifying certificate {}", self.hash);
+        let mut dependencies_builder = DependenciesBuilder::new(config.clone());
+        let certificate_repository = dependencies_builder
+            .get_certificate_repository()
+            .await
+            .with_context(|| "Dependencies Builder can not get certificate repository")?;
+        let certificate = certificate_repository
+            .get_certificate::<Certificate>(&self.hash)
+            .await?
+            .ok_or_else(|| anyhow!("verify-certificate: certificate '{}' not found", self.hash))?;
+
+        let multi_signer = dependencies_builder
+            .get_multi_signer()
+            .await
+            .with_context(|| "Dependencies Builder can not get multi signer")?;
+        let result = multi_signer
+            .read()
+            .await
+            .verify_aggregate_signature(&certificate)
+            .await;
+
+        match result {
+            Ok(()) => {
+                println!("Certificate {} is valid", self.hash);
+                Ok(())
+            }
+            Err(error) => match error.downcast_ref::<VerificationError>() {
+                Some(verification_error) => {
+                    println!("Certificate {} is invalid: {verification_error}", self.hash);
+                    Err(error)
+                }
+                None => Err(error).with_context(|| "verify-certificate: verification error"),
+            },
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Context};
 use config::{ConfigError, Map, Source, Value, ValueKind};
+use ipnet::IpNet;
 use mithril_common::chain_observer::ChainObserverType;
 use mithril_common::crypto_helper::ProtocolGenesisSigner;
 use mithril_common::era::adapters::EraReaderAdapterType;
@@ -10,11 +11,14 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use mithril_common::entities::{
-    CardanoTransactionsSigningConfig, CompressionAlgorithm, HexEncodedGenesisVerificationKey,
-    ProtocolParameters, SignedEntityConfig, SignedEntityTypeDiscriminants,
+    CardanoTransactionLeafFormat, CardanoTransactionsSigningConfig, CompressionAlgorithm,
+    HexEncodedGenesisVerificationKey, NetworkType, ProtocolParameters, SignedEntityConfig,
+    SignedEntityTypeDiscriminants,
 };
 use mithril_common::{CardanoNetwork, StdResult};
 
+use crate::runtime::{DEFAULT_EPOCH_EVENT_LOG_CAPACITY, DEFAULT_MAX_PENDING_OPERATIONS};
+
 /// Different kinds of execution environments
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum ExecutionEnvironment {
@@ -75,12 +79,12 @@ pub struct Configuration {
     /// Cardano chain observer type
     pub chain_observer_type: ChainObserverType,
 
-    /// Protocol parameters
+    /// Protocol parameters, defaults to the parameters of the [NetworkType] of the `network`
     #[example = "`{ k: 5, m: 100, phi_f: 0.65 }`"]
-    pub protocol_parameters: ProtocolParameters,
+    pub protocol_parameters: Option<ProtocolParameters>,
 
     /// Type of snapshot uploader to use
-    #[example = "`gcp` or `local`"]
+    #[example = "`gcp`, `local` or `ipfs`"]
     pub snapshot_uploader_type: SnapshotUploaderType,
 
     /// Bucket name where the snapshots are stored if snapshot_uploader_type is Gcp
@@ -89,6 +93,49 @@ pub struct Configuration {
     /// Use CDN domain to construct snapshot urls if snapshot_uploader_type is Gcp
     pub snapshot_use_cdn_domain: bool,
 
+    /// Region of the bucket where the snapshots are stored if snapshot_uploader_type is S3
+    #[example = "`eu-west-3`"]
+    pub snapshot_s3_region: Option<String>,
+
+    /// Endpoint of the S3 compatible storage where the snapshots are stored if
+    /// snapshot_uploader_type is S3, the AWS endpoint of the region is used if not set
+    #[example = "`https://s3.eu-west-3.amazonaws.com`"]
+    pub snapshot_s3_endpoint: Option<String>,
+
+    /// Move the archives of the snapshots older than `snapshot_cold_storage_age_threshold`
+    /// epochs to the `GLACIER` storage class if snapshot_uploader_type is S3
+    pub snapshot_s3_cold_storage: bool,
+
+    /// Size, in megabytes, of the chunks of the S3 multipart uploads of the snapshots if
+    /// snapshot_uploader_type is S3, the snapshots are uploaded in a single request if not set
+    #[example = "`100`"]
+    pub snapshot_s3_multipart_chunk_size_mb: Option<usize>,
+
+    /// Maximum number of chunks of an S3 multipart upload uploaded at the same time
+    pub snapshot_s3_multipart_max_parallel_chunks: usize,
+
+    /// Url of the RPC API of the IPFS node where the snapshots are stored if snapshot_uploader_type is Ipfs
+    pub ipfs_api_url: Option<String>,
+
+    /// Remote pinning service that must also pin the snapshots if snapshot_uploader_type is Ipfs
+    #[example = "`{ service_name: \"pinata\" }`"]
+    pub ipfs_pinning_service: Option<PinningServiceConfig>,
+
+    /// IPFS gateway used to redirect the downloads of the snapshots stored on IPFS
+    pub ipfs_gateway_url: String,
+
+    /// Type of the snapshot uploader of the cold tier, where the snapshots older than
+    /// `snapshot_cold_storage_age_threshold` epochs are moved (requires a `local` snapshot_uploader_type)
+    #[example = "`gcp` or `ipfs`"]
+    pub snapshot_cold_storage_uploader_type: Option<SnapshotUploaderType>,
+
+    /// Age, in epochs, after which the snapshots are moved to the cold tier
+    pub snapshot_cold_storage_age_threshold: u64,
+
+    /// Maximum size, in bytes, of the archives of the stored snapshots, a new snapshot that
+    /// would exceed it is not uploaded
+    pub snapshots_storage_budget: Option<u64>,
+
     /// Server listening IP
     pub server_ip: String,
 
@@ -147,6 +194,11 @@ pub struct Configuration {
     #[example = "`{ level: 9, number_of_workers: 4 }`"]
     pub zstandard_parameters: Option<ZstandardCompressionParameters>,
 
+    /// Specific parameters when [snapshot_compression_algorithm][Self::snapshot_compression_algorithm]
+    /// is set to [gzip][CompressionAlgorithm::Gzip].
+    #[example = "`{ level: 6 }`"]
+    pub gzip_parameters: Option<GzipCompressionParameters>,
+
     /// Url to CExplorer list of pools to import as signer in the database.
     pub cexplorer_pools_url: Option<String>,
 
@@ -164,9 +216,53 @@ pub struct Configuration {
     /// Cardano transactions database connection pool size
     pub cardano_transactions_database_connection_pool_size: usize,
 
+    /// Maximum number of Cardano transactions buffered before being written to the database
+    pub cardano_transactions_store_batch_size: usize,
+
+    /// Maximum time the Cardano transactions are buffered before being written to the database (in ms)
+    pub cardano_transactions_store_flush_interval: u64,
+
     /// Cardano transactions signing configuration
     #[example = "`{ security_parameter: 3000, step: 120 }`"]
     pub cardano_transactions_signing_config: CardanoTransactionsSigningConfig,
+
+    /// Format of the Merkle tree leaves of the Cardano transactions of the new block ranges, it
+    /// must be the same for the aggregator and its signers.
+    #[example = "`transaction_hash` or `transaction_and_block_hash`"]
+    pub cardano_transactions_leaf_format: CardanoTransactionLeafFormat,
+
+    /// Number of events kept in the runtime epoch event log
+    pub epoch_event_log_capacity: usize,
+
+    /// Maximum number of signing operations processed at the same time, the next operations
+    /// wait for one of them to complete
+    pub max_pending_operations: usize,
+
+    /// Maximum age of the last time point read from the chain for the aggregator to be
+    /// considered ready to serve traffic (in seconds).
+    pub readiness_time_point_max_age: u64,
+
+    /// Reward granted to a signer for each valid single signature it sent during an epoch,
+    /// no reward is computed if set to `0`.
+    pub reward_per_signature: u64,
+
+    /// Fraction of the registered signers that must send a signature to create a
+    /// multi-signature, on top of the signing threshold.
+    ///
+    /// If not set, only the signing threshold is checked.
+    #[example = "`0.67`"]
+    pub bft_honest_majority: Option<f64>,
+
+    /// IP networks allowed to call the admin routes (comma separated list).
+    ///
+    /// If not set, the admin routes are not restricted.
+    #[example = "`10.0.0.0/8,192.168.1.12/32`"]
+    pub allowlisted_ips: Option<String>,
+
+    /// IP networks of the reverse proxies whose `X-Forwarded-For` header is used to find the
+    /// client IP (comma separated list).
+    #[example = "`10.0.0.0/8`"]
+    pub trusted_proxies: Option<String>,
 }
 
 /// Uploader needed to copy the snapshot once computed.
@@ -177,6 +273,17 @@ pub enum SnapshotUploaderType {
     Gcp,
     /// Uploader to local storage.
     Local,
+    /// Uploader to an IPFS node.
+    Ipfs,
+    /// Uploader to an S3 compatible storage.
+    S3,
+}
+
+/// Remote pinning service, registered on the IPFS node, that must also pin the snapshots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinningServiceConfig {
+    /// Name of the pinning service on the IPFS node
+    pub service_name: String,
 }
 
 /// [Zstandard][CompressionAlgorithm::Zstandard] specific parameters
@@ -198,6 +305,19 @@ impl Default for ZstandardCompressionParameters {
     }
 }
 
+/// [Gzip][CompressionAlgorithm::Gzip] specific parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GzipCompressionParameters {
+    /// Level of compression, from 0 (no compression) to 9 (best compression), default to 6.
+    pub level: u32,
+}
+
+impl Default for GzipCompressionParameters {
+    fn default() -> Self {
+        Self { level: 6 }
+    }
+}
+
 impl Configuration {
     /// Create a sample configuration mainly for tests
     pub fn new_sample() -> Self {
@@ -213,14 +333,26 @@ impl Configuration {
             network_magic: Some(42),
             network: "devnet".to_string(),
             chain_observer_type: ChainObserverType::Fake,
-            protocol_parameters: ProtocolParameters {
+            protocol_parameters: Some(ProtocolParameters {
                 k: 5,
                 m: 100,
                 phi_f: 0.95,
-            },
+                weighted: false,
+            }),
             snapshot_uploader_type: SnapshotUploaderType::Local,
             snapshot_bucket_name: None,
             snapshot_use_cdn_domain: false,
+            snapshot_s3_region: None,
+            snapshot_s3_endpoint: None,
+            snapshot_s3_cold_storage: false,
+            snapshot_s3_multipart_chunk_size_mb: None,
+            snapshot_s3_multipart_max_parallel_chunks: 4,
+            ipfs_api_url: None,
+            ipfs_pinning_service: None,
+            ipfs_gateway_url: "https://ipfs.io".to_string(),
+            snapshot_cold_storage_uploader_type: None,
+            snapshot_cold_storage_age_threshold: 10,
+            snapshots_storage_budget: None,
             server_ip: "0.0.0.0".to_string(),
             server_port: 8000,
             run_interval: 5000,
@@ -236,15 +368,26 @@ impl Configuration {
             signed_entity_types: None,
             snapshot_compression_algorithm: CompressionAlgorithm::Zstandard,
             zstandard_parameters: Some(ZstandardCompressionParameters::default()),
+            gzip_parameters: Some(GzipCompressionParameters::default()),
             cexplorer_pools_url: None,
             signer_importer_run_interval: 1,
             allow_unparsable_block: false,
             cardano_transactions_prover_cache_pool_size: 3,
             cardano_transactions_database_connection_pool_size: 5,
+            cardano_transactions_store_batch_size: 100,
+            cardano_transactions_store_flush_interval: 1000,
             cardano_transactions_signing_config: CardanoTransactionsSigningConfig {
                 security_parameter: 100,
                 step: 15,
             },
+            cardano_transactions_leaf_format: CardanoTransactionLeafFormat::TransactionHash,
+            epoch_event_log_capacity: 100,
+            max_pending_operations: 100,
+            readiness_time_point_max_age: 600,
+            reward_per_signature: 1,
+            bft_honest_majority: None,
+            allowlisted_ips: None,
+            trusted_proxies: None,
         }
     }
 
@@ -259,6 +402,23 @@ impl Configuration {
             .map_err(|e| anyhow!(ConfigError::Message(e.to_string())))
     }
 
+    /// Return the protocol parameters, the default parameters of the [NetworkType] of the
+    /// `network` if none are configured.
+    pub fn get_protocol_parameters(&self) -> StdResult<ProtocolParameters> {
+        match &self.protocol_parameters {
+            Some(protocol_parameters) => Ok(protocol_parameters.clone()),
+            None => NetworkType::from_code(&self.network)
+                .map(ProtocolParameters::for_network)
+                .map_err(|e| anyhow!(ConfigError::Message(e.to_string())))
+                .with_context(|| {
+                    format!(
+                        "No protocol parameters configured and no default parameters for network '{}'",
+                        self.network
+                    )
+                }),
+        }
+    }
+
     /// Return the file of the SQLite stores. If the directory does not exist, it is created.
     pub fn get_sqlite_dir(&self) -> PathBuf {
         let store_dir = &self.data_stores_directory;
@@ -297,6 +457,31 @@ impl Configuration {
             cardano_transactions_signing_config: self.cardano_transactions_signing_config.clone(),
         })
     }
+
+    /// Parse the [allowlisted_ips][Self::allowlisted_ips] IP networks.
+    pub fn get_allowlisted_ips(&self) -> StdResult<Vec<IpNet>> {
+        parse_ip_networks(self.allowlisted_ips.as_deref())
+            .with_context(|| "Invalid 'allowlisted_ips' configuration")
+    }
+
+    /// Parse the [trusted_proxies][Self::trusted_proxies] IP networks.
+    pub fn get_trusted_proxies(&self) -> StdResult<Vec<IpNet>> {
+        parse_ip_networks(self.trusted_proxies.as_deref())
+            .with_context(|| "Invalid 'trusted_proxies' configuration")
+    }
+}
+
+fn parse_ip_networks(list: Option<&str>) -> StdResult<Vec<IpNet>> {
+    list.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|network| !network.is_empty())
+        .map(|network| {
+            network
+                .parse::<IpNet>()
+                .with_context(|| format!("Invalid IP network: '{network}'"))
+        })
+        .collect()
 }
 
 /// Default configuration with all the default values for configurations.
@@ -342,6 +527,18 @@ pub struct DefaultConfiguration {
     /// Use CDN domain to construct snapshot urls default setting (if snapshot_uploader_type is Gcp)
     pub snapshot_use_cdn_domain: String,
 
+    /// Move the old snapshots archives to the cold storage default setting (if snapshot_uploader_type is S3)
+    pub snapshot_s3_cold_storage: String,
+
+    /// Maximum number of chunks of an S3 multipart upload uploaded at the same time default setting
+    pub snapshot_s3_multipart_max_parallel_chunks: usize,
+
+    /// IPFS gateway default setting (if snapshot_uploader_type is Ipfs)
+    pub ipfs_gateway_url: String,
+
+    /// Snapshot cold storage age threshold default setting
+    pub snapshot_cold_storage_age_threshold: u64,
+
     /// Signer importer run interval default setting
     pub signer_importer_run_interval: u64,
 
@@ -356,8 +553,29 @@ pub struct DefaultConfiguration {
     /// Cardano transactions database connection pool size
     pub cardano_transactions_database_connection_pool_size: u32,
 
+    /// Cardano transactions store batch size default setting
+    pub cardano_transactions_store_batch_size: u32,
+
+    /// Cardano transactions store flush interval default setting
+    pub cardano_transactions_store_flush_interval: u64,
+
     /// Cardano transactions signing configuration
     pub cardano_transactions_signing_config: CardanoTransactionsSigningConfig,
+
+    /// Cardano transactions leaf format default setting
+    pub cardano_transactions_leaf_format: String,
+
+    /// Epoch event log capacity default setting
+    pub epoch_event_log_capacity: u32,
+
+    /// Max pending operations default setting
+    pub max_pending_operations: usize,
+
+    /// Readiness time point max age default setting
+    pub readiness_time_point_max_age: u64,
+
+    /// Reward per signature default setting
+    pub reward_per_signature: u64,
 }
 
 impl Default for DefaultConfiguration {
@@ -376,14 +594,25 @@ impl Default for DefaultConfiguration {
             disable_digests_cache: "false".to_string(),
             snapshot_compression_algorithm: "zstandard".to_string(),
             snapshot_use_cdn_domain: "false".to_string(),
+            snapshot_s3_cold_storage: "false".to_string(),
+            snapshot_s3_multipart_max_parallel_chunks: 4,
+            ipfs_gateway_url: "https://ipfs.io".to_string(),
+            snapshot_cold_storage_age_threshold: 10,
             signer_importer_run_interval: 720,
             allow_unparsable_block: "false".to_string(),
             cardano_transactions_prover_cache_pool_size: 10,
             cardano_transactions_database_connection_pool_size: 10,
+            cardano_transactions_store_batch_size: 10000,
+            cardano_transactions_store_flush_interval: 5000,
             cardano_transactions_signing_config: CardanoTransactionsSigningConfig {
                 security_parameter: 3000,
                 step: 120,
             },
+            cardano_transactions_leaf_format: "transaction_hash".to_string(),
+            epoch_event_log_capacity: DEFAULT_EPOCH_EVENT_LOG_CAPACITY as u32,
+            max_pending_operations: DEFAULT_MAX_PENDING_OPERATIONS,
+            readiness_time_point_max_age: 600,
+            reward_per_signature: 1,
         }
     }
 }
@@ -450,6 +679,22 @@ impl Source for DefaultConfiguration {
             "snapshot_use_cdn_domain".to_string(),
             into_value(myself.snapshot_use_cdn_domain),
         );
+        result.insert(
+            "snapshot_s3_cold_storage".to_string(),
+            into_value(myself.snapshot_s3_cold_storage),
+        );
+        result.insert(
+            "snapshot_s3_multipart_max_parallel_chunks".to_string(),
+            into_value(myself.snapshot_s3_multipart_max_parallel_chunks as u64),
+        );
+        result.insert(
+            "ipfs_gateway_url".to_string(),
+            into_value(myself.ipfs_gateway_url),
+        );
+        result.insert(
+            "snapshot_cold_storage_age_threshold".to_string(),
+            into_value(myself.snapshot_cold_storage_age_threshold),
+        );
         result.insert(
             "signer_importer_run_interval".to_string(),
             into_value(myself.signer_importer_run_interval),
@@ -466,6 +711,14 @@ impl Source for DefaultConfiguration {
             "cardano_transactions_prover_cache_pool_size".to_string(),
             into_value(myself.cardano_transactions_database_connection_pool_size),
         );
+        result.insert(
+            "cardano_transactions_store_batch_size".to_string(),
+            into_value(myself.cardano_transactions_store_batch_size),
+        );
+        result.insert(
+            "cardano_transactions_store_flush_interval".to_string(),
+            into_value(myself.cardano_transactions_store_flush_interval),
+        );
         result.insert(
             "cardano_transactions_signing_config".to_string(),
             into_value(HashMap::from([
@@ -483,6 +736,26 @@ impl Source for DefaultConfiguration {
                 ),
             ])),
         );
+        result.insert(
+            "cardano_transactions_leaf_format".to_string(),
+            into_value(myself.cardano_transactions_leaf_format),
+        );
+        result.insert(
+            "epoch_event_log_capacity".to_string(),
+            into_value(myself.epoch_event_log_capacity),
+        );
+        result.insert(
+            "max_pending_operations".to_string(),
+            into_value(myself.max_pending_operations as u64),
+        );
+        result.insert(
+            "readiness_time_point_max_age".to_string(),
+            into_value(myself.readiness_time_point_max_age),
+        );
+        result.insert(
+            "reward_per_signature".to_string(),
+            into_value(myself.reward_per_signature),
+        );
 
         Ok(result)
     }
@@ -492,6 +765,53 @@ impl Source for DefaultConfiguration {
 mod test {
     use super::*;
 
+    #[test]
+    fn get_protocol_parameters_defaults_to_the_parameters_of_the_network_type() {
+        for (network, network_type) in [
+            ("mainnet", NetworkType::Mainnet),
+            ("testnet", NetworkType::Testnet),
+        ] {
+            let configuration = Configuration {
+                network: network.to_string(),
+                protocol_parameters: None,
+                ..Configuration::new_sample()
+            };
+
+            assert_eq!(
+                ProtocolParameters::for_network(network_type),
+                configuration.get_protocol_parameters().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn get_protocol_parameters_returns_the_configured_parameters() {
+        let protocol_parameters = ProtocolParameters::new(10, 20, 0.5);
+        let configuration = Configuration {
+            network: "mainnet".to_string(),
+            protocol_parameters: Some(protocol_parameters.clone()),
+            ..Configuration::new_sample()
+        };
+
+        assert_eq!(
+            protocol_parameters,
+            configuration.get_protocol_parameters().unwrap()
+        );
+    }
+
+    #[test]
+    fn get_protocol_parameters_of_a_network_without_network_type_fails() {
+        let configuration = Configuration {
+            network: "devnet".to_string(),
+            protocol_parameters: None,
+            ..Configuration::new_sample()
+        };
+
+        configuration
+            .get_protocol_parameters()
+            .expect_err("devnet has no default protocol parameters");
+    }
+
     #[test]
     fn safe_epoch_retention_limit_wont_change_a_value_higher_than_three() {
         for limit in 4..=10u64 {
@@ -523,6 +843,38 @@ mod test {
         }
     }
 
+    #[test]
+    fn get_allowlisted_ips_parses_the_comma_separated_networks() {
+        let configuration = Configuration {
+            allowlisted_ips: Some("10.0.0.0/8, 192.168.1.12/32,".to_string()),
+            ..Configuration::new_sample()
+        };
+
+        assert_eq!(
+            vec![
+                "10.0.0.0/8".parse::<IpNet>().unwrap(),
+                "192.168.1.12/32".parse::<IpNet>().unwrap()
+            ],
+            configuration.get_allowlisted_ips().unwrap()
+        );
+        assert!(Configuration::new_sample()
+            .get_allowlisted_ips()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn get_trusted_proxies_fails_with_an_invalid_network() {
+        let configuration = Configuration {
+            trusted_proxies: Some("10.0.0.0/8,not-an-ip".to_string()),
+            ..Configuration::new_sample()
+        };
+
+        configuration
+            .get_trusted_proxies()
+            .expect_err("'not-an-ip' should not be parsed as an IP network");
+    }
+
     #[test]
     fn can_build_config_with_ctx_signing_config_from_default_configuration() {
         #[derive(Debug, Deserialize)]
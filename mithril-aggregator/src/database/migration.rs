@@ -752,5 +752,157 @@ pragma foreign_keys=true;
                 SignedEntityTypeDiscriminants::CardanoTransactions.index()
             ),
         ),
+        // Migration 26
+        // Add the `beacon_locks` table used to coordinate the aggregator replicas of a cluster.
+        SqlMigration::new(
+            26,
+            r#"
+create table beacon_locks (
+    epoch         integer   not null primary key,
+    holder_id     text      not null,
+    expires_at    integer   not null,
+    created_at    text      not null
+);
+"#,
+        ),
+        // Migration 27
+        // Add the `beacon_log` table used to replicate the beacons to a secondary aggregator.
+        SqlMigration::new(
+            27,
+            r#"
+create table beacon_log (
+    sequence                integer   not null primary key autoincrement,
+    epoch                   integer   not null,
+    network                 text      not null,
+    immutable_file_number   integer   not null,
+    recorded_at             text      not null
+);
+"#,
+        ),
+        // Migration 28
+        // Add the `certificate_archive` table indexing the certificates moved to cold storage.
+        SqlMigration::new(
+            28,
+            r#"
+create table certificate_archive (
+    certificate_id  text      not null primary key,
+    epoch           integer   not null
+);
+"#,
+        ),
+        // Migration 29
+        // Add the `certificate_saga` table tracking the steps of the certificate creation.
+        SqlMigration::new(
+            29,
+            r#"
+create table certificate_saga (
+    saga_id     integer   not null primary key autoincrement,
+    epoch       integer   not null,
+    status      text      not null,
+    created_at  text      not null,
+    updated_at  text      not null
+);
+"#,
+        ),
+        // Migration 30
+        // Add the `rewards` table storing the rewards of the signers computed at each epoch.
+        SqlMigration::new(
+            30,
+            r#"
+create table rewards (
+    epoch       integer   not null,
+    party_id    text      not null,
+    reward      integer   not null,
+    primary key (epoch, party_id)
+);
+"#,
+        ),
+        // Migration 31
+        // Add the `certificate_transaction_index` table mapping the cardano transactions to the
+        // certificate that certifies them.
+        SqlMigration::new(
+            31,
+            r#"
+create table certificate_transaction_index (
+    transaction_hash    text      not null primary key,
+    certificate_id      text      not null,
+    block_number        integer   not null
+);
+create index certificate_transaction_index_block_number_index on certificate_transaction_index(block_number);
+"#,
+        ),
+        // Migration 32
+        // Add the `beacon_slots` table storing the range of slots of the saved beacons of
+        // each epoch.
+        SqlMigration::new(
+            32,
+            r#"
+create table beacon_slots (
+    epoch       integer   not null primary key,
+    start_slot  integer   not null,
+    end_slot    integer   not null
+);
+create index beacon_slots_slot_range_index on beacon_slots(start_slot, end_slot);
+create index beacon_log_epoch_index on beacon_log(epoch);
+"#,
+        ),
+        // Migration 33
+        // Add the `certificate_fts` full-text search table over the signers and protocol message
+        // of the certificates, kept up to date by triggers.
+        SqlMigration::new(
+            33,
+            r#"
+create virtual table certificate_fts using fts5(signers, protocol_message, content='certificate', content_rowid='rowid');
+create trigger certificate_fts_insert after insert on certificate begin
+    insert into certificate_fts(rowid, signers, protocol_message) values (new.rowid, new.signers, new.protocol_message);
+end;
+create trigger certificate_fts_delete after delete on certificate begin
+    insert into certificate_fts(certificate_fts, rowid, signers, protocol_message) values ('delete', old.rowid, old.signers, old.protocol_message);
+end;
+create trigger certificate_fts_update after update on certificate begin
+    insert into certificate_fts(certificate_fts, rowid, signers, protocol_message) values ('delete', old.rowid, old.signers, old.protocol_message);
+    insert into certificate_fts(rowid, signers, protocol_message) values (new.rowid, new.signers, new.protocol_message);
+end;
+insert into certificate_fts(certificate_fts) values ('rebuild');
+"#,
+        ),
+        // Migration 34
+        // Add the `signer_contribution` table storing the valid single signatures of the signers,
+        // once per signature, used to compute the rewards of each epoch.
+        SqlMigration::new(
+            34,
+            r#"
+create table signer_contribution (
+    epoch               integer   not null,
+    party_id            text      not null,
+    signature           text      not null,
+    stake               integer   not null,
+    won_indexes_count   integer   not null,
+    primary key (epoch, party_id, signature)
+);
+"#,
+        ),
+        // Migration 35
+        // Index the immutable file number of the snapshots to list them by range.
+        SqlMigration::new(
+            35,
+            r#"
+create index signed_entity_immutable_file_number_index on signed_entity(signed_entity_type_id, json_extract(beacon, '$.immutable_file_number'));
+"#,
+        ),
+        // Migration 36
+        // Add the `certificate_pending_session` table recording the outcome of the closed
+        // sessions of the pending certificates.
+        SqlMigration::new(
+            36,
+            r#"
+create table certificate_pending_session (
+    epoch       integer     not null,
+    outcome     text        not null,
+    closed_at   text        not null,
+    primary key (epoch)
+);
+"#,
+        ),
     ]
 }
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::BeaconLockRecord;
+
+/// Query to delete [BeaconLockRecord] from the sqlite database
+pub struct DeleteBeaconLockQuery {
+    condition: WhereCondition,
+}
+
+impl Query for DeleteBeaconLockQuery {
+    type Entity = BeaconLockRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection()
+            .expand(SourceAlias::new(&[("{:beacon_locks:}", "beacon_locks")]));
+
+        format!("delete from beacon_locks where {condition} returning {projection}")
+    }
+}
+
+impl DeleteBeaconLockQuery {
+    /// Create the SQL query to delete the lock of the given epoch held by the given holder.
+    pub fn by_epoch_and_holder(epoch: Epoch, holder_id: &str) -> StdResult<Self> {
+        let condition = WhereCondition::new(
+            "epoch = ?* and holder_id = ?*",
+            vec![
+                Value::Integer(epoch.try_into()?),
+                Value::String(holder_id.to_string()),
+            ],
+        );
+
+        Ok(Self { condition })
+    }
+
+    /// Create the SQL query to delete the lock of the given epoch if it expired before `now`.
+    pub fn expired(epoch: Epoch, now: DateTime<Utc>) -> StdResult<Self> {
+        let condition = WhereCondition::new(
+            "epoch = ?* and expires_at <= ?*",
+            vec![
+                Value::Integer(epoch.try_into()?),
+                Value::Integer(now.timestamp_millis()),
+            ],
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_persistence::sqlite::ConnectionExtensions;
+
+    use crate::database::query::InsertOrIgnoreBeaconLockQuery;
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    fn insert_lock(
+        connection: &mithril_persistence::sqlite::SqliteConnection,
+        epoch: Epoch,
+        holder_id: &str,
+        expires_at: DateTime<Utc>,
+    ) {
+        connection
+            .fetch_first(InsertOrIgnoreBeaconLockQuery::one(epoch, holder_id, expires_at).unwrap())
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn delete_by_epoch_and_holder_only_delete_the_lock_of_the_holder() {
+        let connection = main_db_connection().unwrap();
+        let expires_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        insert_lock(&connection, Epoch(3), "replica-1", expires_at);
+
+        let cursor = connection
+            .fetch(DeleteBeaconLockQuery::by_epoch_and_holder(Epoch(3), "replica-2").unwrap())
+            .unwrap();
+        assert_eq!(0, cursor.count());
+
+        let cursor = connection
+            .fetch(DeleteBeaconLockQuery::by_epoch_and_holder(Epoch(3), "replica-1").unwrap())
+            .unwrap();
+        assert_eq!(1, cursor.count());
+    }
+
+    #[test]
+    fn delete_expired_only_delete_locks_expired_before_now() {
+        let connection = main_db_connection().unwrap();
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        insert_lock(&connection, Epoch(3), "replica-1", now);
+        insert_lock(
+            &connection,
+            Epoch(4),
+            "replica-1",
+            now + chrono::Duration::seconds(10),
+        );
+
+        let cursor = connection
+            .fetch(DeleteBeaconLockQuery::expired(Epoch(4), now).unwrap())
+            .unwrap();
+        assert_eq!(0, cursor.count());
+
+        let cursor = connection
+            .fetch(DeleteBeaconLockQuery::expired(Epoch(3), now).unwrap())
+            .unwrap();
+        assert_eq!(1, cursor.count());
+    }
+}
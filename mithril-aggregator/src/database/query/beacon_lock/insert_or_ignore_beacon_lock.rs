@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::BeaconLockRecord;
+
+/// Query to insert a [BeaconLockRecord] in the sqlite database if the epoch is not locked yet.
+///
+/// Nothing is returned if the epoch is already locked.
+pub struct InsertOrIgnoreBeaconLockQuery {
+    condition: WhereCondition,
+}
+
+impl InsertOrIgnoreBeaconLockQuery {
+    pub fn one(epoch: Epoch, holder_id: &str, expires_at: DateTime<Utc>) -> StdResult<Self> {
+        let condition = WhereCondition::new(
+            "(epoch, holder_id, expires_at, created_at) values (?*, ?*, ?*, ?*)",
+            vec![
+                Value::Integer(epoch.try_into()?),
+                Value::String(holder_id.to_string()),
+                Value::Integer(expires_at.timestamp_millis()),
+                Value::String(Utc::now().to_rfc3339()),
+            ],
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+impl Query for InsertOrIgnoreBeaconLockQuery {
+    type Entity = BeaconLockRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection()
+            .expand(SourceAlias::new(&[("{:beacon_locks:}", "beacon_locks")]));
+
+        format!("insert or ignore into beacon_locks {condition} returning {projection}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_persistence::sqlite::ConnectionExtensions;
+
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    #[test]
+    fn insert_an_already_locked_epoch_is_ignored() {
+        let connection = main_db_connection().unwrap();
+        let expires_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        let record = connection
+            .fetch_first(
+                InsertOrIgnoreBeaconLockQuery::one(Epoch(3), "replica-1", expires_at).unwrap(),
+            )
+            .unwrap()
+            .expect("The first lock of the epoch should be inserted");
+        assert_eq!(Epoch(3), record.epoch);
+        assert_eq!("replica-1", record.holder_id);
+        assert_eq!(expires_at, record.expires_at);
+
+        let record = connection
+            .fetch_first(
+                InsertOrIgnoreBeaconLockQuery::one(Epoch(3), "replica-2", expires_at).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(None, record);
+    }
+}
@@ -0,0 +1,5 @@
+mod delete_beacon_lock;
+mod insert_or_ignore_beacon_lock;
+
+pub use delete_beacon_lock::*;
+pub use insert_or_ignore_beacon_lock::*;
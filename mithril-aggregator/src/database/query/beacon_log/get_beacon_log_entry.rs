@@ -0,0 +1,61 @@
+use sqlite::Value;
+
+use mithril_common::entities::SlotNumber;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::BeaconLogEntry;
+
+/// Simple queries to retrieve [BeaconLogEntry] from the sqlite database.
+pub struct GetBeaconLogEntryQuery {
+    condition: WhereCondition,
+}
+
+impl GetBeaconLogEntryQuery {
+    /// Query the entries recorded after the given sequence, in the order they were recorded.
+    pub fn since(sequence: u64) -> StdResult<Self> {
+        Ok(Self {
+            condition: WhereCondition::new(
+                "sequence > ?*",
+                vec![Value::Integer(sequence.try_into()?)],
+            ),
+        })
+    }
+
+    /// Query the last recorded entry of the epoch whose range of slots, recorded in the
+    /// `beacon_slots` table, contains the given slot.
+    pub fn last_at_slot(slot: SlotNumber) -> StdResult<Self> {
+        Ok(Self {
+            condition: WhereCondition::new(
+                "sequence = (select max(log.sequence) from beacon_log as log \
+                join beacon_slots as bs on bs.epoch = log.epoch \
+                where ?* between bs.start_slot and bs.end_slot)",
+                vec![Value::Integer(slot.try_into()?)],
+            ),
+        })
+    }
+
+    /// Query the last recorded entry.
+    pub fn last() -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "sequence = (select max(sequence) from beacon_log)",
+                vec![],
+            ),
+        }
+    }
+}
+
+impl Query for GetBeaconLogEntryQuery {
+    type Entity = BeaconLogEntry;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        let aliases = SourceAlias::new(&[("{:beacon_log:}", "bl")]);
+        let projection = Self::Entity::get_projection().expand(aliases);
+        format!("select {projection} from beacon_log as bl where {condition} order by sequence asc")
+    }
+}
@@ -0,0 +1,51 @@
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::EpochBeaconStats;
+
+/// Query to aggregate, per epoch, the beacons of the `beacon_log` table with their range of
+/// slots from the `beacon_slots` table.
+pub struct GetEpochBeaconStatsQuery {
+    condition: WhereCondition,
+}
+
+impl GetEpochBeaconStatsQuery {
+    /// Aggregate the beacons of the epochs from `from` to `to`, both included.
+    ///
+    /// Epochs without a recorded range of slots are skipped.
+    pub fn between(from: Epoch, to: Epoch) -> StdResult<Self> {
+        Ok(Self {
+            condition: WhereCondition::new(
+                "bl.epoch between ?* and ?*",
+                vec![
+                    Value::Integer(from.try_into()?),
+                    Value::Integer(to.try_into()?),
+                ],
+            ),
+        })
+    }
+}
+
+impl Query for GetEpochBeaconStatsQuery {
+    type Entity = EpochBeaconStats;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        let aliases = SourceAlias::new(&[("{:beacon_log:}", "bl"), ("{:beacon_slots:}", "bs")]);
+        let projection = Self::Entity::get_projection().expand(aliases);
+
+        format!(
+            "select {projection} from beacon_log as bl \
+            join beacon_slots as bs on bs.epoch = bl.epoch \
+            where {condition} \
+            group by bl.epoch \
+            order by bl.epoch asc"
+        )
+    }
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use sqlite::Value;
+
+use mithril_common::entities::CardanoDbBeacon;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::BeaconLogEntry;
+
+/// Query to append a [BeaconLogEntry] to the beacon log in the sqlite database.
+///
+/// The sequence of the entry is assigned by the database.
+pub struct InsertBeaconLogEntryQuery {
+    condition: WhereCondition,
+}
+
+impl InsertBeaconLogEntryQuery {
+    pub fn one(beacon: &CardanoDbBeacon, recorded_at: DateTime<Utc>) -> StdResult<Self> {
+        let condition = WhereCondition::new(
+            "(epoch, network, immutable_file_number, recorded_at) values (?*, ?*, ?*, ?*)",
+            vec![
+                Value::Integer(beacon.epoch.try_into()?),
+                Value::String(beacon.network.clone()),
+                Value::Integer(beacon.immutable_file_number.try_into()?),
+                Value::String(recorded_at.to_rfc3339()),
+            ],
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+impl Query for InsertBeaconLogEntryQuery {
+    type Entity = BeaconLogEntry;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection()
+            .expand(SourceAlias::new(&[("{:beacon_log:}", "beacon_log")]));
+
+        format!("insert into beacon_log {condition} returning {projection}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::Epoch;
+    use mithril_persistence::sqlite::ConnectionExtensions;
+
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    #[test]
+    fn inserted_entries_get_increasing_sequences() {
+        let connection = main_db_connection().unwrap();
+        let recorded_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 3, 12);
+
+        let first_entry = connection
+            .fetch_first(InsertBeaconLogEntryQuery::one(&beacon, recorded_at).unwrap())
+            .unwrap()
+            .expect("The entry should be inserted");
+        let second_entry = connection
+            .fetch_first(InsertBeaconLogEntryQuery::one(&beacon, recorded_at).unwrap())
+            .unwrap()
+            .expect("The entry should be inserted");
+
+        assert_eq!(Epoch(3), first_entry.epoch);
+        assert_eq!(beacon, first_entry.beacon);
+        assert_eq!(recorded_at.naive_utc(), first_entry.recorded_at);
+        assert!(second_entry.sequence > first_entry.sequence);
+    }
+}
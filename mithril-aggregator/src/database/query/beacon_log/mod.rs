@@ -0,0 +1,9 @@
+mod get_beacon_log_entry;
+mod get_epoch_beacon_stats;
+mod insert_beacon_log_entry;
+mod upsert_beacon_slots;
+
+pub use get_beacon_log_entry::*;
+pub use get_epoch_beacon_stats::*;
+pub use insert_beacon_log_entry::*;
+pub use upsert_beacon_slots::*;
@@ -0,0 +1,53 @@
+use sqlite::Value;
+
+use mithril_common::entities::{Epoch, SlotNumber};
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::BeaconSlotsRecord;
+
+/// Query to extend the range of slots of an epoch in the `beacon_slots` table.
+pub struct UpsertBeaconSlotsQuery {
+    condition: WhereCondition,
+}
+
+impl UpsertBeaconSlotsQuery {
+    /// Extend the range of slots of the given epoch so it contains the given slot, the range
+    /// is created if the epoch has none.
+    pub fn one(epoch: Epoch, slot: SlotNumber) -> StdResult<Self> {
+        let slot: i64 = slot.try_into()?;
+        let condition = WhereCondition::new(
+            "(epoch, start_slot, end_slot) values (?*, ?*, ?*)",
+            vec![
+                Value::Integer(epoch.try_into()?),
+                Value::Integer(slot),
+                Value::Integer(slot),
+            ],
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+impl Query for UpsertBeaconSlotsQuery {
+    type Entity = BeaconSlotsRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection()
+            .expand(SourceAlias::new(&[("{:beacon_slots:}", "beacon_slots")]));
+
+        format!(
+            "insert into beacon_slots {condition} \
+            on conflict (epoch) do update set \
+            start_slot = min(start_slot, excluded.start_slot), \
+            end_slot = max(end_slot, excluded.end_slot) \
+            returning {projection}"
+        )
+    }
+}
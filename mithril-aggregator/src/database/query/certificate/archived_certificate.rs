@@ -0,0 +1,135 @@
+use std::iter::repeat_n;
+
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::ArchivedCertificateRecord;
+
+/// Query to record in the sqlite database the certificates moved to an archive bundle.
+pub struct InsertArchivedCertificateQuery {
+    condition: WhereCondition,
+}
+
+impl InsertArchivedCertificateQuery {
+    /// Record that the certificates with the given ids are in the bundle of the given epoch.
+    pub fn many(certificate_ids: &[&str], epoch: Epoch) -> StdResult<Self> {
+        let epoch: i64 = epoch.try_into()?;
+        let values_columns: Vec<&str> = repeat_n("(?*, ?*)", certificate_ids.len()).collect();
+        let values = certificate_ids
+            .iter()
+            .flat_map(|id| vec![Value::String(id.to_string()), Value::Integer(epoch)])
+            .collect();
+
+        let condition = WhereCondition::new(
+            format!(
+                "(certificate_id, epoch) values {}",
+                values_columns.join(", ")
+            )
+            .as_str(),
+            values,
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+impl Query for InsertArchivedCertificateQuery {
+    type Entity = ArchivedCertificateRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:certificate_archive:}",
+            "certificate_archive",
+        )]));
+
+        format!("insert or replace into certificate_archive {condition} returning {projection}")
+    }
+}
+
+/// Query to clear in the sqlite database the content of the certificates moved to an archive
+/// bundle.
+///
+/// Their row is kept, with their hash, parent and epoch, so the certificates and the signed
+/// entities still stored can reference them.
+pub struct ClearArchivedCertificateContentQuery {
+    condition: WhereCondition,
+}
+
+impl ClearArchivedCertificateContentQuery {
+    /// Clear the content of the certificates with the given ids.
+    pub fn by_ids(certificate_ids: &[&str]) -> Self {
+        let ids_values = certificate_ids
+            .iter()
+            .map(|id| Value::String(id.to_string()))
+            .collect();
+
+        Self {
+            condition: WhereCondition::where_in("certificate_id", ids_values),
+        }
+    }
+}
+
+impl Query for ClearArchivedCertificateContentQuery {
+    type Entity = ArchivedCertificateRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:certificate_archive:}",
+            "certificate",
+        )]));
+
+        format!(
+            r#"
+update certificate
+    set message = '', signature = '', aggregate_verification_key = '',
+        protocol_message = '{{}}', signers = '[]'
+where {condition}
+returning {projection}"#
+        )
+    }
+}
+
+/// Simple queries to retrieve [ArchivedCertificateRecord] from the sqlite database.
+pub struct GetArchivedCertificateQuery {
+    condition: WhereCondition,
+}
+
+impl GetArchivedCertificateQuery {
+    pub fn by_certificate_id(certificate_id: &str) -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "certificate_id = ?*",
+                vec![Value::String(certificate_id.to_owned())],
+            ),
+        }
+    }
+}
+
+impl Query for GetArchivedCertificateQuery {
+    type Entity = ArchivedCertificateRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        let aliases = SourceAlias::new(&[("{:certificate_archive:}", "ca")]);
+        let projection = Self::Entity::get_projection().expand(aliases);
+        format!("select {projection} from certificate_archive as ca where {condition}")
+    }
+}
@@ -0,0 +1,115 @@
+use std::iter::repeat_n;
+
+use sqlite::Value;
+
+use mithril_common::entities::{BlockNumber, TransactionHash};
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::CertificateTransactionIndexRecord;
+
+/// Query to record in the sqlite database the transactions certified by a certificate.
+pub struct InsertCertificateTransactionIndexQuery {
+    condition: WhereCondition,
+    conflict_clause: &'static str,
+}
+
+impl InsertCertificateTransactionIndexQuery {
+    /// Record that the given transactions, with the number of their block, are certified by the
+    /// certificate with the given id.
+    pub fn many(
+        certificate_id: &str,
+        transactions: &[(TransactionHash, BlockNumber)],
+    ) -> StdResult<Self> {
+        Self::with_conflict_clause(certificate_id, transactions, "or replace")
+    }
+
+    /// Same as [many][Self::many] but the transactions already recorded are kept.
+    pub fn many_if_absent(
+        certificate_id: &str,
+        transactions: &[(TransactionHash, BlockNumber)],
+    ) -> StdResult<Self> {
+        Self::with_conflict_clause(certificate_id, transactions, "or ignore")
+    }
+
+    fn with_conflict_clause(
+        certificate_id: &str,
+        transactions: &[(TransactionHash, BlockNumber)],
+        conflict_clause: &'static str,
+    ) -> StdResult<Self> {
+        let values_columns: Vec<&str> = repeat_n("(?*, ?*, ?*)", transactions.len()).collect();
+        let mut values = vec![];
+        for (transaction_hash, block_number) in transactions {
+            values.push(Value::String(transaction_hash.to_owned()));
+            values.push(Value::String(certificate_id.to_owned()));
+            values.push(Value::Integer((*block_number).try_into()?));
+        }
+
+        let condition = WhereCondition::new(
+            format!(
+                "(transaction_hash, certificate_id, block_number) values {}",
+                values_columns.join(", ")
+            )
+            .as_str(),
+            values,
+        );
+
+        Ok(Self {
+            condition,
+            conflict_clause,
+        })
+    }
+}
+
+impl Query for InsertCertificateTransactionIndexQuery {
+    type Entity = CertificateTransactionIndexRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:certificate_transaction_index:}",
+            "certificate_transaction_index",
+        )]));
+
+        format!(
+            "insert {} into certificate_transaction_index {condition} returning {projection}",
+            self.conflict_clause
+        )
+    }
+}
+
+/// Simple queries to retrieve [CertificateTransactionIndexRecord] from the sqlite database.
+pub struct GetCertificateTransactionIndexQuery {
+    condition: WhereCondition,
+}
+
+impl GetCertificateTransactionIndexQuery {
+    /// Lookup the index entry of the given transaction, using the primary key of the table.
+    pub fn by_transaction_hash(transaction_hash: &TransactionHash) -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "transaction_hash = ?*",
+                vec![Value::String(transaction_hash.to_owned())],
+            ),
+        }
+    }
+}
+
+impl Query for GetCertificateTransactionIndexQuery {
+    type Entity = CertificateTransactionIndexRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        let aliases = SourceAlias::new(&[("{:certificate_transaction_index:}", "cti")]);
+        let projection = Self::Entity::get_projection().expand(aliases);
+        format!("select {projection} from certificate_transaction_index as cti where {condition}")
+    }
+}
@@ -1,8 +1,6 @@
 use sqlite::Value;
 
-#[cfg(test)]
 use mithril_common::entities::Epoch;
-#[cfg(test)]
 use mithril_common::StdResult;
 use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
 
@@ -11,12 +9,14 @@ use crate::database::record::CertificateRecord;
 /// Simple queries to retrieve [CertificateRecord] from the sqlite database.
 pub struct GetCertificateRecordQuery {
     condition: WhereCondition,
+    limit: Option<usize>,
 }
 
 impl GetCertificateRecordQuery {
     pub fn all() -> Self {
         Self {
             condition: WhereCondition::default(),
+            limit: None,
         }
     }
 
@@ -26,6 +26,45 @@ impl GetCertificateRecordQuery {
                 "certificate_id = ?*",
                 vec![Value::String(certificate_id.to_owned())],
             ),
+            limit: None,
+        }
+    }
+
+    /// Query at most `limit` certificates stored before the given certificate, or the latest
+    /// certificates if no certificate is given.
+    pub fn page_before(certificate_id: Option<&str>, limit: usize) -> Self {
+        let condition = match certificate_id {
+            Some(certificate_id) => WhereCondition::new(
+                "c.ROWID < (select ROWID from certificate where certificate_id = ?*)",
+                vec![Value::String(certificate_id.to_owned())],
+            ),
+            None => WhereCondition::default(),
+        };
+
+        Self {
+            condition,
+            limit: Some(limit),
+        }
+    }
+
+    /// Query the certificates of the epochs strictly before the given epoch.
+    pub fn before_epoch(epoch: Epoch) -> StdResult<Self> {
+        Ok(Self {
+            condition: WhereCondition::new("epoch < ?*", vec![Value::Integer(epoch.try_into()?)]),
+            limit: None,
+        })
+    }
+
+    /// Query the certificates whose signers or protocol message contain the given text.
+    ///
+    /// The text is searched as a phrase in the `certificate_fts` full-text search table.
+    pub fn search(text: &str) -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "c.ROWID in (select rowid from certificate_fts where certificate_fts match ?*)",
+                vec![Value::String(format!("\"{}\"", text.replace('"', "\"\"")))],
+            ),
+            limit: None,
         }
     }
 
@@ -33,6 +72,7 @@ impl GetCertificateRecordQuery {
     pub fn by_epoch(epoch: Epoch) -> StdResult<Self> {
         Ok(Self {
             condition: WhereCondition::new("epoch = ?*", vec![Value::Integer(epoch.try_into()?)]),
+            limit: None,
         })
     }
 }
@@ -47,7 +87,19 @@ impl Query for GetCertificateRecordQuery {
     fn get_definition(&self, condition: &str) -> String {
         let aliases = SourceAlias::new(&[("{:certificate:}", "c")]);
         let projection = Self::Entity::get_projection().expand(aliases);
-        format!("select {projection} from certificate as c where {condition} order by ROWID desc")
+        let limit = self
+            .limit
+            .map(|limit| format!(" limit {limit}"))
+            .unwrap_or_default();
+        // The archived certificates keep a row without content, they are never returned
+        format!(
+            r#"
+select {projection}
+from certificate as c
+where c.certificate_id not in (select certificate_id from certificate_archive)
+    and ({condition})
+order by ROWID desc{limit}"#
+        )
     }
 }
 
@@ -110,4 +162,30 @@ mod tests {
             .unwrap();
         assert_eq!(expected_certificate_records, certificate_records);
     }
+
+    #[test]
+    fn test_get_certificate_records_page_before() {
+        let (certificates, _) = setup_certificate_chain(5, 2);
+        let expected_certificate_records: Vec<CertificateRecord> = certificates
+            .iter()
+            .map(|c| c.to_owned().into())
+            .rev()
+            .collect();
+
+        let connection = main_db_connection().unwrap();
+        insert_certificate_records(&connection, certificates.clone());
+
+        let first_page: Vec<CertificateRecord> = connection
+            .fetch_collect(GetCertificateRecordQuery::page_before(None, 3))
+            .unwrap();
+        assert_eq!(expected_certificate_records[..3], first_page);
+
+        let second_page: Vec<CertificateRecord> = connection
+            .fetch_collect(GetCertificateRecordQuery::page_before(
+                Some(&first_page[2].certificate_id),
+                3,
+            ))
+            .unwrap();
+        assert_eq!(expected_certificate_records[3..], second_page);
+    }
 }
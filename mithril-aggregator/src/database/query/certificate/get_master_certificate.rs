@@ -48,7 +48,8 @@ select {projection}
 from certificate
     left join certificate as parent_certificate
         on parent_certificate.certificate_id = certificate.parent_certificate_id
-where {condition}
+where certificate.certificate_id not in (select certificate_id from certificate_archive)
+    and ({condition})
 order by certificate.ROWID desc"#
         )
     }
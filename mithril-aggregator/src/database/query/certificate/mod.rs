@@ -1,8 +1,12 @@
+mod archived_certificate;
+mod certificate_transaction_index;
 mod delete_certificate;
 mod get_certificate;
 mod get_master_certificate;
 mod insert_certificate;
 
+pub use archived_certificate::*;
+pub use certificate_transaction_index::*;
 pub use delete_certificate::*;
 pub use get_certificate::*;
 pub use get_master_certificate::*;
@@ -0,0 +1,90 @@
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::CertificatePendingSessionRecord;
+
+/// Query to delete [CertificatePendingSessionRecord] from the sqlite database
+pub struct DeleteCertificatePendingSessionQuery {
+    condition: WhereCondition,
+}
+
+impl Query for DeleteCertificatePendingSessionQuery {
+    type Entity = CertificatePendingSessionRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:certificate_pending_session:}",
+            "certificate_pending_session",
+        )]));
+
+        format!("delete from certificate_pending_session where {condition} returning {projection}")
+    }
+}
+
+impl DeleteCertificatePendingSessionQuery {
+    /// Create the SQL query to delete the sessions of the epochs strictly below the given one.
+    pub fn below_epoch(epoch: Epoch) -> StdResult<Self> {
+        let condition = WhereCondition::new("epoch < ?*", vec![Value::Integer(epoch.try_into()?)]);
+
+        Ok(Self { condition })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use mithril_persistence::sqlite::ConnectionExtensions;
+
+    use crate::database::query::{
+        GetCertificatePendingSessionQuery, InsertOrReplaceCertificatePendingSessionQuery,
+    };
+    use crate::database::record::SessionOutcome;
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    #[test]
+    fn delete_below_epoch_keeps_the_sessions_of_the_given_epoch_and_after() {
+        let connection = main_db_connection().unwrap();
+        for epoch in [Epoch(3), Epoch(4), Epoch(5)] {
+            connection
+                .fetch_first(
+                    InsertOrReplaceCertificatePendingSessionQuery::one(
+                        epoch,
+                        SessionOutcome::Committed,
+                        Utc::now(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap()
+                .unwrap();
+        }
+
+        let cursor = connection
+            .fetch(DeleteCertificatePendingSessionQuery::below_epoch(Epoch(4)).unwrap())
+            .unwrap();
+        assert_eq!(1, cursor.count());
+
+        let remaining: Vec<CertificatePendingSessionRecord> = [Epoch(3), Epoch(4), Epoch(5)]
+            .into_iter()
+            .filter_map(|epoch| {
+                connection
+                    .fetch_first(GetCertificatePendingSessionQuery::by_epoch(epoch).unwrap())
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            vec![Epoch(4), Epoch(5)],
+            remaining.iter().map(|r| r.epoch).collect::<Vec<_>>()
+        );
+    }
+}
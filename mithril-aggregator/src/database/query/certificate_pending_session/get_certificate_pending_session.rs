@@ -0,0 +1,36 @@
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::CertificatePendingSessionRecord;
+
+/// Simple queries to retrieve [CertificatePendingSessionRecord] from the sqlite database.
+pub struct GetCertificatePendingSessionQuery {
+    condition: WhereCondition,
+}
+
+impl GetCertificatePendingSessionQuery {
+    pub fn by_epoch(epoch: Epoch) -> StdResult<Self> {
+        Ok(Self {
+            condition: WhereCondition::new("epoch = ?*", vec![Value::Integer(epoch.try_into()?)]),
+        })
+    }
+}
+
+impl Query for GetCertificatePendingSessionQuery {
+    type Entity = CertificatePendingSessionRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        let aliases = SourceAlias::new(&[("{:certificate_pending_session:}", "cps")]);
+        let projection = Self::Entity::get_projection().expand(aliases);
+        format!(
+            "select {projection} from certificate_pending_session as cps where {condition} order by epoch"
+        )
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::{CertificatePendingSessionRecord, SessionOutcome};
+
+/// Query to insert or replace a [CertificatePendingSessionRecord] in the sqlite database.
+pub struct InsertOrReplaceCertificatePendingSessionQuery {
+    condition: WhereCondition,
+}
+
+impl InsertOrReplaceCertificatePendingSessionQuery {
+    pub fn one(epoch: Epoch, outcome: SessionOutcome, closed_at: DateTime<Utc>) -> StdResult<Self> {
+        let condition = WhereCondition::new(
+            "(epoch, outcome, closed_at) values (?*, ?*, ?*)",
+            vec![
+                Value::Integer(epoch.try_into()?),
+                Value::String(outcome.as_str().to_string()),
+                Value::String(closed_at.to_rfc3339()),
+            ],
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+impl Query for InsertOrReplaceCertificatePendingSessionQuery {
+    type Entity = CertificatePendingSessionRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:certificate_pending_session:}",
+            "certificate_pending_session",
+        )]));
+
+        format!(
+            "insert or replace into certificate_pending_session {condition} returning {projection}"
+        )
+    }
+}
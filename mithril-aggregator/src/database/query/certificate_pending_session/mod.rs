@@ -0,0 +1,7 @@
+mod delete_certificate_pending_session;
+mod get_certificate_pending_session;
+mod insert_or_replace_certificate_pending_session;
+
+pub use delete_certificate_pending_session::*;
+pub use get_certificate_pending_session::*;
+pub use insert_or_replace_certificate_pending_session::*;
@@ -0,0 +1,47 @@
+use sqlite::Value;
+
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::{CertificateSagaRecord, CertificateSagaStatus};
+
+/// Simple queries to retrieve [CertificateSagaRecord] from the sqlite database.
+pub struct GetCertificateSagaQuery {
+    condition: WhereCondition,
+}
+
+impl GetCertificateSagaQuery {
+    pub fn by_saga_id(saga_id: u64) -> StdResult<Self> {
+        Ok(Self {
+            condition: WhereCondition::new(
+                "saga_id = ?*",
+                vec![Value::Integer(saga_id.try_into()?)],
+            ),
+        })
+    }
+
+    pub fn by_status(status: CertificateSagaStatus) -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "status = ?*",
+                vec![Value::String(status.as_str().to_string())],
+            ),
+        }
+    }
+}
+
+impl Query for GetCertificateSagaQuery {
+    type Entity = CertificateSagaRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        let aliases = SourceAlias::new(&[("{:certificate_saga:}", "cs")]);
+        let projection = Self::Entity::get_projection().expand(aliases);
+        format!(
+            "select {projection} from certificate_saga as cs where {condition} order by saga_id"
+        )
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::{CertificateSagaRecord, CertificateSagaStatus};
+
+/// Query to insert a [CertificateSagaRecord] in the sqlite database.
+///
+/// The id of the saga is assigned by the database.
+pub struct InsertCertificateSagaQuery {
+    condition: WhereCondition,
+}
+
+impl InsertCertificateSagaQuery {
+    pub fn in_progress(epoch: Epoch, created_at: DateTime<Utc>) -> StdResult<Self> {
+        let condition = WhereCondition::new(
+            "(epoch, status, created_at, updated_at) values (?*, ?*, ?*, ?*)",
+            vec![
+                Value::Integer(epoch.try_into()?),
+                Value::String(CertificateSagaStatus::InProgress.as_str().to_string()),
+                Value::String(created_at.to_rfc3339()),
+                Value::String(created_at.to_rfc3339()),
+            ],
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+impl Query for InsertCertificateSagaQuery {
+    type Entity = CertificateSagaRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:certificate_saga:}",
+            "certificate_saga",
+        )]));
+
+        format!("insert into certificate_saga {condition} returning {projection}")
+    }
+}
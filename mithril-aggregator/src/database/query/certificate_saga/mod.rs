@@ -0,0 +1,7 @@
+mod get_certificate_saga;
+mod insert_certificate_saga;
+mod update_certificate_saga;
+
+pub use get_certificate_saga::*;
+pub use insert_certificate_saga::*;
+pub use update_certificate_saga::*;
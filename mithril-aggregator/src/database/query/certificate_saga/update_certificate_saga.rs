@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use sqlite::Value;
+
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::{CertificateSagaRecord, CertificateSagaStatus};
+
+/// Query to update the status of a [CertificateSagaRecord] in the sqlite database.
+pub struct UpdateCertificateSagaStatusQuery {
+    condition: WhereCondition,
+}
+
+impl UpdateCertificateSagaStatusQuery {
+    pub fn one(
+        saga_id: u64,
+        status: CertificateSagaStatus,
+        updated_at: DateTime<Utc>,
+    ) -> StdResult<Self> {
+        let condition = WhereCondition::new(
+            "status = ?*, updated_at = ?* where saga_id = ?*",
+            vec![
+                Value::String(status.as_str().to_string()),
+                Value::String(updated_at.to_rfc3339()),
+                Value::Integer(saga_id.try_into()?),
+            ],
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+impl Query for UpdateCertificateSagaStatusQuery {
+    type Entity = CertificateSagaRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:certificate_saga:}",
+            "certificate_saga",
+        )]));
+
+        format!("update certificate_saga set {condition} returning {projection}")
+    }
+}
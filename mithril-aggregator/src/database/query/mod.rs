@@ -1,16 +1,26 @@
 //! Aggregator related database queries
+mod beacon_lock;
+mod beacon_log;
 mod certificate;
+mod certificate_pending_session;
+mod certificate_saga;
 mod epoch_setting;
 mod open_message;
+mod reward;
 mod signed_entity;
 mod signer;
 mod signer_registration;
 mod single_signature;
 mod stake_pool;
 
+pub use beacon_lock::*;
+pub use beacon_log::*;
 pub use certificate::*;
+pub use certificate_pending_session::*;
+pub use certificate_saga::*;
 pub use epoch_setting::*;
 pub use open_message::*;
+pub use reward::*;
 pub use signed_entity::*;
 pub use signer::*;
 pub use signer_registration::*;
@@ -1,4 +1,5 @@
 use sqlite::Value;
+use uuid::Uuid;
 
 use mithril_common::entities::Epoch;
 use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
@@ -19,6 +20,15 @@ impl DeleteOpenMessageQuery {
             ),
         }
     }
+
+    pub fn by_id(open_message_id: &Uuid) -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "open_message_id = ?*",
+                vec![Value::String(open_message_id.to_string())],
+            ),
+        }
+    }
 }
 
 impl Query for DeleteOpenMessageQuery {
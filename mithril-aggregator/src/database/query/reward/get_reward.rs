@@ -0,0 +1,34 @@
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::RewardRecord;
+
+/// Simple queries to retrieve [RewardRecord] from the sqlite database.
+pub struct GetRewardQuery {
+    condition: WhereCondition,
+}
+
+impl GetRewardQuery {
+    pub fn by_epoch(epoch: Epoch) -> StdResult<Self> {
+        Ok(Self {
+            condition: WhereCondition::new("epoch = ?*", vec![Value::Integer(epoch.try_into()?)]),
+        })
+    }
+}
+
+impl Query for GetRewardQuery {
+    type Entity = RewardRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        let aliases = SourceAlias::new(&[("{:rewards:}", "r")]);
+        let projection = Self::Entity::get_projection().expand(aliases);
+        format!("select {projection} from rewards as r where {condition} order by party_id asc")
+    }
+}
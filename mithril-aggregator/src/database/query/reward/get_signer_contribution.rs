@@ -0,0 +1,36 @@
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::SignerContributionRecord;
+
+/// Simple queries to retrieve [SignerContributionRecord] from the sqlite database.
+pub struct GetSignerContributionQuery {
+    condition: WhereCondition,
+}
+
+impl GetSignerContributionQuery {
+    pub fn by_epoch(epoch: Epoch) -> StdResult<Self> {
+        Ok(Self {
+            condition: WhereCondition::new("epoch = ?*", vec![Value::Integer(epoch.try_into()?)]),
+        })
+    }
+}
+
+impl Query for GetSignerContributionQuery {
+    type Entity = SignerContributionRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        let aliases = SourceAlias::new(&[("{:signer_contribution:}", "sc")]);
+        let projection = Self::Entity::get_projection().expand(aliases);
+        format!(
+            "select {projection} from signer_contribution as sc where {condition} order by ROWID asc"
+        )
+    }
+}
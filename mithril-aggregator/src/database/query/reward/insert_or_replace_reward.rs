@@ -0,0 +1,51 @@
+use std::iter::repeat_n;
+
+use sqlite::Value;
+
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::RewardRecord;
+
+/// Query to insert or replace [RewardRecord] in the sqlite database
+pub struct InsertOrReplaceRewardQuery {
+    condition: WhereCondition,
+}
+
+impl InsertOrReplaceRewardQuery {
+    pub fn many(rewards_records: Vec<RewardRecord>) -> StdResult<Self> {
+        let columns = "(epoch, party_id, reward)";
+        let values_columns: Vec<&str> = repeat_n("(?*, ?*, ?*)", rewards_records.len()).collect();
+
+        let mut values: Vec<Value> = Vec::with_capacity(rewards_records.len() * 3);
+        for record in rewards_records {
+            values.push(Value::Integer(record.epoch.try_into()?));
+            values.push(Value::String(record.party_id));
+            values.push(Value::Integer(record.reward.try_into()?));
+        }
+
+        let condition = WhereCondition::new(
+            format!("{columns} values {}", values_columns.join(", ")).as_str(),
+            values,
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+impl Query for InsertOrReplaceRewardQuery {
+    type Entity = RewardRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection =
+            Self::Entity::get_projection().expand(SourceAlias::new(&[("{:rewards:}", "rewards")]));
+
+        format!("insert or replace into rewards {condition} returning {projection}")
+    }
+}
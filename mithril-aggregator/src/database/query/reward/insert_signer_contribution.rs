@@ -0,0 +1,48 @@
+use sqlite::Value;
+
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::SignerContributionRecord;
+
+/// Query to insert a [SignerContributionRecord] in the sqlite database, a contribution already
+/// recorded is ignored and not returned.
+pub struct InsertOrIgnoreSignerContributionQuery {
+    condition: WhereCondition,
+}
+
+impl InsertOrIgnoreSignerContributionQuery {
+    pub fn one(record: SignerContributionRecord) -> StdResult<Self> {
+        let condition = WhereCondition::new(
+            "(epoch, party_id, signature, stake, won_indexes_count) values (?*, ?*, ?*, ?*, ?*)",
+            vec![
+                Value::Integer(record.epoch.try_into()?),
+                Value::String(record.party_id),
+                Value::String(record.signature),
+                Value::Integer(record.stake.try_into()?),
+                Value::Integer(record.won_indexes_count.try_into()?),
+            ],
+        );
+
+        Ok(Self { condition })
+    }
+}
+
+impl Query for InsertOrIgnoreSignerContributionQuery {
+    type Entity = SignerContributionRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:signer_contribution:}",
+            "signer_contribution",
+        )]));
+
+        format!("insert or ignore into signer_contribution {condition} returning {projection}")
+    }
+}
@@ -0,0 +1,9 @@
+mod get_reward;
+mod get_signer_contribution;
+mod insert_or_replace_reward;
+mod insert_signer_contribution;
+
+pub use get_reward::*;
+pub use get_signer_contribution::*;
+pub use insert_or_replace_reward::*;
+pub use insert_signer_contribution::*;
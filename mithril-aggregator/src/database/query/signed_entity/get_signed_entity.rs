@@ -1,6 +1,6 @@
 use sqlite::Value;
 
-use mithril_common::entities::SignedEntityTypeDiscriminants;
+use mithril_common::entities::{Epoch, ImmutableFileNumber, SignedEntityTypeDiscriminants};
 use mithril_common::StdResult;
 use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
 
@@ -60,6 +60,41 @@ impl GetSignedEntityRecordQuery {
             ),
         })
     }
+
+    pub fn cardano_immutable_files_full_by_immutable_file_number_range(
+        from: ImmutableFileNumber,
+        to: ImmutableFileNumber,
+    ) -> Self {
+        let signed_entity_type_id =
+            SignedEntityTypeDiscriminants::CardanoImmutableFilesFull.index() as i64;
+
+        Self {
+            condition: WhereCondition::new(
+                "signed_entity_type_id = ?* and json_extract(beacon, '$.immutable_file_number') between ?* and ?*",
+                vec![
+                    Value::Integer(signed_entity_type_id),
+                    Value::Integer(from as i64),
+                    Value::Integer(to as i64),
+                ],
+            ),
+        }
+    }
+
+    pub fn cardano_immutable_files_full_not_in_cold_storage_before_epoch(epoch: Epoch) -> Self {
+        let signed_entity_type_id =
+            SignedEntityTypeDiscriminants::CardanoImmutableFilesFull.index() as i64;
+
+        Self {
+            condition: WhereCondition::new(
+                "signed_entity_type_id = ?* and json_extract(beacon, '$.epoch') < ?* \
+                and coalesce(json_extract(artifact, '$.storage_class'), 'Standard') != 'ColdStorage'",
+                vec![
+                    Value::Integer(signed_entity_type_id),
+                    Value::Integer(*epoch as i64),
+                ],
+            ),
+        }
+    }
 }
 
 impl Query for GetSignedEntityRecordQuery {
@@ -128,4 +163,87 @@ mod tests {
             signed_entity_records.iter().map(|c| c.to_owned()).collect();
         assert_eq!(expected_signed_entity_records, signed_entity_records);
     }
+
+    #[test]
+    fn test_get_cardano_immutable_files_full_records_by_immutable_file_number_range() {
+        let signed_entity_records: Vec<SignedEntityRecord> = [5, 10, 15, 20]
+            .into_iter()
+            .map(|immutable_file_number| {
+                let mut record = SignedEntityRecord::fake_records(1).remove(0);
+                record.signed_entity_id = format!("snapshot-{immutable_file_number}");
+                record.signed_entity_type = SignedEntityType::CardanoImmutableFilesFull(
+                    CardanoDbBeacon::new("devnet", 1, immutable_file_number),
+                );
+                record
+            })
+            .collect();
+        let connection = main_db_connection().unwrap();
+        insert_signed_entities(&connection, signed_entity_records.clone()).unwrap();
+
+        let records: Vec<SignedEntityRecord> = connection
+            .fetch_collect(
+                GetSignedEntityRecordQuery::cardano_immutable_files_full_by_immutable_file_number_range(
+                    10, 15,
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(
+            vec!["snapshot-15".to_string(), "snapshot-10".to_string()],
+            records
+                .into_iter()
+                .map(|r| r.signed_entity_id)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_get_cardano_immutable_files_full_records_not_in_cold_storage_before_epoch() {
+        let signed_entity_records: Vec<SignedEntityRecord> = [
+            (
+                "snapshot-epoch-1-cold",
+                1,
+                r#"{"storage_class":"ColdStorage"}"#,
+            ),
+            (
+                "snapshot-epoch-2-standard",
+                2,
+                r#"{"storage_class":"Standard"}"#,
+            ),
+            ("snapshot-epoch-3", 3, "{}"),
+            ("snapshot-epoch-4", 4, "{}"),
+        ]
+        .into_iter()
+        .map(|(signed_entity_id, epoch, artifact)| {
+            let mut record = SignedEntityRecord::fake_records(1).remove(0);
+            record.signed_entity_id = signed_entity_id.to_string();
+            record.signed_entity_type = SignedEntityType::CardanoImmutableFilesFull(
+                CardanoDbBeacon::new("devnet", epoch, 10),
+            );
+            record.artifact = artifact.to_string();
+            record
+        })
+        .collect();
+        let connection = main_db_connection().unwrap();
+        insert_signed_entities(&connection, signed_entity_records.clone()).unwrap();
+
+        let records: Vec<SignedEntityRecord> = connection
+            .fetch_collect(
+                GetSignedEntityRecordQuery::cardano_immutable_files_full_not_in_cold_storage_before_epoch(
+                    Epoch(4),
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                "snapshot-epoch-3".to_string(),
+                "snapshot-epoch-2-standard".to_string()
+            ],
+            records
+                .into_iter()
+                .map(|r| r.signed_entity_id)
+                .collect::<Vec<_>>()
+        );
+    }
 }
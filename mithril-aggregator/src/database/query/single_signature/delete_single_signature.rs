@@ -0,0 +1,81 @@
+use sqlite::Value;
+
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::SingleSignatureRecord;
+
+/// Query to delete [SingleSignatureRecord] from the sqlite database
+pub struct DeleteSingleSignatureRecordQuery {
+    condition: WhereCondition,
+}
+
+impl DeleteSingleSignatureRecordQuery {
+    /// Delete the single signatures registered for the given open message
+    pub fn by_open_message_id(open_message_id: &str) -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "open_message_id = ?*",
+                vec![Value::String(open_message_id.to_string())],
+            ),
+        }
+    }
+}
+
+impl Query for DeleteSingleSignatureRecordQuery {
+    type Entity = SingleSignatureRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:single_signature:}",
+            "single_signature",
+        )]));
+
+        format!("delete from single_signature where {condition} returning {projection}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_helper::{
+        insert_single_signatures_in_db, main_db_connection, setup_single_signature_records,
+    };
+    use mithril_persistence::sqlite::ConnectionExtensions;
+
+    use super::*;
+
+    #[test]
+    fn test_delete_single_signatures_of_an_open_message() {
+        let single_signature_records = setup_single_signature_records(2, 3, 4);
+        let connection = main_db_connection().unwrap();
+        insert_single_signatures_in_db(&connection, single_signature_records.clone()).unwrap();
+        let open_message_id = single_signature_records[0].open_message_id.to_string();
+
+        let deleted: Vec<SingleSignatureRecord> = connection
+            .fetch_collect(DeleteSingleSignatureRecordQuery::by_open_message_id(
+                &open_message_id,
+            ))
+            .unwrap();
+
+        let expected: Vec<SingleSignatureRecord> = single_signature_records
+            .iter()
+            .filter(|record| record.open_message_id.to_string() == open_message_id)
+            .cloned()
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(expected.len(), deleted.len());
+        assert!(deleted.iter().all(|record| expected.contains(record)));
+
+        let remaining: Vec<SingleSignatureRecord> = connection
+            .fetch_collect(DeleteSingleSignatureRecordQuery::by_open_message_id(
+                &open_message_id,
+            ))
+            .unwrap();
+        assert!(remaining.is_empty());
+    }
+}
@@ -0,0 +1,42 @@
+use mithril_common::entities::Epoch;
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Index entry of a certificate moved to a cold [CertificateArchiveStore][crate::CertificateArchiveStore].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedCertificateRecord {
+    /// Hash of the archived certificate
+    pub certificate_id: String,
+
+    /// Epoch of the archive bundle containing the certificate
+    pub epoch: Epoch,
+}
+
+impl SqLiteEntity for ArchivedCertificateRecord {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let epoch_int = row.read::<i64, _>(1);
+
+        Ok(Self {
+            certificate_id: row.read::<&str, _>(0).to_string(),
+            epoch: Epoch(epoch_int.try_into().map_err(|e| {
+                HydrationError::InvalidData(format!(
+                    "Could not cast i64 ({epoch_int}) to u64. Error: '{e}'"
+                ))
+            })?),
+        })
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field(
+            "certificate_id",
+            "{:certificate_archive:}.certificate_id",
+            "text",
+        );
+        projection.add_field("epoch", "{:certificate_archive:}.epoch", "integer");
+
+        projection
+    }
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+
+use mithril_common::entities::Epoch;
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Lock held by an aggregator replica on an epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeaconLockRecord {
+    /// Locked epoch
+    pub epoch: Epoch,
+
+    /// Identifier of the replica holding the lock
+    pub holder_id: String,
+
+    /// DateTime after which the lock can be taken over by another replica
+    pub expires_at: DateTime<Utc>,
+
+    /// DateTime of the record creation
+    pub created_at: DateTime<Utc>,
+}
+
+impl SqLiteEntity for BeaconLockRecord {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let epoch_int = row.read::<i64, _>(0);
+        let expires_at_millis = row.read::<i64, _>(2);
+        let created_at = &row.read::<&str, _>(3);
+
+        let beacon_lock = Self {
+            epoch: Epoch(epoch_int.try_into().map_err(|e| {
+                HydrationError::InvalidData(format!(
+                    "Could not cast i64 ({epoch_int}) to u64. Error: '{e}'"
+                ))
+            })?),
+            holder_id: row.read::<&str, _>(1).to_string(),
+            expires_at: DateTime::from_timestamp_millis(expires_at_millis).ok_or_else(|| {
+                HydrationError::InvalidData(format!(
+                    "Could not turn timestamp '{expires_at_millis}' to Datetime."
+                ))
+            })?,
+            created_at: DateTime::parse_from_rfc3339(created_at)
+                .map_err(|e| {
+                    HydrationError::InvalidData(format!(
+                        "Could not turn string '{created_at}' to rfc3339 Datetime. Error: {e}"
+                    ))
+                })?
+                .with_timezone(&Utc),
+        };
+
+        Ok(beacon_lock)
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field("epoch", "{:beacon_locks:}.epoch", "integer");
+        projection.add_field("holder_id", "{:beacon_locks:}.holder_id", "text");
+        projection.add_field("expires_at", "{:beacon_locks:}.expires_at", "integer");
+        projection.add_field("created_at", "{:beacon_locks:}.created_at", "text");
+
+        projection
+    }
+}
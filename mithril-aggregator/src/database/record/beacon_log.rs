@@ -0,0 +1,78 @@
+use chrono::{DateTime, NaiveDateTime};
+
+use mithril_common::entities::{CardanoDbBeacon, Epoch};
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Entry of the log of the beacons saved by the aggregator, used to replicate them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeaconLogEntry {
+    /// Position of the entry in the log, strictly increasing
+    pub sequence: u64,
+
+    /// Epoch of the beacon
+    pub epoch: Epoch,
+
+    /// Saved beacon
+    pub beacon: CardanoDbBeacon,
+
+    /// DateTime (UTC) at which the beacon was saved
+    pub recorded_at: NaiveDateTime,
+}
+
+impl SqLiteEntity for BeaconLogEntry {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let sequence_int = row.read::<i64, _>(0);
+        let epoch_int = row.read::<i64, _>(1);
+        let network = row.read::<&str, _>(2).to_string();
+        let immutable_file_number_int = row.read::<i64, _>(3);
+        let recorded_at = &row.read::<&str, _>(4);
+
+        let cast_error = |value: i64, e: std::num::TryFromIntError| {
+            HydrationError::InvalidData(format!(
+                "Could not cast i64 ({value}) to u64. Error: '{e}'"
+            ))
+        };
+        let epoch = Epoch(epoch_int.try_into().map_err(|e| cast_error(epoch_int, e))?);
+
+        let beacon_log_entry = Self {
+            sequence: sequence_int
+                .try_into()
+                .map_err(|e| cast_error(sequence_int, e))?,
+            epoch,
+            beacon: CardanoDbBeacon {
+                network,
+                epoch,
+                immutable_file_number: immutable_file_number_int
+                    .try_into()
+                    .map_err(|e| cast_error(immutable_file_number_int, e))?,
+            },
+            recorded_at: DateTime::parse_from_rfc3339(recorded_at)
+                .map_err(|e| {
+                    HydrationError::InvalidData(format!(
+                        "Could not turn string '{recorded_at}' to rfc3339 Datetime. Error: {e}"
+                    ))
+                })?
+                .naive_utc(),
+        };
+
+        Ok(beacon_log_entry)
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field("sequence", "{:beacon_log:}.sequence", "integer");
+        projection.add_field("epoch", "{:beacon_log:}.epoch", "integer");
+        projection.add_field("network", "{:beacon_log:}.network", "text");
+        projection.add_field(
+            "immutable_file_number",
+            "{:beacon_log:}.immutable_file_number",
+            "integer",
+        );
+        projection.add_field("recorded_at", "{:beacon_log:}.recorded_at", "text");
+
+        projection
+    }
+}
@@ -0,0 +1,45 @@
+use mithril_common::entities::{Epoch, SlotNumber};
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Range of the slots of the time points read from the chain during an epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeaconSlotsRecord {
+    /// Epoch of the beacons
+    pub epoch: Epoch,
+
+    /// Slot of the first time point of the epoch
+    pub start_slot: SlotNumber,
+
+    /// Slot of the last time point of the epoch
+    pub end_slot: SlotNumber,
+}
+
+impl SqLiteEntity for BeaconSlotsRecord {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let cast = |value: i64| {
+            value.try_into().map_err(|e| {
+                HydrationError::InvalidData(format!(
+                    "Could not cast i64 ({value}) to u64. Error: '{e}'"
+                ))
+            })
+        };
+
+        Ok(Self {
+            epoch: Epoch(cast(row.read::<i64, _>(0))?),
+            start_slot: cast(row.read::<i64, _>(1))?,
+            end_slot: cast(row.read::<i64, _>(2))?,
+        })
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field("epoch", "{:beacon_slots:}.epoch", "integer");
+        projection.add_field("start_slot", "{:beacon_slots:}.start_slot", "integer");
+        projection.add_field("end_slot", "{:beacon_slots:}.end_slot", "integer");
+
+        projection
+    }
+}
@@ -71,6 +71,7 @@ pub struct CertificateRecord {
 
 #[cfg(test)]
 impl CertificateRecord {
+    /// Create a dummy genesis certificate record
     pub fn dummy_genesis(
         id: &str,
         epoch: Epoch,
@@ -89,6 +90,7 @@ impl CertificateRecord {
         }
     }
 
+    /// Create a dummy certificate record for a Cardano immutable files full signed entity
     pub fn dummy_db_snapshot(
         id: &str,
         parent_id: &str,
@@ -108,6 +110,7 @@ impl CertificateRecord {
         )
     }
 
+    /// Create a dummy certificate record for a Mithril stake distribution signed entity
     pub fn dummy_msd(
         id: &str,
         parent_id: &str,
@@ -123,6 +126,7 @@ impl CertificateRecord {
         )
     }
 
+    /// Create a dummy certificate record for the given signed entity type
     pub fn dummy(
         id: &str,
         parent_id: &str,
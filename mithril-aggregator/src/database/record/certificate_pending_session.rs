@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+
+use mithril_common::entities::Epoch;
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Outcome of a closed certificate pending session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionOutcome {
+    /// The certificate of the epoch was created
+    Committed,
+
+    /// The certificate of the epoch was abandoned
+    Aborted,
+}
+
+impl SessionOutcome {
+    /// Name of the outcome, as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Committed => "Committed",
+            Self::Aborted => "Aborted",
+        }
+    }
+}
+
+impl TryFrom<&str> for SessionOutcome {
+    type Error = HydrationError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "Committed" => Ok(Self::Committed),
+            "Aborted" => Ok(Self::Aborted),
+            _ => Err(HydrationError::InvalidData(format!(
+                "Invalid certificate pending session outcome: '{value}'"
+            ))),
+        }
+    }
+}
+
+/// Certificate pending session record is the outcome of the closed session of the pending
+/// certificate of an epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertificatePendingSessionRecord {
+    /// Epoch of the session
+    pub epoch: Epoch,
+
+    /// Outcome of the session
+    pub outcome: SessionOutcome,
+
+    /// DateTime (UTC) at which the session was closed
+    pub closed_at: DateTime<Utc>,
+}
+
+impl SqLiteEntity for CertificatePendingSessionRecord {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let epoch_int = row.read::<i64, _>(0);
+        let outcome = row.read::<&str, _>(1);
+        let closed_at = row.read::<&str, _>(2);
+
+        let record = Self {
+            epoch: Epoch(epoch_int.try_into().map_err(|e| {
+                HydrationError::InvalidData(format!(
+                    "Could not cast i64 ({epoch_int}) to u64. Error: '{e}'"
+                ))
+            })?),
+            outcome: SessionOutcome::try_from(outcome)?,
+            closed_at: DateTime::parse_from_rfc3339(closed_at)
+                .map_err(|e| {
+                    HydrationError::InvalidData(format!(
+                        "Could not turn string '{closed_at}' to rfc3339 Datetime. Error: {e}"
+                    ))
+                })?
+                .with_timezone(&Utc),
+        };
+
+        Ok(record)
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field("epoch", "{:certificate_pending_session:}.epoch", "integer");
+        projection.add_field("outcome", "{:certificate_pending_session:}.outcome", "text");
+        projection.add_field(
+            "closed_at",
+            "{:certificate_pending_session:}.closed_at",
+            "text",
+        );
+
+        projection
+    }
+}
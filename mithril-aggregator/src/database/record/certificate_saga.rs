@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+
+use mithril_common::entities::Epoch;
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Status of a [CertificateSagaRecord]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateSagaStatus {
+    /// The certificate creation is ongoing
+    InProgress,
+
+    /// The certificate was created and stored
+    Committed,
+
+    /// The certificate creation was stopped before the certificate was stored
+    Aborted,
+}
+
+impl CertificateSagaStatus {
+    /// Name of the status, as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InProgress => "InProgress",
+            Self::Committed => "Committed",
+            Self::Aborted => "Aborted",
+        }
+    }
+}
+
+impl TryFrom<&str> for CertificateSagaStatus {
+    type Error = HydrationError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "InProgress" => Ok(Self::InProgress),
+            "Committed" => Ok(Self::Committed),
+            "Aborted" => Ok(Self::Aborted),
+            _ => Err(HydrationError::InvalidData(format!(
+                "Invalid certificate saga status: '{value}'"
+            ))),
+        }
+    }
+}
+
+/// Certificate saga record is the representation of the steps of the creation of the
+/// certificate of an epoch, which must all succeed or be rolled back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertificateSagaRecord {
+    /// Saga id
+    pub saga_id: u64,
+
+    /// Epoch of the created certificate
+    pub epoch: Epoch,
+
+    /// Status of the saga
+    pub status: CertificateSagaStatus,
+
+    /// DateTime (UTC) at which the saga was created
+    pub created_at: DateTime<Utc>,
+
+    /// DateTime (UTC) at which the status of the saga was last updated
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SqLiteEntity for CertificateSagaRecord {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let saga_id_int = row.read::<i64, _>(0);
+        let epoch_int = row.read::<i64, _>(1);
+        let status = row.read::<&str, _>(2);
+        let created_at = row.read::<&str, _>(3);
+        let updated_at = row.read::<&str, _>(4);
+
+        let cast_error = |value: i64, e: std::num::TryFromIntError| {
+            HydrationError::InvalidData(format!(
+                "Could not cast i64 ({value}) to u64. Error: '{e}'"
+            ))
+        };
+        let parse_date = |value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|date| date.with_timezone(&Utc))
+                .map_err(|e| {
+                    HydrationError::InvalidData(format!(
+                        "Could not turn string '{value}' to rfc3339 Datetime. Error: {e}"
+                    ))
+                })
+        };
+
+        let record = Self {
+            saga_id: saga_id_int
+                .try_into()
+                .map_err(|e| cast_error(saga_id_int, e))?,
+            epoch: Epoch(epoch_int.try_into().map_err(|e| cast_error(epoch_int, e))?),
+            status: CertificateSagaStatus::try_from(status)?,
+            created_at: parse_date(created_at)?,
+            updated_at: parse_date(updated_at)?,
+        };
+
+        Ok(record)
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field("saga_id", "{:certificate_saga:}.saga_id", "integer");
+        projection.add_field("epoch", "{:certificate_saga:}.epoch", "integer");
+        projection.add_field("status", "{:certificate_saga:}.status", "text");
+        projection.add_field("created_at", "{:certificate_saga:}.created_at", "text");
+        projection.add_field("updated_at", "{:certificate_saga:}.updated_at", "text");
+
+        projection
+    }
+}
@@ -0,0 +1,55 @@
+use mithril_common::entities::{BlockNumber, TransactionHash};
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Index entry of a cardano transaction certified by a certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateTransactionIndexRecord {
+    /// Hash of the certified transaction
+    pub transaction_hash: TransactionHash,
+
+    /// Hash of the certificate certifying the transaction
+    pub certificate_id: String,
+
+    /// Number of the block containing the transaction
+    pub block_number: BlockNumber,
+}
+
+impl SqLiteEntity for CertificateTransactionIndexRecord {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let block_number_int = row.read::<i64, _>(2);
+
+        Ok(Self {
+            transaction_hash: row.read::<&str, _>(0).to_string(),
+            certificate_id: row.read::<&str, _>(1).to_string(),
+            block_number: block_number_int.try_into().map_err(|e| {
+                HydrationError::InvalidData(format!(
+                    "Could not cast i64 ({block_number_int}) to u64. Error: '{e}'"
+                ))
+            })?,
+        })
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field(
+            "transaction_hash",
+            "{:certificate_transaction_index:}.transaction_hash",
+            "text",
+        );
+        projection.add_field(
+            "certificate_id",
+            "{:certificate_transaction_index:}.certificate_id",
+            "text",
+        );
+        projection.add_field(
+            "block_number",
+            "{:certificate_transaction_index:}.block_number",
+            "integer",
+        );
+
+        projection
+    }
+}
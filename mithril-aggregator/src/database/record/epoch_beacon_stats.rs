@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use mithril_common::entities::{Epoch, SlotNumber};
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Statistics of the beacons saved during an epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochBeaconStats {
+    /// Epoch of the beacons
+    pub epoch: Epoch,
+
+    /// Number of beacons saved during the epoch
+    pub beacon_count: u64,
+
+    /// Average number of slots between two consecutive beacons of the epoch, computed over the
+    /// range of slots of the time points of the epoch, `0.0` if only one beacon was saved
+    pub avg_slot_gap: f64,
+
+    /// Slot of the first time point of the epoch
+    pub min_slot: SlotNumber,
+
+    /// Slot of the last time point of the epoch
+    pub max_slot: SlotNumber,
+}
+
+impl SqLiteEntity for EpochBeaconStats {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let cast = |value: i64| {
+            value.try_into().map_err(|e| {
+                HydrationError::InvalidData(format!(
+                    "Could not cast i64 ({value}) to u64. Error: '{e}'"
+                ))
+            })
+        };
+
+        Ok(Self {
+            epoch: Epoch(cast(row.read::<i64, _>(0))?),
+            beacon_count: cast(row.read::<i64, _>(1))?,
+            avg_slot_gap: row.read::<f64, _>(2),
+            min_slot: cast(row.read::<i64, _>(3))?,
+            max_slot: cast(row.read::<i64, _>(4))?,
+        })
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field("epoch", "{:beacon_log:}.epoch", "integer");
+        projection.add_field("beacon_count", "count({:beacon_log:}.sequence)", "integer");
+        projection.add_field(
+            "avg_slot_gap",
+            "case when count({:beacon_log:}.sequence) > 1 \
+            then cast({:beacon_slots:}.end_slot - {:beacon_slots:}.start_slot as real) \
+            / (count({:beacon_log:}.sequence) - 1) \
+            else 0.0 end",
+            "real",
+        );
+        projection.add_field("min_slot", "{:beacon_slots:}.start_slot", "integer");
+        projection.add_field("max_slot", "{:beacon_slots:}.end_slot", "integer");
+
+        projection
+    }
+}
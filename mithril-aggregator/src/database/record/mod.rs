@@ -1,21 +1,41 @@
 //! Aggregator related database records
 
+mod archived_certificate;
+mod beacon_lock;
+mod beacon_log;
+mod beacon_slots;
 mod certificate;
+mod certificate_pending_session;
+mod certificate_saga;
+mod certificate_transaction_index;
+mod epoch_beacon_stats;
 mod epoch_setting;
 mod open_message;
 mod open_message_with_single_signatures;
+mod reward;
 mod signed_entity;
 mod signer;
+mod signer_contribution;
 mod signer_registration;
 mod single_signature;
 mod stake_pool;
 
+pub use archived_certificate::*;
+pub use beacon_lock::*;
+pub use beacon_log::*;
+pub use beacon_slots::*;
 pub use certificate::*;
+pub use certificate_pending_session::*;
+pub use certificate_saga::*;
+pub use certificate_transaction_index::*;
+pub use epoch_beacon_stats::*;
 pub use epoch_setting::*;
 pub use open_message::*;
 pub use open_message_with_single_signatures::*;
+pub use reward::*;
 pub use signed_entity::*;
 pub use signer::*;
+pub use signer_contribution::*;
 pub use signer_registration::*;
 pub use single_signature::*;
 pub use stake_pool::*;
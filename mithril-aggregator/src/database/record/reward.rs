@@ -0,0 +1,73 @@
+use mithril_common::entities::{Epoch, PartyId};
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+use crate::entities::RewardEntry;
+
+/// Reward record is the representation of the reward granted to a signer at an epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewardRecord {
+    /// Epoch of the rewarded contribution
+    pub epoch: Epoch,
+
+    /// Party id of the rewarded signer
+    pub party_id: PartyId,
+
+    /// Amount of the reward
+    pub reward: u64,
+}
+
+impl From<RewardEntry> for RewardRecord {
+    fn from(other: RewardEntry) -> Self {
+        Self {
+            epoch: other.epoch,
+            party_id: other.party_id,
+            reward: other.reward,
+        }
+    }
+}
+
+impl From<RewardRecord> for RewardEntry {
+    fn from(other: RewardRecord) -> Self {
+        Self {
+            epoch: other.epoch,
+            party_id: other.party_id,
+            reward: other.reward,
+        }
+    }
+}
+
+impl SqLiteEntity for RewardRecord {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let epoch_int = row.read::<i64, _>(0);
+        let party_id = row.read::<&str, _>(1).to_string();
+        let reward_int = row.read::<i64, _>(2);
+
+        let cast_error = |value: i64, e: std::num::TryFromIntError| {
+            HydrationError::InvalidData(format!(
+                "Could not cast i64 ({value}) to u64. Error: '{e}'"
+            ))
+        };
+
+        let record = Self {
+            epoch: Epoch(epoch_int.try_into().map_err(|e| cast_error(epoch_int, e))?),
+            party_id,
+            reward: reward_int
+                .try_into()
+                .map_err(|e| cast_error(reward_int, e))?,
+        };
+
+        Ok(record)
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field("epoch", "{:rewards:}.epoch", "integer");
+        projection.add_field("party_id", "{:rewards:}.party_id", "text");
+        projection.add_field("reward", "{:rewards:}.reward", "integer");
+
+        projection
+    }
+}
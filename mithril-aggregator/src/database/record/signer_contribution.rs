@@ -0,0 +1,68 @@
+use mithril_common::entities::{Epoch, PartyId, Stake};
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Signer contribution record is the representation of a valid single signature sent by a
+/// signer during an epoch, counted once for the rewards of the epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerContributionRecord {
+    /// Epoch of the contribution
+    pub epoch: Epoch,
+
+    /// Party id of the signer
+    pub party_id: PartyId,
+
+    /// Hex encoded bytes of the single signature
+    pub signature: String,
+
+    /// Stake of the signer
+    pub stake: Stake,
+
+    /// Number of lotteries won by the single signature
+    pub won_indexes_count: u64,
+}
+
+impl SqLiteEntity for SignerContributionRecord {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let epoch_int = row.read::<i64, _>(0);
+        let party_id = row.read::<&str, _>(1).to_string();
+        let signature = row.read::<&str, _>(2).to_string();
+        let stake_int = row.read::<i64, _>(3);
+        let won_indexes_count_int = row.read::<i64, _>(4);
+
+        let cast_error = |value: i64, e: std::num::TryFromIntError| {
+            HydrationError::InvalidData(format!(
+                "Could not cast i64 ({value}) to u64. Error: '{e}'"
+            ))
+        };
+
+        let record = Self {
+            epoch: Epoch(epoch_int.try_into().map_err(|e| cast_error(epoch_int, e))?),
+            party_id,
+            signature,
+            stake: stake_int.try_into().map_err(|e| cast_error(stake_int, e))?,
+            won_indexes_count: won_indexes_count_int
+                .try_into()
+                .map_err(|e| cast_error(won_indexes_count_int, e))?,
+        };
+
+        Ok(record)
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field("epoch", "{:signer_contribution:}.epoch", "integer");
+        projection.add_field("party_id", "{:signer_contribution:}.party_id", "text");
+        projection.add_field("signature", "{:signer_contribution:}.signature", "text");
+        projection.add_field("stake", "{:signer_contribution:}.stake", "integer");
+        projection.add_field(
+            "won_indexes_count",
+            "{:signer_contribution:}.won_indexes_count",
+            "integer",
+        );
+
+        projection
+    }
+}
@@ -0,0 +1,278 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use slog_scope::warn;
+
+use mithril_common::entities::Epoch;
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{ConnectionExtensions, SqliteConnection};
+
+use crate::database::query::{DeleteBeaconLockQuery, InsertOrIgnoreBeaconLockQuery};
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Store shared by the aggregator replicas of a cluster to make sure that only one of them
+/// advances the beacon of an epoch.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait DistributedBeaconStore: Sync + Send {
+    /// Try to lock the given epoch for the given holder.
+    ///
+    /// Only one holder can lock an epoch at a time, the lock can be taken over by another
+    /// holder once its `ttl` has elapsed.
+    async fn acquire_epoch_lock(
+        &self,
+        epoch: Epoch,
+        holder_id: &str,
+        ttl: Duration,
+    ) -> StdResult<EpochLock>;
+
+    /// Release the lock of the given epoch if it's held by the given holder.
+    async fn release_epoch_lock(&self, epoch: Epoch, holder_id: &str) -> StdResult<()>;
+}
+
+type EpochLockReleaser = Box<dyn FnOnce() -> StdResult<()> + Send + Sync>;
+
+/// Lock on an epoch acquired from a [DistributedBeaconStore], released when dropped.
+pub struct EpochLock {
+    epoch: Epoch,
+    holder_id: String,
+    expires_at: DateTime<Utc>,
+    releaser: Option<EpochLockReleaser>,
+}
+
+impl EpochLock {
+    /// EpochLock factory, `releaser` is called when the lock is dropped
+    pub fn new<F>(epoch: Epoch, holder_id: &str, expires_at: DateTime<Utc>, releaser: F) -> Self
+    where
+        F: FnOnce() -> StdResult<()> + Send + Sync + 'static,
+    {
+        Self {
+            epoch,
+            holder_id: holder_id.to_string(),
+            expires_at,
+            releaser: Some(Box::new(releaser)),
+        }
+    }
+
+    /// Locked epoch
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// Identifier of the holder of the lock
+    pub fn holder_id(&self) -> &str {
+        &self.holder_id
+    }
+
+    /// DateTime after which the lock can be taken over by another holder
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
+impl std::fmt::Debug for EpochLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EpochLock")
+            .field("epoch", &self.epoch)
+            .field("holder_id", &self.holder_id)
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for EpochLock {
+    fn drop(&mut self) {
+        if let Some(releaser) = self.releaser.take() {
+            if let Err(error) = releaser() {
+                warn!(
+                    "Could not release epoch lock";
+                    "epoch" => ?self.epoch, "holder_id" => &self.holder_id, "error" => ?error
+                );
+            }
+        }
+    }
+}
+
+/// SQLite implementation of the [DistributedBeaconStore], backed by the `beacon_locks` table.
+pub struct BeaconLockStore {
+    connection: Arc<SqliteConnection>,
+}
+
+impl BeaconLockStore {
+    /// Create a new BeaconLockStore
+    pub fn new(connection: Arc<SqliteConnection>) -> Self {
+        Self { connection }
+    }
+
+    fn delete_lock(connection: &SqliteConnection, epoch: Epoch, holder_id: &str) -> StdResult<()> {
+        let _ = connection
+            .fetch_first(DeleteBeaconLockQuery::by_epoch_and_holder(
+                epoch, holder_id,
+            )?)
+            .with_context(|| {
+                format!("Could not release lock of epoch '{epoch}' held by '{holder_id}'")
+            })?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DistributedBeaconStore for BeaconLockStore {
+    async fn acquire_epoch_lock(
+        &self,
+        epoch: Epoch,
+        holder_id: &str,
+        ttl: Duration,
+    ) -> StdResult<EpochLock> {
+        let now = Utc::now();
+        let expires_at = now
+            + chrono::Duration::from_std(ttl)
+                .with_context(|| format!("Invalid epoch lock ttl: '{ttl:?}'"))?;
+
+        // Expired locks can be taken over
+        let _ = self
+            .connection
+            .fetch_first(DeleteBeaconLockQuery::expired(epoch, now)?)
+            .with_context(|| format!("Could not delete expired lock of epoch '{epoch}'"))?;
+        let record = self
+            .connection
+            .fetch_first(InsertOrIgnoreBeaconLockQuery::one(
+                epoch, holder_id, expires_at,
+            )?)
+            .with_context(|| format!("Could not acquire lock of epoch '{epoch}'"))?
+            .ok_or_else(|| anyhow!("Epoch '{epoch}' is already locked by another holder"))?;
+
+        let connection = self.connection.clone();
+        let releaser_holder_id = holder_id.to_string();
+
+        Ok(EpochLock::new(
+            record.epoch,
+            &record.holder_id,
+            record.expires_at,
+            move || Self::delete_lock(&connection, epoch, &releaser_holder_id),
+        ))
+    }
+
+    async fn release_epoch_lock(&self, epoch: Epoch, holder_id: &str) -> StdResult<()> {
+        Self::delete_lock(&self.connection, epoch, holder_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn beacon_lock_store() -> BeaconLockStore {
+        BeaconLockStore::new(Arc::new(main_db_connection().unwrap()))
+    }
+
+    #[tokio::test]
+    async fn only_one_of_concurrent_holders_acquire_the_epoch_lock() {
+        let store = Arc::new(beacon_lock_store());
+
+        let tasks: Vec<_> = ["replica-1", "replica-2"]
+            .into_iter()
+            .map(|holder_id| {
+                let store = store.clone();
+                tokio::spawn(
+                    async move { store.acquire_epoch_lock(Epoch(3), holder_id, TTL).await },
+                )
+            })
+            .collect();
+        let mut results = vec![];
+        for task in tasks {
+            results.push(task.await.unwrap());
+        }
+
+        assert_eq!(1, results.iter().filter(|r| r.is_ok()).count());
+        assert_eq!(1, results.iter().filter(|r| r.is_err()).count());
+    }
+
+    #[tokio::test]
+    async fn epoch_lock_is_released_when_dropped() {
+        let store = beacon_lock_store();
+
+        let lock = store
+            .acquire_epoch_lock(Epoch(3), "replica-1", TTL)
+            .await
+            .unwrap();
+        store
+            .acquire_epoch_lock(Epoch(3), "replica-2", TTL)
+            .await
+            .expect_err("Epoch should be locked by replica-1");
+        drop(lock);
+
+        let lock = store
+            .acquire_epoch_lock(Epoch(3), "replica-2", TTL)
+            .await
+            .unwrap();
+        assert_eq!("replica-2", lock.holder_id());
+    }
+
+    #[tokio::test]
+    async fn release_epoch_lock_only_release_the_lock_of_its_holder() {
+        let store = beacon_lock_store();
+        let _lock = store
+            .acquire_epoch_lock(Epoch(3), "replica-1", TTL)
+            .await
+            .unwrap();
+
+        store
+            .release_epoch_lock(Epoch(3), "replica-2")
+            .await
+            .unwrap();
+        store
+            .acquire_epoch_lock(Epoch(3), "replica-2", TTL)
+            .await
+            .expect_err("Epoch should still be locked by replica-1");
+
+        store
+            .release_epoch_lock(Epoch(3), "replica-1")
+            .await
+            .unwrap();
+        store
+            .acquire_epoch_lock(Epoch(3), "replica-2", TTL)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn expired_epoch_lock_can_be_taken_over() {
+        let store = beacon_lock_store();
+        let _expired_lock = store
+            .acquire_epoch_lock(Epoch(3), "replica-1", Duration::ZERO)
+            .await
+            .unwrap();
+
+        let lock = store
+            .acquire_epoch_lock(Epoch(3), "replica-2", TTL)
+            .await
+            .unwrap();
+
+        assert_eq!("replica-2", lock.holder_id());
+    }
+
+    #[tokio::test]
+    async fn lock_on_an_epoch_does_not_lock_other_epochs() {
+        let store = beacon_lock_store();
+        let _lock = store
+            .acquire_epoch_lock(Epoch(3), "replica-1", TTL)
+            .await
+            .unwrap();
+
+        store
+            .acquire_epoch_lock(Epoch(4), "replica-2", TTL)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,447 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use chrono::Utc;
+use thiserror::Error;
+
+use mithril_common::entities::{CardanoDbBeacon, Epoch, SlotNumber};
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{ConnectionExtensions, SqliteConnection};
+
+use crate::database::query::{
+    GetBeaconLogEntryQuery, GetEpochBeaconStatsQuery, InsertBeaconLogEntryQuery,
+    UpsertBeaconSlotsQuery,
+};
+use crate::database::record::{BeaconLogEntry, EpochBeaconStats};
+
+#[cfg(test)]
+use mockall::automock;
+
+/// [BeaconStore] related errors.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BeaconStoreError {
+    /// Raised when a beacon would move the current beacon back to a previous epoch.
+    #[error("beacon of epoch '{next}' can not be saved after a beacon of epoch '{previous}'")]
+    NonMonotonicEpoch {
+        /// Epoch of the previous beacon
+        previous: Epoch,
+
+        /// Epoch of the rejected beacon
+        next: Epoch,
+    },
+}
+
+/// Store of the current beacon of the aggregator that keeps a log of its changes, so a
+/// secondary aggregator can replicate them.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait BeaconStore: Sync + Send {
+    /// Save the current beacon, appending it to the replication log.
+    async fn save_current_beacon(&self, beacon: CardanoDbBeacon) -> StdResult<BeaconLogEntry>;
+
+    /// Save the given beacons in order as if they were saved one by one, all of them or none.
+    ///
+    /// Fail with a [BeaconStoreError::NonMonotonicEpoch] if a beacon has an epoch lower than
+    /// the epoch of the beacon saved before it. Return the epoch of the new current beacon.
+    async fn advance_epochs(&self, beacons: Vec<CardanoDbBeacon>) -> StdResult<Epoch>;
+
+    /// Get the entries of the replication log recorded after the entry with the given sequence.
+    ///
+    /// A secondary aggregator can poll this with the sequence of the last entry it applied.
+    async fn replication_log_since(&self, sequence: u64) -> StdResult<Vec<BeaconLogEntry>>;
+
+    /// Get the last saved beacon, if any.
+    async fn get_current_beacon(&self) -> StdResult<Option<CardanoDbBeacon>>;
+
+    /// Record that a time point of the given epoch was read at the given slot, extending the
+    /// range of slots of the epoch.
+    async fn record_beacon_slot(&self, epoch: Epoch, slot: SlotNumber) -> StdResult<()>;
+
+    /// Get the last saved beacon of the epoch whose range of slots contains the given slot, if
+    /// any.
+    ///
+    /// The ranges only contain the slots of the recorded time points: a slot between the last
+    /// time point of an epoch and the first one of the next epoch is in no range.
+    async fn get_beacon_at_slot(&self, slot: SlotNumber) -> StdResult<Option<CardanoDbBeacon>>;
+
+    /// Get the statistics of the beacons saved during each epoch from `from` to `to`, both
+    /// included, ordered by epoch.
+    ///
+    /// Epochs without a recorded slot are skipped, so a missing epoch is a gap in the beacons.
+    async fn aggregate_by_epoch(&self, from: Epoch, to: Epoch) -> StdResult<Vec<EpochBeaconStats>>;
+}
+
+/// SQLite implementation of the [BeaconStore], backed by the `beacon_log` table.
+pub struct BeaconLogStore {
+    connection: Arc<SqliteConnection>,
+}
+
+impl BeaconLogStore {
+    /// Create a new BeaconLogStore
+    pub fn new(connection: Arc<SqliteConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl BeaconStore for BeaconLogStore {
+    async fn save_current_beacon(&self, beacon: CardanoDbBeacon) -> StdResult<BeaconLogEntry> {
+        self.connection
+            .fetch_first(InsertBeaconLogEntryQuery::one(&beacon, Utc::now())?)
+            .with_context(|| format!("Could not append beacon '{beacon}' to the beacon log"))?
+            .ok_or_else(|| anyhow!("No entry returned when appending beacon '{beacon}'"))
+    }
+
+    async fn advance_epochs(&self, beacons: Vec<CardanoDbBeacon>) -> StdResult<Epoch> {
+        let new_epoch = beacons
+            .last()
+            .map(|beacon| beacon.epoch)
+            .ok_or_else(|| anyhow!("No beacon to advance the epochs to"))?;
+
+        // Dropping the transaction without committing it rolls back the saved beacons
+        let transaction = self.connection.begin_transaction()?;
+        let mut previous_epoch = self
+            .connection
+            .fetch_first(GetBeaconLogEntryQuery::last())
+            .with_context(|| "Could not get the last entry of the beacon log")?
+            .map(|entry| entry.epoch);
+        for beacon in &beacons {
+            if let Some(previous) = previous_epoch.filter(|previous| beacon.epoch < *previous) {
+                return Err(BeaconStoreError::NonMonotonicEpoch {
+                    previous,
+                    next: beacon.epoch,
+                }
+                .into());
+            }
+            let _ = self
+                .connection
+                .fetch_first(InsertBeaconLogEntryQuery::one(beacon, Utc::now())?)
+                .with_context(|| format!("Could not append beacon '{beacon}' to the beacon log"))?;
+            previous_epoch = Some(beacon.epoch);
+        }
+        transaction.commit()?;
+
+        Ok(new_epoch)
+    }
+
+    async fn replication_log_since(&self, sequence: u64) -> StdResult<Vec<BeaconLogEntry>> {
+        let entries = self
+            .connection
+            .fetch_collect(GetBeaconLogEntryQuery::since(sequence)?)
+            .with_context(|| {
+                format!("Could not get the beacon log entries since sequence '{sequence}'")
+            })?;
+
+        Ok(entries)
+    }
+
+    async fn get_current_beacon(&self) -> StdResult<Option<CardanoDbBeacon>> {
+        let entry = self
+            .connection
+            .fetch_first(GetBeaconLogEntryQuery::last())
+            .with_context(|| "Could not get the last entry of the beacon log")?;
+
+        Ok(entry.map(|entry| entry.beacon))
+    }
+
+    async fn record_beacon_slot(&self, epoch: Epoch, slot: SlotNumber) -> StdResult<()> {
+        let _ = self
+            .connection
+            .fetch_first(UpsertBeaconSlotsQuery::one(epoch, slot)?)
+            .with_context(|| format!("Could not record slot '{slot}' for epoch '{epoch}'"))?;
+
+        Ok(())
+    }
+
+    async fn get_beacon_at_slot(&self, slot: SlotNumber) -> StdResult<Option<CardanoDbBeacon>> {
+        let entry = self
+            .connection
+            .fetch_first(GetBeaconLogEntryQuery::last_at_slot(slot)?)
+            .with_context(|| format!("Could not get the beacon at slot '{slot}'"))?;
+
+        Ok(entry.map(|entry| entry.beacon))
+    }
+
+    async fn aggregate_by_epoch(&self, from: Epoch, to: Epoch) -> StdResult<Vec<EpochBeaconStats>> {
+        let stats = self
+            .connection
+            .fetch_collect(GetEpochBeaconStatsQuery::between(from, to)?)
+            .with_context(|| {
+                format!("Could not aggregate the beacons of the epochs from '{from}' to '{to}'")
+            })?;
+
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    fn beacon_log_store() -> BeaconLogStore {
+        BeaconLogStore::new(Arc::new(main_db_connection().unwrap()))
+    }
+
+    #[tokio::test]
+    async fn replication_log_since_returns_the_entries_after_the_sequence() {
+        let store = beacon_log_store();
+        for immutable_file_number in 1..=5 {
+            store
+                .save_current_beacon(CardanoDbBeacon::new(
+                    "devnet".to_string(),
+                    immutable_file_number / 2,
+                    immutable_file_number,
+                ))
+                .await
+                .unwrap();
+        }
+
+        let entries = store.replication_log_since(2).await.unwrap();
+
+        assert_eq!(
+            vec![3, 4, 5],
+            entries.iter().map(|e| e.sequence).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                CardanoDbBeacon::new("devnet".to_string(), 1, 3),
+                CardanoDbBeacon::new("devnet".to_string(), 2, 4),
+                CardanoDbBeacon::new("devnet".to_string(), 2, 5),
+            ],
+            entries.iter().map(|e| e.beacon.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!(Epoch(2), entries[2].epoch);
+    }
+
+    #[tokio::test]
+    async fn get_current_beacon_returns_the_last_saved_beacon() {
+        let store = beacon_log_store();
+        assert_eq!(None, store.get_current_beacon().await.unwrap());
+
+        for immutable_file_number in 1..=3 {
+            store
+                .save_current_beacon(CardanoDbBeacon::new(
+                    "devnet".to_string(),
+                    1,
+                    immutable_file_number,
+                ))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            Some(CardanoDbBeacon::new("devnet".to_string(), 1, 3)),
+            store.get_current_beacon().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn advance_epochs_saves_all_the_beacons_in_order() {
+        let store = beacon_log_store();
+        let beacons: Vec<CardanoDbBeacon> = (1..=10)
+            .map(|epoch| CardanoDbBeacon::new("devnet".to_string(), epoch, epoch * 2))
+            .collect();
+
+        let epoch = store.advance_epochs(beacons.clone()).await.unwrap();
+
+        assert_eq!(Epoch(10), epoch);
+        assert_eq!(
+            beacons,
+            store
+                .replication_log_since(0)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.beacon)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(CardanoDbBeacon::new("devnet".to_string(), 10, 20)),
+            store.get_current_beacon().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn advance_epochs_with_a_non_monotonic_epoch_saves_none_of_the_beacons() {
+        let store = beacon_log_store();
+        let current_beacon = CardanoDbBeacon::new("devnet".to_string(), 2, 4);
+        store
+            .save_current_beacon(current_beacon.clone())
+            .await
+            .unwrap();
+        let beacons: Vec<CardanoDbBeacon> = [3, 4, 5, 4, 6]
+            .into_iter()
+            .map(|epoch| CardanoDbBeacon::new("devnet".to_string(), epoch, epoch * 2))
+            .collect();
+
+        let error = store
+            .advance_epochs(beacons)
+            .await
+            .expect_err("advance_epochs should fail with a non monotonic epoch");
+
+        assert_eq!(
+            Some(&BeaconStoreError::NonMonotonicEpoch {
+                previous: Epoch(5),
+                next: Epoch(4),
+            }),
+            error.downcast_ref::<BeaconStoreError>()
+        );
+        assert_eq!(1, store.replication_log_since(0).await.unwrap().len());
+        assert_eq!(
+            Some(current_beacon),
+            store.get_current_beacon().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn advance_epochs_rejects_a_beacon_older_than_the_current_one() {
+        let store = beacon_log_store();
+        store
+            .save_current_beacon(CardanoDbBeacon::new("devnet".to_string(), 5, 10))
+            .await
+            .unwrap();
+
+        let error = store
+            .advance_epochs(vec![CardanoDbBeacon::new("devnet".to_string(), 4, 11)])
+            .await
+            .expect_err("advance_epochs should fail with an epoch older than the current one");
+
+        assert_eq!(
+            Some(&BeaconStoreError::NonMonotonicEpoch {
+                previous: Epoch(5),
+                next: Epoch(4),
+            }),
+            error.downcast_ref::<BeaconStoreError>()
+        );
+    }
+
+    #[tokio::test]
+    async fn replication_log_since_last_sequence_is_empty() {
+        let store = beacon_log_store();
+        let entry = store
+            .save_current_beacon(CardanoDbBeacon::new("devnet".to_string(), 1, 1))
+            .await
+            .unwrap();
+
+        let entries = store.replication_log_since(entry.sequence).await.unwrap();
+
+        assert!(entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_beacon_at_slot_returns_the_beacon_of_the_epoch_containing_the_slot() {
+        let store = beacon_log_store();
+        for epoch in 1..=5 {
+            // Epoch `n` spans the slots `[n * 100, n * 100 + 99]`
+            for immutable_file_number in [epoch * 3, epoch * 3 + 1] {
+                store
+                    .save_current_beacon(CardanoDbBeacon::new(
+                        "devnet".to_string(),
+                        epoch,
+                        immutable_file_number,
+                    ))
+                    .await
+                    .unwrap();
+            }
+            store
+                .record_beacon_slot(Epoch(epoch), epoch * 100)
+                .await
+                .unwrap();
+            store
+                .record_beacon_slot(Epoch(epoch), epoch * 100 + 99)
+                .await
+                .unwrap();
+        }
+
+        for epoch in 1..=5 {
+            for slot in [epoch * 100, epoch * 100 + 50, epoch * 100 + 99] {
+                assert_eq!(
+                    Some(CardanoDbBeacon::new(
+                        "devnet".to_string(),
+                        epoch,
+                        epoch * 3 + 1
+                    )),
+                    store.get_beacon_at_slot(slot).await.unwrap(),
+                    "slot {slot} should be in epoch {epoch}"
+                );
+            }
+        }
+        assert_eq!(None, store.get_beacon_at_slot(99).await.unwrap());
+        assert_eq!(None, store.get_beacon_at_slot(600).await.unwrap());
+    }
+    #[tokio::test]
+    async fn aggregate_by_epoch_computes_the_stats_of_each_epoch() {
+        let store = beacon_log_store();
+        // Epoch 1: 3 beacons from slot 100 to 160
+        // Epoch 2: 1 beacon at slot 200
+        // Epoch 3: 5 beacons from slot 300 to 380
+        // Epoch 4: 2 beacons but no recorded slot
+        for (epoch, slots) in [
+            (1, vec![100, 120, 160]),
+            (2, vec![200]),
+            (3, vec![300, 310, 350, 370, 380]),
+            (4, vec![]),
+        ] {
+            let beacon_count = if slots.is_empty() {
+                2
+            } else {
+                slots.len() as u64
+            };
+            for immutable_file_number in 1..=beacon_count {
+                store
+                    .save_current_beacon(CardanoDbBeacon::new(
+                        "devnet".to_string(),
+                        epoch,
+                        epoch * 10 + immutable_file_number,
+                    ))
+                    .await
+                    .unwrap();
+            }
+            for slot in slots {
+                store.record_beacon_slot(Epoch(epoch), slot).await.unwrap();
+            }
+        }
+
+        let stats = store.aggregate_by_epoch(Epoch(1), Epoch(4)).await.unwrap();
+
+        assert_eq!(
+            vec![
+                EpochBeaconStats {
+                    epoch: Epoch(1),
+                    beacon_count: 3,
+                    avg_slot_gap: 30.0,
+                    min_slot: 100,
+                    max_slot: 160,
+                },
+                EpochBeaconStats {
+                    epoch: Epoch(2),
+                    beacon_count: 1,
+                    avg_slot_gap: 0.0,
+                    min_slot: 200,
+                    max_slot: 200,
+                },
+                EpochBeaconStats {
+                    epoch: Epoch(3),
+                    beacon_count: 5,
+                    avg_slot_gap: 20.0,
+                    min_slot: 300,
+                    max_slot: 380,
+                },
+            ],
+            stats
+        );
+        assert_eq!(
+            vec![Epoch(2)],
+            store
+                .aggregate_by_epoch(Epoch(2), Epoch(2))
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|stats| stats.epoch)
+                .collect::<Vec<_>>()
+        );
+    }
+}
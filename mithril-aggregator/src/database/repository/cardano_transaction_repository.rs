@@ -4,7 +4,7 @@ use async_trait::async_trait;
 
 use mithril_common::crypto_helper::MKTreeNode;
 use mithril_common::entities::{
-    BlockNumber, BlockRange, CardanoTransaction, ChainPoint, TransactionHash,
+    BlockHash, BlockNumber, BlockRange, CardanoTransaction, ChainPoint, SlotNumber, TransactionHash,
 };
 use mithril_common::StdResult;
 use mithril_persistence::database::repository::CardanoTransactionRepository;
@@ -38,6 +38,33 @@ impl TransactionStore for CardanoTransactionRepository {
         })
     }
 
+    async fn get_transactions_in_slot_range(
+        &self,
+        from_slot: SlotNumber,
+        to_slot: SlotNumber,
+    ) -> StdResult<Vec<CardanoTransaction>> {
+        self.get_transactions_in_slot_range(from_slot, to_slot)
+            .await
+            .map(|v| {
+                v.into_iter()
+                    .map(|record| record.into())
+                    .collect::<Vec<CardanoTransaction>>()
+            })
+    }
+
+    async fn get_transactions_by_block_hash(
+        &self,
+        block_hash: &BlockHash,
+    ) -> StdResult<Vec<CardanoTransaction>> {
+        self.get_transactions_by_block_hash(block_hash.to_owned())
+            .await
+            .map(|v| {
+                v.into_iter()
+                    .map(|record| record.into())
+                    .collect::<Vec<CardanoTransaction>>()
+            })
+    }
+
     async fn store_block_range_roots(
         &self,
         block_ranges: Vec<(BlockRange, MKTreeNode)>,
@@ -0,0 +1,206 @@
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use lru::LruCache;
+use prometheus::{IntCounter, Opts, Registry};
+
+use mithril_common::entities::{Certificate, TransactionHash};
+use mithril_common::StdResult;
+
+use super::{CertificateStore, ConsistencyReport};
+
+/// Default number of certificates kept by a [LruCertificateCache]
+pub const DEFAULT_CERTIFICATE_CACHE_CAPACITY: usize = 100;
+
+/// In-memory LRU cache in front of a [CertificateStore].
+///
+/// Certificates are immutable once issued, so a cached certificate never needs to be
+/// invalidated: it's only dropped when the cache is full and it's the least recently used.
+pub struct LruCertificateCache {
+    inner: Arc<dyn CertificateStore>,
+    cache: Mutex<LruCache<String, Certificate>>,
+    hits: IntCounter,
+    misses: IntCounter,
+}
+
+impl LruCertificateCache {
+    /// Create a new cache in front of the given store with the default capacity
+    pub fn new(inner: Arc<dyn CertificateStore>) -> StdResult<Self> {
+        Ok(Self {
+            inner,
+            cache: Mutex::new(LruCache::new(Self::non_zero_capacity(
+                DEFAULT_CERTIFICATE_CACHE_CAPACITY,
+            ))),
+            hits: IntCounter::with_opts(Opts::new(
+                "mithril_aggregator_certificate_cache_hits",
+                "Number of certificates read from the certificate cache",
+            ))?,
+            misses: IntCounter::with_opts(Opts::new(
+                "mithril_aggregator_certificate_cache_misses",
+                "Number of certificates not found in the certificate cache",
+            ))?,
+        })
+    }
+
+    /// Set the maximum number of certificates kept in the cache, a zero capacity is
+    /// raised to one.
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        self.lock_cache().resize(Self::non_zero_capacity(capacity));
+        self
+    }
+
+    /// Register the hits and misses counters of the cache in the given registry
+    pub fn register_metrics(&self, registry: &Registry) -> StdResult<()> {
+        registry.register(Box::new(self.hits.clone()))?;
+        registry.register(Box::new(self.misses.clone()))?;
+
+        Ok(())
+    }
+
+    /// Ratio of reads served from the cache, `0.0` if the cache was never read
+    pub fn hit_ratio(&self) -> f64 {
+        let hits = self.hits.get();
+        let total = hits + self.misses.get();
+
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+
+    fn non_zero_capacity(capacity: usize) -> NonZeroUsize {
+        NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN)
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, LruCache<String, Certificate>> {
+        // A panic while holding the lock can't leave the cache in an inconsistent state
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn cache_certificate(&self, certificate: Certificate) {
+        self.lock_cache().put(certificate.hash.clone(), certificate);
+    }
+}
+
+#[async_trait]
+impl CertificateStore for LruCertificateCache {
+    async fn get_certificate(&self, hash: &str) -> StdResult<Option<Certificate>> {
+        let cached_certificate = self.lock_cache().get(hash).cloned();
+        if let Some(certificate) = cached_certificate {
+            self.hits.inc();
+            return Ok(Some(certificate));
+        }
+        self.misses.inc();
+
+        let certificate = self.inner.get_certificate(hash).await.with_context(|| {
+            format!("Certificate cache: could not fetch certificate '{hash}' from inner store")
+        })?;
+        if let Some(certificate) = &certificate {
+            self.cache_certificate(certificate.clone());
+        }
+
+        Ok(certificate)
+    }
+
+    async fn save_certificate(&self, certificate: Certificate) -> StdResult<Certificate> {
+        let certificate = self.inner.save_certificate(certificate).await?;
+        self.cache_certificate(certificate.clone());
+
+        Ok(certificate)
+    }
+
+    fn stream_certificates(&self) -> BoxStream<'static, StdResult<Certificate>> {
+        // Streamed certificates are not cached: a full chain would evict all the cached ones
+        self.inner.stream_certificates()
+    }
+
+    async fn find_certificate_for_transaction(
+        &self,
+        tx_hash: &TransactionHash,
+    ) -> StdResult<Option<Certificate>> {
+        self.inner.find_certificate_for_transaction(tx_hash).await
+    }
+
+    async fn verify_consistency(&self) -> StdResult<ConsistencyReport> {
+        // The cached certificates are the ones of the inner store so they don't need to be checked
+        self.inner.verify_consistency().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::fake_data;
+
+    use crate::database::repository::MockCertificateStore;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn second_get_of_the_same_certificate_is_served_from_cache() {
+        let certificate = fake_data::certificate("hash-1".to_string());
+        let mut store = MockCertificateStore::new();
+        {
+            let certificate = certificate.clone();
+            store
+                .expect_get_certificate()
+                .withf(|hash| hash == "hash-1")
+                .return_once(move |_| Ok(Some(certificate)))
+                .once();
+        }
+        let cache = LruCertificateCache::new(Arc::new(store)).unwrap();
+
+        let first = cache.get_certificate("hash-1").await.unwrap();
+        let second = cache.get_certificate("hash-1").await.unwrap();
+
+        assert_eq!(Some(certificate.clone()), first);
+        assert_eq!(Some(certificate), second);
+        assert_eq!(0.5, cache.hit_ratio());
+    }
+
+    #[tokio::test]
+    async fn saved_certificate_is_served_from_cache() {
+        let certificate = fake_data::certificate("hash-1".to_string());
+        let mut store = MockCertificateStore::new();
+        store.expect_save_certificate().returning(Ok).once();
+        store.expect_get_certificate().never();
+        let cache = LruCertificateCache::new(Arc::new(store)).unwrap();
+
+        cache.save_certificate(certificate.clone()).await.unwrap();
+        let cached = cache.get_certificate("hash-1").await.unwrap();
+
+        assert_eq!(Some(certificate), cached);
+        assert_eq!(1.0, cache.hit_ratio());
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_certificate_is_evicted() {
+        let mut store = MockCertificateStore::new();
+        store
+            .expect_get_certificate()
+            .returning(|hash| Ok(Some(fake_data::certificate(hash.to_string()))))
+            .times(3);
+        let cache = LruCertificateCache::new(Arc::new(store))
+            .unwrap()
+            .with_capacity(1);
+
+        cache.get_certificate("hash-1").await.unwrap();
+        cache.get_certificate("hash-2").await.unwrap();
+        cache.get_certificate("hash-1").await.unwrap();
+
+        assert_eq!(0.0, cache.hit_ratio());
+    }
+
+    #[tokio::test]
+    async fn metrics_can_be_registered() {
+        let cache = LruCertificateCache::new(Arc::new(MockCertificateStore::new())).unwrap();
+        let registry = Registry::new();
+
+        cache.register_metrics(&registry).unwrap();
+
+        assert_eq!(2, registry.gather().len());
+    }
+}
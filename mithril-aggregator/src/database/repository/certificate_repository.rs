@@ -1,29 +1,124 @@
+use std::collections::{BTreeMap, HashSet};
+use std::ops::Range;
 use std::sync::Arc;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::Serialize;
+use slog_scope::{debug, warn};
 use sqlite::ConnectionThreadSafe;
 
 use mithril_common::certificate_chain::{CertificateRetriever, CertificateRetrieverError};
-use mithril_common::entities::{Certificate, Epoch};
+use mithril_common::entities::{
+    BlockNumber, Certificate, Epoch, ProtocolMessagePartKey, TransactionHash,
+};
 use mithril_common::StdResult;
 use mithril_persistence::sqlite::ConnectionExtensions;
 
 use crate::database::query::{
-    DeleteCertificateQuery, GetCertificateRecordQuery, InsertCertificateRecordQuery,
-    MasterCertificateQuery,
+    ClearArchivedCertificateContentQuery, DeleteCertificateQuery, GetArchivedCertificateQuery,
+    GetCertificateRecordQuery, GetCertificateTransactionIndexQuery, InsertArchivedCertificateQuery,
+    InsertCertificateRecordQuery, InsertCertificateTransactionIndexQuery, MasterCertificateQuery,
 };
 use crate::database::record::CertificateRecord;
+use crate::services::TransactionStore;
+use crate::CertificateArchiveStore;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Store of [certificates][Certificate]
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait CertificateStore: Sync + Send {
+    /// Return the certificate corresponding to the given hash if any.
+    async fn get_certificate(&self, hash: &str) -> StdResult<Option<Certificate>>;
+
+    /// Save the given certificate and return it as stored.
+    async fn save_certificate(&self, certificate: Certificate) -> StdResult<Certificate>;
+
+    /// Stream all the certificates one at a time, from the latest to the oldest.
+    fn stream_certificates(&self) -> BoxStream<'static, StdResult<Certificate>>;
+
+    /// Return the certificate that certifies the given cardano transaction if any.
+    async fn find_certificate_for_transaction(
+        &self,
+        tx_hash: &TransactionHash,
+    ) -> StdResult<Option<Certificate>>;
+
+    /// Check that the stored certificates are not corrupted, see [ConsistencyReport].
+    async fn verify_consistency(&self) -> StdResult<ConsistencyReport>;
+}
+
+/// Result of the consistency verification of the stored certificates
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConsistencyReport {
+    /// Number of checked certificates
+    pub checked: u64,
+
+    /// Hashes of the certificates whose stored hash differs from the hash computed from their
+    /// fields
+    pub hash_mismatches: Vec<String>,
+
+    /// Hash and previous hash of the certificates whose previous hash is not the hash of a
+    /// certificate stored before them
+    pub broken_chain_links: Vec<(String, String)>,
+}
+
+/// Number of certificates read at once by a certificates stream.
+const CERTIFICATES_STREAM_PAGE_SIZE: usize = 16;
+
+/// Number of transactions recorded at once in the certificates transactions index.
+const TRANSACTION_INDEX_INSERT_CHUNK_SIZE: usize = 100;
+
+/// Number of blocks which transactions are read at once when the certificates transactions
+/// index is built for the first time.
+///
+/// Only the most recent range is indexed when the certificate is created, the older blocks are
+/// indexed in the background.
+const TRANSACTION_INDEX_BACKFILL_BLOCK_RANGE: BlockNumber = 1_000;
+
+/// Transactions to record in the certificates transactions index for a new certificate
+struct TransactionsIndexUpdate {
+    /// Transactions, with the number of their block, indexed with the certificate creation
+    transactions: Vec<(TransactionHash, BlockNumber)>,
+
+    /// Blocks to index in the background, when the index is built for the first time
+    backfill_blocks: Range<BlockNumber>,
+}
 
 /// Database frontend API for Certificate queries.
 pub struct CertificateRepository {
     connection: Arc<ConnectionThreadSafe>,
+    transaction_store: Option<Arc<dyn TransactionStore>>,
+    archive_store: Option<Arc<dyn CertificateArchiveStore>>,
 }
 
 impl CertificateRepository {
     /// Instantiate a new repository
     pub fn new(connection: Arc<ConnectionThreadSafe>) -> Self {
-        Self { connection }
+        Self {
+            connection,
+            transaction_store: None,
+            archive_store: None,
+        }
+    }
+
+    /// Index the transactions certified by the created certificates, read from the given store.
+    ///
+    /// Without a transaction store the transactions are not indexed and
+    /// [find_certificate_for_transaction][Self::find_certificate_for_transaction] finds nothing.
+    pub fn with_transaction_store(mut self, transaction_store: Arc<dyn TransactionStore>) -> Self {
+        self.transaction_store = Some(transaction_store);
+        self
+    }
+
+    /// Look for the certificates archived with [archive_before][Self::archive_before] in the
+    /// given store when they are retrieved as a [CertificateRetriever].
+    pub fn with_archive_store(mut self, archive_store: Arc<dyn CertificateArchiveStore>) -> Self {
+        self.archive_store = Some(archive_store);
+        self
     }
 
     /// Return the certificate corresponding to the given hash if any.
@@ -48,6 +143,19 @@ impl CertificateRepository {
         Ok(cursor.take(last_n).map(|v| v.into()).collect())
     }
 
+    /// Return the certificates whose signers or protocol message contain the given text, from
+    /// the latest to the oldest.
+    pub async fn search_certificates<T>(&self, text: &str) -> StdResult<Vec<T>>
+    where
+        T: From<CertificateRecord>,
+    {
+        let cursor = self
+            .connection
+            .fetch(GetCertificateRecordQuery::search(text))?;
+
+        Ok(cursor.map(|v| v.into()).collect())
+    }
+
     /// Return the first certificate signed per epoch as the reference
     /// certificate for this Epoch. This will be the parent certificate for all
     /// other certificates issued within this Epoch.
@@ -62,8 +170,61 @@ impl CertificateRepository {
         Ok(record.map(|c| c.into()))
     }
 
+    /// Stream all the certificates one at a time, from the latest to the oldest.
+    ///
+    /// The certificates are read by pages of [CERTIFICATES_STREAM_PAGE_SIZE] certificates, the
+    /// database is only used while a page is read and no cursor is kept open between two pages.
+    pub fn stream_certificates<T>(&self) -> BoxStream<'static, StdResult<T>>
+    where
+        T: From<CertificateRecord> + Send + 'static,
+    {
+        let connection = self.connection.clone();
+        // The state is the hash of the last streamed certificate, `None` once the chain is read
+        stream::unfold(
+            Some(None),
+            move |last_certificate_id: Option<Option<String>>| {
+                let connection = connection.clone();
+                async move {
+                    let last_certificate_id = last_certificate_id?;
+                    let page = match connection.fetch_collect::<_, Vec<CertificateRecord>>(
+                        GetCertificateRecordQuery::page_before(
+                            last_certificate_id.as_deref(),
+                            CERTIFICATES_STREAM_PAGE_SIZE,
+                        ),
+                    ) {
+                        Ok(records) => records,
+                        Err(error) => return Some((vec![Err(error)], None)),
+                    };
+                    let next_state = (page.len() == CERTIFICATES_STREAM_PAGE_SIZE)
+                        .then(|| page.last().map(|record| record.certificate_id.clone()));
+
+                    Some((
+                        page.into_iter().map(|record| Ok(record.into())).collect(),
+                        next_state,
+                    ))
+                }
+            },
+        )
+        .flat_map(stream::iter)
+        .boxed()
+    }
+
     /// Create a new certificate in the database.
+    ///
+    /// The certificate and the transactions it certifies are recorded in the same database
+    /// transaction.
     pub async fn create_certificate(&self, certificate: Certificate) -> StdResult<Certificate> {
+        let index_update = self
+            .prepare_transactions_index_update(&certificate)
+            .await
+            .with_context(|| {
+                format!(
+                    "Could not read the transactions of certificate '{}'",
+                    certificate.hash
+                )
+            })?;
+
+        let transaction = self.connection.begin_transaction()?;
         let record = self
             .connection
             .fetch_first(InsertCertificateRecordQuery::one(
@@ -72,10 +233,177 @@ impl CertificateRepository {
             .unwrap_or_else(|| {
                 panic!("No entity returned by the persister, certificate = {certificate:#?}")
             });
+        if let Some(index_update) = &index_update {
+            Self::insert_transactions_index(
+                &self.connection,
+                &certificate.hash,
+                &index_update.transactions,
+                false,
+            )
+            .with_context(|| {
+                format!(
+                    "Could not index the transactions of certificate '{}'",
+                    certificate.hash
+                )
+            })?;
+        }
+        transaction.commit()?;
+
+        if let Some(index_update) = index_update {
+            if !index_update.backfill_blocks.is_empty() {
+                self.spawn_transactions_index_backfill(
+                    certificate.hash.clone(),
+                    index_update.backfill_blocks,
+                );
+            }
+        }
 
         Ok(record.into())
     }
 
+    /// Read the transactions certified by the given certificate that must be recorded in the
+    /// `certificate_transaction_index` table.
+    ///
+    /// The protocol message of a cardano transactions certificate only holds the merkle root of
+    /// the transactions and the latest signed block number: the certified transactions are the
+    /// ones of the transaction store in the blocks following the last indexed block, up to the
+    /// latest signed block.
+    ///
+    /// When the index is empty only the most recent blocks are read, the older ones are left
+    /// to a background backfill.
+    async fn prepare_transactions_index_update(
+        &self,
+        certificate: &Certificate,
+    ) -> StdResult<Option<TransactionsIndexUpdate>> {
+        let (Some(transaction_store), Some(latest_block_number)) = (
+            &self.transaction_store,
+            certificate
+                .protocol_message
+                .get_message_part(&ProtocolMessagePartKey::LatestBlockNumber),
+        ) else {
+            return Ok(None);
+        };
+        let latest_block_number: BlockNumber = latest_block_number.parse().with_context(|| {
+            format!("Invalid latest block number '{latest_block_number}' in protocol message")
+        })?;
+        let last_indexed_block_number = self.connection.query_single_cell::<_, Option<i64>>(
+            "select max(block_number) from certificate_transaction_index",
+            &[],
+        )?;
+        let (first_block_number, backfill_blocks) = match last_indexed_block_number {
+            Some(block_number) => (block_number as BlockNumber + 1, 0..0),
+            None => {
+                let first_block_number = (latest_block_number + 1)
+                    .saturating_sub(TRANSACTION_INDEX_BACKFILL_BLOCK_RANGE);
+                (first_block_number, 0..first_block_number)
+            }
+        };
+        if first_block_number > latest_block_number {
+            return Ok(None);
+        }
+
+        let transactions = transaction_store
+            .get_transactions_in_range(first_block_number..(latest_block_number + 1))
+            .await?
+            .into_iter()
+            .map(|transaction| (transaction.transaction_hash, transaction.block_number))
+            .collect();
+
+        Ok(Some(TransactionsIndexUpdate {
+            transactions,
+            backfill_blocks,
+        }))
+    }
+
+    fn insert_transactions_index(
+        connection: &ConnectionThreadSafe,
+        certificate_id: &str,
+        transactions: &[(TransactionHash, BlockNumber)],
+        is_backfill: bool,
+    ) -> StdResult<()> {
+        for chunk in transactions.chunks(TRANSACTION_INDEX_INSERT_CHUNK_SIZE) {
+            let query = if is_backfill {
+                InsertCertificateTransactionIndexQuery::many_if_absent(certificate_id, chunk)?
+            } else {
+                InsertCertificateTransactionIndexQuery::many(certificate_id, chunk)?
+            };
+            let _ = connection.fetch_collect::<_, Vec<_>>(query)?;
+        }
+
+        Ok(())
+    }
+
+    /// Index in the background the transactions of the given blocks, certified by the given
+    /// certificate.
+    ///
+    /// The blocks are indexed from the most recent so an interrupted backfill never lowers the
+    /// last indexed block that the next certificates start from, and the transactions already
+    /// indexed are kept.
+    fn spawn_transactions_index_backfill(
+        &self,
+        certificate_id: String,
+        blocks: Range<BlockNumber>,
+    ) -> tokio::task::JoinHandle<()> {
+        let connection = self.connection.clone();
+        let Some(transaction_store) = self.transaction_store.clone() else {
+            return tokio::spawn(async {});
+        };
+
+        tokio::spawn(async move {
+            let mut end = blocks.end;
+            while end > blocks.start {
+                let start = end
+                    .saturating_sub(TRANSACTION_INDEX_BACKFILL_BLOCK_RANGE)
+                    .max(blocks.start);
+                let result = async {
+                    let transactions: Vec<(TransactionHash, BlockNumber)> = transaction_store
+                        .get_transactions_in_range(start..end)
+                        .await?
+                        .into_iter()
+                        .map(|transaction| (transaction.transaction_hash, transaction.block_number))
+                        .collect();
+                    Self::insert_transactions_index(
+                        &connection,
+                        &certificate_id,
+                        &transactions,
+                        true,
+                    )
+                }
+                .await;
+                if let Err(error) = result {
+                    warn!(
+                        "Certificate transactions index backfill interrupted";
+                        "certificate_id" => &certificate_id, "blocks" => ?(start..end), "error" => ?error
+                    );
+                    return;
+                }
+                end = start;
+            }
+            debug!("Certificate transactions index backfill completed"; "certificate_id" => &certificate_id);
+        })
+    }
+
+    /// Return the certificate that certifies the given cardano transaction if any.
+    ///
+    /// The transaction is looked up with the primary key of the `certificate_transaction_index`
+    /// table, then the certificate with the primary key of the `certificate` table.
+    pub async fn find_certificate_for_transaction<T>(
+        &self,
+        tx_hash: &TransactionHash,
+    ) -> StdResult<Option<T>>
+    where
+        T: From<CertificateRecord>,
+    {
+        let Some(index_entry) = self.connection.fetch_first(
+            GetCertificateTransactionIndexQuery::by_transaction_hash(tx_hash),
+        )?
+        else {
+            return Ok(None);
+        };
+
+        self.get_certificate(&index_entry.certificate_id).await
+    }
+
     /// Create many certificates at once in the database.
     pub async fn create_many_certificates(
         &self,
@@ -94,6 +422,122 @@ impl CertificateRepository {
         Ok(new_certificates.map(|cert| cert.into()).collect())
     }
 
+    /// Move the certificates of the epochs strictly before the given epoch to the given
+    /// archive store, bundled by epoch, and return the number of archived certificates.
+    ///
+    /// The archived certificates are kept in an index so they can still be retrieved with
+    /// [Self::get_archived_certificate]. The remaining certificates and signed entities may still
+    /// reference them, so their row is kept without its content and the queries of this
+    /// repository skip it.
+    pub async fn archive_before(
+        &self,
+        epoch: Epoch,
+        archive_store: Arc<dyn CertificateArchiveStore>,
+    ) -> StdResult<u64> {
+        let mut bundles: BTreeMap<Epoch, Vec<Certificate>> = BTreeMap::new();
+        for record in self
+            .connection
+            .fetch(GetCertificateRecordQuery::before_epoch(epoch)?)?
+        {
+            bundles.entry(record.epoch).or_default().push(record.into());
+        }
+
+        let mut nb_archived_certificates = 0;
+        for (bundle_epoch, certificates) in bundles {
+            let ids: Vec<String> = certificates.iter().map(|c| c.hash.clone()).collect();
+            let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+            archive_store
+                .save_bundle(bundle_epoch, certificates)
+                .await
+                .with_context(|| {
+                    format!("Could not archive the certificates of epoch '{bundle_epoch}'")
+                })?;
+
+            let transaction = self.connection.begin_transaction()?;
+            let _ = self.connection.fetch_collect::<_, Vec<_>>(
+                InsertArchivedCertificateQuery::many(&ids, bundle_epoch)?,
+            )?;
+            let _ = self
+                .connection
+                .fetch_collect::<_, Vec<_>>(ClearArchivedCertificateContentQuery::by_ids(&ids))?;
+            transaction.commit().with_context(|| {
+                format!("Could not clear the archived certificates of epoch '{bundle_epoch}'")
+            })?;
+
+            nb_archived_certificates += ids.len() as u64;
+        }
+
+        Ok(nb_archived_certificates)
+    }
+
+    /// Return the certificate corresponding to the given hash from the given archive store, if
+    /// it was archived with [Self::archive_before].
+    pub async fn get_archived_certificate(
+        &self,
+        hash: &str,
+        archive_store: &dyn CertificateArchiveStore,
+    ) -> StdResult<Option<Certificate>> {
+        let Some(archived) = self
+            .connection
+            .fetch_first(GetArchivedCertificateQuery::by_certificate_id(hash))?
+        else {
+            return Ok(None);
+        };
+
+        let certificate = archive_store
+            .load_bundle(archived.epoch)
+            .await
+            .with_context(|| {
+                format!(
+                    "Could not load the archived certificates of epoch '{}'",
+                    archived.epoch
+                )
+            })?
+            .into_iter()
+            .find(|c| c.hash == hash);
+
+        Ok(certificate)
+    }
+
+    /// Check the consistency of all the stored certificates.
+    ///
+    /// The hash of each certificate is computed again from its fields and compared to its stored
+    /// hash, and the previous hash of each non-genesis certificate must be the hash of a
+    /// certificate stored before it, either still in the database or archived.
+    pub async fn verify_consistency(&self) -> StdResult<ConsistencyReport> {
+        let records: Vec<CertificateRecord> = self
+            .connection
+            .fetch_collect(GetCertificateRecordQuery::all())?;
+
+        let mut report = ConsistencyReport::default();
+        let mut earlier_hashes = HashSet::new();
+        // The records are read from the latest to the oldest
+        for record in records.into_iter().rev() {
+            let certificate: Certificate = record.into();
+            if certificate.compute_hash() != certificate.hash {
+                report.hash_mismatches.push(certificate.hash.clone());
+            }
+            if !certificate.is_genesis()
+                && !earlier_hashes.contains(&certificate.previous_hash)
+                && self
+                    .connection
+                    .fetch_first(GetArchivedCertificateQuery::by_certificate_id(
+                        &certificate.previous_hash,
+                    ))?
+                    .is_none()
+            {
+                report
+                    .broken_chain_links
+                    .push((certificate.hash.clone(), certificate.previous_hash.clone()));
+            }
+
+            report.checked += 1;
+            earlier_hashes.insert(certificate.hash);
+        }
+
+        Ok(report)
+    }
+
     /// Delete all the given certificates from the database
     pub async fn delete_certificates(&self, certificates: &[&Certificate]) -> StdResult<()> {
         let ids = certificates
@@ -109,29 +553,72 @@ impl CertificateRepository {
     }
 }
 
+#[async_trait]
+impl CertificateStore for CertificateRepository {
+    async fn get_certificate(&self, hash: &str) -> StdResult<Option<Certificate>> {
+        CertificateRepository::get_certificate(self, hash).await
+    }
+
+    async fn save_certificate(&self, certificate: Certificate) -> StdResult<Certificate> {
+        self.create_certificate(certificate).await
+    }
+
+    fn stream_certificates(&self) -> BoxStream<'static, StdResult<Certificate>> {
+        CertificateRepository::stream_certificates(self)
+    }
+
+    async fn find_certificate_for_transaction(
+        &self,
+        tx_hash: &TransactionHash,
+    ) -> StdResult<Option<Certificate>> {
+        CertificateRepository::find_certificate_for_transaction(self, tx_hash).await
+    }
+
+    async fn verify_consistency(&self) -> StdResult<ConsistencyReport> {
+        CertificateRepository::verify_consistency(self).await
+    }
+}
+
 #[async_trait]
 impl CertificateRetriever for CertificateRepository {
     async fn get_certificate_details(
         &self,
         certificate_hash: &str,
     ) -> Result<Certificate, CertificateRetrieverError> {
-        self.get_certificate(certificate_hash)
+        let mut certificate = self
+            .get_certificate(certificate_hash)
             .await
-            .map_err(|e| CertificateRetrieverError(anyhow!(e)))?
-            .ok_or(CertificateRetrieverError(anyhow!(format!(
-                "Certificate does not exist: '{}'",
-                certificate_hash
-            ))))
+            .map_err(|e| CertificateRetrieverError(anyhow!(e)))?;
+        if let (None, Some(archive_store)) = (&certificate, &self.archive_store) {
+            certificate = self
+                .get_archived_certificate(certificate_hash, archive_store.as_ref())
+                .await
+                .map_err(|e| CertificateRetrieverError(anyhow!(e)))?;
+        }
+
+        certificate.ok_or(CertificateRetrieverError(anyhow!(format!(
+            "Certificate does not exist: '{}'",
+            certificate_hash
+        ))))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::ops::Range;
+
     use mithril_common::crypto_helper::tests_setup::setup_certificate_chain;
+    use mithril_common::entities::{CardanoTransaction, StakeDistributionParty};
+    use mithril_common::test_utils::fake_data;
 
-    use crate::database::test_helper::{insert_certificate_records, main_db_connection};
+    use crate::database::record::SignedEntityRecord;
+    use crate::database::test_helper::{
+        insert_certificate_records, insert_signed_entities, main_db_connection,
+    };
     use crate::dependency_injection::DependenciesBuilder;
-    use crate::Configuration;
+    use crate::services::MockTransactionStore;
+    use crate::store::MockCertificateArchiveStore;
+    use crate::{Configuration, FileCertificateArchiveStore};
 
     use super::*;
 
@@ -235,6 +722,108 @@ mod tests {
         assert_eq!(0, current_records.len());
     }
 
+    fn fake_certificates(count: usize) -> Vec<Certificate> {
+        let certificate = fake_data::certificate("certificate".to_string());
+        (0..count)
+            .map(|i| Certificate {
+                hash: format!("certificate-{i}"),
+                ..certificate.clone()
+            })
+            .collect()
+    }
+
+    async fn assert_all_certificates_are_streamed_from_the_latest(count: usize) {
+        let certificates = fake_certificates(count);
+        let connection = main_db_connection().unwrap();
+        insert_certificate_records(&connection, certificates.clone());
+        let repository = CertificateRepository::new(Arc::new(connection));
+
+        let streamed_hashes: Vec<String> = repository
+            .stream_certificates::<Certificate>()
+            .map(|certificate| certificate.unwrap().hash)
+            .collect()
+            .await;
+
+        let expected_hashes: Vec<String> = certificates.into_iter().rev().map(|c| c.hash).collect();
+        assert_eq!(expected_hashes, streamed_hashes);
+    }
+
+    #[tokio::test]
+    async fn stream_certificates_yields_all_certificates_from_the_latest() {
+        assert_all_certificates_are_streamed_from_the_latest(CERTIFICATES_STREAM_PAGE_SIZE * 3 + 1)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn stream_certificates_yields_all_certificates_when_the_last_page_is_full() {
+        assert_all_certificates_are_streamed_from_the_latest(CERTIFICATES_STREAM_PAGE_SIZE * 2)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn stream_certificates_of_an_empty_database_yields_nothing() {
+        let repository = CertificateRepository::new(Arc::new(main_db_connection().unwrap()));
+
+        let stream = repository.stream_certificates::<Certificate>();
+
+        assert_eq!(0, stream.count().await);
+    }
+
+    #[tokio::test]
+    async fn the_connection_can_be_used_between_two_pages_of_a_certificates_stream() {
+        let connection = main_db_connection().unwrap();
+        insert_certificate_records(
+            &connection,
+            fake_certificates(CERTIFICATES_STREAM_PAGE_SIZE * 4),
+        );
+        let repository = CertificateRepository::new(Arc::new(connection));
+
+        let mut stream = repository.stream_certificates::<Certificate>();
+        let first_certificate = stream.next().await.unwrap().unwrap();
+        assert_eq!("certificate-63", first_certificate.hash);
+
+        let certificates = repository
+            .get_latest_certificates::<Certificate>(2)
+            .await
+            .unwrap();
+        assert_eq!(2, certificates.len());
+
+        assert_eq!(CERTIFICATES_STREAM_PAGE_SIZE * 4 - 1, stream.count().await);
+    }
+
+    #[tokio::test]
+    async fn search_certificates_returns_the_certificates_signed_by_a_party() {
+        let certificates: Vec<Certificate> = [
+            vec!["pool1aaa", "pool1bbb"],
+            vec!["pool1ccc", "pool1ddd"],
+            vec!["pool1aaa", "pool1eee"],
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, party_ids)| {
+            let mut certificate = fake_data::certificate(format!("certificate-{index}"));
+            certificate.metadata.signers = party_ids
+                .into_iter()
+                .map(|party_id| StakeDistributionParty {
+                    party_id: party_id.to_string(),
+                    stake: 100,
+                })
+                .collect();
+            certificate
+        })
+        .collect();
+        let connection = main_db_connection().unwrap();
+        insert_certificate_records(&connection, certificates.clone());
+        let repository = CertificateRepository::new(Arc::new(connection));
+
+        let found_certificates = repository
+            .search_certificates::<Certificate>("pool1ddd")
+            .await
+            .unwrap();
+
+        assert_eq!(vec![certificates[1].clone()], found_certificates);
+    }
+
     #[tokio::test]
     async fn repository_get_certificate() {
         let (certificates, _) = setup_certificate_chain(5, 2);
@@ -542,6 +1131,88 @@ mod tests {
         }
     }
 
+    fn certificate_chain_from_the_oldest(
+        total_certificates: u64,
+        certificates_per_epoch: u64,
+    ) -> Vec<Certificate> {
+        let (mut certificates, _) =
+            setup_certificate_chain(total_certificates, certificates_per_epoch);
+        certificates.reverse();
+        certificates
+    }
+
+    #[tokio::test]
+    async fn verify_consistency_of_a_valid_chain_reports_nothing() {
+        let certificates = certificate_chain_from_the_oldest(5, 2);
+        let connection = Arc::new(main_db_connection().unwrap());
+        insert_certificate_records(&connection, certificates.clone());
+        let repository = CertificateRepository::new(connection);
+
+        let report = repository.verify_consistency().await.unwrap();
+
+        assert_eq!(
+            ConsistencyReport {
+                checked: certificates.len() as u64,
+                ..ConsistencyReport::default()
+            },
+            report
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_consistency_reports_certificates_with_a_corrupted_hash() {
+        let mut certificates = certificate_chain_from_the_oldest(5, 2);
+        certificates.last_mut().unwrap().hash = "corrupted-hash".to_string();
+        let connection = Arc::new(main_db_connection().unwrap());
+        insert_certificate_records(&connection, certificates.clone());
+        let repository = CertificateRepository::new(connection);
+
+        let report = repository.verify_consistency().await.unwrap();
+
+        assert_eq!(certificates.len() as u64, report.checked);
+        assert_eq!(vec!["corrupted-hash".to_string()], report.hash_mismatches);
+        assert!(report.broken_chain_links.is_empty());
+    }
+
+    #[tokio::test]
+    async fn verify_consistency_reports_certificates_chained_to_a_missing_certificate() {
+        let mut certificates = certificate_chain_from_the_oldest(5, 2);
+        let missing_certificate = certificates.remove(2);
+        let connection = Arc::new(main_db_connection().unwrap());
+        insert_certificate_records(&connection, certificates.clone());
+        let repository = CertificateRepository::new(connection);
+
+        let report = repository.verify_consistency().await.unwrap();
+
+        assert!(report.hash_mismatches.is_empty());
+        assert_eq!(
+            vec![(certificates[2].hash.clone(), missing_certificate.hash)],
+            report.broken_chain_links
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_consistency_reports_certificates_chained_to_a_later_certificate() {
+        let certificates = certificate_chain_from_the_oldest(3, 1);
+        let connection = Arc::new(main_db_connection().unwrap());
+        insert_certificate_records(
+            &connection,
+            vec![
+                certificates[0].clone(),
+                certificates[2].clone(),
+                certificates[1].clone(),
+            ],
+        );
+        let repository = CertificateRepository::new(connection);
+
+        let report = repository.verify_consistency().await.unwrap();
+
+        assert_eq!(
+            vec![(certificates[2].hash.clone(), certificates[1].hash.clone())],
+            report.broken_chain_links
+        );
+    }
+
     #[tokio::test]
     async fn delete_only_given_certificates() {
         let mut deps = DependenciesBuilder::new(Configuration::new_sample());
@@ -574,4 +1245,307 @@ mod tests {
 
         assert_eq!(vec![expected_remaining_certificate], remaining_certificates)
     }
+
+    #[tokio::test]
+    async fn archive_before_moves_the_old_certificates_to_the_archive_store() {
+        let mut deps = DependenciesBuilder::new(Configuration::new_sample());
+        let connection = deps.get_sqlite_connection().await.unwrap();
+        let repository = CertificateRepository::new(connection.clone());
+        let mut records = vec![CertificateRecord::dummy_genesis("cert-0", Epoch(1), 0)];
+        for i in 1..110 {
+            records.push(CertificateRecord::dummy_db_snapshot(
+                &format!("cert-{i}"),
+                &format!("cert-{}", i - 1),
+                Epoch(1 + i / 10),
+                i,
+            ));
+        }
+        insert_certificate_records(&connection, records.clone());
+        let certificates: Vec<Certificate> = records.into_iter().map(|c| c.into()).collect();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_store = Arc::new(FileCertificateArchiveStore::new(archive_dir.path()).unwrap());
+
+        let nb_archived = repository
+            .archive_before(Epoch(11), archive_store.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(100, nb_archived);
+        let remaining_certificates: Vec<Certificate> = repository
+            .get_latest_certificates(usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            certificates[100..]
+                .iter()
+                .rev()
+                .cloned()
+                .collect::<Vec<_>>(),
+            remaining_certificates
+        );
+        for certificate in &certificates[..100] {
+            assert_eq!(
+                None,
+                repository
+                    .get_certificate::<Certificate>(&certificate.hash)
+                    .await
+                    .unwrap()
+            );
+            assert_eq!(
+                Some(certificate.clone()),
+                repository
+                    .get_archived_certificate(&certificate.hash, archive_store.as_ref())
+                    .await
+                    .unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn archive_before_keeps_the_archived_certificates_referenced_by_the_foreign_keys() {
+        let mut deps = DependenciesBuilder::new(Configuration::new_sample());
+        let connection = deps.get_sqlite_connection().await.unwrap();
+        let repository = CertificateRepository::new(connection.clone());
+        insert_certificate_records(
+            &connection,
+            vec![
+                CertificateRecord::dummy_genesis("cert-0", Epoch(1), 0),
+                CertificateRecord::dummy_db_snapshot("cert-1", "cert-0", Epoch(2), 1),
+            ],
+        );
+        insert_signed_entities(
+            &connection,
+            vec![SignedEntityRecord {
+                certificate_id: "cert-0".to_string(),
+                ..SignedEntityRecord::fake_records(1).remove(0)
+            }],
+        )
+        .unwrap();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_store = Arc::new(FileCertificateArchiveStore::new(archive_dir.path()).unwrap());
+
+        let nb_archived = repository
+            .archive_before(Epoch(2), archive_store)
+            .await
+            .unwrap();
+
+        assert_eq!(1, nb_archived);
+        let foreign_keys_enabled = connection
+            .query_single_cell::<_, i64>("pragma foreign_keys", &[])
+            .unwrap();
+        assert_eq!(1, foreign_keys_enabled);
+        let nb_foreign_keys_to_certificate = connection
+            .query_single_cell::<_, i64>(
+                r#"
+select
+    (select count(*) from pragma_foreign_key_list('certificate') where "table" = 'certificate')
+    + (select count(*) from pragma_foreign_key_list('signed_entity') where "table" = 'certificate')"#,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(2, nb_foreign_keys_to_certificate);
+        let nb_foreign_key_violations = connection
+            .query_single_cell::<_, i64>("select count(*) from pragma_foreign_key_check", &[])
+            .unwrap();
+        assert_eq!(0, nb_foreign_key_violations);
+    }
+
+    #[tokio::test]
+    async fn certificate_retriever_falls_back_to_the_archive_store() {
+        let mut deps = DependenciesBuilder::new(Configuration::new_sample());
+        let connection = deps.get_sqlite_connection().await.unwrap();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_store = Arc::new(FileCertificateArchiveStore::new(archive_dir.path()).unwrap());
+        let repository = CertificateRepository::new(connection.clone())
+            .with_archive_store(archive_store.clone());
+        let records = vec![
+            CertificateRecord::dummy_genesis("cert-0", Epoch(1), 0),
+            CertificateRecord::dummy_db_snapshot("cert-1", "cert-0", Epoch(2), 1),
+        ];
+        insert_certificate_records(&connection, records.clone());
+        repository
+            .archive_before(Epoch(2), archive_store)
+            .await
+            .unwrap();
+
+        let archived_certificate = repository.get_certificate_details("cert-0").await.unwrap();
+        let stored_certificate = repository.get_certificate_details("cert-1").await.unwrap();
+
+        assert_eq!(Certificate::from(records[0].clone()), archived_certificate);
+        assert_eq!(Certificate::from(records[1].clone()), stored_certificate);
+        repository
+            .get_certificate_details("not-a-certificate")
+            .await
+            .expect_err("An unknown certificate should not be found");
+    }
+
+    #[tokio::test]
+    async fn get_archived_certificate_of_a_certificate_not_archived_is_none() {
+        let mut deps = DependenciesBuilder::new(Configuration::new_sample());
+        let connection = deps.get_sqlite_connection().await.unwrap();
+        let repository = CertificateRepository::new(connection.clone());
+        insert_certificate_records(
+            &connection,
+            vec![CertificateRecord::dummy_genesis("cert-0", Epoch(1), 0)],
+        );
+        let archive_store = MockCertificateArchiveStore::new();
+
+        let certificate = repository
+            .get_archived_certificate("cert-0", &archive_store)
+            .await
+            .unwrap();
+
+        assert_eq!(None, certificate);
+    }
+
+    fn transactions_certificate(hash: &str, latest_block_number: BlockNumber) -> Certificate {
+        let mut certificate = fake_data::certificate(hash.to_string());
+        certificate.protocol_message.set_message_part(
+            ProtocolMessagePartKey::LatestBlockNumber,
+            latest_block_number.to_string(),
+        );
+
+        certificate
+    }
+
+    /// Transaction store with a transaction `tx-<block_number>` in each of the given blocks
+    fn transaction_store_with_blocks(blocks: Range<BlockNumber>) -> MockTransactionStore {
+        let mut transaction_store = MockTransactionStore::new();
+        transaction_store
+            .expect_get_transactions_in_range()
+            .returning(move |range| {
+                Ok(range
+                    .filter(|block_number| blocks.contains(block_number))
+                    .map(|block_number| {
+                        CardanoTransaction::new(
+                            format!("tx-{block_number}"),
+                            block_number,
+                            block_number * 10,
+                            format!("block-hash-{block_number}"),
+                            block_number / 5,
+                        )
+                    })
+                    .collect())
+            });
+
+        transaction_store
+    }
+
+    #[tokio::test]
+    async fn find_certificate_for_transaction_returns_the_certificate_covering_the_transaction() {
+        let repository = CertificateRepository::new(Arc::new(main_db_connection().unwrap()))
+            .with_transaction_store(Arc::new(transaction_store_with_blocks(0..50)));
+        let first_certificate = repository
+            .create_certificate(transactions_certificate("certificate-1", 19))
+            .await
+            .unwrap();
+        let second_certificate = repository
+            .create_certificate(transactions_certificate("certificate-2", 39))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some(first_certificate.clone()),
+            repository
+                .find_certificate_for_transaction(&"tx-0".to_string())
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(first_certificate),
+            repository
+                .find_certificate_for_transaction(&"tx-19".to_string())
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(second_certificate),
+            repository
+                .find_certificate_for_transaction(&"tx-20".to_string())
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            None::<Certificate>,
+            repository
+                .find_certificate_for_transaction(&"tx-45".to_string())
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn first_certificate_indexes_its_older_transactions_in_the_background() {
+        let repository = CertificateRepository::new(Arc::new(main_db_connection().unwrap()))
+            .with_transaction_store(Arc::new(transaction_store_with_blocks(0..3000)));
+        let certificate = repository
+            .create_certificate(transactions_certificate("certificate-1", 2500))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some(certificate.clone()),
+            repository
+                .find_certificate_for_transaction(&"tx-2500".to_string())
+                .await
+                .unwrap()
+        );
+
+        let mut indexed_certificate = None;
+        for _ in 0..100 {
+            indexed_certificate = repository
+                .find_certificate_for_transaction(&"tx-0".to_string())
+                .await
+                .unwrap();
+            if indexed_certificate.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(Some(certificate), indexed_certificate);
+    }
+
+    #[tokio::test]
+    async fn transactions_index_backfill_keeps_the_transactions_already_indexed() {
+        let repository = CertificateRepository::new(Arc::new(main_db_connection().unwrap()))
+            .with_transaction_store(Arc::new(transaction_store_with_blocks(0..50)));
+        let certificate = repository
+            .create_certificate(transactions_certificate("certificate-1", 19))
+            .await
+            .unwrap();
+
+        repository
+            .spawn_transactions_index_backfill("certificate-0".to_string(), 0..20)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some(certificate),
+            repository
+                .find_certificate_for_transaction(&"tx-5".to_string())
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn certificates_without_latest_block_number_do_not_index_transactions() {
+        let mut transaction_store = MockTransactionStore::new();
+        transaction_store.expect_get_transactions_in_range().never();
+        let repository = CertificateRepository::new(Arc::new(main_db_connection().unwrap()))
+            .with_transaction_store(Arc::new(transaction_store));
+
+        repository
+            .create_certificate(fake_data::certificate("certificate-1".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            None::<Certificate>,
+            repository
+                .find_certificate_for_transaction(&"tx-0".to_string())
+                .await
+                .unwrap()
+        );
+    }
 }
@@ -1,17 +1,27 @@
 //! Aggregator related database repositories
+mod beacon_lock_store;
+mod beacon_log_store;
 mod cardano_transaction_repository;
+mod certificate_cache;
 mod certificate_repository;
 mod epoch_setting_store;
 mod open_message_repository;
+mod replicated_beacon_store;
+mod reward_store;
 mod signed_entity_store;
 mod signer_registration_store;
 mod signer_store;
 mod single_signature_repository;
 mod stake_pool_store;
 
+pub use beacon_lock_store::*;
+pub use beacon_log_store::*;
+pub use certificate_cache::*;
 pub use certificate_repository::*;
 pub use epoch_setting_store::*;
 pub use open_message_repository::*;
+pub use replicated_beacon_store::*;
+pub use reward_store::*;
 pub use signed_entity_store::*;
 pub use signer_registration_store::*;
 pub use signer_store::*;
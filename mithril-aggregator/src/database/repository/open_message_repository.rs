@@ -7,8 +7,8 @@ use mithril_common::StdResult;
 use mithril_persistence::sqlite::{ConnectionExtensions, SqliteConnection};
 
 use crate::database::query::{
-    DeleteOpenMessageQuery, GetOpenMessageQuery, GetOpenMessageWithSingleSignaturesQuery,
-    InsertOpenMessageQuery, UpdateOpenMessageQuery,
+    DeleteOpenMessageQuery, DeleteSingleSignatureRecordQuery, GetOpenMessageQuery,
+    GetOpenMessageWithSingleSignaturesQuery, InsertOpenMessageQuery, UpdateOpenMessageQuery,
 };
 use crate::database::record::{OpenMessageRecord, OpenMessageWithSingleSignaturesRecord};
 
@@ -100,6 +100,22 @@ impl OpenMessageRepository {
 
         Ok(cursor.count())
     }
+
+    /// Remove the given [OpenMessageRecord] along with its single signatures from the database.
+    pub async fn delete_open_message(&self, open_message: &OpenMessageRecord) -> StdResult<()> {
+        let transaction = self.connection.begin_transaction()?;
+        self.connection
+            .fetch(DeleteSingleSignatureRecordQuery::by_open_message_id(
+                &open_message.open_message_id.to_string(),
+            ))?
+            .count();
+        self.connection
+            .fetch(DeleteOpenMessageQuery::by_id(&open_message.open_message_id))?
+            .count();
+        transaction.commit()?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -319,6 +335,61 @@ mod tests {
         assert_eq!(2, count);
     }
 
+    #[tokio::test]
+    async fn repository_delete_open_message_removes_its_single_signatures() {
+        let connection = Arc::new(main_db_connection().unwrap());
+        let repository = OpenMessageRepository::new(connection.clone());
+        let mut open_messages = vec![];
+        for signed_entity_type in [
+            SignedEntityType::MithrilStakeDistribution(Epoch(1)),
+            SignedEntityType::CardanoStakeDistribution(Epoch(1)),
+        ] {
+            let open_message = repository
+                .create_open_message(Epoch(1), &signed_entity_type, &ProtocolMessage::default())
+                .await
+                .unwrap();
+            let single_signature_records = setup_single_signature_records(1, 1, 4)
+                .into_iter()
+                .map(|s| SingleSignatureRecord {
+                    open_message_id: open_message.open_message_id,
+                    ..s
+                })
+                .collect();
+            insert_single_signatures_in_db(&connection, single_signature_records).unwrap();
+            open_messages.push(open_message);
+        }
+        let count_single_signatures = |open_message: &OpenMessageRecord| -> i64 {
+            connection
+                .query_single_cell(
+                    "select count(*) from single_signature where open_message_id = ?",
+                    &[sqlite::Value::String(
+                        open_message.open_message_id.to_string(),
+                    )],
+                )
+                .unwrap()
+        };
+
+        repository
+            .delete_open_message(&open_messages[0])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            None,
+            repository
+                .get_open_message(&open_messages[0].signed_entity_type)
+                .await
+                .unwrap()
+        );
+        assert_eq!(0, count_single_signatures(&open_messages[0]));
+        assert!(repository
+            .get_open_message(&open_messages[1].signed_entity_type)
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(4, count_single_signatures(&open_messages[1]));
+    }
+
     #[tokio::test]
     async fn repository_get_open_message_with_single_signatures_when_signatures_exist() {
         let connection = Arc::new(main_db_connection().unwrap());
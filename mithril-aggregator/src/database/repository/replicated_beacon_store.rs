@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use prometheus::{Histogram, HistogramOpts, Registry};
+use slog_scope::warn;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use mithril_common::entities::{CardanoDbBeacon, Epoch, SlotNumber};
+use mithril_common::StdResult;
+
+use crate::database::record::{BeaconLogEntry, EpochBeaconStats};
+
+use super::BeaconStore;
+
+/// Default replication latency, in milliseconds, above which a warning is logged
+pub const DEFAULT_REPLICATION_LAG_WARNING_MS: u64 = 500;
+
+/// [BeaconStore] of a primary aggregator mirrored to the store of a hot-standby aggregator.
+///
+/// The beacons are saved to the `primary` store then replicated in the background to the
+/// `replica` store, in the order they were saved. The current beacon is read from the `primary`
+/// store, or from the `replica` store if the primary is unavailable.
+pub struct ReplicatedBeaconStore {
+    primary: Arc<dyn BeaconStore>,
+    replica: Arc<dyn BeaconStore>,
+    replication_lag_warning_ms: u64,
+    replication_latency: Histogram,
+    last_replication: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ReplicatedBeaconStore {
+    /// Create a new ReplicatedBeaconStore
+    pub fn new(
+        primary: Arc<dyn BeaconStore>,
+        replica: Arc<dyn BeaconStore>,
+        replication_lag_warning_ms: u64,
+    ) -> StdResult<Self> {
+        Ok(Self {
+            primary,
+            replica,
+            replication_lag_warning_ms,
+            replication_latency: Histogram::with_opts(HistogramOpts::new(
+                "mithril_aggregator_beacon_replication_latency_seconds",
+                "Time elapsed between the save of a beacon to the primary store and to the replica store",
+            ))?,
+            last_replication: Mutex::new(None),
+        })
+    }
+
+    /// Register the replication latency histogram in the given registry
+    pub fn register_metrics(&self, registry: &Registry) -> StdResult<()> {
+        registry.register(Box::new(self.replication_latency.clone()))?;
+
+        Ok(())
+    }
+
+    /// Wait until the beacons saved so far are replicated to the replica store.
+    pub async fn flush_replication(&self) {
+        if let Some(last_replication) = self.last_replication.lock().await.take() {
+            let _ = last_replication.await;
+        }
+    }
+
+    fn replicate(
+        &self,
+        beacon: CardanoDbBeacon,
+        previous: Option<JoinHandle<()>>,
+    ) -> JoinHandle<()> {
+        let replica = self.replica.clone();
+        let replication_latency = self.replication_latency.clone();
+        let replication_lag_warning_ms = self.replication_lag_warning_ms;
+        let saved_at = Instant::now();
+
+        tokio::spawn(async move {
+            // The replica must receive the beacons in the order they were saved to the primary
+            if let Some(previous) = previous {
+                let _ = previous.await;
+            }
+
+            if let Err(error) = replica.save_current_beacon(beacon.clone()).await {
+                warn!("Could not replicate the beacon to the replica store"; "beacon" => ?beacon, "error" => ?error);
+                return;
+            }
+            let latency = saved_at.elapsed();
+            replication_latency.observe(latency.as_secs_f64());
+            if latency.as_millis() > replication_lag_warning_ms as u128 {
+                warn!(
+                    "Beacon replication lag above threshold";
+                    "beacon" => ?beacon, "lag_ms" => latency.as_millis(),
+                    "threshold_ms" => replication_lag_warning_ms
+                );
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl BeaconStore for ReplicatedBeaconStore {
+    async fn save_current_beacon(&self, beacon: CardanoDbBeacon) -> StdResult<BeaconLogEntry> {
+        let entry = self.primary.save_current_beacon(beacon.clone()).await?;
+
+        let mut last_replication = self.last_replication.lock().await;
+        let previous = last_replication.take();
+        *last_replication = Some(self.replicate(beacon, previous));
+
+        Ok(entry)
+    }
+
+    async fn advance_epochs(&self, beacons: Vec<CardanoDbBeacon>) -> StdResult<Epoch> {
+        let epoch = self.primary.advance_epochs(beacons.clone()).await?;
+
+        let mut last_replication = self.last_replication.lock().await;
+        for beacon in beacons {
+            let previous = last_replication.take();
+            *last_replication = Some(self.replicate(beacon, previous));
+        }
+
+        Ok(epoch)
+    }
+
+    async fn replication_log_since(&self, sequence: u64) -> StdResult<Vec<BeaconLogEntry>> {
+        self.primary.replication_log_since(sequence).await
+    }
+
+    async fn get_current_beacon(&self) -> StdResult<Option<CardanoDbBeacon>> {
+        match self.primary.get_current_beacon().await {
+            Ok(beacon) => Ok(beacon),
+            Err(error) => {
+                warn!("Primary beacon store unavailable, reading the current beacon from the replica"; "error" => ?error);
+                self.replica.get_current_beacon().await
+            }
+        }
+    }
+
+    async fn record_beacon_slot(&self, epoch: Epoch, slot: SlotNumber) -> StdResult<()> {
+        self.primary.record_beacon_slot(epoch, slot).await?;
+        if let Err(error) = self.replica.record_beacon_slot(epoch, slot).await {
+            warn!("Could not replicate the beacon slot to the replica store"; "epoch" => ?epoch, "slot" => slot, "error" => ?error);
+        }
+
+        Ok(())
+    }
+
+    async fn get_beacon_at_slot(&self, slot: SlotNumber) -> StdResult<Option<CardanoDbBeacon>> {
+        match self.primary.get_beacon_at_slot(slot).await {
+            Ok(beacon) => Ok(beacon),
+            Err(error) => {
+                warn!("Primary beacon store unavailable, reading the beacon at slot from the replica"; "slot" => slot, "error" => ?error);
+                self.replica.get_beacon_at_slot(slot).await
+            }
+        }
+    }
+
+    async fn aggregate_by_epoch(&self, from: Epoch, to: Epoch) -> StdResult<Vec<EpochBeaconStats>> {
+        match self.primary.aggregate_by_epoch(from, to).await {
+            Ok(stats) => Ok(stats),
+            Err(error) => {
+                warn!("Primary beacon store unavailable, aggregating the beacons from the replica"; "from" => ?from, "to" => ?to, "error" => ?error);
+                self.replica.aggregate_by_epoch(from, to).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use chrono::Utc;
+
+    use crate::database::repository::{BeaconLogStore, MockBeaconStore};
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    fn beacon(immutable_file_number: u64) -> CardanoDbBeacon {
+        CardanoDbBeacon::new("devnet".to_string(), 1, immutable_file_number)
+    }
+
+    fn beacon_log_store() -> Arc<BeaconLogStore> {
+        Arc::new(BeaconLogStore::new(Arc::new(main_db_connection().unwrap())))
+    }
+
+    /// Primary store that saves the beacons but fails to read them back
+    fn failing_primary() -> MockBeaconStore {
+        let mut primary = MockBeaconStore::new();
+        primary.expect_save_current_beacon().returning(|beacon| {
+            Ok(BeaconLogEntry {
+                sequence: beacon.immutable_file_number,
+                epoch: beacon.epoch,
+                beacon,
+                recorded_at: Utc::now().naive_utc(),
+            })
+        });
+        primary
+            .expect_get_current_beacon()
+            .returning(|| Err(anyhow!("primary store unavailable")));
+
+        primary
+    }
+
+    #[tokio::test]
+    async fn get_current_beacon_reads_from_the_primary() {
+        let primary = beacon_log_store();
+        let replica = beacon_log_store();
+        let store = ReplicatedBeaconStore::new(primary.clone(), replica.clone(), 100).unwrap();
+
+        store.save_current_beacon(beacon(1)).await.unwrap();
+        store.flush_replication().await;
+        replica.save_current_beacon(beacon(2)).await.unwrap();
+
+        assert_eq!(Some(beacon(1)), store.get_current_beacon().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn saved_beacons_are_replicated_in_order() {
+        let replica = beacon_log_store();
+        let store = ReplicatedBeaconStore::new(beacon_log_store(), replica.clone(), 100).unwrap();
+
+        for immutable_file_number in 1..=5 {
+            store
+                .save_current_beacon(beacon(immutable_file_number))
+                .await
+                .unwrap();
+        }
+        store.flush_replication().await;
+
+        assert_eq!(
+            (1..=5).map(beacon).collect::<Vec<_>>(),
+            replica
+                .replication_log_since(0)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.beacon)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(5, store.replication_latency.get_sample_count());
+    }
+
+    #[tokio::test]
+    async fn get_current_beacon_falls_back_to_the_replica_if_the_primary_fails() {
+        let store =
+            ReplicatedBeaconStore::new(Arc::new(failing_primary()), beacon_log_store(), 100)
+                .unwrap();
+
+        store.save_current_beacon(beacon(1)).await.unwrap();
+        store.save_current_beacon(beacon(2)).await.unwrap();
+        store.flush_replication().await;
+
+        assert_eq!(Some(beacon(2)), store.get_current_beacon().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn replication_failure_does_not_fail_the_save() {
+        let mut replica = MockBeaconStore::new();
+        replica
+            .expect_save_current_beacon()
+            .returning(|_| Err(anyhow!("replica store unavailable")));
+        let store = ReplicatedBeaconStore::new(beacon_log_store(), Arc::new(replica), 100).unwrap();
+
+        store.save_current_beacon(beacon(1)).await.unwrap();
+        store.flush_replication().await;
+
+        assert_eq!(0, store.replication_latency.get_sample_count());
+    }
+}
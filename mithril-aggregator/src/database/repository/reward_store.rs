@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+use mithril_common::entities::{Epoch, PartyId, SingleSignatures, Stake};
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{ConnectionExtensions, SqliteConnection};
+
+use crate::database::query::{
+    GetRewardQuery, GetSignerContributionQuery, InsertOrIgnoreSignerContributionQuery,
+    InsertOrReplaceRewardQuery,
+};
+use crate::database::record::{RewardRecord, SignerContributionRecord};
+use crate::entities::{RewardEntry, SignerContribution};
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Store of the contributions of the signers during each epoch and of the rewards computed
+/// from them at the end of the epoch
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait RewardStorer: Sync + Send {
+    /// Record the contribution of a valid single signature sent during the given epoch.
+    ///
+    /// Returns `false` if the same signature was already recorded, it is then not counted again.
+    async fn record_contribution(
+        &self,
+        epoch: Epoch,
+        stake: Stake,
+        single_signature: &SingleSignatures,
+    ) -> StdResult<bool>;
+
+    /// Get the contributions of the signers during the given epoch, ordered by party id.
+    async fn get_contributions(&self, epoch: Epoch) -> StdResult<Vec<SignerContribution>>;
+
+    /// Save the given rewards, replacing the rewards already saved for the same signers and
+    /// epochs.
+    async fn save_rewards(&self, rewards: Vec<RewardEntry>) -> StdResult<()>;
+
+    /// Get the rewards of the given epoch, ordered by party id.
+    async fn get_rewards_by_epoch(&self, epoch: Epoch) -> StdResult<Vec<RewardEntry>>;
+}
+
+/// SQLite implementation of the [RewardStorer], backed by the `rewards` table.
+pub struct RewardStore {
+    connection: Arc<SqliteConnection>,
+}
+
+impl RewardStore {
+    /// Create a new RewardStore
+    pub fn new(connection: Arc<SqliteConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl RewardStorer for RewardStore {
+    async fn record_contribution(
+        &self,
+        epoch: Epoch,
+        stake: Stake,
+        single_signature: &SingleSignatures,
+    ) -> StdResult<bool> {
+        let record = SignerContributionRecord {
+            epoch,
+            party_id: single_signature.party_id.clone(),
+            signature: hex::encode(single_signature.signature.to_bytes()),
+            stake,
+            won_indexes_count: single_signature.won_indexes.len() as u64,
+        };
+        let inserted = self
+            .connection
+            .fetch_first(InsertOrIgnoreSignerContributionQuery::one(record)?)
+            .with_context(|| {
+                format!(
+                    "Could not record the contribution of signer '{}'",
+                    single_signature.party_id
+                )
+            })?;
+
+        Ok(inserted.is_some())
+    }
+
+    async fn get_contributions(&self, epoch: Epoch) -> StdResult<Vec<SignerContribution>> {
+        let records: Vec<SignerContributionRecord> = self
+            .connection
+            .fetch_collect(GetSignerContributionQuery::by_epoch(epoch)?)
+            .with_context(|| format!("Could not get the contributions of epoch '{epoch}'"))?;
+
+        let mut contributions: BTreeMap<PartyId, SignerContribution> = BTreeMap::new();
+        for record in records {
+            let signature_bytes = hex::decode(&record.signature).with_context(|| {
+                format!(
+                    "Could not decode a signature of signer '{}' at epoch '{epoch}'",
+                    record.party_id
+                )
+            })?;
+            let contribution = contributions
+                .entry(record.party_id.clone())
+                .or_insert_with(|| SignerContribution {
+                    party_id: record.party_id,
+                    stake: record.stake,
+                    signatures_count: 0,
+                    won_indexes_count: 0,
+                    signatures_bytes: vec![],
+                });
+            contribution.signatures_count += 1;
+            contribution.won_indexes_count += record.won_indexes_count;
+            contribution.signatures_bytes.extend(signature_bytes);
+        }
+
+        Ok(contributions.into_values().collect())
+    }
+
+    async fn save_rewards(&self, rewards: Vec<RewardEntry>) -> StdResult<()> {
+        if rewards.is_empty() {
+            return Ok(());
+        }
+
+        let records: Vec<RewardRecord> = rewards.into_iter().map(Into::into).collect();
+        let _ = self
+            .connection
+            .fetch_collect::<_, Vec<_>>(InsertOrReplaceRewardQuery::many(records)?)
+            .with_context(|| "Could not save the rewards")?;
+
+        Ok(())
+    }
+
+    async fn get_rewards_by_epoch(&self, epoch: Epoch) -> StdResult<Vec<RewardEntry>> {
+        let records: Vec<RewardRecord> = self
+            .connection
+            .fetch_collect(GetRewardQuery::by_epoch(epoch)?)
+            .with_context(|| format!("Could not get the rewards of epoch '{epoch}'"))?;
+
+        Ok(records.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::{fake_data, fake_keys};
+
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    fn reward(epoch: u64, party_id: &str, reward: u64) -> RewardEntry {
+        RewardEntry {
+            epoch: Epoch(epoch),
+            party_id: party_id.to_string(),
+            reward,
+        }
+    }
+
+    #[tokio::test]
+    async fn get_rewards_by_epoch_returns_only_the_rewards_of_the_epoch() {
+        let store = RewardStore::new(Arc::new(main_db_connection().unwrap()));
+        store
+            .save_rewards(vec![
+                reward(4, "party-1", 10),
+                reward(5, "party-2", 20),
+                reward(5, "party-1", 30),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![reward(5, "party-1", 30), reward(5, "party-2", 20)],
+            store.get_rewards_by_epoch(Epoch(5)).await.unwrap()
+        );
+        assert_eq!(
+            Vec::<RewardEntry>::new(),
+            store.get_rewards_by_epoch(Epoch(6)).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn saving_a_reward_twice_replaces_it() {
+        let store = RewardStore::new(Arc::new(main_db_connection().unwrap()));
+        store
+            .save_rewards(vec![reward(5, "party-1", 10)])
+            .await
+            .unwrap();
+        store
+            .save_rewards(vec![reward(5, "party-1", 15)])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![reward(5, "party-1", 15)],
+            store.get_rewards_by_epoch(Epoch(5)).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn a_contribution_is_counted_only_on_its_first_receipt() {
+        let store = RewardStore::new(Arc::new(main_db_connection().unwrap()));
+        let first_signature = fake_data::single_signatures(vec![1, 4]);
+        let second_signature = SingleSignatures::new(
+            first_signature.party_id.clone(),
+            fake_keys::single_signature()[1].try_into().unwrap(),
+            vec![2],
+        );
+
+        assert!(store
+            .record_contribution(Epoch(5), 100, &first_signature)
+            .await
+            .unwrap());
+        assert!(!store
+            .record_contribution(Epoch(5), 100, &first_signature)
+            .await
+            .unwrap());
+        assert!(store
+            .record_contribution(Epoch(5), 100, &second_signature)
+            .await
+            .unwrap());
+        assert!(store
+            .record_contribution(Epoch(6), 100, &first_signature)
+            .await
+            .unwrap());
+
+        let contributions = store.get_contributions(Epoch(5)).await.unwrap();
+        assert_eq!(1, contributions.len());
+        assert_eq!(2, contributions[0].signatures_count);
+        assert_eq!(3, contributions[0].won_indexes_count);
+        assert_eq!(100, contributions[0].stake);
+        assert_eq!(
+            [
+                first_signature.signature.to_bytes(),
+                second_signature.signature.to_bytes()
+            ]
+            .concat(),
+            contributions[0].signatures_bytes
+        );
+    }
+}
@@ -1,16 +1,24 @@
 use std::sync::Arc;
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 
-use mithril_common::entities::SignedEntityTypeDiscriminants;
+use mithril_common::entities::{
+    Epoch, ImmutableFileNumber, SignedEntityType, SignedEntityTypeDiscriminants, Snapshot,
+};
 use mithril_common::StdResult;
 use mithril_persistence::sqlite::{ConnectionExtensions, SqliteConnection};
+use sqlite::Value;
 
 use crate::database::query::{
     GetSignedEntityRecordQuery, InsertSignedEntityRecordQuery, UpdateSignedEntityQuery,
 };
 use crate::database::record::SignedEntityRecord;
+use crate::store::{ColdArchiveHandle, RestoreStatus, SnapshotStorageClass};
+use crate::tools::{
+    ArchiveRangeReader, ColdStorageLifecycle, SnapshotArchiveReader, COLD_STORAGE_CLASS,
+};
+use crate::SnapshotStore;
 
 /// Signed entity storer trait
 #[cfg_attr(test, mockall::automock)]
@@ -44,6 +52,13 @@ pub trait SignedEntityStorer: Sync + Send {
         total: usize,
     ) -> StdResult<Vec<SignedEntityRecord>>;
 
+    /// Get the signed snapshots which immutable file number is in the given inclusive range
+    async fn get_snapshots_by_immutable_file_number_range(
+        &self,
+        from: ImmutableFileNumber,
+        to: ImmutableFileNumber,
+    ) -> StdResult<Vec<SignedEntityRecord>>;
+
     /// Perform an update for all the given signed entities.
     async fn update_signed_entities(
         &self,
@@ -54,12 +69,85 @@ pub trait SignedEntityStorer: Sync + Send {
 /// Service to deal with signed_entity (read & write).
 pub struct SignedEntityStore {
     connection: Arc<SqliteConnection>,
+    cold_storage: Option<Arc<dyn ColdStorageLifecycle>>,
+    archive_reader: Option<Arc<dyn SnapshotArchiveReader>>,
 }
 
 impl SignedEntityStore {
     /// Create a new SignedEntityStoreAdapter service
     pub fn new(connection: Arc<SqliteConnection>) -> Self {
-        Self { connection }
+        Self {
+            connection,
+            cold_storage: None,
+            archive_reader: None,
+        }
+    }
+
+    /// Move the snapshot archives to the cold storage with the given lifecycle API.
+    ///
+    /// Without it the snapshot archives can't be moved to the cold storage.
+    pub fn with_cold_storage(mut self, cold_storage: Arc<dyn ColdStorageLifecycle>) -> Self {
+        self.cold_storage = Some(cold_storage);
+        self
+    }
+
+    /// Read the snapshot archives by ranges of bytes with the given reader.
+    ///
+    /// Without it the snapshot archives can't be read by ranges of bytes.
+    pub fn with_archive_reader(mut self, archive_reader: Arc<dyn SnapshotArchiveReader>) -> Self {
+        self.archive_reader = Some(archive_reader);
+        self
+    }
+
+    fn get_cold_storage(&self) -> StdResult<&Arc<dyn ColdStorageLifecycle>> {
+        self.cold_storage
+            .as_ref()
+            .ok_or_else(|| anyhow!("No cold storage configured for the snapshot archives"))
+    }
+
+    /// Return the record of the snapshot with the given digest with its artifact, the storage
+    /// class of its archive is kept in the `storage_class` field of the artifact.
+    fn get_snapshot_record(
+        &self,
+        digest: &str,
+    ) -> StdResult<Option<(SignedEntityRecord, Snapshot, SnapshotStorageClass)>> {
+        let Some(record) = self
+            .connection
+            .fetch_first(GetSignedEntityRecordQuery::by_signed_entity_id(digest))?
+        else {
+            return Ok(None);
+        };
+        if !matches!(
+            record.signed_entity_type,
+            SignedEntityType::CardanoImmutableFilesFull(_)
+        ) {
+            return Ok(None);
+        }
+
+        let artifact: serde_json::Value = serde_json::from_str(&record.artifact)
+            .with_context(|| format!("Can not parse the artifact of snapshot '{digest}'"))?;
+        let storage_class = artifact
+            .get("storage_class")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()
+            .with_context(|| format!("Invalid storage class for snapshot '{digest}'"))?
+            .unwrap_or_default();
+        let snapshot = serde_json::from_value(artifact)
+            .with_context(|| format!("Can not parse the artifact of snapshot '{digest}'"))?;
+
+        Ok(Some((record, snapshot, storage_class)))
+    }
+
+    /// The archive object is named after the last segment of the snapshot location.
+    fn archive_object_key(snapshot: &Snapshot) -> StdResult<String> {
+        snapshot
+            .locations
+            .first()
+            .and_then(|location| location.rsplit('/').next())
+            .filter(|key| !key.is_empty())
+            .map(|key| key.to_string())
+            .ok_or_else(|| anyhow!("Snapshot '{}' has no archive location", snapshot.digest))
     }
 }
 
@@ -127,6 +215,22 @@ impl SignedEntityStorer for SignedEntityStore {
         Ok(signed_entities)
     }
 
+    async fn get_snapshots_by_immutable_file_number_range(
+        &self,
+        from: ImmutableFileNumber,
+        to: ImmutableFileNumber,
+    ) -> StdResult<Vec<SignedEntityRecord>> {
+        self.connection
+            .fetch_collect(
+                GetSignedEntityRecordQuery::cardano_immutable_files_full_by_immutable_file_number_range(
+                    from, to,
+                ),
+            )
+            .with_context(|| {
+                format!("get snapshots by immutable file number range failure, range: {from}..={to}")
+            })
+    }
+
     async fn update_signed_entities(
         &self,
         signed_entities: Vec<SignedEntityRecord>,
@@ -148,11 +252,148 @@ impl SignedEntityStorer for SignedEntityStore {
     }
 }
 
+#[async_trait]
+impl SnapshotStore for SignedEntityStore {
+    async fn total_stored_bytes(&self) -> StdResult<u64> {
+        let total_bytes = self
+            .connection
+            .query_single_cell::<_, i64>(
+                "select coalesce(sum(json_extract(artifact, '$.size')), 0) from signed_entity where signed_entity_type_id = ?",
+                &[Value::Integer(
+                    SignedEntityTypeDiscriminants::CardanoImmutableFilesFull.index() as i64,
+                )],
+            )
+            .with_context(|| "get total size of the stored snapshots failure")?;
+
+        Ok(total_bytes.try_into()?)
+    }
+
+    async fn find_by_digest(&self, digest: &str) -> StdResult<Option<Snapshot>> {
+        Ok(self
+            .get_snapshot_record(digest)?
+            .map(|(_, snapshot, _)| snapshot))
+    }
+
+    fn has_cold_storage(&self) -> bool {
+        self.cold_storage.is_some()
+    }
+
+    async fn get_storage_class(&self, digest: &str) -> StdResult<Option<SnapshotStorageClass>> {
+        Ok(self
+            .get_snapshot_record(digest)?
+            .map(|(_, _, storage_class)| storage_class))
+    }
+
+    async fn archive_to_cold(&self, digest: &str) -> StdResult<ColdArchiveHandle> {
+        let cold_storage = self.get_cold_storage()?;
+        let (mut record, snapshot, storage_class) = self
+            .get_snapshot_record(digest)?
+            .ok_or_else(|| anyhow!("No snapshot found with digest '{digest}'"))?;
+        let object_key = Self::archive_object_key(&snapshot)?;
+
+        if storage_class != SnapshotStorageClass::ColdStorage {
+            cold_storage
+                .transition_object(&object_key, COLD_STORAGE_CLASS)
+                .await
+                .with_context(|| {
+                    format!("Can not move the archive of snapshot '{digest}' to the cold storage")
+                })?;
+
+            let mut artifact = serde_json::to_value(&snapshot)?;
+            artifact["storage_class"] = serde_json::to_value(SnapshotStorageClass::ColdStorage)?;
+            record.artifact = serde_json::to_string(&artifact)?;
+            self.connection
+                .fetch_first(UpdateSignedEntityQuery::one(record)?)
+                .with_context(|| {
+                    format!("Can not mark snapshot '{digest}' as moved to the cold storage")
+                })?;
+        }
+
+        Ok(ColdArchiveHandle {
+            digest: digest.to_string(),
+            object_key,
+            storage_class: COLD_STORAGE_CLASS.to_string(),
+        })
+    }
+
+    async fn archive_to_cold_before_epoch(
+        &self,
+        epoch: Epoch,
+    ) -> StdResult<Vec<ColdArchiveHandle>> {
+        let records: Vec<SignedEntityRecord> = self
+            .connection
+            .fetch_collect(
+                GetSignedEntityRecordQuery::cardano_immutable_files_full_not_in_cold_storage_before_epoch(
+                    epoch,
+                ),
+            )
+            .with_context(|| format!("get snapshots to archive failure, before epoch: {epoch}"))?;
+
+        let mut handles = vec![];
+        for record in records {
+            handles.push(self.archive_to_cold(&record.signed_entity_id).await?);
+        }
+
+        Ok(handles)
+    }
+
+    async fn restore_from_cold(&self, digest: &str, restore_days: u8) -> StdResult<RestoreStatus> {
+        let (_, snapshot, storage_class) = self
+            .get_snapshot_record(digest)?
+            .ok_or_else(|| anyhow!("No snapshot found with digest '{digest}'"))?;
+        if storage_class != SnapshotStorageClass::ColdStorage {
+            return Ok(RestoreStatus::Available);
+        }
+
+        self.get_cold_storage()?
+            .restore_object(&Self::archive_object_key(&snapshot)?, restore_days)
+            .await
+            .with_context(|| {
+                format!("Can not restore the archive of snapshot '{digest}' from the cold storage")
+            })
+    }
+
+    async fn get_byte_range(
+        &self,
+        digest: &str,
+        start: u64,
+        end: u64,
+    ) -> StdResult<ArchiveRangeReader> {
+        let archive_reader = self.archive_reader.as_ref().ok_or_else(|| {
+            anyhow!("No archive reader configured to read the snapshot archives by ranges")
+        })?;
+        let (_, snapshot, _) = self
+            .get_snapshot_record(digest)?
+            .ok_or_else(|| anyhow!("No snapshot found with digest '{digest}'"))?;
+        if start > end || end >= snapshot.size {
+            return Err(anyhow!(
+                "Invalid range {start}-{end} for the archive of snapshot '{digest}' of {} bytes",
+                snapshot.size
+            ));
+        }
+
+        archive_reader
+            .read_range(&Self::archive_object_key(&snapshot)?, start, end)
+            .await
+            .with_context(|| {
+                format!(
+                    "Can not read the range {start}-{end} of the archive of snapshot '{digest}'"
+                )
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use mithril_common::entities::{MithrilStakeDistribution, SignedEntity, Snapshot};
+    use chrono::Utc;
+    use mithril_common::entities::{CardanoDbBeacon, MithrilStakeDistribution, SignedEntity};
+    use mithril_common::test_utils::{fake_data, TempDir};
+    use tokio::io::AsyncReadExt;
 
     use crate::database::test_helper::{insert_signed_entities, main_db_connection};
+    use crate::tools::{
+        LocalSnapshotArchiveReader, MockColdStorageLifecycle, MockSnapshotArchiveReader,
+    };
 
     use super::*;
 
@@ -235,6 +476,268 @@ mod tests {
         assert_eq!(mithril_stake_distributions.len(), 1);
     }
 
+    #[tokio::test]
+    async fn total_stored_bytes_sums_the_size_of_the_snapshots_only() {
+        let connection = main_db_connection().unwrap();
+        insert_golden_signed_entities(&connection);
+        let records = SignedEntityRecord::fake_records(3);
+        insert_signed_entities(&connection, records.clone()).unwrap();
+        let store = SignedEntityStore::new(Arc::new(connection));
+
+        let golden_snapshot_size = 1_689_696_245;
+        let expected_bytes: u64 = records
+            .into_iter()
+            .map(|record| Snapshot::from(record).size)
+            .sum::<u64>()
+            + golden_snapshot_size;
+
+        assert_eq!(expected_bytes, store.total_stored_bytes().await.unwrap());
+    }
+
+    fn insert_snapshot_with_location(connection: &SqliteConnection, location: &str) -> Snapshot {
+        let snapshot = Snapshot {
+            locations: vec![location.to_string()],
+            ..fake_data::snapshots(1).remove(0)
+        };
+        insert_signed_entities(
+            connection,
+            vec![SignedEntityRecord::from_snapshot(
+                snapshot.clone(),
+                "certificate-1".to_string(),
+                Utc::now(),
+            )],
+        )
+        .unwrap();
+
+        snapshot
+    }
+
+    #[tokio::test]
+    async fn find_by_digest_returns_only_stored_snapshots() {
+        let connection = main_db_connection().unwrap();
+        let snapshot = insert_snapshot_with_location(&connection, "http://host/archive.tar.gz");
+        let msd_record = SignedEntityRecord {
+            signed_entity_id: "msd-id".to_string(),
+            signed_entity_type: SignedEntityType::MithrilStakeDistribution(Epoch(4)),
+            ..SignedEntityRecord::fake_records(1).remove(0)
+        };
+        insert_signed_entities(&connection, vec![msd_record]).unwrap();
+        let store = SignedEntityStore::new(Arc::new(connection));
+
+        assert_eq!(
+            Some(snapshot.clone()),
+            store.find_by_digest(&snapshot.digest).await.unwrap()
+        );
+        assert_eq!(None, store.find_by_digest("msd-id").await.unwrap());
+        assert_eq!(None, store.find_by_digest("unknown").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn archive_to_cold_transitions_the_archive_and_marks_the_snapshot() {
+        let connection = main_db_connection().unwrap();
+        let snapshot = insert_snapshot_with_location(
+            &connection,
+            "https://storage.googleapis.com/bucket/archive.tar.gz",
+        );
+        let mut cold_storage = MockColdStorageLifecycle::new();
+        cold_storage
+            .expect_transition_object()
+            .withf(|object_key, storage_class| {
+                object_key == "archive.tar.gz" && storage_class == "GLACIER"
+            })
+            .once()
+            .returning(|_, _| Ok(()));
+        let store =
+            SignedEntityStore::new(Arc::new(connection)).with_cold_storage(Arc::new(cold_storage));
+
+        let handle = store.archive_to_cold(&snapshot.digest).await.unwrap();
+        // Archiving a snapshot already in the cold storage doesn't transition it again
+        store.archive_to_cold(&snapshot.digest).await.unwrap();
+
+        assert_eq!(
+            ColdArchiveHandle {
+                digest: snapshot.digest.clone(),
+                object_key: "archive.tar.gz".to_string(),
+                storage_class: "GLACIER".to_string(),
+            },
+            handle
+        );
+        assert_eq!(
+            Some(SnapshotStorageClass::ColdStorage),
+            store.get_storage_class(&snapshot.digest).await.unwrap()
+        );
+        let record = store
+            .get_signed_entity(&snapshot.digest)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot, Snapshot::from(record));
+    }
+
+    #[tokio::test]
+    async fn archive_to_cold_before_epoch_only_moves_the_older_snapshots() {
+        let connection = main_db_connection().unwrap();
+        let snapshots: Vec<Snapshot> = fake_data::snapshots(2)
+            .into_iter()
+            .zip([4, 5])
+            .map(|(snapshot, epoch)| Snapshot {
+                beacon: CardanoDbBeacon::new("devnet", epoch, 10),
+                locations: vec![format!("https://host/archive-{epoch}.tar.gz")],
+                ..snapshot
+            })
+            .collect();
+        insert_signed_entities(
+            &connection,
+            snapshots
+                .iter()
+                .map(|snapshot| {
+                    SignedEntityRecord::from_snapshot(
+                        snapshot.clone(),
+                        "certificate-1".to_string(),
+                        Utc::now(),
+                    )
+                })
+                .collect(),
+        )
+        .unwrap();
+        let mut cold_storage = MockColdStorageLifecycle::new();
+        cold_storage
+            .expect_transition_object()
+            .withf(|object_key, _| object_key == "archive-4.tar.gz")
+            .once()
+            .returning(|_, _| Ok(()));
+        let store =
+            SignedEntityStore::new(Arc::new(connection)).with_cold_storage(Arc::new(cold_storage));
+
+        let handles = store.archive_to_cold_before_epoch(Epoch(5)).await.unwrap();
+        // The snapshots already in the cold storage are not moved again
+        let handles_of_second_run = store.archive_to_cold_before_epoch(Epoch(5)).await.unwrap();
+
+        assert_eq!(
+            vec![snapshots[0].digest.clone()],
+            handles
+                .into_iter()
+                .map(|handle| handle.digest)
+                .collect::<Vec<_>>()
+        );
+        assert!(handles_of_second_run.is_empty());
+        assert_eq!(
+            Some(SnapshotStorageClass::Standard),
+            store.get_storage_class(&snapshots[1].digest).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn archive_to_cold_without_cold_storage_fails() {
+        let connection = main_db_connection().unwrap();
+        let snapshot = insert_snapshot_with_location(&connection, "https://host/archive.tar.gz");
+        let store = SignedEntityStore::new(Arc::new(connection));
+
+        store
+            .archive_to_cold(&snapshot.digest)
+            .await
+            .expect_err("Archiving without cold storage should fail");
+        assert_eq!(
+            Some(SnapshotStorageClass::Standard),
+            store.get_storage_class(&snapshot.digest).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn archive_downloaded_by_ranges_matches_the_full_archive() {
+        let directory = TempDir::create("signed_entity_store", "archive_downloaded_by_ranges");
+        let archive: Vec<u8> = (0..1_000_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(directory.join("archive.tar.gz"), &archive).unwrap();
+        let connection = main_db_connection().unwrap();
+        let snapshot = Snapshot {
+            locations: vec!["https://host/archive.tar.gz".to_string()],
+            size: archive.len() as u64,
+            ..fake_data::snapshots(1).remove(0)
+        };
+        insert_signed_entities(
+            &connection,
+            vec![SignedEntityRecord::from_snapshot(
+                snapshot.clone(),
+                "certificate-1".to_string(),
+                Utc::now(),
+            )],
+        )
+        .unwrap();
+        let store = SignedEntityStore::new(Arc::new(connection))
+            .with_archive_reader(Arc::new(LocalSnapshotArchiveReader::new(directory)));
+
+        let mut downloaded = vec![];
+        for (start, end) in [(0, 333_332), (333_333, 666_665), (666_666, 999_999)] {
+            let mut chunk = vec![];
+            store
+                .get_byte_range(&snapshot.digest, start, end)
+                .await
+                .unwrap()
+                .read_to_end(&mut chunk)
+                .await
+                .unwrap();
+            assert_eq!(end - start + 1, chunk.len() as u64);
+            downloaded.extend(chunk);
+        }
+
+        assert_eq!(archive, downloaded);
+    }
+
+    #[tokio::test]
+    async fn get_byte_range_outside_of_the_archive_fails() {
+        let connection = main_db_connection().unwrap();
+        let snapshot = insert_snapshot_with_location(&connection, "https://host/archive.tar.gz");
+        let mut archive_reader = MockSnapshotArchiveReader::new();
+        archive_reader.expect_read_range().never();
+        let store = SignedEntityStore::new(Arc::new(connection))
+            .with_archive_reader(Arc::new(archive_reader));
+
+        store
+            .get_byte_range(&snapshot.digest, 0, snapshot.size)
+            .await
+            .err()
+            .expect("Reading past the end of the archive should fail");
+        store
+            .get_byte_range(&snapshot.digest, 10, 5)
+            .await
+            .err()
+            .expect("Reading a range ending before its start should fail");
+    }
+
+    #[tokio::test]
+    async fn restore_from_cold_a_snapshot_not_archived_is_available() {
+        let connection = main_db_connection().unwrap();
+        let snapshot = insert_snapshot_with_location(&connection, "https://host/archive.tar.gz");
+        let store = SignedEntityStore::new(Arc::new(connection))
+            .with_cold_storage(Arc::new(MockColdStorageLifecycle::new()));
+
+        let status = store.restore_from_cold(&snapshot.digest, 3).await.unwrap();
+
+        assert_eq!(RestoreStatus::Available, status);
+    }
+
+    #[tokio::test]
+    async fn restore_from_cold_requests_the_restoration_of_an_archived_snapshot() {
+        let connection = main_db_connection().unwrap();
+        let snapshot = insert_snapshot_with_location(&connection, "https://host/archive.tar.gz");
+        let mut cold_storage = MockColdStorageLifecycle::new();
+        cold_storage
+            .expect_transition_object()
+            .returning(|_, _| Ok(()));
+        cold_storage
+            .expect_restore_object()
+            .withf(|object_key, restore_days| object_key == "archive.tar.gz" && *restore_days == 3)
+            .once()
+            .returning(|_, _| Ok(RestoreStatus::Pending));
+        let store =
+            SignedEntityStore::new(Arc::new(connection)).with_cold_storage(Arc::new(cold_storage));
+        store.archive_to_cold(&snapshot.digest).await.unwrap();
+
+        let status = store.restore_from_cold(&snapshot.digest, 3).await.unwrap();
+
+        assert_eq!(RestoreStatus::Pending, status);
+    }
+
     #[tokio::test]
     async fn test_get_signed_entity_record_by_certificate_id() {
         let expected_record = SignedEntityRecord::fake_records(1).remove(0);
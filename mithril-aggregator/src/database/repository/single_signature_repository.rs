@@ -38,4 +38,23 @@ impl SingleSignatureRepository {
 
         Ok(record)
     }
+    /// Create the given Single Signatures in database, either all of them are created or none
+    pub async fn create_single_signatures(
+        &self,
+        single_signatures: &[SingleSignatures],
+        open_message: &OpenMessageRecord,
+    ) -> StdResult<Vec<SingleSignatureRecord>> {
+        // Dropping the transaction without committing it rolls back the created signatures
+        let transaction = self.connection.begin_transaction()?;
+        let mut records = Vec::with_capacity(single_signatures.len());
+        for single_signature in single_signatures {
+            records.push(
+                self.create_single_signature(single_signature, open_message)
+                    .await?,
+            );
+        }
+        transaction.commit()?;
+
+        Ok(records)
+    }
 }
@@ -1,4 +1,5 @@
 use anyhow::Context;
+use prometheus::Registry;
 use semver::Version;
 use slog::Logger;
 use std::sync::Arc;
@@ -22,10 +23,13 @@ use mithril_common::{
     },
     digesters::{
         cache::{ImmutableFileDigestCacheProvider, JsonImmutableFileDigestCacheProviderBuilder},
-        CardanoImmutableDigester, DumbImmutableFileObserver, ImmutableDigester,
+        CachingImmutableFileListingProvider, CardanoImmutableDigester,
+        DirectoryImmutableFileListingProvider, DumbImmutableFileObserver, ImmutableDigester,
         ImmutableFileObserver, ImmutableFileSystemObserver,
     },
-    entities::{CertificatePending, CompressionAlgorithm, Epoch, SignedEntityConfig},
+    entities::{
+        CertificatePending, CompressionAlgorithm, Epoch, ProtocolParameters, SignedEntityConfig,
+    },
     era::{
         adapters::{EraReaderAdapterBuilder, EraReaderDummyAdapter},
         EraChecker, EraMarker, EraReader, EraReaderAdapter, SupportedEra,
@@ -51,24 +55,33 @@ use crate::{
     },
     configuration::ExecutionEnvironment,
     database::repository::{
-        CertificateRepository, EpochSettingStore, OpenMessageRepository, SignedEntityStore,
-        SignedEntityStorer, SignerRegistrationStore, SignerStore, SingleSignatureRepository,
-        StakePoolStore,
+        BeaconLogStore, BeaconStore, CertificateRepository, EpochSettingStore, LruCertificateCache,
+        OpenMessageRepository, RewardStore, RewardStorer, SignedEntityStore, SignedEntityStorer,
+        SignerRegistrationStore, SignerStore, SingleSignatureRepository, StakePoolStore,
     },
     event_store::{EventMessage, EventStore, TransmitterService},
-    http_server::routes::router,
+    http_server::{routes::router, IpAllowlist},
+    reward_per_signature_policy,
     services::{
-        CardanoTransactionsImporter, CertifierService, MessageService, MithrilCertifierService,
-        MithrilEpochService, MithrilMessageService, MithrilProverService,
+        BatchingTransactionStore, CardanoTransactionsImporter, CertifierService, MessageService,
+        MithrilCertifierService, MithrilEpochService, MithrilMessageService, MithrilProverService,
         MithrilSignedEntityService, MithrilStakeDistributionService, ProverService,
         SignedEntityService, StakeDistributionService,
     },
-    tools::{CExplorerSignerRetriever, GcpFileUploader, GenesisToolsDependency, SignersImporter},
-    AggregatorConfig, AggregatorRunner, AggregatorRuntime, CertificatePendingStore,
-    CompressedArchiveSnapshotter, Configuration, DependencyContainer, DumbSnapshotUploader,
-    DumbSnapshotter, LocalSnapshotUploader, MithrilSignerRegisterer, MultiSigner, MultiSignerImpl,
-    ProtocolParametersStorer, RemoteSnapshotUploader, SnapshotUploader, SnapshotUploaderType,
-    Snapshotter, SnapshotterCompressionAlgorithm, VerificationKeyStorer,
+    snapshot_uploaders::S3_MULTIPART_MIN_CHUNK_SIZE_MB,
+    tools::{
+        CExplorerSignerRetriever, GcpArchiveUrlPresigner, GcpFileUploader, GenesisToolsDependency,
+        LocalSnapshotArchiveReader, RemoteSnapshotArchiveReader, S3Client, S3ColdStorageLifecycle,
+        S3FileUploader, SignersImporter,
+    },
+    AggregatorConfig, AggregatorRunner, AggregatorRuntime, CertificateArchiveStore,
+    CertificatePendingStore, CompressedArchiveSnapshotter, Configuration, DependencyContainer,
+    DumbSnapshotUploader, DumbSnapshotter, EpochEventLog, FileCertificateArchiveStore,
+    IpfsSnapshotUploader, LocalSnapshotUploader, MithrilSignerRegisterer, MultiSigner,
+    MultiSignerImpl, PendingOperationsLimiter, ProtocolParametersStorer, RemoteSnapshotUploader,
+    SnapshotStore, SnapshotUploader, SnapshotUploaderType, Snapshotter,
+    SnapshotterCompressionAlgorithm, TieredSnapshotStore, TimePointTracker, UploadSession,
+    UploadSessionStore, VerificationKeyStorer,
 };
 
 use super::{DependenciesBuilderError, EpochServiceWrapper, Result};
@@ -107,6 +120,9 @@ pub struct DependenciesBuilder {
     /// Snapshot uploader service.
     pub snapshot_uploader: Option<Arc<dyn SnapshotUploader>>,
 
+    /// Tiered snapshot store, set if the snapshots are moved to a cold storage.
+    pub tiered_snapshot_store: Option<Arc<TieredSnapshotStore>>,
+
     /// Multisigner service.
     pub multi_signer: Option<Arc<RwLock<dyn MultiSigner>>>,
 
@@ -134,6 +150,9 @@ pub struct DependenciesBuilder {
     /// Cardano transactions repository.
     pub transaction_repository: Option<Arc<CardanoTransactionRepository>>,
 
+    /// Cardano transactions store, batching the writes to the transactions repository.
+    pub transaction_store: Option<Arc<BatchingTransactionStore>>,
+
     /// Cardano block scanner.
     pub block_scanner: Option<Arc<dyn BlockScanner>>,
 
@@ -206,6 +225,9 @@ pub struct DependenciesBuilder {
     /// Signed Entity storer
     pub signed_entity_storer: Option<Arc<dyn SignedEntityStorer>>,
 
+    /// Snapshot store
+    pub snapshot_store: Option<Arc<dyn SnapshotStore>>,
+
     /// HTTP Message service
     pub message_service: Option<Arc<dyn MessageService>>,
 
@@ -215,6 +237,27 @@ pub struct DependenciesBuilder {
     /// Signed Entity Type Lock
     pub signed_entity_type_lock: Option<Arc<SignedEntityTypeLock>>,
 
+    /// Epoch event log
+    pub epoch_event_log: Option<EpochEventLog>,
+
+    /// Pending operations limiter
+    pub pending_operations_limiter: Option<Arc<PendingOperationsLimiter>>,
+
+    /// Registry of the metrics of the aggregator
+    pub metrics_registry: Option<Registry>,
+
+    /// Time point tracker
+    pub time_point_tracker: Option<TimePointTracker>,
+
+    /// Beacon store
+    pub beacon_store: Option<Arc<dyn BeaconStore>>,
+
+    /// Reward store
+    pub reward_store: Option<Arc<dyn RewardStorer>>,
+
+    /// Admin routes IP allowlist
+    pub admin_ip_allowlist: Option<Arc<IpAllowlist>>,
+
     /// Transactions Importer
     pub transactions_importer: Option<Arc<dyn TransactionsImporter>>,
 }
@@ -229,6 +272,7 @@ impl DependenciesBuilder {
             sqlite_connection_cardano_transaction_pool: None,
             stake_store: None,
             snapshot_uploader: None,
+            tiered_snapshot_store: None,
             multi_signer: None,
             certificate_pending_store: None,
             certificate_repository: None,
@@ -239,6 +283,7 @@ impl DependenciesBuilder {
             chain_observer: None,
             block_scanner: None,
             transaction_repository: None,
+            transaction_store: None,
             immutable_digester: None,
             immutable_file_observer: None,
             immutable_cache_provider: None,
@@ -261,9 +306,17 @@ impl DependenciesBuilder {
             certifier_service: None,
             epoch_service: None,
             signed_entity_storer: None,
+            snapshot_store: None,
             message_service: None,
             prover_service: None,
             signed_entity_type_lock: None,
+            epoch_event_log: None,
+            pending_operations_limiter: None,
+            metrics_registry: None,
+            time_point_tracker: None,
+            beacon_store: None,
+            reward_store: None,
+            admin_ip_allowlist: None,
             transactions_importer: None,
         }
     }
@@ -398,32 +451,83 @@ impl DependenciesBuilder {
     }
 
     async fn build_snapshot_uploader(&mut self) -> Result<Arc<dyn SnapshotUploader>> {
+        if let Some(tiered_snapshot_store) = self.get_tiered_snapshot_store().await? {
+            return Ok(tiered_snapshot_store);
+        }
+
         if self.configuration.environment == ExecutionEnvironment::Production {
-            match self.configuration.snapshot_uploader_type {
-                SnapshotUploaderType::Gcp => {
-                    let bucket = self
-                        .configuration
-                        .snapshot_bucket_name
-                        .to_owned()
-                        .ok_or_else(|| {
-                            DependenciesBuilderError::MissingConfiguration(
-                                "snapshot_bucket_name".to_string(),
-                            )
-                        })?;
+            self.build_snapshot_uploader_of_type(self.configuration.snapshot_uploader_type)
+                .await
+        } else {
+            Ok(Arc::new(DumbSnapshotUploader::new()))
+        }
+    }
 
-                    Ok(Arc::new(RemoteSnapshotUploader::new(
+    async fn build_snapshot_uploader_of_type(
+        &mut self,
+        uploader_type: SnapshotUploaderType,
+    ) -> Result<Arc<dyn SnapshotUploader>> {
+        match uploader_type {
+            SnapshotUploaderType::Gcp => {
+                let bucket = self
+                    .configuration
+                    .snapshot_bucket_name
+                    .to_owned()
+                    .ok_or_else(|| {
+                        DependenciesBuilderError::MissingConfiguration(
+                            "snapshot_bucket_name".to_string(),
+                        )
+                    })?;
+
+                Ok(Arc::new(
+                    RemoteSnapshotUploader::new(
                         Box::new(GcpFileUploader::new(bucket.clone())),
                         bucket,
                         self.configuration.snapshot_use_cdn_domain,
-                    )))
+                    )
+                    .with_upload_session_store(self.build_upload_session_store().await?),
+                ))
+            }
+            SnapshotUploaderType::Local => Ok(Arc::new(LocalSnapshotUploader::new(
+                self.configuration.get_server_url(),
+                &self.configuration.snapshot_directory,
+            ))),
+            SnapshotUploaderType::S3 => {
+                let s3_client = self.build_s3_client()?;
+                let snapshot_uploader = RemoteSnapshotUploader::new(
+                    Box::new(S3FileUploader::new(s3_client.clone())),
+                    s3_client.bucket().to_string(),
+                    false,
+                )
+                .with_location_base_url(s3_client.bucket_url());
+
+                match self.configuration.snapshot_s3_multipart_chunk_size_mb {
+                    Some(chunk_size_mb) if chunk_size_mb < S3_MULTIPART_MIN_CHUNK_SIZE_MB => {
+                        Err(DependenciesBuilderError::Initialization {
+                            message: format!(
+                                "The chunks of the S3 multipart uploads must be at least {S3_MULTIPART_MIN_CHUNK_SIZE_MB} MB, got {chunk_size_mb} MB."
+                            ),
+                            error: None,
+                        })
+                    }
+                    Some(chunk_size_mb) => Ok(Arc::new(snapshot_uploader.parallel(
+                        chunk_size_mb,
+                        self.configuration
+                            .snapshot_s3_multipart_max_parallel_chunks,
+                    ))),
+                    None => Ok(Arc::new(snapshot_uploader)),
                 }
-                SnapshotUploaderType::Local => Ok(Arc::new(LocalSnapshotUploader::new(
-                    self.configuration.get_server_url(),
-                    &self.configuration.snapshot_directory,
-                ))),
             }
-        } else {
-            Ok(Arc::new(DumbSnapshotUploader::new()))
+            SnapshotUploaderType::Ipfs => {
+                let api_url = self.configuration.ipfs_api_url.to_owned().ok_or_else(|| {
+                    DependenciesBuilderError::MissingConfiguration("ipfs_api_url".to_string())
+                })?;
+
+                Ok(Arc::new(IpfsSnapshotUploader::new(
+                    api_url,
+                    self.configuration.ipfs_pinning_service.clone(),
+                )))
+            }
         }
     }
 
@@ -436,8 +540,86 @@ impl DependenciesBuilder {
         Ok(self.snapshot_uploader.as_ref().cloned().unwrap())
     }
 
+    fn build_s3_client(&self) -> Result<Arc<S3Client>> {
+        let bucket = self
+            .configuration
+            .snapshot_bucket_name
+            .to_owned()
+            .ok_or_else(|| {
+                DependenciesBuilderError::MissingConfiguration("snapshot_bucket_name".to_string())
+            })?;
+        let region = self
+            .configuration
+            .snapshot_s3_region
+            .to_owned()
+            .ok_or_else(|| {
+                DependenciesBuilderError::MissingConfiguration("snapshot_s3_region".to_string())
+            })?;
+
+        Ok(Arc::new(S3Client::new(
+            bucket,
+            region,
+            self.configuration.snapshot_s3_endpoint.clone(),
+        )))
+    }
+
+    async fn build_tiered_snapshot_store(&mut self) -> Result<Option<Arc<TieredSnapshotStore>>> {
+        let cold_uploader_type = match self.configuration.snapshot_cold_storage_uploader_type {
+            Some(uploader_type)
+                if self.configuration.environment == ExecutionEnvironment::Production =>
+            {
+                uploader_type
+            }
+            _ => return Ok(None),
+        };
+        if self.configuration.snapshot_uploader_type != SnapshotUploaderType::Local {
+            return Err(DependenciesBuilderError::Initialization {
+                message: "The snapshot cold storage requires a 'local' snapshot_uploader_type."
+                    .to_string(),
+                error: None,
+            });
+        }
+
+        let hot = self
+            .build_snapshot_uploader_of_type(SnapshotUploaderType::Local)
+            .await?;
+        let cold = self
+            .build_snapshot_uploader_of_type(cold_uploader_type)
+            .await?;
+
+        let signed_entity_storer = self.get_signed_entity_storer().await?;
+
+        Ok(Some(Arc::new(TieredSnapshotStore::new(
+            hot,
+            cold,
+            &self.configuration.snapshot_directory,
+            signed_entity_storer,
+            self.configuration.snapshot_cold_storage_age_threshold,
+        ))))
+    }
+
+    /// Get the [TieredSnapshotStore] if the snapshots are moved to a cold storage
+    pub async fn get_tiered_snapshot_store(&mut self) -> Result<Option<Arc<TieredSnapshotStore>>> {
+        if self.tiered_snapshot_store.is_none() {
+            self.tiered_snapshot_store = self.build_tiered_snapshot_store().await?;
+        }
+
+        Ok(self.tiered_snapshot_store.clone())
+    }
+
     async fn build_multi_signer(&mut self) -> Result<Arc<RwLock<dyn MultiSigner>>> {
-        let multi_signer = MultiSignerImpl::new(self.get_epoch_service().await?);
+        let mut multi_signer = MultiSignerImpl::new(self.get_epoch_service().await?)
+            .with_event_log(self.get_epoch_event_log().await?)
+            .with_reward_store(self.get_reward_store().await?)
+            .with_verification_key_store(self.get_verification_key_store().await?);
+        if self.configuration.reward_per_signature > 0 {
+            multi_signer = multi_signer.with_reward_policy(Arc::new(reward_per_signature_policy(
+                self.configuration.reward_per_signature,
+            )));
+        }
+        if let Some(honest_majority) = self.configuration.bft_honest_majority {
+            multi_signer = multi_signer.with_bft_quorum(honest_majority);
+        }
 
         Ok(Arc::new(RwLock::new(multi_signer)))
     }
@@ -479,7 +661,39 @@ impl DependenciesBuilder {
             }
         };
 
-        Ok(Arc::new(CertificatePendingStore::new(adapter)))
+        Ok(Arc::new(
+            CertificatePendingStore::new(adapter)
+                .with_saga_connection(self.get_sqlite_connection().await?),
+        ))
+    }
+
+    async fn build_upload_session_store(&mut self) -> Result<Arc<UploadSessionStore>> {
+        let adapter: Box<dyn StoreAdapter<Key = String, Record = UploadSession>> =
+            match self.configuration.environment {
+                ExecutionEnvironment::Production => {
+                    let adapter =
+                        SQLiteAdapter::new("upload_session", self.get_sqlite_connection().await?)
+                            .map_err(|e| DependenciesBuilderError::Initialization {
+                            message: "Cannot create SQLite adapter for UploadSession Store."
+                                .to_string(),
+                            error: Some(e.into()),
+                        })?;
+
+                    Box::new(adapter)
+                }
+                _ => {
+                    let adapter = MemoryAdapter::new(None).map_err(|e| {
+                        DependenciesBuilderError::Initialization {
+                            message: "Cannot create Memory adapter for UploadSession Store."
+                                .to_string(),
+                            error: Some(e.into()),
+                        }
+                    })?;
+                    Box::new(adapter)
+                }
+            };
+
+        Ok(Arc::new(UploadSessionStore::new(adapter)))
     }
 
     /// Get a configured [CertificatePendingStore].
@@ -492,9 +706,14 @@ impl DependenciesBuilder {
     }
 
     async fn build_certificate_repository(&mut self) -> Result<Arc<CertificateRepository>> {
-        Ok(Arc::new(CertificateRepository::new(
-            self.get_sqlite_connection().await?,
-        )))
+        let mut certificate_repository =
+            CertificateRepository::new(self.get_sqlite_connection().await?)
+                .with_transaction_store(self.get_transaction_repository().await?);
+        if let Some(archive_store) = self.build_certificate_archive_store()? {
+            certificate_repository = certificate_repository.with_archive_store(archive_store);
+        }
+
+        Ok(Arc::new(certificate_repository))
     }
 
     /// Get a configured [CertificateRepository].
@@ -558,8 +777,9 @@ impl DependenciesBuilder {
                 error: None,
             })?;
 
+        let protocol_parameters = self.get_configured_protocol_parameters()?;
         protocol_parameters_store
-            .handle_discrepancies_at_startup(current_epoch, &self.configuration.protocol_parameters)
+            .handle_discrepancies_at_startup(current_epoch, &protocol_parameters)
             .await
             .map_err(|e| DependenciesBuilderError::Initialization {
                 message: "can not create aggregator runner".to_string(),
@@ -714,6 +934,25 @@ impl DependenciesBuilder {
         Ok(self.transaction_repository.as_ref().cloned().unwrap())
     }
 
+    async fn build_transaction_store(&mut self) -> Result<Arc<BatchingTransactionStore>> {
+        let transaction_store = BatchingTransactionStore::new(
+            self.get_transaction_repository().await?,
+            self.configuration.cardano_transactions_store_batch_size,
+            Duration::from_millis(self.configuration.cardano_transactions_store_flush_interval),
+        );
+
+        Ok(Arc::new(transaction_store))
+    }
+
+    /// Transaction store.
+    pub async fn get_transaction_store(&mut self) -> Result<Arc<BatchingTransactionStore>> {
+        if self.transaction_store.is_none() {
+            self.transaction_store = Some(self.build_transaction_store().await?);
+        }
+
+        Ok(self.transaction_store.as_ref().cloned().unwrap())
+    }
+
     async fn build_block_scanner(&mut self) -> Result<Arc<dyn BlockScanner>> {
         let block_scanner = CardanoBlockScanner::new(
             self.get_logger()?,
@@ -742,7 +981,11 @@ impl DependenciesBuilder {
             ExecutionEnvironment::Production => Some(self.get_immutable_cache_provider().await?),
             _ => None,
         };
-        let digester = CardanoImmutableDigester::new(immutable_digester_cache, self.get_logger()?);
+        let listing_provider = CachingImmutableFileListingProvider::new(Arc::new(
+            DirectoryImmutableFileListingProvider,
+        ));
+        let digester = CardanoImmutableDigester::new(immutable_digester_cache, self.get_logger()?)
+            .with_listing_provider(Arc::new(listing_provider));
 
         Ok(Arc::new(digester))
     }
@@ -764,14 +1007,19 @@ impl DependenciesBuilder {
                     .snapshot_directory
                     .join("pending_snapshot");
 
-                let algorithm = match self.configuration.snapshot_compression_algorithm {
-                    CompressionAlgorithm::Gzip => SnapshotterCompressionAlgorithm::Gzip,
-                    CompressionAlgorithm::Zstandard => self
-                        .configuration
-                        .zstandard_parameters
-                        .unwrap_or_default()
-                        .into(),
-                };
+                let algorithm: SnapshotterCompressionAlgorithm =
+                    match self.configuration.snapshot_compression_algorithm {
+                        CompressionAlgorithm::Gzip => self
+                            .configuration
+                            .gzip_parameters
+                            .unwrap_or_default()
+                            .into(),
+                        CompressionAlgorithm::Zstandard => self
+                            .configuration
+                            .zstandard_parameters
+                            .unwrap_or_default()
+                            .into(),
+                    };
 
                 Arc::new(CompressedArchiveSnapshotter::new(
                     self.configuration.db_directory.clone(),
@@ -1092,13 +1340,21 @@ impl DependenciesBuilder {
         let snapshot_uploader = self.build_snapshot_uploader().await?;
         let cardano_node_version = Version::parse(&self.configuration.cardano_node_version)
             .map_err(|e| DependenciesBuilderError::Initialization { message: format!("Could not parse configuration setting 'cardano_node_version' value '{}' as Semver.", self.configuration.cardano_node_version), error: Some(e.into()) })?;
-        let cardano_immutable_files_full_artifact_builder =
-            Arc::new(CardanoImmutableFilesFullArtifactBuilder::new(
+        let mut cardano_immutable_files_full_artifact_builder =
+            CardanoImmutableFilesFullArtifactBuilder::new(
                 &cardano_node_version,
                 snapshotter,
                 snapshot_uploader,
                 self.configuration.snapshot_compression_algorithm,
-            ));
+            )
+            .with_deduplication(self.get_snapshot_store().await?);
+        if let Some(max_bytes) = self.configuration.snapshots_storage_budget {
+            cardano_immutable_files_full_artifact_builder =
+                cardano_immutable_files_full_artifact_builder
+                    .with_size_budget(self.get_snapshot_store().await?, max_bytes);
+        }
+        let cardano_immutable_files_full_artifact_builder =
+            Arc::new(cardano_immutable_files_full_artifact_builder);
         let prover_service = self.get_prover_service().await?;
         let cardano_transactions_artifact_builder = Arc::new(
             CardanoTransactionsArtifactBuilder::new(prover_service.clone()),
@@ -1134,12 +1390,21 @@ impl DependenciesBuilder {
         Ok(self.signed_entity_service.as_ref().cloned().unwrap())
     }
 
+    fn get_configured_protocol_parameters(&self) -> Result<ProtocolParameters> {
+        self.configuration.get_protocol_parameters().map_err(|e| {
+            DependenciesBuilderError::Initialization {
+                message: "cannot get the protocol parameters from the configuration".to_string(),
+                error: Some(e),
+            }
+        })
+    }
+
     async fn build_epoch_service(&mut self) -> Result<EpochServiceWrapper> {
         let verification_key_store = self.get_verification_key_store().await?;
         let protocol_parameters_store = self.get_protocol_parameters_store().await?;
 
         let epoch_service = Arc::new(RwLock::new(MithrilEpochService::new(
-            self.configuration.protocol_parameters.clone(),
+            self.get_configured_protocol_parameters()?,
             protocol_parameters_store,
             verification_key_store,
         )));
@@ -1172,6 +1437,40 @@ impl DependenciesBuilder {
         Ok(self.signed_entity_storer.as_ref().cloned().unwrap())
     }
 
+    async fn build_snapshot_store(&mut self) -> Result<Arc<dyn SnapshotStore>> {
+        let snapshot_store = SignedEntityStore::new(self.get_sqlite_connection().await?);
+        let snapshot_store = match self.configuration.snapshot_uploader_type {
+            SnapshotUploaderType::Local => snapshot_store.with_archive_reader(Arc::new(
+                LocalSnapshotArchiveReader::new(self.configuration.snapshot_directory.clone()),
+            )),
+            SnapshotUploaderType::Gcp => match self.configuration.snapshot_bucket_name.clone() {
+                Some(bucket) => snapshot_store.with_archive_reader(Arc::new(
+                    RemoteSnapshotArchiveReader::new(Arc::new(GcpArchiveUrlPresigner::new(bucket))),
+                )),
+                None => snapshot_store,
+            },
+            // The IPFS archives are downloaded from a gateway
+            SnapshotUploaderType::Ipfs => snapshot_store,
+            SnapshotUploaderType::S3 if self.configuration.snapshot_s3_cold_storage => {
+                snapshot_store.with_cold_storage(Arc::new(S3ColdStorageLifecycle::new(
+                    self.build_s3_client()?,
+                )))
+            }
+            SnapshotUploaderType::S3 => snapshot_store,
+        };
+
+        Ok(Arc::new(snapshot_store))
+    }
+
+    /// [SnapshotStore] service
+    pub async fn get_snapshot_store(&mut self) -> Result<Arc<dyn SnapshotStore>> {
+        if self.snapshot_store.is_none() {
+            self.snapshot_store = Some(self.build_snapshot_store().await?);
+        }
+
+        Ok(self.snapshot_store.as_ref().cloned().unwrap())
+    }
+
     async fn build_signed_entity_lock(&mut self) -> Result<Arc<SignedEntityTypeLock>> {
         let signed_entity_type_lock = Arc::new(SignedEntityTypeLock::default());
         Ok(signed_entity_type_lock)
@@ -1185,13 +1484,130 @@ impl DependenciesBuilder {
         Ok(self.signed_entity_type_lock.as_ref().cloned().unwrap())
     }
 
+    async fn build_epoch_event_log(&mut self) -> Result<EpochEventLog> {
+        Ok(EpochEventLog::new(
+            self.configuration.epoch_event_log_capacity,
+        ))
+    }
+
+    async fn get_epoch_event_log(&mut self) -> Result<EpochEventLog> {
+        if self.epoch_event_log.is_none() {
+            self.epoch_event_log = Some(self.build_epoch_event_log().await?);
+        }
+
+        Ok(self.epoch_event_log.as_ref().cloned().unwrap())
+    }
+
+    async fn build_pending_operations_limiter(&mut self) -> Result<Arc<PendingOperationsLimiter>> {
+        let pending_operations_limiter = PendingOperationsLimiter::new(
+            self.configuration.max_pending_operations,
+        )
+        .map_err(|e| DependenciesBuilderError::Initialization {
+            message: "Could not create the pending operations limiter".to_string(),
+            error: Some(e),
+        })?;
+        pending_operations_limiter
+            .register_metrics(&self.get_metrics_registry().await?)
+            .map_err(|e| DependenciesBuilderError::Initialization {
+                message: "Could not register the pending operations limiter metrics".to_string(),
+                error: Some(e),
+            })?;
+
+        Ok(Arc::new(pending_operations_limiter))
+    }
+
+    /// [PendingOperationsLimiter] service
+    pub async fn get_pending_operations_limiter(
+        &mut self,
+    ) -> Result<Arc<PendingOperationsLimiter>> {
+        if self.pending_operations_limiter.is_none() {
+            self.pending_operations_limiter = Some(self.build_pending_operations_limiter().await?);
+        }
+
+        Ok(self.pending_operations_limiter.as_ref().cloned().unwrap())
+    }
+
+    /// Registry of the metrics of the aggregator
+    pub async fn get_metrics_registry(&mut self) -> Result<Registry> {
+        if self.metrics_registry.is_none() {
+            self.metrics_registry = Some(Registry::new());
+        }
+
+        Ok(self.metrics_registry.as_ref().cloned().unwrap())
+    }
+
+    async fn build_time_point_tracker(&mut self) -> Result<TimePointTracker> {
+        Ok(TimePointTracker::new())
+    }
+
+    async fn get_time_point_tracker(&mut self) -> Result<TimePointTracker> {
+        if self.time_point_tracker.is_none() {
+            self.time_point_tracker = Some(self.build_time_point_tracker().await?);
+        }
+
+        Ok(self.time_point_tracker.as_ref().cloned().unwrap())
+    }
+
+    async fn build_beacon_store(&mut self) -> Result<Arc<dyn BeaconStore>> {
+        Ok(Arc::new(BeaconLogStore::new(
+            self.get_sqlite_connection().await?,
+        )))
+    }
+
+    /// [BeaconStore] service
+    pub async fn get_beacon_store(&mut self) -> Result<Arc<dyn BeaconStore>> {
+        if self.beacon_store.is_none() {
+            self.beacon_store = Some(self.build_beacon_store().await?);
+        }
+
+        Ok(self.beacon_store.as_ref().cloned().unwrap())
+    }
+
+    async fn build_reward_store(&mut self) -> Result<Arc<dyn RewardStorer>> {
+        Ok(Arc::new(RewardStore::new(
+            self.get_sqlite_connection().await?,
+        )))
+    }
+
+    /// [RewardStorer] service
+    pub async fn get_reward_store(&mut self) -> Result<Arc<dyn RewardStorer>> {
+        if self.reward_store.is_none() {
+            self.reward_store = Some(self.build_reward_store().await?);
+        }
+
+        Ok(self.reward_store.as_ref().cloned().unwrap())
+    }
+
+    async fn build_admin_ip_allowlist(&mut self) -> Result<Arc<IpAllowlist>> {
+        let allowlist = IpAllowlist::from_configuration(&self.configuration).map_err(|e| {
+            DependenciesBuilderError::Initialization {
+                message: "Could not build the admin routes IP allowlist".to_string(),
+                error: Some(e),
+            }
+        })?;
+
+        Ok(Arc::new(allowlist))
+    }
+
+    /// [IpAllowlist] of the admin routes
+    pub async fn get_admin_ip_allowlist(&mut self) -> Result<Arc<IpAllowlist>> {
+        if self.admin_ip_allowlist.is_none() {
+            self.admin_ip_allowlist = Some(self.build_admin_ip_allowlist().await?);
+        }
+
+        Ok(self.admin_ip_allowlist.as_ref().cloned().unwrap())
+    }
+
     async fn build_transactions_importer(&mut self) -> Result<Arc<dyn TransactionsImporter>> {
-        let transactions_importer = Arc::new(CardanoTransactionsImporter::new(
-            self.get_block_scanner().await?,
-            self.get_transaction_repository().await?,
-            &self.configuration.db_directory,
-            self.get_logger()?,
-        ));
+        let transactions_importer = Arc::new(
+            CardanoTransactionsImporter::new(
+                self.get_block_scanner().await?,
+                self.get_transaction_store().await?,
+                &self.configuration.db_directory,
+                self.get_logger()?,
+            )
+            .with_leaf_format(self.configuration.cardano_transactions_leaf_format),
+        );
 
         Ok(transactions_importer)
     }
@@ -1241,12 +1657,21 @@ impl DependenciesBuilder {
             epoch_service: self.get_epoch_service().await?,
             ticker_service: self.get_ticker_service().await?,
             signed_entity_storer: self.get_signed_entity_storer().await?,
+            snapshot_store: self.get_snapshot_store().await?,
             signer_getter: self.get_signer_store().await?,
             message_service: self.get_message_service().await?,
             block_scanner: self.get_block_scanner().await?,
-            transaction_store: self.get_transaction_repository().await?,
+            transaction_store: self.get_transaction_store().await?,
             prover_service: self.get_prover_service().await?,
             signed_entity_type_lock: self.get_signed_entity_lock().await?,
+            epoch_event_log: self.get_epoch_event_log().await?,
+            pending_operations_limiter: self.get_pending_operations_limiter().await?,
+            metrics_registry: self.get_metrics_registry().await?,
+            time_point_tracker: self.get_time_point_tracker().await?,
+            beacon_store: self.get_beacon_store().await?,
+            reward_store: self.get_reward_store().await?,
+            admin_ip_allowlist: self.get_admin_ip_allowlist().await?,
+            tiered_snapshot_store: self.get_tiered_snapshot_store().await?,
         };
 
         Ok(dependency_manager)
@@ -1267,6 +1692,8 @@ impl DependenciesBuilder {
             Duration::from_millis(self.configuration.run_interval),
             self.get_signed_entity_config()?,
         );
+        let epoch_event_log = dependency_container.epoch_event_log.clone();
+        let certificate_pending_store = dependency_container.certificate_pending_store.clone();
         let runtime = AggregatorRuntime::new(
             config,
             None,
@@ -1276,7 +1703,9 @@ impl DependenciesBuilder {
         .map_err(|e| DependenciesBuilderError::Initialization {
             message: "Cannot initialize Aggregator runtime.".to_string(),
             error: Some(e.into()),
-        })?;
+        })?
+        .with_event_log(epoch_event_log)
+        .with_certificate_saga_store(certificate_pending_store);
 
         Ok(runtime)
     }
@@ -1403,12 +1832,49 @@ impl DependenciesBuilder {
         let certificate_repository = Arc::new(CertificateRepository::new(
             self.get_sqlite_connection().await?,
         ));
+        let certificate_cache =
+            LruCertificateCache::new(certificate_repository.clone()).map_err(|e| {
+                DependenciesBuilderError::Initialization {
+                    message: "Could not create the certificate cache".to_string(),
+                    error: Some(e),
+                }
+            })?;
+        certificate_cache
+            .register_metrics(&self.get_metrics_registry().await?)
+            .map_err(|e| DependenciesBuilderError::Initialization {
+                message: "Could not register the certificate cache metrics".to_string(),
+                error: Some(e),
+            })?;
         let signed_entity_storer = self.get_signed_entity_storer().await?;
-        let service = MithrilMessageService::new(certificate_repository, signed_entity_storer);
+        let mut service = MithrilMessageService::new(certificate_repository, signed_entity_storer)
+            .with_certificate_cache(Arc::new(certificate_cache));
+        if let Some(archive_store) = self.build_certificate_archive_store()? {
+            service = service.with_certificate_archive_store(archive_store);
+        }
 
         Ok(Arc::new(service))
     }
 
+    /// Build the store of the archived certificates, only in the production environment.
+    fn build_certificate_archive_store(&self) -> Result<Option<Arc<dyn CertificateArchiveStore>>> {
+        if self.configuration.environment != ExecutionEnvironment::Production {
+            return Ok(None);
+        }
+
+        let archive_store = FileCertificateArchiveStore::new(
+            &self
+                .configuration
+                .get_sqlite_dir()
+                .join("certificates_archive"),
+        )
+        .map_err(|e| DependenciesBuilderError::Initialization {
+            message: "Could not create the certificate archive store".to_string(),
+            error: Some(e),
+        })?;
+
+        Ok(Some(Arc::new(archive_store)))
+    }
+
     /// [MessageService] service
     pub async fn get_message_service(&mut self) -> Result<Arc<dyn MessageService>> {
         if self.message_service.is_none() {
@@ -1431,7 +1897,8 @@ impl DependenciesBuilder {
             block_range_root_retriever,
             mk_map_pool_size,
             logger,
-        );
+        )
+        .with_leaf_format(self.configuration.cardano_transactions_leaf_format);
 
         Ok(Arc::new(prover_service))
     }
@@ -0,0 +1,258 @@
+//! Initialisation of components according to their dependencies.
+
+use futures::future::{try_join_all, BoxFuture, FutureExt};
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use thiserror::Error;
+
+use mithril_common::StdResult;
+
+/// Error raised when the initialisation order of the components of a [ComponentGraph] can not be
+/// computed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DependencyError {
+    /// The components depend on each other, the first component is repeated at the end of the
+    /// cycle.
+    #[error("circular dependency between components: {}", .0.join(" -> "))]
+    CircularDependency(Vec<String>),
+
+    /// A component depends on a component that is not in the graph, or has dependencies
+    /// declared without being added to the graph (then `dependency` is the component itself).
+    #[error("component '{component}' depends on unknown component '{dependency}'")]
+    UnknownComponent {
+        /// Name of the dependent component
+        component: String,
+
+        /// Name of the missing dependency
+        dependency: String,
+    },
+}
+
+/// Graph of the components to initialise with the dependencies between them.
+///
+/// The components are initialised by layers: the components of a layer only depend on
+/// components of the previous layers, they are initialised concurrently.
+#[derive(Default)]
+pub struct ComponentGraph {
+    initialisers: BTreeMap<String, BoxFuture<'static, StdResult<()>>>,
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ComponentGraph {
+    /// ComponentGraph factory
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a component, initialised by the given future.
+    pub fn add_component<F>(&mut self, component: &str, initialiser: F)
+    where
+        F: Future<Output = StdResult<()>> + Send + 'static,
+    {
+        self.initialisers
+            .insert(component.to_string(), initialiser.boxed());
+        self.dependencies.entry(component.to_string()).or_default();
+    }
+
+    /// Declare that `component` must be initialised after `dependency`.
+    pub fn depends_on(&mut self, component: &str, dependency: &str) {
+        self.dependencies
+            .entry(component.to_string())
+            .or_default()
+            .insert(dependency.to_string());
+    }
+
+    /// Sort the components in layers, each layer only depends on the previous layers.
+    pub fn sort(&self) -> Result<Vec<Vec<String>>, DependencyError> {
+        for (component, dependencies) in &self.dependencies {
+            if !self.initialisers.contains_key(component) {
+                return Err(DependencyError::UnknownComponent {
+                    component: component.to_owned(),
+                    dependency: component.to_owned(),
+                });
+            }
+            if let Some(dependency) = dependencies
+                .iter()
+                .find(|d| !self.initialisers.contains_key(*d))
+            {
+                return Err(DependencyError::UnknownComponent {
+                    component: component.to_owned(),
+                    dependency: dependency.to_owned(),
+                });
+            }
+        }
+
+        let mut remaining = self.dependencies.clone();
+        let mut layers = vec![];
+        while !remaining.is_empty() {
+            let layer: Vec<String> = remaining
+                .iter()
+                .filter(|(_, dependencies)| dependencies.iter().all(|d| !remaining.contains_key(d)))
+                .map(|(component, _)| component.to_owned())
+                .collect();
+            if layer.is_empty() {
+                return Err(DependencyError::CircularDependency(Self::find_cycle(
+                    &remaining,
+                )));
+            }
+
+            for component in &layer {
+                remaining.remove(component);
+            }
+            layers.push(layer);
+        }
+
+        Ok(layers)
+    }
+
+    /// Follow the dependencies of the components that could not be sorted until a component
+    /// is visited twice.
+    fn find_cycle(remaining: &BTreeMap<String, BTreeSet<String>>) -> Vec<String> {
+        let mut path: Vec<String> = vec![];
+        let mut component = remaining.keys().next().cloned().unwrap_or_default();
+        while !path.contains(&component) {
+            path.push(component.clone());
+            component = remaining[&component]
+                .iter()
+                .find(|d| remaining.contains_key(*d))
+                .cloned()
+                .unwrap_or_default();
+        }
+        let cycle_start = path.iter().position(|c| *c == component).unwrap_or(0);
+        let mut cycle = path.split_off(cycle_start);
+        cycle.push(component);
+
+        cycle
+    }
+
+    /// Initialise all the components, the components of a layer are initialised concurrently.
+    ///
+    /// Fail with a [DependencyError] before initialising any component if the dependencies
+    /// can't be sorted.
+    pub async fn initialise(mut self) -> StdResult<()> {
+        for layer in self.sort()? {
+            try_join_all(
+                layer
+                    .iter()
+                    .filter_map(|component| self.initialisers.remove(component)),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    use super::*;
+
+    fn recording_initialiser(
+        component: &str,
+        starts: Arc<Mutex<BTreeMap<String, Instant>>>,
+    ) -> impl Future<Output = StdResult<()>> + Send + 'static {
+        let component = component.to_string();
+        async move {
+            starts.lock().unwrap().insert(component, Instant::now());
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sort_components_in_dependency_layers() {
+        let mut graph = ComponentGraph::new();
+        for component in ["digester", "stake_distribution", "time_point", "runner"] {
+            graph.add_component(component, async { Ok(()) });
+        }
+        graph.depends_on("digester", "time_point");
+        graph.depends_on("runner", "digester");
+        graph.depends_on("runner", "stake_distribution");
+
+        assert_eq!(
+            vec![
+                vec!["stake_distribution".to_string(), "time_point".to_string()],
+                vec!["digester".to_string()],
+                vec!["runner".to_string()],
+            ],
+            graph.sort().unwrap()
+        );
+    }
+
+    #[test]
+    fn sort_components_with_circular_dependencies_fails() {
+        let mut graph = ComponentGraph::new();
+        for component in ["a", "b", "c", "d"] {
+            graph.add_component(component, async { Ok(()) });
+        }
+        graph.depends_on("a", "d");
+        graph.depends_on("b", "c");
+        graph.depends_on("c", "d");
+        graph.depends_on("d", "b");
+
+        assert_eq!(
+            Err(DependencyError::CircularDependency(vec![
+                "d".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "d".to_string()
+            ])),
+            graph.sort()
+        );
+    }
+
+    #[test]
+    fn sort_components_with_an_unknown_dependency_fails() {
+        let mut graph = ComponentGraph::new();
+        graph.add_component("a", async { Ok(()) });
+        graph.depends_on("a", "b");
+
+        assert!(matches!(
+            graph.sort(),
+            Err(DependencyError::UnknownComponent { .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn independent_components_are_initialised_concurrently() {
+        let starts = Arc::new(Mutex::new(BTreeMap::new()));
+        let mut graph = ComponentGraph::new();
+        for component in ["digester", "stake_distribution", "runner"] {
+            graph.add_component(component, recording_initialiser(component, starts.clone()));
+        }
+        graph.depends_on("runner", "digester");
+        graph.depends_on("runner", "stake_distribution");
+        let init_start = Instant::now();
+
+        graph.initialise().await.unwrap();
+
+        let starts = starts.lock().unwrap();
+        assert_eq!(init_start, starts["digester"]);
+        assert_eq!(init_start, starts["stake_distribution"]);
+        assert_eq!(init_start + Duration::from_millis(100), starts["runner"]);
+    }
+
+    #[tokio::test]
+    async fn initialisation_stops_at_the_first_failing_layer() {
+        let runner_initialised = Arc::new(Mutex::new(false));
+        let mut graph = ComponentGraph::new();
+        graph.add_component("digester", async { Err(anyhow::anyhow!("digest failure")) });
+        let runner_initialised_clone = runner_initialised.clone();
+        graph.add_component("runner", async move {
+            *runner_initialised_clone.lock().unwrap() = true;
+            Ok(())
+        });
+        graph.depends_on("runner", "digester");
+
+        graph
+            .initialise()
+            .await
+            .expect_err("initialisation should fail");
+
+        assert!(!*runner_initialised.lock().unwrap());
+    }
+}
@@ -1,5 +1,7 @@
+use anyhow::{anyhow, Context};
 use mithril_persistence::sqlite::SqliteConnectionPool;
-use std::sync::Arc;
+use prometheus::Registry;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
 
 use mithril_common::{
@@ -9,31 +11,37 @@ use mithril_common::{
     chain_observer::ChainObserver,
     crypto_helper::ProtocolGenesisVerifier,
     digesters::{ImmutableDigester, ImmutableFileObserver},
-    entities::{Epoch, ProtocolParameters, SignedEntityConfig, SignerWithStake, StakeDistribution},
+    entities::{
+        CardanoDbBeacon, Epoch, ProtocolParameters, SignedEntityConfig, SignerWithStake,
+        StakeDistribution, TimePoint,
+    },
     era::{EraChecker, EraReader},
     signable_builder::SignableBuilderService,
     signed_entity_type_lock::SignedEntityTypeLock,
     test_utils::MithrilFixture,
-    TickerService,
+    StdResult, TickerService,
 };
 use mithril_persistence::{sqlite::SqliteConnection, store::StakeStorer};
 
 use crate::{
     configuration::*,
     database::repository::{
-        CertificateRepository, OpenMessageRepository, SignedEntityStorer, SignerGetter,
-        StakePoolStore,
+        BeaconStore, CertificateRepository, OpenMessageRepository, RewardStorer,
+        SignedEntityStorer, SignerGetter, StakePoolStore,
     },
+    dependency_injection::{ComponentGraph, DependenciesBuilder},
     event_store::{EventMessage, TransmitterService},
+    http_server::IpAllowlist,
     multi_signer::MultiSigner,
     services::{
         CertifierService, EpochService, MessageService, ProverService, SignedEntityService,
         StakeDistributionService, TransactionStore,
     },
     signer_registerer::SignerRecorder,
-    snapshot_uploaders::SnapshotUploader,
-    CertificatePendingStore, ProtocolParametersStorer, SignerRegisterer,
-    SignerRegistrationRoundOpener, Snapshotter, VerificationKeyStorer,
+    snapshot_uploaders::{SnapshotUploader, TieredSnapshotStore},
+    CertificatePendingStore, EpochEventLog, PendingOperationsLimiter, ProtocolParametersStorer,
+    SignerRegisterer, SignerRegistrationRoundOpener, SnapshotStore, Snapshotter, TimePointTracker,
+    VerificationKeyStorer,
 };
 
 /// MultiSignerWrapper wraps a [MultiSigner]
@@ -150,6 +158,9 @@ pub struct DependencyContainer {
     /// Signed Entity storer
     pub signed_entity_storer: Arc<dyn SignedEntityStorer>,
 
+    /// Snapshot store
+    pub snapshot_store: Arc<dyn SnapshotStore>,
+
     /// Signer getter service
     pub signer_getter: Arc<dyn SignerGetter>,
 
@@ -161,10 +172,89 @@ pub struct DependencyContainer {
 
     /// Signed Entity Type Lock
     pub signed_entity_type_lock: Arc<SignedEntityTypeLock>,
+
+    /// Log of the last events of the runtime
+    pub epoch_event_log: EpochEventLog,
+
+    /// Backpressure on the signing operations
+    pub pending_operations_limiter: Arc<PendingOperationsLimiter>,
+
+    /// Registry of the metrics of the aggregator
+    pub metrics_registry: Registry,
+
+    /// Tracker of the last time point read from the chain
+    pub time_point_tracker: TimePointTracker,
+
+    /// Store of the current beacon and of its replication log
+    pub beacon_store: Arc<dyn BeaconStore>,
+
+    /// Store of the rewards of the signers
+    pub reward_store: Arc<dyn RewardStorer>,
+
+    /// Allowlist of the clients of the admin routes
+    pub admin_ip_allowlist: Arc<IpAllowlist>,
+
+    /// Tiered snapshot store, set if the snapshots are moved to a cold storage
+    pub tiered_snapshot_store: Option<Arc<TieredSnapshotStore>>,
 }
 
 #[doc(hidden)]
 impl DependencyContainer {
+    /// Build the dependencies with the given builder then run their startup initialisation
+    /// steps, run by the `serve` command before starting the runtime. The independent steps
+    /// are run concurrently by a [ComponentGraph]:
+    /// * `stake_distribution`: compute the stake distribution of the current epoch,
+    /// * `time_point`: read the current time point of the chain,
+    /// * `immutable_digest`: in production, compute the digest of the current immutable files
+    ///   to fill the digester cache, after `time_point`.
+    pub async fn build_async(builder: &mut DependenciesBuilder) -> StdResult<Arc<Self>> {
+        let dependencies = Arc::new(builder.build_dependency_container().await?);
+        let time_point: Arc<OnceLock<TimePoint>> = Arc::new(OnceLock::new());
+        let mut graph = ComponentGraph::new();
+
+        let stake_distribution_service = dependencies.stake_distribution_service.clone();
+        graph.add_component("stake_distribution", async move {
+            stake_distribution_service
+                .update_stake_distribution()
+                .await
+                .with_context(|| "Dependency container can not update the stake distribution")
+        });
+
+        let ticker_service = dependencies.ticker_service.clone();
+        let current_time_point = time_point.clone();
+        graph.add_component("time_point", async move {
+            let _ = current_time_point.set(ticker_service.get_current_time_point().await?);
+            Ok(())
+        });
+
+        if dependencies.config.environment == ExecutionEnvironment::Production {
+            let digester = dependencies.digester.clone();
+            let config = dependencies.config.clone();
+            graph.add_component("immutable_digest", async move {
+                let time_point = time_point
+                    .get()
+                    .ok_or_else(|| anyhow!("The current time point was not read"))?;
+                let beacon = CardanoDbBeacon::new(
+                    config.network.clone(),
+                    *time_point.epoch,
+                    time_point.immutable_file_number,
+                );
+                digester
+                    .compute_digest(&config.db_directory, &beacon)
+                    .await
+                    .with_context(|| {
+                        "Dependency container can not compute the current immutable files digest"
+                    })?;
+                Ok(())
+            });
+            graph.depends_on("immutable_digest", "time_point");
+        }
+
+        graph.initialise().await?;
+
+        Ok(dependencies)
+    }
+
     /// `TEST METHOD ONLY`
     ///
     /// Get the first two epochs that will be used by a newly started aggregator
@@ -272,14 +362,25 @@ impl DependencyContainer {
     }
 }
 
+/// Dependency container helpers for the tests
 #[cfg(test)]
 pub mod tests {
     use crate::{dependency_injection::DependenciesBuilder, Configuration, DependencyContainer};
 
+    /// Build a [DependencyContainer] from a sample configuration
     pub async fn initialize_dependencies() -> DependencyContainer {
         let config = Configuration::new_sample();
         let mut builder = DependenciesBuilder::new(config);
 
         builder.build_dependency_container().await.unwrap()
     }
+
+    #[tokio::test]
+    async fn build_async_runs_the_initialisation_steps() {
+        let mut builder = DependenciesBuilder::new(Configuration::new_sample());
+
+        DependencyContainer::build_async(&mut builder)
+            .await
+            .expect("build_async should not fail");
+    }
 }
@@ -12,9 +12,11 @@
 //! The Builder ensure every services has required dependencies to build and
 //! provide services containers for each sub process.
 mod builder;
+mod component_graph;
 mod containers;
 mod error;
 
 pub use builder::*;
+pub use component_graph::*;
 pub use containers::*;
 pub use error::*;
@@ -2,11 +2,15 @@
 //!
 //! This module provide domain entities for the services & state machine.
 mod open_message;
+mod reward;
 mod signer_registration_message;
 mod signer_ticker_message;
+mod snapshot_manifest;
 
 pub use open_message::OpenMessage;
+pub use reward::{RewardEntry, SignerContribution};
 pub use signer_registration_message::{
     SignerRegistrationsListItemMessage, SignerRegistrationsMessage,
 };
 pub use signer_ticker_message::{SignerTickerListItemMessage, SignersTickersMessage};
+pub use snapshot_manifest::{ManifestEntry, SnapshotManifest};
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use mithril_common::entities::{Epoch, PartyId, Stake};
+
+/// Contribution of a signer to the signing rounds of an epoch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerContribution {
+    /// Party id of the signer
+    pub party_id: PartyId,
+
+    /// Stake of the signer
+    pub stake: Stake,
+
+    /// Number of valid single signatures sent by the signer
+    pub signatures_count: u64,
+
+    /// Number of lotteries won by the valid single signatures of the signer
+    pub won_indexes_count: u64,
+
+    /// Concatenated bytes of the valid single signatures of the signer, in the order they
+    /// were received
+    pub signatures_bytes: Vec<u8>,
+}
+
+/// Reward granted to a signer for its contribution to the signing rounds of an epoch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardEntry {
+    /// Epoch of the rewarded contribution
+    pub epoch: Epoch,
+
+    /// Party id of the rewarded signer
+    pub party_id: PartyId,
+
+    /// Amount of the reward
+    pub reward: u64,
+}
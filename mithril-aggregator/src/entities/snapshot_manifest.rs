@@ -0,0 +1,89 @@
+use mithril_common::entities::ImmutableFileNumber;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Archive listed in a [SnapshotManifest]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Digest of the snapshot
+    pub digest: String,
+
+    /// Number of the last immutable file included in the snapshot
+    pub immutable_file_number: ImmutableFileNumber,
+
+    /// Size of the snapshot archive in bytes
+    pub size_bytes: u64,
+
+    /// Location where the snapshot archive can be downloaded
+    pub url: String,
+}
+
+/// Manifest of the snapshots available in a range of immutable files
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Snapshots ordered by ascending immutable file number
+    pub entries: Vec<ManifestEntry>,
+
+    /// Hash of the digests of all the entries, in their order
+    pub root_digest: String,
+}
+
+impl SnapshotManifest {
+    /// SnapshotManifest factory, the entries are sorted by ascending immutable file number
+    pub fn new(mut entries: Vec<ManifestEntry>) -> Self {
+        entries.sort_by_key(|entry| entry.immutable_file_number);
+        let root_digest = Self::compute_root_digest(&entries);
+
+        Self {
+            entries,
+            root_digest,
+        }
+    }
+
+    fn compute_root_digest(entries: &[ManifestEntry]) -> String {
+        let mut hasher = Sha256::new();
+        for entry in entries {
+            hasher.update(entry.digest.as_bytes());
+        }
+
+        hex::encode(hasher.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(digest: &str, immutable_file_number: ImmutableFileNumber) -> ManifestEntry {
+        ManifestEntry {
+            digest: digest.to_string(),
+            immutable_file_number,
+            size_bytes: 10,
+            url: format!("http://{digest}"),
+        }
+    }
+
+    #[test]
+    fn entries_are_sorted_by_ascending_immutable_file_number() {
+        let manifest = SnapshotManifest::new(vec![entry("c", 30), entry("a", 10), entry("b", 20)]);
+
+        assert_eq!(
+            vec![entry("a", 10), entry("b", 20), entry("c", 30)],
+            manifest.entries
+        );
+    }
+
+    #[test]
+    fn root_digest_depends_on_the_entries_digests() {
+        let manifest = SnapshotManifest::new(vec![entry("a", 10), entry("b", 20)]);
+
+        assert_eq!(
+            manifest.root_digest,
+            SnapshotManifest::new(vec![entry("b", 20), entry("a", 10)]).root_digest
+        );
+        assert_ne!(
+            manifest.root_digest,
+            SnapshotManifest::new(vec![entry("a", 10), entry("c", 20)]).root_digest
+        );
+    }
+}
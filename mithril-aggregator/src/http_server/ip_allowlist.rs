@@ -0,0 +1,124 @@
+use ipnet::IpNet;
+use std::net::{IpAddr, SocketAddr};
+
+use mithril_common::StdResult;
+
+use crate::Configuration;
+
+/// Allowlist of the IP networks of the clients that can call the admin routes.
+///
+/// If the request comes from a trusted reverse proxy, the client IP is read from its
+/// `X-Forwarded-For` header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpAllowlist {
+    allowed_networks: Vec<IpNet>,
+    trusted_proxies: Vec<IpNet>,
+}
+
+impl IpAllowlist {
+    /// IpAllowlist factory, all clients are allowed if `allowed_networks` is empty.
+    pub fn new(allowed_networks: Vec<IpNet>, trusted_proxies: Vec<IpNet>) -> Self {
+        Self {
+            allowed_networks,
+            trusted_proxies,
+        }
+    }
+
+    /// Create the allowlist from the `allowlisted_ips` and `trusted_proxies` configurations.
+    pub fn from_configuration(configuration: &Configuration) -> StdResult<Self> {
+        Ok(Self::new(
+            configuration.get_allowlisted_ips()?,
+            configuration.get_trusted_proxies()?,
+        ))
+    }
+
+    /// Check if a request from the given remote address, with the given `X-Forwarded-For`
+    /// header value, is allowed.
+    pub fn is_allowed(&self, remote_addr: Option<SocketAddr>, forwarded_for: Option<&str>) -> bool {
+        if self.allowed_networks.is_empty() {
+            return true;
+        }
+
+        remote_addr
+            .and_then(|addr| self.client_ip(addr.ip(), forwarded_for))
+            .is_some_and(|ip| self.allowed_networks.iter().any(|net| net.contains(&ip)))
+    }
+
+    /// Find the IP of the client, the `X-Forwarded-For` header is only used if the request
+    /// comes from a trusted proxy.
+    ///
+    /// The header is read from right to left since each proxy appends the address it received
+    /// the request from: the client is the first address that is not a trusted proxy.
+    fn client_ip(&self, remote_ip: IpAddr, forwarded_for: Option<&str>) -> Option<IpAddr> {
+        let forwarded_for = match forwarded_for {
+            Some(forwarded_for) if self.is_trusted_proxy(&remote_ip) => forwarded_for,
+            _ => return Some(remote_ip),
+        };
+
+        let mut client_ip = remote_ip;
+        for hop in forwarded_for.rsplit(',').map(str::trim) {
+            // An unparsable address can't be checked, the request is denied
+            client_ip = hop.parse().ok()?;
+            if !self.is_trusted_proxy(&client_ip) {
+                break;
+            }
+        }
+
+        Some(client_ip)
+    }
+
+    fn is_trusted_proxy(&self, ip: &IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(ip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn networks(networks: &[&str]) -> Vec<IpNet> {
+        networks.iter().map(|net| net.parse().unwrap()).collect()
+    }
+
+    fn addr(ip: &str) -> Option<SocketAddr> {
+        Some(SocketAddr::new(ip.parse().unwrap(), 4321))
+    }
+
+    #[test]
+    fn empty_allowlist_allows_everyone() {
+        let allowlist = IpAllowlist::default();
+
+        assert!(allowlist.is_allowed(addr("203.0.113.7"), None));
+        assert!(allowlist.is_allowed(None, None));
+    }
+
+    #[test]
+    fn only_clients_in_the_allowed_networks_are_allowed() {
+        let allowlist = IpAllowlist::new(networks(&["10.0.0.0/8", "192.168.1.12/32"]), vec![]);
+
+        assert!(allowlist.is_allowed(addr("10.1.2.3"), None));
+        assert!(allowlist.is_allowed(addr("192.168.1.12"), None));
+        assert!(!allowlist.is_allowed(addr("192.168.1.13"), None));
+        assert!(!allowlist.is_allowed(None, None));
+    }
+
+    #[test]
+    fn forwarded_for_header_is_ignored_if_the_remote_is_not_a_trusted_proxy() {
+        let allowlist = IpAllowlist::new(networks(&["10.0.0.0/8"]), vec![]);
+
+        assert!(!allowlist.is_allowed(addr("203.0.113.7"), Some("10.1.2.3")));
+    }
+
+    #[test]
+    fn client_ip_is_read_from_the_forwarded_for_header_of_trusted_proxies() {
+        let allowlist = IpAllowlist::new(networks(&["10.0.0.0/8"]), networks(&["172.16.0.0/12"]));
+
+        assert!(allowlist.is_allowed(addr("172.16.0.2"), Some("10.1.2.3")));
+        assert!(allowlist.is_allowed(
+            addr("172.16.0.2"),
+            Some("203.0.113.7, 10.1.2.3, 172.16.0.3")
+        ));
+        assert!(!allowlist.is_allowed(addr("172.16.0.2"), Some("10.1.2.3, 203.0.113.7")));
+        assert!(!allowlist.is_allowed(addr("172.16.0.2"), Some("not-an-ip")));
+    }
+}
@@ -1,3 +1,6 @@
+mod ip_allowlist;
 pub mod routes;
 
+pub use ip_allowlist::IpAllowlist;
+
 pub const SERVER_BASE_PATH: &str = "aggregator";
@@ -1,14 +1,25 @@
 use crate::http_server::routes::middlewares;
 use crate::http_server::SERVER_BASE_PATH;
 use crate::DependencyContainer;
+use mithril_common::entities::ImmutableFileNumber;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use warp::hyper::Uri;
 use warp::Filter;
 
+#[derive(Deserialize, Serialize, Debug)]
+struct SnapshotManifestQueryParams {
+    from: ImmutableFileNumber,
+    to: ImmutableFileNumber,
+}
+
 pub fn routes(
     dependency_manager: Arc<DependencyContainer>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     artifact_cardano_full_immutable_snapshots(dependency_manager.clone())
+        .or(artifact_cardano_full_immutable_snapshots_manifest(
+            dependency_manager.clone(),
+        ))
         .or(artifact_cardano_full_immutable_snapshot_by_id(
             dependency_manager.clone(),
         ))
@@ -24,10 +35,22 @@ fn artifact_cardano_full_immutable_snapshots(
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("artifact" / "snapshots")
         .and(warp::get())
+        .and(warp::header::optional::<String>("if-none-match"))
         .and(middlewares::with_http_message_service(dependency_manager))
         .and_then(handlers::list_artifacts)
 }
 
+/// GET /artifact/snapshots/manifest
+fn artifact_cardano_full_immutable_snapshots_manifest(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("artifact" / "snapshots" / "manifest")
+        .and(warp::get())
+        .and(warp::query::<SnapshotManifestQueryParams>())
+        .and(middlewares::with_signed_entity_service(dependency_manager))
+        .and_then(handlers::snapshots_manifest)
+}
+
 /// GET /artifact/snapshot/:id
 fn artifact_cardano_full_immutable_snapshot_by_id(
     dependency_manager: Arc<DependencyContainer>,
@@ -44,8 +67,13 @@ fn snapshot_download(
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("artifact" / "snapshot" / String / "download")
         .and(warp::get().or(warp::head()).unify())
+        .and(warp::header::optional::<String>("range"))
         .and(middlewares::with_config(dependency_manager.clone()))
-        .and(middlewares::with_signed_entity_service(dependency_manager))
+        .and(middlewares::with_signed_entity_service(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_snapshot_store(dependency_manager.clone()))
+        .and(middlewares::with_tiered_snapshot_store(dependency_manager))
         .and_then(handlers::snapshot_download)
 }
 
@@ -92,26 +120,124 @@ mod handlers {
     use crate::http_server::routes::reply;
     use crate::http_server::SERVER_BASE_PATH;
     use crate::services::MessageService;
+    use crate::snapshot_uploaders::ipfs_location_to_gateway_url;
     use crate::{services::SignedEntityService, Configuration};
+    use crate::{
+        RestoreStatus, SnapshotStorageClass, SnapshotStore, SnapshotTier, TieredSnapshotStore,
+    };
     use slog_scope::{debug, warn};
     use std::convert::Infallible;
     use std::str::FromStr;
     use std::sync::Arc;
-    use warp::http::{StatusCode, Uri};
+    use tokio_util::io::ReaderStream;
+    use warp::http::{Response, StatusCode, Uri};
+    use warp::hyper::Body;
+
+    use super::SnapshotManifestQueryParams;
+
+    /// Number of days a snapshot archive restored from the cold storage stays downloadable
+    pub const COLD_ARCHIVE_RESTORE_DAYS: u8 = 7;
+
+    /// Delay, in seconds, advised to the clients before retrying the download of a snapshot
+    /// archive being restored from the cold storage
+    pub const COLD_ARCHIVE_RETRY_AFTER_SECONDS: u64 = 3600;
 
     pub const LIST_MAX_ITEMS: usize = 20;
 
+    /// Range of bytes of a snapshot archive requested with the `Range` header of a download
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum RequestedRange {
+        /// No range or a range that can't be parsed, the whole archive is downloaded
+        Full,
+
+        /// The bytes from `start` to `end`, both included
+        Bytes { start: u64, end: u64 },
+
+        /// A range outside of the archive
+        NotSatisfiable,
+    }
+
+    /// Parse the `Range` header of a download of an archive of `size` bytes.
+    ///
+    /// Only a single range is supported, a header with several ranges is ignored.
+    pub fn parse_range_header(range: Option<&str>, size: u64) -> RequestedRange {
+        let Some((start, end)) = range
+            .and_then(|range| range.trim().strip_prefix("bytes="))
+            .filter(|range| !range.contains(','))
+            .and_then(|range| range.split_once('-'))
+        else {
+            return RequestedRange::Full;
+        };
+        let (start, end) = match (start.trim(), end.trim()) {
+            ("", "") => return RequestedRange::Full,
+            // A suffix range requests the last `n` bytes
+            ("", suffix_length) => match suffix_length.parse::<u64>() {
+                Ok(0) => return RequestedRange::NotSatisfiable,
+                Ok(suffix_length) => (size.saturating_sub(suffix_length), size.saturating_sub(1)),
+                Err(_) => return RequestedRange::Full,
+            },
+            (start, "") => match start.parse::<u64>() {
+                Ok(start) => (start, size.saturating_sub(1)),
+                Err(_) => return RequestedRange::Full,
+            },
+            (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+                (Ok(start), Ok(end)) if start <= end => (start, end.min(size.saturating_sub(1))),
+                _ => return RequestedRange::Full,
+            },
+        };
+
+        if size == 0 || start >= size {
+            RequestedRange::NotSatisfiable
+        } else {
+            RequestedRange::Bytes { start, end }
+        }
+    }
+
+    /// Check if the `If-None-Match` header of a request matches the given ETag.
+    ///
+    /// The header is either `*`, matching any ETag, or a comma separated list of ETags compared
+    /// with the weak comparison: the `W/` prefix of the weak ETags is ignored.
+    pub fn if_none_match_matches(if_none_match: &str, etag: &str) -> bool {
+        if if_none_match.trim() == "*" {
+            return true;
+        }
+
+        if_none_match.split(',').any(|tag| {
+            let tag = tag.trim();
+            tag.strip_prefix("W/").unwrap_or(tag).trim_matches('"') == etag
+        })
+    }
+
     /// List Snapshot artifacts
+    ///
+    /// The list is not sent again if the client already has it, ie: if the `If-None-Match`
+    /// header of the request matches the ETag of the list.
     pub async fn list_artifacts(
+        if_none_match: Option<String>,
         http_message_service: Arc<dyn MessageService>,
     ) -> Result<impl warp::Reply, Infallible> {
         debug!("⇄ HTTP SERVER: artifacts");
 
         match http_message_service
-            .get_snapshot_list_message(LIST_MAX_ITEMS)
+            .get_snapshot_list_message_with_etag(LIST_MAX_ITEMS)
             .await
         {
-            Ok(message) => Ok(reply::json(&message, StatusCode::OK)),
+            Ok((_message, etag))
+                if if_none_match
+                    .as_deref()
+                    .is_some_and(|if_none_match| if_none_match_matches(if_none_match, &etag)) =>
+            {
+                Ok(Box::new(warp::reply::with_header(
+                    reply::empty(StatusCode::NOT_MODIFIED),
+                    "etag",
+                    format!("\"{etag}\""),
+                )) as Box<dyn warp::Reply>)
+            }
+            Ok((message, etag)) => Ok(Box::new(warp::reply::with_header(
+                reply::json(&message, StatusCode::OK),
+                "etag",
+                format!("\"{etag}\""),
+            )) as Box<dyn warp::Reply>),
             Err(err) => {
                 warn!("list_artifacts_snapshot"; "error" => ?err);
                 Ok(reply::internal_server_error(err))
@@ -119,6 +245,34 @@ mod handlers {
         }
     }
 
+    /// Manifest of the Snapshot artifacts in a range of immutable files
+    pub async fn snapshots_manifest(
+        query_params: SnapshotManifestQueryParams,
+        signed_entity_service: Arc<dyn SignedEntityService>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        let SnapshotManifestQueryParams { from, to } = query_params;
+        debug!("⇄ HTTP SERVER: artifact/snapshots/manifest?from={from}&to={to}");
+
+        if from > to {
+            warn!("snapshots_manifest::bad_request"; "from" => from, "to" => to);
+            return Ok(reply::bad_request(
+                "invalid_immutable_file_range".to_string(),
+                format!("'from' ({from}) must be lower or equal to 'to' ({to})"),
+            ));
+        }
+
+        match signed_entity_service
+            .generate_snapshot_manifest(from, to)
+            .await
+        {
+            Ok(manifest) => Ok(reply::json(&manifest, StatusCode::OK)),
+            Err(err) => {
+                warn!("snapshots_manifest::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err))
+            }
+        }
+    }
+
     /// Get Artifact by signed entity id
     pub async fn get_artifact_by_signed_entity_id(
         signed_entity_id: String,
@@ -175,19 +329,76 @@ mod handlers {
     }
 
     /// Snapshot download
+    ///
+    /// A single range of bytes of the archive can be downloaded with a `Range` header.
+    ///
+    /// The archives moved to the cold tier of the tiered snapshot store are downloaded from
+    /// their cold tier location.
     pub async fn snapshot_download(
         digest: String,
+        range: Option<String>,
         config: Configuration,
         signed_entity_service: Arc<dyn SignedEntityService>,
+        snapshot_store: Arc<dyn SnapshotStore>,
+        tiered_snapshot_store: Option<Arc<TieredSnapshotStore>>,
     ) -> Result<impl warp::Reply, Infallible> {
         debug!("⇄ HTTP SERVER: snapshot_download/{}", digest);
 
+        // Without cold storage all the archives have the standard storage class, there's no
+        // need to read it
+        let storage_class = match snapshot_store.has_cold_storage() {
+            true => snapshot_store.get_storage_class(&digest).await,
+            false => Ok(None),
+        };
+        match storage_class {
+            Ok(Some(SnapshotStorageClass::ColdStorage)) => {
+                match snapshot_store
+                    .restore_from_cold(&digest, COLD_ARCHIVE_RESTORE_DAYS)
+                    .await
+                {
+                    Ok(RestoreStatus::Pending) => {
+                        debug!("snapshot_download::restore_pending"; "digest" => &digest);
+                        return Ok(Box::new(warp::reply::with_header(
+                            reply::empty(StatusCode::ACCEPTED),
+                            "Retry-After",
+                            COLD_ARCHIVE_RETRY_AFTER_SECONDS.to_string(),
+                        )) as Box<dyn warp::Reply>);
+                    }
+                    Ok(RestoreStatus::Available) => {}
+                    Err(err) => {
+                        warn!("snapshot_download::restore_error"; "error" => ?err);
+                        return Ok(reply::internal_server_error(err));
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                warn!("snapshot_download::storage_class_error"; "error" => ?err);
+                return Ok(reply::internal_server_error(err));
+            }
+        }
+
         match signed_entity_service
             .get_signed_snapshot_by_id(&digest)
             .await
         {
             Ok(Some(signed_entity)) => {
                 let snapshot = signed_entity.artifact;
+                if let Some(gateway_url) = snapshot.locations.iter().find_map(|location| {
+                    ipfs_location_to_gateway_url(location, &config.ipfs_gateway_url)
+                }) {
+                    return match Uri::from_str(&gateway_url) {
+                        Ok(snapshot_uri) => {
+                            Ok(Box::new(warp::redirect::found(snapshot_uri))
+                                as Box<dyn warp::Reply>)
+                        }
+                        Err(err) => {
+                            warn!("snapshot_download::invalid_ipfs_gateway_url"; "url" => &gateway_url, "error" => ?err);
+                            Ok(reply::internal_server_error(err.to_string()))
+                        }
+                    };
+                }
+
                 let filename = format!(
                     "{}-e{}-i{}.{}.{}",
                     snapshot.beacon.network,
@@ -196,6 +407,44 @@ mod handlers {
                     snapshot.digest,
                     snapshot.compression_algorithm.tar_file_extension()
                 );
+                if let Some(cold_location) = tiered_snapshot_store
+                    .filter(|store| store.find_tier(&filename) == SnapshotTier::Cold)
+                    .and_then(|_| snapshot.locations.first().cloned())
+                {
+                    match Uri::from_str(&cold_location) {
+                        Ok(snapshot_uri) => {
+                            return Ok(Box::new(warp::redirect::found(snapshot_uri))
+                                as Box<dyn warp::Reply>)
+                        }
+                        Err(err) => {
+                            warn!("snapshot_download::invalid_cold_location"; "location" => &cold_location, "error" => ?err);
+                            return Ok(reply::internal_server_error(err.to_string()));
+                        }
+                    }
+                }
+
+                match parse_range_header(range.as_deref(), snapshot.size) {
+                    RequestedRange::Bytes { start, end } => {
+                        return Ok(snapshot_range_download(
+                            &digest,
+                            start,
+                            end,
+                            snapshot.size,
+                            snapshot_store,
+                        )
+                        .await);
+                    }
+                    RequestedRange::NotSatisfiable => {
+                        warn!("snapshot_download::range_not_satisfiable"; "range" => ?range, "size" => snapshot.size);
+                        return Ok(Box::new(warp::reply::with_header(
+                            reply::empty(StatusCode::RANGE_NOT_SATISFIABLE),
+                            "Content-Range",
+                            format!("bytes */{}", snapshot.size),
+                        )));
+                    }
+                    RequestedRange::Full => {}
+                }
+
                 let snapshot_uri = format!(
                     "{}{}/snapshot_download/{}",
                     config.get_server_url(),
@@ -204,7 +453,11 @@ mod handlers {
                 );
                 let snapshot_uri = Uri::from_str(&snapshot_uri).unwrap();
 
-                Ok(Box::new(warp::redirect::found(snapshot_uri)) as Box<dyn warp::Reply>)
+                Ok(Box::new(warp::reply::with_header(
+                    warp::redirect::found(snapshot_uri),
+                    "Accept-Ranges",
+                    "bytes",
+                )) as Box<dyn warp::Reply>)
             }
             Ok(None) => {
                 warn!("snapshot_download::not_found");
@@ -216,16 +469,52 @@ mod handlers {
             }
         }
     }
+
+    /// Reply with the bytes from `start` to `end` of the archive of the snapshot with the given
+    /// digest, streamed from the snapshot store
+    async fn snapshot_range_download(
+        digest: &str,
+        start: u64,
+        end: u64,
+        size: u64,
+        snapshot_store: Arc<dyn SnapshotStore>,
+    ) -> Box<dyn warp::Reply> {
+        let reader = match snapshot_store.get_byte_range(digest, start, end).await {
+            Ok(reader) => reader,
+            Err(err) => {
+                warn!("snapshot_download::range_error"; "error" => ?err);
+                return reply::internal_server_error(err);
+            }
+        };
+
+        match Response::builder()
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header("Content-Type", "application/gzip")
+            .header("Content-Length", end - start + 1)
+            .header("Content-Range", format!("bytes {start}-{end}/{size}"))
+            .header("Accept-Ranges", "bytes")
+            .body(Body::wrap_stream(ReaderStream::new(reader)))
+        {
+            Ok(response) => Box::new(response),
+            Err(err) => {
+                warn!("snapshot_download::range_response_error"; "error" => ?err);
+                reply::internal_server_error(anyhow::anyhow!(err))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::http_server::routes::artifact_routes::test_utils::*;
     use crate::{
+        entities::{ManifestEntry, SnapshotManifest},
         http_server::SERVER_BASE_PATH,
         initialize_dependencies,
         message_adapters::{ToSnapshotListMessageAdapter, ToSnapshotMessageAdapter},
         services::{MockMessageService, MockSignedEntityService},
+        store::MockSnapshotStore,
+        RestoreStatus, SnapshotStorageClass,
     };
     use mithril_common::{
         entities::{CardanoDbBeacon, SignedEntityType, Snapshot},
@@ -263,8 +552,8 @@ mod tests {
         let message = ToSnapshotListMessageAdapter::adapt(signed_entities);
         let mut mock_http_message_service = MockMessageService::new();
         mock_http_message_service
-            .expect_get_snapshot_list_message()
-            .return_once(|_| Ok(message))
+            .expect_get_snapshot_list_message_with_etag()
+            .return_once(|_| Ok((message, "etag".to_string())))
             .once();
         let mut dependency_manager = initialize_dependencies().await;
         dependency_manager.message_service = Arc::new(mock_http_message_service);
@@ -294,7 +583,7 @@ mod tests {
     async fn test_snapshots_get_ko() {
         let mut mock_http_message_service = MockMessageService::new();
         mock_http_message_service
-            .expect_get_snapshot_list_message()
+            .expect_get_snapshot_list_message_with_etag()
             .return_once(|_| Err(HydrationError::InvalidData("invalid data".to_string()).into()))
             .once();
         let mut dependency_manager = initialize_dependencies().await;
@@ -321,6 +610,153 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_snapshots_get_not_modified_if_the_list_did_not_change() {
+        let dependency_manager = Arc::new(initialize_dependencies().await);
+
+        let method = Method::GET.as_str();
+        let path = "/artifact/snapshots";
+
+        let first_response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+        assert_eq!(StatusCode::OK, first_response.status());
+        let etag = first_response
+            .headers()
+            .get("etag")
+            .expect("an ETag header should be set")
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let second_response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .header("if-none-match", &etag)
+            .reply(&setup_router(dependency_manager))
+            .await;
+
+        assert!(second_response.body().is_empty());
+        assert_eq!(
+            Some(etag.as_str()),
+            second_response
+                .headers()
+                .get("etag")
+                .and_then(|etag| etag.to_str().ok())
+        );
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &second_response,
+            &StatusCode::NOT_MODIFIED,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_manifest_get_ok() {
+        let manifest = SnapshotManifest::new(vec![ManifestEntry {
+            digest: "digest-10".to_string(),
+            immutable_file_number: 10,
+            size_bytes: 100,
+            url: "http://10".to_string(),
+        }]);
+        let mut mock_signed_entity_service = MockSignedEntityService::new();
+        mock_signed_entity_service
+            .expect_generate_snapshot_manifest()
+            .withf(|from, to| *from == 5 && *to == 20)
+            .return_once(|_, _| Ok(manifest))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signed_entity_service = Arc::new(mock_signed_entity_service);
+
+        let method = Method::GET.as_str();
+        let path = "/artifact/snapshots/manifest";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}?from=5&to=20"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_manifest_get_with_inverted_range_returns_bad_request() {
+        let mut mock_signed_entity_service = MockSignedEntityService::new();
+        mock_signed_entity_service
+            .expect_generate_snapshot_manifest()
+            .never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signed_entity_service = Arc::new(mock_signed_entity_service);
+
+        let method = Method::GET.as_str();
+        let path = "/artifact/snapshots/manifest";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}?from=20&to=5"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::BAD_REQUEST,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_manifest_get_ko() {
+        let mut mock_signed_entity_service = MockSignedEntityService::new();
+        mock_signed_entity_service
+            .expect_generate_snapshot_manifest()
+            .return_once(|_, _| Err(HydrationError::InvalidData("invalid data".to_string()).into()))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signed_entity_service = Arc::new(mock_signed_entity_service);
+
+        let method = Method::GET.as_str();
+        let path = "/artifact/snapshots/manifest";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}?from=5&to=20"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .unwrap();
+    }
+
     #[tokio::test]
     async fn test_snapshot_digest_get_ok() {
         let signed_entity = create_signed_entities(
@@ -459,6 +895,74 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_snapshot_ipfs_download_returns_302_found_to_the_ipfs_gateway() {
+        let cid = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+        let signed_entity = create_signed_entity(
+            SignedEntityType::CardanoImmutableFilesFull(CardanoDbBeacon::default()),
+            Snapshot {
+                locations: vec![format!("ipfs://{cid}")],
+                ..fake_data::snapshots(1)[0].clone()
+            },
+        );
+        let mut mock_signed_entity_service = MockSignedEntityService::new();
+        mock_signed_entity_service
+            .expect_get_signed_snapshot_by_id()
+            .return_once(|_| Ok(Some(signed_entity)))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signed_entity_service = Arc::new(mock_signed_entity_service);
+        dependency_manager.config.ipfs_gateway_url = "https://gateway.ipfs.example".to_string();
+
+        let method = Method::GET.as_str();
+        let path = "/artifact/snapshot/{digest}/download";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(
+            format!("https://gateway.ipfs.example/ipfs/{cid}"),
+            std::str::from_utf8(response.headers()["location"].as_bytes()).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_ipfs_download_returns_500_with_an_invalid_ipfs_gateway_url() {
+        let signed_entity = create_signed_entity(
+            SignedEntityType::CardanoImmutableFilesFull(CardanoDbBeacon::default()),
+            Snapshot {
+                locations: vec![
+                    "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+                        .to_string(),
+                ],
+                ..fake_data::snapshots(1)[0].clone()
+            },
+        );
+        let mut mock_signed_entity_service = MockSignedEntityService::new();
+        mock_signed_entity_service
+            .expect_get_signed_snapshot_by_id()
+            .return_once(|_| Ok(Some(signed_entity)))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signed_entity_service = Arc::new(mock_signed_entity_service);
+        dependency_manager.config.ipfs_gateway_url = "invalid gateway url".to_string();
+
+        let method = Method::GET.as_str();
+        let path = "/artifact/snapshot/{digest}/download";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
     #[tokio::test]
     async fn test_snapshot_download_returns_404_not_found_when_no_snapshot() {
         let mut mock_signed_entity_service = MockSignedEntityService::new();
@@ -520,4 +1024,230 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_snapshot_download_returns_202_accepted_when_the_archive_is_being_restored() {
+        let mut mock_snapshot_store = MockSnapshotStore::new();
+        mock_snapshot_store
+            .expect_has_cold_storage()
+            .return_const(true);
+        mock_snapshot_store
+            .expect_get_storage_class()
+            .return_once(|_| Ok(Some(SnapshotStorageClass::ColdStorage)))
+            .once();
+        mock_snapshot_store
+            .expect_restore_from_cold()
+            .withf(|digest, restore_days| {
+                digest == "digest-123" && *restore_days == handlers::COLD_ARCHIVE_RESTORE_DAYS
+            })
+            .return_once(|_, _| Ok(RestoreStatus::Pending))
+            .once();
+        let mut mock_signed_entity_service = MockSignedEntityService::new();
+        mock_signed_entity_service
+            .expect_get_signed_snapshot_by_id()
+            .never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.snapshot_store = Arc::new(mock_snapshot_store);
+        dependency_manager.signed_entity_service = Arc::new(mock_signed_entity_service);
+
+        let method = Method::GET.as_str();
+        let path = "/artifact/snapshot/{digest}/download";
+
+        let response = request()
+            .method(method)
+            .path(&format!(
+                "/{SERVER_BASE_PATH}/artifact/snapshot/digest-123/download"
+            ))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(
+            handlers::COLD_ARCHIVE_RETRY_AFTER_SECONDS.to_string(),
+            std::str::from_utf8(response.headers()["retry-after"].as_bytes()).unwrap()
+        );
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/gzip",
+            &Null,
+            &response,
+            &StatusCode::ACCEPTED,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_download_redirects_when_the_archive_is_restored() {
+        let mut mock_snapshot_store = MockSnapshotStore::new();
+        mock_snapshot_store
+            .expect_has_cold_storage()
+            .return_const(true);
+        mock_snapshot_store
+            .expect_get_storage_class()
+            .return_once(|_| Ok(Some(SnapshotStorageClass::ColdStorage)))
+            .once();
+        mock_snapshot_store
+            .expect_restore_from_cold()
+            .return_once(|_, _| Ok(RestoreStatus::Available))
+            .once();
+        let signed_entity = create_signed_entity(
+            SignedEntityType::CardanoImmutableFilesFull(CardanoDbBeacon::default()),
+            fake_data::snapshots(1)[0].clone(),
+        );
+        let mut mock_signed_entity_service = MockSignedEntityService::new();
+        mock_signed_entity_service
+            .expect_get_signed_snapshot_by_id()
+            .return_once(|_| Ok(Some(signed_entity)))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.snapshot_store = Arc::new(mock_snapshot_store);
+        dependency_manager.signed_entity_service = Arc::new(mock_signed_entity_service);
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!(
+                "/{SERVER_BASE_PATH}/artifact/snapshot/digest-123/download"
+            ))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::FOUND);
+    }
+
+    async fn range_download_dependencies(snapshot_store: MockSnapshotStore) -> DependencyContainer {
+        let signed_entity = create_signed_entity(
+            SignedEntityType::CardanoImmutableFilesFull(CardanoDbBeacon::default()),
+            Snapshot {
+                size: 1000,
+                ..fake_data::snapshots(1)[0].clone()
+            },
+        );
+        let mut mock_signed_entity_service = MockSignedEntityService::new();
+        mock_signed_entity_service
+            .expect_get_signed_snapshot_by_id()
+            .return_once(|_| Ok(Some(signed_entity)))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.snapshot_store = Arc::new(snapshot_store);
+        dependency_manager.signed_entity_service = Arc::new(mock_signed_entity_service);
+
+        dependency_manager
+    }
+
+    #[test]
+    fn if_none_match_matches_the_etag() {
+        assert!(handlers::if_none_match_matches("\"etag\"", "etag"));
+        assert!(handlers::if_none_match_matches("W/\"etag\"", "etag"));
+        assert!(handlers::if_none_match_matches("*", "etag"));
+        assert!(handlers::if_none_match_matches(
+            "\"other\", W/\"etag\"",
+            "etag"
+        ));
+        assert!(!handlers::if_none_match_matches("\"other\"", "etag"));
+        assert!(!handlers::if_none_match_matches(
+            "\"other\", W/\"another\"",
+            "etag"
+        ));
+    }
+
+    #[test]
+    fn parse_range_header_of_a_download() {
+        assert_eq!(
+            handlers::RequestedRange::Full,
+            handlers::parse_range_header(None, 1000)
+        );
+        assert_eq!(
+            handlers::RequestedRange::Bytes { start: 0, end: 99 },
+            handlers::parse_range_header(Some("bytes=0-99"), 1000)
+        );
+        assert_eq!(
+            handlers::RequestedRange::Bytes {
+                start: 500,
+                end: 999
+            },
+            handlers::parse_range_header(Some("bytes=500-"), 1000)
+        );
+        assert_eq!(
+            handlers::RequestedRange::Bytes {
+                start: 900,
+                end: 999
+            },
+            handlers::parse_range_header(Some("bytes=-100"), 1000)
+        );
+        assert_eq!(
+            handlers::RequestedRange::Bytes {
+                start: 900,
+                end: 999
+            },
+            handlers::parse_range_header(Some("bytes=900-5000"), 1000)
+        );
+        assert_eq!(
+            handlers::RequestedRange::NotSatisfiable,
+            handlers::parse_range_header(Some("bytes=1000-1100"), 1000)
+        );
+        assert_eq!(
+            handlers::RequestedRange::Full,
+            handlers::parse_range_header(Some("bytes=0-9,20-29"), 1000)
+        );
+        assert_eq!(
+            handlers::RequestedRange::Full,
+            handlers::parse_range_header(Some("items=0-9"), 1000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_download_returns_206_partial_content_for_a_range() {
+        let mut mock_snapshot_store = MockSnapshotStore::new();
+        mock_snapshot_store
+            .expect_has_cold_storage()
+            .return_const(false);
+        mock_snapshot_store.expect_get_storage_class().never();
+        mock_snapshot_store
+            .expect_get_byte_range()
+            .withf(|_, start, end| *start == 100 && *end == 199)
+            .return_once(|_, _, _| Ok(Box::new(std::io::Cursor::new(vec![7u8; 100]))))
+            .once();
+        let dependency_manager = range_download_dependencies(mock_snapshot_store).await;
+
+        let method = Method::GET.as_str();
+        let path = "/artifact/snapshot/{digest}/download";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .header("range", "bytes=100-199")
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!("bytes 100-199/1000", response.headers()["content-range"]);
+        assert_eq!("bytes", response.headers()["accept-ranges"]);
+        assert_eq!(vec![7u8; 100], response.body().to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_download_returns_416_range_not_satisfiable_for_a_range_after_the_archive(
+    ) {
+        let mut mock_snapshot_store = MockSnapshotStore::new();
+        mock_snapshot_store
+            .expect_has_cold_storage()
+            .return_const(false);
+        mock_snapshot_store.expect_get_storage_class().never();
+        mock_snapshot_store.expect_get_byte_range().never();
+        let dependency_manager = range_download_dependencies(mock_snapshot_store).await;
+
+        let method = Method::GET.as_str();
+        let path = "/artifact/snapshot/{digest}/download";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .header("range", "bytes=1000-1999")
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!("bytes */1000", response.headers()["content-range"]);
+    }
 }
@@ -8,6 +8,8 @@ pub fn routes(
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     certificate_pending(dependency_manager.clone())
         .or(certificate_certificates(dependency_manager.clone()))
+        .or(certificates_export(dependency_manager.clone()))
+        .or(certificates_consistency(dependency_manager.clone()))
         .or(certificate_certificate_hash(dependency_manager))
 }
 
@@ -35,6 +37,31 @@ fn certificate_certificates(
         .and_then(handlers::certificate_certificates)
 }
 
+/// GET /certificates/export
+fn certificates_export(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("certificates" / "export")
+        .and(warp::get())
+        .and(middlewares::with_certificate_repository(dependency_manager))
+        .and_then(handlers::certificates_export)
+}
+
+/// GET /certificates/consistency
+///
+/// Admin route, only the clients in the admin IP allowlist can call it.
+fn certificates_consistency(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("certificates" / "consistency")
+        .and(warp::get())
+        .and(middlewares::with_admin_ip_allowlist(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_certificate_repository(dependency_manager))
+        .and_then(handlers::certificates_consistency)
+}
+
 /// GET /certificate/{certificate_hash}
 fn certificate_certificate_hash(
     dependency_manager: Arc<DependencyContainer>,
@@ -47,15 +74,19 @@ fn certificate_certificate_hash(
 
 mod handlers {
     use crate::{
-        http_server::routes::reply, services::MessageService, unwrap_to_internal_server_error,
-        CertificatePendingStore, Configuration, ToCertificatePendingMessageAdapter,
+        database::repository::CertificateRepository, http_server::routes::reply,
+        services::MessageService, unwrap_to_internal_server_error, CertificatePendingStore,
+        Configuration, ToCertificatePendingMessageAdapter,
     };
 
+    use futures::StreamExt;
+    use mithril_common::messages::CertificateMessage;
     use mithril_common::TickerService;
     use slog_scope::{debug, warn};
     use std::convert::Infallible;
     use std::sync::Arc;
-    use warp::http::StatusCode;
+    use warp::http::{header::CONTENT_TYPE, Response, StatusCode};
+    use warp::hyper::Body;
 
     pub const LIST_MAX_ITEMS: usize = 20;
 
@@ -109,6 +140,54 @@ mod handlers {
         }
     }
 
+    /// Export the whole certificate chain as newline-delimited JSON, from the latest certificate
+    ///
+    /// The certificates are streamed into the response body as they are read from the database.
+    /// An error while reading aborts the response since its status is already sent.
+    pub async fn certificates_export(
+        certificate_repository: Arc<CertificateRepository>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: certificates_export");
+
+        let lines = certificate_repository
+            .stream_certificates::<CertificateMessage>()
+            .map(|certificate| {
+                let mut line = serde_json::to_vec(&certificate.inspect_err(|err| {
+                    warn!("certificates_export::error"; "error" => ?err);
+                })?)?;
+                line.push(b'\n');
+
+                Ok::<_, anyhow::Error>(line)
+            });
+
+        match Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "application/x-ndjson")
+            .body(Body::wrap_stream(lines))
+        {
+            Ok(response) => Ok(Box::new(response) as Box<dyn warp::Reply>),
+            Err(err) => {
+                warn!("certificates_export::error"; "error" => ?err);
+                Ok(reply::internal_server_error(anyhow::anyhow!(err)))
+            }
+        }
+    }
+
+    /// Consistency report of the stored certificates
+    pub async fn certificates_consistency(
+        certificate_repository: Arc<CertificateRepository>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: certificates_consistency");
+
+        match certificate_repository.verify_consistency().await {
+            Ok(report) => Ok(reply::json(&report, StatusCode::OK)),
+            Err(err) => {
+                warn!("certificates_consistency::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err))
+            }
+        }
+    }
+
     /// Certificate by certificate hash
     pub async fn certificate_certificate_hash(
         certificate_hash: String,
@@ -137,7 +216,9 @@ mod handlers {
 mod tests {
     use anyhow::anyhow;
     use mithril_common::{
+        crypto_helper::tests_setup::setup_certificate_chain,
         entities::CertificatePending,
+        messages::CertificateMessage,
         test_utils::{apispec::APISpec, fake_data},
     };
     use mithril_persistence::store::adapter::DumbStoreAdapter;
@@ -167,6 +248,106 @@ mod tests {
             .and(routes(dependency_manager).with(cors))
     }
 
+    #[tokio::test]
+    async fn test_certificates_export_get_ok() {
+        let method = Method::GET.as_str();
+        let path = "/certificates/export";
+        let dependency_manager = initialize_dependencies().await;
+        dependency_manager
+            .certificate_repository
+            .create_certificate(fake_data::genesis_certificate("certificate-1"))
+            .await
+            .unwrap();
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/x-ndjson",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_certificates_export_streams_one_certificate_per_line() {
+        let dependency_manager = initialize_dependencies().await;
+        let certificates: Vec<_> = (1..=3)
+            .map(|i| fake_data::genesis_certificate(&format!("certificate-{i}")))
+            .collect();
+        dependency_manager
+            .certificate_repository
+            .create_many_certificates(certificates)
+            .await
+            .unwrap();
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!("/{SERVER_BASE_PATH}/certificates/export"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let hashes: Vec<String> = std::str::from_utf8(response.body())
+            .unwrap()
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<CertificateMessage>(line)
+                    .unwrap()
+                    .hash
+            })
+            .collect();
+        assert_eq!(
+            vec!["certificate-3", "certificate-2", "certificate-1"],
+            hashes
+        );
+    }
+
+    #[tokio::test]
+    async fn test_certificates_consistency_get_ok() {
+        let method = Method::GET.as_str();
+        let path = "/certificates/consistency";
+        let dependency_manager = initialize_dependencies().await;
+        let (mut certificates, _) = setup_certificate_chain(3, 1);
+        // The chain is built from the latest certificate, they must be stored from the oldest
+        certificates.reverse();
+        dependency_manager
+            .certificate_repository
+            .create_many_certificates(certificates)
+            .await
+            .unwrap();
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+        let report: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            serde_json::json!({ "checked": 3, "hash_mismatches": [], "broken_chain_links": [] }),
+            report
+        );
+    }
+
     #[tokio::test]
     async fn test_certificate_pending_with_content_get_ok_200() {
         let method = Method::GET.as_str();
@@ -1,12 +1,21 @@
 use crate::http_server::routes::middlewares;
 use crate::DependencyContainer;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use warp::Filter;
 
+#[derive(Deserialize, Serialize, Debug)]
+struct EpochStatsQueryParams {
+    from: u64,
+    to: u64,
+}
+
 pub fn routes(
     dependency_manager: Arc<DependencyContainer>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    epoch_settings(dependency_manager)
+    epoch_settings(dependency_manager.clone())
+        .or(epoch_rewards(dependency_manager.clone()))
+        .or(epoch_stats(dependency_manager))
 }
 
 /// GET /epoch-settings
@@ -19,16 +28,41 @@ fn epoch_settings(
         .and_then(handlers::epoch_settings)
 }
 
+/// GET /epochs/{epoch}/rewards
+fn epoch_rewards(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("epochs" / u64 / "rewards")
+        .and(warp::get())
+        .and(middlewares::with_reward_store(dependency_manager))
+        .and_then(handlers::epoch_rewards)
+}
+
+/// GET /epochs/stats?from={from}&to={to}
+fn epoch_stats(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("epochs" / "stats")
+        .and(warp::get())
+        .and(warp::query::<EpochStatsQueryParams>())
+        .and(middlewares::with_beacon_store(dependency_manager))
+        .and_then(handlers::epoch_stats)
+}
+
 mod handlers {
+    use crate::database::repository::{BeaconStore, RewardStorer};
     use crate::dependency_injection::EpochServiceWrapper;
     use crate::http_server::routes::reply;
     use crate::ToEpochSettingsMessageAdapter;
-    use mithril_common::entities::EpochSettings;
+    use mithril_common::entities::{Epoch, EpochSettings};
     use mithril_common::messages::ToMessageAdapter;
     use slog_scope::{debug, warn};
     use std::convert::Infallible;
+    use std::sync::Arc;
     use warp::http::StatusCode;
 
+    use super::EpochStatsQueryParams;
+
     /// Epoch Settings
     pub async fn epoch_settings(
         epoch_service: EpochServiceWrapper,
@@ -56,12 +90,57 @@ mod handlers {
             }
         }
     }
+
+    /// Rewards of the signers for an epoch
+    pub async fn epoch_rewards(
+        epoch: u64,
+        reward_store: Arc<dyn RewardStorer>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: epoch_rewards/{epoch}");
+
+        match reward_store.get_rewards_by_epoch(Epoch(epoch)).await {
+            Ok(rewards) => Ok(reply::json(&rewards, StatusCode::OK)),
+            Err(err) => {
+                warn!("epoch_rewards::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err))
+            }
+        }
+    }
+
+    /// Statistics of the beacons saved during each epoch of a range
+    pub async fn epoch_stats(
+        query_params: EpochStatsQueryParams,
+        beacon_store: Arc<dyn BeaconStore>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        let EpochStatsQueryParams { from, to } = query_params;
+        debug!("⇄ HTTP SERVER: epochs/stats?from={from}&to={to}");
+
+        if from > to {
+            warn!("epoch_stats::bad_request"; "from" => from, "to" => to);
+            return Ok(reply::bad_request(
+                "invalid_epoch_range".to_string(),
+                format!("'from' ({from}) must be lower or equal to 'to' ({to})"),
+            ));
+        }
+
+        match beacon_store
+            .aggregate_by_epoch(Epoch(from), Epoch(to))
+            .await
+        {
+            Ok(stats) => Ok(reply::json(&stats, StatusCode::OK)),
+            Err(err) => {
+                warn!("epoch_stats::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use mithril_common::{
-        entities::Epoch,
+        crypto_helper::tests_setup::setup_message,
+        entities::{Epoch, SingleSignatures},
         test_utils::{apispec::APISpec, MithrilFixtureBuilder},
     };
     use serde_json::Value::Null;
@@ -69,8 +148,13 @@ mod tests {
     use warp::http::{Method, StatusCode};
     use warp::test::request;
 
+    use crate::database::record::EpochBeaconStats;
+    use crate::database::repository::{MockBeaconStore, MockRewardStorer, RewardStore};
+    use crate::database::test_helper::main_db_connection;
+    use crate::entities::{RewardEntry, SignerContribution};
     use crate::http_server::SERVER_BASE_PATH;
     use crate::initialize_dependencies;
+    use crate::multi_signer::{MultiSigner, MultiSignerImpl};
     use crate::services::FakeEpochService;
 
     use super::*;
@@ -138,4 +222,215 @@ mod tests {
         )
         .unwrap();
     }
+
+    fn flat_rate_reward_policy(
+        contributions: &[SignerContribution],
+        epoch: Epoch,
+    ) -> Vec<RewardEntry> {
+        contributions
+            .iter()
+            .map(|contribution| RewardEntry {
+                epoch,
+                party_id: contribution.party_id.clone(),
+                reward: 10,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_epoch_rewards_get_ok_returns_one_entry_per_contributing_signer() {
+        let method = Method::GET.as_str();
+        let path = "/epochs/{epoch}/rewards";
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )))
+        .with_reward_store(Arc::new(RewardStore::new(Arc::new(
+            main_db_connection().unwrap(),
+        ))));
+        multi_signer
+            .set_reward_policy(Arc::new(flat_rate_reward_policy))
+            .await;
+        let message = setup_message();
+        let mut contributing_signers = vec![];
+        for signer_fixture in fixture.signers_fixture() {
+            if let Some(signature) = signer_fixture
+                .protocol_signer
+                .sign(message.compute_hash().as_bytes())
+            {
+                let won_indexes = signature.indexes.clone();
+                multi_signer
+                    .verify_single_signature(
+                        &message,
+                        &SingleSignatures::new(
+                            signer_fixture.signer_with_stake.party_id.to_owned(),
+                            signature.into(),
+                            won_indexes,
+                        ),
+                    )
+                    .await
+                    .unwrap();
+                contributing_signers.push(signer_fixture.signer_with_stake.party_id.to_owned());
+            }
+        }
+        let rewards = multi_signer.compute_rewards(epoch).await.unwrap();
+        let mut reward_store = MockRewardStorer::new();
+        reward_store
+            .expect_get_rewards_by_epoch()
+            .return_once(|_| Ok(rewards))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.reward_store = Arc::new(reward_store);
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}/epochs/5/rewards"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        let returned_rewards: Vec<RewardEntry> = serde_json::from_slice(response.body()).unwrap();
+        assert!(!contributing_signers.is_empty());
+        assert_eq!(contributing_signers.len(), returned_rewards.len());
+        for party_id in &contributing_signers {
+            assert!(returned_rewards
+                .iter()
+                .any(|reward| &reward.party_id == party_id));
+        }
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_epoch_rewards_get_ko_500() {
+        let method = Method::GET.as_str();
+        let path = "/epochs/{epoch}/rewards";
+        let mut reward_store = MockRewardStorer::new();
+        reward_store
+            .expect_get_rewards_by_epoch()
+            .return_once(|_| Err(anyhow::anyhow!("an error")))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.reward_store = Arc::new(reward_store);
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}/epochs/5/rewards"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .unwrap();
+    }
+    #[tokio::test]
+    async fn test_epoch_stats_get_ok() {
+        let method = Method::GET.as_str();
+        let path = "/epochs/stats";
+        let mut beacon_store = MockBeaconStore::new();
+        beacon_store
+            .expect_aggregate_by_epoch()
+            .withf(|from, to| *from == Epoch(1) && *to == Epoch(3))
+            .return_once(|_, _| {
+                Ok(vec![EpochBeaconStats {
+                    epoch: Epoch(1),
+                    beacon_count: 3,
+                    avg_slot_gap: 30.0,
+                    min_slot: 100,
+                    max_slot: 160,
+                }])
+            })
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.beacon_store = Arc::new(beacon_store);
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}?from=1&to=3"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_epoch_stats_get_with_inverted_range_returns_bad_request() {
+        let method = Method::GET.as_str();
+        let path = "/epochs/stats";
+        let mut beacon_store = MockBeaconStore::new();
+        beacon_store.expect_aggregate_by_epoch().never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.beacon_store = Arc::new(beacon_store);
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}?from=3&to=1"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::BAD_REQUEST,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_epoch_stats_get_ko_500() {
+        let method = Method::GET.as_str();
+        let path = "/epochs/stats";
+        let mut beacon_store = MockBeaconStore::new();
+        beacon_store
+            .expect_aggregate_by_epoch()
+            .return_once(|_, _| Err(anyhow::anyhow!("an error")))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.beacon_store = Arc::new(beacon_store);
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}?from=1&to=3"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .unwrap();
+    }
 }
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use warp::Filter;
+
+use crate::http_server::routes::middlewares;
+use crate::DependencyContainer;
+
+#[derive(Deserialize, Serialize, Debug)]
+struct EventsQueryParams {
+    limit: Option<usize>,
+}
+
+pub fn routes(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    events(dependency_manager)
+}
+
+/// GET /events
+///
+/// Admin route, only the clients in the admin IP allowlist can call it.
+fn events(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("events")
+        .and(warp::get())
+        .and(middlewares::with_admin_ip_allowlist(
+            dependency_manager.clone(),
+        ))
+        .and(warp::query::<EventsQueryParams>())
+        .and(middlewares::with_epoch_event_log(dependency_manager))
+        .and_then(handlers::events)
+}
+
+mod handlers {
+    use slog_scope::debug;
+    use std::convert::Infallible;
+    use warp::http::StatusCode;
+
+    use crate::http_server::routes::reply;
+    use crate::EpochEventLog;
+
+    use super::EventsQueryParams;
+
+    /// Last events of the runtime
+    pub async fn events(
+        query_params: EventsQueryParams,
+        epoch_event_log: EpochEventLog,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: events?limit={:?}", query_params.limit);
+
+        let events = epoch_event_log
+            .last_events(query_params.limit.unwrap_or(usize::MAX))
+            .await;
+
+        Ok(reply::json(&events, StatusCode::OK))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::{entities::Epoch, test_utils::apispec::APISpec};
+    use serde_json::Value::Null;
+    use warp::http::{Method, StatusCode};
+    use warp::test::request;
+
+    use crate::http_server::{routes::router::handle_custom, IpAllowlist, SERVER_BASE_PATH};
+    use crate::{initialize_dependencies, EpochEvent};
+
+    use super::*;
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyContainer>,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        let cors = warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec!["content-type"])
+            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+
+        warp::any()
+            .and(warp::path(SERVER_BASE_PATH))
+            .and(routes(dependency_manager).with(cors))
+            .recover(handle_custom)
+    }
+
+    fn dependency_manager_with_allowlist(
+        dependency_manager: DependencyContainer,
+        allowed_networks: &[&str],
+        trusted_proxies: &[&str],
+    ) -> Arc<DependencyContainer> {
+        let parse = |networks: &[&str]| networks.iter().map(|n| n.parse().unwrap()).collect();
+
+        Arc::new(DependencyContainer {
+            admin_ip_allowlist: Arc::new(IpAllowlist::new(
+                parse(allowed_networks),
+                parse(trusted_proxies),
+            )),
+            ..dependency_manager
+        })
+    }
+
+    #[tokio::test]
+    async fn test_events_get_ok() {
+        let method = Method::GET.as_str();
+        let path = "/events";
+        let dependency_manager = initialize_dependencies().await;
+        let event_log = dependency_manager.epoch_event_log.clone();
+        event_log.record(EpochEvent::SigningStarted(Epoch(3))).await;
+        event_log
+            .record(EpochEvent::CertificateIssued("hash".to_string()))
+            .await;
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}?limit=1"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+        let events: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            serde_json::json!([{ "type": "CertificateIssued", "value": "hash" }]),
+            events
+        );
+    }
+
+    #[tokio::test]
+    async fn test_events_get_from_an_allowed_ip_ok() {
+        let method = Method::GET.as_str();
+        let path = "/events";
+        let dependency_manager = dependency_manager_with_allowlist(
+            initialize_dependencies().await,
+            &["10.0.0.0/8"],
+            &[],
+        );
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .remote_addr("10.1.2.3:4321".parse().unwrap())
+            .reply(&setup_router(dependency_manager))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_events_get_from_a_disallowed_ip_forbidden() {
+        let method = Method::GET.as_str();
+        let path = "/events";
+        let dependency_manager = dependency_manager_with_allowlist(
+            initialize_dependencies().await,
+            &["10.0.0.0/8"],
+            &[],
+        );
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .remote_addr("203.0.113.7:4321".parse().unwrap())
+            .reply(&setup_router(dependency_manager))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::FORBIDDEN,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_events_get_behind_a_trusted_proxy_uses_the_forwarded_client_ip() {
+        let path = format!("/{SERVER_BASE_PATH}/events");
+        let router = setup_router(dependency_manager_with_allowlist(
+            initialize_dependencies().await,
+            &["10.0.0.0/8"],
+            &["172.16.0.0/12"],
+        ));
+
+        let allowed_response = request()
+            .path(&path)
+            .remote_addr("172.16.0.2:4321".parse().unwrap())
+            .header("x-forwarded-for", "10.1.2.3")
+            .reply(&router)
+            .await;
+        let disallowed_response = request()
+            .path(&path)
+            .remote_addr("172.16.0.2:4321".parse().unwrap())
+            .header("x-forwarded-for", "203.0.113.7")
+            .reply(&router)
+            .await;
+
+        assert_eq!(StatusCode::OK, allowed_response.status());
+        assert_eq!(StatusCode::FORBIDDEN, disallowed_response.status());
+    }
+}
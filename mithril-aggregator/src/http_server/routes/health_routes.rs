@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use warp::Filter;
+
+use crate::http_server::routes::middlewares;
+use crate::DependencyContainer;
+
+/// Result of the readiness checks of the aggregator
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReadinessMessage {
+    /// Description of the checks that failed, empty if the aggregator is ready
+    pub failed_checks: Vec<String>,
+}
+
+pub fn routes(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    livez().or(readyz(dependency_manager))
+}
+
+/// GET /livez
+fn livez() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("livez")
+        .and(warp::get())
+        .and_then(handlers::livez)
+}
+
+/// GET /readyz
+fn readyz(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("readyz")
+        .and(warp::get())
+        .and(middlewares::with_config(dependency_manager.clone()))
+        .and(middlewares::with_sqlite_connection(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_time_point_tracker(dependency_manager))
+        .and_then(handlers::readyz)
+}
+
+mod handlers {
+    use mithril_persistence::sqlite::SqliteConnection;
+    use slog_scope::{debug, warn};
+    use std::{convert::Infallible, sync::Arc, time::Duration};
+    use warp::http::StatusCode;
+
+    use crate::http_server::routes::reply;
+    use crate::{Configuration, TimePointTracker};
+
+    use super::ReadinessMessage;
+
+    /// Maximum time given to the Tokio runtime to schedule a task
+    const LIVENESS_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Liveness probe
+    pub async fn livez() -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: livez");
+
+        match tokio::time::timeout(LIVENESS_TIMEOUT, tokio::spawn(async {})).await {
+            Ok(Ok(())) => Ok(reply::empty(StatusCode::OK)),
+            Ok(Err(error)) => {
+                warn!("livez::task_failed"; "error" => ?error);
+                Ok(reply::empty(StatusCode::SERVICE_UNAVAILABLE))
+            }
+            Err(_) => {
+                warn!("livez::timeout"; "timeout" => ?LIVENESS_TIMEOUT);
+                Ok(reply::empty(StatusCode::SERVICE_UNAVAILABLE))
+            }
+        }
+    }
+
+    /// Readiness probe
+    pub async fn readyz(
+        config: Configuration,
+        sqlite_connection: Arc<SqliteConnection>,
+        time_point_tracker: TimePointTracker,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: readyz");
+
+        let mut failed_checks = vec![];
+        if let Err(error) = sqlite_connection.execute("select 1") {
+            failed_checks.push(format!("SQLite database is not reachable: {error}"));
+        }
+
+        let max_age = Duration::from_secs(config.readiness_time_point_max_age);
+        if time_point_tracker.is_stale(max_age).await {
+            failed_checks.push(format!(
+                "No time point was read from the chain during the last {max_age:?}"
+            ));
+        }
+
+        if failed_checks.is_empty() {
+            Ok(reply::json(
+                &ReadinessMessage { failed_checks },
+                StatusCode::OK,
+            ))
+        } else {
+            warn!("readyz::not_ready"; "failed_checks" => ?failed_checks);
+            Ok(reply::json(
+                &ReadinessMessage { failed_checks },
+                StatusCode::SERVICE_UNAVAILABLE,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use mithril_common::{entities::TimePoint, test_utils::apispec::APISpec};
+    use serde_json::Value::Null;
+    use warp::http::{Method, StatusCode};
+    use warp::test::request;
+
+    use crate::http_server::SERVER_BASE_PATH;
+    use crate::initialize_dependencies;
+
+    use super::*;
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyContainer>,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        let cors = warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec!["content-type"])
+            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+
+        warp::any()
+            .and(warp::path(SERVER_BASE_PATH))
+            .and(routes(dependency_manager).with(cors))
+    }
+
+    fn stale_time_point_date(dependency_manager: &DependencyContainer) -> chrono::DateTime<Utc> {
+        let max_age = dependency_manager.config.readiness_time_point_max_age as i64;
+
+        Utc::now() - chrono::Duration::seconds(max_age + 1)
+    }
+
+    #[tokio::test]
+    async fn test_readyz_get_ok() {
+        let method = Method::GET.as_str();
+        let path = "/readyz";
+        let dependency_manager = initialize_dependencies().await;
+        dependency_manager
+            .time_point_tracker
+            .record(TimePoint::dummy())
+            .await;
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_readyz_and_livez_with_stale_time_point() {
+        let dependency_manager = initialize_dependencies().await;
+        dependency_manager
+            .time_point_tracker
+            .record_at(
+                TimePoint::dummy(),
+                stale_time_point_date(&dependency_manager),
+            )
+            .await;
+        let router = setup_router(Arc::new(dependency_manager));
+
+        let method = Method::GET.as_str();
+        let path = "/readyz";
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&router)
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::SERVICE_UNAVAILABLE,
+        )
+        .unwrap();
+        let message: ReadinessMessage = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(1, message.failed_checks.len());
+
+        let path = "/livez";
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&router)
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+}
@@ -1,18 +1,29 @@
+use slog_scope::warn;
 use std::convert::Infallible;
+use std::io::Read;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
-use warp::Filter;
+use serde::de::DeserializeOwned;
+use warp::hyper::body::Bytes;
+use warp::{Filter, Rejection};
 
 use mithril_common::entities::SignedEntityConfig;
 use mithril_common::{api_version::APIVersionProvider, TickerService};
+use mithril_persistence::sqlite::SqliteConnection;
 
-use crate::database::repository::SignerGetter;
+use crate::database::repository::{BeaconStore, CertificateRepository, RewardStorer, SignerGetter};
 use crate::dependency_injection::EpochServiceWrapper;
 use crate::event_store::{EventMessage, TransmitterService};
+use crate::http_server::{
+    routes::router::{CompressedBodyDecodingError, IpNotAllowedError},
+    IpAllowlist,
+};
 use crate::services::{CertifierService, MessageService, ProverService, SignedEntityService};
 use crate::{
-    CertificatePendingStore, Configuration, DependencyContainer, SignerRegisterer,
-    VerificationKeyStorer,
+    CertificatePendingStore, Configuration, DependencyContainer, EpochEventLog,
+    PendingOperationsLimiter, SignerRegisterer, SnapshotStore, TieredSnapshotStore,
+    TimePointTracker, VerificationKeyStorer,
 };
 
 /// With certificate pending store
@@ -22,6 +33,135 @@ pub(crate) fn with_certificate_pending_store(
     warp::any().map(move || dependency_manager.certificate_pending_store.clone())
 }
 
+/// With certificate repository middleware
+pub(crate) fn with_certificate_repository(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (Arc<CertificateRepository>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.certificate_repository.clone())
+}
+
+/// With epoch event log middleware
+pub fn with_epoch_event_log(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (EpochEventLog,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.epoch_event_log.clone())
+}
+
+/// With pending operations limiter middleware
+pub fn with_pending_operations_limiter(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (Arc<PendingOperationsLimiter>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.pending_operations_limiter.clone())
+}
+
+/// Reject the requests of the clients that are not in the admin routes IP allowlist
+pub fn with_admin_ip_allowlist(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and(warp::header::optional::<String>("x-forwarded-for"))
+        .and(warp::any().map(move || dependency_manager.admin_ip_allowlist.clone()))
+        .and_then(
+            |remote_addr: Option<SocketAddr>,
+             forwarded_for: Option<String>,
+             allowlist: Arc<IpAllowlist>| async move {
+                if allowlist.is_allowed(remote_addr, forwarded_for.as_deref()) {
+                    Ok(())
+                } else {
+                    warn!(
+                        "⇄ HTTP SERVER::ip_allowlist::client not allowed";
+                        "remote_addr" => ?remote_addr, "forwarded_for" => ?forwarded_for
+                    );
+                    Err(warp::reject::custom(IpNotAllowedError))
+                }
+            },
+        )
+        .untuple_one()
+}
+
+/// Maximum size of a zstd compressed request body once decompressed, so a small compressed body
+/// can't exhaust the memory of the aggregator
+pub const MAX_DECOMPRESSED_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Maximum size of a request body as it is sent, compressed or not, larger bodies are rejected
+/// before being read
+pub const MAX_BODY_SIZE: u64 = MAX_DECOMPRESSED_BODY_SIZE;
+
+fn decompress_zstd_body(body: &[u8], limit: u64) -> Result<Vec<u8>, String> {
+    let decoder = zstd::stream::read::Decoder::new(body)
+        .map_err(|e| format!("Could not decompress zstd body: {e}"))?;
+    let mut decompressed = Vec::new();
+    decoder
+        .take(limit + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| format!("Could not decompress zstd body: {e}"))?;
+    if decompressed.len() as u64 > limit {
+        return Err(format!(
+            "Could not decompress zstd body: decompressed body exceeds {limit} bytes"
+        ));
+    }
+
+    Ok(decompressed)
+}
+
+/// Deserialize the JSON body of the request, the body is decompressed first if it is sent with
+/// a `Content-Encoding: zstd` header
+pub fn json_body<T: DeserializeOwned + Send>(
+) -> impl Filter<Extract = (T,), Error = Rejection> + Clone {
+    // The compressed branch must not reject once the body is read, otherwise the uncompressed
+    // branch would be tried with an already consumed body
+    let compressed = warp::header::exact_ignore_case("content-encoding", "zstd")
+        .and(warp::body::content_length_limit(MAX_BODY_SIZE))
+        .and(warp::body::bytes())
+        .map(|body: Bytes| {
+            decompress_zstd_body(body.as_ref(), MAX_DECOMPRESSED_BODY_SIZE).and_then(|json| {
+                serde_json::from_slice::<T>(&json)
+                    .map_err(|e| format!("Could not deserialize decompressed body: {e}"))
+            })
+        });
+    let uncompressed = warp::body::content_length_limit(MAX_BODY_SIZE)
+        .and(warp::body::json::<T>())
+        .map(Ok::<T, String>);
+
+    compressed
+        .or(uncompressed)
+        .unify()
+        .and_then(|body: Result<T, String>| async move {
+            body.map_err(|message| {
+                warn!("⇄ HTTP SERVER::json_body::invalid compressed body"; "error" => &message);
+                warp::reject::custom(CompressedBodyDecodingError(message))
+            })
+        })
+}
+
+/// With time point tracker middleware
+pub fn with_time_point_tracker(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (TimePointTracker,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.time_point_tracker.clone())
+}
+
+/// With sqlite connection middleware
+pub fn with_sqlite_connection(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (Arc<SqliteConnection>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.sqlite_connection.clone())
+}
+
+/// With beacon store middleware
+pub fn with_beacon_store(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (Arc<dyn BeaconStore>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.beacon_store.clone())
+}
+
+/// With reward store middleware
+pub fn with_reward_store(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (Arc<dyn RewardStorer>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.reward_store.clone())
+}
+
 /// With signer registerer middleware
 pub fn with_signer_registerer(
     dependency_manager: Arc<DependencyContainer>,
@@ -85,6 +225,20 @@ pub fn with_signed_entity_service(
     warp::any().map(move || dependency_manager.signed_entity_service.clone())
 }
 
+/// With snapshot store middleware
+pub fn with_snapshot_store(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (Arc<dyn SnapshotStore>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.snapshot_store.clone())
+}
+
+/// With tiered snapshot store
+pub fn with_tiered_snapshot_store(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (Option<Arc<TieredSnapshotStore>>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.tiered_snapshot_store.clone())
+}
+
 /// With verification key store
 pub fn with_verification_key_store(
     dependency_manager: Arc<DependencyContainer>,
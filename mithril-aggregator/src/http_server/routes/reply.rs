@@ -20,6 +20,14 @@ pub fn bad_request(label: String, message: String) -> Box<dyn warp::Reply> {
     json(&ClientError::new(label, message), StatusCode::BAD_REQUEST)
 }
 
+pub fn forbidden(label: String, message: String) -> Box<dyn warp::Reply> {
+    json(&ClientError::new(label, message), StatusCode::FORBIDDEN)
+}
+
+pub fn conflict(label: String, message: String) -> Box<dyn warp::Reply> {
+    json(&ClientError::new(label, message), StatusCode::CONFLICT)
+}
+
 pub fn internal_server_error<T: Into<InternalServerError>>(message: T) -> Box<dyn warp::Reply> {
     json(&message.into(), StatusCode::INTERNAL_SERVER_ERROR)
 }
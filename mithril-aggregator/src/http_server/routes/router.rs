@@ -1,6 +1,6 @@
 use crate::http_server::routes::{
-    artifact_routes, certificate_routes, epoch_routes, root_routes, signatures_routes,
-    signer_routes, statistics_routes,
+    artifact_routes, certificate_routes, epoch_routes, events_routes, health_routes, root_routes,
+    signatures_routes, signer_routes, statistics_routes,
 };
 use crate::http_server::SERVER_BASE_PATH;
 use crate::DependencyContainer;
@@ -15,7 +15,7 @@ use warp::http::StatusCode;
 use warp::reject::Reject;
 use warp::{Filter, Rejection, Reply};
 
-use super::{middlewares, proof_routes};
+use super::{middlewares, proof_routes, reply};
 
 #[derive(Debug)]
 pub struct VersionMismatchError;
@@ -27,13 +27,27 @@ pub struct VersionParseError;
 
 impl Reject for VersionParseError {}
 
+#[derive(Debug)]
+pub struct IpNotAllowedError;
+
+impl Reject for IpNotAllowedError {}
+
+#[derive(Debug)]
+pub struct CompressedBodyDecodingError(pub String);
+
+impl Reject for CompressedBodyDecodingError {}
+
 /// Routes
 pub fn routes(
     dependency_manager: Arc<DependencyContainer>,
 ) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
     let cors = warp::cors()
         .allow_any_origin()
-        .allow_headers(vec!["content-type", MITHRIL_API_VERSION_HEADER])
+        .allow_headers(vec![
+            "content-type",
+            "content-encoding",
+            MITHRIL_API_VERSION_HEADER,
+        ])
         .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
 
     warp::any()
@@ -57,6 +71,8 @@ pub fn routes(
                 .or(signatures_routes::routes(dependency_manager.clone()))
                 .or(epoch_routes::routes(dependency_manager.clone()))
                 .or(statistics_routes::routes(dependency_manager.clone()))
+                .or(events_routes::routes(dependency_manager.clone()))
+                .or(health_routes::routes(dependency_manager.clone()))
                 .or(root_routes::routes(dependency_manager.clone()))
                 .with(cors),
         )
@@ -107,7 +123,17 @@ fn header_must_be(
 
 pub async fn handle_custom(reject: Rejection) -> Result<impl Reply, Rejection> {
     if reject.find::<VersionMismatchError>().is_some() {
-        Ok(StatusCode::PRECONDITION_FAILED)
+        Ok(reply::empty(StatusCode::PRECONDITION_FAILED))
+    } else if reject.find::<IpNotAllowedError>().is_some() {
+        Ok(reply::forbidden(
+            "ip_not_allowed".to_string(),
+            "The client IP is not allowed to call this route".to_string(),
+        ))
+    } else if let Some(CompressedBodyDecodingError(message)) = reject.find() {
+        Ok(reply::bad_request(
+            "invalid_compressed_body".to_string(),
+            message.clone(),
+        ))
     } else {
         Err(reject)
     }
@@ -6,24 +6,63 @@ use warp::Filter;
 pub fn routes(
     dependency_manager: Arc<DependencyContainer>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    register_signatures(dependency_manager)
+    register_signatures(dependency_manager.clone())
+        .or(register_signatures_batch(dependency_manager))
 }
 
 /// POST /register-signatures
+///
+/// The body can be compressed with zstd, in which case it must be sent with a
+/// `Content-Encoding: zstd` header.
 fn register_signatures(
     dependency_manager: Arc<DependencyContainer>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("register-signatures")
         .and(warp::post())
-        .and(warp::body::json())
+        .and(middlewares::json_body())
         .and(middlewares::with_certifier_service(
             dependency_manager.clone(),
         ))
         .and(middlewares::with_ticker_service(dependency_manager.clone()))
-        .and(middlewares::with_signed_entity_config(dependency_manager))
+        .and(middlewares::with_signed_entity_config(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_epoch_event_log(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_pending_operations_limiter(
+            dependency_manager,
+        ))
         .and_then(handlers::register_signatures)
 }
 
+/// POST /register-signatures-batch
+///
+/// The signatures of the batch are registered atomically: either all of them are registered or
+/// none of them. The body can be compressed with zstd, in which case it must be sent with a
+/// `Content-Encoding: zstd` header.
+fn register_signatures_batch(
+    dependency_manager: Arc<DependencyContainer>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("register-signatures-batch")
+        .and(warp::post())
+        .and(middlewares::json_body())
+        .and(middlewares::with_certifier_service(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_ticker_service(dependency_manager.clone()))
+        .and(middlewares::with_signed_entity_config(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_epoch_event_log(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_pending_operations_limiter(
+            dependency_manager,
+        ))
+        .and_then(handlers::register_signatures_batch)
+}
+
 mod handlers {
     use slog_scope::{debug, trace, warn};
     use std::convert::Infallible;
@@ -32,12 +71,13 @@ mod handlers {
 
     use mithril_common::entities::{SignedEntityConfig, SignedEntityTypeDiscriminants};
     use mithril_common::messages::{RegisterSignatureMessage, TryFromMessageAdapter};
-    use mithril_common::TickerService;
+    use mithril_common::{StdResult, TickerService};
 
     use crate::{
         http_server::routes::reply,
         message_adapters::FromRegisterSingleSignatureAdapter,
         services::{CertifierService, CertifierServiceError},
+        EpochEvent, EpochEventLog, PendingOperationsLimiter,
     };
 
     /// Register Signatures
@@ -46,6 +86,8 @@ mod handlers {
         certifier_service: Arc<dyn CertifierService>,
         ticker_service: Arc<dyn TickerService>,
         signed_entity_config: SignedEntityConfig,
+        epoch_event_log: EpochEventLog,
+        pending_operations_limiter: Arc<PendingOperationsLimiter>,
     ) -> Result<impl warp::Reply, Infallible> {
         debug!("⇄ HTTP SERVER: register_signatures/{:?}", message);
         trace!("⇄ HTTP SERVER: register_signatures"; "complete_message" => #?message );
@@ -74,6 +116,16 @@ mod handlers {
                     }
                 };
 
+                // Wait for the previous signatures to be stored instead of piling up the
+                // pending registrations in memory
+                let _permit = match pending_operations_limiter.acquire().await {
+                    Ok(permit) => permit,
+                    Err(err) => {
+                        warn!("register_signatures::pending_operations_limiter_error"; "error" => ?err);
+                        return Ok(reply::internal_server_error(err));
+                    }
+                };
+
                 match certifier_service
                     .register_single_signature(&signed_entity_type, &signatures)
                     .await
@@ -92,7 +144,12 @@ mod handlers {
                             Ok(reply::internal_server_error(err))
                         }
                     },
-                    Ok(()) => Ok(reply::empty(StatusCode::CREATED)),
+                    Ok(()) => {
+                        epoch_event_log
+                            .record(EpochEvent::SignatureReceived(signatures.party_id))
+                            .await;
+                        Ok(reply::empty(StatusCode::CREATED))
+                    }
                 }
             }
             Err(err) => {
@@ -101,22 +158,135 @@ mod handlers {
             }
         }
     }
+
+    /// Register a batch of Signatures of the same signed entity type
+    pub async fn register_signatures_batch(
+        messages: Vec<RegisterSignatureMessage>,
+        certifier_service: Arc<dyn CertifierService>,
+        ticker_service: Arc<dyn TickerService>,
+        signed_entity_config: SignedEntityConfig,
+        epoch_event_log: EpochEventLog,
+        pending_operations_limiter: Arc<PendingOperationsLimiter>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!(
+            "⇄ HTTP SERVER: register_signatures_batch/{} signatures",
+            messages.len()
+        );
+        trace!("⇄ HTTP SERVER: register_signatures_batch"; "complete_message" => #?messages );
+
+        if messages.is_empty() {
+            return Ok(reply::bad_request(
+                "Empty signatures batch".to_string(),
+                "The batch must contain at least one signature".to_string(),
+            ));
+        }
+
+        // Signatures without a signed entity type are for the current immutable files snapshot
+        let current_signed_entity_type = if messages
+            .iter()
+            .any(|message| message.signed_entity_type.is_none())
+        {
+            match ticker_service.get_current_time_point().await {
+                Ok(time_point) => Some(signed_entity_config.time_point_to_signed_entity(
+                    SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                    &time_point,
+                )),
+                Err(err) => {
+                    warn!("register_signatures_batch::cant_retrieve_signed_entity_type"; "error" => ?err);
+                    return Ok(reply::internal_server_error(err));
+                }
+            }
+        } else {
+            None
+        };
+        let signed_entity_types: Vec<_> = messages
+            .iter()
+            .filter_map(|message| {
+                message
+                    .signed_entity_type
+                    .clone()
+                    .or_else(|| current_signed_entity_type.clone())
+            })
+            .collect();
+        let signed_entity_type = signed_entity_types[0].clone();
+        if signed_entity_types.iter().any(|t| *t != signed_entity_type) {
+            return Ok(reply::bad_request(
+                "Mixed signed entity types".to_string(),
+                "All the signatures of a batch must sign the same signed entity type".to_string(),
+            ));
+        }
+
+        let signatures = match messages
+            .into_iter()
+            .map(FromRegisterSingleSignatureAdapter::try_adapt)
+            .collect::<StdResult<Vec<_>>>()
+        {
+            Ok(signatures) => signatures,
+            Err(err) => {
+                warn!("register_signatures_batch::payload decoding error"; "error" => ?err);
+
+                return Ok(reply::bad_request(
+                    "Could not decode signature payload".to_string(),
+                    err.to_string(),
+                ));
+            }
+        };
+
+        let _permit = match pending_operations_limiter.acquire().await {
+            Ok(permit) => permit,
+            Err(err) => {
+                warn!("register_signatures_batch::pending_operations_limiter_error"; "error" => ?err);
+                return Ok(reply::internal_server_error(err));
+            }
+        };
+
+        match certifier_service
+            .register_single_signatures(&signed_entity_type, &signatures)
+            .await
+        {
+            Err(err) => match err.downcast_ref::<CertifierServiceError>() {
+                Some(CertifierServiceError::AlreadyCertified(signed_entity_type)) => {
+                    debug!("register_signatures_batch::open_message_already_certified"; "signed_entity_type" => ?signed_entity_type);
+                    Ok(reply::empty(StatusCode::GONE))
+                }
+                Some(CertifierServiceError::NotFound(signed_entity_type)) => {
+                    debug!("register_signatures_batch::not_found"; "signed_entity_type" => ?signed_entity_type);
+                    Ok(reply::empty(StatusCode::NOT_FOUND))
+                }
+                Some(_) | None => {
+                    warn!("register_signatures_batch::error"; "error" => ?err);
+                    Ok(reply::internal_server_error(err))
+                }
+            },
+            Ok(()) => {
+                for signature in signatures {
+                    epoch_event_log
+                        .record(EpochEvent::SignatureReceived(signature.party_id))
+                        .await;
+                }
+                Ok(reply::empty(StatusCode::CREATED))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use anyhow::anyhow;
+    use std::time::Duration;
     use warp::http::{Method, StatusCode};
     use warp::test::request;
 
     use mithril_common::{
-        entities::SignedEntityType, messages::RegisterSignatureMessage,
+        entities::{Epoch, SignedEntityType},
+        messages::RegisterSignatureMessage,
         test_utils::apispec::APISpec,
     };
 
     use crate::{
-        http_server::SERVER_BASE_PATH,
+        http_server::{routes::router::handle_custom, SERVER_BASE_PATH},
         initialize_dependencies,
+        runtime::PendingOperationsLimiter,
         services::{CertifierServiceError, MockCertifierService},
     };
 
@@ -133,6 +303,7 @@ mod tests {
         warp::any()
             .and(warp::path(SERVER_BASE_PATH))
             .and(routes(dependency_manager).with(cors))
+            .recover(handle_custom)
     }
 
     #[tokio::test]
@@ -168,6 +339,151 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_register_signatures_post_zstd_compressed_ok() {
+        let message = RegisterSignatureMessage::dummy();
+        let expected_party_id = message.party_id.clone();
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signature()
+            .withf(move |_, signatures| signatures.party_id == expected_party_id)
+            .return_once(move |_, _| Ok(()));
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+
+        let method = Method::POST.as_str();
+        let path = "/register-signatures";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .header("content-type", "application/json")
+            .header("content-encoding", "zstd")
+            .body(zstd::encode_all(serde_json::to_vec(&message).unwrap().as_slice(), 0).unwrap())
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &message,
+            &response,
+            &StatusCode::CREATED,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_register_signatures_post_ko_400_zstd_body_too_large_once_decompressed() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signature()
+            .never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+
+        let message = RegisterSignatureMessage::dummy();
+        let mut json = serde_json::to_vec(&message).unwrap();
+        json.resize(
+            json.len() + middlewares::MAX_DECOMPRESSED_BODY_SIZE as usize,
+            b' ',
+        );
+
+        let method = Method::POST.as_str();
+        let path = "/register-signatures";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .header("content-type", "application/json")
+            .header("content-encoding", "zstd")
+            .body(zstd::encode_all(json.as_slice(), 0).unwrap())
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &message,
+            &response,
+            &StatusCode::BAD_REQUEST,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_register_signatures_post_ko_413_body_too_large() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signature()
+            .never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+        let router = setup_router(Arc::new(dependency_manager));
+
+        let mut json = serde_json::to_vec(&RegisterSignatureMessage::dummy()).unwrap();
+        json.resize(json.len() + middlewares::MAX_BODY_SIZE as usize, b' ');
+        let path = format!("/{SERVER_BASE_PATH}/register-signatures");
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .header("content-type", "application/json")
+            .body(json.clone())
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .header("content-type", "application/json")
+            .header("content-encoding", "zstd")
+            .body(json)
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+    }
+
+    #[tokio::test]
+    async fn test_register_signatures_post_ko_400_invalid_zstd_body() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signature()
+            .never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+
+        let message = RegisterSignatureMessage::dummy();
+
+        let method = Method::POST.as_str();
+        let path = "/register-signatures";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .header("content-type", "application/json")
+            .header("content-encoding", "zstd")
+            .json(&message)
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &message,
+            &response,
+            &StatusCode::BAD_REQUEST,
+        )
+        .unwrap();
+    }
+
     #[tokio::test]
     async fn test_register_signatures_post_ko_400() {
         let mut mock_certifier_service = MockCertifierService::new();
@@ -272,6 +588,163 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_register_signatures_batch_post_ok() {
+        let messages = vec![
+            RegisterSignatureMessage::dummy(),
+            RegisterSignatureMessage {
+                party_id: "party_id_2".to_string(),
+                ..RegisterSignatureMessage::dummy()
+            },
+        ];
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signatures()
+            .withf(|_, signatures| {
+                signatures
+                    .iter()
+                    .map(|signature| signature.party_id.as_str())
+                    .eq(["party_id", "party_id_2"])
+            })
+            .return_once(move |_, _| Ok(()))
+            .once();
+        mock_certifier_service
+            .expect_register_single_signature()
+            .never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+
+        let method = Method::POST.as_str();
+        let path = "/register-signatures-batch";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .json(&messages)
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &messages,
+            &response,
+            &StatusCode::CREATED,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_register_signatures_batch_post_ko_400_mixed_signed_entity_types() {
+        let messages = vec![
+            RegisterSignatureMessage::dummy(),
+            RegisterSignatureMessage {
+                signed_entity_type: Some(SignedEntityType::MithrilStakeDistribution(Epoch(1234))),
+                ..RegisterSignatureMessage::dummy()
+            },
+        ];
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signatures()
+            .never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+
+        let method = Method::POST.as_str();
+        let path = "/register-signatures-batch";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .json(&messages)
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &messages,
+            &response,
+            &StatusCode::BAD_REQUEST,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_register_signatures_batch_post_ko_410() {
+        let signed_entity_type = SignedEntityType::dummy();
+        let messages = vec![RegisterSignatureMessage::dummy()];
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signatures()
+            .return_once(move |_, _| {
+                Err(CertifierServiceError::AlreadyCertified(signed_entity_type).into())
+            });
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+
+        let method = Method::POST.as_str();
+        let path = "/register-signatures-batch";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .json(&messages)
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &messages,
+            &response,
+            &StatusCode::GONE,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_register_signatures_post_waits_while_too_many_signatures_are_being_registered() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signature()
+            .return_once(move |_, _| Ok(()));
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+        let pending_operations_limiter = Arc::new(PendingOperationsLimiter::new(1).unwrap());
+        dependency_manager.pending_operations_limiter = pending_operations_limiter.clone();
+        let permit = pending_operations_limiter.acquire().await.unwrap();
+
+        let message = RegisterSignatureMessage::dummy();
+        let mut registration = tokio::spawn(async move {
+            request()
+                .method(Method::POST.as_str())
+                .path(&format!("/{SERVER_BASE_PATH}/register-signatures"))
+                .json(&message)
+                .reply(&setup_router(Arc::new(dependency_manager)))
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(
+            !registration.is_finished(),
+            "the registration should wait for a pending operation to complete"
+        );
+
+        drop(permit);
+        let response = tokio::time::timeout(Duration::from_secs(5), &mut registration)
+            .await
+            .expect("the registration should complete once a permit is released")
+            .unwrap();
+
+        assert_eq!(StatusCode::CREATED, response.status());
+    }
+
     #[tokio::test]
     async fn test_register_signatures_post_ko_500() {
         let mut mock_certifier_service = MockCertifierService::new();
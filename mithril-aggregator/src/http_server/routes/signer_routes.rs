@@ -31,7 +31,11 @@ fn register_signer(
         .and(middlewares::with_event_transmitter(
             dependency_manager.clone(),
         ))
-        .and(middlewares::with_ticker_service(dependency_manager))
+        .and(middlewares::with_ticker_service(dependency_manager.clone()))
+        .and(middlewares::with_certificate_pending_store(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_epoch_event_log(dependency_manager))
         .and_then(handlers::register_signer)
 }
 
@@ -63,7 +67,8 @@ mod handlers {
     };
     use crate::event_store::{EventMessage, TransmitterService};
     use crate::{
-        http_server::routes::reply, Configuration, SignerRegisterer, SignerRegistrationError,
+        http_server::routes::reply, CertificatePendingStore, Configuration, EpochEvent,
+        EpochEventLog, SignerRegisterer, SignerRegistrationError,
     };
     use crate::{FromRegisterSignerAdapter, VerificationKeyStorer};
     use mithril_common::entities::Epoch;
@@ -81,6 +86,8 @@ mod handlers {
         signer_registerer: Arc<dyn SignerRegisterer>,
         event_transmitter: Arc<TransmitterService<EventMessage>>,
         ticker_service: Arc<dyn TickerService>,
+        certificate_pending_store: Arc<CertificatePendingStore>,
+        epoch_event_log: EpochEventLog,
     ) -> Result<impl warp::Reply, Infallible> {
         debug!(
             "⇄ HTTP SERVER: register_signer/{:?}",
@@ -119,13 +126,16 @@ mod handlers {
             None => Vec::new(),
         };
 
-        let epoch_str = match ticker_service.get_current_epoch().await {
-            Ok(epoch) => format!("{epoch}"),
+        let current_epoch = match ticker_service.get_current_epoch().await {
+            Ok(epoch) => Some(epoch),
             Err(e) => {
                 warn!("Could not read epoch to add in event: {e}");
-                String::new()
+                None
             }
         };
+        let epoch_str = current_epoch
+            .map(|epoch| format!("{epoch}"))
+            .unwrap_or_default();
         if !epoch_str.is_empty() {
             headers.push(("epoch", epoch_str.as_str()));
         }
@@ -141,6 +151,19 @@ mod handlers {
                     &signer_with_stake,
                     headers,
                 );
+                // The pending certificate is kept alive for the epoch that is being signed,
+                // not for the later epoch the signer registers for
+                if let Some(current_epoch) = current_epoch {
+                    if let Err(err) = certificate_pending_store
+                        .update_heartbeat(current_epoch)
+                        .await
+                    {
+                        warn!("register_signer::heartbeat update error"; "error" => ?err);
+                    }
+                }
+                epoch_event_log
+                    .record(EpochEvent::SignerRegistered(signer_with_stake.party_id))
+                    .await;
 
                 Ok(reply::empty(StatusCode::CREATED))
             }
@@ -154,6 +177,13 @@ mod handlers {
                 );
                 Ok(reply::empty(StatusCode::CREATED))
             }
+            Err(SignerRegistrationError::DuplicateNonce(nonce)) => {
+                debug!("register_signer::duplicate_nonce"; "nonce" => &nonce);
+                Ok(reply::conflict(
+                    "duplicate_registration_nonce".to_string(),
+                    SignerRegistrationError::DuplicateNonce(nonce).to_string(),
+                ))
+            }
             Err(SignerRegistrationError::FailedSignerRegistration(err)) => {
                 warn!("register_signer::failed_signer_registration"; "error" => ?err);
                 Ok(reply::bad_request(
@@ -248,6 +278,7 @@ mod tests {
     use anyhow::anyhow;
     use mockall::predicate::eq;
     use serde_json::Value::Null;
+    use std::time::Duration;
     use warp::{
         http::{Method, StatusCode},
         test::request,
@@ -322,6 +353,43 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_register_signer_post_updates_the_heartbeat_of_the_current_epoch() {
+        let signer_with_stake = fake_data::signers_with_stakes(1).pop().unwrap();
+        let mut mock_signer_registerer = MockSignerRegisterer::new();
+        mock_signer_registerer
+            .expect_register_signer()
+            .return_once(|_, _| Ok(signer_with_stake));
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signer_registerer = Arc::new(mock_signer_registerer);
+        let current_epoch = dependency_manager
+            .ticker_service
+            .get_current_epoch()
+            .await
+            .unwrap();
+        let certificate_pending_store = dependency_manager.certificate_pending_store.clone();
+
+        let signer = RegisterSignerMessage {
+            epoch: Some(current_epoch.offset_to_recording_epoch()),
+            ..RegisterSignerMessage::dummy()
+        };
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{SERVER_BASE_PATH}/register-signer"))
+            .json(&signer)
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::CREATED, response.status());
+        assert_eq!(
+            vec![current_epoch],
+            certificate_pending_store
+                .list_stale(Duration::ZERO)
+                .await
+                .unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_register_signer_post_ok_existing() {
         let signer_with_stake = fake_data::signers_with_stakes(1).pop().unwrap();
@@ -403,6 +471,49 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_register_signer_post_ko_409() {
+        let mut mock_signer_registerer = MockSignerRegisterer::new();
+        mock_signer_registerer
+            .expect_register_signer()
+            .return_once(|_, _| {
+                Err(SignerRegistrationError::DuplicateNonce(
+                    "nonce-1".to_string(),
+                ))
+            });
+        mock_signer_registerer
+            .expect_get_current_round()
+            .return_once(|| None);
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signer_registerer = Arc::new(mock_signer_registerer);
+
+        let signer = RegisterSignerMessage {
+            registration_nonce: Some("nonce-1".to_string()),
+            ..RegisterSignerMessage::dummy()
+        };
+
+        let method = Method::POST.as_str();
+        let path = "/register-signer";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .json(&signer)
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &signer,
+            &response,
+            &StatusCode::CONFLICT,
+        )
+        .unwrap();
+    }
+
     #[tokio::test]
     async fn test_register_signer_post_ko_500() {
         let mut mock_signer_registerer = MockSignerRegisterer::new();
@@ -31,34 +31,45 @@ mod tools;
 
 pub use crate::artifact_builder::ArtifactBuilder;
 pub use crate::configuration::{
-    Configuration, DefaultConfiguration, ExecutionEnvironment, SnapshotUploaderType,
-    ZstandardCompressionParameters,
+    Configuration, DefaultConfiguration, ExecutionEnvironment, GzipCompressionParameters,
+    PinningServiceConfig, SnapshotUploaderType, ZstandardCompressionParameters,
+};
+pub use crate::multi_signer::{
+    reward_per_signature_policy, InvalidSignerEntry, MultiSigner, MultiSignerImpl, ProtocolError,
+    RewardPolicy, RoundEvent, RoundState, ThresholdPolicy, VerificationError,
 };
-pub use crate::multi_signer::{MultiSigner, MultiSignerImpl};
 pub use commands::{CommandType, MainOpts};
 pub use dependency_injection::DependencyContainer;
 pub use message_adapters::{
     FromRegisterSignerAdapter, ToCertificatePendingMessageAdapter, ToEpochSettingsMessageAdapter,
 };
 pub use runtime::{
-    AggregatorConfig, AggregatorRunner, AggregatorRunnerTrait, AggregatorRuntime, RuntimeError,
+    AggregatorConfig, AggregatorRunner, AggregatorRunnerTrait, AggregatorRuntime, EpochEvent,
+    EpochEventLog, PendingOperationPermit, PendingOperationsLimiter, RuntimeError,
+    TimePointTracker,
 };
 pub use signer_registerer::{
     MithrilSignerRegisterer, SignerRecorder, SignerRegisterer, SignerRegistrationError,
     SignerRegistrationRound, SignerRegistrationRoundOpener,
 };
 pub use snapshot_uploaders::{
-    DumbSnapshotUploader, LocalSnapshotUploader, RemoteSnapshotUploader, SnapshotUploader,
+    DumbSnapshotUploader, IpfsSnapshotUploader, LocalSnapshotUploader, RemoteSnapshotUploader,
+    SnapshotTier, SnapshotUploader, TieredSnapshotStore,
 };
 pub use snapshotter::{
-    CompressedArchiveSnapshotter, DumbSnapshotter, SnapshotError, Snapshotter,
+    ArchiveFormat, CompressedArchiveSnapshotter, DumbSnapshotter, SnapshotError, Snapshotter,
     SnapshotterCompressionAlgorithm,
 };
 pub use store::{
-    CertificatePendingStore, ProtocolParametersStorer, VerificationKeyStore, VerificationKeyStorer,
+    CertificateArchiveStore, CertificatePendingStore, ColdArchiveHandle, EpochSession,
+    FileCertificateArchiveStore, HeartbeatClock, ProtocolParametersStorer, RestoreStatus,
+    SagaHandle, SessionOutcome, SnapshotStorageClass, SnapshotStore, StoreError, UploadSession,
+    UploadSessionStore, VerificationKeyStore, VerificationKeyStorer,
 };
 pub use tools::{
-    CExplorerSignerRetriever, SignersImporter, SignersImporterPersister, SignersImporterRetriever,
+    CExplorerSignerRetriever, ColdStorageLifecycle, S3Client, S3ColdStorageLifecycle,
+    S3Credentials, S3FileUploader, SignersImporter, SignersImporterPersister,
+    SignersImporterRetriever,
 };
 
 #[cfg(test)]
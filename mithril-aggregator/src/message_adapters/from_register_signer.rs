@@ -36,6 +36,7 @@ impl TryFromMessageAdapter<RegisterSignerMessage, Signer> for FromRegisterSigner
                 _ => None,
             },
             kes_period: register_signer_message.kes_period,
+            registration_nonce: register_signer_message.registration_nonce,
         })
     }
 }
@@ -61,6 +61,7 @@ fn try_adapt_set_proof_message(
 #[cfg(test)]
 mod tests {
     use mithril_common::crypto_helper::MKProof;
+    use mithril_common::entities::CardanoTransaction;
 
     use super::*;
 
@@ -81,8 +82,18 @@ mod tests {
         let transactions_set_proofs = transactions_hashes_certified
             .chunks(2)
             .map(|transaction_hashes_in_chunk| {
-                let mk_proof = MKProof::from_leaves(transaction_hashes_in_chunk).unwrap();
-                CardanoTransactionsSetProof::new(transaction_hashes_in_chunk.to_vec(), mk_proof)
+                let block_hashes =
+                    vec!["block-hash".to_string(); transaction_hashes_in_chunk.len()];
+                let leaves: Vec<_> = transaction_hashes_in_chunk
+                    .iter()
+                    .map(|hash| CardanoTransaction::compute_leaf(hash, "block-hash"))
+                    .collect();
+                let mk_proof = MKProof::from_leaves(&leaves).unwrap();
+                CardanoTransactionsSetProof::new(
+                    transaction_hashes_in_chunk.to_vec(),
+                    block_hashes,
+                    mk_proof,
+                )
             })
             .collect::<Vec<_>>();
 
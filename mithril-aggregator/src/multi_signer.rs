@@ -1,15 +1,26 @@
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use chrono::Utc;
+use sha2::{Digest, Sha256};
 use slog_scope::{debug, warn};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::RwLock;
 
 use mithril_common::{
     crypto_helper::{ProtocolAggregationError, ProtocolMultiSignature},
-    entities::{self},
+    entities::{self, CardanoDbBeacon, Certificate, CertificateSignature, Epoch, PartyId},
+    protocol::SignerBuilder,
     StdResult,
 };
 
+use crate::database::repository::RewardStorer;
 use crate::dependency_injection::EpochServiceWrapper;
-use crate::entities::OpenMessage;
+use crate::entities::{OpenMessage, RewardEntry, SignerContribution};
+use crate::runtime::{EpochEvent, EpochEventLog};
+use crate::services::EpochService;
+use crate::store::VerificationKeyStorer;
 
 #[cfg(test)]
 use mockall::automock;
@@ -30,18 +41,673 @@ pub trait MultiSigner: Sync + Send {
         &self,
         open_message: &OpenMessage,
     ) -> StdResult<Option<ProtocolMultiSignature>>;
+
+    /// Get the state of the current signing round
+    async fn get_round_state(&self) -> RoundState;
+
+    /// Abort the current signing round after a fork of the Cardano chain was detected.
+    ///
+    /// The signatures received during the round are dropped, so they can't be used to
+    /// certify the abandoned chain.
+    async fn handle_fork(&self, canonical_beacon: CardanoDbBeacon) -> StdResult<()>;
+
+    /// Get the signing threshold (the quorum `k`) used to aggregate the signatures of the
+    /// current epoch
+    async fn get_current_threshold(&self) -> u64;
+
+    /// Set the policy computing the rewards of the signers from their contribution to the
+    /// signing rounds of an epoch
+    async fn set_reward_policy(&self, policy: Arc<dyn RewardPolicy>);
+
+    /// Compute the rewards of the signers that sent valid single signatures during the given
+    /// epoch, no reward is computed if no reward policy is set.
+    async fn compute_rewards(&self, epoch: Epoch) -> StdResult<Vec<RewardEntry>>;
+
+    /// Compute the hash of the contributions of the signers during the given epoch, stake
+    /// weighted if the `weighted` flag of the current protocol parameters is set.
+    async fn compute_contributions_hash(&self, epoch: Epoch) -> StdResult<String>;
+
+    /// Set the protocol message that is being signed
+    async fn update_current_message(&self, message: entities::ProtocolMessage);
+
+    /// Get the digest of the protocol message that is being signed.
+    ///
+    /// The digest is computed once and cached until the protocol message changes.
+    async fn get_protocol_message_digest(&self) -> StdResult<String>;
+
+    /// Drop the cached digest of the protocol message, ie: on epoch transitions.
+    async fn clear_protocol_message_digest_cache(&self);
+
+    /// Verify the multi-signature of a certificate against the stake distribution of the
+    /// signers of its epoch, without running the aggregation pipeline.
+    ///
+    /// Fail with a [VerificationError] if the certificate is not valid.
+    async fn verify_aggregate_signature(&self, certificate: &Certificate) -> StdResult<()>;
+}
+
+/// Policy computing the rewards of the signers from their contribution to the signing rounds
+/// of an epoch.
+///
+/// Any `Fn(&[SignerContribution], Epoch) -> Vec<RewardEntry>` is a reward policy.
+pub trait RewardPolicy: Fn(&[SignerContribution], Epoch) -> Vec<RewardEntry> + Send + Sync {}
+
+impl<F> RewardPolicy for F where
+    F: Fn(&[SignerContribution], Epoch) -> Vec<RewardEntry> + Send + Sync
+{
+}
+
+/// [RewardPolicy] granting the same reward for each valid single signature sent by a signer
+pub fn reward_per_signature_policy(reward_per_signature: u64) -> impl RewardPolicy {
+    move |contributions: &[SignerContribution], epoch: Epoch| {
+        contributions
+            .iter()
+            .map(|contribution| RewardEntry {
+                epoch,
+                party_id: contribution.party_id.clone(),
+                reward: contribution
+                    .signatures_count
+                    .saturating_mul(reward_per_signature),
+            })
+            .collect()
+    }
+}
+
+/// Policy computing the signing threshold from the signers of an epoch.
+///
+/// The threshold is the number of won lotteries required to aggregate the signatures, it never
+/// goes below the quorum `k` of the epoch protocol parameters which is always used to aggregate
+/// the signatures so the certificates stay verifiable with these parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdPolicy {
+    /// Use the same threshold at every epoch
+    Fixed(u64),
+
+    /// Use a proportion of the number of signers, never going below `minimum`
+    Proportional {
+        /// Numerator of the proportion
+        numerator: u64,
+
+        /// Denominator of the proportion
+        denominator: u64,
+
+        /// Lowest threshold that can be computed
+        minimum: u64,
+    },
+}
+
+impl ThresholdPolicy {
+    /// Compute the threshold for an epoch with the given number of signers
+    pub fn compute_threshold(&self, number_of_signers: usize) -> u64 {
+        match self {
+            Self::Fixed(threshold) => *threshold,
+            Self::Proportional {
+                numerator,
+                denominator,
+                minimum,
+            } => ((number_of_signers as u64).saturating_mul(*numerator) / (*denominator).max(1))
+                .max(*minimum),
+        }
+    }
+}
+
+/// Error raised by the [MultiSigner] when checking single signatures
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// The signer already sent a signature for the current round
+    #[error("Signer '{0}' already sent a signature for the current signing round")]
+    SignatureAlreadyReceived(PartyId),
+
+    /// The aggregation was triggered while no signature was received
+    #[error("Can not aggregate the signing round of epoch '{0}': no signature received")]
+    NoSignatureReceived(Epoch),
+
+    /// Not enough of the registered signers sent a signature before the end of the signing
+    /// round to meet the [BFT quorum][MultiSignerImpl::with_bft_quorum]
+    #[error(
+        "BFT quorum not met: received signatures from {received} signers, {required} required"
+    )]
+    BftQuorumNotMet {
+        /// Number of signers that sent a signature
+        received: usize,
+
+        /// Number of signers required by the quorum
+        required: usize,
+    },
+
+    /// The event can't be applied to the current state of the signing round
+    #[error("Invalid signing round transition: can not apply '{event}' to state '{state}'")]
+    InvalidRoundTransition {
+        /// Name of the current state
+        state: String,
+
+        /// Name of the rejected event
+        event: String,
+    },
+}
+
+/// Error raised by the [MultiSigner] when verifying the multi-signature of a certificate
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The signers of the certificate or its aggregate verification key don't match the stake
+    /// distribution of the epoch
+    #[error("Certificate '{0}' does not match the stake distribution of its epoch")]
+    StakeDistributionMismatch(String),
+
+    /// The multi-signature of the certificate is not valid
+    #[error("Certificate '{hash}' has an invalid multi-signature: {reason}")]
+    SignatureInvalid {
+        /// Hash of the certificate
+        hash: String,
+
+        /// Reason of the failure of the verification
+        reason: String,
+    },
+
+    /// The signed message of the certificate is not computed from its protocol message
+    #[error("Certificate '{0}' signed message does not match its protocol message")]
+    MessageMismatch(String),
+}
+
+/// State of a signing round
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum RoundState {
+    /// No signing round is in progress
+    #[default]
+    Idle,
+
+    /// Single signatures are being collected
+    CollectingSignatures {
+        /// Epoch of the signing round
+        epoch: Epoch,
+
+        /// Signers that already sent their signature
+        received: HashSet<PartyId>,
+    },
+
+    /// The collected signatures are being aggregated in a multi-signature
+    Aggregating,
+
+    /// A certificate was created for the signing round
+    Completed {
+        /// Hash of the created certificate
+        certificate_hash: String,
+    },
+
+    /// The signing round failed
+    Failed(ProtocolError),
+}
+
+/// Event that makes a [RoundState] move to another state
+#[derive(Debug, Clone)]
+pub enum RoundEvent {
+    /// A new signing round is opened at the given epoch
+    RoundStarted(Epoch),
+
+    /// A valid single signature was received from a signer
+    SignatureReceived(PartyId, entities::SingleSignatures),
+
+    /// The collected signatures must be aggregated
+    AggregationTriggered,
+
+    /// A certificate was created from the aggregated signatures, contains its hash
+    CertificateCreated(String),
+
+    /// Drop the current signing round
+    Reset,
+}
+
+impl RoundEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::RoundStarted(_) => "RoundStarted",
+            Self::SignatureReceived(_, _) => "SignatureReceived",
+            Self::AggregationTriggered => "AggregationTriggered",
+            Self::CertificateCreated(_) => "CertificateCreated",
+            Self::Reset => "Reset",
+        }
+    }
+}
+
+impl RoundState {
+    /// Compute the state reached by applying the given event to this state.
+    ///
+    /// A `Reset` is accepted from any state, other events must follow the round lifecycle:
+    /// `Idle` → `CollectingSignatures` → `Aggregating` → `Completed`. Aggregating a round
+    /// without any signature leads to the `Failed` state.
+    pub fn transition(&self, event: RoundEvent) -> Result<RoundState, ProtocolError> {
+        match (self, event) {
+            (_, RoundEvent::Reset) => Ok(Self::Idle),
+            (Self::Idle, RoundEvent::RoundStarted(epoch)) => Ok(Self::CollectingSignatures {
+                epoch,
+                received: HashSet::new(),
+            }),
+            (
+                Self::CollectingSignatures { epoch, received },
+                RoundEvent::SignatureReceived(party_id, _signatures),
+            ) => {
+                if received.contains(&party_id) {
+                    return Err(ProtocolError::SignatureAlreadyReceived(party_id));
+                }
+                let mut received = received.clone();
+                received.insert(party_id);
+
+                Ok(Self::CollectingSignatures {
+                    epoch: *epoch,
+                    received,
+                })
+            }
+            (Self::CollectingSignatures { epoch, received }, RoundEvent::AggregationTriggered) => {
+                if received.is_empty() {
+                    Ok(Self::Failed(ProtocolError::NoSignatureReceived(*epoch)))
+                } else {
+                    Ok(Self::Aggregating)
+                }
+            }
+            (Self::Aggregating, RoundEvent::CertificateCreated(certificate_hash)) => {
+                Ok(Self::Completed { certificate_hash })
+            }
+            (state, event) => Err(ProtocolError::InvalidRoundTransition {
+                state: state.name().to_string(),
+                event: event.name().to_string(),
+            }),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Idle => "Idle",
+            Self::CollectingSignatures { .. } => "CollectingSignatures",
+            Self::Aggregating => "Aggregating",
+            Self::Completed { .. } => "Completed",
+            Self::Failed(_) => "Failed",
+        }
+    }
+}
+
+/// Invalid signatures sent by a signer during an epoch
+///
+/// The party id of a signature failing verification is not authenticated, anyone can send a
+/// signature on behalf of a signer: this is only a report, the signer is never evicted for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSignerEntry {
+    /// Signer that sent the invalid signatures
+    pub party_id: PartyId,
+
+    /// Number of invalid signatures sent
+    pub failure_count: u32,
+
+    /// Verification error of the last invalid signature
+    pub last_error: String,
+}
+
+/// Signing threshold computed for an epoch
+struct EpochThreshold {
+    epoch: Epoch,
+    threshold: u64,
 }
 
 /// MultiSignerImpl is an implementation of the MultiSigner
 pub struct MultiSignerImpl {
     epoch_service: EpochServiceWrapper,
+    threshold_policy: Option<ThresholdPolicy>,
+    bft_honest_majority: Option<f64>,
+    epoch_threshold: RwLock<Option<EpochThreshold>>,
+    invalid_signers: RwLock<BTreeMap<Epoch, BTreeMap<PartyId, InvalidSignerEntry>>>,
+    round_state: RwLock<RoundState>,
+    event_log: EpochEventLog,
+    reward_store: Option<Arc<dyn RewardStorer>>,
+    verification_key_store: Option<Arc<dyn VerificationKeyStorer>>,
+    reward_policy: RwLock<Option<Arc<dyn RewardPolicy>>>,
+    current_message: RwLock<Option<entities::ProtocolMessage>>,
+    protocol_message_digest: RwLock<Option<String>>,
 }
 
 impl MultiSignerImpl {
     /// MultiSignerImpl factory
     pub fn new(epoch_service: EpochServiceWrapper) -> Self {
         debug!("New MultiSignerImpl created");
-        Self { epoch_service }
+        Self {
+            epoch_service,
+            threshold_policy: None,
+            bft_honest_majority: None,
+            epoch_threshold: RwLock::new(None),
+            invalid_signers: RwLock::new(BTreeMap::new()),
+            round_state: RwLock::new(RoundState::default()),
+            event_log: EpochEventLog::default(),
+            reward_store: None,
+            verification_key_store: None,
+            reward_policy: RwLock::new(None),
+            current_message: RwLock::new(None),
+            protocol_message_digest: RwLock::new(None),
+        }
+    }
+
+    /// Record the forks detected by the multi signer in the given log.
+    pub fn with_event_log(mut self, event_log: EpochEventLog) -> Self {
+        self.event_log = event_log;
+        self
+    }
+
+    /// Record the contributions of the signers in the given store, no contribution is recorded
+    /// and no reward computed without it.
+    pub fn with_reward_store(mut self, reward_store: Arc<dyn RewardStorer>) -> Self {
+        self.reward_store = Some(reward_store);
+        self
+    }
+
+    /// Read the signers of the epoch of the verified certificates from the given store.
+    ///
+    /// Without it only the certificates of the epoch of the epoch service can be verified.
+    pub fn with_verification_key_store(
+        mut self,
+        verification_key_store: Arc<dyn VerificationKeyStorer>,
+    ) -> Self {
+        self.verification_key_store = Some(verification_key_store);
+        self
+    }
+
+    /// Compute the rewards of the signers with the given policy
+    pub fn with_reward_policy(mut self, reward_policy: Arc<dyn RewardPolicy>) -> Self {
+        self.reward_policy = RwLock::new(Some(reward_policy));
+        self
+    }
+
+    /// Compute the signing threshold of each epoch with the given policy, it's raised to the `k`
+    /// of the epoch protocol parameters if it's lower.
+    pub fn with_threshold_policy(mut self, threshold_policy: ThresholdPolicy) -> Self {
+        self.threshold_policy = Some(threshold_policy);
+        self
+    }
+
+    /// Require that at least the `honest_majority` fraction (ie: `0.67`) of the registered
+    /// signers sent a signature to create a multi-signature, on top of the signing threshold.
+    ///
+    /// This prevents a few signers winning many lotteries from crowding out the honest signers.
+    /// The required number of signers is rounded up, so the quorum is never below the fraction.
+    pub fn with_bft_quorum(mut self, honest_majority: f64) -> Self {
+        self.bft_honest_majority = Some(honest_majority);
+        self
+    }
+
+    /// Check that enough distinct signers contributed to the open message to meet the BFT
+    /// quorum.
+    ///
+    /// Returns `false` if the quorum is not met yet, and an [ProtocolError::BftQuorumNotMet]
+    /// error if the signing round of the open message is over.
+    fn check_bft_quorum(
+        &self,
+        open_message: &OpenMessage,
+        epoch_service: &dyn EpochService,
+    ) -> StdResult<bool> {
+        let Some(honest_majority) = self.bft_honest_majority else {
+            return Ok(true);
+        };
+        let registered = epoch_service
+            .current_signers_with_stake()
+            .with_context(|| "Multi Signer could not get signers with stake from epoch service")?
+            .len();
+        let required = ((registered as f64) * honest_majority).ceil() as usize;
+        let received = open_message
+            .single_signatures
+            .iter()
+            .map(|signature| &signature.party_id)
+            .collect::<HashSet<_>>()
+            .len();
+        if received >= required {
+            return Ok(true);
+        }
+
+        let is_round_over = open_message.is_expired
+            || open_message
+                .expires_at
+                .is_some_and(|expires_at| expires_at <= Utc::now());
+        if is_round_over {
+            warn!(
+                "BFT quorum not met at the end of the signing round";
+                "signed_entity_type" => ?open_message.signed_entity_type,
+                "received" => received, "required" => required
+            );
+            return Err(ProtocolError::BftQuorumNotMet { received, required }.into());
+        }
+        debug!(
+            "MultiSigner: BFT quorum not met yet";
+            "received" => received, "required" => required
+        );
+
+        Ok(false)
+    }
+
+    /// Get the signers with their stake of the epoch of the given certificate, ie: the signers
+    /// registered at its signer retrieval epoch.
+    async fn get_signers_of_certificate_epoch(
+        &self,
+        certificate: &Certificate,
+    ) -> StdResult<Vec<entities::SignerWithStake>> {
+        if let Some(verification_key_store) = &self.verification_key_store {
+            let signers_epoch = certificate.epoch.offset_to_signer_retrieval_epoch()?;
+
+            return verification_key_store
+                .get_signers(signers_epoch)
+                .await
+                .with_context(|| {
+                    format!("Multi Signer could not get the signers of epoch '{signers_epoch}'")
+                })?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Multi Signer could not find the signers of epoch '{signers_epoch}' for certificate '{}'",
+                        certificate.hash
+                    )
+                });
+        }
+
+        let epoch_service = self.epoch_service.read().await;
+        let current_epoch = epoch_service
+            .epoch_of_current_data()
+            .with_context(|| "Multi Signer could not get current epoch from epoch service")?;
+        if current_epoch != certificate.epoch {
+            return Err(anyhow!(
+                "Multi Signer can only verify the certificates of the current epoch '{current_epoch}' without a verification key store, certificate '{}' is of epoch '{}'",
+                certificate.hash,
+                certificate.epoch
+            ));
+        }
+
+        Ok(epoch_service
+            .current_signers_with_stake()
+            .with_context(|| {
+                "Multi Signer could not get current signers with stake from epoch service"
+            })?
+            .clone())
+    }
+
+    /// Compute the signing threshold if the epoch of the epoch service changed since the last
+    /// computation.
+    async fn refresh_epoch_threshold(&self, epoch_service: &dyn EpochService) -> StdResult<()> {
+        let epoch = epoch_service
+            .epoch_of_current_data()
+            .with_context(|| "Multi Signer could not get current epoch from epoch service")?;
+        if self
+            .epoch_threshold
+            .read()
+            .await
+            .as_ref()
+            .is_some_and(|epoch_threshold| epoch_threshold.epoch == epoch)
+        {
+            return Ok(());
+        }
+
+        let protocol_parameters = epoch_service
+            .current_protocol_parameters()
+            .with_context(|| "Multi Signer could not get protocol parameters from epoch service")?;
+        let threshold = match &self.threshold_policy {
+            None => protocol_parameters.k,
+            Some(threshold_policy) => {
+                let signers = epoch_service
+                    .current_signers_with_stake()
+                    .with_context(|| {
+                        "Multi Signer could not get signers with stake from epoch service"
+                    })?;
+
+                threshold_policy
+                    .compute_threshold(signers.len())
+                    .max(protocol_parameters.k)
+            }
+        };
+
+        debug!("MultiSigner: signing threshold computed"; "epoch" => ?epoch, "threshold" => threshold);
+        *self.epoch_threshold.write().await = Some(EpochThreshold { epoch, threshold });
+
+        Ok(())
+    }
+
+    /// Get the signers that sent invalid signatures at the given epoch
+    pub async fn get_invalid_signer_report(&self, epoch: Epoch) -> Vec<InvalidSignerEntry> {
+        self.invalid_signers
+            .read()
+            .await
+            .get(&epoch)
+            .map(|entries| entries.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    async fn record_invalid_signature(&self, epoch: Epoch, party_id: &PartyId, error: String) {
+        let mut invalid_signers = self.invalid_signers.write().await;
+        // Evictions only last for the current epoch
+        invalid_signers.retain(|entry_epoch, _| *entry_epoch >= epoch);
+
+        let entry = invalid_signers
+            .entry(epoch)
+            .or_default()
+            .entry(party_id.to_owned())
+            .or_insert_with(|| InvalidSignerEntry {
+                party_id: party_id.to_owned(),
+                failure_count: 0,
+                last_error: String::new(),
+            });
+        entry.failure_count += 1;
+        entry.last_error = error;
+    }
+
+    async fn record_contribution(
+        &self,
+        epoch: Epoch,
+        single_signature: &entities::SingleSignatures,
+        epoch_service: &dyn EpochService,
+    ) {
+        let Some(reward_store) = &self.reward_store else {
+            return;
+        };
+        let stake = epoch_service
+            .current_signers_with_stake()
+            .ok()
+            .and_then(|signers| {
+                signers
+                    .iter()
+                    .find(|signer| signer.party_id == single_signature.party_id)
+                    .map(|signer| signer.stake)
+            })
+            .unwrap_or_default();
+
+        // A signature sent again is only counted on its first receipt
+        match reward_store
+            .record_contribution(epoch, stake, single_signature)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                debug!("Signature already counted in the contributions"; "party_id" => &single_signature.party_id, "epoch" => ?epoch);
+            }
+            Err(error) => {
+                warn!("Multi Signer could not record the contribution of a signer"; "party_id" => &single_signature.party_id, "error" => ?error);
+            }
+        }
+    }
+
+    async fn get_contributions(&self, epoch: Epoch) -> StdResult<Vec<SignerContribution>> {
+        match &self.reward_store {
+            Some(reward_store) => reward_store.get_contributions(epoch).await,
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Compute the hash of the contributions of the signers during the given epoch, weighting
+    /// the signature bytes of each signer by its fraction of the total stake of the contributors.
+    ///
+    /// Two epochs with the same signatures but a different stake distribution have different
+    /// weighted hashes.
+    async fn compute_stake_weighted_hash(&self, epoch: Epoch) -> StdResult<String> {
+        let contributions = self.get_contributions(epoch).await?;
+        if contributions.is_empty() {
+            return Err(anyhow!(
+                "Multi Signer has no signer contribution for epoch '{epoch}'"
+            ));
+        }
+        let total_stake: u128 = contributions
+            .iter()
+            .map(|contribution| contribution.stake as u128)
+            .sum();
+        if total_stake == 0 {
+            return Err(anyhow!(
+                "Multi Signer can not weight the contributions of epoch '{epoch}' without stake"
+            ));
+        }
+
+        let mut hasher = Sha256::new();
+        for contribution in &contributions {
+            // Fixed-point fraction of the total stake, so the weight doesn't depend on floats
+            let weight = (contribution.stake as u128 * u64::MAX as u128 / total_stake) as u64;
+            hasher.update(contribution.party_id.as_bytes());
+            hasher.update(weight.to_be_bytes());
+            hasher.update(&contribution.signatures_bytes);
+        }
+
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Compute the hash of the contributions of the signers during the given epoch, with
+    /// signers all weighted equally.
+    async fn compute_flat_hash(&self, epoch: Epoch) -> StdResult<String> {
+        let contributions = self.get_contributions(epoch).await?;
+        if contributions.is_empty() {
+            return Err(anyhow!(
+                "Multi Signer has no signer contribution for epoch '{epoch}'"
+            ));
+        }
+
+        let mut hasher = Sha256::new();
+        for contribution in &contributions {
+            hasher.update(contribution.party_id.as_bytes());
+            hasher.update(&contribution.signatures_bytes);
+        }
+
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    async fn record_received_signature(
+        &self,
+        epoch: Epoch,
+        single_signature: &entities::SingleSignatures,
+    ) -> StdResult<()> {
+        let mut round_state = self.round_state.write().await;
+        let is_collecting_epoch = matches!(
+            &*round_state,
+            RoundState::CollectingSignatures { epoch: round_epoch, .. } if *round_epoch == epoch
+        );
+        if !is_collecting_epoch {
+            *round_state = RoundState::Idle.transition(RoundEvent::RoundStarted(epoch))?;
+        }
+
+        match round_state.transition(RoundEvent::SignatureReceived(
+            single_signature.party_id.clone(),
+            single_signature.clone(),
+        )) {
+            Ok(new_state) => *round_state = new_state,
+            // A signer sends one signature per signed entity type during an epoch
+            Err(ProtocolError::SignatureAlreadyReceived(_)) => {}
+            Err(error) => return Err(error.into()),
+        }
+
+        Ok(())
     }
 }
 
@@ -59,15 +725,38 @@ impl MultiSigner for MultiSignerImpl {
         );
 
         let epoch_service = self.epoch_service.read().await;
+        let epoch = epoch_service
+            .epoch_of_current_data()
+            .with_context(|| "Multi Signer could not get current epoch from epoch service")?;
         let protocol_multi_signer = epoch_service.protocol_multi_signer().with_context(|| {
             "Multi Signer could not get protocol multi-signer from epoch service"
         })?;
 
-        protocol_multi_signer
-            .verify_single_signature(message, single_signature)
-            .with_context(|| {
+        if let Err(error) = protocol_multi_signer.verify_single_signature(message, single_signature)
+        {
+            let party_id = &single_signature.party_id;
+            let is_signer_of_epoch = epoch_service
+                .current_signers_with_stake()
+                .with_context(|| {
+                    "Multi Signer could not get signers with stake from epoch service"
+                })?
+                .iter()
+                .any(|signer| &signer.party_id == party_id);
+            if is_signer_of_epoch {
+                self.record_invalid_signature(epoch, party_id, format!("{error:?}"))
+                    .await;
+            }
+
+            return Err(error).with_context(|| {
                 format!("Multi Signer can not verify single signature for message '{message:?}'")
-            })
+            });
+        }
+        self.record_received_signature(epoch, single_signature)
+            .await?;
+        self.record_contribution(epoch, single_signature, &*epoch_service)
+            .await;
+
+        Ok(())
     }
 
     /// Creates a multi signature from single signatures
@@ -78,6 +767,28 @@ impl MultiSigner for MultiSignerImpl {
         debug!("MultiSigner:create_multi_signature({open_message:?})");
 
         let epoch_service = self.epoch_service.read().await;
+        if !self.check_bft_quorum(open_message, &*epoch_service)? {
+            return Ok(None);
+        }
+        self.refresh_epoch_threshold(&*epoch_service).await?;
+        let threshold = self
+            .epoch_threshold
+            .read()
+            .await
+            .as_ref()
+            .map(|epoch_threshold| epoch_threshold.threshold)
+            .unwrap_or_default();
+        let won_lotteries: u64 = open_message
+            .single_signatures
+            .iter()
+            .map(|signature| signature.won_indexes.len() as u64)
+            .sum();
+        if won_lotteries < threshold {
+            warn!("Could not compute multi-signature: Not enough won lotteries. Got only {} out of {}.", won_lotteries, threshold);
+            return Ok(None);
+        }
+        // The signatures are always aggregated with the epoch protocol parameters, which are
+        // the ones recorded in the certificate
         let protocol_multi_signer = epoch_service.protocol_multi_signer().with_context(|| {
             "Multi Signer could not get protocol multi-signer from epoch service"
         })?;
@@ -97,26 +808,177 @@ impl MultiSigner for MultiSignerImpl {
             ))),
         }
     }
+
+    async fn get_round_state(&self) -> RoundState {
+        self.round_state.read().await.clone()
+    }
+
+    async fn handle_fork(&self, canonical_beacon: CardanoDbBeacon) -> StdResult<()> {
+        let mut round_state = self.round_state.write().await;
+        warn!(
+            "Cardano chain fork detected, aborting the current signing round";
+            "canonical_beacon" => ?canonical_beacon, "round_state" => ?*round_state
+        );
+        *round_state = round_state.transition(RoundEvent::Reset)?;
+        self.event_log
+            .record(EpochEvent::ForkDetected(canonical_beacon))
+            .await;
+
+        Ok(())
+    }
+
+    async fn get_current_threshold(&self) -> u64 {
+        let epoch_service = self.epoch_service.read().await;
+        if let Err(error) = self.refresh_epoch_threshold(&*epoch_service).await {
+            warn!("Multi Signer could not compute the signing threshold"; "error" => ?error);
+        }
+
+        self.epoch_threshold
+            .read()
+            .await
+            .as_ref()
+            .map(|epoch_threshold| epoch_threshold.threshold)
+            .unwrap_or_default()
+    }
+
+    async fn set_reward_policy(&self, policy: Arc<dyn RewardPolicy>) {
+        *self.reward_policy.write().await = Some(policy);
+    }
+
+    async fn compute_rewards(&self, epoch: Epoch) -> StdResult<Vec<RewardEntry>> {
+        let Some(reward_policy) = self.reward_policy.read().await.clone() else {
+            return Ok(vec![]);
+        };
+        let contributions = self.get_contributions(epoch).await?;
+        let rewards = reward_policy(&contributions, epoch);
+        debug!("MultiSigner: rewards computed"; "epoch" => ?epoch, "nb_rewards" => rewards.len());
+
+        Ok(rewards)
+    }
+
+    async fn compute_contributions_hash(&self, epoch: Epoch) -> StdResult<String> {
+        let weighted = self
+            .epoch_service
+            .read()
+            .await
+            .current_protocol_parameters()
+            .with_context(|| "Multi Signer could not get protocol parameters from epoch service")?
+            .weighted;
+
+        if weighted {
+            self.compute_stake_weighted_hash(epoch).await
+        } else {
+            self.compute_flat_hash(epoch).await
+        }
+    }
+
+    async fn update_current_message(&self, message: entities::ProtocolMessage) {
+        let mut current_message = self.current_message.write().await;
+        if current_message.as_ref() != Some(&message) {
+            *self.protocol_message_digest.write().await = None;
+            *current_message = Some(message);
+        }
+    }
+
+    async fn get_protocol_message_digest(&self) -> StdResult<String> {
+        let current_message = self.current_message.read().await;
+        let message = current_message
+            .as_ref()
+            .ok_or_else(|| anyhow!("Multi Signer has no current protocol message"))?;
+
+        let mut protocol_message_digest = self.protocol_message_digest.write().await;
+        let digest = protocol_message_digest.get_or_insert_with(|| message.compute_hash());
+
+        Ok(digest.clone())
+    }
+
+    async fn clear_protocol_message_digest_cache(&self) {
+        *self.protocol_message_digest.write().await = None;
+    }
+
+    async fn verify_aggregate_signature(&self, certificate: &Certificate) -> StdResult<()> {
+        debug!("MultiSigner:verify_aggregate_signature"; "certificate_hash" => &certificate.hash);
+
+        let CertificateSignature::MultiSignature(_, multi_signature) = &certificate.signature
+        else {
+            return Err(anyhow!(
+                "Multi Signer can not verify the aggregate signature of genesis certificate '{}'",
+                certificate.hash
+            ));
+        };
+
+        if certificate.protocol_message.compute_hash() != certificate.signed_message {
+            return Err(VerificationError::MessageMismatch(certificate.hash.clone()).into());
+        }
+
+        let signers_with_stake = self.get_signers_of_certificate_epoch(certificate).await?;
+        let is_signer_of_the_epoch = |party: &entities::StakeDistributionParty| {
+            signers_with_stake
+                .iter()
+                .any(|signer| signer.party_id == party.party_id && signer.stake == party.stake)
+        };
+        if !certificate
+            .metadata
+            .signers
+            .iter()
+            .all(is_signer_of_the_epoch)
+        {
+            return Err(
+                VerificationError::StakeDistributionMismatch(certificate.hash.clone()).into(),
+            );
+        }
+
+        let aggregate_verification_key = SignerBuilder::new(
+            &signers_with_stake,
+            &certificate.metadata.protocol_parameters,
+        )
+        .with_context(|| "Multi Signer could not build the signers of the certificate epoch")?
+        .compute_aggregate_verification_key();
+        if aggregate_verification_key != certificate.aggregate_verification_key {
+            return Err(
+                VerificationError::StakeDistributionMismatch(certificate.hash.clone()).into(),
+            );
+        }
+
+        multi_signature
+            .verify(
+                certificate.signed_message.as_bytes(),
+                &aggregate_verification_key,
+                &certificate.metadata.protocol_parameters.clone().into(),
+            )
+            .map_err(|error| {
+                VerificationError::SignatureInvalid {
+                    hash: certificate.hash.clone(),
+                    reason: error.to_string(),
+                }
+                .into()
+            })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::database::repository::RewardStore;
+    use crate::database::test_helper::main_db_connection;
     use crate::services::FakeEpochService;
-    use mithril_common::entities::SignerWithStake;
+    use crate::store::MockVerificationKeyStorer;
+    use mithril_common::entities::{
+        CertificateMetadata, ProtocolMessage, ProtocolMessagePartKey, SignerWithStake,
+    };
     use mithril_common::{
         crypto_helper::tests_setup::*,
         entities::{CardanoDbBeacon, Epoch, SignedEntityType},
-        test_utils::{fake_data, MithrilFixtureBuilder},
+        test_utils::{fake_data, fake_keys, MithrilFixture, MithrilFixtureBuilder},
     };
-    use std::sync::Arc;
+    use mockall::predicate::eq;
     use tokio::sync::RwLock;
 
     fn take_signatures_until_quorum_is_almost_reached(
         signatures: &mut Vec<entities::SingleSignatures>,
         quorum: usize,
     ) -> Vec<entities::SingleSignatures> {
-        signatures.sort_by(|l, r| l.won_indexes.len().cmp(&r.won_indexes.len()));
+        signatures.sort_by_key(|l| l.won_indexes.len());
 
         let mut result = vec![];
         let mut nb_won_indexes = 0;
@@ -132,6 +994,106 @@ mod tests {
         result
     }
 
+    fn collecting_signatures_state(epoch: Epoch, party_ids: &[&str]) -> RoundState {
+        RoundState::CollectingSignatures {
+            epoch,
+            received: party_ids.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    fn signature_received_event(party_id: &str) -> RoundEvent {
+        RoundEvent::SignatureReceived(
+            party_id.to_string(),
+            fake_data::single_signatures(vec![1, 2]),
+        )
+    }
+
+    #[test]
+    fn round_state_follows_the_signing_round_lifecycle() {
+        let state = RoundState::default()
+            .transition(RoundEvent::RoundStarted(Epoch(3)))
+            .unwrap();
+        assert_eq!(collecting_signatures_state(Epoch(3), &[]), state);
+
+        let state = state
+            .transition(signature_received_event("party-1"))
+            .unwrap()
+            .transition(signature_received_event("party-2"))
+            .unwrap();
+        assert_eq!(
+            collecting_signatures_state(Epoch(3), &["party-1", "party-2"]),
+            state
+        );
+
+        let state = state.transition(RoundEvent::AggregationTriggered).unwrap();
+        assert_eq!(RoundState::Aggregating, state);
+
+        let state = state
+            .transition(RoundEvent::CertificateCreated(
+                "certificate-hash".to_string(),
+            ))
+            .unwrap();
+        assert_eq!(
+            RoundState::Completed {
+                certificate_hash: "certificate-hash".to_string()
+            },
+            state
+        );
+
+        assert_eq!(
+            RoundState::Idle,
+            state.transition(RoundEvent::Reset).unwrap()
+        );
+    }
+
+    #[test]
+    fn round_state_receiving_a_signature_when_completed_fails() {
+        let state = RoundState::Completed {
+            certificate_hash: "certificate-hash".to_string(),
+        };
+
+        let error = state
+            .transition(signature_received_event("party-1"))
+            .unwrap_err();
+
+        assert_eq!(
+            ProtocolError::InvalidRoundTransition {
+                state: "Completed".to_string(),
+                event: "SignatureReceived".to_string(),
+            },
+            error
+        );
+    }
+
+    #[test]
+    fn round_state_receiving_twice_a_signature_from_the_same_signer_fails() {
+        let state = collecting_signatures_state(Epoch(3), &["party-1"]);
+
+        let error = state
+            .transition(signature_received_event("party-1"))
+            .unwrap_err();
+
+        assert_eq!(
+            ProtocolError::SignatureAlreadyReceived("party-1".to_string()),
+            error
+        );
+    }
+
+    #[test]
+    fn round_state_aggregating_without_signatures_fails_the_round() {
+        let state = collecting_signatures_state(Epoch(3), &[]);
+
+        let state = state.transition(RoundEvent::AggregationTriggered).unwrap();
+
+        assert_eq!(
+            RoundState::Failed(ProtocolError::NoSignatureReceived(Epoch(3))),
+            state
+        );
+        assert!(state
+            .transition(RoundEvent::RoundStarted(Epoch(4)))
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_multi_signer_multi_signature_ok() {
         let epoch = Epoch(5);
@@ -219,4 +1181,772 @@ mod tests {
             "no multi-signature were computed"
         );
     }
+
+    fn flat_rate_reward_policy(
+        contributions: &[SignerContribution],
+        epoch: Epoch,
+    ) -> Vec<RewardEntry> {
+        contributions
+            .iter()
+            .map(|contribution| RewardEntry {
+                epoch,
+                party_id: contribution.party_id.clone(),
+                reward: 10,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn compute_rewards_applies_the_policy_to_each_contributing_signer() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )))
+        .with_reward_store(reward_store());
+        multi_signer
+            .set_reward_policy(Arc::new(flat_rate_reward_policy))
+            .await;
+        let message = setup_message();
+
+        let mut contributing_signers = vec![];
+        for signer_fixture in fixture.signers_fixture() {
+            if let Some(signature) = signer_fixture
+                .protocol_signer
+                .sign(message.compute_hash().as_bytes())
+            {
+                let won_indexes = signature.indexes.clone();
+                multi_signer
+                    .verify_single_signature(
+                        &message,
+                        &entities::SingleSignatures::new(
+                            signer_fixture.signer_with_stake.party_id.to_owned(),
+                            signature.into(),
+                            won_indexes,
+                        ),
+                    )
+                    .await
+                    .expect("single signature should be valid");
+                contributing_signers.push(signer_fixture.signer_with_stake.party_id.to_owned());
+            }
+        }
+
+        let rewards = multi_signer.compute_rewards(epoch).await.unwrap();
+
+        assert!(!contributing_signers.is_empty());
+        contributing_signers.sort();
+        assert_eq!(
+            contributing_signers
+                .into_iter()
+                .map(|party_id| RewardEntry {
+                    epoch,
+                    party_id,
+                    reward: 10,
+                })
+                .collect::<Vec<_>>(),
+            rewards
+        );
+    }
+
+    fn reward_store() -> Arc<RewardStore> {
+        Arc::new(RewardStore::new(Arc::new(main_db_connection().unwrap())))
+    }
+
+    async fn insert_contributions(
+        reward_store: &RewardStore,
+        epoch: Epoch,
+        stakes: &[(&str, entities::Stake)],
+    ) {
+        for (party_id, stake) in stakes {
+            let single_signature = entities::SingleSignatures::new(
+                party_id.to_string(),
+                fake_keys::single_signature()[0].try_into().unwrap(),
+                vec![1],
+            );
+            reward_store
+                .record_contribution(epoch, *stake, &single_signature)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn stake_weighted_hash_depends_on_the_stakes_of_the_signers() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let reward_store = reward_store();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(Epoch(2), &fixture),
+        )))
+        .with_reward_store(reward_store.clone());
+        insert_contributions(&reward_store, Epoch(1), &[("party-1", 10), ("party-2", 30)]).await;
+        insert_contributions(&reward_store, Epoch(2), &[("party-1", 30), ("party-2", 10)]).await;
+
+        assert_eq!(
+            multi_signer.compute_flat_hash(Epoch(1)).await.unwrap(),
+            multi_signer.compute_flat_hash(Epoch(2)).await.unwrap()
+        );
+        assert_ne!(
+            multi_signer
+                .compute_stake_weighted_hash(Epoch(1))
+                .await
+                .unwrap(),
+            multi_signer
+                .compute_stake_weighted_hash(Epoch(2))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            multi_signer.compute_flat_hash(Epoch(2)).await.unwrap(),
+            multi_signer
+                .compute_contributions_hash(Epoch(2))
+                .await
+                .unwrap(),
+            "The contributions hash of flat protocol parameters should be the flat hash"
+        );
+        multi_signer
+            .compute_stake_weighted_hash(Epoch(3))
+            .await
+            .expect_err("An epoch without contribution should not have a weighted hash");
+    }
+
+    #[tokio::test]
+    async fn a_signature_received_twice_is_counted_once_in_the_contributions() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )))
+        .with_reward_store(reward_store())
+        .with_reward_policy(Arc::new(reward_per_signature_policy(10)));
+        let message = setup_message();
+        let (party_id, single_signature) = fixture
+            .signers_fixture()
+            .iter()
+            .find_map(|signer_fixture| {
+                signer_fixture
+                    .protocol_signer
+                    .sign(message.compute_hash().as_bytes())
+                    .map(|signature| {
+                        let won_indexes = signature.indexes.clone();
+                        (
+                            signer_fixture.signer_with_stake.party_id.to_owned(),
+                            entities::SingleSignatures::new(
+                                signer_fixture.signer_with_stake.party_id.to_owned(),
+                                signature.into(),
+                                won_indexes,
+                            ),
+                        )
+                    })
+            })
+            .expect("at least one signer should win a lottery");
+
+        for _ in 0..2 {
+            multi_signer
+                .verify_single_signature(&message, &single_signature)
+                .await
+                .expect("single signature should be valid");
+        }
+
+        assert_eq!(
+            vec![RewardEntry {
+                epoch,
+                party_id,
+                reward: 10,
+            }],
+            multi_signer.compute_rewards(epoch).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn compute_rewards_without_reward_policy_returns_no_reward() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(Epoch(5), &fixture),
+        )));
+
+        assert_eq!(
+            Vec::<RewardEntry>::new(),
+            multi_signer.compute_rewards(Epoch(5)).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn get_protocol_message_digest_is_cached_until_the_message_changes() {
+        let multi_signer =
+            MultiSignerImpl::new(Arc::new(RwLock::new(FakeEpochService::from_fixture(
+                Epoch(5),
+                &MithrilFixtureBuilder::default().with_signers(1).build(),
+            ))));
+        let mut message = entities::ProtocolMessage::new();
+        message.set_message_part(
+            entities::ProtocolMessagePartKey::SnapshotDigest,
+            "digest-1".to_string(),
+        );
+        multi_signer.update_current_message(message.clone()).await;
+
+        let digest = multi_signer.get_protocol_message_digest().await.unwrap();
+        assert_eq!(message.compute_hash(), digest);
+
+        // A cached digest is returned as is, without computing the hash of the message again
+        *multi_signer.protocol_message_digest.write().await = Some("cached-digest".to_string());
+        multi_signer.update_current_message(message.clone()).await;
+        assert_eq!(
+            "cached-digest",
+            multi_signer.get_protocol_message_digest().await.unwrap()
+        );
+
+        message.set_message_part(
+            entities::ProtocolMessagePartKey::SnapshotDigest,
+            "digest-2".to_string(),
+        );
+        multi_signer.update_current_message(message.clone()).await;
+        assert_eq!(
+            message.compute_hash(),
+            multi_signer.get_protocol_message_digest().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn clear_protocol_message_digest_cache_recomputes_the_digest() {
+        let multi_signer =
+            MultiSignerImpl::new(Arc::new(RwLock::new(FakeEpochService::from_fixture(
+                Epoch(5),
+                &MithrilFixtureBuilder::default().with_signers(1).build(),
+            ))));
+        let message = entities::ProtocolMessage::new();
+        multi_signer.update_current_message(message.clone()).await;
+        *multi_signer.protocol_message_digest.write().await = Some("cached-digest".to_string());
+
+        multi_signer.clear_protocol_message_digest_cache().await;
+
+        assert_eq!(
+            message.compute_hash(),
+            multi_signer.get_protocol_message_digest().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn get_protocol_message_digest_without_current_message_fails() {
+        let multi_signer =
+            MultiSignerImpl::new(Arc::new(RwLock::new(FakeEpochService::from_fixture(
+                Epoch(5),
+                &MithrilFixtureBuilder::default().with_signers(1).build(),
+            ))));
+
+        multi_signer
+            .get_protocol_message_digest()
+            .await
+            .expect_err("getting the digest without a protocol message should fail");
+    }
+
+    #[test]
+    fn threshold_policy_compute_threshold() {
+        assert_eq!(12, ThresholdPolicy::Fixed(12).compute_threshold(200));
+        assert_eq!(
+            4,
+            ThresholdPolicy::Proportional {
+                numerator: 2,
+                denominator: 100,
+                minimum: 1,
+            }
+            .compute_threshold(200)
+        );
+        assert_eq!(
+            3,
+            ThresholdPolicy::Proportional {
+                numerator: 1,
+                denominator: 100,
+                minimum: 3,
+            }
+            .compute_threshold(10)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_proportional_threshold_is_pinned_to_its_minimum() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(200).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(Epoch(5), &fixture),
+        )))
+        .with_threshold_policy(ThresholdPolicy::Proportional {
+            numerator: 1,
+            denominator: 100,
+            minimum: 5,
+        });
+
+        assert_eq!(5, multi_signer.get_current_threshold().await);
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_threshold_defaults_to_the_protocol_parameters_k() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(Epoch(5), &fixture),
+        )));
+
+        assert_eq!(
+            fixture.protocol_parameters().k,
+            multi_signer.get_current_threshold().await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_threshold_is_never_below_the_protocol_parameters_k() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(Epoch(5), &fixture),
+        )))
+        .with_threshold_policy(ThresholdPolicy::Fixed(1));
+
+        assert_eq!(
+            fixture.protocol_parameters().k,
+            multi_signer.get_current_threshold().await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_threshold_is_recomputed_at_each_epoch() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let epoch_service = Arc::new(RwLock::new(FakeEpochService::from_fixture(
+            Epoch(5),
+            &fixture,
+        )));
+        let multi_signer = MultiSignerImpl::new(epoch_service.clone()).with_threshold_policy(
+            ThresholdPolicy::Proportional {
+                numerator: 2,
+                denominator: 1,
+                minimum: 1,
+            },
+        );
+        assert_eq!(6, multi_signer.get_current_threshold().await);
+
+        let next_fixture = MithrilFixtureBuilder::default().with_signers(4).build();
+        *epoch_service.write().await = FakeEpochService::from_fixture(Epoch(6), &next_fixture);
+
+        assert_eq!(8, multi_signer.get_current_threshold().await);
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_does_not_aggregate_below_the_policy_threshold() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let protocol_parameters = fixture.protocol_parameters();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )))
+        // More than the number of lotteries: the quorum can't be reached
+        .with_threshold_policy(ThresholdPolicy::Fixed(protocol_parameters.m + 1));
+
+        let message = setup_message();
+        let signatures: Vec<_> = fixture
+            .signers_fixture()
+            .iter()
+            .filter_map(|signer| signer.sign(&message))
+            .collect();
+        let open_message = OpenMessage {
+            epoch,
+            protocol_message: message,
+            single_signatures: signatures,
+            ..OpenMessage::dummy()
+        };
+
+        assert!(multi_signer
+            .create_multi_signature(&open_message)
+            .await
+            .expect("create multi signature should not fail")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_bft_quorum() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(10).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )))
+        .with_threshold_policy(ThresholdPolicy::Fixed(1))
+        .with_bft_quorum(0.67);
+        let message = setup_message();
+        let signatures: Vec<_> = fixture
+            .signers_fixture()
+            .iter()
+            .filter_map(|signer| signer.sign(&message))
+            .collect();
+        assert!(
+            signatures.len() >= 7,
+            "at least 7 signers should win a lottery, got {}",
+            signatures.len()
+        );
+        let open_message = |nb_signatures: usize| OpenMessage {
+            epoch,
+            protocol_message: message.clone(),
+            single_signatures: signatures[..nb_signatures].to_vec(),
+            expires_at: Some(Utc::now() - chrono::Duration::seconds(1)),
+            ..OpenMessage::dummy()
+        };
+
+        assert!(multi_signer
+            .create_multi_signature(&open_message(7))
+            .await
+            .expect("create multi signature should not fail")
+            .is_some());
+
+        let error = multi_signer
+            .create_multi_signature(&open_message(6))
+            .await
+            .expect_err("create multi signature should fail when the BFT quorum is not met");
+        assert_eq!(
+            Some(&ProtocolError::BftQuorumNotMet {
+                received: 6,
+                required: 7,
+            }),
+            error.downcast_ref::<ProtocolError>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_bft_quorum_waits_for_signatures_until_the_end_of_the_round() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(10).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )))
+        .with_threshold_policy(ThresholdPolicy::Fixed(1))
+        .with_bft_quorum(0.67);
+        let message = setup_message();
+        let signatures: Vec<_> = fixture
+            .signers_fixture()
+            .iter()
+            .filter_map(|signer| signer.sign(&message))
+            .take(5)
+            .collect();
+        let open_message = OpenMessage {
+            epoch,
+            protocol_message: message,
+            single_signatures: signatures,
+            expires_at: Some(Utc::now() + chrono::Duration::minutes(10)),
+            ..OpenMessage::dummy()
+        };
+
+        assert!(multi_signer
+            .create_multi_signature(&open_message)
+            .await
+            .expect("create multi signature should not fail before the end of the round")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_handle_fork_drops_the_signatures_of_the_round() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let event_log = EpochEventLog::default();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )))
+        .with_event_log(event_log.clone());
+        // Two beacons of the same epoch, the second one being on the canonical chain
+        let forked_beacon = CardanoDbBeacon::new("devnet", *epoch, 20);
+        let canonical_beacon = CardanoDbBeacon::new("devnet", *epoch, 15);
+
+        let message = setup_message();
+        let signature = fixture
+            .signers_fixture()
+            .iter()
+            .find_map(|signer| signer.sign(&message))
+            .expect("at least one signer should be able to sign the message");
+        multi_signer
+            .verify_single_signature(&message, &signature)
+            .await
+            .expect("single signature should be valid");
+        assert_eq!(
+            RoundState::CollectingSignatures {
+                epoch: forked_beacon.epoch,
+                received: HashSet::from([signature.party_id.clone()]),
+            },
+            multi_signer.get_round_state().await
+        );
+
+        multi_signer
+            .handle_fork(canonical_beacon.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(RoundState::Idle, multi_signer.get_round_state().await);
+        assert_eq!(
+            vec![EpochEvent::ForkDetected(canonical_beacon)],
+            event_log.last_events(10).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_report_the_invalid_signatures_of_the_signers() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )));
+
+        let message = setup_message();
+        let mut other_message = message.clone();
+        other_message.set_message_part(
+            entities::ProtocolMessagePartKey::SnapshotDigest,
+            "another-digest".to_string(),
+        );
+        let invalid_signature = fixture
+            .signers_fixture()
+            .iter()
+            .find_map(|signer| signer.sign(&other_message))
+            .expect("at least one signer should be able to sign the message");
+        let party_id = invalid_signature.party_id.clone();
+
+        for _ in 0..3 {
+            multi_signer
+                .verify_single_signature(&message, &invalid_signature)
+                .await
+                .expect_err("invalid single signature should fail to verify");
+        }
+
+        let report = multi_signer.get_invalid_signer_report(epoch).await;
+        assert_eq!(1, report.len());
+        assert_eq!(party_id, report[0].party_id);
+        assert_eq!(3, report[0].failure_count);
+        assert!(!report[0].last_error.is_empty());
+        assert!(multi_signer
+            .get_invalid_signer_report(epoch.next())
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_invalid_signatures_do_not_reject_the_valid_signatures_of_the_signer()
+    {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )));
+
+        let message = setup_message();
+        let mut other_message = message.clone();
+        other_message.set_message_part(
+            entities::ProtocolMessagePartKey::SnapshotDigest,
+            "another-digest".to_string(),
+        );
+        let (forged_signature, valid_signature) = fixture
+            .signers_fixture()
+            .iter()
+            .find_map(|signer| Some((signer.sign(&other_message)?, signer.sign(&message)?)))
+            .expect("at least one signer should be able to sign both messages");
+
+        for _ in 0..10 {
+            multi_signer
+                .verify_single_signature(&message, &forged_signature)
+                .await
+                .expect_err("forged single signature should fail to verify");
+        }
+
+        multi_signer
+            .verify_single_signature(&message, &valid_signature)
+            .await
+            .expect("valid single signature of a reported signer should be verified");
+    }
+
+    #[tokio::test]
+    async fn test_multi_signer_does_not_report_invalid_signatures_of_unknown_parties() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )));
+
+        let message = setup_message();
+        let mut other_message = message.clone();
+        other_message.set_message_part(
+            entities::ProtocolMessagePartKey::SnapshotDigest,
+            "another-digest".to_string(),
+        );
+        let unknown_party_signature = entities::SingleSignatures {
+            party_id: "unknown-party".to_string(),
+            ..fixture
+                .signers_fixture()
+                .iter()
+                .find_map(|signer| signer.sign(&other_message))
+                .expect("at least one signer should be able to sign the message")
+        };
+
+        multi_signer
+            .verify_single_signature(&message, &unknown_party_signature)
+            .await
+            .expect_err("signature of an unknown party should fail to verify");
+        assert!(multi_signer
+            .get_invalid_signer_report(epoch)
+            .await
+            .is_empty());
+    }
+
+    fn certify(fixture: &MithrilFixture, epoch: Epoch, message: &ProtocolMessage) -> Certificate {
+        let multi_signature = SignerBuilder::new(
+            &fixture.signers_with_stake(),
+            &fixture.protocol_parameters(),
+        )
+        .unwrap()
+        .build_multi_signer()
+        .aggregate_single_signatures(&fixture.sign_all(message), message)
+        .unwrap();
+        let metadata = CertificateMetadata::new(
+            "devnet",
+            1,
+            "0.1.0",
+            fixture.protocol_parameters(),
+            Utc::now(),
+            Utc::now(),
+            fixture.stake_distribution_parties(),
+        );
+
+        Certificate::new(
+            "previous_hash",
+            epoch,
+            metadata,
+            message.clone(),
+            fixture.compute_avk(),
+            CertificateSignature::MultiSignature(SignedEntityType::dummy(), multi_signature),
+        )
+    }
+
+    fn assert_verification_error(expected: VerificationError, result: StdResult<()>) {
+        let error = result.expect_err("verification should fail");
+        assert_eq!(
+            Some(&expected),
+            error.downcast_ref::<VerificationError>(),
+            "unexpected error: {error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_aggregate_signature_of_a_valid_certificate() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )));
+        let certificate = certify(&fixture, epoch, &setup_message());
+
+        multi_signer
+            .verify_aggregate_signature(&certificate)
+            .await
+            .expect("certificate should be valid");
+    }
+
+    #[tokio::test]
+    async fn verify_aggregate_signature_of_a_certificate_with_a_tampered_protocol_message() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )));
+        let mut certificate = certify(&fixture, epoch, &setup_message());
+        certificate.protocol_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotDigest,
+            "tampered-digest".to_string(),
+        );
+
+        assert_verification_error(
+            VerificationError::MessageMismatch(certificate.hash.clone()),
+            multi_signer.verify_aggregate_signature(&certificate).await,
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_aggregate_signature_of_a_certificate_with_a_tampered_signed_message() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )));
+        let mut certificate = certify(&fixture, epoch, &setup_message());
+        certificate.protocol_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotDigest,
+            "tampered-digest".to_string(),
+        );
+        certificate.signed_message = certificate.protocol_message.compute_hash();
+
+        let error = multi_signer
+            .verify_aggregate_signature(&certificate)
+            .await
+            .expect_err("verification should fail");
+
+        assert!(
+            matches!(
+                error.downcast_ref::<VerificationError>(),
+                Some(VerificationError::SignatureInvalid { .. })
+            ),
+            "unexpected error: {error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_aggregate_signature_uses_the_signers_of_the_certificate_epoch() {
+        let certificate_epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let current_epoch_fixture = MithrilFixtureBuilder::default()
+            .with_signers(5)
+            .with_party_id_seed([7; 32])
+            .build();
+        let mut verification_key_store = MockVerificationKeyStorer::new();
+        let signers_with_stake = fixture.signers_with_stake();
+        verification_key_store
+            .expect_get_signers()
+            .with(eq(certificate_epoch
+                .offset_to_signer_retrieval_epoch()
+                .unwrap()))
+            .return_once(move |_| Ok(Some(signers_with_stake)));
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(certificate_epoch + 3, &current_epoch_fixture),
+        )))
+        .with_verification_key_store(Arc::new(verification_key_store));
+        let certificate = certify(&fixture, certificate_epoch, &setup_message());
+
+        multi_signer
+            .verify_aggregate_signature(&certificate)
+            .await
+            .expect("certificate should be valid");
+    }
+
+    #[tokio::test]
+    async fn verify_aggregate_signature_without_store_fails_for_a_certificate_of_another_epoch() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(Epoch(8), &fixture),
+        )));
+        let certificate = certify(&fixture, Epoch(5), &setup_message());
+
+        let error = multi_signer
+            .verify_aggregate_signature(&certificate)
+            .await
+            .expect_err("certificate of another epoch can not be verified without a store");
+
+        assert!(
+            error.downcast_ref::<VerificationError>().is_none(),
+            "the certificate should not be reported as invalid: {error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_aggregate_signature_of_a_certificate_signed_by_other_signers() {
+        let epoch = Epoch(5);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let other_fixture = MithrilFixtureBuilder::default()
+            .with_signers(5)
+            .with_party_id_seed([7; 32])
+            .build();
+        let multi_signer = MultiSignerImpl::new(Arc::new(RwLock::new(
+            FakeEpochService::from_fixture(epoch, &fixture),
+        )));
+        let certificate = certify(&other_fixture, epoch, &setup_message());
+
+        assert_verification_error(
+            VerificationError::StakeDistributionMismatch(certificate.hash.clone()),
+            multi_signer.verify_aggregate_signature(&certificate).await,
+        );
+    }
 }
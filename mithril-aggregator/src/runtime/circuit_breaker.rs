@@ -0,0 +1,188 @@
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// State of a [CircuitBreaker]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitBreakerState {
+    /// The calls are permitted
+    Closed,
+
+    /// The calls are rejected since the given instant
+    Open(Instant),
+
+    /// The recovery timeout is elapsed, a limited number of calls are permitted to check if
+    /// the dependency has recovered
+    HalfOpen,
+}
+
+/// Circuit breaker that stops the calls to a failing dependency.
+///
+/// The circuit opens after `failure_threshold` consecutive failures, the calls are then rejected
+/// until the `recovery_timeout` is elapsed. The circuit is then half open: up to
+/// `half_open_max_attempts` calls are permitted, the first success closes the circuit while
+/// failing all of them opens it again.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    /// Number of consecutive failures that opens the circuit
+    pub failure_threshold: u32,
+
+    /// Time during which the circuit stays open
+    pub recovery_timeout: Duration,
+
+    /// Number of calls permitted while the circuit is half open
+    pub half_open_max_attempts: u32,
+
+    state: CircuitBreakerState,
+    consecutive_failures: u32,
+    half_open_attempts: u32,
+}
+
+impl CircuitBreaker {
+    /// CircuitBreaker factory, the circuit starts closed
+    pub fn new(
+        failure_threshold: u32,
+        recovery_timeout: Duration,
+        half_open_max_attempts: u32,
+    ) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            recovery_timeout,
+            half_open_max_attempts: half_open_max_attempts.max(1),
+            state: CircuitBreakerState::Closed,
+            consecutive_failures: 0,
+            half_open_attempts: 0,
+        }
+    }
+
+    /// Current state of the circuit
+    pub fn state(&self) -> CircuitBreakerState {
+        self.state
+    }
+
+    /// Check if a call is permitted, the circuit becomes half open if it's open since the
+    /// recovery timeout.
+    pub fn is_call_permitted(&mut self) -> bool {
+        match self.state {
+            CircuitBreakerState::Closed => true,
+            CircuitBreakerState::Open(opened_at) => {
+                if opened_at.elapsed() < self.recovery_timeout {
+                    return false;
+                }
+                self.state = CircuitBreakerState::HalfOpen;
+                self.half_open_attempts = 1;
+                true
+            }
+            CircuitBreakerState::HalfOpen => {
+                if self.half_open_attempts >= self.half_open_max_attempts {
+                    return false;
+                }
+                self.half_open_attempts += 1;
+                true
+            }
+        }
+    }
+
+    /// Time left before the open circuit becomes half open, `None` if the circuit is not open.
+    pub fn remaining_open_duration(&self) -> Option<Duration> {
+        match self.state {
+            CircuitBreakerState::Open(opened_at) => {
+                Some(self.recovery_timeout.saturating_sub(opened_at.elapsed()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Record a successful call, closing the circuit.
+    pub fn record_success(&mut self) {
+        self.state = CircuitBreakerState::Closed;
+        self.consecutive_failures = 0;
+        self.half_open_attempts = 0;
+    }
+
+    /// Record a failed call, opening the circuit if the failure threshold is reached or if
+    /// the last permitted call of the half open circuit failed.
+    pub fn record_failure(&mut self) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+
+        let must_open = match self.state {
+            CircuitBreakerState::Closed => self.consecutive_failures >= self.failure_threshold,
+            CircuitBreakerState::HalfOpen => self.half_open_attempts >= self.half_open_max_attempts,
+            CircuitBreakerState::Open(_) => false,
+        };
+        if must_open {
+            self.state = CircuitBreakerState::Open(Instant::now());
+            self.half_open_attempts = 0;
+        }
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(60), 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circuit_breaker() -> CircuitBreaker {
+        CircuitBreaker::new(3, Duration::from_secs(30), 2)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_opens_after_failure_threshold_consecutive_failures() {
+        let mut breaker = circuit_breaker();
+
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(CircuitBreakerState::Closed, breaker.state());
+
+        breaker.record_failure();
+        assert!(matches!(breaker.state(), CircuitBreakerState::Open(_)));
+        assert!(!breaker.is_call_permitted());
+        assert_eq!(
+            Some(Duration::from_secs(30)),
+            breaker.remaining_open_duration()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_closes_after_recovery_timeout_on_success() {
+        let mut breaker = circuit_breaker();
+        for _ in 0..3 {
+            breaker.record_failure();
+        }
+
+        tokio::time::advance(Duration::from_secs(29)).await;
+        assert!(!breaker.is_call_permitted());
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(breaker.is_call_permitted());
+        assert_eq!(CircuitBreakerState::HalfOpen, breaker.state());
+
+        breaker.record_success();
+        assert_eq!(CircuitBreakerState::Closed, breaker.state());
+        assert!(breaker.is_call_permitted());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn half_open_circuit_opens_again_if_all_its_attempts_fail() {
+        let mut breaker = circuit_breaker();
+        for _ in 0..3 {
+            breaker.record_failure();
+        }
+        tokio::time::advance(Duration::from_secs(30)).await;
+
+        assert!(breaker.is_call_permitted());
+        breaker.record_failure();
+        assert_eq!(CircuitBreakerState::HalfOpen, breaker.state());
+
+        assert!(breaker.is_call_permitted());
+        breaker.record_failure();
+        assert!(matches!(breaker.state(), CircuitBreakerState::Open(_)));
+        assert!(!breaker.is_call_permitted());
+    }
+}
@@ -0,0 +1,144 @@
+use mithril_common::entities::{CardanoDbBeacon, Epoch, PartyId};
+use serde::Serialize;
+use std::{collections::VecDeque, sync::Arc};
+use tokio::sync::RwLock;
+
+/// Default number of events kept by an [EpochEventLog]
+pub const DEFAULT_EPOCH_EVENT_LOG_CAPACITY: usize = 1000;
+
+/// Decision made or fact observed by the aggregator during an epoch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "value")]
+pub enum EpochEvent {
+    /// The signing of a new open message started at the given epoch
+    SigningStarted(Epoch),
+
+    /// A signer registered for the next signing round
+    SignerRegistered(PartyId),
+
+    /// A single signature was received from a signer
+    SignatureReceived(PartyId),
+
+    /// A certificate was issued, contains its hash
+    CertificateIssued(String),
+
+    /// A transition of the state machine failed, contains the error
+    EpochTransitionFailed(String),
+
+    /// The snapshot of the Cardano database was uploaded, contains its digest
+    SnapshotUploaded(String),
+
+    /// A fork of the Cardano chain was detected and the signing round was aborted, contains
+    /// the beacon of the canonical chain
+    ForkDetected(CardanoDbBeacon),
+
+    /// The rewards of the signers of the completed epoch were computed, contains the hash of
+    /// the signers contributions
+    RewardsComputed(String),
+}
+
+/// Bounded log of the last [EpochEvent]s, the oldest events are dropped when it is full.
+///
+/// Clones share the same underlying log.
+#[derive(Debug, Clone)]
+pub struct EpochEventLog {
+    events: Arc<RwLock<VecDeque<EpochEvent>>>,
+    capacity: usize,
+}
+
+impl EpochEventLog {
+    /// Create a new log that keeps at most `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: Arc::new(RwLock::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Append an event to the log, dropping the oldest one if the log is full
+    pub async fn record(&self, event: EpochEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut events = self.events.write().await;
+        while events.len() >= self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Get the last `limit` events, from the oldest to the latest
+    pub async fn last_events(&self, limit: usize) -> Vec<EpochEvent> {
+        let events = self.events.read().await;
+
+        events
+            .iter()
+            .skip(events.len().saturating_sub(limit))
+            .cloned()
+            .collect()
+    }
+
+    /// Get the underlying ring buffer
+    pub fn events(&self) -> Arc<RwLock<VecDeque<EpochEvent>>> {
+        self.events.clone()
+    }
+}
+
+impl Default for EpochEventLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_EPOCH_EVENT_LOG_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn oldest_events_are_dropped_when_capacity_is_reached() {
+        let event_log = EpochEventLog::new(2);
+
+        event_log.record(EpochEvent::SigningStarted(Epoch(1))).await;
+        event_log
+            .record(EpochEvent::CertificateIssued("hash-1".to_string()))
+            .await;
+        event_log.record(EpochEvent::SigningStarted(Epoch(2))).await;
+
+        assert_eq!(
+            vec![
+                EpochEvent::CertificateIssued("hash-1".to_string()),
+                EpochEvent::SigningStarted(Epoch(2)),
+            ],
+            event_log.last_events(10).await
+        );
+    }
+
+    #[tokio::test]
+    async fn last_events_returns_the_latest_events_in_chronological_order() {
+        let event_log = EpochEventLog::new(10);
+        for epoch in 1..=5 {
+            event_log
+                .record(EpochEvent::SigningStarted(Epoch(epoch)))
+                .await;
+        }
+
+        assert_eq!(
+            vec![
+                EpochEvent::SigningStarted(Epoch(4)),
+                EpochEvent::SigningStarted(Epoch(5)),
+            ],
+            event_log.last_events(2).await
+        );
+        assert!(event_log.last_events(0).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn nothing_is_recorded_with_a_zero_capacity() {
+        let event_log = EpochEventLog::new(0);
+
+        event_log.record(EpochEvent::SigningStarted(Epoch(1))).await;
+
+        assert!(event_log.events().read().await.is_empty());
+    }
+}
@@ -1,7 +1,19 @@
+mod circuit_breaker;
+mod epoch_event_log;
 mod error;
+mod pending_operations_limiter;
 mod runner;
+mod signature_checkpoint;
 mod state_machine;
+mod time_point_tracker;
 
+pub use circuit_breaker::{CircuitBreaker, CircuitBreakerState};
+pub use epoch_event_log::{EpochEvent, EpochEventLog, DEFAULT_EPOCH_EVENT_LOG_CAPACITY};
 pub use error::RuntimeError;
+pub use pending_operations_limiter::{
+    PendingOperationPermit, PendingOperationsLimiter, DEFAULT_MAX_PENDING_OPERATIONS,
+};
 pub use runner::{AggregatorConfig, AggregatorRunner, AggregatorRunnerTrait};
+pub use signature_checkpoint::SignatureCheckpoint;
 pub use state_machine::*;
+pub use time_point_tracker::TimePointTracker;
@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Context};
+use prometheus::{IntGauge, Opts, Registry};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+
+use mithril_common::StdResult;
+
+/// Default maximum number of signing operations processed at the same time
+pub const DEFAULT_MAX_PENDING_OPERATIONS: usize = 100;
+
+/// Backpressure on the signing operations (ie: the registration of the single signatures).
+///
+/// Each operation holds a permit while it's processed, when all the permits are held the new
+/// operations wait for a permit to be released instead of piling up in memory.
+pub struct PendingOperationsLimiter {
+    semaphore: Arc<Semaphore>,
+    max_pending_operations: usize,
+    pending_operations: IntGauge,
+}
+
+/// Permit held by an operation of a [PendingOperationsLimiter], released when dropped.
+pub struct PendingOperationPermit {
+    _permit: OwnedSemaphorePermit,
+    pending_operations: IntGauge,
+}
+
+impl Drop for PendingOperationPermit {
+    fn drop(&mut self) {
+        self.pending_operations.dec();
+    }
+}
+
+impl PendingOperationsLimiter {
+    /// Create a new PendingOperationsLimiter, `max_pending_operations` must be greater than zero.
+    pub fn new(max_pending_operations: usize) -> StdResult<Self> {
+        if max_pending_operations == 0 {
+            return Err(anyhow!(
+                "The maximum number of pending operations must be greater than zero"
+            ));
+        }
+
+        Ok(Self {
+            semaphore: Arc::new(Semaphore::new(max_pending_operations)),
+            max_pending_operations,
+            pending_operations: IntGauge::with_opts(Opts::new(
+                "mithril_aggregator_pending_signing_operations",
+                "Number of signing operations being processed",
+            ))?,
+        })
+    }
+
+    /// Register the pending operations gauge in the given registry
+    pub fn register_metrics(&self, registry: &Registry) -> StdResult<()> {
+        registry.register(Box::new(self.pending_operations.clone()))?;
+
+        Ok(())
+    }
+
+    /// Number of operations holding a permit
+    pub fn current_pending_operations(&self) -> usize {
+        self.max_pending_operations - self.semaphore.available_permits()
+    }
+
+    /// Wait for a permit, the operation must keep it until it completes.
+    pub async fn acquire(&self) -> StdResult<PendingOperationPermit> {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .with_context(|| "Pending operations limiter is closed")?;
+        self.pending_operations.inc();
+
+        Ok(PendingOperationPermit {
+            _permit: permit,
+            pending_operations: self.pending_operations.clone(),
+        })
+    }
+
+    /// Wait for a permit then run the given operation in a new task, the permit is released
+    /// when the operation completes.
+    pub async fn spawn<F>(&self, operation: F) -> StdResult<JoinHandle<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let permit = self.acquire().await?;
+
+        Ok(tokio::spawn(async move {
+            let output = operation.await;
+            drop(permit);
+            output
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[test]
+    fn creating_a_limiter_without_pending_operations_fails() {
+        assert!(
+            PendingOperationsLimiter::new(0).is_err(),
+            "a limiter without pending operations should not be created"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn enqueuing_one_operation_more_than_the_maximum_waits_for_one_to_complete() {
+        let max_pending_operations = 3;
+        let limiter = Arc::new(PendingOperationsLimiter::new(max_pending_operations).unwrap());
+        let mut completion_senders = vec![];
+        for _ in 0..max_pending_operations {
+            let (sender, receiver) = oneshot::channel::<()>();
+            completion_senders.push(sender);
+            limiter
+                .spawn(async move {
+                    let _ = receiver.await;
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(max_pending_operations, limiter.current_pending_operations());
+
+        let limiter_clone = limiter.clone();
+        let mut extra_operation = tokio::spawn(async move {
+            limiter_clone.spawn(async {}).await.unwrap().await.unwrap();
+        });
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert!(
+            !extra_operation.is_finished(),
+            "the operation over the maximum should wait for a permit"
+        );
+
+        completion_senders.remove(0).send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), &mut extra_operation)
+            .await
+            .expect("the operation over the maximum should run once a permit is released")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn permits_are_released_when_the_operations_complete() {
+        let limiter = PendingOperationsLimiter::new(2).unwrap();
+        let registry = Registry::new();
+        limiter.register_metrics(&registry).unwrap();
+
+        let permit = limiter.acquire().await.unwrap();
+        assert_eq!(1, limiter.current_pending_operations());
+        assert_eq!(1, limiter.pending_operations.get());
+
+        drop(permit);
+        limiter.spawn(async {}).await.unwrap().await.unwrap();
+        assert_eq!(0, limiter.current_pending_operations());
+        assert_eq!(0, limiter.pending_operations.get());
+    }
+}
@@ -5,18 +5,23 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use mithril_common::entities::{
-    Certificate, CertificatePending, Epoch, ProtocolMessage, ProtocolMessagePartKey,
-    SignedEntityConfig, SignedEntityType, Signer, TimePoint,
+    CardanoDbBeacon, Certificate, CertificatePending, Epoch, ProtocolMessage,
+    ProtocolMessagePartKey, SignedEntityConfig, SignedEntityType, Signer, SingleSignatures,
+    TimePoint,
 };
 use mithril_common::StdResult;
 use mithril_persistence::store::StakeStorer;
 
 use crate::entities::OpenMessage;
+use crate::runtime::EpochEvent;
 use crate::DependencyContainer;
 
 #[cfg(test)]
 use mockall::automock;
 
+/// Duration without any heartbeat after which a pending certificate is considered stale.
+const PENDING_CERTIFICATE_STALE_THRESHOLD: Duration = Duration::from_secs(3600);
+
 /// Configuration structure dedicated to the AggregatorRuntime.
 #[derive(Debug, Clone)]
 pub struct AggregatorConfig {
@@ -99,6 +104,20 @@ pub trait AggregatorRunnerTrait: Sync + Send {
     /// Drop the actual pending certificate in the store.
     async fn drop_pending_certificate(&self) -> StdResult<Option<CertificatePending>>;
 
+    /// List the epochs of the pending certificates that did not receive any heartbeat lately.
+    async fn list_stale_pending_certificate_epochs(&self) -> StdResult<Vec<Epoch>>;
+
+    /// Handle the epochs whose pending certificate is considered stale.
+    async fn handle_stale_epochs(&self, epochs: Vec<Epoch>) -> StdResult<()>;
+
+    /// Abort the current signing round after a fork of the Cardano chain was detected, the
+    /// open message of the given signed entity type is removed with its single signatures.
+    async fn handle_fork(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        canonical_time_point: &TimePoint,
+    ) -> StdResult<()>;
+
     /// Tell the certifier to try to create a new certificate.
     async fn create_certificate(
         &self,
@@ -127,6 +146,13 @@ pub trait AggregatorRunnerTrait: Sync + Send {
         signed_entity_type: &SignedEntityType,
         protocol_message: &ProtocolMessage,
     ) -> StdResult<OpenMessage>;
+
+    /// Register the given single signatures to the open message of the signed entity type.
+    async fn register_single_signatures(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: &[SingleSignatures],
+    ) -> StdResult<()>;
 }
 
 /// The runner responsibility is to expose a code API for the state machine. It
@@ -154,6 +180,85 @@ impl AggregatorRunner {
             .filter_unlocked_entries(signed_entity_types)
             .await
     }
+
+    /// Save the beacon of the given time point to the beacon store if it differs from the
+    /// beacon of the last time point read from the chain, then record the slot of its chain
+    /// point in the range of slots of its epoch.
+    ///
+    /// The slot is recorded on every tick, not only when the beacon changes, so the range of
+    /// slots of an epoch spans all the time points read during the epoch.
+    ///
+    /// A failure only delays the replication of the beacon, so it's logged and ignored.
+    async fn save_beacon_and_slot(&self, time_point: &TimePoint) {
+        let is_changed = match self.dependencies.time_point_tracker.last_time_point().await {
+            Some((last_time_point, _)) => {
+                last_time_point.epoch != time_point.epoch
+                    || last_time_point.immutable_file_number != time_point.immutable_file_number
+            }
+            None => true,
+        };
+        if is_changed {
+            let beacon = CardanoDbBeacon::new(
+                self.dependencies.signed_entity_config.network.to_string(),
+                *time_point.epoch,
+                time_point.immutable_file_number,
+            );
+            if let Err(error) = self
+                .dependencies
+                .beacon_store
+                .save_current_beacon(beacon)
+                .await
+            {
+                warn!("RUNNER: could not save the current beacon"; "error" => ?error);
+                return;
+            }
+        }
+        if let Err(error) = self
+            .dependencies
+            .beacon_store
+            .record_beacon_slot(time_point.epoch, time_point.chain_point.slot_number)
+            .await
+        {
+            warn!("RUNNER: could not record the slot of the current time point"; "error" => ?error);
+        }
+    }
+
+    /// Compute the rewards of the signers for their contribution to the completed epoch and
+    /// save them to the reward store.
+    ///
+    /// The hash of the contributions the rewards are computed from is recorded in the epoch
+    /// event log.
+    ///
+    /// The rewards are not needed to sign the new epoch, so a failure is logged and ignored.
+    async fn save_rewards(&self, completed_epoch: Epoch) {
+        let multi_signer = self.dependencies.multi_signer.read().await;
+        let rewards = match multi_signer.compute_rewards(completed_epoch).await {
+            Ok(rewards) => rewards,
+            Err(error) => {
+                warn!("RUNNER: could not compute the rewards"; "epoch" => ?completed_epoch, "error" => ?error);
+                return;
+            }
+        };
+        if !rewards.is_empty() {
+            match multi_signer
+                .compute_contributions_hash(completed_epoch)
+                .await
+            {
+                Ok(contributions_hash) => {
+                    self.dependencies
+                        .epoch_event_log
+                        .record(EpochEvent::RewardsComputed(contributions_hash))
+                        .await;
+                }
+                Err(error) => {
+                    warn!("RUNNER: could not compute the hash of the contributions"; "epoch" => ?completed_epoch, "error" => ?error);
+                }
+            }
+        }
+        if let Err(error) = self.dependencies.reward_store.save_rewards(rewards).await {
+            warn!("RUNNER: could not save the rewards"; "epoch" => ?completed_epoch, "error" => ?error);
+        }
+    }
 }
 
 #[cfg_attr(test, automock)]
@@ -167,6 +272,11 @@ impl AggregatorRunnerTrait for AggregatorRunner {
             .ticker_service
             .get_current_time_point()
             .await?;
+        self.save_beacon_and_slot(&time_point).await;
+        self.dependencies
+            .time_point_tracker
+            .record(time_point.clone())
+            .await;
 
         Ok(time_point)
     }
@@ -378,6 +488,62 @@ impl AggregatorRunnerTrait for AggregatorRunner {
         Ok(certificate_pending)
     }
 
+    async fn list_stale_pending_certificate_epochs(&self) -> StdResult<Vec<Epoch>> {
+        debug!("RUNNER: list stale pending certificate epochs");
+
+        self.dependencies
+            .certificate_pending_store
+            .list_stale(PENDING_CERTIFICATE_STALE_THRESHOLD)
+            .await
+            .with_context(|| "CertificatePendingStore can not list stale epochs")
+    }
+
+    async fn handle_stale_epochs(&self, epochs: Vec<Epoch>) -> StdResult<()> {
+        for epoch in epochs {
+            warn!(
+                " > handle_stale_epochs::no signer heartbeat received lately for the pending certificate";
+                "epoch" => ?epoch,
+                "threshold" => ?PENDING_CERTIFICATE_STALE_THRESHOLD
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn handle_fork(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        canonical_time_point: &TimePoint,
+    ) -> StdResult<()> {
+        debug!(
+            "RUNNER: handle fork";
+            "signed_entity_type" => ?signed_entity_type,
+            "canonical_time_point" => ?canonical_time_point
+        );
+        let canonical_beacon = CardanoDbBeacon::new(
+            self.dependencies.signed_entity_config.network.to_string(),
+            *canonical_time_point.epoch,
+            canonical_time_point.immutable_file_number,
+        );
+
+        self.dependencies
+            .multi_signer
+            .read()
+            .await
+            .handle_fork(canonical_beacon)
+            .await
+            .with_context(|| "Multi Signer can not handle the Cardano chain fork")?;
+        self.dependencies
+            .certifier_service
+            .abort_open_message(signed_entity_type)
+            .await
+            .with_context(|| {
+                format!("CertifierService can not abort the open message for signed_entity_type: '{signed_entity_type}'")
+            })?;
+
+        Ok(())
+    }
+
     async fn create_certificate(
         &self,
         signed_entity_type: &SignedEntityType,
@@ -472,6 +638,56 @@ impl AggregatorRunnerTrait for AggregatorRunner {
             .inform_epoch(epoch)
             .await?;
 
+        self.dependencies
+            .multi_signer
+            .read()
+            .await
+            .clear_protocol_message_digest_cache()
+            .await;
+
+        if let Ok(previous_epoch) = epoch.previous() {
+            self.save_rewards(previous_epoch).await;
+        }
+
+        let closed_sessions = self
+            .dependencies
+            .certificate_pending_store
+            .close_sessions_before(epoch)
+            .await
+            .with_context(|| {
+                format!("CertificatePendingStore can not close the sessions before epoch '{epoch}'")
+            })?;
+        for (closed_epoch, outcome) in closed_sessions {
+            debug!(" > inform_new_epoch::pending certificate session closed"; "epoch" => ?closed_epoch, "outcome" => ?outcome);
+        }
+
+        // Moving the archives can take a while, it's done in the background to not delay
+        // the signing of the new epoch
+        if let Some(tiered_snapshot_store) = self.dependencies.tiered_snapshot_store.clone() {
+            let threshold_epoch = tiered_snapshot_store.threshold_epoch(epoch);
+            tokio::spawn(async move {
+                if let Err(error) = tiered_snapshot_store.demote_to_cold(threshold_epoch).await {
+                    warn!("Failed to move the old snapshots to the cold storage: {error:?}");
+                }
+            });
+        }
+        if self.dependencies.snapshot_store.has_cold_storage() {
+            let snapshot_store = self.dependencies.snapshot_store.clone();
+            let threshold_epoch = Epoch(
+                epoch.saturating_sub(self.dependencies.config.snapshot_cold_storage_age_threshold),
+            );
+            tokio::spawn(async move {
+                if let Err(error) = snapshot_store
+                    .archive_to_cold_before_epoch(threshold_epoch)
+                    .await
+                {
+                    warn!(
+                        "Failed to move the old snapshot archives to the cold storage: {error:?}"
+                    );
+                }
+            });
+        }
+
         Ok(())
     }
 
@@ -480,21 +696,58 @@ impl AggregatorRunnerTrait for AggregatorRunner {
         signed_entity_type: &SignedEntityType,
         protocol_message: &ProtocolMessage,
     ) -> StdResult<OpenMessage> {
-        self.dependencies
+        let open_message = self
+            .dependencies
             .certifier_service
             .create_open_message(signed_entity_type, protocol_message)
+            .await?;
+        self.dependencies
+            .multi_signer
+            .read()
             .await
+            .update_current_message(protocol_message.clone())
+            .await;
+
+        Ok(open_message)
+    }
+
+    async fn register_single_signatures(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: &[SingleSignatures],
+    ) -> StdResult<()> {
+        debug!("RUNNER: register single signatures"; "signed_entity_type" => ?signed_entity_type, "signatures" => signatures.len());
+        for signature in signatures {
+            self.dependencies
+                .certifier_service
+                .register_single_signature(signed_entity_type, signature)
+                .await
+                .with_context(|| {
+                    format!(
+                        "CertifierService can not register the single signature of party '{}'",
+                        signature.party_id
+                    )
+                })?;
+        }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 pub mod tests {
+    use crate::database::{
+        record::BeaconLogEntry,
+        repository::{MockBeaconStore, MockRewardStorer},
+    };
+    use crate::multi_signer::MockMultiSigner;
     use crate::services::FakeEpochService;
     use crate::{
-        entities::OpenMessage,
+        entities::{OpenMessage, RewardEntry},
         initialize_dependencies,
-        runtime::{AggregatorRunner, AggregatorRunnerTrait},
+        runtime::{AggregatorRunner, AggregatorRunnerTrait, EpochEvent},
         services::{MithrilStakeDistributionService, MockCertifierService},
+        store::{MockSnapshotStore, SessionOutcome},
         DependencyContainer, MithrilSignerRegisterer, SignerRegistrationRound,
     };
     use async_trait::async_trait;
@@ -505,8 +758,8 @@ pub mod tests {
         chain_observer::FakeObserver,
         digesters::DumbImmutableFileObserver,
         entities::{
-            CertificatePending, ProtocolMessage, SignedEntityType, Signer, StakeDistribution,
-            TimePoint,
+            CertificatePending, Epoch, ProtocolMessage, SignedEntityType, Signer,
+            StakeDistribution, TimePoint,
         },
         signable_builder::SignableBuilderService,
         test_utils::{fake_data, MithrilFixtureBuilder},
@@ -516,6 +769,7 @@ pub mod tests {
     use mockall::predicate::eq;
     use mockall::{mock, Sequence};
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::sync::RwLock;
 
     mock! {
@@ -640,6 +894,44 @@ pub mod tests {
         assert_eq!(expected, res.unwrap());
     }
 
+    #[tokio::test]
+    async fn get_time_point_from_chain_saves_the_beacon_only_when_it_changes_but_records_every_slot(
+    ) {
+        let time_point = TimePoint::new(2, 17, ChainPoint::dummy());
+        let mut dependencies = initialize_dependencies().await;
+        let immutable_file_observer = Arc::new(DumbImmutableFileObserver::default());
+        immutable_file_observer
+            .shall_return(Some(time_point.immutable_file_number))
+            .await;
+        dependencies.ticker_service = Arc::new(MithrilTickerService::new(
+            Arc::new(FakeObserver::new(Some(time_point.clone()))),
+            immutable_file_observer,
+        ));
+        let mut beacon_store = MockBeaconStore::new();
+        beacon_store
+            .expect_save_current_beacon()
+            .withf(|beacon| beacon.epoch == Epoch(2) && beacon.immutable_file_number == 17)
+            .returning(|beacon| {
+                Ok(BeaconLogEntry {
+                    sequence: 1,
+                    epoch: beacon.epoch,
+                    beacon,
+                    recorded_at: chrono::NaiveDateTime::default(),
+                })
+            })
+            .once();
+        beacon_store
+            .expect_record_beacon_slot()
+            .withf(|epoch, slot| *epoch == Epoch(2) && *slot == ChainPoint::dummy().slot_number)
+            .returning(|_, _| Ok(()))
+            .times(2);
+        dependencies.beacon_store = Arc::new(beacon_store);
+        let runner = AggregatorRunner::new(Arc::new(dependencies));
+
+        runner.get_time_point_from_chain().await.unwrap();
+        runner.get_time_point_from_chain().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_update_stake_distribution() {
         let mut deps = initialize_dependencies().await;
@@ -780,6 +1072,29 @@ pub mod tests {
         assert_eq!(Some(open_message_expected), open_message_expired);
     }
 
+    #[tokio::test]
+    async fn test_handle_fork_aborts_the_open_message() {
+        let open_message = OpenMessage::dummy();
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_abort_open_message()
+            .with(eq(open_message.signed_entity_type.clone()))
+            .return_once(|_| Ok(Some(open_message)))
+            .once();
+
+        let mut deps = initialize_dependencies().await;
+        deps.certifier_service = Arc::new(mock_certifier_service);
+
+        let runner = build_runner_with_fixture_data(deps).await;
+        runner
+            .handle_fork(
+                &OpenMessage::dummy().signed_entity_type,
+                &TimePoint::dummy(),
+            )
+            .await
+            .expect("handle_fork should not fail");
+    }
+
     #[tokio::test]
     async fn test_create_new_pending_certificate() {
         let deps = initialize_dependencies().await;
@@ -907,6 +1222,141 @@ pub mod tests {
         runner.inform_new_epoch(current_epoch).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn inform_new_epoch_closes_the_pending_certificate_sessions_of_the_previous_epochs() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_inform_epoch()
+            .returning(|_| Ok(()));
+
+        let mut deps = initialize_dependencies().await;
+        deps.certifier_service = Arc::new(mock_certifier_service);
+        deps.epoch_service = Arc::new(RwLock::new(FakeEpochService::from_fixture(
+            Epoch(5),
+            &MithrilFixtureBuilder::default().build(),
+        )));
+        let certificate_pending_store = deps.certificate_pending_store.clone();
+        certificate_pending_store
+            .save(CertificatePending {
+                epoch: Epoch(4),
+                ..fake_data::certificate_pending()
+            })
+            .await
+            .unwrap();
+        let runner = AggregatorRunner::new(Arc::new(deps));
+
+        runner.inform_new_epoch(Epoch(5)).await.unwrap();
+
+        assert_eq!(
+            Some(SessionOutcome::Aborted),
+            certificate_pending_store
+                .get_session_outcome(Epoch(4))
+                .await
+                .unwrap()
+        );
+        assert_eq!(None, certificate_pending_store.get().await.unwrap());
+        certificate_pending_store
+            .acquire_epoch_session(Epoch(5))
+            .await
+            .expect("the session of the new epoch should be open");
+    }
+
+    #[tokio::test]
+    async fn inform_new_epoch_moves_the_old_snapshot_archives_to_the_cold_storage() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_inform_epoch()
+            .returning(|_| Ok(()));
+        let (archived_tx, mut archived_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut mock_snapshot_store = MockSnapshotStore::new();
+        mock_snapshot_store
+            .expect_has_cold_storage()
+            .return_const(true);
+        mock_snapshot_store
+            .expect_archive_to_cold_before_epoch()
+            .with(eq(Epoch(3)))
+            .return_once(move |epoch| {
+                archived_tx.send(epoch).unwrap();
+                Ok(vec![])
+            })
+            .times(1);
+
+        let mut deps = initialize_dependencies().await;
+        deps.config.snapshot_cold_storage_age_threshold = 2;
+        deps.certifier_service = Arc::new(mock_certifier_service);
+        deps.snapshot_store = Arc::new(mock_snapshot_store);
+        deps.epoch_service = Arc::new(RwLock::new(FakeEpochService::from_fixture(
+            Epoch(5),
+            &MithrilFixtureBuilder::default().build(),
+        )));
+        let runner = AggregatorRunner::new(Arc::new(deps));
+
+        runner.inform_new_epoch(Epoch(5)).await.unwrap();
+
+        // The archives are moved in the background
+        let archived_epoch = tokio::time::timeout(Duration::from_secs(5), archived_rx.recv())
+            .await
+            .expect("the old snapshot archives should be moved to the cold storage");
+        assert_eq!(Some(Epoch(3)), archived_epoch);
+    }
+
+    #[tokio::test]
+    async fn inform_new_epoch_saves_the_rewards_of_the_previous_epoch() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_inform_epoch()
+            .returning(|_| Ok(()));
+        let rewards = vec![RewardEntry {
+            epoch: Epoch(4),
+            party_id: "party-1".to_string(),
+            reward: 10,
+        }];
+        let mut mock_multi_signer = MockMultiSigner::new();
+        mock_multi_signer
+            .expect_compute_rewards()
+            .with(eq(Epoch(4)))
+            .return_once({
+                let rewards = rewards.clone();
+                move |_| Ok(rewards)
+            })
+            .times(1);
+        mock_multi_signer
+            .expect_compute_contributions_hash()
+            .with(eq(Epoch(4)))
+            .return_once(|_| Ok("contributions-hash".to_string()))
+            .times(1);
+        mock_multi_signer
+            .expect_clear_protocol_message_digest_cache()
+            .return_const(())
+            .times(1);
+        let mut mock_reward_store = MockRewardStorer::new();
+        mock_reward_store
+            .expect_save_rewards()
+            .with(eq(rewards))
+            .return_once(|_| Ok(()))
+            .times(1);
+
+        let mut deps = initialize_dependencies().await;
+        deps.certifier_service = Arc::new(mock_certifier_service);
+        deps.multi_signer = Arc::new(RwLock::new(mock_multi_signer));
+        deps.reward_store = Arc::new(mock_reward_store);
+        deps.epoch_service = Arc::new(RwLock::new(FakeEpochService::from_fixture(
+            Epoch(5),
+            &MithrilFixtureBuilder::default().build(),
+        )));
+        let epoch_event_log = deps.epoch_event_log.clone();
+        let runner = AggregatorRunner::new(Arc::new(deps));
+
+        runner.inform_new_epoch(Epoch(5)).await.unwrap();
+
+        assert_eq!(
+            vec![EpochEvent::RewardsComputed(
+                "contributions-hash".to_string()
+            )],
+            epoch_event_log.last_events(1).await
+        );
+    }
+
     #[tokio::test]
     async fn test_update_protocol_parameters() {
         let mut mock_certifier_service = MockCertifierService::new();
@@ -918,7 +1368,7 @@ pub mod tests {
         let mut deps = initialize_dependencies().await;
         deps.certifier_service = Arc::new(mock_certifier_service);
         let protocol_parameters_store = deps.protocol_parameters_store.clone();
-        let expected_protocol_parameters = deps.config.protocol_parameters.clone();
+        let expected_protocol_parameters = deps.config.get_protocol_parameters().unwrap();
         let current_epoch = deps.ticker_service.get_current_epoch().await.unwrap();
         let insert_epoch = current_epoch.offset_to_protocol_parameters_recording_epoch();
 
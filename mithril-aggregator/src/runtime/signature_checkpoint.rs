@@ -0,0 +1,98 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use mithril_common::entities::{ProtocolMessage, SignedEntityType, SingleSignatures, TimePoint};
+use mithril_common::StdResult;
+
+/// Signing round in progress when the [AggregatorRuntime][super::AggregatorRuntime] was shut
+/// down, saved so the round can be resumed on the next startup without the signers having to
+/// send their signatures again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureCheckpoint {
+    /// Time point at which the signing round started
+    pub time_point: TimePoint,
+
+    /// Signed entity type of the open message being signed
+    pub signed_entity_type: SignedEntityType,
+
+    /// Protocol message of the open message being signed
+    pub protocol_message: ProtocolMessage,
+
+    /// Single signatures received for the open message
+    pub single_signatures: Vec<SingleSignatures>,
+}
+
+impl SignatureCheckpoint {
+    /// Write the checkpoint to the given file.
+    ///
+    /// The checkpoint is first written to a temporary file then renamed, so an interrupted
+    /// write can't leave a truncated checkpoint.
+    pub fn write_to(&self, path: &Path) -> StdResult<()> {
+        let temporary_path = path.with_extension("tmp");
+        let content = serde_json::to_vec(self).with_context(|| {
+            format!(
+                "Could not serialize the signature checkpoint of '{}'",
+                self.signed_entity_type
+            )
+        })?;
+        std::fs::write(&temporary_path, content).with_context(|| {
+            format!(
+                "Could not write the signature checkpoint to '{}'",
+                temporary_path.display()
+            )
+        })?;
+        std::fs::rename(&temporary_path, path).with_context(|| {
+            format!(
+                "Could not move the signature checkpoint to '{}'",
+                path.display()
+            )
+        })?;
+
+        Ok(())
+    }
+
+    /// Read a checkpoint from the given file.
+    pub fn read_from(path: &Path) -> StdResult<Self> {
+        let content = std::fs::read(path).with_context(|| {
+            format!(
+                "Could not read the signature checkpoint from '{}'",
+                path.display()
+            )
+        })?;
+
+        serde_json::from_slice(&content).with_context(|| {
+            format!(
+                "Could not deserialize the signature checkpoint of '{}'",
+                path.display()
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::{fake_data, TempDir};
+
+    use super::*;
+
+    #[test]
+    fn read_a_written_checkpoint() {
+        let path = TempDir::create("signature_checkpoint", "read_a_written_checkpoint")
+            .join("checkpoint.json");
+        let checkpoint = SignatureCheckpoint {
+            time_point: TimePoint::dummy(),
+            signed_entity_type: SignedEntityType::dummy(),
+            protocol_message: ProtocolMessage::new(),
+            single_signatures: vec![
+                fake_data::single_signatures(vec![1, 4]),
+                fake_data::single_signatures(vec![2, 3]),
+            ],
+        };
+
+        checkpoint.write_to(&path).unwrap();
+
+        assert_eq!(checkpoint, SignatureCheckpoint::read_from(&path).unwrap());
+        assert!(!path.with_extension("tmp").exists());
+    }
+}
@@ -1,14 +1,23 @@
 use crate::{
     entities::OpenMessage,
-    runtime::{AggregatorRunnerTrait, RuntimeError},
-    AggregatorConfig,
+    runtime::{
+        AggregatorRunnerTrait, CircuitBreaker, CircuitBreakerState, EpochEvent, EpochEventLog,
+        RuntimeError, SignatureCheckpoint,
+    },
+    AggregatorConfig, CertificatePendingStore, SagaHandle,
 };
 
-use anyhow::Context;
-use mithril_common::entities::TimePoint;
+use anyhow::{anyhow, Context};
+use mithril_common::entities::{
+    Certificate, ProtocolMessagePartKey, SignedEntityType, SingleSignatures, TimePoint,
+};
+use mithril_common::StdResult;
 use slog_scope::{crit, info, trace, warn};
+use std::collections::VecDeque;
 use std::fmt::Display;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
 use tokio::time::sleep;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -65,6 +74,21 @@ pub struct AggregatorRuntime {
 
     /// specific runner for this state machine
     runner: Arc<dyn AggregatorRunnerTrait>,
+
+    /// log of the last events of the state machine
+    event_log: EpochEventLog,
+
+    /// stops the certificate creation attempts while it keeps failing
+    circuit_breaker: Mutex<CircuitBreaker>,
+
+    /// store recording the sagas of the certificate creations, if any
+    certificate_saga_store: Option<Arc<CertificatePendingStore>>,
+
+    /// saga of the ongoing certificate creation
+    certificate_saga: Mutex<Option<SagaHandle>>,
+
+    /// file where the signing round in progress is saved on shutdown, if any
+    checkpoint_path: Option<PathBuf>,
 }
 
 impl AggregatorRuntime {
@@ -90,9 +114,193 @@ impl AggregatorRuntime {
             config: aggregator_config,
             state,
             runner,
+            event_log: EpochEventLog::default(),
+            circuit_breaker: Mutex::new(CircuitBreaker::default()),
+            certificate_saga_store: None,
+            certificate_saga: Mutex::new(None),
+            checkpoint_path: None,
         })
     }
 
+    /// Guard the certificate creation with the given circuit breaker.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Mutex::new(circuit_breaker);
+        self
+    }
+
+    /// Record the events of the state machine in the given log.
+    pub fn with_event_log(mut self, event_log: EpochEventLog) -> Self {
+        self.event_log = event_log;
+        self
+    }
+
+    /// Record a saga in the given store for each certificate creation, it's committed once the
+    /// certificate is stored and aborted if the signing round ends without certificate.
+    pub fn with_certificate_saga_store(
+        mut self,
+        certificate_saga_store: Arc<CertificatePendingStore>,
+    ) -> Self {
+        self.certificate_saga_store = Some(certificate_saga_store);
+        self
+    }
+
+    /// Save the signing round in progress to the given file when [Self::save_checkpoint] is
+    /// called.
+    pub fn with_checkpoint_path(mut self, checkpoint_path: PathBuf) -> Self {
+        self.checkpoint_path = Some(checkpoint_path);
+        self
+    }
+
+    /// Save the signing round in progress, with the single signatures received so far, to the
+    /// checkpoint file so it can be [resumed][Self::restore_from_checkpoint] after a restart.
+    ///
+    /// Nothing is saved if the runtime is not signing an open message.
+    pub async fn save_checkpoint(&self) -> StdResult<()> {
+        let checkpoint_path = self
+            .checkpoint_path
+            .as_ref()
+            .ok_or_else(|| anyhow!("AggregatorRuntime has no checkpoint path"))?;
+        let AggregatorState::Signing(state) = &self.state else {
+            info!("STATE MACHINE: no signing round in progress, no checkpoint saved"; "state" => %self.state);
+            return Ok(());
+        };
+
+        let signed_entity_type = &state.open_message.signed_entity_type;
+        match self
+            .runner
+            .get_current_open_message_for_signed_entity_type(signed_entity_type)
+            .await
+            .with_context(|| format!("AggregatorRuntime can not get the current open message for signed entity type: '{signed_entity_type}'"))?
+        {
+            Some(open_message) if !open_message.is_certified => {
+                let checkpoint = SignatureCheckpoint {
+                    time_point: state.current_time_point.clone(),
+                    signed_entity_type: open_message.signed_entity_type,
+                    protocol_message: open_message.protocol_message,
+                    single_signatures: open_message.single_signatures,
+                };
+                checkpoint.write_to(checkpoint_path)?;
+                info!(
+                    "STATE MACHINE: signing round saved to the checkpoint";
+                    "signed_entity_type" => ?checkpoint.signed_entity_type,
+                    "signatures" => checkpoint.single_signatures.len()
+                );
+            }
+            _ => {
+                info!("STATE MACHINE: the open message is certified or gone, no checkpoint saved"; "signed_entity_type" => ?signed_entity_type);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resume the signing round saved in the given checkpoint file, registering again the
+    /// single signatures it contains, then remove the file.
+    ///
+    /// The round is not resumed if the epoch changed since the checkpoint was saved.
+    ///
+    /// A checkpoint that can't be restored does not prevent the runtime from starting: it is
+    /// renamed with a `discarded` extension so it's not restored again, and the runtime keeps
+    /// its initial state.
+    pub async fn restore_from_checkpoint(mut self, path: &Path) -> Self {
+        let initial_state = self.state.clone();
+        match self.try_restore_from_checkpoint(path).await {
+            Ok(()) => {
+                if let Err(error) = std::fs::remove_file(path) {
+                    warn!(
+                        "STATE MACHINE: could not remove the restored signature checkpoint";
+                        "path" => %path.display(), "error" => ?error
+                    );
+                }
+            }
+            Err(error) => {
+                let discarded_path = path.with_extension("discarded");
+                warn!(
+                    "STATE MACHINE: the signature checkpoint can not be restored, it is discarded";
+                    "path" => %path.display(), "discarded_path" => %discarded_path.display(), "error" => ?error
+                );
+                self.state = initial_state;
+                if let Err(error) = std::fs::rename(path, &discarded_path) {
+                    warn!(
+                        "STATE MACHINE: could not discard the signature checkpoint";
+                        "path" => %path.display(), "error" => ?error
+                    );
+                }
+            }
+        }
+
+        self
+    }
+
+    async fn try_restore_from_checkpoint(&mut self, path: &Path) -> StdResult<()> {
+        let checkpoint = SignatureCheckpoint::read_from(path)?;
+        let last_time_point = self
+            .runner
+            .get_time_point_from_chain()
+            .await
+            .with_context(|| "AggregatorRuntime can not get current time point from chain")?;
+
+        if last_time_point.epoch == checkpoint.time_point.epoch {
+            self.resume_signing_round(checkpoint).await?;
+        } else {
+            warn!(
+                "STATE MACHINE: the epoch changed since the checkpoint was saved, the signing round is not resumed";
+                "checkpoint_time_point" => ?checkpoint.time_point, "last_time_point" => ?last_time_point
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn resume_signing_round(&mut self, checkpoint: SignatureCheckpoint) -> StdResult<()> {
+        self.try_transition_from_idle_to_ready(None, checkpoint.time_point.clone())
+            .await?;
+        self.state = AggregatorState::Ready(ReadyState {
+            current_time_point: checkpoint.time_point.clone(),
+        });
+
+        let signed_entity_type = &checkpoint.signed_entity_type;
+        let open_message = match self
+            .runner
+            .get_current_open_message_for_signed_entity_type(signed_entity_type)
+            .await?
+        {
+            Some(open_message) => open_message,
+            None => {
+                self.runner
+                    .create_open_message(signed_entity_type, &checkpoint.protocol_message)
+                    .await?
+            }
+        };
+        if open_message.is_certified || open_message.is_expired {
+            info!("STATE MACHINE: the open message of the checkpoint is certified or expired, the signing round is not resumed"; "signed_entity_type" => ?signed_entity_type);
+            return Ok(());
+        }
+
+        // The signatures already registered to the open message must not be registered twice
+        let registered_signers = open_message.get_signers_id();
+        let missing_signatures: Vec<SingleSignatures> = checkpoint
+            .single_signatures
+            .into_iter()
+            .filter(|signature| !registered_signers.contains(&signature.party_id))
+            .collect();
+        self.runner
+            .register_single_signatures(signed_entity_type, &missing_signatures)
+            .await?;
+        let new_state = self
+            .transition_from_ready_to_signing(checkpoint.time_point, open_message)
+            .await?;
+        self.state = AggregatorState::Signing(new_state);
+        info!("STATE MACHINE: signing round resumed from the checkpoint"; "signed_entity_type" => ?signed_entity_type);
+
+        Ok(())
+    }
+
+    /// Return the last events recorded by the state machine, from the oldest to the latest.
+    pub fn event_log(&self) -> Arc<RwLock<VecDeque<EpochEvent>>> {
+        self.event_log.events()
+    }
+
     /// Return the actual state of the state machine.
     pub fn get_state(&self) -> String {
         match self.state {
@@ -105,6 +313,7 @@ impl AggregatorRuntime {
     /// Launches an infinite loop ticking the state machine.
     pub async fn run(&mut self) -> Result<(), RuntimeError> {
         info!("STATE MACHINE: launching");
+        self.recover_certificate_sagas().await;
 
         loop {
             if let Err(e) = self.cycle().await {
@@ -116,6 +325,9 @@ impl AggregatorRuntime {
                         nested_error: _,
                     } => {
                         crit!("state machine: a critical error occurred: {e:?}");
+                        self.event_log
+                            .record(EpochEvent::EpochTransitionFailed(e.to_string()))
+                            .await;
 
                         return Err(e);
                     }
@@ -142,6 +354,10 @@ impl AggregatorRuntime {
                                 .map(|e| format!("{e:?}"))
                                 .unwrap_or("None".into())
                         );
+                        self.event_log
+                            .record(EpochEvent::EpochTransitionFailed(e.to_string()))
+                            .await;
+                        self.abort_certificate_saga().await;
                         self.state = AggregatorState::Idle(IdleState {
                             current_time_point: None,
                         });
@@ -249,6 +465,13 @@ impl AggregatorRuntime {
                     info!("→ Epoch changed, transitioning to IDLE");
                     let new_state = self.transition_from_signing_to_idle(state).await?;
                     self.state = AggregatorState::Idle(new_state);
+                } else if is_chain_fork(&state.current_time_point, &last_time_point) {
+                    // SIGNING > READY
+                    warn!("→ Cardano chain fork detected, transitioning to READY"; "last_time_point" => ?last_time_point);
+                    let new_state = self
+                        .transition_from_signing_to_ready_fork(state, last_time_point)
+                        .await?;
+                    self.state = AggregatorState::Ready(new_state);
                 } else if exists_newer_open_message || is_expired_open_message {
                     // SIGNING > READY
                     info!("→ Open message changed, transitioning to READY");
@@ -315,14 +538,29 @@ impl AggregatorRuntime {
         state: SigningState,
     ) -> Result<ReadyState, RuntimeError> {
         trace!("launching transition from SIGNING to READY state");
-        let certificate = self
-            .runner
-            .create_certificate(&state.open_message.signed_entity_type)
-            .await?
-            .ok_or_else(|| RuntimeError::KeepState {
-                message: "not enough signature yet to create a certificate, waiting…".to_string(),
-                nested_error: None,
-            })?;
+        let certificate = match self.create_certificate_with_circuit_breaker(&state).await? {
+            Some(certificate) => certificate,
+            None => {
+                let stale_epochs = self.runner.list_stale_pending_certificate_epochs().await?;
+                if !stale_epochs.is_empty() {
+                    self.runner.handle_stale_epochs(stale_epochs).await?;
+                }
+
+                return Err(RuntimeError::KeepState {
+                    message: "not enough signature yet to create a certificate, waiting…"
+                        .to_string(),
+                    nested_error: None,
+                });
+            }
+        };
+        if let Some(saga) = self.certificate_saga.lock().await.take() {
+            if let Err(error) = saga.commit() {
+                warn!("Certificate saga could not be committed"; "error" => ?error);
+            }
+        }
+        self.event_log
+            .record(EpochEvent::CertificateIssued(certificate.hash.clone()))
+            .await;
         self.runner
             .drop_pending_certificate()
             .await
@@ -339,12 +577,67 @@ impl AggregatorRuntime {
                     .to_string(),
                 nested_error: Some(e),
             })?;
+        if let SignedEntityType::CardanoImmutableFilesFull(_) =
+            state.open_message.signed_entity_type
+        {
+            if let Some(digest) = certificate
+                .protocol_message
+                .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+            {
+                self.event_log
+                    .record(EpochEvent::SnapshotUploaded(digest.clone()))
+                    .await;
+            }
+        }
 
         Ok(ReadyState {
             current_time_point: state.current_time_point,
         })
     }
 
+    /// Create the certificate of the open message, unless the circuit breaker is open after
+    /// too many consecutive failures: the runtime then waits for the circuit to be half open
+    /// instead of trying again.
+    async fn create_certificate_with_circuit_breaker(
+        &self,
+        state: &SigningState,
+    ) -> Result<Option<Certificate>, RuntimeError> {
+        let mut circuit_breaker = self.circuit_breaker.lock().await;
+        if !circuit_breaker.is_call_permitted() {
+            let remaining = circuit_breaker.remaining_open_duration();
+            warn!(
+                "Circuit breaker of the certificate creation is open, waiting before trying again";
+                "state" => ?circuit_breaker.state(), "remaining" => ?remaining
+            );
+            if let Some(remaining) = remaining {
+                sleep(remaining).await;
+            }
+
+            return Err(RuntimeError::KeepState {
+                message: "certificate creation circuit breaker is open, waiting…".to_string(),
+                nested_error: None,
+            });
+        }
+
+        match self
+            .runner
+            .create_certificate(&state.open_message.signed_entity_type)
+            .await
+        {
+            Ok(certificate) => {
+                circuit_breaker.record_success();
+                Ok(certificate)
+            }
+            Err(error) => {
+                circuit_breaker.record_failure();
+                if let CircuitBreakerState::Open(_) = circuit_breaker.state() {
+                    warn!("Circuit breaker of the certificate creation opened after consecutive failures");
+                }
+                Err(error.into())
+            }
+        }
+    }
+
     /// Perform a transition from `SIGNING` state to `IDLE` state when a new
     /// epoch is detected.
     async fn transition_from_signing_to_idle(
@@ -353,6 +646,7 @@ impl AggregatorRuntime {
     ) -> Result<IdleState, RuntimeError> {
         trace!("launching transition from SIGNING to IDLE state");
         self.runner.drop_pending_certificate().await?;
+        self.abort_certificate_saga().await;
 
         Ok(IdleState {
             current_time_point: Some(state.current_time_point),
@@ -367,12 +661,62 @@ impl AggregatorRuntime {
     ) -> Result<ReadyState, RuntimeError> {
         trace!("launching transition from SIGNING to READY state");
         self.runner.drop_pending_certificate().await?;
+        self.abort_certificate_saga().await;
 
         Ok(ReadyState {
             current_time_point: state.current_time_point,
         })
     }
 
+    /// Perform a transition from `SIGNING` state to `READY` state when a fork
+    /// of the Cardano chain is detected.
+    async fn transition_from_signing_to_ready_fork(
+        &self,
+        state: SigningState,
+        canonical_time_point: TimePoint,
+    ) -> Result<ReadyState, RuntimeError> {
+        trace!("launching transition from SIGNING to READY state after a fork");
+        self.runner
+            .handle_fork(
+                &state.open_message.signed_entity_type,
+                &canonical_time_point,
+            )
+            .await?;
+        self.runner.drop_pending_certificate().await?;
+        self.abort_certificate_saga().await;
+
+        Ok(ReadyState {
+            current_time_point: canonical_time_point,
+        })
+    }
+
+    /// Recover the certificate sagas left in progress by a previous run of the aggregator.
+    async fn recover_certificate_sagas(&self) {
+        let Some(certificate_saga_store) = &self.certificate_saga_store else {
+            return;
+        };
+
+        match certificate_saga_store.recover_in_progress_sagas().await {
+            Ok(recovered_sagas) => {
+                for saga in recovered_sagas {
+                    warn!(
+                        "STATE MACHINE: recovered certificate saga left in progress";
+                        "saga_id" => saga.saga_id, "epoch" => ?saga.epoch, "status" => saga.status.as_str()
+                    );
+                }
+            }
+            Err(error) => {
+                warn!("STATE MACHINE: could not recover the certificate sagas"; "error" => ?error);
+            }
+        }
+    }
+
+    /// Abort the saga of the ongoing certificate creation, if any.
+    async fn abort_certificate_saga(&self) {
+        // The saga is marked as aborted when its handle is dropped
+        drop(self.certificate_saga.lock().await.take());
+    }
+
     /// Perform a transition from `READY` state to `SIGNING` state when a new
     /// open message is opened.
     async fn transition_from_ready_to_signing(
@@ -381,6 +725,14 @@ impl AggregatorRuntime {
         open_message: OpenMessage,
     ) -> Result<SigningState, RuntimeError> {
         trace!("launching transition from READY to SIGNING state");
+        if let Some(certificate_saga_store) = &self.certificate_saga_store {
+            let saga = certificate_saga_store
+                .begin_saga(new_time_point.epoch)
+                .await
+                .with_context(|| "AggregatorRuntime can not begin the certificate saga")?;
+            // A saga left by a previous failed transition is aborted when replaced
+            *self.certificate_saga.get_mut() = Some(saga);
+        }
 
         let certificate_pending = self
             .runner
@@ -392,6 +744,9 @@ impl AggregatorRuntime {
         self.runner
             .save_pending_certificate(certificate_pending.clone())
             .await?;
+        self.event_log
+            .record(EpochEvent::SigningStarted(new_time_point.epoch))
+            .await;
         let state = SigningState {
             current_time_point: new_time_point,
             open_message,
@@ -401,6 +756,13 @@ impl AggregatorRuntime {
     }
 }
 
+/// A fork of the Cardano chain is detected when two consecutive time points share the same
+/// epoch but the immutable file number goes backward.
+fn is_chain_fork(current_time_point: &TimePoint, last_time_point: &TimePoint) -> bool {
+    current_time_point.epoch == last_time_point.epoch
+        && last_time_point.immutable_file_number < current_time_point.immutable_file_number
+}
+
 #[cfg(test)]
 mod tests {
     use crate::entities::OpenMessage;
@@ -408,8 +770,14 @@ mod tests {
     use mockall::predicate;
     use std::time::Duration;
 
-    use mithril_common::entities::{Epoch, SignedEntityConfig, SignedEntityType};
-    use mithril_common::test_utils::fake_data;
+    use mithril_common::entities::{
+        CertificatePending, Epoch, SignedEntityConfig, SignedEntityType,
+    };
+    use mithril_common::test_utils::{fake_data, TempDir};
+    use mithril_persistence::store::adapter::DumbStoreAdapter;
+
+    use crate::database::record::CertificateSagaStatus;
+    use crate::database::test_helper::main_db_connection;
 
     use super::super::runner::MockAggregatorRunner;
     use super::*;
@@ -684,6 +1052,107 @@ mod tests {
             .expect_create_certificate()
             .once()
             .returning(|_| Ok(None));
+        runner
+            .expect_list_stale_pending_certificate_epochs()
+            .once()
+            .returning(|| Ok(vec![]));
+        runner.expect_handle_stale_epochs().never();
+        let state = SigningState {
+            current_time_point: TimePoint::dummy(),
+            open_message: OpenMessage::dummy(),
+        };
+        let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner).await;
+        let err = runtime
+            .cycle()
+            .await
+            .expect_err("cycle should have returned an error");
+
+        match err {
+            RuntimeError::KeepState { .. } => (),
+            _ => panic!("KeepState error expected, got {err:?}."),
+        };
+
+        assert_eq!("signing".to_string(), runtime.get_state());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn signing_certificate_creation_is_stopped_by_the_circuit_breaker() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_time_point_from_chain()
+            .times(4)
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_get_current_open_message_for_signed_entity_type()
+            .times(4)
+            .returning(|_| Ok(Some(OpenMessage::dummy())));
+        let mut calls = 0;
+        runner
+            .expect_create_certificate()
+            .times(3)
+            .returning(move |_| {
+                calls += 1;
+                if calls <= 2 {
+                    Err(anyhow!("unresponsive cardano node"))
+                } else {
+                    Ok(None)
+                }
+            });
+        runner
+            .expect_list_stale_pending_certificate_epochs()
+            .once()
+            .returning(|| Ok(vec![]));
+        let state = SigningState {
+            current_time_point: TimePoint::dummy(),
+            open_message: OpenMessage::dummy(),
+        };
+        let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner)
+            .await
+            .with_circuit_breaker(CircuitBreaker::new(2, Duration::from_secs(10), 1));
+
+        runtime.cycle().await.unwrap_err();
+        runtime.cycle().await.unwrap_err();
+        assert!(matches!(
+            runtime.circuit_breaker.lock().await.state(),
+            CircuitBreakerState::Open(_)
+        ));
+
+        let start = tokio::time::Instant::now();
+        runtime.cycle().await.unwrap_err();
+        assert_eq!(Duration::from_secs(10), start.elapsed());
+
+        runtime.cycle().await.unwrap_err();
+        assert_eq!(
+            CircuitBreakerState::Closed,
+            runtime.circuit_breaker.lock().await.state()
+        );
+        assert_eq!("signing".to_string(), runtime.get_state());
+    }
+
+    #[tokio::test]
+    async fn signing_certificate_is_not_created_with_stale_epochs() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_time_point_from_chain()
+            .once()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_get_current_open_message_for_signed_entity_type()
+            .once()
+            .returning(|_| Ok(Some(OpenMessage::dummy())));
+        runner
+            .expect_create_certificate()
+            .once()
+            .returning(|_| Ok(None));
+        runner
+            .expect_list_stale_pending_certificate_epochs()
+            .once()
+            .returning(|| Ok(vec![Epoch(3)]));
+        runner
+            .expect_handle_stale_epochs()
+            .with(predicate::eq(vec![Epoch(3)]))
+            .once()
+            .returning(|_| Ok(()));
         let state = SigningState {
             current_time_point: TimePoint::dummy(),
             open_message: OpenMessage::dummy(),
@@ -775,6 +1244,421 @@ mod tests {
         assert_eq!("ready".to_string(), runtime.get_state());
     }
 
+    fn certificate_saga_store() -> Arc<CertificatePendingStore> {
+        Arc::new(
+            CertificatePendingStore::new(Box::new(
+                DumbStoreAdapter::<String, CertificatePending>::new(),
+            ))
+            .with_saga_connection(Arc::new(main_db_connection().unwrap())),
+        )
+    }
+
+    #[tokio::test]
+    async fn signing_certificate_is_created_commits_the_certificate_saga() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_time_point_from_chain()
+            .once()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_get_current_open_message_for_signed_entity_type()
+            .once()
+            .returning(|_| Ok(Some(OpenMessage::dummy())));
+        runner
+            .expect_create_certificate()
+            .return_once(move |_| Ok(Some(fake_data::certificate("whatever".to_string()))));
+        runner
+            .expect_drop_pending_certificate()
+            .once()
+            .returning(|| Ok(Some(fake_data::certificate_pending())));
+        runner
+            .expect_create_artifact()
+            .once()
+            .returning(|_, _| Ok(()));
+        let saga_store = certificate_saga_store();
+        let saga = saga_store.begin_saga(Epoch(1)).await.unwrap();
+        let saga_id = saga.saga_id();
+        let state = SigningState {
+            current_time_point: TimePoint::dummy(),
+            open_message: OpenMessage::dummy(),
+        };
+        let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner)
+            .await
+            .with_certificate_saga_store(saga_store.clone());
+        *runtime.certificate_saga.get_mut() = Some(saga);
+
+        runtime.cycle().await.unwrap();
+
+        assert_eq!(
+            CertificateSagaStatus::Committed,
+            saga_store.get_saga(saga_id).await.unwrap().unwrap().status
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_round_ended_without_certificate_aborts_the_certificate_saga() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_time_point_from_chain()
+            .once()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_get_current_open_message_for_signed_entity_type()
+            .once()
+            .returning(|_| {
+                Ok(Some(OpenMessage {
+                    signed_entity_type: SignedEntityType::MithrilStakeDistribution(Epoch(1)),
+                    ..OpenMessage::dummy()
+                }))
+            });
+        runner
+            .expect_drop_pending_certificate()
+            .once()
+            .returning(|| Ok(Some(fake_data::certificate_pending())));
+        let saga_store = certificate_saga_store();
+        let saga = saga_store.begin_saga(Epoch(1)).await.unwrap();
+        let saga_id = saga.saga_id();
+        let state = SigningState {
+            current_time_point: TimePoint::dummy(),
+            open_message: OpenMessage {
+                signed_entity_type: SignedEntityType::MithrilStakeDistribution(Epoch(2)),
+                ..OpenMessage::dummy()
+            },
+        };
+        let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner)
+            .await
+            .with_certificate_saga_store(saga_store.clone());
+        *runtime.certificate_saga.get_mut() = Some(saga);
+
+        runtime.cycle().await.unwrap();
+
+        assert_eq!("ready".to_string(), runtime.get_state());
+        assert_eq!(
+            CertificateSagaStatus::Aborted,
+            saga_store.get_saga(saga_id).await.unwrap().unwrap().status
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_chain_fork_aborts_the_signing_round() {
+        let forked_time_point = TimePoint::dummy();
+        let canonical_time_point = TimePoint {
+            immutable_file_number: forked_time_point.immutable_file_number - 1,
+            ..forked_time_point.clone()
+        };
+        let expected_time_point = canonical_time_point.clone();
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_time_point_from_chain()
+            .once()
+            .returning(move || Ok(canonical_time_point.clone()));
+        runner
+            .expect_get_current_open_message_for_signed_entity_type()
+            .once()
+            .returning(|_| Ok(Some(OpenMessage::dummy())));
+        runner
+            .expect_handle_fork()
+            .with(
+                predicate::eq(OpenMessage::dummy().signed_entity_type),
+                predicate::eq(expected_time_point.clone()),
+            )
+            .once()
+            .returning(|_, _| Ok(()));
+        runner
+            .expect_drop_pending_certificate()
+            .once()
+            .returning(|| Ok(Some(fake_data::certificate_pending())));
+        runner.expect_create_certificate().never();
+
+        let state = SigningState {
+            current_time_point: forked_time_point,
+            open_message: OpenMessage::dummy(),
+        };
+        let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner).await;
+        runtime.cycle().await.unwrap();
+
+        assert_eq!(
+            AggregatorState::Ready(ReadyState {
+                current_time_point: expected_time_point,
+            }),
+            runtime.state
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_cycle_is_recorded_in_event_log() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_time_point_from_chain()
+            .times(2)
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_get_current_non_certified_open_message()
+            .once()
+            .returning(|_| Ok(Some(OpenMessage::dummy())));
+        runner
+            .expect_create_new_pending_certificate()
+            .once()
+            .returning(|_, _| Ok(fake_data::certificate_pending()));
+        runner
+            .expect_save_pending_certificate()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_get_current_open_message_for_signed_entity_type()
+            .once()
+            .returning(|_| Ok(Some(OpenMessage::dummy())));
+        runner
+            .expect_create_certificate()
+            .return_once(move |_| Ok(Some(fake_data::certificate("certificate-hash".to_string()))));
+        runner
+            .expect_drop_pending_certificate()
+            .once()
+            .returning(|| Ok(Some(fake_data::certificate_pending())));
+        runner
+            .expect_create_artifact()
+            .once()
+            .returning(|_, _| Ok(()));
+
+        let mut runtime = init_runtime(
+            Some(AggregatorState::Ready(ReadyState {
+                current_time_point: TimePoint::dummy(),
+            })),
+            runner,
+        )
+        .await;
+        runtime.cycle().await.unwrap();
+        assert_eq!("signing".to_string(), runtime.get_state());
+        runtime.cycle().await.unwrap();
+        assert_eq!("ready".to_string(), runtime.get_state());
+
+        let event_log = runtime.event_log();
+        let events = event_log.read().await;
+        let signing_started_position = events
+            .iter()
+            .position(|e| *e == EpochEvent::SigningStarted(TimePoint::dummy().epoch))
+            .expect("SigningStarted event should have been recorded");
+        assert!(events
+            .iter()
+            .skip(signing_started_position + 1)
+            .any(|e| *e == EpochEvent::CertificateIssued("certificate-hash".to_string())));
+    }
+
+    fn signature_of(party_id: &str) -> SingleSignatures {
+        SingleSignatures {
+            party_id: party_id.to_string(),
+            ..fake_data::single_signatures(vec![1, 2])
+        }
+    }
+
+    #[tokio::test]
+    async fn signing_round_resumed_from_checkpoint_does_not_need_the_signatures_again() {
+        let checkpoint_path = TempDir::create(
+            "aggregator_runtime",
+            "signing_round_resumed_from_checkpoint",
+        )
+        .join("signature_checkpoint.json");
+        let received_signatures: Vec<SingleSignatures> = ["party-1", "party-2", "party-3"]
+            .into_iter()
+            .map(signature_of)
+            .collect();
+
+        // Shutdown after three of the five signatures are received
+        {
+            let open_message = OpenMessage {
+                single_signatures: received_signatures.clone(),
+                ..OpenMessage::dummy()
+            };
+            let mut runner = MockAggregatorRunner::new();
+            runner
+                .expect_get_current_open_message_for_signed_entity_type()
+                .once()
+                .returning(move |_| Ok(Some(open_message.clone())));
+            let state = SigningState {
+                current_time_point: TimePoint::dummy(),
+                open_message: OpenMessage::dummy(),
+            };
+            let runtime = init_runtime(Some(AggregatorState::Signing(state)), runner)
+                .await
+                .with_checkpoint_path(checkpoint_path.clone());
+
+            runtime.save_checkpoint().await.unwrap();
+        }
+
+        // Restart from the checkpoint, the open message was lost with the aggregator
+        let registered_signatures = Arc::new(std::sync::Mutex::new(Vec::<SingleSignatures>::new()));
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_time_point_from_chain()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_close_signer_registration_round()
+            .once()
+            .returning(|| Ok(()));
+        runner
+            .expect_update_era_checker()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_inform_new_epoch()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_update_stake_distribution()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_open_signer_registration_round()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_update_protocol_parameters()
+            .once()
+            .returning(|| Ok(()));
+        runner
+            .expect_precompute_epoch_data()
+            .once()
+            .returning(|| Ok(()));
+        runner
+            .expect_is_certificate_chain_valid()
+            .once()
+            .returning(|_| Ok(()));
+        {
+            let registered_signatures = registered_signatures.clone();
+            let mut is_open_message_created = false;
+            runner
+                .expect_get_current_open_message_for_signed_entity_type()
+                .returning(move |_| {
+                    let open_message = OpenMessage {
+                        single_signatures: registered_signatures.lock().unwrap().clone(),
+                        ..OpenMessage::dummy()
+                    };
+                    let result = is_open_message_created.then_some(open_message);
+                    is_open_message_created = true;
+                    Ok(result)
+                });
+        }
+        runner
+            .expect_create_open_message()
+            .once()
+            .returning(|_, _| Ok(OpenMessage::dummy()));
+        {
+            let registered_signatures = registered_signatures.clone();
+            runner
+                .expect_register_single_signatures()
+                .once()
+                .returning(move |_, signatures| {
+                    registered_signatures
+                        .lock()
+                        .unwrap()
+                        .extend_from_slice(signatures);
+                    Ok(())
+                });
+        }
+        runner
+            .expect_create_new_pending_certificate()
+            .once()
+            .returning(|_, _| Ok(fake_data::certificate_pending()));
+        runner
+            .expect_save_pending_certificate()
+            .once()
+            .returning(|_| Ok(()));
+        {
+            let registered_signatures = registered_signatures.clone();
+            runner
+                .expect_create_certificate()
+                .once()
+                .returning(move |_| {
+                    let certificate = (registered_signatures.lock().unwrap().len() == 5)
+                        .then(|| fake_data::certificate("certificate-hash".to_string()));
+                    Ok(certificate)
+                });
+        }
+        runner
+            .expect_drop_pending_certificate()
+            .once()
+            .returning(|| Ok(Some(fake_data::certificate_pending())));
+        runner
+            .expect_create_artifact()
+            .once()
+            .returning(|_, _| Ok(()));
+
+        let mut runtime = init_runtime(None, runner)
+            .await
+            .restore_from_checkpoint(&checkpoint_path)
+            .await;
+        assert_eq!("signing".to_string(), runtime.get_state());
+        assert_eq!(
+            received_signatures,
+            registered_signatures.lock().unwrap().clone()
+        );
+        assert!(!checkpoint_path.exists());
+
+        // The signers that did not sign before the shutdown send their signatures
+        registered_signatures
+            .lock()
+            .unwrap()
+            .extend(["party-4", "party-5"].into_iter().map(signature_of));
+        runtime.cycle().await.unwrap();
+
+        assert_eq!("ready".to_string(), runtime.get_state());
+        let event_log = runtime.event_log();
+        assert!(event_log
+            .read()
+            .await
+            .contains(&EpochEvent::CertificateIssued(
+                "certificate-hash".to_string()
+            )));
+    }
+
+    #[tokio::test]
+    async fn unreadable_checkpoint_is_discarded_and_the_runtime_keeps_its_initial_state() {
+        let checkpoint_path =
+            TempDir::create("aggregator_runtime", "unreadable_checkpoint_is_discarded")
+                .join("signature_checkpoint.json");
+        std::fs::write(&checkpoint_path, "not a checkpoint").unwrap();
+
+        let runtime = init_runtime(None, MockAggregatorRunner::new())
+            .await
+            .restore_from_checkpoint(&checkpoint_path)
+            .await;
+
+        assert_eq!("idle".to_string(), runtime.get_state());
+        assert!(!checkpoint_path.exists());
+        assert!(checkpoint_path.with_extension("discarded").exists());
+    }
+
+    #[tokio::test]
+    async fn checkpoint_failing_to_resume_is_discarded_and_the_runtime_keeps_its_initial_state() {
+        let checkpoint_path = TempDir::create(
+            "aggregator_runtime",
+            "checkpoint_failing_to_resume_is_discarded",
+        )
+        .join("signature_checkpoint.json");
+        SignatureCheckpoint {
+            time_point: TimePoint::dummy(),
+            signed_entity_type: OpenMessage::dummy().signed_entity_type,
+            protocol_message: OpenMessage::dummy().protocol_message,
+            single_signatures: vec![signature_of("party-1")],
+        }
+        .write_to(&checkpoint_path)
+        .unwrap();
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_time_point_from_chain()
+            .once()
+            .returning(|| Err(anyhow!("chain unreachable")));
+
+        let runtime = init_runtime(None, runner)
+            .await
+            .restore_from_checkpoint(&checkpoint_path)
+            .await;
+
+        assert_eq!("idle".to_string(), runtime.get_state());
+        assert!(!checkpoint_path.exists());
+        assert!(checkpoint_path.with_extension("discarded").exists());
+    }
+
     #[tokio::test]
     pub async fn critical_error() {
         let mut runner = MockAggregatorRunner::new();
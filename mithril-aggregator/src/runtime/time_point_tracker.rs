@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use mithril_common::entities::TimePoint;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+
+/// A time point read from the chain with the date at which it was read
+type ObservedTimePoint = (TimePoint, DateTime<Utc>);
+
+/// Keep track of the last [TimePoint] read from the chain and when it was read.
+///
+/// Clones share the same tracked time point.
+#[derive(Debug, Clone, Default)]
+pub struct TimePointTracker {
+    last_time_point: Arc<RwLock<Option<ObservedTimePoint>>>,
+}
+
+impl TimePointTracker {
+    /// Create a new tracker that did not observe any time point yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a time point read from the chain now
+    pub async fn record(&self, time_point: TimePoint) {
+        self.record_at(time_point, Utc::now()).await;
+    }
+
+    /// Record a time point read from the chain at the given date
+    pub async fn record_at(&self, time_point: TimePoint, observed_at: DateTime<Utc>) {
+        *self.last_time_point.write().await = Some((time_point, observed_at));
+    }
+
+    /// Get the last time point read from the chain and when it was read
+    pub async fn last_time_point(&self) -> Option<(TimePoint, DateTime<Utc>)> {
+        self.last_time_point.read().await.clone()
+    }
+
+    /// Check if no time point was read from the chain during the last `max_age`
+    pub async fn is_stale(&self, max_age: Duration) -> bool {
+        match self.last_time_point().await {
+            Some((_, observed_at)) => Utc::now()
+                .signed_duration_since(observed_at)
+                .to_std()
+                .is_ok_and(|age| age > max_age),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_AGE: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn tracker_without_time_point_is_stale() {
+        let tracker = TimePointTracker::new();
+
+        assert!(tracker.is_stale(MAX_AGE).await);
+    }
+
+    #[tokio::test]
+    async fn tracker_is_stale_when_last_time_point_is_older_than_max_age() {
+        let tracker = TimePointTracker::new();
+
+        tracker.record(TimePoint::dummy()).await;
+        assert!(!tracker.is_stale(MAX_AGE).await);
+
+        tracker
+            .record_at(
+                TimePoint::dummy(),
+                Utc::now() - chrono::Duration::seconds(61),
+            )
+            .await;
+        assert!(tracker.is_stale(MAX_AGE).await);
+    }
+
+    #[tokio::test]
+    async fn clones_share_the_same_time_point() {
+        let tracker = TimePointTracker::new();
+        let cloned_tracker = tracker.clone();
+
+        cloned_tracker.record(TimePoint::dummy()).await;
+
+        assert_eq!(
+            Some(TimePoint::dummy()),
+            tracker
+                .last_time_point()
+                .await
+                .map(|(time_point, _)| time_point)
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::ops::Range;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use slog_scope::warn;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use mithril_common::crypto_helper::MKTreeNode;
+use mithril_common::entities::{
+    BlockHash, BlockNumber, BlockRange, CardanoTransaction, ChainPoint, SlotNumber,
+};
+use mithril_common::StdResult;
+
+use crate::services::TransactionStore;
+
+struct BatchingState {
+    inner: Arc<dyn TransactionStore>,
+    buffer: Mutex<Vec<CardanoTransaction>>,
+}
+
+impl BatchingState {
+    /// Write the buffered transactions to the inner store.
+    ///
+    /// The buffer lock is held during the write so batches reach the inner store in the order
+    /// they were received. If the write fails the transactions are kept for the next flush.
+    async fn flush(&self) -> StdResult<()> {
+        let mut buffer = self.buffer.lock().await;
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let transactions = std::mem::take(&mut *buffer);
+        if let Err(error) = self.inner.store_transactions(transactions.clone()).await {
+            *buffer = transactions;
+            return Err(error);
+        }
+
+        Ok(())
+    }
+}
+
+/// A [TransactionStore] that buffers the stored transactions and writes them to an inner
+/// store in batches.
+///
+/// The buffer is flushed when it reaches `max_batch_size` transactions, when `flush_interval`
+/// elapses, or before any other call to the inner store so that reads always see the buffered
+/// transactions.
+pub struct BatchingTransactionStore {
+    state: Arc<BatchingState>,
+    max_batch_size: usize,
+    periodic_flush_task: JoinHandle<()>,
+}
+
+impl BatchingTransactionStore {
+    /// BatchingTransactionStore factory, must be called from within a Tokio runtime.
+    pub fn new(
+        inner: Arc<dyn TransactionStore>,
+        max_batch_size: usize,
+        flush_interval: Duration,
+    ) -> Self {
+        let state = Arc::new(BatchingState {
+            inner,
+            buffer: Mutex::new(Vec::new()),
+        });
+        let periodic_flush_task = tokio::spawn(Self::run_periodic_flush(
+            Arc::downgrade(&state),
+            flush_interval,
+        ));
+
+        Self {
+            state,
+            max_batch_size: max_batch_size.max(1),
+            periodic_flush_task,
+        }
+    }
+
+    async fn run_periodic_flush(state: Weak<BatchingState>, flush_interval: Duration) {
+        let mut interval = tokio::time::interval(flush_interval);
+        // The first tick completes immediately
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            if let Err(error) = state.flush().await {
+                warn!("BatchingTransactionStore: periodic flush failed"; "error" => ?error);
+            }
+        }
+    }
+
+    /// Write the buffered transactions to the inner store now, to be called before shutdown.
+    pub async fn flush(&self) -> StdResult<()> {
+        self.state.flush().await
+    }
+}
+
+impl Drop for BatchingTransactionStore {
+    fn drop(&mut self) {
+        self.periodic_flush_task.abort();
+    }
+}
+
+#[async_trait]
+impl TransactionStore for BatchingTransactionStore {
+    async fn get_highest_beacon(&self) -> StdResult<Option<ChainPoint>> {
+        self.flush().await?;
+        self.state.inner.get_highest_beacon().await
+    }
+
+    async fn store_transactions(&self, transactions: Vec<CardanoTransaction>) -> StdResult<()> {
+        let buffered_transactions = {
+            let mut buffer = self.state.buffer.lock().await;
+            buffer.extend(transactions);
+            buffer.len()
+        };
+
+        if buffered_transactions >= self.max_batch_size {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_block_interval_without_block_range_root(
+        &self,
+    ) -> StdResult<Option<Range<BlockNumber>>> {
+        self.flush().await?;
+        self.state
+            .inner
+            .get_block_interval_without_block_range_root()
+            .await
+    }
+
+    async fn get_transactions_in_range(
+        &self,
+        range: Range<BlockNumber>,
+    ) -> StdResult<Vec<CardanoTransaction>> {
+        self.flush().await?;
+        self.state.inner.get_transactions_in_range(range).await
+    }
+
+    async fn get_transactions_in_slot_range(
+        &self,
+        from_slot: SlotNumber,
+        to_slot: SlotNumber,
+    ) -> StdResult<Vec<CardanoTransaction>> {
+        self.flush().await?;
+        self.state
+            .inner
+            .get_transactions_in_slot_range(from_slot, to_slot)
+            .await
+    }
+
+    async fn get_transactions_by_block_hash(
+        &self,
+        block_hash: &BlockHash,
+    ) -> StdResult<Vec<CardanoTransaction>> {
+        self.flush().await?;
+        self.state
+            .inner
+            .get_transactions_by_block_hash(block_hash)
+            .await
+    }
+
+    async fn store_block_range_roots(
+        &self,
+        block_ranges: Vec<(BlockRange, MKTreeNode)>,
+    ) -> StdResult<()> {
+        self.flush().await?;
+        self.state.inner.store_block_range_roots(block_ranges).await
+    }
+
+    async fn remove_rolled_back_transactions_and_block_range(
+        &self,
+        block_number: BlockNumber,
+    ) -> StdResult<()> {
+        self.flush().await?;
+        self.state
+            .inner
+            .remove_rolled_back_transactions_and_block_range(block_number)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use anyhow::anyhow;
+
+    use crate::services::MockTransactionStore;
+
+    use super::*;
+
+    const NEVER: Duration = Duration::from_secs(3600);
+
+    fn transaction(block_number: BlockNumber) -> CardanoTransaction {
+        CardanoTransaction::new(
+            format!("tx-hash-{block_number}"),
+            block_number,
+            block_number * 10,
+            format!("block-hash-{block_number}"),
+            1,
+        )
+    }
+
+    /// Mock store counting the calls to `store_transactions` and the stored transactions
+    fn counting_store(calls: Arc<AtomicUsize>, stored: Arc<AtomicUsize>) -> MockTransactionStore {
+        let mut store = MockTransactionStore::new();
+        store
+            .expect_store_transactions()
+            .returning(move |transactions| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                stored.fetch_add(transactions.len(), Ordering::SeqCst);
+                Ok(())
+            });
+
+        store
+    }
+
+    #[tokio::test]
+    async fn storing_records_one_at_a_time_writes_them_in_batches() {
+        let batch_size = 50;
+        let calls = Arc::new(AtomicUsize::new(0));
+        let stored = Arc::new(AtomicUsize::new(0));
+        let store = BatchingTransactionStore::new(
+            Arc::new(counting_store(calls.clone(), stored.clone())),
+            batch_size,
+            NEVER,
+        );
+
+        for block_number in 0..500 {
+            store
+                .store_transactions(vec![transaction(block_number)])
+                .await
+                .unwrap();
+        }
+        store.flush().await.unwrap();
+
+        assert!(calls.load(Ordering::SeqCst) <= 500_usize.div_ceil(batch_size));
+        assert_eq!(500, stored.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn buffered_records_are_flushed_when_the_flush_interval_elapses() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let stored = Arc::new(AtomicUsize::new(0));
+        let store = BatchingTransactionStore::new(
+            Arc::new(counting_store(calls.clone(), stored.clone())),
+            100,
+            Duration::from_millis(10),
+        );
+
+        store
+            .store_transactions(vec![transaction(1), transaction(2)])
+            .await
+            .unwrap();
+        assert_eq!(0, stored.load(Ordering::SeqCst));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+        assert_eq!(2, stored.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn reads_flush_the_buffered_records_first() {
+        let stored = Arc::new(AtomicUsize::new(0));
+        let mut inner = counting_store(Arc::new(AtomicUsize::new(0)), stored.clone());
+        let stored_before_read = stored.clone();
+        inner
+            .expect_get_transactions_in_range()
+            .returning(move |_| {
+                assert_eq!(1, stored_before_read.load(Ordering::SeqCst));
+                Ok(vec![transaction(1)])
+            });
+        let store = BatchingTransactionStore::new(Arc::new(inner), 100, NEVER);
+
+        store
+            .store_transactions(vec![transaction(1)])
+            .await
+            .unwrap();
+        store.get_transactions_in_range(0..10).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_flush_keeps_the_records_for_the_next_flush() {
+        let mut inner = MockTransactionStore::new();
+        inner
+            .expect_store_transactions()
+            .returning(|_| Err(anyhow!("database is locked")))
+            .once();
+        inner
+            .expect_store_transactions()
+            .withf(|transactions| transactions.len() == 2)
+            .returning(|_| Ok(()))
+            .once();
+        let store = BatchingTransactionStore::new(Arc::new(inner), 100, NEVER);
+
+        store
+            .store_transactions(vec![transaction(1), transaction(2)])
+            .await
+            .unwrap();
+        store.flush().await.expect_err("first flush should fail");
+        store.flush().await.unwrap();
+    }
+}
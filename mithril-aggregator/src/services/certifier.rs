@@ -18,7 +18,7 @@ use mithril_common::{
     CardanoNetwork, StdResult, TickerService,
 };
 use slog::Logger;
-use slog_scope::{debug, error, info, trace, warn};
+use slog_scope::{debug, info, trace, warn};
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::RwLock;
@@ -96,6 +96,16 @@ pub trait CertifierService: Sync + Send {
         signature: &SingleSignatures,
     ) -> StdResult<()>;
 
+    /// Add several single signatures for the open message at the given beacon.
+    /// Either all the signatures are registered or none of them: if one of them
+    /// is invalid, or if the open message does not exist or has been certified
+    /// since then, an error is returned.
+    async fn register_single_signatures(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: &[SingleSignatures],
+    ) -> StdResult<()>;
+
     /// Create an open message at the given beacon. If the open message does not
     /// exist or exists at an older beacon, the older open messages are cleared
     /// along with their associated single signatures and the new open message
@@ -119,6 +129,14 @@ pub trait CertifierService: Sync + Send {
         signed_entity_type: &SignedEntityType,
     ) -> StdResult<Option<OpenMessage>>;
 
+    /// Remove the open message at the given beacon along with its single
+    /// signatures, ie: when a fork of the Cardano chain invalidated it. The
+    /// removed open message is returned, None if it did not exist.
+    async fn abort_open_message(
+        &self,
+        signed_entity_type: &SignedEntityType,
+    ) -> StdResult<Option<OpenMessage>>;
+
     /// Create a certificate if possible. If the pointed open message does
     /// not exist or has been already certified, an error is raised. If a multi
     /// signature is created then the flag `is_certified` of the open
@@ -204,6 +222,35 @@ impl MithrilCertifierService {
 
         Ok(open_message_with_single_signatures)
     }
+
+    /// Return the open message record at the given beacon if single signatures can still be
+    /// registered for it, fail otherwise.
+    async fn get_open_message_record_accepting_signatures(
+        &self,
+        signed_entity_type: &SignedEntityType,
+    ) -> StdResult<OpenMessageWithSingleSignaturesRecord> {
+        let open_message = self
+            .get_open_message_record(signed_entity_type)
+            .await.with_context(|| format!("CertifierService can not get open message record for signed_entity_type: '{signed_entity_type}'"))?
+            .ok_or_else(|| {
+                warn!("CertifierService::register_single_signature: OpenMessage not found for type {signed_entity_type:?}.");
+                CertifierServiceError::NotFound(signed_entity_type.clone())
+            })?;
+
+        if open_message.is_certified {
+            warn!("CertifierService::register_single_signature: open message {signed_entity_type:?} is already certified, cannot register single signature.");
+
+            return Err(CertifierServiceError::AlreadyCertified(signed_entity_type.clone()).into());
+        }
+
+        if open_message.is_expired {
+            warn!("CertifierService::register_single_signature: open message {signed_entity_type:?} has expired, cannot register single signature.");
+
+            return Err(CertifierServiceError::Expired(signed_entity_type.clone()).into());
+        }
+
+        Ok(open_message)
+    }
 }
 
 #[async_trait]
@@ -231,24 +278,8 @@ impl CertifierService for MithrilCertifierService {
         trace!("CertifierService::register_single_signature"; "complete_single_signatures" => #?signature);
 
         let open_message = self
-            .get_open_message_record(signed_entity_type)
-            .await.with_context(|| format!("CertifierService can not get open message record for signed_entity_type: '{signed_entity_type}'"))?
-            .ok_or_else(|| {
-                warn!("CertifierService::register_single_signature: OpenMessage not found for type {signed_entity_type:?}.");
-                CertifierServiceError::NotFound(signed_entity_type.clone())
-            })?;
-
-        if open_message.is_certified {
-            warn!("CertifierService::register_single_signature: open message {signed_entity_type:?} is already certified, cannot register single signature.");
-
-            return Err(CertifierServiceError::AlreadyCertified(signed_entity_type.clone()).into());
-        }
-
-        if open_message.is_expired {
-            warn!("CertifierService::register_single_signature: open message {signed_entity_type:?} has expired, cannot register single signature.");
-
-            return Err(CertifierServiceError::Expired(signed_entity_type.clone()).into());
-        }
+            .get_open_message_record_accepting_signatures(signed_entity_type)
+            .await?;
 
         let multi_signer = self.multi_signer.read().await;
         multi_signer
@@ -265,6 +296,34 @@ impl CertifierService for MithrilCertifierService {
         Ok(())
     }
 
+    async fn register_single_signatures(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: &[SingleSignatures],
+    ) -> StdResult<()> {
+        debug!("CertifierService::register_single_signatures(signed_entity_type: {signed_entity_type:?}, nb_single_signatures: {})", signatures.len());
+        trace!("CertifierService::register_single_signatures"; "complete_single_signatures" => #?signatures);
+
+        let open_message = self
+            .get_open_message_record_accepting_signatures(signed_entity_type)
+            .await?;
+
+        let multi_signer = self.multi_signer.read().await;
+        for signature in signatures {
+            multi_signer
+                .verify_single_signature(&open_message.protocol_message, signature)
+                .await?;
+        }
+
+        let single_signatures = self
+            .single_signature_repository
+            .create_single_signatures(signatures, &open_message.clone().into())
+            .await.with_context(|| format!("Certifier can not create the {} single signatures of open_message: '{open_message:?}'", signatures.len()))?;
+        info!("CertifierService::register_single_signatures: created {} single signatures for {signed_entity_type:?}.", single_signatures.len());
+
+        Ok(())
+    }
+
     async fn create_open_message(
         &self,
         signed_entity_type: &SignedEntityType,
@@ -333,6 +392,27 @@ impl CertifierService for MithrilCertifierService {
         Ok(open_message_record.map(|record| record.into()))
     }
 
+    async fn abort_open_message(
+        &self,
+        signed_entity_type: &SignedEntityType,
+    ) -> StdResult<Option<OpenMessage>> {
+        debug!("CertifierService::abort_open_message(signed_entity_type: {signed_entity_type:?})");
+
+        let open_message_record = self
+            .open_message_repository
+            .get_open_message(signed_entity_type)
+            .await
+            .with_context(|| format!("Certifier can not get open message for signed entity type: '{signed_entity_type}'"))?;
+        if let Some(open_message_record) = &open_message_record {
+            self.open_message_repository
+                .delete_open_message(open_message_record)
+                .await
+                .with_context(|| format!("Certifier can not remove open message for signed entity type: '{signed_entity_type}'"))?;
+        }
+
+        Ok(open_message_record.map(|record| record.into()))
+    }
+
     async fn create_certificate(
         &self,
         signed_entity_type: &SignedEntityType,
@@ -684,6 +764,57 @@ mod tests {
         assert!(open_message.is_none());
     }
 
+    #[tokio::test]
+    async fn should_abort_open_message_with_its_single_signatures() {
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 3, 1);
+        let signed_entity_type = SignedEntityType::CardanoImmutableFilesFull(beacon.clone());
+        let protocol_message = ProtocolMessage::new();
+        let epochs_with_signers = (1..=3).map(Epoch).collect::<Vec<_>>();
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let certifier_service =
+            setup_certifier_service(&fixture, &epochs_with_signers, Some(beacon.epoch)).await;
+        certifier_service
+            .create_open_message(&signed_entity_type, &protocol_message)
+            .await
+            .unwrap();
+        let signatures: Vec<_> = fixture
+            .signers_fixture()
+            .iter()
+            .filter_map(|signer_fixture| signer_fixture.sign(&protocol_message))
+            .collect();
+        certifier_service
+            .register_single_signatures(&signed_entity_type, &signatures)
+            .await
+            .unwrap();
+
+        let aborted_open_message = certifier_service
+            .abort_open_message(&signed_entity_type)
+            .await
+            .unwrap()
+            .expect("the aborted open message should be returned");
+
+        assert_eq!(signed_entity_type, aborted_open_message.signed_entity_type);
+        assert!(certifier_service
+            .get_open_message(&signed_entity_type)
+            .await
+            .unwrap()
+            .is_none());
+
+        certifier_service
+            .create_open_message(&signed_entity_type, &protocol_message)
+            .await
+            .unwrap();
+        let open_message = certifier_service
+            .get_open_message(&signed_entity_type)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            open_message.single_signatures.is_empty(),
+            "the single signatures of the aborted open message should have been removed"
+        );
+    }
+
     #[tokio::test]
     async fn should_register_valid_single_signature() {
         let beacon = CardanoDbBeacon::new("devnet".to_string(), 3, 1);
@@ -717,6 +848,73 @@ mod tests {
         assert!(!open_message.single_signatures.is_empty());
     }
 
+    #[tokio::test]
+    async fn should_register_valid_single_signatures_in_batch() {
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 3, 1);
+        let signed_entity_type = SignedEntityType::CardanoImmutableFilesFull(beacon.clone());
+        let protocol_message = ProtocolMessage::new();
+        let epochs_with_signers = (1..=5).map(Epoch).collect::<Vec<_>>();
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let certifier_service =
+            setup_certifier_service(&fixture, &epochs_with_signers, Some(beacon.epoch)).await;
+        certifier_service
+            .create_open_message(&signed_entity_type, &protocol_message)
+            .await
+            .unwrap();
+
+        let signatures = fixture
+            .signers_fixture()
+            .iter()
+            .filter_map(|signer_fixture| signer_fixture.sign(&protocol_message))
+            .collect::<Vec<_>>();
+        certifier_service
+            .register_single_signatures(&signed_entity_type, &signatures)
+            .await
+            .unwrap();
+        let open_message = certifier_service
+            .get_open_message(&signed_entity_type)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(signatures.len(), open_message.single_signatures.len());
+    }
+
+    #[tokio::test]
+    async fn should_not_register_any_single_signature_of_a_batch_with_an_invalid_one() {
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 3, 1);
+        let signed_entity_type = SignedEntityType::CardanoImmutableFilesFull(beacon.clone());
+        let protocol_message = ProtocolMessage::new();
+        let epochs_with_signers = (1..=5).map(Epoch).collect::<Vec<_>>();
+        let fixture = MithrilFixtureBuilder::default().with_signers(2).build();
+        let certifier_service =
+            setup_certifier_service(&fixture, &epochs_with_signers, Some(beacon.epoch)).await;
+        certifier_service
+            .create_open_message(&signed_entity_type, &protocol_message)
+            .await
+            .unwrap();
+
+        let mut other_protocol_message = ProtocolMessage::new();
+        other_protocol_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotDigest,
+            "snapshot-digest-123".to_string(),
+        );
+        let signers_fixture = fixture.signers_fixture();
+        let signatures = vec![
+            signers_fixture[0].sign(&protocol_message).unwrap(),
+            signers_fixture[1].sign(&other_protocol_message).unwrap(),
+        ];
+        certifier_service
+            .register_single_signatures(&signed_entity_type, &signatures)
+            .await
+            .expect_err("register_single_signatures should fail");
+        let open_message = certifier_service
+            .get_open_message(&signed_entity_type)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(open_message.single_signatures.is_empty());
+    }
+
     #[tokio::test]
     async fn should_not_register_invalid_single_signature() {
         let beacon = CardanoDbBeacon::new("devnet".to_string(), 3, 1);
@@ -294,6 +294,7 @@ impl EpochService for MithrilEpochService {
     }
 }
 
+/// Fake [EpochService] whose data is given at construction
 #[cfg(test)]
 pub struct FakeEpochService {
     epoch_data: Option<EpochData>,
@@ -346,6 +347,7 @@ impl FakeEpochService {
         }
     }
 
+    /// Create a fake epoch service using the protocol parameters and signers of the fixture
     pub fn from_fixture(
         epoch: Epoch,
         fixture: &mithril_common::test_utils::MithrilFixture,
@@ -372,6 +374,8 @@ impl FakeEpochService {
         }
     }
 
+    /// Make the `inform_epoch`, `update_protocol_parameters` and `precompute_epoch_data`
+    /// methods fail
     pub fn toggle_errors(
         &mut self,
         inform_epoch: bool,
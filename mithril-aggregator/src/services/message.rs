@@ -3,6 +3,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
 use mithril_common::{
@@ -15,7 +16,8 @@ use mithril_common::{
     StdResult,
 };
 
-use crate::database::repository::{CertificateRepository, SignedEntityStorer};
+use crate::database::repository::{CertificateRepository, CertificateStore, SignedEntityStorer};
+use crate::CertificateArchiveStore;
 
 #[cfg(test)]
 use mockall::automock;
@@ -51,6 +53,13 @@ pub trait MessageService: Sync + Send {
     /// passed as argument.
     async fn get_snapshot_list_message(&self, limit: usize) -> StdResult<SnapshotListMessage>;
 
+    /// Return the list of the last signed snapshots with its ETag: the hex encoded SHA-256 of
+    /// the serialized list. The limit of the list is passed as argument.
+    async fn get_snapshot_list_message_with_etag(
+        &self,
+        limit: usize,
+    ) -> StdResult<(SnapshotListMessage, String)>;
+
     /// Return the information regarding the MSD for the given identifier.
     async fn get_mithril_stake_distribution_message(
         &self,
@@ -80,6 +89,8 @@ pub trait MessageService: Sync + Send {
 pub struct MithrilMessageService {
     certificate_repository: Arc<CertificateRepository>,
     signed_entity_storer: Arc<dyn SignedEntityStorer>,
+    certificate_archive_store: Option<Arc<dyn CertificateArchiveStore>>,
+    certificate_cache: Option<Arc<dyn CertificateStore>>,
 }
 
 impl MithrilMessageService {
@@ -91,8 +102,26 @@ impl MithrilMessageService {
         Self {
             certificate_repository,
             signed_entity_storer,
+            certificate_archive_store: None,
+            certificate_cache: None,
         }
     }
+
+    /// Read the certificates through the given cache instead of reading them from the database
+    /// on every request.
+    pub fn with_certificate_cache(mut self, certificate_cache: Arc<dyn CertificateStore>) -> Self {
+        self.certificate_cache = Some(certificate_cache);
+        self
+    }
+
+    /// Look for the certificates missing from the database in the given archive store.
+    pub fn with_certificate_archive_store(
+        mut self,
+        certificate_archive_store: Arc<dyn CertificateArchiveStore>,
+    ) -> Self {
+        self.certificate_archive_store = Some(certificate_archive_store);
+        self
+    }
 }
 
 #[async_trait]
@@ -101,9 +130,28 @@ impl MessageService for MithrilMessageService {
         &self,
         certificate_hash: &str,
     ) -> StdResult<Option<CertificateMessage>> {
-        self.certificate_repository
-            .get_certificate(certificate_hash)
-            .await
+        let certificate = match &self.certificate_cache {
+            Some(certificate_cache) => certificate_cache
+                .get_certificate(certificate_hash)
+                .await?
+                .map(CertificateMessage::try_from)
+                .transpose()?,
+            None => {
+                self.certificate_repository
+                    .get_certificate(certificate_hash)
+                    .await?
+            }
+        };
+
+        match (&certificate, &self.certificate_archive_store) {
+            (None, Some(archive_store)) => self
+                .certificate_repository
+                .get_archived_certificate(certificate_hash, archive_store.as_ref())
+                .await?
+                .map(CertificateMessage::try_from)
+                .transpose(),
+            _ => Ok(certificate),
+        }
     }
 
     async fn get_certificate_list_message(
@@ -137,6 +185,16 @@ impl MessageService for MithrilMessageService {
         entities.into_iter().map(|i| i.try_into()).collect()
     }
 
+    async fn get_snapshot_list_message_with_etag(
+        &self,
+        limit: usize,
+    ) -> StdResult<(SnapshotListMessage, String)> {
+        let message = self.get_snapshot_list_message(limit).await?;
+        let etag = hex::encode(Sha256::digest(serde_json::to_vec(&message)?));
+
+        Ok((message, etag))
+    }
+
     async fn get_mithril_stake_distribution_message(
         &self,
         signed_entity_id: &str,
@@ -200,14 +258,18 @@ mod tests {
     use mithril_common::test_utils::MithrilFixtureBuilder;
 
     use crate::database::record::SignedEntityRecord;
-    use crate::database::repository::MockSignedEntityStorer;
+    use crate::database::repository::{
+        LruCertificateCache, MockCertificateStore, MockSignedEntityStorer,
+    };
     use crate::dependency_injection::DependenciesBuilder;
     use crate::message_adapters::{
         ToCardanoTransactionListMessageAdapter, ToCardanoTransactionMessageAdapter,
         ToMithrilStakeDistributionListMessageAdapter, ToMithrilStakeDistributionMessageAdapter,
         ToSnapshotListMessageAdapter, ToSnapshotMessageAdapter,
     };
-    use crate::Configuration;
+    use crate::{Configuration, FileCertificateArchiveStore};
+
+    use super::{MessageService, MithrilMessageService};
 
     #[tokio::test]
     async fn get_no_certificate() {
@@ -248,6 +310,63 @@ mod tests {
         assert_eq!(genesis_certificate.hash, certificate_message.hash);
     }
 
+    #[tokio::test]
+    async fn get_certificate_falls_through_to_the_archive_store() {
+        let configuration = Configuration::new_sample();
+        let mut dep_builder = DependenciesBuilder::new(configuration);
+        let repository = dep_builder.get_certificate_repository().await.unwrap();
+        let signed_entity_storer = dep_builder.get_signed_entity_storer().await.unwrap();
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let genesis_certificate = fixture.create_genesis_certificate("whatever", Epoch(2), 1);
+        repository
+            .create_certificate(genesis_certificate.clone())
+            .await
+            .unwrap();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_store = Arc::new(FileCertificateArchiveStore::new(archive_dir.path()).unwrap());
+        repository
+            .archive_before(Epoch(3), archive_store.clone())
+            .await
+            .unwrap();
+        let service = MithrilMessageService::new(repository, signed_entity_storer)
+            .with_certificate_archive_store(archive_store);
+
+        let certificate_message = service
+            .get_certificate_message(&genesis_certificate.hash)
+            .await
+            .unwrap()
+            .expect("The archived certificate should be found.");
+        assert_eq!(genesis_certificate.hash, certificate_message.hash);
+    }
+
+    #[tokio::test]
+    async fn get_certificate_twice_with_a_cache_reads_the_store_once() {
+        let configuration = Configuration::new_sample();
+        let mut dep_builder = DependenciesBuilder::new(configuration);
+        let repository = dep_builder.get_certificate_repository().await.unwrap();
+        let signed_entity_storer = dep_builder.get_signed_entity_storer().await.unwrap();
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let genesis_certificate = fixture.create_genesis_certificate("whatever", Epoch(2), 1);
+        let mut store = MockCertificateStore::new();
+        let stored_certificate = genesis_certificate.clone();
+        store
+            .expect_get_certificate()
+            .return_once(move |_| Ok(Some(stored_certificate)))
+            .once();
+        let certificate_cache = LruCertificateCache::new(Arc::new(store)).unwrap();
+        let service = MithrilMessageService::new(repository, signed_entity_storer)
+            .with_certificate_cache(Arc::new(certificate_cache));
+
+        for _ in 0..2 {
+            let certificate_message = service
+                .get_certificate_message(&genesis_certificate.hash)
+                .await
+                .unwrap()
+                .expect("There should be a certificate.");
+            assert_eq!(genesis_certificate.hash, certificate_message.hash);
+        }
+    }
+
     #[tokio::test]
     async fn get_last_certificates() {
         let configuration = Configuration::new_sample();
@@ -342,6 +461,48 @@ mod tests {
         assert_eq!(message, response);
     }
 
+    #[tokio::test]
+    async fn get_snapshot_list_message_with_etag_changes_with_the_list() {
+        let configuration = Configuration::new_sample();
+        let mut dep_builder = DependenciesBuilder::new(configuration);
+        let mut storer = MockSignedEntityStorer::new();
+        storer
+            .expect_get_last_signed_entities_by_type()
+            .returning(|_, limit| {
+                Ok((0..limit)
+                    .map(|_| {
+                        let entity = SignedEntity::<Snapshot>::dummy();
+                        SignedEntityRecord {
+                            signed_entity_id: entity.signed_entity_id.clone(),
+                            signed_entity_type: entity.signed_entity_type.clone(),
+                            certificate_id: entity.certificate_id.clone(),
+                            artifact: serde_json::to_string(&entity.artifact).unwrap(),
+                            created_at: entity.created_at,
+                        }
+                    })
+                    .collect())
+            });
+        dep_builder.signed_entity_storer = Some(Arc::new(storer));
+        let service = dep_builder.get_message_service().await.unwrap();
+
+        let (message, etag) = service
+            .get_snapshot_list_message_with_etag(2)
+            .await
+            .unwrap();
+        let (_, same_list_etag) = service
+            .get_snapshot_list_message_with_etag(2)
+            .await
+            .unwrap();
+        let (_, other_list_etag) = service
+            .get_snapshot_list_message_with_etag(1)
+            .await
+            .unwrap();
+
+        assert_eq!(2, message.len());
+        assert_eq!(etag, same_list_etag);
+        assert_ne!(etag, other_list_etag);
+    }
+
     #[tokio::test]
     async fn get_mithril_stake_distribution() {
         let entity = SignedEntity::<MithrilStakeDistribution>::dummy();
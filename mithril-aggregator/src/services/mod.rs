@@ -9,6 +9,7 @@
 //!
 //! Each service is defined by a public API (a trait) that is used in the controllers (runtimes).
 
+mod cardano_transactions_batching_store;
 mod cardano_transactions_importer;
 mod certifier;
 mod epoch_service;
@@ -17,6 +18,7 @@ mod prover;
 mod signed_entity;
 mod stake_distribution;
 
+pub use cardano_transactions_batching_store::*;
 pub use cardano_transactions_importer::*;
 pub use certifier::*;
 pub use epoch_service::*;
@@ -8,9 +8,10 @@ use std::{
 };
 
 use mithril_common::{
-    crypto_helper::{MKMap, MKMapNode, MKTree},
+    crypto_helper::{MKMap, MKMapNode, MKMapValue, MKTree, MKTreeNode},
     entities::{
-        BlockNumber, BlockRange, CardanoTransaction, CardanoTransactionsSetProof, TransactionHash,
+        BlockNumber, BlockRange, CardanoTransaction, CardanoTransactionLeafFormat,
+        CardanoTransactionsSetProof, TransactionHash,
     },
     resource_pool::ResourcePool,
     signable_builder::BlockRangeRootRetriever,
@@ -55,6 +56,7 @@ pub struct MithrilProverService {
     transaction_retriever: Arc<dyn TransactionsRetriever>,
     block_range_root_retriever: Arc<dyn BlockRangeRootRetriever>,
     mk_map_pool: ResourcePool<MKMap<BlockRange, MKMapNode<BlockRange>>>,
+    leaf_format: CardanoTransactionLeafFormat,
     logger: Logger,
 }
 
@@ -70,25 +72,63 @@ impl MithrilProverService {
             transaction_retriever,
             block_range_root_retriever,
             mk_map_pool: ResourcePool::new(mk_map_pool_size, vec![]),
+            leaf_format: CardanoTransactionLeafFormat::default(),
             logger,
         }
     }
 
-    async fn get_block_ranges(
-        &self,
-        transaction_hashes: &[TransactionHash],
-        up_to: BlockNumber,
-    ) -> StdResult<Vec<BlockRange>> {
-        let transactions = self
-            .transaction_retriever
-            .get_by_hashes(transaction_hashes.to_vec(), up_to)
-            .await?;
+    /// Set the format of the Merkle tree leaves of the new block ranges, without it the leaves
+    /// are computed from the transactions hashes only.
+    pub fn with_leaf_format(mut self, leaf_format: CardanoTransactionLeafFormat) -> Self {
+        self.leaf_format = leaf_format;
+        self
+    }
+
+    fn compute_block_range_tree(
+        transactions: &[CardanoTransaction],
+        leaf_format: CardanoTransactionLeafFormat,
+    ) -> StdResult<MKTree> {
+        let leaves: Vec<MKTreeNode> = transactions
+            .iter()
+            .map(|transaction| transaction.leaf(leaf_format))
+            .collect();
+
+        MKTree::new(&leaves)
+    }
+
+    /// Compute the Merkle tree of a block range with the leaf format its root was certified with.
+    ///
+    /// The roots of the block ranges stored before the switch to the configured format were
+    /// computed with the legacy leaves, they are recomputed with this format when the root differs.
+    fn compute_certified_block_range_tree(
+        mk_map: &MKMap<BlockRange, MKMapNode<BlockRange>>,
+        block_range: &BlockRange,
+        transactions: &[CardanoTransaction],
+        leaf_format: CardanoTransactionLeafFormat,
+    ) -> StdResult<(CardanoTransactionLeafFormat, MKTree)> {
+        let mk_tree = Self::compute_block_range_tree(transactions, leaf_format)?;
+        match mk_map.get(block_range) {
+            Some(certified_node)
+                if leaf_format != CardanoTransactionLeafFormat::TransactionHash
+                    && certified_node.compute_root()? != mk_tree.compute_root()? =>
+            {
+                let legacy_format = CardanoTransactionLeafFormat::TransactionHash;
+                Ok((
+                    legacy_format,
+                    Self::compute_block_range_tree(transactions, legacy_format)?,
+                ))
+            }
+            _ => Ok((leaf_format, mk_tree)),
+        }
+    }
+
+    fn get_block_ranges(transactions: &[CardanoTransaction]) -> Vec<BlockRange> {
         let block_ranges = transactions
             .iter()
             .map(|t| BlockRange::from_block_number(t.block_number))
             .collect::<BTreeSet<_>>();
 
-        Ok(block_ranges.into_iter().collect::<Vec<_>>())
+        block_ranges.into_iter().collect::<Vec<_>>()
     }
 
     /// Get all the transactions of the block ranges
@@ -120,42 +160,85 @@ impl ProverService for MithrilProverService {
         transaction_hashes: &[TransactionHash],
     ) -> StdResult<Vec<CardanoTransactionsSetProof>> {
         // 1 - Compute the set of block ranges with transactions to prove
-        let block_ranges_transactions = self.get_block_ranges(transaction_hashes, up_to).await?;
+        let transactions_to_prove = self
+            .transaction_retriever
+            .get_by_hashes(transaction_hashes.to_vec(), up_to)
+            .await?;
+        let block_ranges_transactions = Self::get_block_ranges(&transactions_to_prove);
         let block_range_transactions = self
             .get_all_transactions_for_block_ranges(&block_ranges_transactions)
             .await?;
 
-        // 2 - Compute block ranges sub Merkle trees
-        let mk_trees: StdResult<Vec<(BlockRange, MKTree)>> = block_range_transactions
-            .into_iter()
-            .map(|(block_range, transactions)| {
-                let mk_tree = MKTree::new(&transactions)?;
-                Ok((block_range, mk_tree))
-            })
-            .collect();
-        let mk_trees = BTreeMap::from_iter(mk_trees?);
-
-        // 3 - Compute block range roots Merkle map
+        // 2 - Acquire a block range roots Merkle map
         let acquire_timeout = Duration::from_millis(1000);
         let mut mk_map = self.mk_map_pool.acquire_resource(acquire_timeout)?;
 
-        // 4 - Enrich the Merkle map with the block ranges Merkle trees
-        for (block_range, mk_tree) in mk_trees {
-            mk_map.insert(block_range, mk_tree.into())?;
+        // 3 - Compute block ranges sub Merkle trees and enrich the Merkle map with them
+        let leaf_format = self.leaf_format;
+        let mut block_ranges_leaf_format = HashMap::new();
+        for (block_range, transactions) in BTreeMap::from_iter(block_range_transactions) {
+            let (block_range_leaf_format, mk_tree) = Self::compute_certified_block_range_tree(
+                &mk_map,
+                &block_range,
+                &transactions,
+                leaf_format,
+            )?;
+            mk_map.insert(block_range.clone(), mk_tree.into())?;
+            block_ranges_leaf_format.insert(block_range, block_range_leaf_format);
         }
-
-        // 5 - Compute the proof for all transactions
-        if let Ok(mk_proof) = mk_map.compute_proof(transaction_hashes) {
+        let transaction_leaf_format = |transaction: &CardanoTransaction| {
+            block_ranges_leaf_format
+                .get(&BlockRange::from_block_number(transaction.block_number))
+                .copied()
+                .unwrap_or(leaf_format)
+        };
+
+        // 4 - Compute the proof for all transactions
+        let leaves_to_prove: Vec<MKTreeNode> = transactions_to_prove
+            .iter()
+            .map(|transaction| transaction.leaf(transaction_leaf_format(transaction)))
+            .collect();
+        if let Ok(mk_proof) = mk_map.compute_proof(&leaves_to_prove) {
             self.mk_map_pool.give_back_resource_pool_item(mk_map)?;
             let mk_proof_leaves = mk_proof.leaves();
-            let transaction_hashes_certified: Vec<TransactionHash> = transaction_hashes
+            let transactions_certified: Vec<(&CardanoTransaction, CardanoTransactionLeafFormat)> =
+                transaction_hashes
+                    .iter()
+                    .filter_map(|hash| {
+                        transactions_to_prove
+                            .iter()
+                            .find(|transaction| &transaction.transaction_hash == hash)
+                    })
+                    .map(|transaction| (transaction, transaction_leaf_format(transaction)))
+                    .filter(|(transaction, format)| {
+                        mk_proof_leaves.contains(&transaction.leaf(*format))
+                    })
+                    .collect();
+            let transaction_hashes_certified = transactions_certified
                 .iter()
-                .filter(|hash| mk_proof_leaves.contains(&hash.as_str().into()))
-                .cloned()
+                .map(|(transaction, _)| transaction.transaction_hash.clone())
                 .collect();
+            // An empty block hash marks a legacy leaf, computed from the transaction hash only
+            let block_hashes_certified = if transactions_certified
+                .iter()
+                .all(|(_, format)| *format == CardanoTransactionLeafFormat::TransactionHash)
+            {
+                vec![]
+            } else {
+                transactions_certified
+                    .iter()
+                    .map(|(transaction, format)| match format {
+                        CardanoTransactionLeafFormat::TransactionHash => String::new(),
+                        CardanoTransactionLeafFormat::TransactionAndBlockHash => {
+                            transaction.block_hash.clone()
+                        }
+                    })
+                    .collect()
+            };
 
             Ok(vec![CardanoTransactionsSetProof::new(
                 transaction_hashes_certified,
+                block_hashes_certified,
                 mk_proof,
             )])
         } else {
@@ -411,6 +494,84 @@ mod tests {
         transactions_set_proof[0].verify().unwrap();
     }
 
+    #[tokio::test]
+    async fn compute_proof_for_transactions_certified_before_and_after_the_leaf_format_switch() {
+        let transactions = CardanoTransactionsBuilder::new()
+            .max_transactions_per_block(1)
+            .blocks_per_block_range(3)
+            .build_block_ranges(5);
+        let transactions_to_prove =
+            test_data::filter_transactions_for_indices(&[1, 2, 10], &transactions);
+        let test_data = test_data::build_test_data(&transactions_to_prove, &transactions);
+        let legacy_block_range = BlockRange::from_block_number(transactions[1].block_number);
+        let prover = build_prover(
+            |transaction_retriever_mock| {
+                let transaction_hashes_to_prove = test_data.transaction_hashes_to_prove.clone();
+                let transactions_to_prove = transactions_to_prove.clone();
+                transaction_retriever_mock
+                    .expect_get_by_hashes()
+                    .with(eq(transaction_hashes_to_prove), eq(test_data.beacon))
+                    .return_once(move |_, _| Ok(transactions_to_prove));
+
+                let block_ranges_to_prove = test_data.block_ranges_to_prove.clone();
+                let all_transactions_in_block_ranges_to_prove =
+                    test_data.all_transactions_in_block_ranges_to_prove.clone();
+                transaction_retriever_mock
+                    .expect_get_by_block_ranges()
+                    .with(eq(block_ranges_to_prove))
+                    .return_once(move |_| Ok(all_transactions_in_block_ranges_to_prove));
+            },
+            |block_range_root_retriever_mock| {
+                let block_ranges_map = test_data.block_ranges_map.clone();
+                let legacy_block_range = legacy_block_range.clone();
+                block_range_root_retriever_mock
+                    .expect_compute_merkle_map_from_block_range_roots()
+                    .return_once(move |_| {
+                        MKMap::new_from_iter(block_ranges_map.into_iter().map(
+                            |(block_range, transactions)| {
+                                let leaf_format = if block_range == legacy_block_range {
+                                    CardanoTransactionLeafFormat::TransactionHash
+                                } else {
+                                    CardanoTransactionLeafFormat::TransactionAndBlockHash
+                                };
+                                let mk_tree = MithrilProverService::compute_block_range_tree(
+                                    &transactions,
+                                    leaf_format,
+                                )
+                                .unwrap();
+                                (
+                                    block_range,
+                                    MKMapNode::TreeNode(mk_tree.compute_root().unwrap()),
+                                )
+                            },
+                        ))
+                    });
+            },
+        )
+        .with_leaf_format(CardanoTransactionLeafFormat::TransactionAndBlockHash);
+        prover.compute_cache(test_data.beacon).await.unwrap();
+
+        let transactions_set_proof = prover
+            .compute_transactions_proofs(test_data.beacon, &test_data.transaction_hashes_to_prove)
+            .await
+            .unwrap();
+
+        assert_eq!(transactions_set_proof.len(), 1);
+        assert_eq!(
+            transactions_set_proof[0].transactions_hashes(),
+            test_data.transaction_hashes_to_prove
+        );
+        assert_eq!(
+            transactions_set_proof[0].block_hashes(),
+            vec![
+                String::new(),
+                String::new(),
+                transactions_to_prove[2].block_hash.clone()
+            ]
+        );
+        transactions_set_proof[0].verify().unwrap();
+    }
+
     #[tokio::test]
     async fn cant_compute_proof_for_not_yet_certified_transaction() {
         let transactions = CardanoTransactionsBuilder::new()
@@ -11,8 +11,8 @@ use std::sync::Arc;
 use mithril_common::{
     entities::{
         BlockNumber, CardanoDbBeacon, CardanoTransactionsSnapshot, Certificate, Epoch,
-        MithrilStakeDistribution, SignedEntity, SignedEntityType, SignedEntityTypeDiscriminants,
-        Snapshot,
+        ImmutableFileNumber, MithrilStakeDistribution, SignedEntity, SignedEntityType,
+        SignedEntityTypeDiscriminants, Snapshot,
     },
     signable_builder::Artifact,
     StdResult,
@@ -21,6 +21,7 @@ use mithril_common::{
 use crate::{
     artifact_builder::ArtifactBuilder,
     database::{record::SignedEntityRecord, repository::SignedEntityStorer},
+    entities::{ManifestEntry, SnapshotManifest},
 };
 
 #[cfg(test)]
@@ -66,6 +67,14 @@ pub trait SignedEntityService: Send + Sync {
         &self,
         signed_entity_id: &str,
     ) -> StdResult<Option<SignedEntity<MithrilStakeDistribution>>>;
+
+    /// Return the manifest of the signed snapshots which immutable file number is in the
+    /// given inclusive range.
+    async fn generate_snapshot_manifest(
+        &self,
+        from: ImmutableFileNumber,
+        to: ImmutableFileNumber,
+    ) -> StdResult<SnapshotManifest>;
 }
 
 /// Mithril ArtifactBuilder Service
@@ -295,6 +304,34 @@ impl SignedEntityService for MithrilSignedEntityService {
 
         Ok(entity)
     }
+
+    async fn generate_snapshot_manifest(
+        &self,
+        from: ImmutableFileNumber,
+        to: ImmutableFileNumber,
+    ) -> StdResult<SnapshotManifest> {
+        let signed_entities_records = self
+            .signed_entity_storer
+            .get_snapshots_by_immutable_file_number_range(from, to)
+            .await
+            .with_context(|| {
+                format!("Signed Entity Service can not get the snapshots of range {from}..={to}")
+            })?;
+        let mut entries = Vec::new();
+
+        for record in signed_entities_records {
+            let snapshot: SignedEntity<Snapshot> = record.try_into()?;
+            let snapshot = snapshot.artifact;
+            entries.push(ManifestEntry {
+                immutable_file_number: snapshot.beacon.immutable_file_number,
+                size_bytes: snapshot.size,
+                url: snapshot.locations.first().cloned().unwrap_or_default(),
+                digest: snapshot.digest,
+            });
+        }
+
+        Ok(SnapshotManifest::new(entries))
+    }
 }
 
 #[cfg(test)]
@@ -405,6 +442,49 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn generate_snapshot_manifest_list_snapshots_in_range_by_ascending_immutable_file_number()
+    {
+        let snapshots: Vec<Snapshot> = [(30, 300), (10, 100), (20, 200)]
+            .into_iter()
+            .map(|(immutable_file_number, size)| Snapshot {
+                digest: format!("digest-{immutable_file_number}"),
+                beacon: CardanoDbBeacon::new("devnet", 1, immutable_file_number),
+                size,
+                ..fake_data::snapshots(1)[0].clone()
+            })
+            .collect();
+        let records: Vec<SignedEntityRecord> = snapshots
+            .into_iter()
+            .map(|snapshot| {
+                SignedEntityRecord::from_snapshot(snapshot, "certificate".to_string(), Utc::now())
+            })
+            .collect();
+        let mut mock_container = MockDependencyInjector::new();
+        mock_container
+            .mock_signed_entity_storer
+            .expect_get_snapshots_by_immutable_file_number_range()
+            .with(mockall::predicate::eq(10), mockall::predicate::eq(30))
+            .return_once(|_, _| Ok(records))
+            .once();
+        let service = mock_container.build_artifact_builder_service();
+
+        let manifest = service.generate_snapshot_manifest(10, 30).await.unwrap();
+
+        assert_eq!(
+            vec![
+                ("digest-10".to_string(), 10, 100),
+                ("digest-20".to_string(), 20, 200),
+                ("digest-30".to_string(), 30, 300),
+            ],
+            manifest
+                .entries
+                .into_iter()
+                .map(|e| (e.digest, e.immutable_file_number, e.size_bytes))
+                .collect::<Vec<_>>()
+        );
+    }
+
     #[tokio::test]
     async fn build_snapshot_artifact_when_given_cardano_immutable_files_full_entity_type() {
         let mut mock_container = MockDependencyInjector::new();
@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use std::collections::HashSet;
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::RwLock;
@@ -41,6 +42,10 @@ pub enum SignerRegistrationError {
     #[error("signer already registered")]
     ExistingSigner(Box<SignerWithStake>),
 
+    /// A registration with the same nonce was already processed during the current round.
+    #[error("a signer registration with nonce '{0}' was already processed for this round")]
+    DuplicateNonce(String),
+
     /// Store error.
     #[error("store error")]
     StoreError(#[source] StdError),
@@ -65,6 +70,7 @@ pub struct SignerRegistrationRound {
 
 #[cfg(test)]
 impl SignerRegistrationRound {
+    /// Create a registration round for the given epoch and stake distribution
     pub fn dummy(epoch: Epoch, stake_distribution: StakeDistribution) -> Self {
         Self {
             epoch,
@@ -128,6 +134,9 @@ pub struct MithrilSignerRegisterer {
     /// Number of epochs before previous records will be deleted at the next registration round
     /// opening
     verification_key_epoch_retention_limit: Option<u64>,
+
+    /// Nonces of the registrations processed during the current round
+    registration_nonces: RwLock<HashSet<String>>,
 }
 
 impl MithrilSignerRegisterer {
@@ -144,9 +153,11 @@ impl MithrilSignerRegisterer {
             verification_key_store,
             signer_recorder,
             verification_key_epoch_retention_limit,
+            registration_nonces: RwLock::new(HashSet::new()),
         }
     }
 
+    /// Get the current registration round, if any
     #[cfg(test)]
     pub async fn get_current_round(&self) -> Option<SignerRegistrationRound> {
         self.current_round.read().await.as_ref().cloned()
@@ -165,6 +176,7 @@ impl SignerRegistrationRoundOpener for MithrilSignerRegisterer {
             epoch: registration_epoch,
             stake_distribution,
         });
+        self.registration_nonces.write().await.clear();
 
         if let Some(retention_limit) = self.verification_key_epoch_retention_limit {
             self.verification_key_store
@@ -208,6 +220,38 @@ impl SignerRegisterer for MithrilSignerRegisterer {
             });
         }
 
+        // The nonce is reserved before the registration so two deliveries of the same request
+        // can't be processed concurrently, it's released if the registration fails
+        if let Some(nonce) = &signer.registration_nonce {
+            if !self.registration_nonces.write().await.insert(nonce.clone()) {
+                return Err(SignerRegistrationError::DuplicateNonce(nonce.clone()));
+            }
+        }
+
+        let result = self
+            .register_signer_in_round(registration_round, signer)
+            .await;
+        if let (Err(error), Some(nonce)) = (&result, &signer.registration_nonce) {
+            // An existing signer is still registered with the new keys
+            if !matches!(error, SignerRegistrationError::ExistingSigner(_)) {
+                self.registration_nonces.write().await.remove(nonce);
+            }
+        }
+
+        result
+    }
+
+    async fn get_current_round(&self) -> Option<SignerRegistrationRound> {
+        self.current_round.read().await.as_ref().cloned()
+    }
+}
+
+impl MithrilSignerRegisterer {
+    async fn register_signer_in_round(
+        &self,
+        registration_round: &SignerRegistrationRound,
+        signer: &Signer,
+    ) -> Result<SignerWithStake, SignerRegistrationError> {
         let mut key_registration = ProtocolKeyRegistration::init(
             &registration_round
                 .stake_distribution
@@ -277,10 +321,6 @@ impl SignerRegisterer for MithrilSignerRegisterer {
             None => Ok(signer_save),
         }
     }
-
-    async fn get_current_round(&self) -> Option<SignerRegistrationRound> {
-        self.current_round.read().await.as_ref().cloned()
-    }
 }
 
 #[cfg(test)]
@@ -295,8 +335,8 @@ mod tests {
     use mithril_persistence::store::adapter::MemoryAdapter;
 
     use crate::{
-        MithrilSignerRegisterer, SignerRegisterer, SignerRegistrationRoundOpener,
-        VerificationKeyStore, VerificationKeyStorer,
+        MithrilSignerRegisterer, SignerRegisterer, SignerRegistrationError,
+        SignerRegistrationRoundOpener, VerificationKeyStore, VerificationKeyStorer,
     };
 
     use super::MockSignerRecorder;
@@ -346,6 +386,47 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn registration_with_an_already_processed_nonce_is_rejected() {
+        let verification_key_store = Arc::new(VerificationKeyStore::new(Box::new(
+            MemoryAdapter::<Epoch, HashMap<PartyId, SignerWithStake>>::new(None).unwrap(),
+        )));
+        let mut signer_recorder = MockSignerRecorder::new();
+        signer_recorder
+            .expect_record_signer_registration()
+            .returning(|_| Ok(()))
+            .once();
+        let signer_registerer = MithrilSignerRegisterer::new(
+            Arc::new(FakeObserver::default()),
+            verification_key_store.clone(),
+            Arc::new(signer_recorder),
+            None,
+        );
+        let registration_epoch = Epoch(1);
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let signer_to_register = fixture.signers()[0]
+            .to_owned()
+            .with_registration_nonce("nonce-1".to_string());
+        signer_registerer
+            .open_registration_round(registration_epoch, fixture.stake_distribution())
+            .await
+            .unwrap();
+
+        signer_registerer
+            .register_signer(registration_epoch, &signer_to_register)
+            .await
+            .expect("first signer registration should not fail");
+        let error = signer_registerer
+            .register_signer(registration_epoch, &signer_to_register)
+            .await
+            .expect_err("second signer registration with the same nonce should fail");
+
+        assert!(
+            matches!(error, SignerRegistrationError::DuplicateNonce(ref nonce) if nonce == "nonce-1"),
+            "unexpected error: {error:?}"
+        );
+    }
+
     #[tokio::test]
     async fn can_register_signer_if_registration_round_is_opened_without_operational_certificate() {
         let verification_key_store = Arc::new(VerificationKeyStore::new(Box::new(
@@ -0,0 +1,259 @@
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use reqwest::{multipart, Body, Client, Response};
+use serde::Deserialize;
+use slog_scope::debug;
+use std::path::Path;
+use tokio_util::io::ReaderStream;
+
+use mithril_common::StdResult;
+
+use crate::snapshot_uploaders::{SnapshotLocation, SnapshotUploader};
+use crate::PinningServiceConfig;
+
+/// Scheme of the snapshot locations of the archives stored on IPFS
+pub const IPFS_LOCATION_SCHEME: &str = "ipfs://";
+
+#[derive(Debug, Deserialize)]
+struct IpfsAddResponse {
+    #[serde(rename = "Hash")]
+    hash: String,
+}
+
+/// IpfsSnapshotUploader is a snapshot uploader that adds and pins the archives on an IPFS node
+pub struct IpfsSnapshotUploader {
+    /// Url of the RPC API of the IPFS node
+    api_url: String,
+
+    /// Remote pinning service that must also pin the archives
+    pinning_service: Option<PinningServiceConfig>,
+
+    http_client: Client,
+}
+
+impl IpfsSnapshotUploader {
+    /// IpfsSnapshotUploader factory
+    pub fn new(api_url: String, pinning_service: Option<PinningServiceConfig>) -> Self {
+        debug!("New IpfsSnapshotUploader created"; "api_url" => &api_url);
+        Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            pinning_service,
+            http_client: Client::new(),
+        }
+    }
+
+    fn rpc_url(&self, command: &str) -> String {
+        format!("{}/api/v0/{command}", self.api_url)
+    }
+
+    async fn check_response(response: Response, command: &str) -> StdResult<Response> {
+        let status = response.status();
+        if status.is_success() {
+            Ok(response)
+        } else {
+            let body = response.text().await.unwrap_or_default();
+            Err(anyhow!(
+                "IPFS '{command}' command failed with status '{status}': {body}"
+            ))
+        }
+    }
+
+    async fn add(&self, snapshot_filepath: &Path) -> StdResult<String> {
+        let file_name = snapshot_filepath
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("Invalid snapshot file path: '{snapshot_filepath:?}'"))?
+            .to_string();
+        let file = tokio::fs::File::open(snapshot_filepath)
+            .await
+            .with_context(|| format!("Can not open snapshot file '{snapshot_filepath:?}'"))?;
+        let file_size = file.metadata().await?.len();
+        let part = multipart::Part::stream_with_length(
+            Body::wrap_stream(ReaderStream::new(file)),
+            file_size,
+        )
+        .file_name(file_name);
+
+        let response = self
+            .http_client
+            .post(self.rpc_url("add"))
+            .query(&[("pin", "false"), ("cid-version", "1")])
+            .multipart(multipart::Form::new().part("file", part))
+            .send()
+            .await
+            .with_context(|| "IPFS 'add' request failed")?;
+        let added: IpfsAddResponse = Self::check_response(response, "add")
+            .await?
+            .json()
+            .await
+            .with_context(|| "Invalid IPFS 'add' response")?;
+
+        Ok(added.hash)
+    }
+
+    async fn pin(&self, cid: &str) -> StdResult<()> {
+        let response = self
+            .http_client
+            .post(self.rpc_url("pin/add"))
+            .query(&[("arg", cid)])
+            .send()
+            .await
+            .with_context(|| "IPFS 'pin/add' request failed")?;
+        Self::check_response(response, "pin/add").await?;
+
+        if let Some(pinning_service) = &self.pinning_service {
+            let response = self
+                .http_client
+                .post(self.rpc_url("pin/remote/add"))
+                .query(&[("arg", cid), ("service", &pinning_service.service_name)])
+                .send()
+                .await
+                .with_context(|| "IPFS 'pin/remote/add' request failed")?;
+            Self::check_response(response, "pin/remote/add").await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SnapshotUploader for IpfsSnapshotUploader {
+    async fn upload_snapshot(&self, snapshot_filepath: &Path) -> StdResult<SnapshotLocation> {
+        let cid = self
+            .add(snapshot_filepath)
+            .await
+            .with_context(|| format!("Can not add snapshot '{snapshot_filepath:?}' to IPFS"))?;
+        self.pin(&cid)
+            .await
+            .with_context(|| format!("Can not pin snapshot with CID '{cid}' on IPFS"))?;
+
+        Ok(format!("{IPFS_LOCATION_SCHEME}{cid}"))
+    }
+}
+
+/// Translate an `ipfs://` snapshot location to its url on the given IPFS gateway.
+///
+/// Returns `None` if the location is not stored on IPFS.
+pub fn ipfs_location_to_gateway_url(location: &str, gateway_url: &str) -> Option<String> {
+    location
+        .strip_prefix(IPFS_LOCATION_SCHEME)
+        .map(|cid| format!("{}/ipfs/{cid}", gateway_url.trim_end_matches('/')))
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::prelude::*;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    const CID: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+    fn create_fake_archive(dir: &Path) -> std::path::PathBuf {
+        let file_path = dir
+            .join("test.41e27b9ed5a32531b95b2b7ff3c0757591a06a337efaf19a524a998e348028e7.tar.gz");
+        let mut file = std::fs::File::create(&file_path).unwrap();
+        writeln!(
+            file,
+            "I swear, this is an archive, not a temporary test file."
+        )
+        .unwrap();
+
+        file_path
+    }
+
+    #[tokio::test]
+    async fn upload_snapshot_adds_and_pins_the_archive() {
+        let server = MockServer::start();
+        let add_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v0/add")
+                .query_param("pin", "false");
+            then.status(200)
+                .json_body(json!({ "Name": "archive", "Hash": CID, "Size": "64" }));
+        });
+        let pin_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v0/pin/add")
+                .query_param("arg", CID);
+            then.status(200).json_body(json!({ "Pins": [CID] }));
+        });
+        let source_dir = tempdir().unwrap();
+        let archive = create_fake_archive(source_dir.path());
+        let uploader = IpfsSnapshotUploader::new(server.url(""), None);
+
+        let location = uploader.upload_snapshot(&archive).await.unwrap();
+
+        add_mock.assert();
+        pin_mock.assert();
+        assert_eq!(format!("ipfs://{CID}"), location);
+    }
+
+    #[tokio::test]
+    async fn upload_snapshot_pins_the_archive_on_the_pinning_service() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/api/v0/add");
+            then.status(200).json_body(json!({ "Hash": CID }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/api/v0/pin/add");
+            then.status(200).json_body(json!({ "Pins": [CID] }));
+        });
+        let remote_pin_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v0/pin/remote/add")
+                .query_param("arg", CID)
+                .query_param("service", "pinata");
+            then.status(200)
+                .json_body(json!({ "Cid": CID, "Status": "pinned" }));
+        });
+        let source_dir = tempdir().unwrap();
+        let archive = create_fake_archive(source_dir.path());
+        let uploader = IpfsSnapshotUploader::new(
+            server.url(""),
+            Some(PinningServiceConfig {
+                service_name: "pinata".to_string(),
+            }),
+        );
+
+        uploader.upload_snapshot(&archive).await.unwrap();
+
+        remote_pin_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn upload_snapshot_fails_if_pinning_fails() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/api/v0/add");
+            then.status(200).json_body(json!({ "Hash": CID }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/api/v0/pin/add");
+            then.status(500).body("pinning failed");
+        });
+        let source_dir = tempdir().unwrap();
+        let archive = create_fake_archive(source_dir.path());
+        let uploader = IpfsSnapshotUploader::new(server.url(""), None);
+
+        uploader
+            .upload_snapshot(&archive)
+            .await
+            .expect_err("upload should fail when the archive can't be pinned");
+    }
+
+    #[test]
+    fn translate_ipfs_location_to_gateway_url() {
+        assert_eq!(
+            Some(format!("https://ipfs.io/ipfs/{CID}")),
+            ipfs_location_to_gateway_url(&format!("ipfs://{CID}"), "https://ipfs.io/")
+        );
+        assert_eq!(
+            None,
+            ipfs_location_to_gateway_url("https://cdn.example/snapshot.tar.gz", "https://ipfs.io")
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use mithril_common::StdResult;
 use slog_scope::debug;
@@ -7,6 +7,7 @@ use std::path::{Path, PathBuf};
 use crate::http_server;
 use crate::snapshot_uploaders::{SnapshotLocation, SnapshotUploader};
 use crate::tools;
+use crate::ArchiveFormat;
 
 /// LocalSnapshotUploader is a snapshot uploader working using local files
 pub struct LocalSnapshotUploader {
@@ -26,6 +27,36 @@ impl LocalSnapshotUploader {
             target_location: target_location.to_path_buf(),
         }
     }
+
+    /// Check that the stored archive of the snapshot with the given digest is of a known
+    /// [format][ArchiveFormat].
+    pub fn validate_archive(&self, digest: &str) -> StdResult<ArchiveFormat> {
+        let archive_path = self.find_archive(digest)?.ok_or_else(|| {
+            anyhow!(
+                "No archive found for snapshot '{digest}' in '{:?}'",
+                self.target_location
+            )
+        })?;
+
+        ArchiveFormat::validate(&archive_path)
+            .with_context(|| format!("Invalid archive for snapshot '{digest}'"))
+    }
+
+    fn find_archive(&self, digest: &str) -> StdResult<Option<PathBuf>> {
+        for entry in std::fs::read_dir(&self.target_location).with_context(|| {
+            format!(
+                "Can not read snapshot directory '{:?}'",
+                self.target_location
+            )
+        })? {
+            let path = entry?.path();
+            if path.is_file() && tools::extract_digest_from_path(&path).is_ok_and(|d| d == digest) {
+                return Ok(Some(path));
+            }
+        }
+
+        Ok(None)
+    }
 }
 
 #[async_trait]
@@ -33,16 +64,25 @@ impl SnapshotUploader for LocalSnapshotUploader {
     async fn upload_snapshot(&self, snapshot_filepath: &Path) -> StdResult<SnapshotLocation> {
         let archive_name = snapshot_filepath.file_name().unwrap().to_str().unwrap();
         let target_path = &self.target_location.join(archive_name);
+        let digest = tools::extract_digest_from_path(Path::new(archive_name))
+            .with_context(|| format!("Can not extract digest from archive '{archive_name}'"))?;
         tokio::fs::copy(snapshot_filepath, target_path)
             .await
             .with_context(|| "Snapshot copy failure")?;
 
-        let digest = tools::extract_digest_from_path(Path::new(archive_name));
+        // An archive of an unknown format could not be restored by the clients
+        if let Err(error) = self.validate_archive(&digest) {
+            tokio::fs::remove_file(target_path)
+                .await
+                .with_context(|| format!("Can not remove invalid archive '{target_path:?}'"))?;
+            return Err(error);
+        }
+
         let location = format!(
             "{}{}/artifact/snapshot/{}/download",
             self.snapshot_server_url,
             http_server::SERVER_BASE_PATH,
-            digest.unwrap()
+            digest
         );
 
         Ok(location)
@@ -54,6 +94,8 @@ mod tests {
     use super::LocalSnapshotUploader;
     use crate::http_server;
     use crate::snapshot_uploaders::SnapshotUploader;
+    use crate::{ArchiveFormat, SnapshotError};
+    use flate2::{write::GzEncoder, Compression};
     use std::fs::File;
     use std::io::Write;
     use std::path::{Path, PathBuf};
@@ -61,12 +103,13 @@ mod tests {
 
     fn create_fake_archive(dir: &Path, digest: &str) -> PathBuf {
         let file_path = dir.join(format!("test.{digest}.tar.gz"));
-        let mut file = File::create(&file_path).unwrap();
+        let mut encoder = GzEncoder::new(File::create(&file_path).unwrap(), Compression::fast());
         writeln!(
-            file,
+            encoder,
             "I swear, this is an archive, not a temporary test file."
         )
         .unwrap();
+        encoder.finish().unwrap();
 
         file_path
     }
@@ -109,4 +152,48 @@ mod tests {
             .join(archive.file_name().unwrap())
             .exists());
     }
+
+    #[tokio::test]
+    async fn validate_archive_detects_the_format_of_the_uploaded_archive() {
+        let source_dir = tempdir().unwrap();
+        let target_dir = tempdir().unwrap();
+        let digest = "41e27b9ed5a32531b95b2b7ff3c0757591a06a337efaf19a524a998e348028e7";
+        let archive = create_fake_archive(source_dir.path(), digest);
+        let uploader =
+            LocalSnapshotUploader::new("http://test.com:8080/".to_string(), target_dir.path());
+        uploader.upload_snapshot(&archive).await.unwrap();
+
+        assert_eq!(
+            ArchiveFormat::Gzip,
+            uploader.validate_archive(digest).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn upload_of_a_text_file_disguised_as_an_archive_fails() {
+        let source_dir = tempdir().unwrap();
+        let target_dir = tempdir().unwrap();
+        let digest = "41e27b9ed5a32531b95b2b7ff3c0757591a06a337efaf19a524a998e348028e7";
+        let archive = source_dir.path().join(format!("test.{digest}.tar.gz"));
+        std::fs::write(&archive, "Not an archive").unwrap();
+        let uploader =
+            LocalSnapshotUploader::new("http://test.com:8080/".to_string(), target_dir.path());
+
+        let error = uploader
+            .upload_snapshot(&archive)
+            .await
+            .expect_err("upload of a text file should fail");
+
+        assert!(
+            matches!(
+                error.downcast_ref::<SnapshotError>(),
+                Some(SnapshotError::UnrecognisedFormat([b'N', b'o', b't', b' ']))
+            ),
+            "expected an UnrecognisedFormat error, got: {error:?}"
+        );
+        assert!(!target_dir
+            .path()
+            .join(archive.file_name().unwrap())
+            .exists());
+    }
 }
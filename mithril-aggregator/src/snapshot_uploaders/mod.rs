@@ -1,13 +1,21 @@
 mod dumb_snapshot_uploader;
+mod ipfs_snapshot_uploader;
 mod local_snapshot_uploader;
+mod parallel_chunk_uploader;
 mod remote_snapshot_uploader;
+mod s3_etag_validator;
 mod snapshot_uploader;
+mod tiered_snapshot_store;
 
 pub use dumb_snapshot_uploader::*;
+pub use ipfs_snapshot_uploader::{ipfs_location_to_gateway_url, IpfsSnapshotUploader};
 pub use local_snapshot_uploader::LocalSnapshotUploader;
+pub use parallel_chunk_uploader::{ParallelChunkUploader, S3_MULTIPART_MIN_CHUNK_SIZE_MB};
 pub use remote_snapshot_uploader::RemoteSnapshotUploader;
+pub use s3_etag_validator::S3ETagValidator;
 pub use snapshot_uploader::SnapshotLocation;
 pub use snapshot_uploader::SnapshotUploader;
+pub use tiered_snapshot_store::{SnapshotTier, TieredSnapshotStore};
 
 #[cfg(test)]
 pub use snapshot_uploader::MockSnapshotUploader;
@@ -0,0 +1,399 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use mithril_common::StdResult;
+use slog_scope::{info, warn};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::snapshot_uploaders::{
+    RemoteSnapshotUploader, S3ETagValidator, SnapshotLocation, SnapshotUploader,
+};
+use crate::tools::RemoteFileUploader;
+
+/// Minimum size, in megabytes, of the chunks of an S3 multipart upload (but the last one)
+pub const S3_MULTIPART_MIN_CHUNK_SIZE_MB: usize = 5;
+
+/// Snapshot uploader that splits the archives in chunks uploaded concurrently with an S3
+/// multipart upload, the chunks are assembled by the storage once they are all uploaded.
+///
+/// Built with [RemoteSnapshotUploader::parallel].
+pub struct ParallelChunkUploader {
+    file_uploader: Arc<dyn RemoteFileUploader>,
+    location_base_url: String,
+    chunk_size: u64,
+    max_parallel: usize,
+}
+
+impl ParallelChunkUploader {
+    /// ParallelChunkUploader factory
+    pub fn new(
+        file_uploader: Arc<dyn RemoteFileUploader>,
+        location_base_url: String,
+        chunk_size: u64,
+        max_parallel: usize,
+    ) -> Self {
+        Self {
+            file_uploader,
+            location_base_url,
+            chunk_size: chunk_size.max(1),
+            max_parallel: max_parallel.max(1),
+        }
+    }
+
+    async fn upload_chunk(
+        &self,
+        snapshot_filepath: &Path,
+        upload_id: &str,
+        part_number: u32,
+    ) -> StdResult<(u32, String)> {
+        let offset = (part_number - 1) as u64 * self.chunk_size;
+        let chunk =
+            RemoteSnapshotUploader::read_part(snapshot_filepath, offset, self.chunk_size).await?;
+        let etag = self
+            .file_uploader
+            .upload_multipart_chunk(snapshot_filepath, upload_id, part_number, chunk)
+            .await
+            .with_context(|| {
+                format!("Upload of chunk {part_number} of '{snapshot_filepath:?}' failed")
+            })?;
+
+        Ok((part_number, etag))
+    }
+
+    /// Upload the chunks concurrently then assemble them
+    ///
+    /// Returns the ETag of the assembled object if the storage returns one.
+    async fn upload_chunks(
+        &self,
+        snapshot_filepath: &Path,
+        upload_id: &str,
+        chunks_count: u32,
+    ) -> StdResult<Option<String>> {
+        let mut parts: Vec<(u32, String)> = futures::stream::iter(1..=chunks_count)
+            .map(|part_number| self.upload_chunk(snapshot_filepath, upload_id, part_number))
+            .buffer_unordered(self.max_parallel)
+            .try_collect()
+            .await?;
+        parts.sort_by_key(|(part_number, _)| *part_number);
+
+        self.file_uploader
+            .complete_multipart_upload(snapshot_filepath, upload_id, parts)
+            .await
+            .with_context(|| {
+                format!("Could not complete the multipart upload of '{snapshot_filepath:?}'")
+            })
+    }
+}
+
+#[async_trait]
+impl SnapshotUploader for ParallelChunkUploader {
+    async fn upload_snapshot(&self, snapshot_filepath: &Path) -> StdResult<SnapshotLocation> {
+        let total_size = tokio::fs::metadata(snapshot_filepath).await?.len();
+        // An empty archive is still uploaded as a single empty chunk
+        let chunks_count = total_size.div_ceil(self.chunk_size).max(1) as u32;
+        let upload_id = self
+            .file_uploader
+            .create_multipart_upload(snapshot_filepath)
+            .await?;
+        info!(
+            "Uploading snapshot in parallel chunks";
+            "file_path" => ?snapshot_filepath, "upload_id" => &upload_id,
+            "chunks" => chunks_count, "max_parallel" => self.max_parallel
+        );
+
+        let etag = match self
+            .upload_chunks(snapshot_filepath, &upload_id, chunks_count)
+            .await
+        {
+            Ok(etag) => etag,
+            Err(error) => {
+                // Without an abort the storage keeps the uploaded chunks of the failed upload
+                if let Err(abort_error) = self
+                    .file_uploader
+                    .abort_multipart_upload(snapshot_filepath, &upload_id)
+                    .await
+                {
+                    warn!(
+                        "Could not abort the failed multipart upload";
+                        "file_path" => ?snapshot_filepath, "upload_id" => &upload_id, "error" => ?abort_error
+                    );
+                }
+
+                return Err(error);
+            }
+        };
+
+        // The ETag of a multipart upload depends on the size of its chunks
+        if let Some(etag) = etag {
+            let filepath = snapshot_filepath.to_path_buf();
+            let chunk_size = self.chunk_size;
+            tokio::task::spawn_blocking(move || {
+                S3ETagValidator::validate(&filepath, &etag, Some(chunk_size))
+            })
+            .await?
+            .with_context(|| format!("Uploaded snapshot '{snapshot_filepath:?}' is corrupted"))?;
+        }
+
+        Ok(RemoteSnapshotUploader::snapshot_location(
+            &self.location_base_url,
+            snapshot_filepath,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use md5::{Digest, Md5};
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tempfile::TempDir;
+    use tokio::sync::Barrier;
+
+    use crate::tools::MockRemoteFileUploader;
+    use crate::SnapshotError;
+
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn create_archive(dir: &TempDir, size: u64) -> PathBuf {
+        let archive_path = dir.path().join("snapshot.xxx.tar.gz");
+        let content: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&archive_path, content).unwrap();
+
+        archive_path
+    }
+
+    /// Parts of a completed multipart upload, with the number of chunks uploaded at completion
+    type CompletedParts = (Vec<(u32, String)>, usize);
+
+    #[derive(Default)]
+    struct MultipartUploadRecords {
+        uploaded_chunks: Mutex<Vec<(u32, u64)>>,
+        /// Parts given to the `CompleteMultipartUpload` call, with the number of chunks
+        /// uploaded at the time of the call
+        completed_parts: Mutex<Option<CompletedParts>>,
+    }
+
+    /// Multipart file uploader whose chunk uploads only end once `concurrent_uploads` chunks
+    /// are being uploaded at the same time
+    struct ConcurrentMultipartUploader {
+        barrier: Barrier,
+        records: Arc<MultipartUploadRecords>,
+    }
+
+    impl ConcurrentMultipartUploader {
+        fn new(concurrent_uploads: usize, records: Arc<MultipartUploadRecords>) -> Self {
+            Self {
+                barrier: Barrier::new(concurrent_uploads),
+                records,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl RemoteFileUploader for ConcurrentMultipartUploader {
+        async fn upload_file(&self, _filepath: &Path) -> StdResult<Option<String>> {
+            Err(anyhow!("the snapshot must be uploaded in chunks"))
+        }
+
+        async fn create_multipart_upload(&self, _filepath: &Path) -> StdResult<String> {
+            Ok("upload-1".to_string())
+        }
+
+        async fn upload_multipart_chunk(
+            &self,
+            _filepath: &Path,
+            _upload_id: &str,
+            part_number: u32,
+            chunk: Vec<u8>,
+        ) -> StdResult<String> {
+            self.barrier.wait().await;
+            self.records
+                .uploaded_chunks
+                .lock()
+                .unwrap()
+                .push((part_number, chunk.len() as u64));
+
+            Ok(format!("etag-{part_number}"))
+        }
+
+        async fn complete_multipart_upload(
+            &self,
+            _filepath: &Path,
+            _upload_id: &str,
+            parts: Vec<(u32, String)>,
+        ) -> StdResult<Option<String>> {
+            let uploaded_chunks_count = self.records.uploaded_chunks.lock().unwrap().len();
+            *self.records.completed_parts.lock().unwrap() = Some((parts, uploaded_chunks_count));
+
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn upload_snapshot_completes_the_multipart_upload_after_all_the_chunks() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir, 25 * MB);
+        let records = Arc::new(MultipartUploadRecords::default());
+        let snapshot_uploader = RemoteSnapshotUploader::new(
+            Box::new(ConcurrentMultipartUploader::new(3, records.clone())),
+            "cardano-testnet".to_string(),
+            false,
+        )
+        .parallel(10, 3);
+
+        let location = tokio::time::timeout(
+            Duration::from_secs(10),
+            snapshot_uploader.upload_snapshot(&archive_path),
+        )
+        .await
+        .expect("the three chunks should be uploaded concurrently")
+        .unwrap();
+
+        assert_eq!(
+            "https://storage.googleapis.com/cardano-testnet/snapshot.xxx.tar.gz",
+            location
+        );
+        let mut uploaded_chunks = records.uploaded_chunks.lock().unwrap().clone();
+        uploaded_chunks.sort();
+        assert_eq!(
+            vec![(1, 10 * MB), (2, 10 * MB), (3, 5 * MB)],
+            uploaded_chunks
+        );
+        assert_eq!(
+            Some((
+                vec![
+                    (1, "etag-1".to_string()),
+                    (2, "etag-2".to_string()),
+                    (3, "etag-3".to_string())
+                ],
+                3
+            )),
+            records.completed_parts.lock().unwrap().clone()
+        );
+    }
+
+    fn multipart_file_uploader(etag: &str) -> MockRemoteFileUploader {
+        let etag = etag.to_string();
+        let mut file_uploader = MockRemoteFileUploader::new();
+        file_uploader
+            .expect_create_multipart_upload()
+            .returning(|_| Ok("upload-1".to_string()));
+        file_uploader
+            .expect_upload_multipart_chunk()
+            .returning(|_, _, part_number, _| Ok(format!("etag-{part_number}")));
+        file_uploader
+            .expect_complete_multipart_upload()
+            .returning(move |_, _, _| Ok(Some(etag.clone())));
+
+        file_uploader
+    }
+
+    #[tokio::test]
+    async fn upload_snapshot_validates_the_etag_with_the_chunk_size() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir, 1000);
+        let content = std::fs::read(&archive_path).unwrap();
+        let mut parts_hasher = Md5::new();
+        for chunk in content.chunks(400) {
+            parts_hasher.update(Md5::digest(chunk));
+        }
+        let etag = format!("\"{}-3\"", hex::encode(parts_hasher.finalize()));
+        let snapshot_uploader = ParallelChunkUploader::new(
+            Arc::new(multipart_file_uploader(&etag)),
+            "".to_string(),
+            400,
+            2,
+        );
+
+        snapshot_uploader
+            .upload_snapshot(&archive_path)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn upload_snapshot_with_a_wrong_etag_fails() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir, 1000);
+        let snapshot_uploader = ParallelChunkUploader::new(
+            Arc::new(multipart_file_uploader(
+                "\"0123456789abcdef0123456789abcdef-3\"",
+            )),
+            "".to_string(),
+            400,
+            2,
+        );
+
+        let error = snapshot_uploader
+            .upload_snapshot(&archive_path)
+            .await
+            .expect_err("upload with a wrong ETag should fail");
+
+        assert!(matches!(
+            error.downcast_ref::<SnapshotError>(),
+            Some(SnapshotError::ETagMismatch { actual, .. })
+                if actual == "0123456789abcdef0123456789abcdef-3"
+        ));
+    }
+
+    #[tokio::test]
+    async fn upload_snapshot_aborts_without_completing_if_a_chunk_upload_fails() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir, 1000);
+        let mut file_uploader = MockRemoteFileUploader::new();
+        file_uploader
+            .expect_create_multipart_upload()
+            .returning(|_| Ok("upload-1".to_string()));
+        file_uploader
+            .expect_upload_multipart_chunk()
+            .returning(|_, _, part_number, _| match part_number {
+                2 => Err(anyhow!("connection reset")),
+                _ => Ok(format!("etag-{part_number}")),
+            });
+        file_uploader.expect_complete_multipart_upload().never();
+        file_uploader
+            .expect_abort_multipart_upload()
+            .withf(|_, upload_id| upload_id == "upload-1")
+            .returning(|_, _| Ok(()))
+            .once();
+        let snapshot_uploader =
+            ParallelChunkUploader::new(Arc::new(file_uploader), "".to_string(), 400, 2);
+
+        snapshot_uploader
+            .upload_snapshot(&archive_path)
+            .await
+            .expect_err("upload with a failed chunk should fail");
+    }
+
+    #[tokio::test]
+    async fn upload_snapshot_aborts_if_the_completion_fails() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir, 1000);
+        let mut file_uploader = MockRemoteFileUploader::new();
+        file_uploader
+            .expect_create_multipart_upload()
+            .returning(|_| Ok("upload-1".to_string()));
+        file_uploader
+            .expect_upload_multipart_chunk()
+            .returning(|_, _, part_number, _| Ok(format!("etag-{part_number}")));
+        file_uploader
+            .expect_complete_multipart_upload()
+            .returning(|_, _, _| Err(anyhow!("invalid part")));
+        file_uploader
+            .expect_abort_multipart_upload()
+            .returning(|_, _| Ok(()))
+            .once();
+        let snapshot_uploader =
+            ParallelChunkUploader::new(Arc::new(file_uploader), "".to_string(), 400, 2);
+
+        snapshot_uploader
+            .upload_snapshot(&archive_path)
+            .await
+            .expect_err("upload with a failed completion should fail");
+    }
+}
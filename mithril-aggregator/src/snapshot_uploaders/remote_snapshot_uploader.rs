@@ -1,16 +1,28 @@
+use anyhow::Context;
 use async_trait::async_trait;
+use chrono::Utc;
 use mithril_common::StdResult;
-use slog_scope::debug;
+use sha2::{Digest, Sha256};
+use slog_scope::{debug, info, warn};
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
+use std::sync::Arc;
 
-use crate::snapshot_uploaders::{SnapshotLocation, SnapshotUploader};
+use crate::snapshot_uploaders::{
+    ParallelChunkUploader, S3ETagValidator, SnapshotLocation, SnapshotUploader,
+};
+use crate::store::{UploadSession, UploadSessionStore};
 use crate::tools::RemoteFileUploader;
 
+/// Default size of the parts of the files uploaded through an upload session
+pub const DEFAULT_UPLOAD_PART_SIZE: u64 = 8 * 1024 * 1024;
+
 /// GCPSnapshotUploader is a snapshot uploader working using Google Cloud Platform services
 pub struct RemoteSnapshotUploader {
-    bucket: String,
     file_uploader: Box<dyn RemoteFileUploader>,
-    use_cdn_domain: bool,
+    location_base_url: String,
+    upload_session_store: Option<Arc<UploadSessionStore>>,
+    upload_part_size: u64,
 }
 
 impl RemoteSnapshotUploader {
@@ -21,46 +33,315 @@ impl RemoteSnapshotUploader {
         use_cdn_domain: bool,
     ) -> Self {
         debug!("New GCPSnapshotUploader created");
+        let location_base_url = if use_cdn_domain {
+            format!("https://{bucket}")
+        } else {
+            format!("https://storage.googleapis.com/{bucket}")
+        };
         Self {
-            bucket,
             file_uploader,
-            use_cdn_domain,
+            location_base_url,
+            upload_session_store: None,
+            upload_part_size: DEFAULT_UPLOAD_PART_SIZE,
         }
     }
+
+    /// Record the uploads in the given store so they can be resumed after an interruption.
+    ///
+    /// Only used if the file uploader supports upload sessions.
+    pub fn with_upload_session_store(
+        mut self,
+        upload_session_store: Arc<UploadSessionStore>,
+    ) -> Self {
+        self.upload_session_store = Some(upload_session_store);
+        self
+    }
+
+    /// Build the locations of the uploaded snapshots from the given URL instead of the Google
+    /// Cloud Storage URL of the bucket
+    pub fn with_location_base_url(mut self, location_base_url: String) -> Self {
+        self.location_base_url = location_base_url;
+        self
+    }
+
+    /// Set the size of the parts of the files uploaded through an upload session
+    pub fn with_upload_part_size(mut self, upload_part_size: u64) -> Self {
+        self.upload_part_size = upload_part_size.max(1);
+        self
+    }
+
+    /// Upload the snapshots with an S3 multipart upload, sending chunks of `chunk_size_mb`
+    /// megabytes over up to `max_parallel` concurrent streams.
+    pub fn parallel(self, chunk_size_mb: usize, max_parallel: usize) -> ParallelChunkUploader {
+        ParallelChunkUploader::new(
+            Arc::from(self.file_uploader),
+            self.location_base_url,
+            chunk_size_mb as u64 * 1024 * 1024,
+            max_parallel,
+        )
+    }
+
+    pub(super) fn snapshot_location(
+        location_base_url: &str,
+        snapshot_filepath: &Path,
+    ) -> SnapshotLocation {
+        let archive_name = snapshot_filepath.file_name().unwrap().to_str().unwrap();
+        format!("{location_base_url}/{archive_name}")
+    }
+
+    /// Fingerprint of a file computed from its metadata, cheap enough to be computed before
+    /// each upload unlike a digest of its content.
+    async fn compute_file_fingerprint(filepath: &Path) -> StdResult<String> {
+        let metadata = tokio::fs::metadata(filepath)
+            .await
+            .with_context(|| format!("Can not read metadata of file '{filepath:?}'"))?;
+        let modified_at = metadata
+            .modified()
+            .with_context(|| format!("Can not read modification time of file '{filepath:?}'"))?
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        let mut hasher = Sha256::new();
+        hasher.update(filepath.to_string_lossy().as_bytes());
+        hasher.update(metadata.len().to_be_bytes());
+        hasher.update(modified_at.as_nanos().to_be_bytes());
+
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    pub(super) async fn read_part(filepath: &Path, offset: u64, size: u64) -> StdResult<Vec<u8>> {
+        let filepath = filepath.to_path_buf();
+        tokio::task::spawn_blocking(move || -> StdResult<Vec<u8>> {
+            let mut file = std::fs::File::open(&filepath)
+                .with_context(|| format!("Can not open file '{filepath:?}'"))?;
+            file.seek(SeekFrom::Start(offset))?;
+            let mut part = Vec::new();
+            file.take(size).read_to_end(&mut part)?;
+
+            Ok(part)
+        })
+        .await?
+    }
+
+    /// Upload the file through an upload session, resuming the session previously opened for
+    /// the same unmodified file if any.
+    ///
+    /// Returns `false` if the file uploader does not support upload sessions.
+    async fn upload_with_session(
+        &self,
+        upload_session_store: &UploadSessionStore,
+        filepath: &Path,
+    ) -> StdResult<bool> {
+        let file_fingerprint = Self::compute_file_fingerprint(filepath).await?;
+        let mut session = match upload_session_store.get(&file_fingerprint).await? {
+            Some(session) => {
+                info!(
+                    "Resuming upload session";
+                    "session_id" => &session.session_id, "file_path" => ?filepath, "offset" => session.offset
+                );
+                session
+            }
+            None => match self.file_uploader.open_upload_session(filepath).await? {
+                Some(session_id) => UploadSession {
+                    session_id,
+                    file_path: filepath.to_path_buf(),
+                    file_fingerprint,
+                    offset: 0,
+                    started_at: Utc::now(),
+                },
+                None => return Ok(false),
+            },
+        };
+        upload_session_store.save(&session).await?;
+
+        let total_size = tokio::fs::metadata(filepath).await?.len();
+        while session.offset < total_size {
+            let part = Self::read_part(filepath, session.offset, self.upload_part_size).await?;
+            let part_size = part.len() as u64;
+            self.file_uploader
+                .upload_part(&session.session_id, session.offset, part, total_size)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Upload of '{filepath:?}' interrupted at offset {}",
+                        session.offset
+                    )
+                })?;
+            session.offset += part_size;
+            upload_session_store.save(&session).await?;
+        }
+        upload_session_store
+            .remove(&session.file_fingerprint)
+            .await?;
+
+        Ok(true)
+    }
 }
 
 #[async_trait]
 impl SnapshotUploader for RemoteSnapshotUploader {
     async fn upload_snapshot(&self, snapshot_filepath: &Path) -> StdResult<SnapshotLocation> {
-        let archive_name = snapshot_filepath.file_name().unwrap().to_str().unwrap();
-        let location = if self.use_cdn_domain {
-            format!("https://{}/{}", self.bucket, archive_name)
-        } else {
-            format!(
-                "https://storage.googleapis.com/{}/{}",
-                self.bucket, archive_name
-            )
-        };
+        let location = Self::snapshot_location(&self.location_base_url, snapshot_filepath);
 
-        self.file_uploader.upload_file(snapshot_filepath).await?;
+        let uploaded_with_session = match &self.upload_session_store {
+            Some(upload_session_store) => {
+                self.upload_with_session(upload_session_store, snapshot_filepath)
+                    .await?
+            }
+            None => false,
+        };
+        if !uploaded_with_session {
+            if let Some(etag) = self.file_uploader.upload_file(snapshot_filepath).await? {
+                let filepath = snapshot_filepath.to_path_buf();
+                tokio::task::spawn_blocking(move || {
+                    S3ETagValidator::validate(&filepath, &etag, None)
+                })
+                .await?
+                .with_context(|| {
+                    format!("Uploaded snapshot '{snapshot_filepath:?}' is corrupted")
+                })?;
+            }
+        }
 
         Ok(location)
     }
+
+    async fn resume_pending_uploads(&self) -> StdResult<()> {
+        let Some(upload_session_store) = &self.upload_session_store else {
+            return Ok(());
+        };
+
+        for session in upload_session_store.list().await? {
+            if !session.file_path.exists() {
+                warn!(
+                    "Dropping upload session of a file that does not exist anymore";
+                    "session_id" => &session.session_id, "file_path" => ?session.file_path
+                );
+                upload_session_store
+                    .remove(&session.file_fingerprint)
+                    .await?;
+                continue;
+            }
+            if Self::compute_file_fingerprint(&session.file_path).await? != session.file_fingerprint
+            {
+                warn!(
+                    "Dropping upload session of a file that was modified";
+                    "session_id" => &session.session_id, "file_path" => ?session.file_path
+                );
+                upload_session_store
+                    .remove(&session.file_fingerprint)
+                    .await?;
+                continue;
+            }
+
+            self.upload_snapshot(&session.file_path)
+                .await
+                .with_context(|| {
+                    format!("Could not resume the upload of '{:?}'", session.file_path)
+                })?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::RemoteSnapshotUploader;
     use crate::snapshot_uploaders::SnapshotUploader;
+    use crate::store::{UploadSession, UploadSessionStore};
     use crate::tools::MockRemoteFileUploader;
+    use crate::SnapshotError;
     use anyhow::anyhow;
-    use std::path::Path;
+    use mithril_persistence::store::adapter::MemoryAdapter;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    const ARCHIVE_SIZE: u64 = 1000;
+    const PART_SIZE: u64 = 100;
+
+    fn create_archive(dir: &TempDir) -> PathBuf {
+        let archive_path = dir.path().join("snapshot.xxx.tar.gz");
+        let content: Vec<u8> = (0..ARCHIVE_SIZE).map(|i| (i % 256) as u8).collect();
+        std::fs::write(&archive_path, content).unwrap();
+
+        archive_path
+    }
+
+    fn memory_upload_session_store() -> Arc<UploadSessionStore> {
+        Arc::new(UploadSessionStore::new(Box::new(
+            MemoryAdapter::<String, UploadSession>::new(None).unwrap(),
+        )))
+    }
+
+    /// File uploader supporting upload sessions that records the offset and size of the
+    /// uploaded parts, and fails to upload the parts starting at or after `fail_from_offset`
+    fn session_file_uploader(
+        uploaded_parts: Arc<Mutex<Vec<(u64, u64)>>>,
+        fail_from_offset: Option<u64>,
+    ) -> MockRemoteFileUploader {
+        let mut file_uploader = MockRemoteFileUploader::new();
+        file_uploader
+            .expect_open_upload_session()
+            .returning(|_| Ok(Some("session-1".to_string())));
+        file_uploader
+            .expect_upload_part()
+            .returning(move |_, offset, part, _| {
+                if fail_from_offset.is_some_and(|fail_offset| offset >= fail_offset) {
+                    return Err(anyhow!("connection reset"));
+                }
+                uploaded_parts
+                    .lock()
+                    .unwrap()
+                    .push((offset, part.len() as u64));
+                Ok(())
+            });
+        file_uploader.expect_upload_file().never();
+
+        file_uploader
+    }
+
+    /// Upload the archive with an upload that is interrupted after half of the archive is sent
+    async fn interrupt_upload_at_half(
+        upload_session_store: Arc<UploadSessionStore>,
+        archive_path: &Path,
+    ) {
+        let snapshot_uploader = RemoteSnapshotUploader::new(
+            Box::new(session_file_uploader(
+                Arc::new(Mutex::new(vec![])),
+                Some(ARCHIVE_SIZE / 2),
+            )),
+            "cardano-testnet".to_string(),
+            false,
+        )
+        .with_upload_session_store(upload_session_store)
+        .with_upload_part_size(PART_SIZE);
+
+        snapshot_uploader
+            .upload_snapshot(archive_path)
+            .await
+            .expect_err("upload should be interrupted");
+    }
+
+    fn assert_only_the_second_half_was_uploaded(uploaded_parts: &[(u64, u64)]) {
+        assert_eq!(
+            (ARCHIVE_SIZE / 2..ARCHIVE_SIZE)
+                .step_by(PART_SIZE as usize)
+                .map(|offset| (offset, PART_SIZE))
+                .collect::<Vec<_>>(),
+            uploaded_parts
+        );
+        assert_eq!(
+            ARCHIVE_SIZE / 2,
+            uploaded_parts.iter().map(|(_, size)| size).sum::<u64>()
+        );
+    }
 
     #[tokio::test]
     async fn test_upload_snapshot_not_using_cdn_domain_ok() {
         let use_cdn_domain = false;
         let mut file_uploader = MockRemoteFileUploader::new();
-        file_uploader.expect_upload_file().returning(|_| Ok(()));
+        file_uploader.expect_upload_file().returning(|_| Ok(None));
         let snapshot_uploader = RemoteSnapshotUploader::new(
             Box::new(file_uploader),
             "cardano-testnet".to_string(),
@@ -82,7 +363,7 @@ mod tests {
     async fn test_upload_snapshot_using_cdn_domain_ok() {
         let use_cdn_domain = true;
         let mut file_uploader = MockRemoteFileUploader::new();
-        file_uploader.expect_upload_file().returning(|_| Ok(()));
+        file_uploader.expect_upload_file().returning(|_| Ok(None));
         let snapshot_uploader = RemoteSnapshotUploader::new(
             Box::new(file_uploader),
             "cdn.mithril.network".to_string(),
@@ -115,4 +396,150 @@ mod tests {
             .expect_err("remote upload should fail");
         assert_eq!("unexpected error".to_string(), result.to_string());
     }
+
+    #[tokio::test]
+    async fn upload_snapshot_with_a_wrong_etag_fails() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir);
+        let mut file_uploader = MockRemoteFileUploader::new();
+        file_uploader
+            .expect_upload_file()
+            .returning(|_| Ok(Some("\"0123456789abcdef0123456789abcdef\"".to_string())));
+        let snapshot_uploader = RemoteSnapshotUploader::new(
+            Box::new(file_uploader),
+            "cardano-testnet".to_string(),
+            false,
+        );
+
+        let error = snapshot_uploader
+            .upload_snapshot(&archive_path)
+            .await
+            .expect_err("upload with a wrong ETag should fail");
+
+        assert!(matches!(
+            error.downcast_ref::<SnapshotError>(),
+            Some(SnapshotError::ETagMismatch { actual, .. })
+                if actual == "0123456789abcdef0123456789abcdef"
+        ));
+    }
+
+    #[tokio::test]
+    async fn interrupted_upload_keeps_its_session_offset() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir);
+        let upload_session_store = memory_upload_session_store();
+
+        interrupt_upload_at_half(upload_session_store.clone(), &archive_path).await;
+
+        let sessions = upload_session_store.list().await.unwrap();
+        assert_eq!(1, sessions.len());
+        assert_eq!(ARCHIVE_SIZE / 2, sessions[0].offset);
+        assert_eq!(archive_path, sessions[0].file_path);
+    }
+
+    #[tokio::test]
+    async fn upload_of_the_same_file_resumes_the_interrupted_session() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir);
+        let upload_session_store = memory_upload_session_store();
+        interrupt_upload_at_half(upload_session_store.clone(), &archive_path).await;
+
+        let uploaded_parts = Arc::new(Mutex::new(vec![]));
+        // No new session must be opened
+        let mut file_uploader = MockRemoteFileUploader::new();
+        let uploaded_parts_clone = uploaded_parts.clone();
+        file_uploader
+            .expect_upload_part()
+            .withf(|session_id, _, _, total_size| {
+                session_id == "session-1" && *total_size == ARCHIVE_SIZE
+            })
+            .returning(move |_, offset, part, _| {
+                uploaded_parts_clone
+                    .lock()
+                    .unwrap()
+                    .push((offset, part.len() as u64));
+                Ok(())
+            });
+        let snapshot_uploader = RemoteSnapshotUploader::new(
+            Box::new(file_uploader),
+            "cardano-testnet".to_string(),
+            false,
+        )
+        .with_upload_session_store(upload_session_store.clone())
+        .with_upload_part_size(PART_SIZE);
+
+        snapshot_uploader
+            .upload_snapshot(&archive_path)
+            .await
+            .expect("resumed upload should not fail");
+
+        assert_only_the_second_half_was_uploaded(&uploaded_parts.lock().unwrap());
+        assert!(upload_session_store.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn resume_pending_uploads_completes_the_interrupted_sessions() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir);
+        let upload_session_store = memory_upload_session_store();
+        interrupt_upload_at_half(upload_session_store.clone(), &archive_path).await;
+
+        let uploaded_parts = Arc::new(Mutex::new(vec![]));
+        let snapshot_uploader = RemoteSnapshotUploader::new(
+            Box::new(session_file_uploader(uploaded_parts.clone(), None)),
+            "cardano-testnet".to_string(),
+            false,
+        )
+        .with_upload_session_store(upload_session_store.clone())
+        .with_upload_part_size(PART_SIZE);
+
+        snapshot_uploader.resume_pending_uploads().await.unwrap();
+
+        assert_only_the_second_half_was_uploaded(&uploaded_parts.lock().unwrap());
+        assert!(upload_session_store.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn resume_pending_uploads_drops_the_sessions_of_removed_files() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir);
+        let upload_session_store = memory_upload_session_store();
+        interrupt_upload_at_half(upload_session_store.clone(), &archive_path).await;
+        std::fs::remove_file(&archive_path).unwrap();
+
+        let mut file_uploader = MockRemoteFileUploader::new();
+        file_uploader.expect_upload_part().never();
+        let snapshot_uploader = RemoteSnapshotUploader::new(
+            Box::new(file_uploader),
+            "cardano-testnet".to_string(),
+            false,
+        )
+        .with_upload_session_store(upload_session_store.clone());
+
+        snapshot_uploader.resume_pending_uploads().await.unwrap();
+
+        assert!(upload_session_store.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn resume_pending_uploads_drops_the_sessions_of_modified_files() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = create_archive(&dir);
+        let upload_session_store = memory_upload_session_store();
+        interrupt_upload_at_half(upload_session_store.clone(), &archive_path).await;
+        std::fs::write(&archive_path, vec![0; ARCHIVE_SIZE as usize + 1]).unwrap();
+
+        let mut file_uploader = MockRemoteFileUploader::new();
+        file_uploader.expect_upload_part().never();
+        let snapshot_uploader = RemoteSnapshotUploader::new(
+            Box::new(file_uploader),
+            "cardano-testnet".to_string(),
+            false,
+        )
+        .with_upload_session_store(upload_session_store.clone());
+
+        snapshot_uploader.resume_pending_uploads().await.unwrap();
+
+        assert!(upload_session_store.list().await.unwrap().is_empty());
+    }
 }
@@ -0,0 +1,128 @@
+use anyhow::Context;
+use md5::{Digest, Md5};
+use mithril_common::StdResult;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use crate::SnapshotError;
+
+/// Check that the ETag returned by an S3 compatible storage matches the content of the
+/// uploaded file.
+///
+/// The ETag of a file sent with a single `PUT` is the MD5 of its content. The ETag of a
+/// multipart upload is the MD5 of the concatenated MD5 of its parts followed by `-<parts count>`,
+/// so it can only be checked knowing the size of the parts the file was split in.
+pub struct S3ETagValidator;
+
+impl S3ETagValidator {
+    /// Validate the ETag of the given local file, the quotes around the ETag are ignored.
+    ///
+    /// `multipart_part_size` is the size of the parts of a multipart upload (but the last one),
+    /// `None` if the file was sent with a single `PUT`.
+    ///
+    /// Fail with a [SnapshotError::ETagMismatch] if the ETag doesn't match the file content.
+    pub fn validate(
+        local_file: &Path,
+        etag: &str,
+        multipart_part_size: Option<u64>,
+    ) -> StdResult<()> {
+        let expected = Self::compute_etag(local_file, multipart_part_size)?;
+        let actual = etag.trim_matches('"').to_lowercase();
+
+        if expected != actual {
+            return Err(SnapshotError::ETagMismatch { expected, actual }.into());
+        }
+
+        Ok(())
+    }
+
+    fn compute_etag(local_file: &Path, multipart_part_size: Option<u64>) -> StdResult<String> {
+        let file = File::open(local_file)
+            .with_context(|| format!("Can not open file '{local_file:?}'"))?;
+        let mut reader = BufReader::new(file);
+
+        match multipart_part_size {
+            None => {
+                let mut hasher = Md5::new();
+                std::io::copy(&mut reader, &mut hasher)
+                    .with_context(|| format!("Can not read file '{local_file:?}'"))?;
+
+                Ok(hex::encode(hasher.finalize()))
+            }
+            Some(part_size) => {
+                let mut parts_hasher = Md5::new();
+                let mut parts_count = 0;
+                loop {
+                    let mut part_hasher = Md5::new();
+                    let read = std::io::copy(&mut (&mut reader).take(part_size), &mut part_hasher)
+                        .with_context(|| format!("Can not read file '{local_file:?}'"))?;
+                    if read == 0 && parts_count > 0 {
+                        break;
+                    }
+                    parts_hasher.update(part_hasher.finalize());
+                    parts_count += 1;
+                    if read < part_size {
+                        break;
+                    }
+                }
+
+                Ok(format!(
+                    "{}-{parts_count}",
+                    hex::encode(parts_hasher.finalize())
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn write_file(dir: &TempDir, content: &[u8]) -> std::path::PathBuf {
+        let path = dir.path().join("snapshot.xxx.tar.gz");
+        std::fs::write(&path, content).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn single_part_etag_is_the_md5_of_the_file() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(&dir, b"hello world");
+
+        S3ETagValidator::validate(&path, "\"5eb63bbbe01eeed093cb22bb8f5acdc3\"", None).unwrap();
+    }
+
+    #[test]
+    fn multipart_etag_is_the_md5_of_the_parts_md5() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(&dir, b"hello world");
+        let mut parts_hasher = Md5::new();
+        for part in [&b"hello"[..], b" worl", b"d"] {
+            parts_hasher.update(Md5::digest(part));
+        }
+        let etag = format!("{}-3", hex::encode(parts_hasher.finalize()));
+
+        S3ETagValidator::validate(&path, &etag, Some(5)).unwrap();
+    }
+
+    #[test]
+    fn wrong_etag_fails_with_a_mismatch_error() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(&dir, b"hello world");
+
+        let error = S3ETagValidator::validate(&path, "0123456789abcdef0123456789abcdef", None)
+            .expect_err("validation of a wrong ETag should fail");
+
+        assert!(matches!(
+            error.downcast_ref::<SnapshotError>(),
+            Some(SnapshotError::ETagMismatch { expected, actual })
+                if expected == "5eb63bbbe01eeed093cb22bb8f5acdc3"
+                    && actual == "0123456789abcdef0123456789abcdef"
+        ));
+    }
+}
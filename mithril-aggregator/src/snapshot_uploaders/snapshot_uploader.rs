@@ -13,4 +13,9 @@ pub type SnapshotLocation = String;
 pub trait SnapshotUploader: Sync + Send {
     /// Upload a snapshot
     async fn upload_snapshot(&self, snapshot_filepath: &Path) -> StdResult<SnapshotLocation>;
+
+    /// Resume the uploads interrupted by a previous run of the aggregator
+    async fn resume_pending_uploads(&self) -> StdResult<()> {
+        Ok(())
+    }
 }
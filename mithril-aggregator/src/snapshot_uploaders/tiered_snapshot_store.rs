@@ -0,0 +1,325 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use slog_scope::{debug, info};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use mithril_common::entities::{Epoch, Snapshot};
+use mithril_common::StdResult;
+
+use crate::database::repository::SignedEntityStorer;
+use crate::snapshot_uploaders::{SnapshotLocation, SnapshotUploader};
+use crate::tools;
+
+/// Tier of a [TieredSnapshotStore] in which a snapshot archive is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotTier {
+    /// Fast storage of the recent snapshots
+    Hot,
+
+    /// Cheaper storage of the snapshots older than the age threshold
+    Cold,
+}
+
+/// Snapshot uploader that keeps the recent snapshots in a `hot` tier and moves the snapshots
+/// older than an age threshold, in epochs, to a `cold` tier.
+///
+/// The snapshots are always uploaded to the `hot` tier, which must keep the archives in the
+/// `hot_directory` (ie: a [LocalSnapshotUploader][crate::snapshot_uploaders::LocalSnapshotUploader]).
+pub struct TieredSnapshotStore {
+    hot: Arc<dyn SnapshotUploader>,
+    cold: Arc<dyn SnapshotUploader>,
+    hot_directory: PathBuf,
+    signed_entity_storer: Arc<dyn SignedEntityStorer>,
+    age_threshold: u64,
+    demotion_lock: Mutex<()>,
+}
+
+impl TieredSnapshotStore {
+    /// TieredSnapshotStore factory
+    pub fn new(
+        hot: Arc<dyn SnapshotUploader>,
+        cold: Arc<dyn SnapshotUploader>,
+        hot_directory: &Path,
+        signed_entity_storer: Arc<dyn SignedEntityStorer>,
+        age_threshold: u64,
+    ) -> Self {
+        Self {
+            hot,
+            cold,
+            hot_directory: hot_directory.to_path_buf(),
+            signed_entity_storer,
+            age_threshold,
+            demotion_lock: Mutex::new(()),
+        }
+    }
+
+    /// Compute the epoch before which the snapshots must be demoted, given the current epoch
+    pub fn threshold_epoch(&self, current_epoch: Epoch) -> Epoch {
+        Epoch(current_epoch.saturating_sub(self.age_threshold))
+    }
+
+    /// Move the snapshots made before the given epoch from the hot tier to the cold tier,
+    /// the locations of their artifacts are replaced by their cold tier location.
+    ///
+    /// Only the snapshots whose archive is in the hot tier are read. If a demotion is already
+    /// running nothing is done.
+    ///
+    /// Return the number of demoted snapshots.
+    pub async fn demote_to_cold(&self, threshold_epoch: Epoch) -> StdResult<usize> {
+        let Ok(_demotion) = self.demotion_lock.try_lock() else {
+            debug!("A demotion to the cold tier is already running, skipping"; "threshold_epoch" => ?threshold_epoch);
+            return Ok(0);
+        };
+
+        let mut demoted_records = vec![];
+        let mut demoted_archives = vec![];
+        for (digest, archive_path) in self.list_hot_archives()? {
+            let Some(mut record) = self
+                .signed_entity_storer
+                .get_signed_entity(&digest)
+                .await
+                .with_context(|| {
+                    format!("Tiered snapshot store can not get snapshot '{digest}'")
+                })?
+            else {
+                continue;
+            };
+            let mut snapshot: Snapshot =
+                serde_json::from_str(&record.artifact).with_context(|| {
+                    format!("Tiered snapshot store can not parse snapshot '{digest}'")
+                })?;
+            if snapshot.beacon.epoch >= threshold_epoch {
+                continue;
+            }
+
+            debug!("Demoting snapshot to the cold tier"; "digest" => &snapshot.digest, "epoch" => ?snapshot.beacon.epoch);
+            let location = self
+                .cold
+                .upload_snapshot(&archive_path)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Tiered snapshot store can not upload snapshot '{digest}' to the cold tier"
+                    )
+                })?;
+            snapshot.locations = vec![location];
+            record.artifact = serde_json::to_string(&snapshot)?;
+
+            demoted_records.push(record);
+            demoted_archives.push(archive_path);
+        }
+
+        if demoted_records.is_empty() {
+            return Ok(0);
+        }
+
+        // The hot archives are only removed once the cold locations are saved so a snapshot
+        // stays downloadable if the update fails
+        self.signed_entity_storer
+            .update_signed_entities(demoted_records)
+            .await
+            .with_context(|| "Tiered snapshot store can not update the demoted snapshots")?;
+        for archive_path in &demoted_archives {
+            tokio::fs::remove_file(archive_path)
+                .await
+                .with_context(|| {
+                    format!("Tiered snapshot store can not remove hot archive '{archive_path:?}'")
+                })?;
+        }
+        info!("Demoted {} snapshot(s) to the cold tier", demoted_archives.len(); "threshold_epoch" => ?threshold_epoch);
+
+        Ok(demoted_archives.len())
+    }
+
+    /// Get the tier keeping the snapshot archive with the given file name.
+    pub fn find_tier(&self, archive_name: &str) -> SnapshotTier {
+        match self.hot_directory.join(archive_name).is_file() {
+            true => SnapshotTier::Hot,
+            false => SnapshotTier::Cold,
+        }
+    }
+
+    /// List the archives of the hot tier, indexed by the digest of their snapshot
+    fn list_hot_archives(&self) -> StdResult<BTreeMap<String, PathBuf>> {
+        let mut archives = BTreeMap::new();
+        if !self.hot_directory.exists() {
+            return Ok(archives);
+        }
+
+        for entry in std::fs::read_dir(&self.hot_directory).with_context(|| {
+            format!(
+                "Tiered snapshot store can not read hot directory '{:?}'",
+                self.hot_directory
+            )
+        })? {
+            let path = entry?.path();
+            if path.is_file() {
+                if let Ok(digest) = tools::extract_digest_from_path(&path) {
+                    archives.insert(digest, path);
+                }
+            }
+        }
+
+        Ok(archives)
+    }
+}
+
+#[async_trait]
+impl SnapshotUploader for TieredSnapshotStore {
+    async fn upload_snapshot(&self, snapshot_filepath: &Path) -> StdResult<SnapshotLocation> {
+        self.hot.upload_snapshot(snapshot_filepath).await
+    }
+
+    async fn resume_pending_uploads(&self) -> StdResult<()> {
+        self.hot.resume_pending_uploads().await?;
+        self.cold.resume_pending_uploads().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use flate2::{write::GzEncoder, Compression};
+    use std::fs::File;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    use mithril_common::test_utils::fake_data;
+
+    use crate::database::record::SignedEntityRecord;
+    use crate::database::repository::SignedEntityStore;
+    use crate::database::test_helper::{insert_signed_entities, main_db_connection};
+    use crate::snapshot_uploaders::{LocalSnapshotUploader, MockSnapshotUploader};
+
+    use super::*;
+
+    fn create_fake_archive(dir: &Path, digest: &str) -> PathBuf {
+        let file_path = dir.join(format!("test.{digest}.tar.gz"));
+        let mut encoder = GzEncoder::new(File::create(&file_path).unwrap(), Compression::fast());
+        writeln!(encoder, "Fake archive of snapshot {digest}").unwrap();
+        encoder.finish().unwrap();
+
+        file_path
+    }
+
+    #[tokio::test]
+    async fn demoted_snapshot_is_retrievable_from_the_cold_tier() {
+        let source_dir = tempdir().unwrap();
+        let hot_dir = tempdir().unwrap();
+        let cold_dir = tempdir().unwrap();
+        let connection = Arc::new(main_db_connection().unwrap());
+        let signed_entity_storer = Arc::new(SignedEntityStore::new(connection.clone()));
+        let store = TieredSnapshotStore::new(
+            Arc::new(LocalSnapshotUploader::new(
+                "http://hot.test/".to_string(),
+                hot_dir.path(),
+            )),
+            Arc::new(LocalSnapshotUploader::new(
+                "http://cold.test/".to_string(),
+                cold_dir.path(),
+            )),
+            hot_dir.path(),
+            signed_entity_storer.clone(),
+            2,
+        );
+
+        let mut records = vec![];
+        for (epoch, digest) in [(1, "0a1b"), (4, "2c3d")] {
+            let archive = create_fake_archive(source_dir.path(), digest);
+            let location = store.upload_snapshot(&archive).await.unwrap();
+            let mut snapshot = fake_data::snapshots(1).pop().unwrap();
+            snapshot.digest = digest.to_string();
+            snapshot.beacon.epoch = Epoch(epoch);
+            snapshot.locations = vec![location];
+            records.push(SignedEntityRecord::from_snapshot(
+                snapshot,
+                format!("certificate-{digest}"),
+                Utc::now(),
+            ));
+        }
+        insert_signed_entities(&connection, records).unwrap();
+
+        let demoted = store
+            .demote_to_cold(store.threshold_epoch(Epoch(4)))
+            .await
+            .unwrap();
+
+        assert_eq!(1, demoted);
+        assert!(!hot_dir.path().join("test.0a1b.tar.gz").exists());
+        assert!(cold_dir.path().join("test.0a1b.tar.gz").exists());
+        assert_eq!(SnapshotTier::Cold, store.find_tier("test.0a1b.tar.gz"));
+        assert_eq!(SnapshotTier::Hot, store.find_tier("test.2c3d.tar.gz"));
+        let demoted_snapshot: Snapshot = serde_json::from_str(
+            &signed_entity_storer
+                .get_signed_entity("0a1b")
+                .await
+                .unwrap()
+                .unwrap()
+                .artifact,
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["http://cold.test/aggregator/artifact/snapshot/0a1b/download".to_string()],
+            demoted_snapshot.locations
+        );
+    }
+
+    #[tokio::test]
+    async fn demote_to_cold_twice_does_not_upload_again() {
+        let source_dir = tempdir().unwrap();
+        let hot_dir = tempdir().unwrap();
+        let cold_dir = tempdir().unwrap();
+        let connection = Arc::new(main_db_connection().unwrap());
+        let store = TieredSnapshotStore::new(
+            Arc::new(LocalSnapshotUploader::new(
+                "http://hot.test/".to_string(),
+                hot_dir.path(),
+            )),
+            Arc::new(LocalSnapshotUploader::new(
+                "http://cold.test/".to_string(),
+                cold_dir.path(),
+            )),
+            hot_dir.path(),
+            Arc::new(SignedEntityStore::new(connection.clone())),
+            2,
+        );
+        let archive = create_fake_archive(source_dir.path(), "0a1b");
+        let mut snapshot = fake_data::snapshots(1).pop().unwrap();
+        snapshot.digest = "0a1b".to_string();
+        snapshot.beacon.epoch = Epoch(1);
+        snapshot.locations = vec![store.upload_snapshot(&archive).await.unwrap()];
+        insert_signed_entities(
+            &connection,
+            vec![SignedEntityRecord::from_snapshot(
+                snapshot,
+                "certificate".to_string(),
+                Utc::now(),
+            )],
+        )
+        .unwrap();
+
+        assert_eq!(1, store.demote_to_cold(Epoch(5)).await.unwrap());
+        assert_eq!(0, store.demote_to_cold(Epoch(5)).await.unwrap());
+    }
+    #[tokio::test]
+    async fn demote_to_cold_is_skipped_while_a_demotion_is_running() {
+        let hot_dir = tempdir().unwrap();
+        let connection = Arc::new(main_db_connection().unwrap());
+        let store = TieredSnapshotStore::new(
+            Arc::new(MockSnapshotUploader::new()),
+            Arc::new(MockSnapshotUploader::new()),
+            hot_dir.path(),
+            Arc::new(SignedEntityStore::new(connection)),
+            2,
+        );
+        create_fake_archive(hot_dir.path(), "0a1b");
+
+        let _running_demotion = store.demotion_lock.lock().await;
+
+        assert_eq!(0, store.demote_to_cold(Epoch(5)).await.unwrap());
+        assert!(hot_dir.path().join("test.0a1b.tar.gz").exists());
+    }
+}
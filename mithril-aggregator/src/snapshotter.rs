@@ -12,7 +12,7 @@ use thiserror::Error;
 use zstd::{Decoder, Encoder};
 
 use crate::dependency_injection::DependenciesBuilderError;
-use crate::ZstandardCompressionParameters;
+use crate::{GzipCompressionParameters, ZstandardCompressionParameters};
 
 /// Define the ability to create snapshots.
 pub trait Snapshotter: Sync + Send {
@@ -24,11 +24,17 @@ pub trait Snapshotter: Sync + Send {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SnapshotterCompressionAlgorithm {
     /// Gzip compression format
-    Gzip,
+    Gzip(GzipCompressionParameters),
     /// Zstandard compression format
     Zstandard(ZstandardCompressionParameters),
 }
 
+impl From<GzipCompressionParameters> for SnapshotterCompressionAlgorithm {
+    fn from(params: GzipCompressionParameters) -> Self {
+        Self::Gzip(params)
+    }
+}
+
 impl From<ZstandardCompressionParameters> for SnapshotterCompressionAlgorithm {
     fn from(params: ZstandardCompressionParameters) -> Self {
         Self::Zstandard(params)
@@ -86,15 +92,89 @@ pub enum SnapshotError {
     #[error("Upload file error: `{0}`")]
     UploadFileError(String),
 
+    /// Set when the magic bytes of a stored archive don't match a known archive format.
+    #[error("Unrecognised archive format, magic bytes: {0:02x?}")]
+    UnrecognisedFormat([u8; 4]),
+
+    /// Set when the ETag returned by the storage doesn't match the uploaded file content.
+    #[error("ETag mismatch, expected: '{expected}', actual: '{actual}'")]
+    ETagMismatch {
+        /// ETag computed from the local file
+        expected: String,
+        /// ETag returned by the storage
+        actual: String,
+    },
+
+    /// Set when storing a new snapshot would exceed the storage budget of the snapshots.
+    #[error("Snapshots storage budget exceeded, {current} bytes would be stored for a budget of {max} bytes")]
+    BudgetExceeded {
+        /// Size of the stored snapshots with the new snapshot, in bytes
+        current: u64,
+        /// Budget of the stored snapshots, in bytes
+        max: u64,
+    },
+
     /// General error.
     #[error("Snapshot General Error: `{0}`")]
     GeneralError(String),
 }
 
+/// Format of a snapshot archive, detected from its first bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// Gzip compressed archive
+    Gzip,
+    /// Zstandard compressed archive
+    Zstd,
+    /// Brotli compressed archive (using the brotli framing format)
+    Brotli,
+    /// Archive of an unknown format
+    Unknown,
+}
+
+impl ArchiveFormat {
+    const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+    const ZSTD_MAGIC_BYTES: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+    const BROTLI_MAGIC_BYTES: [u8; 4] = [0xce, 0xb2, 0xcf, 0x81];
+
+    /// Detect the format of an archive from its first four bytes.
+    pub fn from_magic_bytes(magic_bytes: &[u8; 4]) -> Self {
+        if magic_bytes.starts_with(&Self::GZIP_MAGIC_BYTES) {
+            Self::Gzip
+        } else if magic_bytes == &Self::ZSTD_MAGIC_BYTES {
+            Self::Zstd
+        } else if magic_bytes == &Self::BROTLI_MAGIC_BYTES {
+            Self::Brotli
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Read the first four bytes of the given archive and check that they match a known
+    /// archive format.
+    ///
+    /// An archive shorter than four bytes is read as if it was padded with zeros.
+    pub fn validate(archive_path: &Path) -> StdResult<Self> {
+        let mut magic_bytes = [0u8; 4];
+        let file = File::open(archive_path)
+            .with_context(|| format!("Can not open archive '{}'", archive_path.display()))?;
+        let mut header = Vec::with_capacity(magic_bytes.len());
+        file.take(magic_bytes.len() as u64)
+            .read_to_end(&mut header)
+            .with_context(|| format!("Can not read archive '{}'", archive_path.display()))?;
+        magic_bytes[..header.len()].copy_from_slice(&header);
+
+        match Self::from_magic_bytes(&magic_bytes) {
+            Self::Unknown => Err(SnapshotError::UnrecognisedFormat(magic_bytes).into()),
+            format => Ok(format),
+        }
+    }
+}
+
 impl Snapshotter for CompressedArchiveSnapshotter {
     fn snapshot(&self, archive_name: &str) -> StdResult<OngoingSnapshot> {
         let archive_path = self.ongoing_snapshot_directory.join(archive_name);
-        let filesize = self.create_and_verify_archive(&archive_path).map_err(|err| {
+        let filesize = self.create_and_verify_archive(&archive_path).inspect_err(|_| {
             if archive_path.exists() {
                 if let Err(remove_error) = std::fs::remove_file(&archive_path) {
                     warn!(
@@ -104,8 +184,6 @@ impl Snapshotter for CompressedArchiveSnapshotter {
                     );
                 }
             }
-
-            err
         }).with_context(|| format!("CompressedArchiveSnapshotter can not create and verify archive: '{}'", archive_path.display()))?;
 
         Ok(OngoingSnapshot {
@@ -165,8 +243,8 @@ impl CompressedArchiveSnapshotter {
         let tar_file = File::create(archive_path).map_err(SnapshotError::CreateArchiveError)?;
 
         match self.compression_algorithm {
-            SnapshotterCompressionAlgorithm::Gzip => {
-                let enc = GzEncoder::new(tar_file, Compression::default());
+            SnapshotterCompressionAlgorithm::Gzip(params) => {
+                let enc = GzEncoder::new(tar_file, Compression::new(params.level));
                 let mut tar = tar::Builder::new(enc);
 
                 tar.append_dir_all(".", &self.db_directory)
@@ -249,7 +327,7 @@ impl CompressedArchiveSnapshotter {
         snapshot_file_tar.seek(SeekFrom::Start(0))?;
 
         let mut snapshot_archive: Archive<Box<dyn Read>> = match self.compression_algorithm {
-            SnapshotterCompressionAlgorithm::Gzip => {
+            SnapshotterCompressionAlgorithm::Gzip(_) => {
                 let snapshot_file_tar = GzDecoder::new(snapshot_file_tar);
                 Archive::new(Box::new(snapshot_file_tar))
             }
@@ -399,6 +477,26 @@ mod tests {
         TempDir::create("snapshotter", dir_name)
     }
 
+    #[test]
+    fn archive_format_is_detected_from_the_magic_bytes() {
+        assert_eq!(
+            ArchiveFormat::Gzip,
+            ArchiveFormat::from_magic_bytes(&[0x1f, 0x8b, 0x08, 0x00])
+        );
+        assert_eq!(
+            ArchiveFormat::Zstd,
+            ArchiveFormat::from_magic_bytes(&[0x28, 0xb5, 0x2f, 0xfd])
+        );
+        assert_eq!(
+            ArchiveFormat::Brotli,
+            ArchiveFormat::from_magic_bytes(&[0xce, 0xb2, 0xcf, 0x81])
+        );
+        assert_eq!(
+            ArchiveFormat::Unknown,
+            ArchiveFormat::from_magic_bytes(b"text")
+        );
+    }
+
     #[test]
     fn test_dumb_snapshotter() {
         let snapshotter = DumbSnapshotter::new();
@@ -428,7 +526,7 @@ mod tests {
             CompressedArchiveSnapshotter::new(
                 db_directory,
                 pending_snapshot_directory.clone(),
-                SnapshotterCompressionAlgorithm::Gzip,
+                GzipCompressionParameters::default().into(),
             )
             .unwrap(),
         );
@@ -451,7 +549,7 @@ mod tests {
             CompressedArchiveSnapshotter::new(
                 db_directory,
                 pending_snapshot_directory.clone(),
-                SnapshotterCompressionAlgorithm::Gzip,
+                GzipCompressionParameters::default().into(),
             )
             .unwrap(),
         );
@@ -476,7 +574,7 @@ mod tests {
             CompressedArchiveSnapshotter::new(
                 db_directory,
                 pending_snapshot_directory.clone(),
-                SnapshotterCompressionAlgorithm::Gzip,
+                GzipCompressionParameters::default().into(),
             )
             .unwrap(),
         );
@@ -511,7 +609,7 @@ mod tests {
             CompressedArchiveSnapshotter::new(
                 db_directory,
                 pending_snapshot_directory.clone(),
-                SnapshotterCompressionAlgorithm::Gzip,
+                GzipCompressionParameters::default().into(),
             )
             .unwrap(),
         );
@@ -532,6 +630,43 @@ mod tests {
             .expect("Snapshotter::snapshot should not fail.");
     }
 
+    #[test]
+    fn should_create_a_valid_archive_with_gzip_snapshotter_at_each_compression_level() {
+        let test_dir = get_test_directory(
+            "should_create_a_valid_archive_with_gzip_snapshotter_at_each_compression_level",
+        );
+        let db_directory = test_dir.join("db");
+
+        DummyImmutablesDbBuilder::new(db_directory.as_os_str().to_str().unwrap())
+            .with_immutables(&[1, 2, 3])
+            .append_immutable_trio()
+            .build();
+
+        for level in [0, 9] {
+            let pending_snapshot_directory = test_dir.join(format!("pending_snapshot_{level}"));
+            let archive_path = pending_snapshot_directory.join("archive.tar.gz");
+            let snapshotter = CompressedArchiveSnapshotter::new(
+                db_directory.clone(),
+                pending_snapshot_directory,
+                GzipCompressionParameters { level }.into(),
+            )
+            .unwrap();
+
+            snapshotter
+                .create_archive(&archive_path)
+                .expect("create_archive should not fail");
+
+            let mut archive = Archive::new(GzDecoder::new(File::open(&archive_path).unwrap()));
+            let nb_files = archive
+                .entries()
+                .expect("Gzip archive should be readable with a GzDecoder")
+                .map(|entry| entry.unwrap())
+                .filter(|entry| entry.header().entry_type() == EntryType::Regular)
+                .count();
+            assert_eq!(12, nb_files, "level: {level}");
+        }
+    }
+
     #[test]
     fn should_create_a_valid_archive_with_zstandard_snapshotter() {
         let test_dir =
@@ -0,0 +1,142 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use mithril_common::entities::{Certificate, Epoch};
+use mithril_common::messages::CertificateMessage;
+use mithril_common::StdResult;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Cold storage of the old [certificates][Certificate], bundled by epoch.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait CertificateArchiveStore: Sync + Send {
+    /// Save the given certificates in the bundle of the given epoch.
+    ///
+    /// Certificates already in the bundle are kept, saving a certificate twice replaces it.
+    async fn save_bundle(&self, epoch: Epoch, certificates: Vec<Certificate>) -> StdResult<()>;
+
+    /// Load the certificates of the bundle of the given epoch, empty if there's no bundle.
+    async fn load_bundle(&self, epoch: Epoch) -> StdResult<Vec<Certificate>>;
+}
+
+/// [CertificateArchiveStore] keeping each bundle in a gzip compressed JSON file, the
+/// certificates are written with their [CertificateMessage] representation.
+pub struct FileCertificateArchiveStore {
+    directory: PathBuf,
+}
+
+impl FileCertificateArchiveStore {
+    /// FileCertificateArchiveStore factory, the bundles are written in the given directory.
+    pub fn new(directory: &Path) -> StdResult<Self> {
+        std::fs::create_dir_all(directory).with_context(|| {
+            format!("Could not create certificate archive directory '{directory:?}'")
+        })?;
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+        })
+    }
+
+    fn bundle_path(&self, epoch: Epoch) -> PathBuf {
+        self.directory.join(format!("certificates-{epoch}.json.gz"))
+    }
+
+    fn read_bundle(&self, epoch: Epoch) -> StdResult<Vec<CertificateMessage>> {
+        let path = self.bundle_path(epoch);
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+
+        let file = File::open(&path)
+            .with_context(|| format!("Could not open certificate bundle '{path:?}'"))?;
+        let certificates = serde_json::from_reader(GzDecoder::new(BufReader::new(file)))
+            .with_context(|| format!("Could not read certificate bundle '{path:?}'"))?;
+
+        Ok(certificates)
+    }
+}
+
+#[async_trait]
+impl CertificateArchiveStore for FileCertificateArchiveStore {
+    async fn save_bundle(&self, epoch: Epoch, certificates: Vec<Certificate>) -> StdResult<()> {
+        let mut bundle: BTreeMap<String, CertificateMessage> = self
+            .read_bundle(epoch)?
+            .into_iter()
+            .map(|c| (c.hash.clone(), c))
+            .collect();
+        for certificate in certificates {
+            let message = CertificateMessage::try_from(certificate)?;
+            bundle.insert(message.hash.clone(), message);
+        }
+
+        // The bundle is written next to the previous one then renamed, so an interruption
+        // can't leave a truncated bundle
+        let path = self.bundle_path(epoch);
+        let temp_path = path.with_extension("gz.tmp");
+        let file = File::create(&temp_path)
+            .with_context(|| format!("Could not create certificate bundle '{temp_path:?}'"))?;
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+        serde_json::to_writer(&mut encoder, &bundle.into_values().collect::<Vec<_>>())
+            .with_context(|| format!("Could not write certificate bundle of epoch '{epoch}'"))?;
+        encoder
+            .finish()
+            .with_context(|| format!("Could not compress certificate bundle of epoch '{epoch}'"))?;
+        std::fs::rename(&temp_path, &path)
+            .with_context(|| format!("Could not move certificate bundle to '{path:?}'"))?;
+
+        Ok(())
+    }
+
+    async fn load_bundle(&self, epoch: Epoch) -> StdResult<Vec<Certificate>> {
+        self.read_bundle(epoch)?
+            .into_iter()
+            .map(Certificate::try_from)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::fake_data;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn load_bundle_without_saved_bundle_is_empty() {
+        let dir = tempdir().unwrap();
+        let store = FileCertificateArchiveStore::new(dir.path()).unwrap();
+
+        assert!(store.load_bundle(Epoch(3)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn saved_bundles_are_merged_by_epoch() {
+        let dir = tempdir().unwrap();
+        let store = FileCertificateArchiveStore::new(dir.path()).unwrap();
+        let certificate_1 = fake_data::certificate("hash-1".to_string());
+        let certificate_2 = fake_data::certificate("hash-2".to_string());
+
+        store
+            .save_bundle(Epoch(3), vec![certificate_1.clone()])
+            .await
+            .unwrap();
+        store
+            .save_bundle(Epoch(3), vec![certificate_2.clone(), certificate_1.clone()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![certificate_1, certificate_2],
+            store.load_bundle(Epoch(3)).await.unwrap()
+        );
+        assert!(store.load_bundle(Epoch(4)).await.unwrap().is_empty());
+    }
+}
@@ -1,14 +1,26 @@
+mod certificate_archive_store;
 mod pending_certificate_store;
 mod protocol_parameters_store;
+mod snapshot_store;
+mod upload_session_store;
 mod verification_key_store;
 
-pub use pending_certificate_store::CertificatePendingStore;
+pub use certificate_archive_store::{CertificateArchiveStore, FileCertificateArchiveStore};
+pub use pending_certificate_store::{
+    CertificatePendingStore, EpochSession, HeartbeatClock, SagaHandle, SessionOutcome, StoreError,
+};
 pub use protocol_parameters_store::ProtocolParametersStorer;
+pub use snapshot_store::{ColdArchiveHandle, RestoreStatus, SnapshotStorageClass, SnapshotStore};
+pub use upload_session_store::{UploadSession, UploadSessionStore};
 pub use verification_key_store::{VerificationKeyStore, VerificationKeyStorer};
 
+#[cfg(test)]
+pub use certificate_archive_store::MockCertificateArchiveStore;
 #[cfg(test)]
 pub use protocol_parameters_store::FakeProtocolParametersStorer;
 #[cfg(test)]
+pub use snapshot_store::MockSnapshotStore;
+#[cfg(test)]
 pub use verification_key_store::test_suite as verification_key_store_test_suite;
 #[cfg(test)]
 pub(crate) use verification_key_store::test_verification_key_storer;
@@ -1,72 +1,593 @@
 use anyhow::{anyhow, Context};
+use chrono::{NaiveDateTime, Utc};
 use mithril_common::StdResult;
-use tokio::sync::RwLock;
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use thiserror::Error;
+use tokio::sync::{watch, RwLock};
 
-use mithril_common::entities::CertificatePending;
+use mithril_common::entities::{CertificatePending, Epoch};
+use mithril_persistence::sqlite::{ConnectionExtensions, SqliteConnection};
 use mithril_persistence::store::adapter::StoreAdapter;
+use slog_scope::warn;
+use sqlite::Value;
+
+use crate::database::query::{
+    DeleteCertificatePendingSessionQuery, GetCertificatePendingSessionQuery,
+    GetCertificateSagaQuery, InsertCertificateSagaQuery,
+    InsertOrReplaceCertificatePendingSessionQuery, UpdateCertificateSagaStatusQuery,
+};
+use crate::database::record::{CertificateSagaRecord, CertificateSagaStatus};
+
+pub use crate::database::record::SessionOutcome;
 
 type Adapter = Box<dyn StoreAdapter<Key = String, Record = CertificatePending>>;
 
+/// Function returning the current time, used to timestamp the heartbeats.
+pub type HeartbeatClock = Arc<dyn Fn() -> NaiveDateTime + Send + Sync>;
+
 const KEY: &str = "certificate_pending";
 
+/// Number of epochs, before the last closed session, for which the session outcomes are kept.
+///
+/// The sessions of the older epochs are still considered closed once their outcome is pruned.
+const SESSION_OUTCOMES_RETENTION_EPOCHS: u64 = 5;
+
 /// Store for [CertificatePending].
 pub struct CertificatePendingStore {
     adapter: RwLock<Adapter>,
+    heartbeats: RwLock<BTreeMap<Epoch, NaiveDateTime>>,
+    clock: HeartbeatClock,
+    watch_sender: Arc<watch::Sender<Option<CertificatePending>>>,
+    saga_connection: Option<Arc<SqliteConnection>>,
+    session_outcomes: RwLock<BTreeMap<Epoch, SessionOutcome>>,
 }
 
-impl CertificatePendingStore {
-    /// Create a new instance.
-    pub fn new(adapter: Adapter) -> Self {
-        Self {
-            adapter: RwLock::new(adapter),
+/// [CertificatePendingStore] related errors.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum StoreError {
+    /// Raised when an [EpochSession] is used after being closed.
+    #[error("the certificate pending session of epoch '{0}' is closed")]
+    SessionClosed(Epoch),
+}
+
+/// Handle on the pending certificate of a single epoch, created with
+/// [CertificatePendingStore::acquire_epoch_session].
+///
+/// The pending certificates of different epochs don't overwrite each other, which matters when
+/// two epochs are active at the same time around an epoch transition.
+pub struct EpochSession<'a> {
+    store: &'a CertificatePendingStore,
+    epoch: Epoch,
+}
+
+impl EpochSession<'_> {
+    /// Epoch of the session
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    fn key(&self) -> String {
+        format!("{KEY}_epoch_{}", self.epoch)
+    }
+
+    async fn ensure_is_open(&self) -> StdResult<()> {
+        if self.store.is_session_closed(self.epoch).await? {
+            return Err(StoreError::SessionClosed(self.epoch).into());
         }
+
+        Ok(())
     }
 
-    /// Fetch the current [CertificatePending] if any.
+    /// Fetch the [CertificatePending] of the session epoch if any.
     pub async fn get(&self) -> StdResult<Option<CertificatePending>> {
-        self.adapter
+        self.ensure_is_open().await?;
+
+        self.store
+            .adapter
             .read()
             .await
-            .get_record(&KEY.to_string())
+            .get_record(&self.key())
             .await
-            .with_context(|| "Certificate pending store: could not GET store.".to_string())
+            .with_context(|| {
+                format!(
+                    "Certificate pending store: could not GET pending certificate of epoch '{}'.",
+                    self.epoch
+                )
+            })
     }
 
-    /// Save the given [CertificatePending].
+    /// Save the given [CertificatePending], which must be of the session epoch.
     pub async fn save(&self, certificate: CertificatePending) -> StdResult<()> {
-        self
+        self.ensure_is_open().await?;
+        if certificate.epoch != self.epoch {
+            return Err(anyhow!(
+                "Certificate pending store: can not save a pending certificate of epoch '{}' in the session of epoch '{}'.",
+                certificate.epoch,
+                self.epoch
+            ));
+        }
+
+        self.store
             .adapter
             .write()
             .await
-            .store_record(&KEY.to_string(), &certificate)
+            .store_record(&self.key(), &certificate)
             .await
-            .with_context(|| format!("Certificate pending store: error while saving pending certificate for epoch '{}'.", certificate.epoch))
+            .with_context(|| {
+                format!(
+                    "Certificate pending store: error while saving pending certificate for epoch '{}'.",
+                    self.epoch
+                )
+            })
     }
 
-    /// Remove and return the current [CertificatePending] if any.
+    /// Remove and return the [CertificatePending] of the session epoch if any.
+    ///
+    /// The session stays open, a pending certificate of another signed entity of the epoch
+    /// can be saved afterward.
     pub async fn remove(&self) -> StdResult<Option<CertificatePending>> {
-        self.adapter
+        self.ensure_is_open().await?;
+
+        self.store
+            .adapter
             .write()
             .await
-            .remove(&KEY.to_string())
+            .remove(&self.key())
             .await
-            .map_err(|e| anyhow!(e))
             .with_context(|| {
                 format!(
-                    "Could not delete certificate pending (key = '{}') from store.",
-                    &KEY
+                    "Certificate pending store: could not remove pending certificate of epoch '{}'.",
+                    self.epoch
                 )
             })
     }
+
+    /// Close the session with the given outcome, its pending certificate is dropped.
+    ///
+    /// The session of the epoch can't be acquired again afterward.
+    pub async fn close(&self, outcome: SessionOutcome) -> StdResult<()> {
+        self.remove().await.with_context(|| {
+            format!(
+                "Certificate pending store: could not close the session of epoch '{}'.",
+                self.epoch
+            )
+        })?;
+        self.store.heartbeats.write().await.remove(&self.epoch);
+
+        self.store.record_session_outcome(self.epoch, outcome).await
+    }
+}
+
+/// Handle of an ongoing certificate saga, created with [CertificatePendingStore::begin_saga].
+///
+/// The saga is marked as `Aborted` when the handle is dropped unless it was
+/// [committed][SagaHandle::commit].
+pub struct SagaHandle {
+    saga_id: u64,
+    epoch: Epoch,
+    connection: Arc<SqliteConnection>,
+    is_finished: bool,
+}
+
+impl SagaHandle {
+    /// Id of the saga
+    pub fn saga_id(&self) -> u64 {
+        self.saga_id
+    }
+
+    /// Epoch of the certificate created by the saga
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// Mark the saga as `Committed`, to call once the certificate is stored.
+    pub fn commit(mut self) -> StdResult<()> {
+        self.finish(CertificateSagaStatus::Committed)
+    }
+
+    fn finish(&mut self, status: CertificateSagaStatus) -> StdResult<()> {
+        self.is_finished = true;
+        self.connection
+            .fetch_first(UpdateCertificateSagaStatusQuery::one(
+                self.saga_id,
+                status,
+                Utc::now(),
+            )?)
+            .with_context(|| {
+                format!(
+                    "Certificate pending store: could not mark saga '{}' as {}.",
+                    self.saga_id,
+                    status.as_str()
+                )
+            })?;
+
+        Ok(())
+    }
+}
+
+impl Drop for SagaHandle {
+    fn drop(&mut self) {
+        if !self.is_finished {
+            if let Err(error) = self.finish(CertificateSagaStatus::Aborted) {
+                warn!("Certificate saga could not be aborted"; "saga_id" => self.saga_id, "error" => ?error);
+            }
+        }
+    }
+}
+
+impl CertificatePendingStore {
+    /// Create a new instance.
+    pub fn new(adapter: Adapter) -> Self {
+        Self {
+            adapter: RwLock::new(adapter),
+            heartbeats: RwLock::new(BTreeMap::new()),
+            clock: Arc::new(|| Utc::now().naive_utc()),
+            watch_sender: Arc::new(watch::channel(None).0),
+            saga_connection: None,
+            session_outcomes: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Record the [certificate sagas][Self::begin_saga] in the `certificate_saga` table, and the
+    /// outcomes of the [epoch sessions][Self::acquire_epoch_session] in the
+    /// `certificate_pending_session` table, of the given database.
+    pub fn with_saga_connection(mut self, connection: Arc<SqliteConnection>) -> Self {
+        self.saga_connection = Some(connection);
+        self
+    }
+
+    /// Begin the saga of the creation of the certificate of the given [Epoch], recorded with
+    /// the `InProgress` status.
+    pub async fn begin_saga(&self, epoch: Epoch) -> StdResult<SagaHandle> {
+        let connection = self.saga_connection.clone().ok_or_else(|| {
+            anyhow!("Certificate pending store: no database configured to record the sagas.")
+        })?;
+        let record = connection
+            .fetch_first(InsertCertificateSagaQuery::in_progress(epoch, Utc::now())?)
+            .with_context(|| {
+                format!("Certificate pending store: could not begin saga for epoch '{epoch}'.")
+            })?
+            .ok_or_else(|| anyhow!("No record returned when beginning saga for epoch '{epoch}'"))?;
+
+        Ok(SagaHandle {
+            saga_id: record.saga_id,
+            epoch,
+            connection,
+            is_finished: false,
+        })
+    }
+
+    /// Recover the sagas left `InProgress` by an interrupted aggregator.
+    ///
+    /// A saga is marked `Committed` if a certificate of its epoch was sealed since it began,
+    /// as the aggregator may have stopped between the storage of the certificate and the
+    /// commit of the saga, otherwise it's marked `Aborted`.
+    ///
+    /// Returns the recovered sagas.
+    pub async fn recover_in_progress_sagas(&self) -> StdResult<Vec<CertificateSagaRecord>> {
+        let Some(connection) = &self.saga_connection else {
+            return Ok(vec![]);
+        };
+
+        let sagas: Vec<CertificateSagaRecord> = connection
+            .fetch_collect(GetCertificateSagaQuery::by_status(
+                CertificateSagaStatus::InProgress,
+            ))
+            .with_context(|| "Certificate pending store: could not list the in progress sagas.")?;
+        let mut recovered_sagas = Vec::with_capacity(sagas.len());
+        for saga in sagas {
+            let is_certificate_stored: i64 = connection.query_single_cell(
+                "select exists(select 1 from certificate where epoch = ?1 and sealed_at >= ?2)",
+                &[
+                    Value::Integer(saga.epoch.try_into()?),
+                    Value::String(saga.created_at.to_rfc3339()),
+                ],
+            )?;
+            let status = if is_certificate_stored == 1 {
+                CertificateSagaStatus::Committed
+            } else {
+                CertificateSagaStatus::Aborted
+            };
+            let recovered_saga = connection
+                .fetch_first(UpdateCertificateSagaStatusQuery::one(
+                    saga.saga_id,
+                    status,
+                    Utc::now(),
+                )?)
+                .with_context(|| {
+                    format!(
+                        "Certificate pending store: could not recover saga '{}'.",
+                        saga.saga_id
+                    )
+                })?
+                .ok_or_else(|| {
+                    anyhow!("No record returned when recovering saga '{}'", saga.saga_id)
+                })?;
+            recovered_sagas.push(recovered_saga);
+        }
+
+        Ok(recovered_sagas)
+    }
+
+    /// Acquire a session isolating the pending certificate of the given [Epoch].
+    ///
+    /// Fails with [StoreError::SessionClosed] if the session of the epoch was closed.
+    pub async fn acquire_epoch_session(&self, epoch: Epoch) -> StdResult<EpochSession<'_>> {
+        let session = EpochSession { store: self, epoch };
+        session.ensure_is_open().await?;
+
+        Ok(session)
+    }
+
+    /// Outcome of the session of the given [Epoch], `None` if it was not closed or if its
+    /// outcome was pruned.
+    pub async fn get_session_outcome(&self, epoch: Epoch) -> StdResult<Option<SessionOutcome>> {
+        let Some(connection) = &self.saga_connection else {
+            return Ok(self.session_outcomes.read().await.get(&epoch).copied());
+        };
+
+        let record = connection
+            .fetch_first(GetCertificatePendingSessionQuery::by_epoch(epoch)?)
+            .with_context(|| {
+                format!("Certificate pending store: could not get the session outcome of epoch '{epoch}'.")
+            })?;
+
+        Ok(record.map(|record| record.outcome))
+    }
+
+    /// Close the sessions of the epochs before the given [Epoch]: the sessions holding a
+    /// pending certificate and the session of the previous epoch.
+    ///
+    /// A session is `Aborted` if its pending certificate was not dropped, since its certificate
+    /// was not created, and `Committed` otherwise.
+    ///
+    /// Returns the outcomes of the closed sessions.
+    pub async fn close_sessions_before(
+        &self,
+        epoch: Epoch,
+    ) -> StdResult<Vec<(Epoch, SessionOutcome)>> {
+        let pending_epochs: Vec<Epoch> = self
+            .list_pending_certificates()
+            .await?
+            .into_iter()
+            .map(|certificate| certificate.epoch)
+            .filter(|pending_epoch| *pending_epoch < epoch)
+            .collect();
+        let mut epochs = pending_epochs.clone();
+        if let Ok(previous_epoch) = epoch.previous() {
+            epochs.push(previous_epoch);
+        }
+        epochs.sort();
+        epochs.dedup();
+
+        let mut outcomes = vec![];
+        for epoch in epochs {
+            if self.is_session_closed(epoch).await? {
+                continue;
+            }
+            let outcome = if pending_epochs.contains(&epoch) {
+                SessionOutcome::Aborted
+            } else {
+                SessionOutcome::Committed
+            };
+            self.acquire_epoch_session(epoch)
+                .await?
+                .close(outcome)
+                .await?;
+            outcomes.push((epoch, outcome));
+        }
+
+        Ok(outcomes)
+    }
+
+    /// A session is closed if its outcome is recorded or if it's older than the recorded
+    /// outcomes, which were pruned.
+    async fn is_session_closed(&self, epoch: Epoch) -> StdResult<bool> {
+        let Some(connection) = &self.saga_connection else {
+            let session_outcomes = self.session_outcomes.read().await;
+            return Ok(session_outcomes.contains_key(&epoch)
+                || session_outcomes
+                    .first_key_value()
+                    .is_some_and(|(oldest_epoch, _)| epoch < *oldest_epoch));
+        };
+
+        let is_closed: i64 = connection
+            .query_single_cell(
+                "select exists(select 1 from certificate_pending_session where epoch = ?1) \
+                or coalesce(?1 < (select min(epoch) from certificate_pending_session), 0)",
+                &[Value::Integer(epoch.try_into()?)],
+            )
+            .with_context(|| {
+                format!("Certificate pending store: could not check if the session of epoch '{epoch}' is closed.")
+            })?;
+
+        Ok(is_closed == 1)
+    }
+
+    /// Record the outcome of the session of the given [Epoch] and prune the outcomes older than
+    /// [SESSION_OUTCOMES_RETENTION_EPOCHS].
+    async fn record_session_outcome(&self, epoch: Epoch, outcome: SessionOutcome) -> StdResult<()> {
+        let retained_epoch = Epoch(epoch.saturating_sub(SESSION_OUTCOMES_RETENTION_EPOCHS));
+        let Some(connection) = &self.saga_connection else {
+            let mut session_outcomes = self.session_outcomes.write().await;
+            session_outcomes.insert(epoch, outcome);
+            session_outcomes.retain(|session_epoch, _| *session_epoch >= retained_epoch);
+            return Ok(());
+        };
+
+        connection
+            .fetch_first(InsertOrReplaceCertificatePendingSessionQuery::one(
+                epoch,
+                outcome,
+                Utc::now(),
+            )?)
+            .with_context(|| {
+                format!("Certificate pending store: could not record the session outcome of epoch '{epoch}'.")
+            })?;
+        connection
+            .fetch_collect::<_, Vec<_>>(DeleteCertificatePendingSessionQuery::below_epoch(
+                retained_epoch,
+            )?)
+            .with_context(|| {
+                "Certificate pending store: could not prune the session outcomes.".to_string()
+            })?;
+
+        Ok(())
+    }
+
+    async fn list_pending_certificates(&self) -> StdResult<Vec<CertificatePending>> {
+        let adapter = self.adapter.read().await;
+        let certificates = adapter
+            .get_iter()
+            .await
+            .with_context(|| "Certificate pending store: could not list the pending certificates.")?
+            .collect();
+
+        Ok(certificates)
+    }
+
+    /// Fetch the saga with the given id if any.
+    pub async fn get_saga(&self, saga_id: u64) -> StdResult<Option<CertificateSagaRecord>> {
+        let Some(connection) = &self.saga_connection else {
+            return Ok(None);
+        };
+
+        connection
+            .fetch_first(GetCertificateSagaQuery::by_saga_id(saga_id)?)
+            .with_context(|| format!("Certificate pending store: could not get saga '{saga_id}'."))
+    }
+
+    /// Replace the clock used to timestamp the heartbeats.
+    pub fn with_clock(mut self, clock: HeartbeatClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Record that the signers are still alive for the given [Epoch].
+    pub async fn update_heartbeat(&self, epoch: Epoch) -> StdResult<()> {
+        let last_seen_at = (self.clock)();
+        self.heartbeats.write().await.insert(epoch, last_seen_at);
+
+        Ok(())
+    }
+
+    /// List the epochs for which no heartbeat was received for more than the given duration.
+    pub async fn list_stale(&self, older_than: Duration) -> StdResult<Vec<Epoch>> {
+        let older_than = chrono::Duration::from_std(older_than).with_context(|| {
+            format!("Certificate pending store: invalid stale duration '{older_than:?}'.")
+        })?;
+        let now = (self.clock)();
+
+        Ok(self
+            .heartbeats
+            .read()
+            .await
+            .iter()
+            .filter(|(_, last_seen_at)| now - **last_seen_at >= older_than)
+            .map(|(epoch, _)| *epoch)
+            .collect())
+    }
+
+    /// Subscribe to the changes of the current [CertificatePending].
+    ///
+    /// The receiver is notified with the saved pending certificate on every
+    /// [save][Self::save] and with `None` on every [remove][Self::remove].
+    /// It initially holds the last pending certificate saved or removed through this store,
+    /// not the one already stored by the adapter before the store was created.
+    pub fn watch(&self) -> watch::Receiver<Option<CertificatePending>> {
+        self.watch_sender.subscribe()
+    }
+
+    /// Fetch the current [CertificatePending], the one of the latest epoch, if any.
+    pub async fn get(&self) -> StdResult<Option<CertificatePending>> {
+        Ok(self
+            .list_pending_certificates()
+            .await
+            .with_context(|| "Certificate pending store: could not GET store.")?
+            .into_iter()
+            .max_by_key(|certificate| certificate.epoch))
+    }
+
+    /// Save the given [CertificatePending] in the [session][Self::acquire_epoch_session] of
+    /// its epoch.
+    ///
+    /// Saving a pending certificate counts as a heartbeat for its epoch.
+    pub async fn save(&self, certificate: CertificatePending) -> StdResult<()> {
+        let epoch = certificate.epoch;
+        self.acquire_epoch_session(epoch)
+            .await?
+            .save(certificate.clone())
+            .await?;
+        self.watch_sender.send_replace(Some(certificate));
+
+        self.update_heartbeat(epoch).await
+    }
+
+    /// Remove and return the current [CertificatePending] if any.
+    pub async fn remove(&self) -> StdResult<Option<CertificatePending>> {
+        let Some(current) = self.get().await? else {
+            self.watch_sender.send_replace(None);
+            return Ok(None);
+        };
+
+        let certificate = self
+            .acquire_epoch_session(current.epoch)
+            .await?
+            .remove()
+            .await?;
+        self.heartbeats.write().await.remove(&current.epoch);
+        self.watch_sender.send_replace(None);
+
+        Ok(certificate)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    use mithril_common::entities::{Epoch, SignedEntityType};
+    use chrono::DateTime;
+    use mithril_common::entities::SignedEntityType;
     use mithril_common::test_utils::fake_data;
-    use mithril_persistence::store::adapter::DumbStoreAdapter;
+    use mithril_persistence::store::adapter::{DumbStoreAdapter, MemoryAdapter};
+    use std::sync::Mutex;
+
+    use crate::database::record::CertificateRecord;
+    use crate::database::test_helper::{insert_certificate_records, main_db_connection};
+
+    /// A clock that only moves forward when told to.
+    struct FakeClock(Arc<Mutex<NaiveDateTime>>);
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self(Arc::new(Mutex::new(
+                DateTime::from_timestamp(1_700_000_000, 0)
+                    .unwrap()
+                    .naive_utc(),
+            )))
+        }
+
+        fn advance(&self, duration: Duration) {
+            let mut now = self.0.lock().unwrap();
+            *now += chrono::Duration::from_std(duration).unwrap();
+        }
+
+        fn as_heartbeat_clock(&self) -> HeartbeatClock {
+            let now = self.0.clone();
+            Arc::new(move || *now.lock().unwrap())
+        }
+    }
+
+    fn dummy_certificate_pending(epoch: Epoch) -> CertificatePending {
+        CertificatePending::new(
+            epoch,
+            SignedEntityType::dummy(),
+            fake_data::protocol_parameters(),
+            fake_data::protocol_parameters(),
+            fake_data::signers(1),
+            fake_data::signers(2),
+        )
+    }
 
     async fn get_certificate_pending_store(is_populated: bool) -> CertificatePendingStore {
         let mut adapter: DumbStoreAdapter<String, CertificatePending> = DumbStoreAdapter::new();
@@ -81,13 +602,19 @@ mod test {
                 fake_data::signers(5),
             );
             adapter
-                .store_record(&KEY.to_string(), &certificate_pending)
+                .store_record(&format!("{KEY}_epoch_0"), &certificate_pending)
                 .await
                 .unwrap();
         }
         CertificatePendingStore::new(Box::new(adapter))
     }
 
+    fn memory_certificate_pending_store() -> CertificatePendingStore {
+        CertificatePendingStore::new(Box::new(
+            MemoryAdapter::<String, CertificatePending>::new(None).unwrap(),
+        ))
+    }
+
     #[tokio::test]
     async fn get_certificate_pending_with_existing_certificate() {
         let store = get_certificate_pending_store(true).await;
@@ -138,4 +665,408 @@ mod test {
         assert_eq!(epoch, certificate_pending.epoch);
         assert!(store.get().await.unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn watch_receiver_observes_saved_and_removed_certificate_pending() {
+        let store = get_certificate_pending_store(false).await;
+        let mut receiver = store.watch();
+        assert_eq!(None, *receiver.borrow_and_update());
+
+        let certificate_pending = dummy_certificate_pending(Epoch(3));
+        store.save(certificate_pending.clone()).await.unwrap();
+        receiver.changed().await.unwrap();
+        assert_eq!(Some(certificate_pending), *receiver.borrow_and_update());
+
+        store.remove().await.unwrap();
+        receiver.changed().await.unwrap();
+        assert_eq!(None, *receiver.borrow_and_update());
+    }
+
+    #[tokio::test]
+    async fn pending_certificate_without_heartbeat_become_stale() {
+        let clock = FakeClock::new();
+        let store = get_certificate_pending_store(false)
+            .await
+            .with_clock(clock.as_heartbeat_clock());
+        let stale_threshold = Duration::from_secs(3600);
+        store
+            .save(dummy_certificate_pending(Epoch(3)))
+            .await
+            .unwrap();
+
+        clock.advance(stale_threshold / 2);
+        assert_eq!(
+            Vec::<Epoch>::new(),
+            store.list_stale(stale_threshold).await.unwrap()
+        );
+
+        clock.advance(stale_threshold / 2);
+        assert_eq!(
+            vec![Epoch(3)],
+            store.list_stale(stale_threshold).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn heartbeat_keeps_pending_certificate_alive() {
+        let clock = FakeClock::new();
+        let store = get_certificate_pending_store(false)
+            .await
+            .with_clock(clock.as_heartbeat_clock());
+        let stale_threshold = Duration::from_secs(3600);
+        store
+            .save(dummy_certificate_pending(Epoch(3)))
+            .await
+            .unwrap();
+
+        clock.advance(stale_threshold / 2);
+        store.update_heartbeat(Epoch(3)).await.unwrap();
+        clock.advance(stale_threshold / 2);
+
+        assert_eq!(
+            Vec::<Epoch>::new(),
+            store.list_stale(stale_threshold).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn removed_pending_certificate_is_not_stale() {
+        let clock = FakeClock::new();
+        let store = get_certificate_pending_store(false)
+            .await
+            .with_clock(clock.as_heartbeat_clock());
+        let stale_threshold = Duration::from_secs(3600);
+        store
+            .save(dummy_certificate_pending(Epoch(3)))
+            .await
+            .unwrap();
+
+        store.remove().await.unwrap();
+        clock.advance(stale_threshold);
+
+        assert_eq!(
+            Vec::<Epoch>::new(),
+            store.list_stale(stale_threshold).await.unwrap()
+        );
+    }
+
+    fn saga_store() -> CertificatePendingStore {
+        CertificatePendingStore::new(Box::new(
+            DumbStoreAdapter::<String, CertificatePending>::new(),
+        ))
+        .with_saga_connection(Arc::new(main_db_connection().unwrap()))
+    }
+
+    #[tokio::test]
+    async fn begin_saga_records_an_in_progress_saga() {
+        let store = saga_store();
+
+        let saga = store.begin_saga(Epoch(5)).await.unwrap();
+
+        let record = store.get_saga(saga.saga_id()).await.unwrap().unwrap();
+        assert_eq!(Epoch(5), record.epoch);
+        assert_eq!(CertificateSagaStatus::InProgress, record.status);
+    }
+
+    #[tokio::test]
+    async fn committed_saga_is_marked_committed() {
+        let store = saga_store();
+        let saga = store.begin_saga(Epoch(5)).await.unwrap();
+        let saga_id = saga.saga_id();
+
+        saga.commit().unwrap();
+
+        let record = store.get_saga(saga_id).await.unwrap().unwrap();
+        assert_eq!(CertificateSagaStatus::Committed, record.status);
+    }
+
+    #[tokio::test]
+    async fn dropping_a_saga_without_commit_marks_it_aborted() {
+        let store = saga_store();
+        let saga = store.begin_saga(Epoch(5)).await.unwrap();
+        let saga_id = saga.saga_id();
+
+        drop(saga);
+
+        let record = store.get_saga(saga_id).await.unwrap().unwrap();
+        assert_eq!(CertificateSagaStatus::Aborted, record.status);
+    }
+
+    #[tokio::test]
+    async fn recover_in_progress_sagas_commits_the_sagas_which_certificate_is_stored() {
+        let connection = Arc::new(main_db_connection().unwrap());
+        let store = CertificatePendingStore::new(Box::new(DumbStoreAdapter::<
+            String,
+            CertificatePending,
+        >::new()))
+        .with_saga_connection(connection.clone());
+        let committed_saga = store.begin_saga(Epoch(5)).await.unwrap();
+        let aborted_saga = store.begin_saga(Epoch(6)).await.unwrap();
+        let finished_saga = store.begin_saga(Epoch(7)).await.unwrap();
+        let (committed_saga_id, aborted_saga_id) =
+            (committed_saga.saga_id(), aborted_saga.saga_id());
+        finished_saga.commit().unwrap();
+        // Simulate a crash: the handles are not dropped so the sagas stay in progress
+        std::mem::forget(committed_saga);
+        std::mem::forget(aborted_saga);
+        insert_certificate_records(
+            &connection,
+            vec![CertificateRecord {
+                sealed_at: Utc::now() + chrono::Duration::minutes(1),
+                ..CertificateRecord::dummy_genesis("certificate-5", Epoch(5), 1)
+            }],
+        );
+
+        let recovered_sagas = store.recover_in_progress_sagas().await.unwrap();
+
+        assert_eq!(
+            vec![
+                (committed_saga_id, CertificateSagaStatus::Committed),
+                (aborted_saga_id, CertificateSagaStatus::Aborted),
+            ],
+            recovered_sagas
+                .iter()
+                .map(|saga| (saga.saga_id, saga.status))
+                .collect::<Vec<_>>()
+        );
+        assert!(store.recover_in_progress_sagas().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn begin_saga_fails_without_saga_connection() {
+        let store = get_certificate_pending_store(false).await;
+
+        store
+            .begin_saga(Epoch(5))
+            .await
+            .err()
+            .expect("begin_saga should fail without database");
+    }
+
+    #[tokio::test]
+    async fn epoch_sessions_reads_are_scoped_to_their_epoch() {
+        let store = memory_certificate_pending_store();
+        let session_epoch_4 = store.acquire_epoch_session(Epoch(4)).await.unwrap();
+        let session_epoch_5 = store.acquire_epoch_session(Epoch(5)).await.unwrap();
+
+        session_epoch_4
+            .save(dummy_certificate_pending(Epoch(4)))
+            .await
+            .unwrap();
+        session_epoch_5
+            .save(dummy_certificate_pending(Epoch(5)))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some(Epoch(4)),
+            session_epoch_4.get().await.unwrap().map(|c| c.epoch)
+        );
+        assert_eq!(
+            Some(Epoch(5)),
+            session_epoch_5.get().await.unwrap().map(|c| c.epoch)
+        );
+        assert_eq!(Some(Epoch(5)), store.get().await.unwrap().map(|c| c.epoch));
+    }
+
+    #[tokio::test]
+    async fn saving_a_pending_certificate_does_not_overwrite_the_one_of_another_epoch() {
+        let store = memory_certificate_pending_store();
+
+        store
+            .save(dummy_certificate_pending(Epoch(4)))
+            .await
+            .unwrap();
+        store
+            .save(dummy_certificate_pending(Epoch(5)))
+            .await
+            .unwrap();
+
+        let session_epoch_4 = store.acquire_epoch_session(Epoch(4)).await.unwrap();
+        assert_eq!(
+            Some(Epoch(4)),
+            session_epoch_4.get().await.unwrap().map(|c| c.epoch)
+        );
+
+        assert_eq!(
+            Some(Epoch(5)),
+            store.remove().await.unwrap().map(|c| c.epoch)
+        );
+        assert_eq!(Some(Epoch(4)), store.get().await.unwrap().map(|c| c.epoch));
+    }
+
+    #[tokio::test]
+    async fn close_sessions_before_aborts_the_sessions_with_a_pending_certificate() {
+        let store = memory_certificate_pending_store();
+        store
+            .save(dummy_certificate_pending(Epoch(3)))
+            .await
+            .unwrap();
+        store
+            .save(dummy_certificate_pending(Epoch(5)))
+            .await
+            .unwrap();
+        store.remove().await.unwrap();
+
+        let outcomes = store.close_sessions_before(Epoch(6)).await.unwrap();
+
+        assert_eq!(
+            vec![
+                (Epoch(3), SessionOutcome::Aborted),
+                (Epoch(5), SessionOutcome::Committed)
+            ],
+            outcomes
+        );
+        assert_eq!(None, store.get().await.unwrap());
+        assert!(store
+            .close_sessions_before(Epoch(6))
+            .await
+            .unwrap()
+            .is_empty());
+        store
+            .save(dummy_certificate_pending(Epoch(5)))
+            .await
+            .expect_err("saving a pending certificate of a closed epoch should fail");
+        store
+            .save(dummy_certificate_pending(Epoch(6)))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn session_outcomes_older_than_the_retention_are_pruned_but_stay_closed() {
+        let store = memory_certificate_pending_store();
+        let last_epoch = Epoch(SESSION_OUTCOMES_RETENTION_EPOCHS + 3);
+        for epoch in 1..=*last_epoch {
+            store.close_sessions_before(Epoch(epoch + 1)).await.unwrap();
+        }
+
+        assert_eq!(
+            1 + SESSION_OUTCOMES_RETENTION_EPOCHS as usize,
+            store.session_outcomes.read().await.len()
+        );
+        assert_eq!(None, store.get_session_outcome(Epoch(1)).await.unwrap());
+        assert_eq!(
+            Some(SessionOutcome::Committed),
+            store.get_session_outcome(last_epoch).await.unwrap()
+        );
+        let error = store
+            .acquire_epoch_session(Epoch(1))
+            .await
+            .err()
+            .expect("acquiring a pruned session should fail");
+        assert_eq!(
+            Some(&StoreError::SessionClosed(Epoch(1))),
+            error.downcast_ref::<StoreError>()
+        );
+    }
+
+    #[tokio::test]
+    async fn session_outcomes_are_persisted_and_pruned_in_the_database() {
+        let connection = Arc::new(main_db_connection().unwrap());
+        let store = memory_certificate_pending_store().with_saga_connection(connection.clone());
+        let last_epoch = Epoch(SESSION_OUTCOMES_RETENTION_EPOCHS + 3);
+        store
+            .save(dummy_certificate_pending(last_epoch))
+            .await
+            .unwrap();
+        for epoch in 1..=*last_epoch {
+            store.close_sessions_before(Epoch(epoch + 1)).await.unwrap();
+        }
+
+        let restarted_store = memory_certificate_pending_store().with_saga_connection(connection);
+        assert!(restarted_store.session_outcomes.read().await.is_empty());
+        assert_eq!(
+            Some(SessionOutcome::Aborted),
+            restarted_store
+                .get_session_outcome(last_epoch)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            restarted_store.get_session_outcome(Epoch(1)).await.unwrap()
+        );
+        for epoch in [Epoch(1), last_epoch] {
+            restarted_store
+                .acquire_epoch_session(epoch)
+                .await
+                .err()
+                .expect("acquiring a closed session should fail");
+        }
+        restarted_store
+            .acquire_epoch_session(last_epoch + 1)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn epoch_session_refuses_a_pending_certificate_of_another_epoch() {
+        let store = memory_certificate_pending_store();
+        let session = store.acquire_epoch_session(Epoch(4)).await.unwrap();
+
+        session
+            .save(dummy_certificate_pending(Epoch(5)))
+            .await
+            .expect_err("saving a pending certificate of another epoch should fail");
+
+        assert_eq!(None, session.get().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn reading_from_a_closed_epoch_session_fails() {
+        let store = memory_certificate_pending_store();
+        let session_epoch_4 = store.acquire_epoch_session(Epoch(4)).await.unwrap();
+        let session_epoch_5 = store.acquire_epoch_session(Epoch(5)).await.unwrap();
+        session_epoch_4
+            .save(dummy_certificate_pending(Epoch(4)))
+            .await
+            .unwrap();
+        session_epoch_5
+            .save(dummy_certificate_pending(Epoch(5)))
+            .await
+            .unwrap();
+
+        session_epoch_4
+            .close(SessionOutcome::Committed)
+            .await
+            .unwrap();
+
+        let error = session_epoch_4
+            .get()
+            .await
+            .expect_err("reading from a closed session should fail");
+        assert_eq!(
+            Some(&StoreError::SessionClosed(Epoch(4))),
+            error.downcast_ref::<StoreError>()
+        );
+        assert!(session_epoch_5.get().await.unwrap().is_some());
+        assert_eq!(
+            Some(SessionOutcome::Committed),
+            store.get_session_outcome(Epoch(4)).await.unwrap()
+        );
+        assert_eq!(None, store.get_session_outcome(Epoch(5)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn acquiring_a_closed_epoch_session_fails() {
+        let store = memory_certificate_pending_store();
+        let session = store.acquire_epoch_session(Epoch(4)).await.unwrap();
+        session.close(SessionOutcome::Aborted).await.unwrap();
+
+        let error = store
+            .acquire_epoch_session(Epoch(4))
+            .await
+            .err()
+            .expect("acquiring a closed session should fail");
+
+        assert_eq!(
+            Some(&StoreError::SessionClosed(Epoch(4))),
+            error.downcast_ref::<StoreError>()
+        );
+        assert_eq!(
+            Some(SessionOutcome::Aborted),
+            store.get_session_outcome(Epoch(4)).await.unwrap()
+        );
+    }
 }
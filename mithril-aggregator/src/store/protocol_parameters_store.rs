@@ -1,8 +1,9 @@
-use std::collections::HashMap;
-
 use async_trait::async_trait;
 use mithril_common::StdResult;
 use slog_scope::debug;
+#[cfg(test)]
+use std::collections::HashMap;
+#[cfg(test)]
 use tokio::sync::RwLock;
 
 use mithril_common::entities::{Epoch, ProtocolParameters};
@@ -42,12 +43,16 @@ pub trait ProtocolParametersStorer: Sync + Send {
     }
 }
 
+/// In memory [ProtocolParametersStorer] used by the tests
+#[cfg(test)]
 pub struct FakeProtocolParametersStorer {
+    /// Protocol parameters stored per epoch
     pub protocol_parameters: RwLock<HashMap<Epoch, ProtocolParameters>>,
 }
 
+#[cfg(test)]
 impl FakeProtocolParametersStorer {
-    #[cfg(test)]
+    /// FakeProtocolParametersStorer factory
     pub fn new(data: Vec<(Epoch, ProtocolParameters)>) -> Self {
         let protocol_parameters = RwLock::new(data.into_iter().collect());
         Self {
@@ -56,6 +61,7 @@ impl FakeProtocolParametersStorer {
     }
 }
 
+#[cfg(test)]
 #[async_trait]
 impl ProtocolParametersStorer for FakeProtocolParametersStorer {
     async fn save_protocol_parameters(
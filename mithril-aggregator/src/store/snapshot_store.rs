@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use mithril_common::entities::{Epoch, Snapshot};
+use mithril_common::StdResult;
+
+use crate::tools::ArchiveRangeReader;
+use crate::SnapshotError;
+
+/// Storage class of the archive of a snapshot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotStorageClass {
+    /// The archive is kept in the storage of the recent snapshots
+    #[default]
+    Standard,
+
+    /// The archive was moved to the cold storage, it must be restored before it's downloaded
+    ColdStorage,
+}
+
+/// Handle of a snapshot archive moved to the cold storage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColdArchiveHandle {
+    /// Digest of the archived snapshot
+    pub digest: String,
+
+    /// Key of the archive object in the storage
+    pub object_key: String,
+
+    /// Storage class the archive object was transitioned to
+    pub storage_class: String,
+}
+
+/// Status of the restoration of a snapshot archive from the cold storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreStatus {
+    /// The archive is being restored, it can't be downloaded yet
+    Pending,
+
+    /// The archive can be downloaded
+    Available,
+}
+
+/// Store of the snapshots produced by the aggregator, used to keep the storage used by their
+/// archives under a budget.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SnapshotStore: Sync + Send {
+    /// Sum of the sizes, in bytes, of the archives of all the stored snapshots.
+    async fn total_stored_bytes(&self) -> StdResult<u64>;
+
+    /// Check that storing a new snapshot archive of `new_snapshot_bytes` keeps the total size
+    /// of the stored archives under `max_bytes`.
+    ///
+    /// Fail with a [SnapshotError::BudgetExceeded] if the budget would be exceeded.
+    async fn enforce_budget(&self, max_bytes: u64, new_snapshot_bytes: u64) -> StdResult<()> {
+        let current = self
+            .total_stored_bytes()
+            .await?
+            .saturating_add(new_snapshot_bytes);
+        if current > max_bytes {
+            return Err(SnapshotError::BudgetExceeded {
+                current,
+                max: max_bytes,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Find the stored snapshot with the given digest, `None` if there's no such snapshot.
+    async fn find_by_digest(&self, digest: &str) -> StdResult<Option<Snapshot>>;
+
+    /// Whether the snapshot archives can be moved to the cold storage, if not all the archives
+    /// have the [SnapshotStorageClass::Standard] storage class.
+    fn has_cold_storage(&self) -> bool;
+
+    /// Return the storage class of the archive of the snapshot with the given digest, `None`
+    /// if there's no such snapshot.
+    async fn get_storage_class(&self, digest: &str) -> StdResult<Option<SnapshotStorageClass>>;
+
+    /// Move the archive of the snapshot with the given digest to the cold storage and mark the
+    /// snapshot with the [SnapshotStorageClass::ColdStorage] storage class.
+    async fn archive_to_cold(&self, digest: &str) -> StdResult<ColdArchiveHandle>;
+
+    /// Move to the cold storage the archives of the snapshots made before the given epoch that
+    /// are not already in it.
+    ///
+    /// Return the handles of the archives moved to the cold storage.
+    async fn archive_to_cold_before_epoch(&self, epoch: Epoch)
+        -> StdResult<Vec<ColdArchiveHandle>>;
+
+    /// Request the restoration, for `restore_days` days, of the archive of the snapshot with the
+    /// given digest if it was moved to the cold storage.
+    async fn restore_from_cold(&self, digest: &str, restore_days: u8) -> StdResult<RestoreStatus>;
+
+    /// Read the bytes from `start` to `end`, both included, of the archive of the snapshot with
+    /// the given digest.
+    async fn get_byte_range(
+        &self,
+        digest: &str,
+        start: u64,
+        end: u64,
+    ) -> StdResult<ArchiveRangeReader>;
+}
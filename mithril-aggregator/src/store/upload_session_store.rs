@@ -0,0 +1,145 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+use mithril_common::StdResult;
+use mithril_persistence::store::adapter::StoreAdapter;
+
+type Adapter = Box<dyn StoreAdapter<Key = String, Record = UploadSession>>;
+
+/// An upload of a file in several parts that can be resumed after an interruption
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadSession {
+    /// Identifier of the session given by the remote storage
+    pub session_id: String,
+
+    /// Path of the uploaded file
+    pub file_path: PathBuf,
+
+    /// Fingerprint of the uploaded file, computed from its path, size and modification time
+    pub file_fingerprint: String,
+
+    /// Number of bytes of the file already uploaded
+    pub offset: u64,
+
+    /// Date and time at which the upload started
+    pub started_at: DateTime<Utc>,
+}
+
+/// Store for the [UploadSession] that are not completed, indexed by file fingerprint.
+pub struct UploadSessionStore {
+    adapter: RwLock<Adapter>,
+}
+
+impl UploadSessionStore {
+    /// Create a new instance.
+    pub fn new(adapter: Adapter) -> Self {
+        Self {
+            adapter: RwLock::new(adapter),
+        }
+    }
+
+    /// Fetch the session uploading the file with the given fingerprint if any.
+    pub async fn get(&self, file_fingerprint: &str) -> StdResult<Option<UploadSession>> {
+        self.adapter
+            .read()
+            .await
+            .get_record(&file_fingerprint.to_string())
+            .await
+            .with_context(|| {
+                format!(
+                    "Upload session store: could not get session for file fingerprint '{file_fingerprint}'."
+                )
+            })
+    }
+
+    /// Save the given [UploadSession], replacing the previous session of the same file.
+    pub async fn save(&self, session: &UploadSession) -> StdResult<()> {
+        self.adapter
+            .write()
+            .await
+            .store_record(&session.file_fingerprint, session)
+            .await
+            .with_context(|| {
+                format!(
+                    "Upload session store: could not save session '{}'.",
+                    session.session_id
+                )
+            })
+    }
+
+    /// Remove the session uploading the file with the given fingerprint.
+    pub async fn remove(&self, file_fingerprint: &str) -> StdResult<()> {
+        self.adapter
+            .write()
+            .await
+            .remove(&file_fingerprint.to_string())
+            .await
+            .with_context(|| {
+                format!("Upload session store: could not remove session for file fingerprint '{file_fingerprint}'.")
+            })?;
+
+        Ok(())
+    }
+
+    /// List the sessions that are not completed.
+    pub async fn list(&self) -> StdResult<Vec<UploadSession>> {
+        Ok(self
+            .adapter
+            .read()
+            .await
+            .get_iter()
+            .await
+            .with_context(|| "Upload session store: could not list sessions.")?
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_persistence::store::adapter::MemoryAdapter;
+
+    use super::*;
+
+    fn session(file_fingerprint: &str, offset: u64) -> UploadSession {
+        UploadSession {
+            session_id: format!("session-{file_fingerprint}"),
+            file_path: PathBuf::from(format!("snapshot.{file_fingerprint}.tar.gz")),
+            file_fingerprint: file_fingerprint.to_string(),
+            offset,
+            started_at: DateTime::<Utc>::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn save_replaces_the_session_of_the_same_file() {
+        let store = UploadSessionStore::new(Box::new(
+            MemoryAdapter::<String, UploadSession>::new(None).unwrap(),
+        ));
+
+        store.save(&session("fingerprint-1", 0)).await.unwrap();
+        store.save(&session("fingerprint-1", 100)).await.unwrap();
+        store.save(&session("fingerprint-2", 50)).await.unwrap();
+
+        assert_eq!(
+            Some(session("fingerprint-1", 100)),
+            store.get("fingerprint-1").await.unwrap()
+        );
+        assert_eq!(2, store.list().await.unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn remove_deletes_the_session() {
+        let store = UploadSessionStore::new(Box::new(
+            MemoryAdapter::<String, UploadSession>::new(None).unwrap(),
+        ));
+        store.save(&session("fingerprint-1", 100)).await.unwrap();
+
+        store.remove("fingerprint-1").await.unwrap();
+
+        assert_eq!(None, store.get("fingerprint-1").await.unwrap());
+        assert!(store.list().await.unwrap().is_empty());
+    }
+}
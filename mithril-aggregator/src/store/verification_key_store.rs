@@ -304,7 +304,7 @@ pub mod test_suite {
             .get_verification_keys(Epoch(1))
             .await
             .unwrap()
-            .map(|x| BTreeMap::from_iter(x.into_iter()));
+            .map(BTreeMap::from_iter);
 
         assert_eq!(expected_signers, res);
     }
@@ -322,7 +322,7 @@ pub mod test_suite {
             .get_signers(Epoch(1))
             .await
             .unwrap()
-            .map(|x| BTreeSet::from_iter(x.into_iter()));
+            .map(BTreeSet::from_iter);
 
         assert_eq!(expected_signers, res);
     }
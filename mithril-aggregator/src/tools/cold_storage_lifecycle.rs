@@ -0,0 +1,211 @@
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use mithril_common::StdResult;
+use reqwest::{Method, StatusCode};
+use std::sync::Arc;
+
+use crate::store::RestoreStatus;
+use crate::tools::S3Client;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Storage class of the S3 objects moved to the cold storage.
+///
+/// The objects of this class can't be read until they are restored with a `RestoreObject`
+/// request (unlike the `GLACIER_IR` objects that are readable immediately).
+pub const COLD_STORAGE_CLASS: &str = "GLACIER";
+
+/// Lifecycle API of an S3 compatible storage, used to move the snapshot archives between
+/// the hot and the cold storage classes
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait ColdStorageLifecycle: Sync + Send {
+    /// Transition the object with the given key to the given storage class
+    async fn transition_object(&self, object_key: &str, storage_class: &str) -> StdResult<()>;
+
+    /// Request a temporary copy, kept for `restore_days` days, of the object with the given key
+    /// that was transitioned to a cold storage class
+    async fn restore_object(&self, object_key: &str, restore_days: u8) -> StdResult<RestoreStatus>;
+}
+
+/// [ColdStorageLifecycle] of the objects of an S3 bucket
+pub struct S3ColdStorageLifecycle {
+    client: Arc<S3Client>,
+}
+
+impl S3ColdStorageLifecycle {
+    /// S3ColdStorageLifecycle factory
+    pub fn new(client: Arc<S3Client>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ColdStorageLifecycle for S3ColdStorageLifecycle {
+    /// The object is copied onto itself with the new storage class
+    async fn transition_object(&self, object_key: &str, storage_class: &str) -> StdResult<()> {
+        let copy_source = self.client.copy_source(object_key);
+        let response = self
+            .client
+            .send(
+                Method::PUT,
+                object_key,
+                &[],
+                &[
+                    ("x-amz-copy-source", &copy_source),
+                    ("x-amz-metadata-directive", "COPY"),
+                    ("x-amz-storage-class", storage_class),
+                ],
+                vec![],
+            )
+            .await?;
+        let response = S3Client::error_for_status(response)
+            .await
+            .with_context(|| {
+                format!("Can not transition object '{object_key}' to storage class {storage_class}")
+            })?;
+
+        // A copy can fail after the storage answered with a success status, the error is then
+        // given in the body
+        let body = response.text().await.unwrap_or_default();
+        if body.contains("<Error>") {
+            return Err(anyhow!(
+                "Can not transition object '{object_key}' to storage class {storage_class}: {body}"
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn restore_object(&self, object_key: &str, restore_days: u8) -> StdResult<RestoreStatus> {
+        let restore_request = format!(
+            "<RestoreRequest><Days>{restore_days}</Days>\
+            <GlacierJobParameters><Tier>Standard</Tier></GlacierJobParameters></RestoreRequest>"
+        );
+        let response = self
+            .client
+            .send(
+                Method::POST,
+                object_key,
+                &[("restore", "")],
+                &[("content-type", "application/xml")],
+                restore_request.into_bytes(),
+            )
+            .await?;
+
+        match response.status() {
+            // The restoration is started
+            StatusCode::ACCEPTED => Ok(RestoreStatus::Pending),
+            // The object was already restored, only the expiry of its copy is updated
+            StatusCode::OK => Ok(RestoreStatus::Available),
+            StatusCode::CONFLICT => {
+                let error = response.text().await.unwrap_or_default();
+                if error.contains("RestoreAlreadyInProgress") {
+                    Ok(RestoreStatus::Pending)
+                } else {
+                    Err(anyhow!(
+                        "Can not restore object '{object_key}', restore conflict: {error}"
+                    ))
+                }
+            }
+            _ => S3Client::error_for_status(response)
+                .await
+                .map(|_| RestoreStatus::Available)
+                .with_context(|| format!("Can not restore object '{object_key}'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::MockServer;
+
+    use crate::tools::S3Credentials;
+
+    use super::*;
+
+    fn lifecycle(server: &MockServer) -> S3ColdStorageLifecycle {
+        S3ColdStorageLifecycle::new(Arc::new(
+            S3Client::new(
+                "bucket".to_string(),
+                "eu-west-3".to_string(),
+                Some(server.url("")),
+            )
+            .with_credentials(S3Credentials::new("key-id", "secret", None)),
+        ))
+    }
+
+    #[tokio::test]
+    async fn transition_object_copies_the_object_onto_itself_with_the_storage_class() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::PUT)
+                .path("/bucket/archive.tar.gz")
+                .header("x-amz-copy-source", "bucket/archive.tar.gz")
+                .header("x-amz-metadata-directive", "COPY")
+                .header("x-amz-storage-class", COLD_STORAGE_CLASS);
+            then.status(200)
+                .body("<CopyObjectResult></CopyObjectResult>");
+        });
+
+        lifecycle(&server)
+            .transition_object("archive.tar.gz", COLD_STORAGE_CLASS)
+            .await
+            .unwrap();
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn transition_object_fails_if_the_copy_fails_after_a_success_status() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::PUT);
+            then.status(200)
+                .body("<Error><Code>InternalError</Code></Error>");
+        });
+
+        lifecycle(&server)
+            .transition_object("archive.tar.gz", COLD_STORAGE_CLASS)
+            .await
+            .expect_err("a copy that failed should fail the transition");
+    }
+
+    async fn restore_status_for_response(status: u16, body: &str) -> StdResult<RestoreStatus> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/bucket/archive.tar.gz")
+                .query_param("restore", "")
+                .body_contains("<Days>2</Days>");
+            then.status(status).body(body);
+        });
+
+        lifecycle(&server).restore_object("archive.tar.gz", 2).await
+    }
+
+    #[tokio::test]
+    async fn restore_object_status_depends_on_the_storage_response() {
+        assert_eq!(
+            RestoreStatus::Pending,
+            restore_status_for_response(202, "").await.unwrap()
+        );
+        assert_eq!(
+            RestoreStatus::Available,
+            restore_status_for_response(200, "").await.unwrap()
+        );
+        assert_eq!(
+            RestoreStatus::Pending,
+            restore_status_for_response(
+                409,
+                "<Error><Code>RestoreAlreadyInProgress</Code></Error>"
+            )
+            .await
+            .unwrap()
+        );
+        restore_status_for_response(403, "<Error><Code>AccessDenied</Code></Error>")
+            .await
+            .expect_err("a denied restore should fail");
+    }
+}
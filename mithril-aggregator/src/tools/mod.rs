@@ -1,20 +1,35 @@
 mod certificates_hash_migrator;
+mod cold_storage_lifecycle;
 mod digest_helpers;
 mod era;
 mod genesis;
 #[cfg(test)]
 pub mod mocks;
 mod remote_file_uploader;
+mod s3_client;
 mod signer_importer;
+mod snapshot_archive_reader;
 
 pub use certificates_hash_migrator::CertificatesHashMigrator;
+pub use cold_storage_lifecycle::{
+    ColdStorageLifecycle, S3ColdStorageLifecycle, COLD_STORAGE_CLASS,
+};
 pub use digest_helpers::extract_digest_from_path;
 pub use era::EraTools;
 pub use genesis::{GenesisTools, GenesisToolsDependency};
-pub use remote_file_uploader::{GcpFileUploader, RemoteFileUploader};
+pub use remote_file_uploader::{GcpFileUploader, RemoteFileUploader, S3FileUploader};
+pub use s3_client::{S3Client, S3Credentials};
 pub use signer_importer::{
     CExplorerSignerRetriever, SignersImporter, SignersImporterPersister, SignersImporterRetriever,
 };
+pub use snapshot_archive_reader::{
+    ArchiveRangeReader, GcpArchiveUrlPresigner, LocalSnapshotArchiveReader,
+    RemoteSnapshotArchiveReader, SnapshotArchiveReader,
+};
 
+#[cfg(test)]
+pub use cold_storage_lifecycle::MockColdStorageLifecycle;
 #[cfg(test)]
 pub use remote_file_uploader::MockRemoteFileUploader;
+#[cfg(test)]
+pub use snapshot_archive_reader::MockSnapshotArchiveReader;
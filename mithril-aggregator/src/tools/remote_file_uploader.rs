@@ -1,14 +1,21 @@
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use base64::prelude::{Engine, BASE64_STANDARD};
 use cloud_storage::{
     bucket::Entity, bucket_access_control::Role, object_access_control::NewObjectAccessControl,
-    Client,
+    Client, Token, TokenCache,
 };
 use mithril_common::StdResult;
+use reqwest::{
+    header::{CONTENT_LENGTH, CONTENT_RANGE, ETAG, LOCATION, RANGE},
+    redirect, Body, Method, StatusCode,
+};
 use slog_scope::info;
-use std::{env, path::Path};
+use std::{env, path::Path, sync::Arc};
 use tokio_util::{codec::BytesCodec, codec::FramedRead};
 
+use crate::tools::S3Client;
+
 #[cfg(test)]
 use mockall::automock;
 
@@ -17,37 +24,174 @@ use mockall::automock;
 #[async_trait]
 pub trait RemoteFileUploader: Sync + Send {
     /// Upload a snapshot
-    async fn upload_file(&self, filepath: &Path) -> StdResult<()>;
+    ///
+    /// Returns the ETag of the uploaded object if the storage returns one that is computed
+    /// like an S3 ETag, it's then checked against the local file.
+    async fn upload_file(&self, filepath: &Path) -> StdResult<Option<String>>;
+
+    /// Open a session to upload the given file in several parts.
+    ///
+    /// Returns the identifier of the session, or `None` if the uploader can't resume an
+    /// interrupted upload, in which case the file must be uploaded with [Self::upload_file].
+    async fn open_upload_session(&self, _filepath: &Path) -> StdResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Upload the `part` of a file starting at byte `offset` in the given session.
+    ///
+    /// The upload is completed when the part reaching `total_size` is uploaded.
+    async fn upload_part(
+        &self,
+        session_id: &str,
+        _offset: u64,
+        _part: Vec<u8>,
+        _total_size: u64,
+    ) -> StdResult<()> {
+        Err(anyhow!(
+            "upload session '{session_id}' can't be resumed: this uploader does not support upload sessions"
+        ))
+    }
+
+    /// Start an S3 multipart upload of the given file.
+    ///
+    /// Returns the identifier of the multipart upload, its chunks can then be uploaded
+    /// concurrently with [Self::upload_multipart_chunk].
+    async fn create_multipart_upload(&self, filepath: &Path) -> StdResult<String> {
+        Err(anyhow!(
+            "multipart upload of '{filepath:?}' can't be started: this uploader does not support multipart uploads"
+        ))
+    }
+
+    /// Upload the chunk number `part_number` (starting at 1) of the multipart upload of the
+    /// given file.
+    ///
+    /// Returns the ETag of the uploaded chunk.
+    async fn upload_multipart_chunk(
+        &self,
+        _filepath: &Path,
+        upload_id: &str,
+        _part_number: u32,
+        _chunk: Vec<u8>,
+    ) -> StdResult<String> {
+        Err(anyhow!(
+            "chunk of multipart upload '{upload_id}' can't be uploaded: this uploader does not support multipart uploads"
+        ))
+    }
+
+    /// Assemble the uploaded chunks of the multipart upload of the given file, given as their
+    /// part number and ETag ordered by part number (the S3 `CompleteMultipartUpload` call).
+    ///
+    /// Returns the ETag of the assembled object if the storage returns one.
+    async fn complete_multipart_upload(
+        &self,
+        _filepath: &Path,
+        upload_id: &str,
+        _parts: Vec<(u32, String)>,
+    ) -> StdResult<Option<String>> {
+        Err(anyhow!(
+            "multipart upload '{upload_id}' can't be completed: this uploader does not support multipart uploads"
+        ))
+    }
+
+    /// Abort the multipart upload of the given file, the storage drops its uploaded chunks
+    /// (the S3 `AbortMultipartUpload` call).
+    async fn abort_multipart_upload(&self, _filepath: &Path, upload_id: &str) -> StdResult<()> {
+        Err(anyhow!(
+            "multipart upload '{upload_id}' can't be aborted: this uploader does not support multipart uploads"
+        ))
+    }
 }
 
+/// Url of the Google Cloud Storage API used to open the resumable uploads
+const GCP_UPLOAD_API_URL: &str = "https://storage.googleapis.com/upload/storage/v1";
+
 /// GcpFileUploader represents a Google Cloud Platform file uploader interactor
 pub struct GcpFileUploader {
     bucket: String,
+    upload_api_url: String,
+    token: Token,
 }
 
 impl GcpFileUploader {
     /// GcpFileUploader factory
     pub fn new(bucket: String) -> Self {
-        Self { bucket }
+        Self {
+            bucket,
+            upload_api_url: GCP_UPLOAD_API_URL.to_string(),
+            token: Token::default(),
+        }
     }
-}
 
-#[async_trait]
-impl RemoteFileUploader for GcpFileUploader {
-    async fn upload_file(&self, filepath: &Path) -> StdResult<()> {
+    fn check_credentials() -> StdResult<()> {
         if env::var("GOOGLE_APPLICATION_CREDENTIALS_JSON").is_err() {
             return Err(anyhow!(
                 "Missing GOOGLE_APPLICATION_CREDENTIALS_JSON environment variable".to_string()
             ));
         };
 
+        Ok(())
+    }
+
+    /// Convert the base64 encoded MD5 of a Google Cloud Storage object to the hex encoded MD5
+    /// that is the ETag of an S3 object sent with a single `PUT`
+    fn md5_hash_to_etag(md5_hash: &str) -> Option<String> {
+        BASE64_STANDARD.decode(md5_hash).ok().map(hex::encode)
+    }
+
+    async fn get_access_token(&self) -> StdResult<String> {
+        self.token
+            .get(&cloud_storage_reqwest::Client::new())
+            .await
+            .with_context(|| "could not get a Google Cloud Storage access token")
+    }
+
+    fn http_client() -> StdResult<reqwest::Client> {
+        // The storage answers `308 Resume Incomplete` to the parts that don't complete an
+        // upload, it must not be followed as a redirection
+        reqwest::Client::builder()
+            .redirect(redirect::Policy::none())
+            .build()
+            .with_context(|| "could not build the HTTP client of the uploader")
+    }
+
+    /// Ensure the uploaded file as public read access
+    async fn make_public(&self, filename: &str) -> StdResult<()> {
+        // when a file is uploaded to gcloud storage its permissions are overwritten so
+        // we need to put them back
+        let new_bucket_access_control = NewObjectAccessControl {
+            entity: Entity::AllUsers,
+            role: Role::Reader,
+        };
+
+        info!(
+            "updating acl for {}: {:?}",
+            filename, new_bucket_access_control
+        );
+
+        Client::default()
+            .object_access_control()
+            .create(&self.bucket, filename, &new_bucket_access_control)
+            .await
+            .with_context(|| "updating acl failure")?;
+
+        info!("updated acl for {} ", filename);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RemoteFileUploader for GcpFileUploader {
+    async fn upload_file(&self, filepath: &Path) -> StdResult<Option<String>> {
+        Self::check_credentials()?;
+
         let filename = filepath.file_name().unwrap().to_str().unwrap();
 
         info!("uploading {}", filename);
         let client = Client::default();
         let file = tokio::fs::File::open(filepath).await.unwrap();
         let stream = FramedRead::new(file, BytesCodec::new());
-        client
+        let object = client
             .object()
             .create_streamed(
                 &self.bucket,
@@ -61,27 +205,477 @@ impl RemoteFileUploader for GcpFileUploader {
 
         info!("uploaded {}", filename);
 
-        // ensure the uploaded file as public read access
-        // when a file is uploaded to gcloud storage its permissions are overwritten so
-        // we need to put them back
-        let new_bucket_access_control = NewObjectAccessControl {
-            entity: Entity::AllUsers,
-            role: Role::Reader,
+        self.make_public(filename).await?;
+
+        // The ETags of Google Cloud Storage are not computed from the object content, but the
+        // MD5 of the content is given for the objects that are not composed of other objects
+        Ok(object.md5_hash.as_deref().and_then(Self::md5_hash_to_etag))
+    }
+
+    /// Open a resumable upload, the session identifier is the URI of the upload session.
+    async fn open_upload_session(&self, filepath: &Path) -> StdResult<Option<String>> {
+        Self::check_credentials()?;
+
+        let filename = filepath.file_name().unwrap().to_str().unwrap();
+        let response = Self::http_client()?
+            .post(format!("{}/b/{}/o", self.upload_api_url, self.bucket))
+            .query(&[("uploadType", "resumable"), ("name", filename)])
+            .bearer_auth(self.get_access_token().await?)
+            .header("X-Upload-Content-Type", "application/octet-stream")
+            .header(CONTENT_LENGTH, 0)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("could not open an upload session for '{filename}'"))?;
+        let session_uri = response
+            .headers()
+            .get(LOCATION)
+            .ok_or_else(|| anyhow!("no session URI returned for the upload of '{filename}'"))?
+            .to_str()
+            .with_context(|| format!("invalid session URI for the upload of '{filename}'"))?;
+
+        info!("opened upload session for {}", filename);
+
+        Ok(Some(session_uri.to_string()))
+    }
+
+    async fn upload_part(
+        &self,
+        session_id: &str,
+        offset: u64,
+        part: Vec<u8>,
+        total_size: u64,
+    ) -> StdResult<()> {
+        let part_end = offset + part.len() as u64;
+        let response = Self::http_client()?
+            .put(session_id)
+            .header(
+                CONTENT_RANGE,
+                format!("bytes {offset}-{}/{total_size}", part_end.saturating_sub(1)),
+            )
+            .body(part)
+            .send()
+            .await
+            .with_context(|| format!("could not upload the part at offset {offset}"))?;
+
+        match response.status() {
+            StatusCode::PERMANENT_REDIRECT => {
+                // The persisted bytes are given as `bytes=0-<last persisted byte>`
+                let persisted_end = response
+                    .headers()
+                    .get(RANGE)
+                    .and_then(|range| range.to_str().ok())
+                    .and_then(|range| range.rsplit('-').next())
+                    .and_then(|last_byte| last_byte.parse::<u64>().ok())
+                    .map(|last_byte| last_byte + 1)
+                    .unwrap_or_default();
+                if persisted_end != part_end {
+                    return Err(anyhow!(
+                        "the part at offset {offset} was not fully persisted, the storage holds {persisted_end} bytes"
+                    ));
+                }
+
+                Ok(())
+            }
+            status if status.is_success() => {
+                let object: serde_json::Value = response
+                    .json()
+                    .await
+                    .with_context(|| "could not read the uploaded object")?;
+                let filename = object["name"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("no name in the uploaded object"))?;
+                info!("uploaded {}", filename);
+
+                self.make_public(filename).await
+            }
+            status => Err(anyhow!(
+                "upload of the part at offset {offset} failed with status {status}"
+            )),
+        }
+    }
+}
+
+/// S3FileUploader uploads the files to a bucket of an S3 compatible storage
+///
+/// The objects are not given a public ACL, the bucket policy must allow to read them.
+pub struct S3FileUploader {
+    client: Arc<S3Client>,
+}
+
+impl S3FileUploader {
+    /// S3FileUploader factory
+    pub fn new(client: Arc<S3Client>) -> Self {
+        Self { client }
+    }
+
+    fn etag(response: &reqwest::Response) -> Option<String> {
+        response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(|etag| etag.to_string())
+    }
+
+    fn object_key(filepath: &Path) -> StdResult<&str> {
+        filepath
+            .file_name()
+            .and_then(|filename| filename.to_str())
+            .ok_or_else(|| anyhow!("no valid file name in '{filepath:?}'"))
+    }
+}
+
+#[async_trait]
+impl RemoteFileUploader for S3FileUploader {
+    async fn upload_file(&self, filepath: &Path) -> StdResult<Option<String>> {
+        let object_key = Self::object_key(filepath)?;
+        let file = tokio::fs::File::open(filepath)
+            .await
+            .with_context(|| format!("could not open '{filepath:?}'"))?;
+        let file_size = file.metadata().await?.len();
+
+        info!("uploading {}", object_key);
+        let response = self
+            .client
+            .send_streamed(
+                Method::PUT,
+                object_key,
+                &[("content-type", "application/octet-stream")],
+                Body::wrap_stream(FramedRead::new(file, BytesCodec::new())),
+                file_size,
+            )
+            .await?;
+        let response = S3Client::error_for_status(response)
+            .await
+            .with_context(|| format!("remote uploading of '{object_key}' failure"))?;
+        info!("uploaded {}", object_key);
+
+        Ok(Self::etag(&response))
+    }
+
+    async fn create_multipart_upload(&self, filepath: &Path) -> StdResult<String> {
+        let object_key = Self::object_key(filepath)?;
+        let response = self
+            .client
+            .send(
+                Method::POST,
+                object_key,
+                &[("uploads", "")],
+                &[("content-type", "application/octet-stream")],
+                vec![],
+            )
+            .await?;
+        let response = S3Client::error_for_status(response)
+            .await
+            .with_context(|| format!("could not start the multipart upload of '{object_key}'"))?;
+        let body = response.text().await?;
+
+        body.split_once("<UploadId>")
+            .and_then(|(_, rest)| rest.split_once("</UploadId>"))
+            .map(|(upload_id, _)| upload_id.to_string())
+            .ok_or_else(|| {
+                anyhow!("no upload id returned for the multipart upload of '{object_key}': {body}")
+            })
+    }
+
+    async fn upload_multipart_chunk(
+        &self,
+        filepath: &Path,
+        upload_id: &str,
+        part_number: u32,
+        chunk: Vec<u8>,
+    ) -> StdResult<String> {
+        let object_key = Self::object_key(filepath)?;
+        let response = self
+            .client
+            .send(
+                Method::PUT,
+                object_key,
+                &[
+                    ("partNumber", &part_number.to_string()),
+                    ("uploadId", upload_id),
+                ],
+                &[],
+                chunk,
+            )
+            .await?;
+        let response = S3Client::error_for_status(response)
+            .await
+            .with_context(|| format!("could not upload chunk {part_number} of '{object_key}'"))?;
+
+        Self::etag(&response)
+            .ok_or_else(|| anyhow!("no ETag returned for chunk {part_number} of '{object_key}'"))
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        filepath: &Path,
+        upload_id: &str,
+        parts: Vec<(u32, String)>,
+    ) -> StdResult<Option<String>> {
+        let object_key = Self::object_key(filepath)?;
+        let parts: String = parts
+            .into_iter()
+            .map(|(part_number, etag)| {
+                format!("<Part><PartNumber>{part_number}</PartNumber><ETag>{etag}</ETag></Part>")
+            })
+            .collect();
+        let response = self
+            .client
+            .send(
+                Method::POST,
+                object_key,
+                &[("uploadId", upload_id)],
+                &[("content-type", "application/xml")],
+                format!("<CompleteMultipartUpload>{parts}</CompleteMultipartUpload>").into_bytes(),
+            )
+            .await?;
+        let response = S3Client::error_for_status(response)
+            .await
+            .with_context(|| {
+                format!("could not complete the multipart upload of '{object_key}'")
+            })?;
+
+        // The assembly can fail after the storage answered with a success status, the error
+        // is then given in the body
+        let body = response.text().await.unwrap_or_default();
+        if body.contains("<Error>") {
+            return Err(anyhow!(
+                "could not complete the multipart upload of '{object_key}': {body}"
+            ));
+        }
+        info!("uploaded {}", object_key);
+
+        // The quotes around the ETag are escaped in the XML body
+        Ok(body
+            .split_once("<ETag>")
+            .and_then(|(_, rest)| rest.split_once("</ETag>"))
+            .map(|(etag, _)| etag.replace("&quot;", "\"").replace("&#34;", "\"")))
+    }
+
+    async fn abort_multipart_upload(&self, filepath: &Path, upload_id: &str) -> StdResult<()> {
+        let object_key = Self::object_key(filepath)?;
+        let response = self
+            .client
+            .send(
+                Method::DELETE,
+                object_key,
+                &[("uploadId", upload_id)],
+                &[],
+                vec![],
+            )
+            .await?;
+        S3Client::error_for_status(response)
+            .await
+            .with_context(|| format!("could not abort the multipart upload of '{object_key}'"))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::MockServer;
+
+    use crate::tools::S3Credentials;
+
+    use super::*;
+
+    fn uploader_with_session(server: &MockServer) -> (GcpFileUploader, String) {
+        let uploader = GcpFileUploader {
+            bucket: "bucket".to_string(),
+            upload_api_url: server.url(""),
+            token: Token::default(),
         };
 
-        info!(
-            "updating acl for {}: {:?}",
-            filename, new_bucket_access_control
+        (uploader, server.url("/session"))
+    }
+
+    #[tokio::test]
+    async fn upload_part_not_completing_the_upload_expects_the_next_part() {
+        let server = MockServer::start();
+        let session_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::PUT)
+                .path("/session")
+                .header("content-range", "bytes 100-199/1000");
+            then.status(308).header("range", "bytes=0-199");
+        });
+        let (uploader, session_id) = uploader_with_session(&server);
+
+        uploader
+            .upload_part(&session_id, 100, vec![0; 100], 1000)
+            .await
+            .unwrap();
+
+        session_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn upload_part_not_fully_persisted_fails() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::PUT).path("/session");
+            then.status(308).header("range", "bytes=0-149");
+        });
+        let (uploader, session_id) = uploader_with_session(&server);
+
+        uploader
+            .upload_part(&session_id, 100, vec![0; 100], 1000)
+            .await
+            .expect_err("a part not fully persisted should fail");
+    }
+
+    #[test]
+    fn gcp_md5_hash_is_converted_to_a_single_part_etag() {
+        assert_eq!(
+            Some("5eb63bbbe01eeed093cb22bb8f5acdc3".to_string()),
+            GcpFileUploader::md5_hash_to_etag("XrY7u+Ae7tCTyyK7j1rNww==")
         );
+        assert_eq!(None, GcpFileUploader::md5_hash_to_etag("not base64"));
+    }
 
-        client
-            .object_access_control()
-            .create(&self.bucket, filename, &new_bucket_access_control)
+    fn s3_uploader(server: &MockServer) -> S3FileUploader {
+        S3FileUploader::new(Arc::new(
+            S3Client::new(
+                "bucket".to_string(),
+                "eu-west-3".to_string(),
+                Some(server.url("")),
+            )
+            .with_credentials(S3Credentials::new("key-id", "secret", None)),
+        ))
+    }
+
+    #[tokio::test]
+    async fn s3_multipart_upload_of_a_file() {
+        let filepath = Path::new("/tmp/snapshot.tar.gz");
+        let server = MockServer::start();
+        let create_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/bucket/snapshot.tar.gz")
+                .query_param("uploads", "");
+            then.status(200).body(
+                "<InitiateMultipartUploadResult><Bucket>bucket</Bucket>\
+                <Key>snapshot.tar.gz</Key><UploadId>upload-1</UploadId>\
+                </InitiateMultipartUploadResult>",
+            );
+        });
+        let chunk_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::PUT)
+                .path("/bucket/snapshot.tar.gz")
+                .query_param("partNumber", "2")
+                .query_param("uploadId", "upload-1")
+                .body("chunk");
+            then.status(200).header("etag", "\"etag-2\"");
+        });
+        let complete_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/bucket/snapshot.tar.gz")
+                .query_param("uploadId", "upload-1")
+                .body(
+                    "<CompleteMultipartUpload>\
+                    <Part><PartNumber>1</PartNumber><ETag>\"etag-1\"</ETag></Part>\
+                    <Part><PartNumber>2</PartNumber><ETag>\"etag-2\"</ETag></Part>\
+                    </CompleteMultipartUpload>",
+                );
+            then.status(200).body(
+                "<CompleteMultipartUploadResult><Key>snapshot.tar.gz</Key>\
+                <ETag>&quot;3858f62230ac3c915f300c664312c11f-2&quot;</ETag>\
+                </CompleteMultipartUploadResult>",
+            );
+        });
+        let uploader = s3_uploader(&server);
+
+        let upload_id = uploader.create_multipart_upload(filepath).await.unwrap();
+        let etag = uploader
+            .upload_multipart_chunk(filepath, &upload_id, 2, b"chunk".to_vec())
             .await
-            .with_context(|| "updating acl failure")?;
+            .unwrap();
+        let object_etag = uploader
+            .complete_multipart_upload(
+                filepath,
+                &upload_id,
+                vec![(1, "\"etag-1\"".to_string()), (2, etag)],
+            )
+            .await
+            .unwrap();
 
-        info!("updated acl for {} ", filename);
+        assert_eq!("upload-1", upload_id);
+        assert_eq!(
+            Some("\"3858f62230ac3c915f300c664312c11f-2\"".to_string()),
+            object_etag
+        );
+        create_mock.assert();
+        chunk_mock.assert();
+        complete_mock.assert();
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn s3_multipart_upload_completed_with_an_error_body_fails() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .query_param("uploadId", "upload-1");
+            then.status(200)
+                .body("<Error><Code>InternalError</Code></Error>");
+        });
+
+        s3_uploader(&server)
+            .complete_multipart_upload(Path::new("snapshot.tar.gz"), "upload-1", vec![])
+            .await
+            .expect_err("an assembly that failed should fail the completion");
+    }
+
+    #[tokio::test]
+    async fn s3_abort_multipart_upload() {
+        let server = MockServer::start();
+        let abort_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::DELETE)
+                .path("/bucket/snapshot.tar.gz")
+                .query_param("uploadId", "upload-1");
+            then.status(204);
+        });
+
+        s3_uploader(&server)
+            .abort_multipart_upload(Path::new("snapshot.tar.gz"), "upload-1")
+            .await
+            .unwrap();
+
+        abort_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn s3_upload_file_returns_the_etag_of_the_uploaded_object() {
+        let dir = tempfile::tempdir().unwrap();
+        let filepath = dir.path().join("snapshot.tar.gz");
+        std::fs::write(&filepath, b"snapshot content").unwrap();
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::PUT)
+                .path("/bucket/snapshot.tar.gz")
+                .header("x-amz-content-sha256", "UNSIGNED-PAYLOAD")
+                .body("snapshot content");
+            then.status(200).header("etag", "\"etag-1\"");
+        });
+        let uploader = s3_uploader(&server);
+
+        let etag = uploader.upload_file(&filepath).await.unwrap();
+
+        assert_eq!(Some("\"etag-1\"".to_string()), etag);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn upload_part_of_an_expired_session_fails() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::PUT).path("/session");
+            then.status(404);
+        });
+        let (uploader, session_id) = uploader_with_session(&server);
+
+        uploader
+            .upload_part(&session_id, 0, vec![0; 100], 1000)
+            .await
+            .expect_err("the upload of a part of an expired session should fail");
     }
 }
@@ -0,0 +1,189 @@
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use cloud_storage::Client;
+use futures::TryStreamExt;
+use mithril_common::StdResult;
+use reqwest::{header::RANGE, StatusCode};
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::StreamReader;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Duration, in seconds, of the validity of the presigned URLs used to read the archives
+pub const PRESIGNED_URL_EXPIRATION_SECONDS: u32 = 300;
+
+/// Reader of a range of bytes of an archive
+pub type ArchiveRangeReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Source of the snapshot archives, used to read them by ranges of bytes
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait SnapshotArchiveReader: Sync + Send {
+    /// Read the bytes from `start` to `end`, both included, of the archive object with the
+    /// given key
+    async fn read_range(
+        &self,
+        object_key: &str,
+        start: u64,
+        end: u64,
+    ) -> StdResult<ArchiveRangeReader>;
+}
+
+/// [SnapshotArchiveReader] of the archives stored in a local directory
+pub struct LocalSnapshotArchiveReader {
+    directory: PathBuf,
+}
+
+impl LocalSnapshotArchiveReader {
+    /// LocalSnapshotArchiveReader factory
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+}
+
+#[async_trait]
+impl SnapshotArchiveReader for LocalSnapshotArchiveReader {
+    async fn read_range(
+        &self,
+        object_key: &str,
+        start: u64,
+        end: u64,
+    ) -> StdResult<ArchiveRangeReader> {
+        let path = self.directory.join(object_key);
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .with_context(|| format!("Can not open the archive '{}'", path.display()))?;
+        file.seek(SeekFrom::Start(start)).await.with_context(|| {
+            format!(
+                "Can not seek to byte {start} of the archive '{}'",
+                path.display()
+            )
+        })?;
+
+        Ok(Box::new(file.take(end - start + 1)))
+    }
+}
+
+/// Generate the presigned URLs used to download the archive objects of a remote storage
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait ArchiveUrlPresigner: Sync + Send {
+    /// Generate a URL to download the object with the given key, valid for `expires_in_seconds`
+    async fn presign_get_url(&self, object_key: &str, expires_in_seconds: u32)
+        -> StdResult<String>;
+}
+
+/// [ArchiveUrlPresigner] of the objects of a GCP bucket
+pub struct GcpArchiveUrlPresigner {
+    bucket: String,
+}
+
+impl GcpArchiveUrlPresigner {
+    /// GcpArchiveUrlPresigner factory
+    pub fn new(bucket: String) -> Self {
+        Self { bucket }
+    }
+}
+
+#[async_trait]
+impl ArchiveUrlPresigner for GcpArchiveUrlPresigner {
+    async fn presign_get_url(
+        &self,
+        object_key: &str,
+        expires_in_seconds: u32,
+    ) -> StdResult<String> {
+        let object = Client::default()
+            .object()
+            .read(&self.bucket, object_key)
+            .await
+            .with_context(|| {
+                format!(
+                    "Can not read the object '{object_key}' of the bucket '{}'",
+                    self.bucket
+                )
+            })?;
+
+        object
+            .download_url(expires_in_seconds)
+            .with_context(|| format!("Can not presign the download URL of '{object_key}'"))
+    }
+}
+
+/// [SnapshotArchiveReader] of the archives stored in a remote storage, the ranges are
+/// downloaded from a presigned URL with a `Range` header
+pub struct RemoteSnapshotArchiveReader {
+    presigner: Arc<dyn ArchiveUrlPresigner>,
+    http_client: reqwest::Client,
+}
+
+impl RemoteSnapshotArchiveReader {
+    /// RemoteSnapshotArchiveReader factory
+    pub fn new(presigner: Arc<dyn ArchiveUrlPresigner>) -> Self {
+        Self {
+            presigner,
+            http_client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl SnapshotArchiveReader for RemoteSnapshotArchiveReader {
+    async fn read_range(
+        &self,
+        object_key: &str,
+        start: u64,
+        end: u64,
+    ) -> StdResult<ArchiveRangeReader> {
+        let url = self
+            .presigner
+            .presign_get_url(object_key, PRESIGNED_URL_EXPIRATION_SECONDS)
+            .await?;
+        let response = self
+            .http_client
+            .get(url)
+            .header(RANGE, format!("bytes={start}-{end}"))
+            .send()
+            .await
+            .with_context(|| format!("Can not download a range of the archive '{object_key}'"))?;
+        if response.status() != StatusCode::PARTIAL_CONTENT {
+            return Err(anyhow!(
+                "Unexpected status '{}' when downloading a range of the archive '{object_key}'",
+                response.status()
+            ));
+        }
+
+        let stream = response.bytes_stream().map_err(std::io::Error::other);
+
+        Ok(Box::new(StreamReader::new(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::TempDir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn local_reader_reads_the_requested_range() {
+        let directory = TempDir::create("snapshot_archive_reader", "local_range");
+        let content: Vec<u8> = (0..=255).collect();
+        std::fs::write(directory.join("archive.tar.gz"), &content).unwrap();
+        let reader = LocalSnapshotArchiveReader::new(directory);
+
+        let mut range = vec![];
+        reader
+            .read_range("archive.tar.gz", 10, 19)
+            .await
+            .unwrap()
+            .read_to_end(&mut range)
+            .await
+            .unwrap();
+
+        assert_eq!(content[10..=19].to_vec(), range);
+    }
+}
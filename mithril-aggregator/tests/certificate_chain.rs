@@ -16,9 +16,10 @@ async fn certificate_chain() {
         k: 5,
         m: 100,
         phi_f: 0.95,
+        weighted: false,
     };
     let configuration = Configuration {
-        protocol_parameters: protocol_parameters.clone(),
+        protocol_parameters: Some(protocol_parameters.clone()),
         data_stores_directory: get_test_dir("certificate_chain"),
         ..Configuration::new_sample()
     };
@@ -16,9 +16,10 @@ async fn create_certificate() {
         k: 5,
         m: 150,
         phi_f: 0.95,
+        weighted: false,
     };
     let configuration = Configuration {
-        protocol_parameters: protocol_parameters.clone(),
+        protocol_parameters: Some(protocol_parameters.clone()),
         signed_entity_types: Some(SignedEntityTypeDiscriminants::CardanoTransactions.to_string()),
         data_stores_directory: get_test_dir("create_certificate"),
         cardano_transactions_signing_config: CardanoTransactionsSigningConfig {
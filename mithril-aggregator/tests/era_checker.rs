@@ -17,9 +17,10 @@ async fn testing_eras() {
         k: 5,
         m: 100,
         phi_f: 0.95,
+        weighted: false,
     };
     let configuration = Configuration {
-        protocol_parameters: protocol_parameters.clone(),
+        protocol_parameters: Some(protocol_parameters.clone()),
         data_stores_directory: get_test_dir("testing_eras"),
         ..Configuration::new_sample()
     };
@@ -50,6 +51,7 @@ async fn testing_eras() {
         k: 5,
         m: 100,
         phi_f: 0.95,
+        weighted: false,
     };
     tester.era_reader_adapter.set_markers(vec![
         EraMarker::new(&SupportedEra::dummy().to_string(), Some(Epoch(0))),
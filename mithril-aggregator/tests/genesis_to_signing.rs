@@ -13,9 +13,10 @@ async fn genesis_to_signing() {
         k: 5,
         m: 100,
         phi_f: 0.65,
+        weighted: false,
     };
     let configuration = Configuration {
-        protocol_parameters: protocol_parameters.clone(),
+        protocol_parameters: Some(protocol_parameters.clone()),
         data_stores_directory: get_test_dir("genesis_to_signing"),
         ..Configuration::new_sample()
     };
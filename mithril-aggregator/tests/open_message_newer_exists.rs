@@ -16,9 +16,10 @@ async fn open_message_newer_exists() {
         k: 5,
         m: 150,
         phi_f: 0.95,
+        weighted: false,
     };
     let configuration = Configuration {
-        protocol_parameters: protocol_parameters.clone(),
+        protocol_parameters: Some(protocol_parameters.clone()),
         data_stores_directory: get_test_dir("open_message_newer_exists"),
         ..Configuration::new_sample()
     };
@@ -4,7 +4,7 @@
 //! to manipulate Mithril certified types from a Mithril Aggregator:
 //! * Cardano DB: List, Show, download and verify
 //! * Mithril Stake Distribution: List, download and verify
-//
+//!
 //! You can find more information on how it works reading the [documentation website](https://mithril.network/doc/mithril/mithril-network/client).
 
 pub mod commands;
@@ -6,6 +6,7 @@ use wasm_bindgen::prelude::*;
 use mithril_client::{
     feedback::{FeedbackReceiver, MithrilEvent},
     CardanoTransactionsProofs, Client, ClientBuilder, MessageBuilder, MithrilCertificate,
+    VerifiedCardanoTransactions,
 };
 
 use crate::WasmResult;
@@ -294,6 +295,42 @@ impl MithrilUnstableClient {
     }
 }
 
+/// Cardano transactions verified by [verify_batch]
+#[derive(Serialize)]
+struct VerifiedCardanoTransactionsWasm {
+    certificate_hash: String,
+    certified_transactions: Vec<String>,
+}
+
+impl From<VerifiedCardanoTransactions> for VerifiedCardanoTransactionsWasm {
+    fn from(verified_transactions: VerifiedCardanoTransactions) -> Self {
+        Self {
+            certificate_hash: verified_transactions.certificate_hash().to_string(),
+            certified_transactions: verified_transactions.certified_transactions().to_vec(),
+        }
+    }
+}
+
+/// Verify a batch of cardano transactions proofs in a single call.
+///
+/// Returns an array with the result of the verification of each proof, in the same order as
+/// the given proofs: `{ Ok: { certificate_hash, certified_transactions } }` if the proof is
+/// valid or `{ Err: "<error>" }` otherwise.
+#[wasm_bindgen]
+pub fn verify_batch(proofs: Vec<CardanoTransactionsProofs>) -> WasmResult {
+    let results: Vec<Result<VerifiedCardanoTransactionsWasm, String>> = proofs
+        .iter()
+        .map(|proof| {
+            proof
+                .verify()
+                .map(VerifiedCardanoTransactionsWasm::from)
+                .map_err(|err| format!("{err:?}"))
+        })
+        .collect();
+
+    Ok(serde_wasm_bindgen::to_value(&results)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -556,4 +593,49 @@ mod tests {
             .await
             .expect("Compute tx proof message for matching cert failed");
     }
+
+    #[derive(serde::Deserialize)]
+    struct VerifiedTransactions {
+        certified_transactions: Vec<String>,
+    }
+
+    async fn get_cardano_transaction_proofs(
+        client: &MithrilClient,
+        tx_hash: &str,
+    ) -> CardanoTransactionsProofs {
+        client
+            .unstable
+            .get_cardano_transaction_proofs(Box::new([JsValue::from(tx_hash)]))
+            .await
+            .expect("get_cardano_transaction_proofs should not fail")
+    }
+
+    #[wasm_bindgen_test]
+    async fn verify_batch_should_return_the_results_in_the_order_of_the_proofs() {
+        let tx_hash = test_data::proof_transaction_hashes()[0];
+        let client = get_mithril_client();
+        let invalid_proof = CardanoTransactionsProofs::default();
+
+        let results_js_value = verify_batch(vec![
+            get_cardano_transaction_proofs(&client, tx_hash).await,
+            invalid_proof.clone(),
+            get_cardano_transaction_proofs(&client, tx_hash).await,
+            invalid_proof,
+        ])
+        .expect("verify_batch should not fail");
+        let results = serde_wasm_bindgen::from_value::<Vec<Result<VerifiedTransactions, String>>>(
+            results_js_value,
+        )
+        .expect("conversion should not fail");
+
+        assert_eq!(4, results.len());
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+        assert!(results[3].is_err());
+        assert_eq!(
+            vec![tx_hash.to_string()],
+            results[0].as_ref().unwrap().certified_transactions
+        );
+    }
 }
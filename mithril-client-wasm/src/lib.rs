@@ -4,7 +4,7 @@
 
 mod client_wasm;
 
-pub use client_wasm::MithrilClient;
+pub use client_wasm::{verify_batch, MithrilClient};
 
 #[cfg(test)]
 mod test_data;
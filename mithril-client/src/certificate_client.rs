@@ -295,7 +295,7 @@ mod tests {
     use mockall::predicate::eq;
 
     use crate::aggregator_client::MockAggregatorHTTPClient;
-    use crate::feedback::StackFeedbackReceiver;
+    use crate::feedback::{FeedbackReceiver, StackFeedbackReceiver};
     use crate::test_utils;
 
     use super::*;
@@ -440,7 +440,7 @@ mod tests {
                 MithrilCertificateVerifier::new(
                     aggregator_client,
                     &verification_key,
-                    FeedbackSender::new(&[feedback_receiver.clone()]),
+                    FeedbackSender::new(&[feedback_receiver.clone() as Arc<dyn FeedbackReceiver>]),
                     test_utils::test_logger(),
                 )
                 .unwrap(),
@@ -296,7 +296,7 @@ mod tests {
     #[tokio::test]
     async fn send_event_same_thread() {
         let receiver = Arc::new(StackFeedbackReceiver::new());
-        let sender = FeedbackSender::new(&[receiver.clone()]);
+        let sender = FeedbackSender::new(&[receiver.clone() as Arc<dyn FeedbackReceiver>]);
 
         sender
             .send_event(SnapshotDownloadStarted {
@@ -423,7 +423,7 @@ mod tests {
     async fn send_event_in_one_thread_and_receive_in_another_thread() {
         let receiver = Arc::new(StackFeedbackReceiver::new());
         let receiver2 = receiver.clone();
-        let sender = FeedbackSender::new(&[receiver.clone()]);
+        let sender = FeedbackSender::new(&[receiver.clone() as Arc<dyn FeedbackReceiver>]);
         let mut join_set = JoinSet::new();
 
         join_set.spawn(async move {
@@ -258,7 +258,7 @@ impl SnapshotClient {
 mod tests_download {
     use crate::{
         aggregator_client::MockAggregatorHTTPClient,
-        feedback::{MithrilEvent, StackFeedbackReceiver},
+        feedback::{FeedbackReceiver, MithrilEvent, StackFeedbackReceiver},
         snapshot_downloader::MockHttpSnapshotDownloader,
         test_utils,
     };
@@ -277,7 +277,7 @@ mod tests_download {
         let client = SnapshotClient::new(
             Arc::new(MockAggregatorHTTPClient::new()),
             Arc::new(snapshot_downloader),
-            FeedbackSender::new(&[feedback_receiver.clone()]),
+            FeedbackSender::new(&[feedback_receiver.clone() as Arc<dyn FeedbackReceiver>]),
             test_utils::test_logger(),
         );
         let snapshot = Snapshot::dummy();
@@ -5,7 +5,7 @@
 //!
 //! Snapshots locations can be of various kinds, right now we only support HTTP
 //! download (using the [HttpSnapshotDownloader]) but other types may be added in
-//! the future.
+//! the future. IPFS locations (`ipfs://<cid>`) are downloaded through an HTTP IPFS gateway.
 
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
@@ -44,10 +44,17 @@ pub trait SnapshotDownloader: Sync + Send {
     async fn probe(&self, location: &str) -> MithrilResult<()>;
 }
 
+/// Scheme of the snapshot locations stored on IPFS
+pub const IPFS_LOCATION_SCHEME: &str = "ipfs://";
+
+/// Default IPFS gateway used to download the snapshots stored on IPFS
+pub const DEFAULT_IPFS_GATEWAY_URL: &str = "https://ipfs.io";
+
 /// A snapshot downloader that only handles download through HTTP.
 pub struct HttpSnapshotDownloader {
     http_client: reqwest::Client,
     feedback_sender: FeedbackSender,
+    ipfs_gateway_url: String,
     logger: Logger,
 }
 
@@ -61,13 +68,28 @@ impl HttpSnapshotDownloader {
         Ok(Self {
             http_client,
             feedback_sender,
+            ipfs_gateway_url: DEFAULT_IPFS_GATEWAY_URL.to_string(),
             logger,
         })
     }
 
+    /// Set the IPFS gateway used to download the snapshots stored on IPFS
+    pub fn with_ipfs_gateway_url(mut self, ipfs_gateway_url: &str) -> Self {
+        self.ipfs_gateway_url = ipfs_gateway_url.to_string();
+        self
+    }
+
+    /// Get the HTTP url of a snapshot location, IPFS locations are translated to a gateway url
+    fn resolve_location(&self, location: &str) -> String {
+        match location.strip_prefix(IPFS_LOCATION_SCHEME) {
+            Some(cid) => format!("{}/ipfs/{cid}", self.ipfs_gateway_url.trim_end_matches('/')),
+            None => location.to_string(),
+        }
+    }
+
     async fn get(&self, location: &str) -> MithrilResult<Response> {
         debug!(self.logger, "GET Snapshot location='{location}'.");
-        let request_builder = self.http_client.get(location);
+        let request_builder = self.http_client.get(self.resolve_location(location));
         let response = request_builder.send().await.with_context(|| {
             format!("Cannot perform a GET for the snapshot (location='{location}')")
         })?;
@@ -143,7 +165,7 @@ impl SnapshotDownloader for HttpSnapshotDownloader {
     async fn probe(&self, location: &str) -> MithrilResult<()> {
         debug!(self.logger, "HEAD Snapshot location='{location}'.");
 
-        let request_builder = self.http_client.head(location);
+        let request_builder = self.http_client.head(self.resolve_location(location));
         let response = request_builder.send().await.with_context(|| {
             format!("Cannot perform a HEAD for snapshot at location='{location}'")
         })?;
@@ -155,3 +177,36 @@ impl SnapshotDownloader for HttpSnapshotDownloader {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils;
+
+    use super::*;
+
+    fn build_downloader() -> HttpSnapshotDownloader {
+        HttpSnapshotDownloader::new(FeedbackSender::new(&[]), test_utils::test_logger()).unwrap()
+    }
+
+    #[test]
+    fn http_locations_are_not_translated() {
+        let downloader = build_downloader();
+
+        assert_eq!(
+            "https://example.com/snapshot.tar.gz",
+            downloader.resolve_location("https://example.com/snapshot.tar.gz")
+        );
+    }
+
+    #[test]
+    fn ipfs_locations_are_translated_to_the_gateway_url() {
+        let downloader = build_downloader().with_ipfs_gateway_url("https://gateway.example/");
+
+        assert_eq!(
+            "https://gateway.example/ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+            downloader.resolve_location(
+                "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+            )
+        );
+    }
+}
@@ -146,7 +146,8 @@ mod proof {
     use super::*;
     use mithril_client::common::ProtocolMessagePartKey;
     use mithril_client::{CardanoTransactionsProofs, CardanoTransactionsSetProof};
-    use mithril_common::crypto_helper::{MKProof, ProtocolMkProof};
+    use mithril_common::crypto_helper::{MKProof, MKTreeNode, ProtocolMkProof};
+    use mithril_common::entities::CardanoTransaction;
 
     impl FakeAggregator {
         pub fn spawn_with_transactions_proofs(
@@ -154,18 +155,30 @@ mod proof {
             tx_hashes: &[&str],
             certificate_hash: &str,
         ) -> TestHttpServer {
-            let proof = MKProof::from_leaves(tx_hashes).unwrap();
+            let block_hashes: Vec<String> = tx_hashes
+                .iter()
+                .map(|h| format!("block-hash-of-{h}"))
+                .collect();
+            let leaves: Vec<MKTreeNode> = tx_hashes
+                .iter()
+                .zip(&block_hashes)
+                .map(|(hash, block_hash)| CardanoTransaction::compute_leaf(hash, block_hash))
+                .collect();
+            let proof = MKProof::from_leaves(&leaves).unwrap();
 
             let proofs_json = serde_json::to_string(&CardanoTransactionsProofs {
                 certificate_hash: certificate_hash.to_string(),
                 certified_transactions: vec![CardanoTransactionsSetProof {
                     transactions_hashes: tx_hashes.iter().map(|h| h.to_string()).collect(),
+                    block_hashes,
                     proof: ProtocolMkProof::new(proof.to_owned().into())
                         .to_json_hex()
                         .unwrap(),
                 }],
                 non_certified_transactions: vec![],
                 latest_block_number: 9999,
+                max_transactions: None,
+                max_proof_bytes: None,
             })
             .unwrap();
 
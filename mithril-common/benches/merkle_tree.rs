@@ -1,3 +1,4 @@
+use blake2::{Blake2s256, Digest};
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use mithril_common::crypto_helper::{MKTree, MKTreeNode};
 
@@ -33,6 +34,26 @@ fn create_merkle_tree_root_benches(c: &mut Criterion) {
     group.finish();
 }
 
+fn create_merkle_tree_parallel_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("create_merkle_tree_parallel");
+    let hashes: Vec<[u8; 32]> = (0..M)
+        .map(|i| Blake2s256::digest(format!("bench-{i}")).into())
+        .collect();
+    for workers in [1, 4, 8] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{M}_leaves_{workers}_workers")),
+            &workers,
+            |b, &workers| {
+                b.iter(|| {
+                    let mk_tree = MKTree::from_hashes_parallel(hashes.clone(), workers).unwrap();
+                    mk_tree.compute_root().unwrap();
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
 fn create_merkle_tree_proof_benches(c: &mut Criterion) {
     let mut group = c.benchmark_group("create_merkle_tree_proof");
     for total_leaves in TOTAL_LEAVES_BENCHES.iter() {
@@ -73,6 +94,7 @@ criterion_group!(
     config = Criterion::default().sample_size(10);
     targets=
       create_merkle_tree_root_benches,
+      create_merkle_tree_parallel_benches,
       create_merkle_tree_proof_benches,
       verify_merkle_tree_proof_benches
 );
@@ -0,0 +1,162 @@
+use anyhow::Context;
+use pallas_addresses::Network;
+use pallas_traverse::MultiEraBlock;
+use thiserror::Error;
+
+use crate::cardano_block_scanner::ScannedBlock;
+use crate::entities::{CardanoNetwork, CardanoTransaction, ImmutableFileNumber};
+use crate::{MagicId, StdResult};
+
+/// Error raised when a transaction doesn't belong to the network of the parser
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("transaction '{transaction_hash}' has an output on the {found:?} network, expected the {expected:?} network")]
+pub struct TransactionNetworkMismatchError {
+    /// Hash of the transaction
+    pub transaction_hash: String,
+    /// Network of the parser
+    pub expected: Network,
+    /// Network of the addresses of the transaction
+    pub found: Network,
+}
+
+/// Parser of the CBOR encoded blocks of the Shelley based eras, which extracts their
+/// [Cardano transactions][CardanoTransaction].
+///
+/// Outputs holding multi-assets values (Mary era onwards) are supported.
+pub struct CborTransactionParser {
+    network: Network,
+}
+
+impl CborTransactionParser {
+    /// Create a parser of the blocks of the network with the given magic
+    pub fn new(network_magic: MagicId) -> Self {
+        let network = if network_magic == CardanoNetwork::MainNet.code() {
+            Network::Mainnet
+        } else {
+            Network::Testnet
+        };
+
+        Self { network }
+    }
+
+    /// Decode the given block, the number, slot and hash of the transactions are read
+    /// from the block header.
+    ///
+    /// Fail if an output of a transaction is sent to an address of another network.
+    pub fn parse_block(
+        &self,
+        block: &[u8],
+        immutable_file_number: ImmutableFileNumber,
+    ) -> StdResult<Vec<CardanoTransaction>> {
+        let multi_era_block =
+            MultiEraBlock::decode(block).with_context(|| "Could not decode CBOR block")?;
+        self.check_network(&multi_era_block)?;
+
+        Ok(ScannedBlock::convert(multi_era_block, immutable_file_number).into_transactions())
+    }
+
+    fn check_network(&self, block: &MultiEraBlock) -> StdResult<()> {
+        for tx in block.txs() {
+            for output in tx.outputs() {
+                let address = output.address().with_context(|| {
+                    format!("Could not decode output address of transaction '{}'", tx.hash())
+                })?;
+
+                // Byron addresses don't carry a network id
+                match address.network() {
+                    Some(network) if network != self.network => {
+                        return Err(TransactionNetworkMismatchError {
+                            transaction_hash: tx.hash().to_string(),
+                            expected: self.network,
+                            found: network,
+                        }
+                        .into());
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_block(name: &str) -> Vec<u8> {
+        let hex_block =
+            std::fs::read_to_string(format!("../mithril-test-lab/test_data/blocks/{name}.block"))
+                .unwrap();
+
+        hex::decode(hex_block.trim()).unwrap()
+    }
+
+    fn mainnet_parser() -> CborTransactionParser {
+        CborTransactionParser::new(CardanoNetwork::MainNet.code())
+    }
+
+    #[test]
+    fn parse_shelley_block_transactions() {
+        let transactions = mainnet_parser()
+            .parse_block(&read_block("shelley1"), 12)
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                "48347a50990c63680b9c4af9808bbca2e2e9782fe7f8b2f811ac6c51952863bc",
+                "9d1ad32177c90c866be4e29650b7bbaddec7f8707cf7c2a4d0fc80faa32a04e3",
+                "fdb308fe3c32d0b27eea6af70e0086b8c3aa8efe7c79f0322351b8083e853859",
+                "8ac3db74ed1f93b232c37e3e1a1509d1977cf65fd54a38c438273c1925dbfe6f",
+            ],
+            transactions
+                .iter()
+                .map(|t| t.transaction_hash.as_str())
+                .collect::<Vec<_>>()
+        );
+        for transaction in transactions {
+            assert_eq!(7948610, transaction.slot_number);
+            assert_eq!(4662237, transaction.block_number);
+            assert_eq!(
+                "7dce9cfd6d44c5eb58eb5200532b3fa04086ee26cbdd712a4dd04f1b1ef90ca5",
+                transaction.block_hash
+            );
+            assert_eq!(12, transaction.immutable_file_number);
+        }
+    }
+
+    #[test]
+    fn parse_block_with_multi_assets_outputs() {
+        let transactions = mainnet_parser()
+            .parse_block(&read_block("mary1"), 12)
+            .unwrap();
+
+        assert_eq!(14, transactions.len());
+        assert_eq!(
+            "39949ce990b150f7f1e5903114080ab6f8cca777c07ac76fcb32c2d9353fbf56",
+            transactions[0].transaction_hash
+        );
+        assert!(transactions.iter().all(|t| t.slot_number == 27388606));
+    }
+
+    #[test]
+    fn parse_block_of_another_network_fails() {
+        let error = CborTransactionParser::new(1)
+            .parse_block(&read_block("shelley1"), 12)
+            .unwrap_err();
+
+        let mismatch = error
+            .downcast_ref::<TransactionNetworkMismatchError>()
+            .expect("should be a network mismatch error");
+        assert_eq!(Network::Testnet, mismatch.expected);
+        assert_eq!(Network::Mainnet, mismatch.found);
+    }
+
+    #[test]
+    fn parse_invalid_cbor_fails() {
+        mainnet_parser()
+            .parse_block(&[0x82, 0x02, 0xff], 12)
+            .expect_err("parsing invalid CBOR should fail");
+    }
+}
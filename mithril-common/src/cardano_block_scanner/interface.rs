@@ -35,10 +35,9 @@ use crate::StdResult;
 ///         }
 ///     }
 ///
-///     #[test]
 ///     fn test_mock() {
 ///         let mut mock = MockBlockScannerImpl::new();
-///         mock.expect_scan().return_once(|_, _| {
+///         mock.expect_scan().return_once(|_, _, _| {
 ///             Err(anyhow!("parse error"))
 ///         });
 ///     }
@@ -1,11 +1,13 @@
 //! The module used for parsing Cardano transactions
 mod block_scanner;
+mod cbor_transaction_parser;
 mod dumb_block_scanner;
 mod immutable_block_streamer;
 mod interface;
 mod scanned_block;
 
 pub use block_scanner::*;
+pub use cbor_transaction_parser::*;
 pub use dumb_block_scanner::*;
 pub use immutable_block_streamer::*;
 pub use interface::*;
@@ -22,6 +22,11 @@ pub struct FakeObserver {
     ///
     /// [get_current_datums]: ChainObserver::get_current_datums
     pub datums: RwLock<Vec<TxDatum>>,
+
+    /// A [KESPeriod], used by [get_current_kes_period]
+    ///
+    /// [get_current_kes_period]: ChainObserver::get_current_kes_period
+    pub current_kes_period: RwLock<KESPeriod>,
 }
 
 impl FakeObserver {
@@ -31,6 +36,7 @@ impl FakeObserver {
             signers: RwLock::new(vec![]),
             current_time_point: RwLock::new(current_time_point.clone()),
             datums: RwLock::new(vec![]),
+            current_kes_period: RwLock::new(0),
         }
     }
 
@@ -97,6 +103,13 @@ impl FakeObserver {
         let mut datums = self.datums.write().await;
         *datums = new_datums;
     }
+
+    /// Set the KES period returned by
+    /// [get_current_kes_period][ChainObserver::get_current_kes_period].
+    pub async fn set_current_kes_period(&self, new_current_kes_period: KESPeriod) {
+        let mut current_kes_period = self.current_kes_period.write().await;
+        *current_kes_period = new_current_kes_period;
+    }
 }
 
 impl Default for FakeObserver {
@@ -153,7 +166,7 @@ impl ChainObserver for FakeObserver {
         &self,
         _opcert: &OpCert,
     ) -> Result<Option<KESPeriod>, ChainObserverError> {
-        Ok(Some(0))
+        Ok(Some(*self.current_kes_period.read().await))
     }
 }
 
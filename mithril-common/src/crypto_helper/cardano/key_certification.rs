@@ -12,7 +12,7 @@ use crate::{
         },
         ProtocolOpCert,
     },
-    StdError, StdResult,
+    entities, StdError, StdResult,
 };
 
 use mithril_stm::key_reg::{ClosedKeyReg, KeyReg};
@@ -30,6 +30,7 @@ use kes_summed_ed25519::traits::{KesSig, KesSk};
 use rand_core::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::path::Path;
 use thiserror::Error;
 
@@ -105,6 +106,26 @@ pub struct StmInitializerWrapper {
     kes_signature: Option<Sum6KesSig>, // todo: The option is ONLY for a smooth transition. We have to remove this.
 }
 
+/// Version of a [StmInitializerWrapper], identifying the protocol parameters it was set up
+/// with.
+///
+/// An initializer can only sign the epochs whose protocol parameters have the same version.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ProtocolInitializerVersion(String);
+
+impl ProtocolInitializerVersion {
+    /// Version of the initializers set up with the given protocol parameters
+    pub fn from_protocol_parameters(params: &ProtocolParameters) -> Self {
+        Self(entities::ProtocolParameters::new(params.k, params.m, params.phi_f).compute_hash())
+    }
+}
+
+impl Display for ProtocolInitializerVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Wrapper structure for [MithrilStm:KeyReg](mithril_stm::key_reg::KeyReg).
 /// The wrapper not only contains a map between `Mithril vkey <-> Stake`, but also
 /// a map `PoolID <-> Stake`. This information is recovered from the node state, and
@@ -179,6 +200,11 @@ impl StmInitializerWrapper {
         self.stm_initializer.params
     }
 
+    /// Version of the initializer, computed from its protocol parameters
+    pub fn version(&self) -> ProtocolInitializerVersion {
+        ProtocolInitializerVersion::from_protocol_parameters(&self.stm_initializer.params)
+    }
+
     /// Extract the stake of the party
     pub fn get_stake(&self) -> Stake {
         self.stm_initializer.stake
@@ -67,6 +67,7 @@ pub fn encode_bech32(human_readable_part: &str, data: &[u8]) -> StdResult<String
     bech32::encode::<Bech32>(human_readable_part, data).map_err(|e| anyhow!(e))
 }
 
+/// Tests of the key codec
 #[cfg(test)]
 pub mod tests {
     use hex::FromHex;
@@ -10,7 +10,7 @@ use std::{
 
 use crate::{resource_pool::Reset, StdError, StdResult};
 
-use super::{MKProof, MKTree, MKTreeNode};
+use super::{CompressedMKProof, MKProof, MKProofNodesTable, MKTree, MKTreeNode, SparseMKProof};
 
 /// The trait implemented by the keys of a MKMap
 pub trait MKMapKey: PartialEq + Eq + PartialOrd + Ord + Clone + Hash + Into<MKTreeNode> {}
@@ -329,6 +329,31 @@ impl<K: MKMapKey> MKMapProof<K> {
             .ok_or(anyhow!("MKMapProof does not contain leaf {:?}", leaf))
     }
 
+    /// Compress the master proof and the sub proofs with [MKProof::compress_path]
+    pub fn compress_path(&self) -> CompressedMKMapProof<K> {
+        CompressedMKMapProof {
+            master_proof: self.master_proof.compress_path(),
+            sub_proofs: self
+                .sub_proofs
+                .iter()
+                .map(|(k, p)| (k.to_owned(), p.compress_path()))
+                .collect(),
+        }
+    }
+
+    /// Convert the master proof and the sub proofs with [MKProof::to_sparse], their nodes are
+    /// stored in the given table
+    pub fn to_sparse(&self, nodes: &mut MKProofNodesTable) -> SparseMKMapProof<K> {
+        SparseMKMapProof {
+            master_proof: self.master_proof.to_sparse(nodes),
+            sub_proofs: self
+                .sub_proofs
+                .iter()
+                .map(|(k, p)| (k.to_owned(), p.to_sparse(nodes)))
+                .collect(),
+        }
+    }
+
     /// List the leaves of the merkelized map proof
     pub fn leaves(&self) -> Vec<MKTreeNode> {
         if self.sub_proofs.is_empty() {
@@ -344,6 +369,48 @@ impl<K: MKMapKey> MKMapProof<K> {
     }
 }
 
+/// A [MKMapProof] compressed with [MKMapProof::compress_path]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CompressedMKMapProof<K: MKMapKey> {
+    master_proof: CompressedMKProof,
+    sub_proofs: Vec<(K, CompressedMKMapProof<K>)>,
+}
+
+impl<K: MKMapKey> CompressedMKMapProof<K> {
+    /// Rebuild the [MKMapProof]
+    pub fn decompress(&self) -> StdResult<MKMapProof<K>> {
+        Ok(MKMapProof {
+            master_proof: self.master_proof.decompress()?,
+            sub_proofs: self
+                .sub_proofs
+                .iter()
+                .map(|(k, p)| Ok((k.to_owned(), p.decompress()?)))
+                .collect::<StdResult<Vec<_>>>()?,
+        })
+    }
+}
+
+/// A [MKMapProof] converted with [MKMapProof::to_sparse]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SparseMKMapProof<K: MKMapKey> {
+    master_proof: SparseMKProof,
+    sub_proofs: Vec<(K, SparseMKMapProof<K>)>,
+}
+
+impl<K: MKMapKey> SparseMKMapProof<K> {
+    /// Rebuild the [MKMapProof] from the nodes of the given table
+    pub fn to_proof(&self, nodes: &MKProofNodesTable) -> StdResult<MKMapProof<K>> {
+        Ok(MKMapProof {
+            master_proof: self.master_proof.to_proof(nodes)?,
+            sub_proofs: self
+                .sub_proofs
+                .iter()
+                .map(|(k, p)| Ok((k.to_owned(), p.to_proof(nodes)?)))
+                .collect::<StdResult<Vec<_>>>()?,
+        })
+    }
+}
+
 impl<K: MKMapKey> From<MKProof> for MKMapProof<K> {
     fn from(other: MKProof) -> Self {
         MKMapProof::new(other, BTreeMap::default())
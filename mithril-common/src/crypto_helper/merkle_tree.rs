@@ -3,7 +3,9 @@ use blake2::{Blake2s256, Digest};
 use ckb_merkle_mountain_range::{
     MMRStoreReadOps, MMRStoreWriteOps, Merge, MerkleProof, Result as MMRResult, MMR,
 };
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
 use std::{
     collections::{BTreeMap, HashMap},
     fmt::Display,
@@ -13,6 +15,9 @@ use std::{
 
 use crate::{StdError, StdResult};
 
+#[cfg(target_family = "wasm")]
+use wasm_bindgen::prelude::*;
+
 /// Alias for a byte
 type Bytes = Vec<u8>;
 
@@ -119,6 +124,7 @@ impl Merge for MergeMKTreeNode {
 
 /// A Merkle proof
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(target_family = "wasm", wasm_bindgen)]
 pub struct MKProof {
     inner_root: Arc<MKTreeNode>,
     inner_leaves: Vec<(MKTreeLeafPosition, Arc<MKTreeNode>)>,
@@ -160,6 +166,79 @@ impl MKProof {
             .collect::<Vec<_>>()
     }
 
+    /// Encode the proof in its compact format: CBOR with the hashes stored as byte strings.
+    pub fn to_compact_bytes(&self) -> StdResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&MKProofCompactRepr::from(self), &mut bytes)
+            .with_context(|| "MKProof can not serialize to compact bytes")?;
+
+        Ok(bytes)
+    }
+
+    /// Decode a proof encoded with [MKProof::to_compact_bytes].
+    pub fn from_compact_bytes(bytes: &[u8]) -> StdResult<Self> {
+        let repr: MKProofCompactRepr = ciborium::de::from_reader(bytes)
+            .with_context(|| "MKProof can not deserialize from compact bytes")?;
+
+        Ok(repr.into())
+    }
+
+    /// Hex representation of [MKProof::to_compact_bytes].
+    pub fn to_compact_hex(&self) -> StdResult<String> {
+        Ok(hex::encode(self.to_compact_bytes()?))
+    }
+
+    /// Decode a proof from the hex representation of its compact format.
+    pub fn from_compact_hex(hex: &str) -> StdResult<Self> {
+        let bytes =
+            hex::decode(hex).with_context(|| "MKProof can not decode compact hex string")?;
+
+        Self::from_compact_bytes(&bytes)
+    }
+
+    /// Compress the proof by dropping the nodes that can be recomputed from the rest of its
+    /// path: the root is omitted and the leaves positions are delta encoded.
+    pub fn compress_path(&self) -> CompressedMKProof {
+        let mut previous_position = 0;
+        let leaves = self
+            .inner_leaves
+            .iter()
+            .map(|(position, leaf)| {
+                let position_delta = *position as i64 - previous_position as i64;
+                previous_position = *position;
+                (position_delta, ByteBuf::from(leaf.hash.clone()))
+            })
+            .collect();
+
+        CompressedMKProof {
+            leaves,
+            proof_size: self.inner_proof_size,
+            proof_items: self
+                .inner_proof_items
+                .iter()
+                .map(|item| ByteBuf::from(item.hash.clone()))
+                .collect(),
+        }
+    }
+
+    /// Convert the proof to a [SparseMKProof] which nodes are stored in the given table,
+    /// shared with the other proofs converted with the same table.
+    pub fn to_sparse(&self, nodes: &mut MKProofNodesTable) -> SparseMKProof {
+        SparseMKProof {
+            leaves: self
+                .inner_leaves
+                .iter()
+                .map(|(position, leaf)| (*position, nodes.insert(leaf)))
+                .collect(),
+            proof_size: self.inner_proof_size,
+            proof_items: self
+                .inner_proof_items
+                .iter()
+                .map(|item| nodes.insert(item))
+                .collect(),
+        }
+    }
+
     cfg_test_tools! {
         /// Build a [MKProof] based on the given leaves (*Test only*).
         pub fn from_leaves<T: Into<MKTreeNode> + Clone>(
@@ -188,6 +267,189 @@ impl MKProof {
     }
 }
 
+#[cfg(target_family = "wasm")]
+#[wasm_bindgen]
+impl MKProof {
+    /// Hex representation of the proof in its compact format
+    #[wasm_bindgen(js_name = "to_compact_hex")]
+    pub fn wasm_to_compact_hex(&self) -> Result<String, JsValue> {
+        self.to_compact_hex()
+            .map_err(|e| JsValue::from_str(&format!("{e:?}")))
+    }
+
+    /// Decode a proof from the hex representation of its compact format
+    #[wasm_bindgen(js_name = "from_compact_hex")]
+    pub fn wasm_from_compact_hex(hex: &str) -> Result<MKProof, JsValue> {
+        Self::from_compact_hex(hex).map_err(|e| JsValue::from_str(&format!("{e:?}")))
+    }
+}
+
+/// Representation of a [MKProof] used by its compact format, the hashes are stored as
+/// byte strings instead of arrays of numbers.
+#[derive(Serialize, Deserialize)]
+struct MKProofCompactRepr {
+    root: ByteBuf,
+    leaves: Vec<(MKTreeLeafPosition, ByteBuf)>,
+    proof_size: u64,
+    proof_items: Vec<ByteBuf>,
+}
+
+impl From<&MKProof> for MKProofCompactRepr {
+    fn from(proof: &MKProof) -> Self {
+        Self {
+            root: ByteBuf::from(proof.inner_root.hash.clone()),
+            leaves: proof
+                .inner_leaves
+                .iter()
+                .map(|(position, leaf)| (*position, ByteBuf::from(leaf.hash.clone())))
+                .collect(),
+            proof_size: proof.inner_proof_size,
+            proof_items: proof
+                .inner_proof_items
+                .iter()
+                .map(|item| ByteBuf::from(item.hash.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl From<MKProofCompactRepr> for MKProof {
+    fn from(repr: MKProofCompactRepr) -> Self {
+        let to_node = |bytes: ByteBuf| Arc::new(MKTreeNode::new(bytes.into_vec()));
+
+        Self {
+            inner_root: to_node(repr.root),
+            inner_leaves: repr
+                .leaves
+                .into_iter()
+                .map(|(position, leaf)| (position, to_node(leaf)))
+                .collect(),
+            inner_proof_size: repr.proof_size,
+            inner_proof_items: repr.proof_items.into_iter().map(to_node).collect(),
+        }
+    }
+}
+
+/// A [MKProof] compressed with [MKProof::compress_path].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CompressedMKProof {
+    leaves: Vec<(i64, ByteBuf)>,
+    proof_size: u64,
+    proof_items: Vec<ByteBuf>,
+}
+
+impl CompressedMKProof {
+    /// Rebuild the [MKProof], its root is recomputed from its leaves and proof items.
+    pub fn decompress(&self) -> StdResult<MKProof> {
+        let to_node = |bytes: &ByteBuf| Arc::new(MKTreeNode::new(bytes.to_vec()));
+        let mut previous_position: i64 = 0;
+        let inner_leaves = self
+            .leaves
+            .iter()
+            .map(|(position_delta, leaf)| {
+                let position = previous_position
+                    .checked_add(*position_delta)
+                    .filter(|position| *position >= 0)
+                    .ok_or_else(|| anyhow!("Invalid leaf position in CompressedMKProof"))?;
+                previous_position = position;
+                Ok((position as MKTreeLeafPosition, to_node(leaf)))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let inner_proof_items: Vec<_> = self.proof_items.iter().map(to_node).collect();
+        let inner_root = MerkleProof::<Arc<MKTreeNode>, MergeMKTreeNode>::new(
+            self.proof_size,
+            inner_proof_items.clone(),
+        )
+        .calculate_root(inner_leaves.clone())
+        .with_context(|| "CompressedMKProof could not compute the root of the proof")?;
+
+        Ok(MKProof {
+            inner_root,
+            inner_leaves,
+            inner_proof_size: self.proof_size,
+            inner_proof_items,
+        })
+    }
+}
+
+/// Table of the distinct nodes of several [SparseMKProof]s, each node is stored once however
+/// many proofs it belongs to.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MKProofNodesTable {
+    nodes: Vec<ByteBuf>,
+    #[serde(skip)]
+    indexes: HashMap<Bytes, usize>,
+}
+
+impl MKProofNodesTable {
+    /// Number of distinct nodes in the table
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Check if the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn insert(&mut self, node: &MKTreeNode) -> usize {
+        if let Some(index) = self.indexes.get(&node.hash) {
+            return *index;
+        }
+        let index = self.nodes.len();
+        self.nodes.push(ByteBuf::from(node.hash.clone()));
+        self.indexes.insert(node.hash.clone(), index);
+
+        index
+    }
+
+    fn get(&self, index: usize) -> StdResult<Arc<MKTreeNode>> {
+        self.nodes
+            .get(index)
+            .map(|bytes| Arc::new(MKTreeNode::new(bytes.to_vec())))
+            .ok_or_else(|| anyhow!("Node {index} not found in the MKProofNodesTable"))
+    }
+}
+
+/// A [MKProof] converted with [MKProof::to_sparse], its nodes are indexes in a
+/// [MKProofNodesTable] and its root is omitted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SparseMKProof {
+    leaves: Vec<(MKTreeLeafPosition, usize)>,
+    proof_size: u64,
+    proof_items: Vec<usize>,
+}
+
+impl SparseMKProof {
+    /// Rebuild the [MKProof] from the nodes of the given table, its root is recomputed from
+    /// its leaves and proof items.
+    pub fn to_proof(&self, nodes: &MKProofNodesTable) -> StdResult<MKProof> {
+        let inner_leaves = self
+            .leaves
+            .iter()
+            .map(|(position, index)| Ok((*position, nodes.get(*index)?)))
+            .collect::<StdResult<Vec<_>>>()?;
+        let inner_proof_items = self
+            .proof_items
+            .iter()
+            .map(|index| nodes.get(*index))
+            .collect::<StdResult<Vec<_>>>()?;
+        let inner_root = MerkleProof::<Arc<MKTreeNode>, MergeMKTreeNode>::new(
+            self.proof_size,
+            inner_proof_items.clone(),
+        )
+        .calculate_root(inner_leaves.clone())
+        .with_context(|| "SparseMKProof could not compute the root of the proof")?;
+
+        Ok(MKProof {
+            inner_root,
+            inner_leaves,
+            inner_proof_size: self.proof_size,
+            inner_proof_items,
+        })
+    }
+}
+
 impl From<MKProof> for MKTreeNode {
     fn from(other: MKProof) -> Self {
         other.root().to_owned()
@@ -268,6 +530,70 @@ impl MKTree {
         Ok(())
     }
 
+    /// MKTree factory computing the nodes level by level with the given number of parallel
+    /// workers, the tree is the same as the one created by [MKTree::new] with the same leaves.
+    pub fn from_hashes_parallel(hashes: Vec<[u8; 32]>, workers: usize) -> StdResult<Self> {
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(workers.max(1))
+            .build()
+            .with_context(|| "Could not create the Merkle tree thread pool")?;
+        let total_leaves = hashes.len() as u64;
+        let mut inner_leaves = HashMap::with_capacity(hashes.len());
+        let mut inner_store = HashMap::with_capacity(2 * hashes.len());
+
+        let mut level = hashes
+            .into_iter()
+            .map(|hash| Arc::new(MKTreeNode::new(hash.to_vec())))
+            .collect::<Vec<_>>();
+        let mut height = 0;
+        loop {
+            for (index, node) in level.iter().enumerate() {
+                let position = Self::node_position(height, index as u64);
+                if height == 0 {
+                    inner_leaves.insert(node.clone(), position);
+                }
+                inner_store.insert(position, node.clone());
+            }
+            if level.len() < 2 {
+                break;
+            }
+
+            // An odd node is the peak of a smaller tree, it's merged with the other peaks
+            // when the root is computed
+            level = thread_pool.install(|| {
+                level
+                    .par_chunks_exact(2)
+                    .map(|pair| Arc::new(&*pair[0] + &*pair[1]))
+                    .collect()
+            });
+            height += 1;
+        }
+
+        let mmr_size = 2 * total_leaves - total_leaves.count_ones() as u64;
+        let inner_tree = MMR::new(
+            mmr_size,
+            MKTreeStore {
+                inner_store: RwLock::new(inner_store),
+            },
+        );
+
+        Ok(Self {
+            inner_leaves,
+            inner_tree,
+        })
+    }
+
+    /// Position in the Merkle mountain range of the node of the given height covering the
+    /// leaves `index * 2^height` to `(index + 1) * 2^height - 1`.
+    ///
+    /// The nodes are stored in post-order: the node is appended with the last leaf it covers.
+    fn node_position(height: u32, index: u64) -> u64 {
+        let last_leaf_index = ((index + 1) << height) - 1;
+        let last_leaf_position = 2 * last_leaf_index - last_leaf_index.count_ones() as u64;
+
+        last_leaf_position + height as u64
+    }
+
     /// Number of leaves in the Merkle tree
     pub fn total_leaves(&self) -> usize {
         self.inner_leaves.len()
@@ -316,12 +642,12 @@ impl MKTree {
                 .map(|(leaf_position, _leaf)| *leaf_position)
                 .collect(),
         )?;
-        return Ok(MKProof {
+        Ok(MKProof {
             inner_root: Arc::new(self.compute_root()?),
             inner_leaves,
             inner_proof_size: proof.mmr_size(),
             inner_proof_items: proof.proof_items().to_vec(),
-        });
+        })
     }
 }
 
@@ -342,6 +668,62 @@ mod tests {
             .collect()
     }
 
+    fn generate_hashes(total_leaves: usize) -> Vec<[u8; 32]> {
+        (0..total_leaves)
+            .map(|i| Blake2s256::digest(format!("test-{i}")).into())
+            .collect()
+    }
+
+    #[test]
+    fn parallel_merkle_tree_root_matches_the_sequential_root() {
+        for total_leaves in [1, 2, 3, 5, 8, 13, 64, 1000, 1023] {
+            let hashes = generate_hashes(total_leaves);
+            let leaves = hashes
+                .iter()
+                .map(|hash| MKTreeNode::new(hash.to_vec()))
+                .collect::<Vec<_>>();
+            let expected_root = MKTree::new(&leaves).unwrap().compute_root().unwrap();
+
+            for workers in [1, 4] {
+                let mktree = MKTree::from_hashes_parallel(hashes.clone(), workers).unwrap();
+
+                assert_eq!(
+                    expected_root,
+                    mktree.compute_root().unwrap(),
+                    "root mismatch for {total_leaves} leaves with {workers} workers"
+                );
+                assert_eq!(leaves, mktree.leaves());
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_merkle_tree_root_is_deterministic() {
+        let hashes = generate_hashes(777);
+
+        assert_eq!(
+            MKTree::from_hashes_parallel(hashes.clone(), 2)
+                .unwrap()
+                .compute_root()
+                .unwrap(),
+            MKTree::from_hashes_parallel(hashes, 8)
+                .unwrap()
+                .compute_root()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parallel_merkle_tree_proof_is_valid() {
+        let mktree = MKTree::from_hashes_parallel(generate_hashes(100), 4).unwrap();
+        let leaves_to_verify = &mktree.leaves()[10..20];
+
+        let proof = mktree.compute_proof(leaves_to_verify).unwrap();
+
+        proof.verify().unwrap();
+        proof.contains(leaves_to_verify).unwrap();
+    }
+
     #[test]
     fn test_golden_merkle_root() {
         let leaves = vec!["golden-1", "golden-2", "golden-3", "golden-4", "golden-5"];
@@ -375,6 +757,45 @@ mod tests {
         proof.verify().expect_err("The MKProof should be invalid");
     }
 
+    #[test]
+    fn test_compressed_proof_decompresses_to_a_proof_valid_against_the_original_root() {
+        let leaves = generate_leaves(1024);
+        let leaves_to_verify = &[
+            leaves[3].to_owned(),
+            leaves[1000].to_owned(),
+            leaves[512].to_owned(),
+        ];
+        let proof = MKProof::from_subset_of_leaves(&leaves, leaves_to_verify)
+            .expect("MKProof generation should not fail");
+
+        let compressed_proof: CompressedMKProof =
+            serde_json::from_str(&serde_json::to_string(&proof.compress_path()).unwrap()).unwrap();
+        let decompressed_proof = compressed_proof
+            .decompress()
+            .expect("CompressedMKProof decompression should not fail");
+
+        assert_eq!(proof, decompressed_proof);
+        assert_eq!(proof.root(), decompressed_proof.root());
+        decompressed_proof
+            .verify()
+            .expect("The decompressed MKProof should be valid");
+    }
+
+    #[test]
+    fn test_compressed_proof_with_tampered_leaf_decompresses_to_an_invalid_root() {
+        let leaves = generate_leaves(1024);
+        let proof = MKProof::from_subset_of_leaves(&leaves, &[leaves[10].to_owned()])
+            .expect("MKProof generation should not fail");
+        let mut compressed_proof = proof.compress_path();
+        compressed_proof.leaves[0].1 = ByteBuf::from(leaves[11].hash.clone());
+
+        let decompressed_proof = compressed_proof
+            .decompress()
+            .expect("CompressedMKProof decompression should not fail");
+
+        assert_ne!(proof.root(), decompressed_proof.root());
+    }
+
     #[test]
     fn test_should_list_leaves() {
         let leaves: Vec<MKTreeNode> = vec!["test-0".into(), "test-1".into(), "test-2".into()];
@@ -460,4 +881,73 @@ mod tests {
         let proof_leaves = proof.leaves();
         assert_eq!(proof_leaves, leaves_to_verify);
     }
+
+    #[test]
+    fn compact_bytes_round_trip_yield_a_valid_proof() {
+        let leaves = generate_leaves(100);
+        let proof = MKProof::from_leaves(&leaves).expect("MKProof generation should not fail");
+
+        let bytes = proof.to_compact_bytes().unwrap();
+        let decoded_proof = MKProof::from_compact_bytes(&bytes).unwrap();
+
+        assert_eq!(proof, decoded_proof);
+        decoded_proof
+            .verify()
+            .expect("The decoded MKProof should be valid");
+    }
+
+    #[test]
+    fn compact_hex_round_trip() {
+        let leaves = generate_leaves(10);
+        let proof = MKProof::from_leaves(&leaves).expect("MKProof generation should not fail");
+
+        let decoded_proof = MKProof::from_compact_hex(&proof.to_compact_hex().unwrap()).unwrap();
+
+        assert_eq!(proof, decoded_proof);
+    }
+
+    #[test]
+    fn compact_bytes_are_at_least_40_percent_smaller_than_json() {
+        let leaves: Vec<MKTreeNode> = (0..1000).map(|i| format!("{i:064x}").into()).collect();
+        let proof = MKProof::from_leaves(&leaves).expect("MKProof generation should not fail");
+
+        let json_size = serde_json::to_vec(&proof).unwrap().len();
+        let compact_size = proof.to_compact_bytes().unwrap().len();
+
+        assert!(
+            compact_size * 10 <= json_size * 6,
+            "compact size ({compact_size}) should be at least 40% smaller than json size ({json_size})"
+        );
+    }
+
+    #[test]
+    fn from_compact_bytes_fails_with_invalid_data() {
+        MKProof::from_compact_bytes(b"invalid").expect_err("decoding should fail");
+    }
+
+    mod compact_bytes_properties {
+        use proptest::collection::vec;
+        use proptest::prelude::*;
+
+        use super::*;
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(50))]
+
+            #[test]
+            fn compact_bytes_round_trip(
+                leaves in vec(vec(any::<u8>(), 1..64), 1..50),
+                subset_size in 1..50usize,
+            ) {
+                let leaves: Vec<MKTreeNode> = leaves.into_iter().map(MKTreeNode::new).collect();
+                let mktree = MKTree::new(&leaves).unwrap();
+                let subset: Vec<MKTreeNode> = mktree.leaves().into_iter().take(subset_size).collect();
+                let proof = mktree.compute_proof(&subset).unwrap();
+
+                let decoded_proof = MKProof::from_compact_bytes(&proof.to_compact_bytes().unwrap()).unwrap();
+
+                prop_assert_eq!(proof, decoded_proof);
+            }
+        }
+    }
 }
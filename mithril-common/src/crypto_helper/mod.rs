@@ -18,8 +18,8 @@ cfg_random! {
 }
 
 pub use cardano::{
-    KESPeriod, OpCert, ProtocolInitializerErrorWrapper, ProtocolRegistrationErrorWrapper,
-    SerDeShelleyFileFormat, Sum6KesBytes,
+    KESPeriod, OpCert, ProtocolInitializerErrorWrapper, ProtocolInitializerVersion,
+    ProtocolRegistrationErrorWrapper, SerDeShelleyFileFormat, Sum6KesBytes,
 };
 pub use codec::*;
 pub use era::{
@@ -27,8 +27,12 @@ pub use era::{
     EraMarkersVerifierSignature, EraMarkersVerifierVerificationKey,
 };
 pub use genesis::{ProtocolGenesisError, ProtocolGenesisSigner, ProtocolGenesisVerifier};
-pub use merkle_map::{MKMap, MKMapKey, MKMapNode, MKMapProof, MKMapValue};
-pub use merkle_tree::{MKProof, MKTree, MKTreeNode, MKTreeStore};
+pub use merkle_map::{
+    CompressedMKMapProof, MKMap, MKMapKey, MKMapNode, MKMapProof, MKMapValue, SparseMKMapProof,
+};
+pub use merkle_tree::{
+    CompressedMKProof, MKProof, MKProofNodesTable, MKTree, MKTreeNode, MKTreeStore, SparseMKProof,
+};
 pub use types::*;
 
 /// The current protocol version
@@ -1,25 +1,93 @@
 use crate::{
     digesters::{
-        cache::ImmutableFileDigestCacheProvider, ImmutableDigester, ImmutableDigesterError,
-        ImmutableFile,
+        cache::ImmutableFileDigestCacheProvider, DigestPipelineStage, DigesterCheckpoint,
+        DirectoryImmutableFileListingProvider, ImmutableDigester, ImmutableDigesterError,
+        ImmutableFile, ImmutableFileListingError, ImmutableFileListingProvider,
+        list_immutable_files_async,
     },
-    entities::{CardanoDbBeacon, HexEncodedDigest, ImmutableFileName},
+    entities::{CardanoDbBeacon, HexEncodedDigest, ImmutableFileName, ImmutableFileNumber},
 };
 use async_trait::async_trait;
 use sha2::{Digest, Sha256};
 use slog::{debug, info, warn, Logger};
-use std::{collections::BTreeMap, io, path::Path, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::sync::mpsc;
+
+/// Stages of a custom digest pipeline, shared with the thread computing the digest.
+type DigestPipeline = Arc<Vec<Box<dyn DigestPipelineStage>>>;
+
+/// Result of a cache computation, contains the digest, the list of new entries to add
+/// to the [ImmutableFileDigestCacheProvider] and the checkpoint reached at the end of the
+/// computation.
+type CacheComputationResult = Result<
+    (
+        [u8; 32],
+        Vec<(ImmutableFileName, HexEncodedDigest)>,
+        DigesterCheckpoint,
+    ),
+    io::Error,
+>;
+
+/// Progress of a digest computation, sent after each processed immutable file by
+/// [CardanoImmutableDigester::compute_digest_streaming].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImmutableFileProgress {
+    /// Number of the immutable file that was just processed
+    pub immutable_file_number: ImmutableFileNumber,
+
+    /// Total size in bytes of the files processed so far
+    pub bytes_processed: u64,
+
+    /// Number of files processed so far
+    pub files_done: usize,
+
+    /// Number of files to process
+    pub files_total: usize,
+}
+
+/// Configuration of a [CardanoImmutableDigester]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigesterConfig {
+    /// Minimum number of immutable files trios (`chunk`, `primary` and `secondary` files of a
+    /// same number) that must exist in the Cardano database directory to compute a digest.
+    ///
+    /// The last trio is still written by the Cardano node so it's not digested, except if this
+    /// minimum is `1` and it's the only trio of the database, which allows to digest a database
+    /// with only one immutable file (ie: on private networks or in test scenarios).
+    pub min_immutable_files: usize,
+}
 
-/// Result of a cache computation, contains the digest and the list of new entries to add
-/// to the [ImmutableFileDigestCacheProvider].
-type CacheComputationResult =
-    Result<([u8; 32], Vec<(ImmutableFileName, HexEncodedDigest)>), io::Error>;
+impl Default for DigesterConfig {
+    fn default() -> Self {
+        Self {
+            min_immutable_files: 2,
+        }
+    }
+}
 
 /// A digester working directly on a Cardano DB immutables files
 pub struct CardanoImmutableDigester {
     /// A [ImmutableFileDigestCacheProvider] instance
     cache_provider: Option<Arc<dyn ImmutableFileDigestCacheProvider>>,
 
+    /// Path of the file where the [DigesterCheckpoint] are saved during a computation
+    checkpoint_path: Option<PathBuf>,
+
+    /// Provider of the immutable files to digest
+    listing_provider: Arc<dyn ImmutableFileListingProvider>,
+
+    /// Configuration of the digester
+    config: DigesterConfig,
+
+    /// Stages computing the digest of each file, `None` to only hash the files content
+    pipeline: Option<DigestPipeline>,
+
     /// The logger where the logs should be written
     logger: Logger,
 }
@@ -32,20 +100,150 @@ impl CardanoImmutableDigester {
     ) -> Self {
         Self {
             cache_provider,
+            checkpoint_path: None,
+            listing_provider: Arc::new(DirectoryImmutableFileListingProvider),
+            config: DigesterConfig::default(),
+            pipeline: None,
             logger,
         }
     }
-}
 
-#[async_trait]
-impl ImmutableDigester for CardanoImmutableDigester {
-    async fn compute_digest(
+    /// Read the immutable files to digest from the given [ImmutableFileListingProvider]
+    /// instead of listing the Cardano database directory on each computation.
+    pub fn with_listing_provider(
+        mut self,
+        listing_provider: Arc<dyn ImmutableFileListingProvider>,
+    ) -> Self {
+        self.listing_provider = listing_provider;
+        self
+    }
+
+    /// Set the [DigesterConfig] of the digester.
+    pub fn with_config(mut self, config: DigesterConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Compute the digest of each immutable file with the given pipeline stages instead of
+    /// only hashing the files content (ie: adding a [HashFileMetadata][crate::digesters::HashFileMetadata]
+    /// stage to detect renamed files).
+    ///
+    /// The digests of the files computed by a custom pipeline are not the ones stored by the
+    /// cache provider, so the cache is not used.
+    pub fn with_pipeline(mut self, stages: Vec<Box<dyn DigestPipelineStage>>) -> Self {
+        self.pipeline = Some(Arc::new(stages));
+        self
+    }
+
+    /// Save the [DigesterCheckpoint] of the computations to the given file, so they can be
+    /// resumed with [Self::compute_digest_resumable] after an interruption.
+    pub fn with_checkpoint_path(mut self, checkpoint_path: PathBuf) -> Self {
+        self.checkpoint_path = Some(checkpoint_path);
+        self
+    }
+
+    /// Compute the digest, resuming the computation from the given checkpoint if any.
+    ///
+    /// Returns the digest and the checkpoint reached at the end of the computation.
+    pub async fn compute_digest_resumable(
         &self,
         dirpath: &Path,
         beacon: &CardanoDbBeacon,
+        checkpoint: Option<DigesterCheckpoint>,
+    ) -> Result<(String, DigesterCheckpoint), ImmutableDigesterError> {
+        self.compute_digest_with_progress(dirpath, beacon, checkpoint, None)
+            .await
+    }
+
+    /// Compute the digest while streaming the progress of the computation.
+    ///
+    /// The returned receiver yields one [ImmutableFileProgress] per processed file, the
+    /// returned future must be awaited to run the computation and get the digest.
+    pub fn compute_digest_streaming<'a>(
+        &'a self,
+        dirpath: &'a Path,
+        beacon: &'a CardanoDbBeacon,
+    ) -> (
+        impl Future<Output = Result<String, ImmutableDigesterError>> + 'a,
+        mpsc::UnboundedReceiver<ImmutableFileProgress>,
+    ) {
+        // The channel is unbounded so the computation is never blocked by a consumer that
+        // awaits the result before reading the progress.
+        let (progress_sender, progress_receiver) = mpsc::unbounded_channel();
+
+        let computation = async move {
+            let (digest, _checkpoint) = self
+                .compute_digest_with_progress(dirpath, beacon, None, Some(progress_sender))
+                .await?;
+
+            Ok(digest)
+        };
+
+        (computation, progress_receiver)
+    }
+
+    /// Compute the digest of immutable files already loaded in memory, without reading the
+    /// Cardano database from the filesystem.
+    ///
+    /// Each slice is the content of one immutable file, tagged with its immutable file number.
+    /// The slices are hashed by ascending immutable file number, the slices of a same immutable
+    /// file number must be given in the order of their file names (`chunk`, `primary`,
+    /// `secondary`) so the digest is the same as the one computed from the files.
+    ///
+    /// Slices after the beacon immutable file number are ignored.
+    pub fn compute_digest_from_slices(
+        &self,
+        beacon: &CardanoDbBeacon,
+        slices: &[(ImmutableFileNumber, &[u8])],
     ) -> Result<String, ImmutableDigesterError> {
         let up_to_file_number = beacon.immutable_file_number;
-        let immutables = ImmutableFile::list_completed_in_dir(dirpath)?
+        let mut slices = slices
+            .iter()
+            .filter(|(number, _)| *number <= up_to_file_number)
+            .collect::<Vec<_>>();
+        // The sort is stable so the order of the files of a same immutable is kept
+        slices.sort_by_key(|(number, _)| *number);
+
+        match slices.last() {
+            None => Err(ImmutableDigesterError::NotEnoughImmutable {
+                expected_number: up_to_file_number,
+                found_number: None,
+                db_dir: PathBuf::new(),
+            }),
+            Some((last_number, _)) if *last_number < up_to_file_number => {
+                Err(ImmutableDigesterError::NotEnoughImmutable {
+                    expected_number: up_to_file_number,
+                    found_number: Some(*last_number),
+                    db_dir: PathBuf::new(),
+                })
+            }
+            Some(_) => {
+                info!(self.logger, "#compute_digest_from_slices"; "beacon" => #?beacon, "nb_of_slices" => slices.len());
+
+                let mut hasher = Sha256::new();
+                hasher.update(beacon.compute_hash().as_bytes());
+                for (_, content) in slices {
+                    hasher.update(hex::encode(Sha256::digest(content)));
+                }
+                let digest = hex::encode(hasher.finalize());
+
+                debug!(self.logger, "#computed digest: {:?}", digest);
+
+                Ok(digest)
+            }
+        }
+    }
+
+    async fn compute_digest_with_progress(
+        &self,
+        dirpath: &Path,
+        beacon: &CardanoDbBeacon,
+        checkpoint: Option<DigesterCheckpoint>,
+        progress_sender: Option<mpsc::UnboundedSender<ImmutableFileProgress>>,
+    ) -> Result<(String, DigesterCheckpoint), ImmutableDigesterError> {
+        let up_to_file_number = beacon.immutable_file_number;
+        let (listed_immutables, nb_of_trios) = self.list_immutable_files(dirpath).await?;
+        let immutables = listed_immutables
             .into_iter()
             .filter(|f| f.number <= up_to_file_number)
             .collect::<Vec<_>>();
@@ -56,7 +254,10 @@ impl ImmutableDigester for CardanoImmutableDigester {
                 found_number: None,
                 db_dir: dirpath.to_owned(),
             }),
-            Some(last_immutable_file) if last_immutable_file.number < up_to_file_number => {
+            Some(last_immutable_file)
+                if last_immutable_file.number < up_to_file_number
+                    || nb_of_trios < self.config.min_immutable_files =>
+            {
                 Err(ImmutableDigesterError::NotEnoughImmutable {
                     expected_number: up_to_file_number,
                     found_number: Some(last_immutable_file.number),
@@ -66,8 +267,35 @@ impl ImmutableDigester for CardanoImmutableDigester {
             Some(_) => {
                 info!(self.logger, "#compute_digest"; "beacon" => #?beacon, "nb_of_immutables" => immutables.len());
 
-                let cached_values = match self.cache_provider.as_ref() {
-                    None => BTreeMap::from_iter(immutables.into_iter().map(|i| (i, None))),
+                let checkpoint = checkpoint.filter(|c| {
+                    let is_usable = c.last_processed_immutable <= up_to_file_number;
+                    if !is_usable {
+                        warn!(
+                            self.logger,
+                            "Digester checkpoint is beyond the beacon, computing digest from scratch";
+                            "checkpoint_immutable" => c.last_processed_immutable
+                        );
+                    }
+                    is_usable
+                });
+                let immutables = match &checkpoint {
+                    Some(checkpoint) => {
+                        debug!(
+                            self.logger,
+                            "Resuming digest computation after immutable {}",
+                            checkpoint.last_processed_immutable
+                        );
+                        immutables
+                            .into_iter()
+                            .filter(|f| f.number > checkpoint.last_processed_immutable)
+                            .collect()
+                    }
+                    None => immutables,
+                };
+
+                let cache_provider = self.cache_provider.as_ref().filter(|_| self.pipeline.is_none());
+                let mut cached_values = match cache_provider {
+                    None => BTreeMap::new(),
                     Some(cache_provider) => match cache_provider.get(immutables.clone()).await {
                         Ok(values) => values,
                         Err(error) => {
@@ -75,17 +303,31 @@ impl ImmutableDigester for CardanoImmutableDigester {
                                 self.logger,
                                 "Error while getting cached immutable files digests: {}", error
                             );
-                            BTreeMap::from_iter(immutables.into_iter().map(|i| (i, None)))
+                            BTreeMap::new()
                         }
                     },
                 };
+                // Immutables omitted by the cache are digested as if they were not cached
+                for immutable in immutables {
+                    cached_values.entry(immutable).or_insert(None);
+                }
 
                 // digest is done in a separate thread because it is blocking the whole task
                 let logger = self.logger.clone();
                 let thread_beacon = beacon.clone();
-                let (hash, new_cache_entries) =
+                let checkpoint_path = self.checkpoint_path.clone();
+                let pipeline = self.pipeline.clone();
+                let (hash, new_cache_entries, checkpoint) =
                     tokio::task::spawn_blocking(move || -> CacheComputationResult {
-                        compute_hash(logger, &thread_beacon, cached_values)
+                        compute_hash(
+                            logger,
+                            &thread_beacon,
+                            cached_values,
+                            checkpoint,
+                            checkpoint_path.as_deref(),
+                            progress_sender,
+                            pipeline.as_deref().map(|stages| stages.as_slice()),
+                        )
                     })
                     .await
                     .map_err(|e| ImmutableDigesterError::DigestComputationError(e.into()))??;
@@ -93,7 +335,7 @@ impl ImmutableDigester for CardanoImmutableDigester {
 
                 debug!(self.logger, "#computed digest: {:?}", digest);
 
-                if let Some(cache_provider) = self.cache_provider.as_ref() {
+                if let Some(cache_provider) = cache_provider {
                     if let Err(error) = cache_provider.store(new_cache_entries).await {
                         warn!(
                             self.logger,
@@ -102,16 +344,80 @@ impl ImmutableDigester for CardanoImmutableDigester {
                     }
                 }
 
-                Ok(digest)
+                Ok((digest, checkpoint))
+            }
+        }
+    }
+}
+
+impl CardanoImmutableDigester {
+    /// List the completed immutable files of the database with the number of trios it contains.
+    ///
+    /// The last trio, still written by the Cardano node, is only listed if it's the only trio
+    /// of the database and the configured minimum is `1`.
+    ///
+    /// The directory is read asynchronously so the listing doesn't block the tokio runtime.
+    async fn list_immutable_files(
+        &self,
+        dirpath: &Path,
+    ) -> Result<(Vec<ImmutableFile>, usize), ImmutableFileListingError> {
+        if self.config.min_immutable_files > 1 {
+            let completed_immutables = self.listing_provider.list_immutable_files(dirpath).await?;
+            let nb_of_trios = count_trios(&completed_immutables) + 1;
+
+            return Ok((completed_immutables, nb_of_trios));
+        }
+
+        let all_immutables =
+            list_immutable_files_async(dirpath, tokio_util::sync::CancellationToken::new())
+                .await?;
+        let nb_of_trios = count_trios(&all_immutables);
+        match all_immutables.last() {
+            Some(last_immutable) if nb_of_trios > 1 => {
+                let last_number = last_immutable.number;
+                let completed_immutables = all_immutables
+                    .into_iter()
+                    .filter(|f| f.number < last_number)
+                    .collect();
+
+                Ok((completed_immutables, nb_of_trios))
             }
+            _ => Ok((all_immutables, nb_of_trios)),
         }
     }
 }
 
+fn count_trios(immutables: &[ImmutableFile]) -> usize {
+    immutables
+        .iter()
+        .map(|f| f.number)
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+#[async_trait]
+impl ImmutableDigester for CardanoImmutableDigester {
+    async fn compute_digest(
+        &self,
+        dirpath: &Path,
+        beacon: &CardanoDbBeacon,
+    ) -> Result<String, ImmutableDigesterError> {
+        let (digest, _checkpoint) = self
+            .compute_digest_resumable(dirpath, beacon, None)
+            .await?;
+
+        Ok(digest)
+    }
+}
+
 fn compute_hash(
     logger: Logger,
     beacon: &CardanoDbBeacon,
     entries: BTreeMap<ImmutableFile, Option<HexEncodedDigest>>,
+    mut checkpoint: Option<DigesterCheckpoint>,
+    checkpoint_path: Option<&Path>,
+    progress_sender: Option<mpsc::UnboundedSender<ImmutableFileProgress>>,
+    pipeline: Option<&[Box<dyn DigestPipelineStage>]>,
 ) -> CacheComputationResult {
     let mut hasher = Sha256::new();
     let mut new_cached_entries = Vec::new();
@@ -119,27 +425,101 @@ fn compute_hash(
         index: 0,
         total: entries.len(),
     };
+    // Digests of the files of the immutable trio being processed
+    let mut current_immutable_digests = Vec::new();
+    let mut checkpoint_is_outdated = false;
+    let mut bytes_processed = 0;
 
     hasher.update(beacon.compute_hash().as_bytes());
+    if let Some(checkpoint) = &checkpoint {
+        hasher.update(&checkpoint.partial_hash_state);
+    }
 
-    for (ix, (entry, cache)) in entries.iter().enumerate() {
-        match cache {
+    let mut entries = entries.iter().enumerate().peekable();
+    while let Some((ix, (entry, cache))) = entries.next() {
+        let digest = match cache {
             None => {
-                let data = hex::encode(entry.compute_raw_hash::<Sha256>()?);
-                hasher.update(&data);
-                new_cached_entries.push((entry.filename.clone(), data));
-            }
-            Some(digest) => {
-                hasher.update(digest);
+                let data = match pipeline {
+                    None => hex::encode(entry.compute_raw_hash::<Sha256>()?),
+                    Some(stages) => compute_pipeline_digest(entry, stages)?,
+                };
+                new_cached_entries.push((entry.filename.clone(), data.clone()));
+                data
             }
+            Some(digest) => digest.clone(),
         };
+        hasher.update(&digest);
+        current_immutable_digests.extend_from_slice(digest.as_bytes());
+
+        if let Some(sender) = &progress_sender {
+            bytes_processed += entry.path.metadata().map(|m| m.len()).unwrap_or_default();
+            // An error only means that the receiver is not listening anymore.
+            let _ = sender.send(ImmutableFileProgress {
+                immutable_file_number: entry.number,
+                bytes_processed,
+                files_done: ix + 1,
+                files_total: progress.total,
+            });
+        }
 
         if progress.report(ix) {
             info!(logger, "hashing: {}", &progress);
+            checkpoint_is_outdated = true;
         }
+
+        let is_immutable_completed = !matches!(
+            entries.peek(),
+            Some((_, (next, _))) if next.number == entry.number
+        );
+        if is_immutable_completed {
+            checkpoint
+                .get_or_insert_with(|| DigesterCheckpoint {
+                    last_processed_immutable: entry.number,
+                    partial_hash_state: Vec::new(),
+                })
+                .record_immutable(entry.number, &current_immutable_digests);
+            current_immutable_digests.clear();
+
+            if let (Some(path), Some(checkpoint)) = (checkpoint_path, &checkpoint) {
+                if checkpoint_is_outdated {
+                    save_checkpoint(&logger, checkpoint, path);
+                    checkpoint_is_outdated = false;
+                }
+            }
+        }
+    }
+
+    let checkpoint = checkpoint.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "No immutable file to digest")
+    })?;
+    if let Some(path) = checkpoint_path {
+        save_checkpoint(&logger, &checkpoint, path);
+    }
+
+    Ok((hasher.finalize().into(), new_cached_entries, checkpoint))
+}
+
+fn compute_pipeline_digest(
+    entry: &ImmutableFile,
+    stages: &[Box<dyn DigestPipelineStage>],
+) -> Result<HexEncodedDigest, io::Error> {
+    let mut hasher = Sha256::new();
+    for stage in stages {
+        stage.hash(entry, &mut hasher)?;
     }
 
-    Ok((hasher.finalize().into(), new_cached_entries))
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn save_checkpoint(logger: &Logger, checkpoint: &DigesterCheckpoint, path: &Path) {
+    if let Err(error) = checkpoint.save_to_file(path) {
+        warn!(
+            logger,
+            "Error while saving digester checkpoint to '{}': {}",
+            path.display(),
+            error
+        );
+    }
 }
 
 struct Progress {
@@ -150,7 +530,7 @@ struct Progress {
 impl Progress {
     fn report(&mut self, ix: usize) -> bool {
         self.index = ix;
-        (20 * ix) % self.total == 0
+        (20 * ix).is_multiple_of(self.total)
     }
 
     fn percent(&self) -> f64 {
@@ -174,11 +554,12 @@ mod tests {
                 ImmutableDigesterCacheStoreError, ImmutableFileDigestCacheProvider,
                 MemoryImmutableFileDigestCacheProvider, MockImmutableFileDigestCacheProvider,
             },
-            CardanoImmutableDigester, DummyImmutablesDbBuilder, ImmutableDigester,
-            ImmutableDigesterError,
+            CardanoImmutableDigester, DigesterCheckpoint, DigesterConfig, DummyImmutablesDbBuilder,
+            HashFileContents, HashFileMetadata, ImmutableDigester, ImmutableDigesterError,
+            ImmutableFile, ImmutableFileProgress,
         },
         entities::{CardanoDbBeacon, ImmutableFileNumber},
-        test_utils::TestLogger,
+        test_utils::{TempDir, TestLogger},
     };
     use sha2::Sha256;
     use std::{collections::BTreeMap, io, sync::Arc};
@@ -278,6 +659,95 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn can_compute_hash_of_a_single_immutable_trio_with_a_minimum_of_one_immutable_file() {
+        let immutable_db = db_builder("can_compute_hash_of_a_single_immutable_trio")
+            .with_immutables(&[1])
+            .build();
+        let digester = CardanoImmutableDigester::new(None, TestLogger::stdout()).with_config(
+            DigesterConfig {
+                min_immutable_files: 1,
+            },
+        );
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 1);
+
+        let (_digest, checkpoint) = digester
+            .compute_digest_resumable(&immutable_db.dir, &beacon, None)
+            .await
+            .expect("compute_digest must not fail");
+
+        assert_eq!(Some(1), immutable_db.last_immutable_number());
+        assert_eq!(1, checkpoint.last_processed_immutable);
+    }
+
+    #[tokio::test]
+    async fn exclude_the_last_immutable_trio_being_written_with_a_minimum_of_one_immutable_file() {
+        let immutable_db = db_builder("exclude_the_last_immutable_trio_being_written")
+            .with_immutables(&[1, 2])
+            .append_immutable_trio()
+            .build();
+        let digester = CardanoImmutableDigester::new(None, TestLogger::stdout()).with_config(
+            DigesterConfig {
+                min_immutable_files: 1,
+            },
+        );
+        // The uncompleted trio is not part of the immutable files of the dummy db
+        let last_immutable_file_number = immutable_db.last_immutable_number().unwrap();
+        assert_eq!(2, last_immutable_file_number);
+
+        let result = digester
+            .compute_digest(
+                &immutable_db.dir,
+                &CardanoDbBeacon::new("devnet".to_string(), 1, last_immutable_file_number + 1),
+            )
+            .await
+            .expect_err("compute_digest should've failed");
+        assert!(matches!(
+            result,
+            ImmutableDigesterError::NotEnoughImmutable {
+                found_number: Some(2),
+                ..
+            }
+        ));
+
+        let (_digest, checkpoint) = digester
+            .compute_digest_resumable(
+                &immutable_db.dir,
+                &CardanoDbBeacon::new("devnet".to_string(), 1, last_immutable_file_number),
+                None,
+            )
+            .await
+            .expect("compute_digest must not fail");
+        assert_eq!(last_immutable_file_number, checkpoint.last_processed_immutable);
+    }
+
+    #[tokio::test]
+    async fn fail_if_less_immutable_trios_than_the_configured_minimum() {
+        let immutable_db = db_builder("fail_if_less_immutable_trios_than_the_configured_minimum")
+            .with_immutables(&[1, 2])
+            .append_immutable_trio()
+            .build();
+        let digester = CardanoImmutableDigester::new(None, TestLogger::stdout()).with_config(
+            DigesterConfig {
+                min_immutable_files: 4,
+            },
+        );
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 2);
+
+        let result = digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect_err("compute_digest should've failed");
+
+        assert!(matches!(
+            result,
+            ImmutableDigesterError::NotEnoughImmutable {
+                found_number: Some(2),
+                ..
+            }
+        ));
+    }
+
     #[tokio::test]
     async fn fail_if_less_immutable_than_what_required_in_beacon() {
         let immutable_db = db_builder("fail_if_less_immutable_than_what_required_in_beacon")
@@ -329,6 +799,127 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn digest_computed_from_slices_is_the_same_as_from_files() {
+        let immutable_db = db_builder("digest_computed_from_slices_is_the_same_as_from_files")
+            .with_immutables(&[1, 2, 3, 4])
+            .append_immutable_trio()
+            .build();
+        let digester = CardanoImmutableDigester::new(None, TestLogger::stdout());
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 3);
+        let mut contents: Vec<(ImmutableFileNumber, Vec<u8>)> =
+            ImmutableFile::list_completed_in_dir(&immutable_db.dir)
+                .unwrap()
+                .into_iter()
+                .map(|file| (file.number, std::fs::read(&file.path).unwrap()))
+                .collect();
+        // Give the immutables in descending order to check that the slices are sorted by number
+        contents.sort_by_key(|(number, _)| std::cmp::Reverse(*number));
+        let slices: Vec<(ImmutableFileNumber, &[u8])> = contents
+            .iter()
+            .map(|(number, content)| (*number, content.as_slice()))
+            .collect();
+
+        let expected = digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+        let result = digester
+            .compute_digest_from_slices(&beacon, &slices)
+            .expect("compute_digest_from_slices must not fail");
+
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn compute_digest_from_slices_fail_if_not_enough_immutable() {
+        let digester = CardanoImmutableDigester::new(None, TestLogger::stdout());
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 3);
+        let slices: Vec<(ImmutableFileNumber, &[u8])> = vec![(1, b"chunk"), (2, b"chunk")];
+
+        let result = digester
+            .compute_digest_from_slices(&beacon, &slices)
+            .expect_err("compute_digest_from_slices should have failed");
+
+        assert!(
+            matches!(
+                result,
+                ImmutableDigesterError::NotEnoughImmutable {
+                    expected_number: 3,
+                    found_number: Some(2),
+                    ..
+                }
+            ),
+            "unexpected error: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn digest_pipeline_with_metadata_yield_a_different_digest_than_the_default_one() {
+        let immutable_db = db_builder("digest_pipeline_with_metadata")
+            .with_immutables(&[1, 2, 3])
+            .append_immutable_trio()
+            .build();
+        let logger = TestLogger::stdout();
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 3);
+        let default_digester = CardanoImmutableDigester::new(None, logger.clone());
+        let contents_digester = CardanoImmutableDigester::new(None, logger.clone())
+            .with_pipeline(vec![Box::new(HashFileContents)]);
+        let metadata_digester = CardanoImmutableDigester::new(None, logger.clone())
+            .with_pipeline(vec![Box::new(HashFileMetadata), Box::new(HashFileContents)]);
+
+        let default_digest = default_digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .unwrap();
+        let metadata_digest = metadata_digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .unwrap();
+
+        assert_ne!(default_digest, metadata_digest);
+        assert_eq!(
+            default_digest,
+            contents_digester
+                .compute_digest(&immutable_db.dir, &beacon)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            default_digest,
+            default_digester
+                .compute_digest(&immutable_db.dir, &beacon)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            metadata_digest,
+            metadata_digester
+                .compute_digest(&immutable_db.dir, &beacon)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn digest_pipeline_does_not_use_the_cache() {
+        let immutable_db = db_builder("digest_pipeline_does_not_use_the_cache")
+            .with_immutables(&[1, 2])
+            .append_immutable_trio()
+            .build();
+        let mut cache = MockImmutableFileDigestCacheProvider::new();
+        cache.expect_get().never();
+        cache.expect_store().never();
+        let digester = CardanoImmutableDigester::new(Some(Arc::new(cache)), TestLogger::stdout())
+            .with_pipeline(vec![Box::new(HashFileMetadata), Box::new(HashFileContents)]);
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 2);
+
+        digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+    }
+
     #[tokio::test]
     async fn digests_are_stored_into_cache_provider() {
         let immutable_db = db_builder("digests_are_stored_into_cache_provider")
@@ -450,7 +1041,7 @@ mod tests {
         cache.expect_get().returning(|_| Ok(BTreeMap::new()));
         cache.expect_store().returning(|_| {
             Err(ImmutableDigesterCacheProviderError::Store(
-                ImmutableDigesterCacheStoreError::Io(io::Error::new(io::ErrorKind::Other, "error")),
+                ImmutableDigesterCacheStoreError::Io(io::Error::other("error")),
             ))
         });
         let logger = TestLogger::stdout();
@@ -472,7 +1063,7 @@ mod tests {
         let mut cache = MockImmutableFileDigestCacheProvider::new();
         cache.expect_get().returning(|_| {
             Err(ImmutableDigesterCacheProviderError::Get(
-                ImmutableDigesterCacheGetError::Io(io::Error::new(io::ErrorKind::Other, "error")),
+                ImmutableDigesterCacheGetError::Io(io::Error::other("error")),
             ))
         });
         cache.expect_store().returning(|_| Ok(()));
@@ -485,4 +1076,115 @@ mod tests {
             .await
             .expect("compute_digest must not fail even with cache read failure");
     }
+
+    #[tokio::test]
+    async fn resumed_computation_yield_the_same_digest_than_a_full_computation() {
+        let immutable_db =
+            db_builder("resumed_computation_yield_the_same_digest_than_a_full_computation")
+                .with_immutables(&(1..=10).collect::<Vec<ImmutableFileNumber>>())
+                .append_immutable_trio()
+                .build();
+        let checkpoint_path = TempDir::create(
+            "cardano_immutable_digester_checkpoint",
+            "resumed_computation_yield_the_same_digest_than_a_full_computation",
+        )
+        .join("checkpoint.json");
+        let logger = TestLogger::stdout();
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 10);
+
+        let full_digest = CardanoImmutableDigester::new(None, logger.clone())
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+
+        let (_, half_checkpoint) = CardanoImmutableDigester::new(None, logger.clone())
+            .with_checkpoint_path(checkpoint_path.clone())
+            .compute_digest_resumable(
+                &immutable_db.dir,
+                &CardanoDbBeacon::new("devnet".to_string(), 1, 5),
+                None,
+            )
+            .await
+            .expect("compute_digest_resumable must not fail");
+        assert_eq!(5, half_checkpoint.last_processed_immutable);
+
+        // Simulate a restart: the checkpoint is read back from the file
+        let saved_checkpoint = DigesterCheckpoint::load_from_file(&checkpoint_path)
+            .expect("loading checkpoint should not fail");
+        assert_eq!(Some(half_checkpoint), saved_checkpoint);
+
+        let (resumed_digest, final_checkpoint) =
+            CardanoImmutableDigester::new(None, logger.clone())
+                .with_checkpoint_path(checkpoint_path.clone())
+                .compute_digest_resumable(&immutable_db.dir, &beacon, saved_checkpoint)
+                .await
+                .expect("compute_digest_resumable must not fail");
+
+        assert_eq!(full_digest, resumed_digest);
+        assert_eq!(10, final_checkpoint.last_processed_immutable);
+    }
+
+    #[tokio::test]
+    async fn checkpoint_beyond_the_beacon_is_ignored() {
+        let immutable_db = db_builder("checkpoint_beyond_the_beacon_is_ignored")
+            .with_immutables(&[1, 2, 3])
+            .append_immutable_trio()
+            .build();
+        let digester = CardanoImmutableDigester::new(None, TestLogger::stdout());
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 2);
+        let full_digest = digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+
+        let (digest, checkpoint) = digester
+            .compute_digest_resumable(
+                &immutable_db.dir,
+                &beacon,
+                Some(DigesterCheckpoint {
+                    last_processed_immutable: 3,
+                    partial_hash_state: b"unrelated state".to_vec(),
+                }),
+            )
+            .await
+            .expect("compute_digest_resumable must not fail");
+
+        assert_eq!(full_digest, digest);
+        assert_eq!(2, checkpoint.last_processed_immutable);
+    }
+
+    #[tokio::test]
+    async fn streaming_computation_yield_progress_for_each_file() {
+        let immutable_db = db_builder("streaming_computation_yield_progress_for_each_file")
+            .with_immutables(&[1, 2, 3, 4])
+            .append_immutable_trio()
+            .build();
+        let digester = CardanoImmutableDigester::new(None, TestLogger::stdout());
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 4);
+        let expected_digest = digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+
+        let (computation, mut progress_receiver) =
+            digester.compute_digest_streaming(&immutable_db.dir, &beacon);
+        let digest = computation
+            .await
+            .expect("compute_digest_streaming must not fail");
+
+        let mut progresses: Vec<ImmutableFileProgress> = Vec::new();
+        while let Some(progress) = progress_receiver.recv().await {
+            progresses.push(progress);
+        }
+
+        assert_eq!(expected_digest, digest);
+        assert_eq!(immutable_db.immutables_files.len(), progresses.len());
+        assert!(progresses
+            .windows(2)
+            .all(|w| w[0].files_done < w[1].files_done
+                && w[0].bytes_processed <= w[1].bytes_processed));
+        let last_progress = progresses.last().unwrap();
+        assert_eq!(last_progress.files_total, last_progress.files_done);
+        assert_eq!(4, last_progress.immutable_file_number);
+    }
 }
@@ -0,0 +1,49 @@
+use std::{fs::File, io};
+
+use crate::digesters::ImmutableFile;
+
+/// Stage of the pipeline computing the digest of an [ImmutableFile], see
+/// [CardanoImmutableDigester::with_pipeline][crate::digesters::CardanoImmutableDigester::with_pipeline].
+///
+/// Each stage writes the data of the file it's responsible for to the hasher of the file, the
+/// stages are run in order.
+pub trait DigestPipelineStage: Send + Sync {
+    /// Write the data of the given file to hash to the `hasher`
+    fn hash(&self, file: &ImmutableFile, hasher: &mut dyn io::Write) -> io::Result<()>;
+}
+
+impl<F> DigestPipelineStage for F
+where
+    F: Fn(&ImmutableFile, &mut dyn io::Write) -> io::Result<()> + Send + Sync,
+{
+    fn hash(&self, file: &ImmutableFile, hasher: &mut dyn io::Write) -> io::Result<()> {
+        self(file, hasher)
+    }
+}
+
+/// [DigestPipelineStage] hashing the content of the files, the only stage of the default
+/// pipeline.
+pub struct HashFileContents;
+
+impl DigestPipelineStage for HashFileContents {
+    fn hash(&self, file: &ImmutableFile, hasher: &mut dyn io::Write) -> io::Result<()> {
+        let mut content = File::open(&file.path)?;
+        io::copy(&mut content, hasher)?;
+
+        Ok(())
+    }
+}
+
+/// [DigestPipelineStage] hashing the name and the size of the files, to be put before a
+/// [HashFileContents] stage so a renamed file changes the digest.
+pub struct HashFileMetadata;
+
+impl DigestPipelineStage for HashFileMetadata {
+    fn hash(&self, file: &ImmutableFile, hasher: &mut dyn io::Write) -> io::Result<()> {
+        let size = file.path.metadata()?.len();
+        hasher.write_all(file.filename.as_bytes())?;
+        hasher.write_all(&size.to_be_bytes())?;
+
+        Ok(())
+    }
+}
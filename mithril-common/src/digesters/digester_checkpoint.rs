@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::entities::ImmutableFileNumber;
+
+/// State of an interrupted digest computation, allowing to resume it from the last
+/// processed immutable file instead of hashing the whole database again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigesterCheckpoint {
+    /// Number of the last immutable file trio included in the partial hash state
+    pub last_processed_immutable: ImmutableFileNumber,
+
+    /// Data fed to the hasher after the beacon hash, ie. the concatenated digests of the
+    /// processed immutable files.
+    ///
+    /// The beacon hash is not part of the state so a checkpoint can be reused to compute the
+    /// digest of a more recent beacon.
+    pub partial_hash_state: Vec<u8>,
+}
+
+impl DigesterCheckpoint {
+    /// Load a checkpoint from the given file, returns `None` if the file does not exist.
+    pub fn load_from_file(path: &Path) -> io::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(path)?;
+        let checkpoint = serde_json::from_reader(BufReader::new(file))?;
+
+        Ok(Some(checkpoint))
+    }
+
+    /// Save the checkpoint to the given file.
+    ///
+    /// The checkpoint is first written to a temporary file that is then renamed, so an
+    /// interruption while saving can't leave a corrupted checkpoint behind.
+    pub fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        let temporary_path = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&temporary_path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+        drop(writer);
+
+        fs::rename(temporary_path, path)
+    }
+
+    /// Append the digests of the files of the given immutable number to the partial state.
+    pub(super) fn record_immutable(&mut self, number: ImmutableFileNumber, digests: &[u8]) {
+        self.last_processed_immutable = number;
+        self.partial_hash_state.extend_from_slice(digests);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    #[test]
+    fn load_from_missing_file_yield_none() {
+        let path = TempDir::create("digester_checkpoint", "load_from_missing_file_yield_none")
+            .join("checkpoint.json");
+
+        assert_eq!(None, DigesterCheckpoint::load_from_file(&path).unwrap());
+    }
+
+    #[test]
+    fn saved_checkpoint_can_be_loaded() {
+        let path = TempDir::create("digester_checkpoint", "saved_checkpoint_can_be_loaded")
+            .join("checkpoint.json");
+        let checkpoint = DigesterCheckpoint {
+            last_processed_immutable: 12,
+            partial_hash_state: b"digest_1digest_2".to_vec(),
+        };
+
+        checkpoint.save_to_file(&path).unwrap();
+
+        assert_eq!(
+            Some(checkpoint),
+            DigesterCheckpoint::load_from_file(&path).unwrap()
+        );
+    }
+}
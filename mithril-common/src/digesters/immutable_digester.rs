@@ -18,7 +18,7 @@ use thiserror::Error;
 ///     use mithril_common::digesters::{ImmutableDigester, ImmutableDigesterError};
 ///     use mithril_common::entities::CardanoDbBeacon;
 ///     use mockall::mock;
-///     use std::path::Path;
+///     use std::path::{Path, PathBuf};
 ///
 ///     mock! {
 ///         pub ImmutableDigesterImpl { }
@@ -33,14 +33,13 @@ use thiserror::Error;
 ///         }
 ///     }
 ///
-///     #[test]
 ///     fn test_mock() {
-///         let mut mock = MockDigesterImpl::new();
+///         let mut mock = MockImmutableDigesterImpl::new();
 ///         mock.expect_compute_digest().return_once(|_, _| {
 ///             Err(ImmutableDigesterError::NotEnoughImmutable {
 ///                 expected_number: 3,
 ///                 found_number: None,
-///                 db_dir: PathBuff::new(),
+///                 db_dir: PathBuf::new(),
 ///             })
 ///         });
 ///     }
@@ -65,7 +64,7 @@ pub enum ImmutableDigesterError {
 
     /// Error raised when there's less than the required number of completed immutables in
     /// the cardano database or even no immutable at all.
-    #[error("At least two immutable chunks should exist in directory '{db_dir}': expected {expected_number} but found {found_number:?}.")]
+    #[error("Not enough immutable chunks exist in directory '{db_dir}': expected {expected_number} but found {found_number:?}.")]
     NotEnoughImmutable {
         /// Expected last [ImmutableFileNumber].
         expected_number: ImmutableFileNumber,
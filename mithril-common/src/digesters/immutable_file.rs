@@ -12,7 +12,7 @@ use std::{
 use thiserror::Error;
 use walkdir::WalkDir;
 
-const IMMUTABLE_FILE_EXTENSIONS: [&str; 3] = ["chunk", "primary", "secondary"];
+pub(crate) const IMMUTABLE_FILE_EXTENSIONS: [&str; 3] = ["chunk", "primary", "secondary"];
 
 fn is_immutable(entry: &walkdir::DirEntry) -> bool {
     let is_file = entry.file_type().is_file();
@@ -22,7 +22,7 @@ fn is_immutable(entry: &walkdir::DirEntry) -> bool {
 }
 
 /// Walk the given path and return the first directory named "immutable" it finds
-fn find_immutables_dir(path_to_walk: &Path) -> Option<PathBuf> {
+pub(crate) fn find_immutables_dir(path_to_walk: &Path) -> Option<PathBuf> {
     WalkDir::new(path_to_walk)
         .into_iter()
         .filter_entry(|e| e.file_type().is_dir())
@@ -80,6 +80,10 @@ pub enum ImmutableFileListingError {
     /// Raised when the "immutable" folder could not be found in a file structure.
     #[error("Couldn't find the 'immutable' folder in '{0:?}'")]
     MissingImmutableFolder(PathBuf),
+
+    /// Raised when the listing was cancelled before its end.
+    #[error("immutable files listing cancelled")]
+    Cancelled,
 }
 
 impl ImmutableFile {
@@ -134,6 +138,25 @@ impl ImmutableFile {
     pub fn list_completed_in_dir(
         dir: &Path,
     ) -> Result<Vec<ImmutableFile>, ImmutableFileListingError> {
+        let files = Self::list_all_in_dir(dir)?;
+
+        match files.last() {
+            // empty list
+            None => Ok(files),
+            // filter out the last immutable file(s)
+            Some(last_file) => {
+                let last_number = last_file.number;
+                Ok(files
+                    .into_iter()
+                    .filter(|f| f.number < last_number)
+                    .collect())
+            }
+        }
+    }
+
+    /// List all [`ImmutableFile`] in a given directory, ordered, the last chunk / primary /
+    /// secondary trio included.
+    pub fn list_all_in_dir(dir: &Path) -> Result<Vec<ImmutableFile>, ImmutableFileListingError> {
         let immutable_dir =
             find_immutables_dir(dir).ok_or(MissingImmutableFolder(dir.to_path_buf()))?;
         let mut files: Vec<ImmutableFile> = vec![];
@@ -150,18 +173,7 @@ impl ImmutableFile {
         }
         files.sort();
 
-        match files.last() {
-            // empty list
-            None => Ok(files),
-            // filter out the last immutable file(s)
-            Some(last_file) => {
-                let last_number = last_file.number;
-                Ok(files
-                    .into_iter()
-                    .filter(|f| f.number < last_number)
-                    .collect())
-            }
-        }
+        Ok(files)
     }
 }
 
@@ -0,0 +1,374 @@
+use std::{
+    collections::VecDeque,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use tokio_util::sync::CancellationToken;
+
+use crate::digesters::{
+    immutable_file::{find_immutables_dir, IMMUTABLE_FILE_EXTENSIONS},
+    ImmutableFile, ImmutableFileListingError,
+};
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Provide the completed [ImmutableFile] of a Cardano database
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait ImmutableFileListingProvider: Sync + Send {
+    /// List the completed [ImmutableFile] of the Cardano database at the given path
+    async fn list_immutable_files(
+        &self,
+        dirpath: &Path,
+    ) -> Result<Vec<ImmutableFile>, ImmutableFileListingError>;
+}
+
+/// An [ImmutableFileListingProvider] that reads the immutable files from the file system with
+/// [list_immutable_files_async], so the listing doesn't block the tokio runtime.
+#[derive(Debug, Default)]
+pub struct DirectoryImmutableFileListingProvider;
+
+#[async_trait]
+impl ImmutableFileListingProvider for DirectoryImmutableFileListingProvider {
+    /// Like [ImmutableFile::list_completed_in_dir], the last trio is skipped since it's not
+    /// complete yet.
+    async fn list_immutable_files(
+        &self,
+        dirpath: &Path,
+    ) -> Result<Vec<ImmutableFile>, ImmutableFileListingError> {
+        let files = list_immutable_files_async(dirpath, CancellationToken::new()).await?;
+
+        match files.last() {
+            None => Ok(files),
+            Some(last_file) => {
+                let last_number = last_file.number;
+                Ok(files
+                    .into_iter()
+                    .filter(|f| f.number < last_number)
+                    .collect())
+            }
+        }
+    }
+}
+
+type ModificationTimeReader = Box<dyn Fn(&Path) -> io::Result<SystemTime> + Sync + Send>;
+
+struct CachedListing {
+    dirpath: PathBuf,
+    immutable_dir_mtime: SystemTime,
+    immutable_files: Vec<ImmutableFile>,
+}
+
+/// An [ImmutableFileListingProvider] that keeps the last listing of another provider in memory.
+///
+/// The cached listing is reused as long as the modification time of the `immutable` directory
+/// of the Cardano database does not change, which avoids slow directory reads on network
+/// file systems.
+pub struct CachingImmutableFileListingProvider {
+    inner: Arc<dyn ImmutableFileListingProvider>,
+    modification_time_reader: ModificationTimeReader,
+    cache: Mutex<Option<CachedListing>>,
+}
+
+impl CachingImmutableFileListingProvider {
+    /// CachingImmutableFileListingProvider factory
+    pub fn new(inner: Arc<dyn ImmutableFileListingProvider>) -> Self {
+        Self {
+            inner,
+            modification_time_reader: Box::new(|path| std::fs::metadata(path)?.modified()),
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Set the function used to read the modification time of a directory
+    pub fn with_modification_time_reader<F>(mut self, modification_time_reader: F) -> Self
+    where
+        F: Fn(&Path) -> io::Result<SystemTime> + Sync + Send + 'static,
+    {
+        self.modification_time_reader = Box::new(modification_time_reader);
+        self
+    }
+
+    /// Drop the cached listing, the next listing will be read from the inner provider
+    pub fn invalidate_cache(&self) {
+        *self.lock_cache() = None;
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, Option<CachedListing>> {
+        // The cache is only a copy of the inner provider listing, it can't be left in an
+        // inconsistent state by a panicking thread.
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[async_trait]
+impl ImmutableFileListingProvider for CachingImmutableFileListingProvider {
+    async fn list_immutable_files(
+        &self,
+        dirpath: &Path,
+    ) -> Result<Vec<ImmutableFile>, ImmutableFileListingError> {
+        let immutable_dir_mtime = match find_immutables_dir(dirpath)
+            .map(|immutable_dir| (self.modification_time_reader)(&immutable_dir))
+        {
+            Some(Ok(mtime)) => mtime,
+            // Let the inner provider report the error
+            _ => return self.inner.list_immutable_files(dirpath).await,
+        };
+
+        if let Some(cached_listing) = self.lock_cache().as_ref().filter(|cached| {
+            cached.dirpath == dirpath && cached.immutable_dir_mtime == immutable_dir_mtime
+        }) {
+            return Ok(cached_listing.immutable_files.clone());
+        }
+
+        // The cache is not locked while the inner provider lists the files
+        let immutable_files = self.inner.list_immutable_files(dirpath).await?;
+        *self.lock_cache() = Some(CachedListing {
+            dirpath: dirpath.to_path_buf(),
+            immutable_dir_mtime,
+            immutable_files: immutable_files.clone(),
+        });
+
+        Ok(immutable_files)
+    }
+}
+
+/// List all [`ImmutableFile`] in a given directory, ordered, the last chunk / primary /
+/// secondary trio included, like [ImmutableFile::list_all_in_dir].
+///
+/// The directories are read with [tokio::fs::read_dir] so the listing doesn't block the tokio
+/// runtime, the listing stops with an [ImmutableFileListingError::Cancelled] error as soon as
+/// the given `cancel` token is cancelled.
+pub async fn list_immutable_files_async(
+    path: &Path,
+    cancel: CancellationToken,
+) -> Result<Vec<ImmutableFile>, ImmutableFileListingError> {
+    let immutable_dir = find_immutables_dir_async(path, &cancel)
+        .await?
+        .ok_or(ImmutableFileListingError::MissingImmutableFolder(path.to_path_buf()))?;
+    let mut files: Vec<ImmutableFile> = vec![];
+
+    let mut entries = tokio::fs::read_dir(&immutable_dir).await?;
+    while let Some(entry) = next_entry_unless_cancelled(&mut entries, &cancel).await? {
+        let is_file = entry.file_type().await?.is_file();
+        let path = entry.path();
+        let extension = path.extension().map(|e| e.to_string_lossy());
+
+        if is_file && extension.is_some_and(|e| IMMUTABLE_FILE_EXTENSIONS.contains(&e.as_ref())) {
+            files.push(ImmutableFile::new(path)?);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+/// Asynchronous version of [find_immutables_dir], the directories are walked breadth first.
+async fn find_immutables_dir_async(
+    path_to_walk: &Path,
+    cancel: &CancellationToken,
+) -> Result<Option<PathBuf>, ImmutableFileListingError> {
+    if path_to_walk.file_name().is_some_and(|name| name == "immutable") {
+        return Ok(Some(path_to_walk.to_path_buf()));
+    }
+
+    let mut dirs_to_walk = VecDeque::from([path_to_walk.to_path_buf()]);
+    while let Some(dir) = dirs_to_walk.pop_front() {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = next_entry_unless_cancelled(&mut entries, cancel).await? {
+            if entry.file_type().await?.is_dir() {
+                if entry.file_name() == "immutable" {
+                    return Ok(Some(entry.path()));
+                }
+                dirs_to_walk.push_back(entry.path());
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+async fn next_entry_unless_cancelled(
+    entries: &mut tokio::fs::ReadDir,
+    cancel: &CancellationToken,
+) -> Result<Option<tokio::fs::DirEntry>, ImmutableFileListingError> {
+    if cancel.is_cancelled() {
+        return Err(ImmutableFileListingError::Cancelled);
+    }
+
+    Ok(entries.next_entry().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    use crate::digesters::DummyImmutablesDbBuilder;
+
+    use super::*;
+
+    fn listing_provider_expecting_calls(
+        dirpath: &Path,
+        times: usize,
+    ) -> Arc<MockImmutableFileListingProvider> {
+        let immutable_files = ImmutableFile::list_completed_in_dir(dirpath).unwrap();
+        let mut inner = MockImmutableFileListingProvider::new();
+        inner
+            .expect_list_immutable_files()
+            .returning(move |_| Ok(immutable_files.clone()))
+            .times(times);
+
+        Arc::new(inner)
+    }
+
+    /// Modification time reader returning the number of seconds stored in `mtime`
+    fn fake_mtime_reader(
+        mtime: Arc<AtomicU64>,
+    ) -> impl Fn(&Path) -> io::Result<SystemTime> + Sync + Send + 'static {
+        move |_| {
+            Ok(SystemTime::UNIX_EPOCH + Duration::from_secs(mtime.load(Ordering::SeqCst)))
+        }
+    }
+
+    #[tokio::test]
+    async fn listing_twice_with_the_same_mtime_reads_the_inner_provider_once() {
+        let db = DummyImmutablesDbBuilder::new("caching_listing_same_mtime")
+            .with_immutables(&[1, 2, 3])
+            .append_immutable_trio()
+            .build();
+        let mtime = Arc::new(AtomicU64::new(10));
+        let provider =
+            CachingImmutableFileListingProvider::new(listing_provider_expecting_calls(&db.dir, 1))
+                .with_modification_time_reader(fake_mtime_reader(mtime));
+
+        let first_listing = provider.list_immutable_files(&db.dir).await.unwrap();
+        let second_listing = provider.list_immutable_files(&db.dir).await.unwrap();
+
+        assert!(!first_listing.is_empty());
+        assert_eq!(first_listing, second_listing);
+    }
+
+    #[tokio::test]
+    async fn listing_after_a_mtime_change_reads_the_inner_provider_again() {
+        let db = DummyImmutablesDbBuilder::new("caching_listing_mtime_change")
+            .with_immutables(&[1, 2])
+            .append_immutable_trio()
+            .build();
+        let mtime = Arc::new(AtomicU64::new(10));
+        let provider =
+            CachingImmutableFileListingProvider::new(listing_provider_expecting_calls(&db.dir, 2))
+                .with_modification_time_reader(fake_mtime_reader(mtime.clone()));
+
+        provider.list_immutable_files(&db.dir).await.unwrap();
+        mtime.store(20, Ordering::SeqCst);
+        provider.list_immutable_files(&db.dir).await.unwrap();
+        provider.list_immutable_files(&db.dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn listing_after_invalidate_cache_reads_the_inner_provider_again() {
+        let db = DummyImmutablesDbBuilder::new("caching_listing_invalidate_cache")
+            .with_immutables(&[1, 2])
+            .append_immutable_trio()
+            .build();
+        let mtime = Arc::new(AtomicU64::new(10));
+        let provider =
+            CachingImmutableFileListingProvider::new(listing_provider_expecting_calls(&db.dir, 2))
+                .with_modification_time_reader(fake_mtime_reader(mtime));
+
+        provider.list_immutable_files(&db.dir).await.unwrap();
+        provider.invalidate_cache();
+        provider.list_immutable_files(&db.dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn async_listing_list_the_same_files_than_the_synchronous_listing() {
+        let db = DummyImmutablesDbBuilder::new("async_listing_same_files")
+            .with_immutables(&[1, 2, 3])
+            .with_non_immutables(&["not_an_immutable.txt"])
+            .append_immutable_trio()
+            .build();
+
+        let immutable_files = list_immutable_files_async(&db.dir, CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ImmutableFile::list_all_in_dir(&db.dir).unwrap(),
+            immutable_files
+        );
+    }
+
+    #[tokio::test]
+    async fn directory_provider_skips_the_last_trio_like_the_synchronous_listing() {
+        let db = DummyImmutablesDbBuilder::new("directory_provider_completed_files")
+            .with_immutables(&[1, 2, 3])
+            .append_immutable_trio()
+            .build();
+
+        let immutable_files = DirectoryImmutableFileListingProvider
+            .list_immutable_files(&db.dir)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ImmutableFile::list_completed_in_dir(&db.dir).unwrap(),
+            immutable_files
+        );
+    }
+
+    #[tokio::test]
+    async fn async_listing_without_immutable_folder_fails() {
+        let dir = crate::test_utils::TempDir::create("immutable_file_listing", "async_no_folder");
+
+        let error = list_immutable_files_async(&dir, CancellationToken::new())
+            .await
+            .expect_err("listing without 'immutable' folder should fail");
+
+        assert!(matches!(
+            error,
+            ImmutableFileListingError::MissingImmutableFolder(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn async_listing_of_a_large_directory_stops_when_cancelled() {
+        let immutables: Vec<_> = (1..=3000).collect();
+        let db = DummyImmutablesDbBuilder::new("async_listing_cancelled")
+            .with_immutables(&immutables)
+            .build();
+        let cancel = CancellationToken::new();
+        // Run when the listing yields while reading the directory on the current thread runtime
+        let cancel_clone = cancel.clone();
+        tokio::spawn(async move { cancel_clone.cancel() });
+
+        let error = list_immutable_files_async(&db.dir, cancel)
+            .await
+            .expect_err("cancelled listing should fail");
+
+        assert!(matches!(error, ImmutableFileListingError::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn async_listing_with_an_already_cancelled_token_fails() {
+        let db = DummyImmutablesDbBuilder::new("async_listing_already_cancelled")
+            .with_immutables(&[1, 2])
+            .build();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let error = list_immutable_files_async(&db.dir, cancel)
+            .await
+            .expect_err("cancelled listing should fail");
+
+        assert!(matches!(error, ImmutableFileListingError::Cancelled));
+    }
+}
@@ -2,14 +2,25 @@
 
 pub mod cache;
 mod cardano_immutable_digester;
+mod digest_pipeline;
+mod digester_checkpoint;
 mod dumb_immutable_observer;
 mod immutable_digester;
 mod immutable_file;
+mod immutable_file_listing;
 mod immutable_file_observer;
 
-pub use cardano_immutable_digester::CardanoImmutableDigester;
+pub use cardano_immutable_digester::{
+    CardanoImmutableDigester, DigesterConfig, ImmutableFileProgress,
+};
+pub use digest_pipeline::{DigestPipelineStage, HashFileContents, HashFileMetadata};
+pub use digester_checkpoint::DigesterCheckpoint;
 pub use immutable_digester::{ImmutableDigester, ImmutableDigesterError};
 pub use immutable_file::{ImmutableFile, ImmutableFileCreationError, ImmutableFileListingError};
+pub use immutable_file_listing::{
+    CachingImmutableFileListingProvider, DirectoryImmutableFileListingProvider,
+    ImmutableFileListingProvider, list_immutable_files_async,
+};
 pub use immutable_file_observer::{
     DumbImmutableFileObserver, ImmutableFileObserver, ImmutableFileObserverError,
     ImmutableFileSystemObserver,
@@ -159,7 +159,7 @@ impl BlockRangesSequence {
     ///
     /// The interval bounds will be corrected to be multiples of [BlockRange::LENGTH].
     pub fn new(interval: Range<BlockNumber>) -> Self {
-        let start = if (interval.start % BlockRange::LENGTH) == 0 {
+        let start = if interval.start.is_multiple_of(BlockRange::LENGTH) {
             interval.start
         } else {
             BlockRange::start(interval.start) + BlockRange::LENGTH
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     crypto_helper::MKTreeNode,
     entities::{BlockHash, BlockNumber, ImmutableFileNumber, SlotNumber},
@@ -6,6 +8,21 @@ use crate::{
 /// TransactionHash is the unique identifier of a cardano transaction.
 pub type TransactionHash = String;
 
+/// Version of the pre-image of the Merkle tree leaves of the Cardano transactions
+///
+/// The signers and the aggregator must compute the leaves of the new block ranges with the same
+/// format, the block ranges already stored keep the format their root was computed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardanoTransactionLeafFormat {
+    /// The leaf is computed from the hash of the transaction only
+    #[default]
+    TransactionHash,
+
+    /// The leaf is computed from the hash of the transaction and the hash of its block
+    TransactionAndBlockHash,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 /// Cardano transaction representation
 pub struct CardanoTransaction {
@@ -42,6 +59,41 @@ impl CardanoTransaction {
             immutable_file_number,
         }
     }
+
+    /// Compute the Merkle tree leaf of a transaction with the
+    /// [TransactionAndBlockHash][CardanoTransactionLeafFormat::TransactionAndBlockHash] format.
+    ///
+    /// The hash of the block is part of the pre-image of the leaf so the same transaction
+    /// included in two different blocks, ie: after a chain reorganisation, gives two different
+    /// leaves. Each hash is prefixed by its length so two different pairs of hashes can't give
+    /// the same pre-image.
+    pub fn compute_leaf(transaction_hash: &str, block_hash: &str) -> MKTreeNode {
+        let mut pre_image = Vec::with_capacity(16 + transaction_hash.len() + block_hash.len());
+        for part in [transaction_hash, block_hash] {
+            pre_image.extend_from_slice(&(part.len() as u64).to_be_bytes());
+            pre_image.extend_from_slice(part.as_bytes());
+        }
+
+        MKTreeNode::new(pre_image)
+    }
+
+    /// Compute the Merkle tree leaf of a transaction with the
+    /// [TransactionHash][CardanoTransactionLeafFormat::TransactionHash] format.
+    pub fn compute_legacy_leaf(transaction_hash: &str) -> MKTreeNode {
+        MKTreeNode::new(transaction_hash.as_bytes().to_vec())
+    }
+
+    /// Compute the Merkle tree leaf of this transaction with the given format.
+    pub fn leaf(&self, format: CardanoTransactionLeafFormat) -> MKTreeNode {
+        match format {
+            CardanoTransactionLeafFormat::TransactionHash => {
+                Self::compute_legacy_leaf(&self.transaction_hash)
+            }
+            CardanoTransactionLeafFormat::TransactionAndBlockHash => {
+                Self::compute_leaf(&self.transaction_hash, &self.block_hash)
+            }
+        }
+    }
 }
 
 impl From<CardanoTransaction> for MKTreeNode {
@@ -50,14 +102,18 @@ impl From<CardanoTransaction> for MKTreeNode {
     }
 }
 
+/// Compute the leaf with the [TransactionHash][CardanoTransactionLeafFormat::TransactionHash]
+/// format, use [CardanoTransaction::leaf] to choose the format.
 impl From<&CardanoTransaction> for MKTreeNode {
     fn from(other: &CardanoTransaction) -> Self {
-        MKTreeNode::new(other.transaction_hash.as_bytes().to_vec())
+        CardanoTransaction::compute_legacy_leaf(&other.transaction_hash)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::crypto_helper::MKTree;
+
     use super::*;
 
     #[test]
@@ -71,4 +127,57 @@ mod tests {
         assert_eq!(expected_mk_tree_node, computed_mktree_node);
         assert_ne!(non_expected_mk_tree_node, computed_mktree_node);
     }
+
+    #[test]
+    fn leaf_with_the_transaction_hash_format_is_the_legacy_leaf() {
+        let transaction = CardanoTransaction::new("tx-hash-123", 10, 4, "block_hash", 1);
+
+        assert_eq!(
+            MKTreeNode::from(&transaction),
+            transaction.leaf(CardanoTransactionLeafFormat::TransactionHash)
+        );
+    }
+
+    #[test]
+    fn transactions_at_the_same_slot_in_different_blocks_give_different_merkle_leaves() {
+        let format = CardanoTransactionLeafFormat::TransactionAndBlockHash;
+        let transaction = CardanoTransaction::new("tx-hash-123", 10, 4, "block_hash-1", 1);
+        let reorganised_transaction =
+            CardanoTransaction::new("tx-hash-123", 10, 4, "block_hash-2", 1);
+
+        assert_ne!(
+            transaction.leaf(format),
+            reorganised_transaction.leaf(format)
+        );
+        assert_ne!(
+            MKTree::new(&[transaction.leaf(format)])
+                .unwrap()
+                .compute_root()
+                .unwrap(),
+            MKTree::new(&[reorganised_transaction.leaf(format)])
+                .unwrap()
+                .compute_root()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn leaf_pre_image_is_not_ambiguous() {
+        assert_ne!(
+            CardanoTransaction::compute_leaf("tx-hash-1", "23block_hash"),
+            CardanoTransaction::compute_leaf("tx-hash-123", "block_hash")
+        );
+    }
+
+    #[test]
+    fn leaf_format_is_read_from_its_configuration_value() {
+        assert_eq!(
+            CardanoTransactionLeafFormat::TransactionHash,
+            serde_json::from_str(r#""transaction_hash""#).unwrap()
+        );
+        assert_eq!(
+            CardanoTransactionLeafFormat::TransactionAndBlockHash,
+            serde_json::from_str(r#""transaction_and_block_hash""#).unwrap()
+        );
+    }
 }
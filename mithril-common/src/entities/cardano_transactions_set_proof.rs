@@ -1,12 +1,18 @@
-use crate::crypto_helper::{MKMapProof, ProtocolMkProof};
-use crate::entities::TransactionHash;
+use crate::crypto_helper::{
+    CompressedMKMapProof, MKMapProof, MKProofNodesTable, MKTreeNode, ProtocolMkProof,
+    SparseMKMapProof,
+};
+use crate::entities::{BlockHash, CardanoTransaction, TransactionHash};
 use crate::messages::CardanoTransactionsSetProofMessagePart;
+use anyhow::anyhow;
+
 use crate::{StdError, StdResult};
+use serde::{Deserialize, Serialize};
 
 use super::BlockRange;
 
 cfg_test_tools! {
-    use crate::crypto_helper::{MKMap, MKTree, MKTreeNode, MKMapNode};
+    use crate::crypto_helper::{MKMap, MKTree, MKMapNode};
     use crate::entities::BlockNumber;
     use std::collections::HashMap;
 }
@@ -17,18 +23,37 @@ pub struct CardanoTransactionsSetProof {
     /// Hashes of the certified transactions
     transactions_hashes: Vec<TransactionHash>,
 
+    /// Hashes of the blocks of the certified transactions, in the same order as the
+    /// transactions hashes.
+    ///
+    /// Empty if the leaves of the proof are computed from the transactions hashes only, a
+    /// transaction which leaf is computed from its hash only has an empty block hash.
+    block_hashes: Vec<BlockHash>,
+
     /// Proof of the transactions
     transactions_proof: ProtocolMkProof,
 }
 
+/// Compute the leaf of a transaction of a proof, an empty block hash means the leaf is
+/// computed from the transaction hash only.
+fn proof_leaf(transaction_hash: &str, block_hash: &str) -> MKTreeNode {
+    if block_hash.is_empty() {
+        CardanoTransaction::compute_legacy_leaf(transaction_hash)
+    } else {
+        CardanoTransaction::compute_leaf(transaction_hash, block_hash)
+    }
+}
+
 impl CardanoTransactionsSetProof {
     /// CardanoTransactionsSetProof factory
     pub fn new<T: Into<MKMapProof<BlockRange>>>(
         transactions_hashes: Vec<TransactionHash>,
+        block_hashes: Vec<BlockHash>,
         transactions_proof: T,
     ) -> Self {
         Self {
             transactions_hashes,
+            block_hashes,
             transactions_proof: ProtocolMkProof::new(transactions_proof.into()),
         }
     }
@@ -43,11 +68,41 @@ impl CardanoTransactionsSetProof {
         &self.transactions_hashes
     }
 
+    /// Get the hashes of the blocks of the transactions certified by this proof
+    pub fn block_hashes(&self) -> &[BlockHash] {
+        &self.block_hashes
+    }
+
+    /// Compress the proof of the transactions, see [MKProof::compress_path][crate::crypto_helper::MKProof::compress_path]
+    pub fn to_compressed(&self) -> CompressedCardanoTransactionsSetProof {
+        CompressedCardanoTransactionsSetProof {
+            transactions_hashes: self.transactions_hashes.clone(),
+            block_hashes: self.block_hashes.clone(),
+            transactions_proof: self.transactions_proof.compress_path(),
+        }
+    }
+
+    /// Convert the proof to a [SparseMerkleProof], see [SparseMerkleProof::from_proofs]
+    pub fn to_sparse(&self) -> SparseMerkleProof {
+        SparseMerkleProof::from_proofs(&[self.to_owned()])
+    }
+
     /// Verify that transactions set proof is valid
     pub fn verify(&self) -> StdResult<()> {
+        if !self.block_hashes.is_empty()
+            && self.transactions_hashes.len() != self.block_hashes.len()
+        {
+            return Err(anyhow!(
+                "CardanoTransactionsSetProof has {} transactions hashes but {} block hashes",
+                self.transactions_hashes.len(),
+                self.block_hashes.len()
+            ));
+        }
         self.transactions_proof.verify()?;
-        for hash in &self.transactions_hashes {
-            self.transactions_proof.contains(&hash.to_owned().into())?;
+        for (index, hash) in self.transactions_hashes.iter().enumerate() {
+            let block_hash = self.block_hashes.get(index).map_or("", String::as_str);
+            self.transactions_proof
+                .contains(&proof_leaf(hash, block_hash))?;
         }
 
         Ok(())
@@ -68,18 +123,48 @@ impl CardanoTransactionsSetProof {
             Self::from_leaves(&leaves).unwrap()
         }
 
+        /// Hash of the block used for the transactions of the leaves given to the test helpers
+        pub fn dummy_block_hash(block_number: BlockNumber) -> BlockHash {
+            format!("block-hash-{block_number}")
+        }
+
         /// Helper to create a proof from a list of leaves
         pub fn from_leaves(leaves: &[(BlockNumber, TransactionHash)]) -> StdResult<Self> {
             let transactions_hashes: Vec<TransactionHash> =
                 leaves.iter().map(|(_, t)| t.into()).collect();
-            let mut transactions_by_block_ranges: HashMap<BlockRange, Vec<TransactionHash>> =
+
+            Self::from_subset_of_leaves(leaves, &transactions_hashes)
+        }
+
+        /// Helper to create a proof of the given transactions from a list of leaves
+        ///
+        /// The transactions of a leaf are in the block given by [Self::dummy_block_hash].
+        pub fn from_subset_of_leaves(
+            leaves: &[(BlockNumber, TransactionHash)],
+            transactions_hashes_to_prove: &[TransactionHash],
+        ) -> StdResult<Self> {
+            let transactions_hashes = transactions_hashes_to_prove.to_vec();
+            let block_hashes: Vec<BlockHash> = transactions_hashes
+                .iter()
+                .map(|hash| {
+                    leaves
+                        .iter()
+                        .find(|(_, transaction_hash)| transaction_hash == hash)
+                        .map(|(block_number, _)| Self::dummy_block_hash(*block_number))
+                        .unwrap_or_default()
+                })
+                .collect();
+            let mut transactions_by_block_ranges: HashMap<BlockRange, Vec<MKTreeNode>> =
                 HashMap::new();
             for (block_number, transaction_hash) in leaves {
                 let block_range = BlockRange::from_block_number(*block_number);
                 transactions_by_block_ranges
                     .entry(block_range)
                     .or_default()
-                    .push(transaction_hash.to_owned());
+                    .push(CardanoTransaction::compute_leaf(
+                        transaction_hash,
+                        &Self::dummy_block_hash(*block_number),
+                    ));
             }
             let mk_map = MKMap::new(
                 transactions_by_block_ranges
@@ -95,21 +180,114 @@ impl CardanoTransactionsSetProof {
             )?;
             let mk_leaves: Vec<MKTreeNode> = transactions_hashes
                 .iter()
-                .map(|h| h.to_owned().into())
+                .zip(&block_hashes)
+                .map(|(hash, block_hash)| CardanoTransaction::compute_leaf(hash, block_hash))
                 .collect();
             let mk_proof = mk_map.compute_proof(&mk_leaves)?;
-            Ok(Self::new(transactions_hashes, mk_proof))
+            Ok(Self::new(transactions_hashes, block_hashes, mk_proof))
         }
 
     }
 }
 
+/// Proof of several sets of Cardano transactions where the nodes shared by their Merkle paths
+/// are stored once.
+///
+/// Proving a few transactions of a large set with individual proofs repeats the siblings near
+/// the root in each proof, those siblings are shared here.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SparseMerkleProof {
+    /// Distinct nodes of the proofs
+    nodes: MKProofNodesTable,
+
+    /// Proofs of the transactions, referencing the distinct nodes
+    transactions_proofs: Vec<SparseMKMapProof<BlockRange>>,
+}
+
+impl SparseMerkleProof {
+    /// Create a sparse proof holding the given proofs
+    pub fn from_proofs(proofs: &[CardanoTransactionsSetProof]) -> Self {
+        let mut nodes = MKProofNodesTable::default();
+        let transactions_proofs = proofs
+            .iter()
+            .map(|proof| proof.transactions_proof.to_sparse(&mut nodes))
+            .collect();
+
+        Self {
+            nodes,
+            transactions_proofs,
+        }
+    }
+
+    /// Verify that all the given transactions, identified by their hash and the hash of their
+    /// block, are proven by this proof, against the given hex encoded Merkle root.
+    ///
+    /// The leaf of a transaction with an empty block hash is computed from its hash only.
+    pub fn verify_batch(
+        &self,
+        transactions: &[(TransactionHash, BlockHash)],
+        root: &str,
+    ) -> StdResult<()> {
+        let proofs = self
+            .transactions_proofs
+            .iter()
+            .map(|proof| proof.to_proof(&self.nodes))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for proof in &proofs {
+            let proof_root = proof.compute_root().to_hex();
+            if proof_root != root {
+                return Err(anyhow!(
+                    "SparseMerkleProof root '{proof_root}' does not match the expected root '{root}'"
+                ));
+            }
+            proof.verify()?;
+        }
+        for (hash, block_hash) in transactions {
+            let leaf = proof_leaf(hash, block_hash);
+            if !proofs.iter().any(|proof| proof.contains(&leaf).is_ok()) {
+                return Err(anyhow!(
+                    "SparseMerkleProof does not prove transaction '{hash}'"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A [CardanoTransactionsSetProof] with a compressed proof of the transactions
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressedCardanoTransactionsSetProof {
+    /// Hashes of the certified transactions
+    transactions_hashes: Vec<TransactionHash>,
+
+    /// Hashes of the blocks of the certified transactions
+    #[serde(default)]
+    block_hashes: Vec<BlockHash>,
+
+    /// Compressed proof of the transactions
+    transactions_proof: CompressedMKMapProof<BlockRange>,
+}
+
+impl CompressedCardanoTransactionsSetProof {
+    /// Rebuild the [CardanoTransactionsSetProof]
+    pub fn decompress(&self) -> StdResult<CardanoTransactionsSetProof> {
+        Ok(CardanoTransactionsSetProof::new(
+            self.transactions_hashes.clone(),
+            self.block_hashes.clone(),
+            self.transactions_proof.decompress()?,
+        ))
+    }
+}
+
 impl TryFrom<CardanoTransactionsSetProof> for CardanoTransactionsSetProofMessagePart {
     type Error = StdError;
 
     fn try_from(proof: CardanoTransactionsSetProof) -> Result<Self, Self::Error> {
         Ok(Self {
             transactions_hashes: proof.transactions_hashes,
+            block_hashes: proof.block_hashes,
             proof: proof.transactions_proof.to_json_hex()?,
         })
     }
@@ -121,6 +299,7 @@ impl TryFrom<CardanoTransactionsSetProofMessagePart> for CardanoTransactionsSetP
     fn try_from(proof: CardanoTransactionsSetProofMessagePart) -> Result<Self, Self::Error> {
         Ok(Self {
             transactions_hashes: proof.transactions_hashes,
+            block_hashes: proof.block_hashes,
             transactions_proof: ProtocolMkProof::from_json_hex(&proof.proof)?,
         })
     }
@@ -128,6 +307,8 @@ impl TryFrom<CardanoTransactionsSetProofMessagePart> for CardanoTransactionsSetP
 
 #[cfg(test)]
 mod tests {
+    use crate::crypto_helper::MKProof;
+
     use super::*;
 
     #[test]
@@ -158,11 +339,143 @@ mod tests {
         let proof = CardanoTransactionsSetProof::from_leaves(&leaves).unwrap();
         let mut transactions_hashes_tampered = proof.transactions_hashes().to_vec();
         transactions_hashes_tampered.push("tx-123".to_string());
+        let mut block_hashes_tampered = proof.block_hashes().to_vec();
+        block_hashes_tampered.push(CardanoTransactionsSetProof::dummy_block_hash(22));
         let proof = CardanoTransactionsSetProof {
             transactions_hashes: transactions_hashes_tampered,
+            block_hashes: block_hashes_tampered,
+            ..proof
+        };
+
+        proof.verify().expect_err("The proof should be invalid");
+    }
+
+    #[test]
+    fn should_verify_a_proof_which_leaves_are_the_transactions_hashes() {
+        let transactions_hashes = vec!["tx-1".to_string(), "tx-2".to_string()];
+        let proof = CardanoTransactionsSetProof::new(
+            transactions_hashes.clone(),
+            vec![],
+            MKProof::from_leaves(&transactions_hashes).unwrap(),
+        );
+
+        proof.verify().expect("The proof should be valid");
+    }
+
+    #[test]
+    fn shouldnt_verify_a_proof_with_a_block_hash_missing() {
+        let leaves = vec![(0, "tx-1".to_string()), (1, "tx-2".to_string())];
+        let proof = CardanoTransactionsSetProof::from_leaves(&leaves).unwrap();
+        let proof = CardanoTransactionsSetProof {
+            block_hashes: proof.block_hashes()[..1].to_vec(),
             ..proof
         };
 
         proof.verify().expect_err("The proof should be invalid");
     }
+
+    #[test]
+    fn shouldnt_verify_where_a_transaction_is_in_another_block() {
+        let leaves = vec![
+            (0, "tx-1".to_string()),
+            (1, "tx-2".to_string()),
+            (10, "tx-3".to_string()),
+        ];
+        let proof = CardanoTransactionsSetProof::from_leaves(&leaves).unwrap();
+        let mut block_hashes_tampered = proof.block_hashes().to_vec();
+        block_hashes_tampered[1] = "block-hash-reorganised".to_string();
+        let proof = CardanoTransactionsSetProof {
+            block_hashes: block_hashes_tampered,
+            ..proof
+        };
+
+        proof.verify().expect_err("The proof should be invalid");
+    }
+
+    fn cbor_size<T: Serialize>(value: &T) -> usize {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(value, &mut bytes).unwrap();
+
+        bytes.len()
+    }
+
+    #[test]
+    fn sparse_proof_of_individual_proofs_is_smaller_and_verifies() {
+        let leaves: Vec<(BlockNumber, TransactionHash)> =
+            (0..64).map(|i| (i / 4, format!("tx-{i}"))).collect();
+        let proven_transactions: Vec<(TransactionHash, BlockHash)> = (0..64)
+            .step_by(8)
+            .map(|i| {
+                (
+                    format!("tx-{i}"),
+                    CardanoTransactionsSetProof::dummy_block_hash(i / 4),
+                )
+            })
+            .collect();
+        let individual_proofs: Vec<CardanoTransactionsSetProof> = proven_transactions
+            .iter()
+            .map(|(hash, _)| {
+                CardanoTransactionsSetProof::from_subset_of_leaves(&leaves, &[hash.to_owned()])
+                    .unwrap()
+            })
+            .collect();
+        let root = individual_proofs[0].merkle_root();
+
+        let sparse_proof = SparseMerkleProof::from_proofs(&individual_proofs);
+
+        let individual_proofs_size: usize = individual_proofs
+            .iter()
+            .map(|proof| cbor_size(&proof.transactions_proof.compress_path()))
+            .sum();
+        assert!(
+            cbor_size(&sparse_proof) < individual_proofs_size,
+            "sparse proof ({} bytes) should be smaller than the individual proofs ({individual_proofs_size} bytes)",
+            cbor_size(&sparse_proof)
+        );
+        sparse_proof
+            .verify_batch(&proven_transactions, &root)
+            .expect("The sparse proof should be valid");
+    }
+
+    #[test]
+    fn sparse_proof_fails_to_verify_an_unproven_hash_or_another_root() {
+        let leaves: Vec<(BlockNumber, TransactionHash)> =
+            (0..64).map(|i| (i / 4, format!("tx-{i}"))).collect();
+        let proof =
+            CardanoTransactionsSetProof::from_subset_of_leaves(&leaves, &["tx-8".to_string()])
+                .unwrap();
+        let sparse_proof = proof.to_sparse();
+        let transaction = |hash: &str| {
+            (
+                hash.to_string(),
+                CardanoTransactionsSetProof::dummy_block_hash(2),
+            )
+        };
+
+        sparse_proof
+            .verify_batch(&[transaction("tx-8")], &proof.merkle_root())
+            .expect("The sparse proof should be valid");
+        sparse_proof
+            .verify_batch(&[transaction("tx-9")], &proof.merkle_root())
+            .expect_err("tx-9 is not proven by the sparse proof");
+        sparse_proof
+            .verify_batch(&[transaction("tx-8")], "0a1b2c")
+            .expect_err("The sparse proof should not verify against another root");
+    }
+
+    #[test]
+    fn compressed_proof_decompresses_to_a_proof_valid_against_the_original_root() {
+        let leaves: Vec<(BlockNumber, TransactionHash)> =
+            (0..1024).map(|i| (i / 4, format!("tx-{i}"))).collect();
+        let proof = CardanoTransactionsSetProof::from_leaves(&leaves).unwrap();
+
+        let compressed_proof: CompressedCardanoTransactionsSetProof =
+            serde_json::from_str(&serde_json::to_string(&proof.to_compressed()).unwrap()).unwrap();
+        let decompressed_proof = compressed_proof.decompress().unwrap();
+
+        assert_eq!(proof.merkle_root(), decompressed_proof.merkle_root());
+        decompressed_proof
+            .verify()
+            .expect("The decompressed proof should be valid");
+    }
 }
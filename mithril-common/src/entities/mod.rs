@@ -14,6 +14,7 @@ mod epoch;
 mod epoch_settings;
 mod http_server_error;
 mod mithril_stake_distribution;
+mod network_type;
 mod protocol_message;
 mod protocol_parameters;
 mod signed_entity;
@@ -29,8 +30,10 @@ pub use block_range::{BlockRange, BlockRangeLength, BlockRangesSequence};
 pub use cardano_chain_point::{BlockHash, BlockNumber, ChainPoint, SlotNumber};
 pub use cardano_db_beacon::CardanoDbBeacon;
 pub use cardano_network::CardanoNetwork;
-pub use cardano_transaction::{CardanoTransaction, TransactionHash};
-pub use cardano_transactions_set_proof::CardanoTransactionsSetProof;
+pub use cardano_transaction::{CardanoTransaction, CardanoTransactionLeafFormat, TransactionHash};
+pub use cardano_transactions_set_proof::{
+    CardanoTransactionsSetProof, CompressedCardanoTransactionsSetProof, SparseMerkleProof,
+};
 pub use cardano_transactions_snapshot::CardanoTransactionsSnapshot;
 pub use certificate::{Certificate, CertificateSignature};
 pub use certificate_metadata::{CertificateMetadata, StakeDistributionParty};
@@ -39,6 +42,7 @@ pub use epoch::{Epoch, EpochError};
 pub use epoch_settings::EpochSettings;
 pub use http_server_error::{ClientError, InternalServerError};
 pub use mithril_stake_distribution::MithrilStakeDistribution;
+pub use network_type::NetworkType;
 pub use protocol_message::{ProtocolMessage, ProtocolMessagePartKey, ProtocolMessagePartValue};
 pub use protocol_parameters::ProtocolParameters;
 pub use signed_entity::*;
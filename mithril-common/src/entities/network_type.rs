@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use super::cardano_network::CardanoNetworkError;
+
+/// The type of the public Cardano network targeted by a Mithril network, used to pick its
+/// default [protocol parameters][crate::entities::ProtocolParameters::for_network].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NetworkType {
+    /// The Cardano mainnet network
+    Mainnet,
+
+    /// The Cardano legacy testnet network
+    Testnet,
+
+    /// The Cardano preview network
+    Preview,
+
+    /// The Cardano preprod network
+    Preprod,
+}
+
+impl NetworkType {
+    /// Instantiates a NetworkType from its code (ie: `mainnet`)
+    pub fn from_code(network_code: &str) -> Result<Self, CardanoNetworkError> {
+        match network_code.to_lowercase().as_str() {
+            "mainnet" => Ok(Self::Mainnet),
+            "testnet" => Ok(Self::Testnet),
+            "preview" => Ok(Self::Preview),
+            "preprod" => Ok(Self::Preprod),
+            what => Err(CardanoNetworkError::ParseFromCode(format!(
+                "network '{what}' has no network type, the only recognized network types are: mainnet, testnet, preview and preprod"
+            ))),
+        }
+    }
+}
+
+impl Display for NetworkType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mainnet => write!(f, "mainnet"),
+            Self::Testnet => write!(f, "testnet"),
+            Self::Preview => write!(f, "preview"),
+            Self::Preprod => write!(f, "preprod"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_type_from_code() {
+        for network_type in [
+            NetworkType::Mainnet,
+            NetworkType::Testnet,
+            NetworkType::Preview,
+            NetworkType::Preprod,
+        ] {
+            assert_eq!(
+                network_type,
+                NetworkType::from_code(&network_type.to_string()).unwrap()
+            );
+        }
+        assert_eq!(
+            NetworkType::Mainnet,
+            NetworkType::from_code("MainNet").unwrap()
+        );
+        NetworkType::from_code("devnet").expect_err("devnet has no network type");
+    }
+}
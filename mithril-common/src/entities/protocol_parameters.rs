@@ -1,9 +1,11 @@
 use fixed::types::U8F24;
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use sha2::{Digest, Sha256};
 
+use crate::entities::NetworkType;
+
 /// Protocol cryptographic parameters
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize)]
 pub struct ProtocolParameters {
     /// Quorum parameter
     pub k: u64,
@@ -13,12 +15,59 @@ pub struct ProtocolParameters {
 
     /// f in phi(w) = 1 - (1 - f)^w, where w is the stake of a participant
     pub phi_f: f64,
+
+    /// Use the stake-weighted hash of the signers contributions instead of the flat one
+    ///
+    /// Only serialized when set in the human readable formats, so the flat parameters keep their
+    /// previous wire format.
+    #[serde(default)]
+    pub weighted: bool,
+}
+
+impl Serialize for ProtocolParameters {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // The binary formats are not self-describing: they can't skip a field and still be
+        // deserialized, so they always carry the weighted flag.
+        let serialize_weighted = self.weighted || !serializer.is_human_readable();
+        let mut state = serializer
+            .serialize_struct("ProtocolParameters", if serialize_weighted { 4 } else { 3 })?;
+        state.serialize_field("k", &self.k)?;
+        state.serialize_field("m", &self.m)?;
+        state.serialize_field("phi_f", &self.phi_f)?;
+        if serialize_weighted {
+            state.serialize_field("weighted", &self.weighted)?;
+        } else {
+            state.skip_field("weighted")?;
+        }
+        state.end()
+    }
 }
 
 impl ProtocolParameters {
     /// ProtocolParameters factory
     pub fn new(k: u64, m: u64, phi_f: f64) -> ProtocolParameters {
-        ProtocolParameters { k, m, phi_f }
+        ProtocolParameters {
+            k,
+            m,
+            phi_f,
+            weighted: false,
+        }
+    }
+
+    /// Default protocol parameters of the Mithril networks of the given [NetworkType].
+    ///
+    /// The networks following mainnet (mainnet and preprod) use the mainnet quorum, the other
+    /// test networks use a smaller quorum so they can run with a handful of signers.
+    pub fn for_network(network: NetworkType) -> ProtocolParameters {
+        match network {
+            NetworkType::Mainnet | NetworkType::Preprod => {
+                ProtocolParameters::new(2422, 20973, 0.2)
+            }
+            NetworkType::Testnet | NetworkType::Preview => ProtocolParameters::new(5, 100, 0.65),
+        }
     }
 
     /// phi_f_fixed is a fixed decimal representatio of phi_f
@@ -33,13 +82,20 @@ impl ProtocolParameters {
         hasher.update(self.k.to_be_bytes());
         hasher.update(self.m.to_be_bytes());
         hasher.update(self.phi_f_fixed().to_be_bytes());
+        // Only hashed when set, so the hash of the flat parameters is unchanged
+        if self.weighted {
+            hasher.update([1u8]);
+        }
         hex::encode(hasher.finalize())
     }
 }
 
 impl PartialEq<ProtocolParameters> for ProtocolParameters {
     fn eq(&self, other: &ProtocolParameters) -> bool {
-        self.k == other.k && self.m == other.m && self.phi_f_fixed() == other.phi_f_fixed()
+        self.k == other.k
+            && self.m == other.m
+            && self.phi_f_fixed() == other.phi_f_fixed()
+            && self.weighted == other.weighted
     }
 }
 
@@ -91,5 +147,48 @@ mod tests {
             hash_expected,
             ProtocolParameters::new(1000, 100, 0.124).compute_hash()
         );
+        assert_ne!(
+            hash_expected,
+            ProtocolParameters {
+                weighted: true,
+                ..ProtocolParameters::new(1000, 100, 0.123)
+            }
+            .compute_hash()
+        );
+    }
+
+    #[test]
+    fn serialize_flat_protocol_parameters_without_weighted_flag() {
+        let json = serde_json::to_string(&ProtocolParameters::new(1000, 100, 0.123)).unwrap();
+
+        assert_eq!(r#"{"k":1000,"m":100,"phi_f":0.123}"#, json);
+    }
+
+    #[test]
+    fn deserialize_protocol_parameters_without_weighted_flag() {
+        let protocol_parameters: ProtocolParameters =
+            serde_json::from_str(r#"{ "k": 1000, "m": 100, "phi_f": 0.123 }"#).unwrap();
+
+        assert_eq!(
+            ProtocolParameters::new(1000, 100, 0.123),
+            protocol_parameters
+        );
+    }
+
+    #[test]
+    fn protocol_parameters_for_mainnet_and_testnet_have_different_quorums() {
+        let mainnet = ProtocolParameters::for_network(NetworkType::Mainnet);
+        let testnet = ProtocolParameters::for_network(NetworkType::Testnet);
+
+        assert_ne!(mainnet.k, testnet.k);
+        assert_ne!(mainnet.phi_f_fixed(), testnet.phi_f_fixed());
+        assert_eq!(
+            mainnet,
+            ProtocolParameters::for_network(NetworkType::Preprod)
+        );
+        assert_eq!(
+            testnet,
+            ProtocolParameters::for_network(NetworkType::Preview)
+        );
     }
 }
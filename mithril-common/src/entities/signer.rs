@@ -34,6 +34,11 @@ pub struct Signer {
     // TODO: This kes period should not be used as is and should probably be within an allowed range of kes period for the epoch
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kes_period: Option<KESPeriod>,
+
+    /// Value unique to each registration request, used by the aggregator to detect the
+    /// retries of a registration it already processed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registration_nonce: Option<String>,
 }
 
 impl PartialEq for Signer {
@@ -57,9 +62,16 @@ impl Signer {
             verification_key_signature,
             operational_certificate,
             kes_period,
+            registration_nonce: None,
         }
     }
 
+    /// Set the nonce identifying the registration request of this signer
+    pub fn with_registration_nonce(mut self, registration_nonce: String) -> Self {
+        self.registration_nonce = Some(registration_nonce);
+        self
+    }
+
     /// Convert the given values to a vec of signers.
     pub fn vec_from<T: Into<Signer>>(from: Vec<T>) -> Vec<Self> {
         from.into_iter().map(|f| f.into()).collect()
@@ -102,6 +114,10 @@ impl Debug for Signer {
                     &format_args!("{:?}", self.operational_certificate),
                 )
                 .field("kes_period", &format_args!("{:?}", self.kes_period))
+                .field(
+                    "registration_nonce",
+                    &format_args!("{:?}", self.registration_nonce),
+                )
                 .finish(),
             false => debug.finish_non_exhaustive(),
         }
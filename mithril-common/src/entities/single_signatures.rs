@@ -1,3 +1,5 @@
+#[cfg(feature = "fs")]
+use anyhow::Context;
 use mithril_stm::stm::StmSig;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
@@ -7,6 +9,14 @@ use crate::{
     entities::{LotteryIndex, PartyId},
 };
 
+#[cfg(feature = "fs")]
+use crate::StdResult;
+
+/// Maximum size of a decompressed batch of single signatures read by
+/// [SingleSignatures::from_compressed_batch]
+#[cfg(feature = "fs")]
+pub const MAX_DECOMPRESSED_BATCH_SIZE: u64 = 16 * 1024 * 1024;
+
 /// SingleSignatures represent single signatures originating from a participant in the network
 /// for a digest at won lottery indexes
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -40,6 +50,44 @@ impl SingleSignatures {
     pub fn to_protocol_signature(&self) -> StmSig {
         self.signature.clone().into()
     }
+
+    /// Serialize the given signatures to a JSON array compressed with zstd, to reduce the size
+    /// of the batches sent over the network for epochs with many signers.
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub fn to_compressed_batch(signatures: &[Self]) -> StdResult<Vec<u8>> {
+        let json = serde_json::to_vec(signatures)
+            .with_context(|| "Could not serialize the single signatures batch")?;
+        let compressed = zstd::encode_all(json.as_slice(), zstd::DEFAULT_COMPRESSION_LEVEL)
+            .with_context(|| "Could not compress the single signatures batch")?;
+
+        Ok(compressed)
+    }
+
+    /// Read back a batch of signatures produced by [Self::to_compressed_batch].
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    ///
+    /// Batches larger than [MAX_DECOMPRESSED_BATCH_SIZE] once decompressed are rejected.
+    pub fn from_compressed_batch(bytes: &[u8]) -> StdResult<Vec<Self>> {
+        use std::io::Read;
+
+        let mut json = Vec::new();
+        zstd::stream::read::Decoder::new(bytes)
+            .with_context(|| "Could not decompress the single signatures batch")?
+            .take(MAX_DECOMPRESSED_BATCH_SIZE + 1)
+            .read_to_end(&mut json)
+            .with_context(|| "Could not decompress the single signatures batch")?;
+        if json.len() as u64 > MAX_DECOMPRESSED_BATCH_SIZE {
+            return Err(anyhow::anyhow!(
+                "The single signatures batch exceeds {MAX_DECOMPRESSED_BATCH_SIZE} bytes once decompressed"
+            ));
+        }
+        let signatures = serde_json::from_slice(&json)
+            .with_context(|| "Could not deserialize the single signatures batch")?;
+
+        Ok(signatures)
+    }
 }
 
 impl Debug for SingleSignatures {
@@ -82,4 +130,66 @@ mod tests {
 
         assert_eq!(protocol_sigs, signature.to_protocol_signature());
     }
+
+    #[cfg(feature = "fs")]
+    fn synthetic_batch(nb_signers: usize) -> Vec<SingleSignatures> {
+        let signature = crate::test_utils::fake_data::single_signatures(vec![]);
+        (0..nb_signers)
+            .map(|i| SingleSignatures {
+                party_id: format!("pool1{i:0>50}"),
+                won_indexes: vec![i as u64, i as u64 + 7, i as u64 + 19],
+                ..signature.clone()
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn compressed_batch_can_be_read_back() {
+        let signatures = synthetic_batch(10);
+
+        let compressed = SingleSignatures::to_compressed_batch(&signatures).unwrap();
+
+        assert_eq!(
+            signatures,
+            SingleSignatures::from_compressed_batch(&compressed).unwrap()
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn compressed_batch_too_large_once_decompressed_is_rejected() {
+        let mut json = serde_json::to_vec(&synthetic_batch(1)).unwrap();
+        json.resize(json.len() + MAX_DECOMPRESSED_BATCH_SIZE as usize, b' ');
+        let compressed = zstd::encode_all(json.as_slice(), 0).unwrap();
+
+        SingleSignatures::from_compressed_batch(&compressed)
+            .expect_err("batch exceeding the decompressed size limit should be rejected");
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn compressed_batch_of_500_signers_is_at_least_three_times_smaller() {
+        let signatures = synthetic_batch(500);
+
+        let uncompressed = serde_json::to_vec(&signatures).unwrap();
+        let compressed = SingleSignatures::to_compressed_batch(&signatures).unwrap();
+
+        let ratio = uncompressed.len() as f64 / compressed.len() as f64;
+        assert!(
+            ratio >= 3.0,
+            "compression ratio should be at least 3, got {ratio:.2} ({} -> {} bytes)",
+            uncompressed.len(),
+            compressed.len()
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn from_compressed_batch_fails_with_uncompressed_bytes() {
+        let uncompressed = serde_json::to_vec(&synthetic_batch(2)).unwrap();
+
+        SingleSignatures::from_compressed_batch(&uncompressed)
+            .expect_err("Reading uncompressed bytes should fail");
+    }
 }
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 
@@ -5,7 +6,7 @@ use crate::entities::{ChainPoint, Epoch, ImmutableFileNumber};
 
 /// TimePoint aggregates all types of point in the Cardano chain and is used by the state machines
 /// for their computations.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimePoint {
     /// Cardano chain epoch number
     pub epoch: Epoch,
@@ -6,7 +6,7 @@
 //! Provide:
 //! - [Digester][digesters] to compute mithril digest from a Cardano database
 //! - Helpers for the [Mithril STM](https://mithril.network/rust-doc/mithril_stm/index.html)
-//! lib with the [crypto_helper].
+//!   lib with the [crypto_helper].
 //! - [certificate chain][certificate_chain] used to validate the Certificate Chain created by an aggregator
 //! - The [entities] used by, and exchanged between, the aggregator, signers and client.
 
@@ -4,6 +4,7 @@ use crate::entities::{
 };
 use crate::messages::CardanoTransactionsSetProofMessagePart;
 use crate::StdError;
+use chrono::{NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -28,6 +29,14 @@ pub struct CardanoTransactionsProofsMessage {
 
     /// Latest block number that has been certified
     pub latest_block_number: BlockNumber,
+
+    /// Maximum number of certified transactions accepted by [Self::verify], unbounded if `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_transactions: Option<usize>,
+
+    /// Maximum size in bytes of each encoded proof accepted by [Self::verify], unbounded if `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_proof_bytes: Option<usize>,
 }
 
 #[cfg_attr(
@@ -63,6 +72,11 @@ impl VerifiedCardanoTransactions {
         &self.certificate_hash
     }
 
+    /// Hex encoded Merkle root of the verified transactions
+    pub fn merkle_root(&self) -> &str {
+        &self.merkle_root
+    }
+
     /// Hashes of the certified transactions
     pub fn certified_transactions(&self) -> &[TransactionHash] {
         &self.certified_transactions
@@ -83,6 +97,23 @@ impl VerifiedCardanoTransactions {
     }
 }
 
+/// Audit log of a [cardano transaction proof verification][CardanoTransactionsProofsMessage::verify_with_audit],
+/// filled whether the verification succeeds or fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationAuditLog {
+    /// Number of proofs checked, including the failing one if any
+    pub proofs_checked: usize,
+
+    /// Index of the proof that failed the verification, if any
+    pub first_failure_index: Option<usize>,
+
+    /// Serialized form of the proof that failed the verification, if any
+    pub raw_proof_bytes: Option<Vec<u8>>,
+
+    /// Date and time of the verification
+    pub timestamp: NaiveDateTime,
+}
+
 /// Error encountered or produced by the [cardano transaction proof verification][CardanoTransactionsProofsMessage::verify].
 #[derive(Error, Debug)]
 pub enum VerifyCardanoTransactionsProofsError {
@@ -110,6 +141,18 @@ pub enum VerifyCardanoTransactionsProofsError {
     /// [CardanoTransactionsProofsMessage] for verification.
     #[error("Malformed data or unknown Cardano Set Proof format")]
     MalformedData(#[source] StdError),
+
+    /// The message is bigger than the limits set with
+    /// [CardanoTransactionsProofsMessage::with_max_proof_size].
+    #[error("Field '{field}' exceeds its maximum size: {actual} > {max}")]
+    ExceedsMaxSize {
+        /// Name of the field that is too big
+        field: &'static str,
+        /// Actual size of the field
+        actual: usize,
+        /// Maximum allowed size of the field
+        max: usize,
+    },
 }
 
 impl CardanoTransactionsProofsMessage {
@@ -125,9 +168,86 @@ impl CardanoTransactionsProofsMessage {
             certified_transactions,
             non_certified_transactions,
             latest_block_number,
+            max_transactions: None,
+            max_proof_bytes: None,
         }
     }
 
+    /// Rebuild a `CardanoTransactionsProofsMessage` from the result of its
+    /// [verification][Self::verify] and the proofs parts that were verified.
+    ///
+    /// The non certified transactions are not part of a [VerifiedCardanoTransactions] so they
+    /// are left empty.
+    pub fn from_verified(
+        verified: &VerifiedCardanoTransactions,
+        original_proofs: Vec<CardanoTransactionsSetProofMessagePart>,
+    ) -> Self {
+        Self::new(
+            verified.certificate_hash(),
+            original_proofs,
+            vec![],
+            verified.latest_block_number,
+        )
+    }
+
+    /// Set the size limits enforced when verifying this message:
+    /// * `max_transactions`: maximum number of certified transactions hashes
+    /// * `max_proof_bytes`: maximum size of each hex encoded proof
+    pub fn with_max_proof_size(mut self, max_transactions: usize, max_proof_bytes: usize) -> Self {
+        self.max_transactions = Some(max_transactions);
+        self.max_proof_bytes = Some(max_proof_bytes);
+        self
+    }
+
+    /// Check that this message is within the limits set with [Self::with_max_proof_size].
+    pub fn validate_size(&self) -> Result<(), VerifyCardanoTransactionsProofsError> {
+        if let Some(max) = self.max_transactions {
+            let actual = self
+                .certified_transactions
+                .iter()
+                .map(|ct| ct.transactions_hashes.len())
+                .sum();
+            if actual > max {
+                return Err(VerifyCardanoTransactionsProofsError::ExceedsMaxSize {
+                    field: "certified_transactions",
+                    actual,
+                    max,
+                });
+            }
+        }
+
+        if let Some(max) = self.max_proof_bytes {
+            if let Some(actual) = self
+                .certified_transactions
+                .iter()
+                .map(|ct| ct.proof.len())
+                .find(|len| *len > max)
+            {
+                return Err(VerifyCardanoTransactionsProofsError::ExceedsMaxSize {
+                    field: "proof",
+                    actual,
+                    max,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merkle root of the first certified transactions proof that can be decoded, without
+    /// checking the proofs.
+    ///
+    /// Allows a quick check against the Merkle root signed by a certificate before the full
+    /// [verification][Self::verify], which remains needed to trust the transactions.
+    pub fn expected_merkle_root(&self) -> Option<String> {
+        self.certified_transactions.iter().find_map(|part| {
+            CardanoTransactionsSetProof::try_from(part.clone())
+                .ok()
+                .map(|proof| proof.merkle_root())
+                .filter(|merkle_root| !merkle_root.is_empty())
+        })
+    }
+
     /// Verify that all the certified transactions proofs are valid
     ///
     /// The following checks will be executed:
@@ -138,29 +258,72 @@ impl CardanoTransactionsProofsMessage {
     ///
     /// 3 - Assert that there's at least one certified transaction
     ///
+    /// Before those checks, the message size is validated against the limits set
+    /// with [Self::with_max_proof_size] if any.
+    ///
     /// If every check is okay, the hex encoded Merkle root of the proof will be returned.
     pub fn verify(
         &self,
     ) -> Result<VerifiedCardanoTransactions, VerifyCardanoTransactionsProofsError> {
+        self.verify_with_audit().0
+    }
+
+    /// Same as [Self::verify], but also returns an audit log of the verification telling which
+    /// proof failed and what it looked like.
+    pub fn verify_with_audit(
+        &self,
+    ) -> (
+        Result<VerifiedCardanoTransactions, VerifyCardanoTransactionsProofsError>,
+        VerificationAuditLog,
+    ) {
+        let mut audit_log = VerificationAuditLog {
+            proofs_checked: 0,
+            first_failure_index: None,
+            raw_proof_bytes: None,
+            timestamp: Utc::now().naive_utc(),
+        };
+        let result = self.verify_proofs(&mut audit_log);
+
+        (result, audit_log)
+    }
+
+    fn verify_proofs(
+        &self,
+        audit_log: &mut VerificationAuditLog,
+    ) -> Result<VerifiedCardanoTransactions, VerifyCardanoTransactionsProofsError> {
+        self.validate_size()?;
         let mut merkle_root = None;
 
-        for certified_transaction in &self.certified_transactions {
-            let certified_transaction: CardanoTransactionsSetProof = certified_transaction
-                .clone()
-                .try_into()
-                .map_err(VerifyCardanoTransactionsProofsError::MalformedData)?;
-            certified_transaction.verify().map_err(|e| {
-                VerifyCardanoTransactionsProofsError::InvalidSetProof {
+        for (index, certified_transaction_part) in self.certified_transactions.iter().enumerate() {
+            audit_log.proofs_checked += 1;
+            let mut record_failure = || {
+                audit_log.first_failure_index = Some(index);
+                audit_log.raw_proof_bytes =
+                    Some(certified_transaction_part.proof.as_bytes().to_vec());
+            };
+
+            let certified_transaction: CardanoTransactionsSetProof =
+                match certified_transaction_part.clone().try_into() {
+                    Ok(certified_transaction) => certified_transaction,
+                    Err(e) => {
+                        record_failure();
+                        return Err(VerifyCardanoTransactionsProofsError::MalformedData(e));
+                    }
+                };
+            if let Err(e) = certified_transaction.verify() {
+                record_failure();
+                return Err(VerifyCardanoTransactionsProofsError::InvalidSetProof {
                     transactions_hashes: certified_transaction.transactions_hashes().to_vec(),
                     source: e,
-                }
-            })?;
+                });
+            }
 
             let tx_merkle_root = Some(certified_transaction.merkle_root());
 
             if merkle_root.is_none() {
                 merkle_root = tx_merkle_root;
             } else if merkle_root != tx_merkle_root {
+                record_failure();
                 return Err(VerifyCardanoTransactionsProofsError::NonMatchingMerkleRoot);
             }
         }
@@ -182,6 +345,7 @@ impl CardanoTransactionsProofsMessage {
 #[cfg(test)]
 mod tests {
     use crate::crypto_helper::MKProof;
+    use crate::entities::CardanoTransaction;
 
     use super::*;
 
@@ -191,6 +355,7 @@ mod tests {
             "whatever",
             vec![CardanoTransactionsSetProofMessagePart {
                 transactions_hashes: vec![],
+                block_hashes: vec![],
                 proof: "invalid".to_string(),
             }],
             vec![],
@@ -250,10 +415,88 @@ mod tests {
         assert_eq!(expected, verified_txs);
     }
 
+    #[test]
+    fn expected_merkle_root_is_the_merkle_root_of_the_verified_transactions() {
+        let txs_proofs = CardanoTransactionsProofsMessage::new(
+            "whatever",
+            vec![CardanoTransactionsSetProof::dummy().try_into().unwrap()],
+            vec![],
+            99999,
+        );
+
+        let verified_txs = txs_proofs
+            .verify()
+            .expect("Valid txs proofs should verify itself");
+
+        assert_eq!(
+            Some(verified_txs.merkle_root().to_string()),
+            txs_proofs.expected_merkle_root()
+        );
+    }
+
+    #[test]
+    fn expected_merkle_root_of_malformed_proofs_is_none() {
+        let txs_proofs = CardanoTransactionsProofsMessage::new(
+            "whatever",
+            vec![CardanoTransactionsSetProofMessagePart {
+                transactions_hashes: vec![],
+                block_hashes: vec![],
+                proof: "invalid".to_string(),
+            }],
+            vec![],
+            99999,
+        );
+
+        assert_eq!(None, txs_proofs.expected_merkle_root());
+    }
+
+    #[test]
+    fn message_rebuilt_from_verified_transactions_is_equal_to_the_verified_message() {
+        let txs_proofs = CardanoTransactionsProofsMessage::new(
+            "whatever",
+            vec![CardanoTransactionsSetProof::dummy().try_into().unwrap()],
+            vec![],
+            99999,
+        );
+        let verified_txs = txs_proofs
+            .verify()
+            .expect("Valid txs proofs should verify itself");
+
+        let rebuilt_txs_proofs = CardanoTransactionsProofsMessage::from_verified(
+            &verified_txs,
+            txs_proofs.certified_transactions.clone(),
+        );
+
+        assert_eq!(txs_proofs, rebuilt_txs_proofs);
+    }
+
+    #[test]
+    fn verify_proofs_of_a_message_without_block_hashes() {
+        let set_proof = CardanoTransactionsSetProof::new(
+            vec!["tx-1".to_string()],
+            vec![],
+            MKProof::from_leaves(&["tx-1"]).unwrap(),
+        );
+        let message_part: CardanoTransactionsSetProofMessagePart = set_proof.try_into().unwrap();
+        let json = serde_json::to_value(&message_part).unwrap();
+        assert!(json.get("block_hashes").is_none());
+        let txs_proofs = CardanoTransactionsProofsMessage::new(
+            "whatever",
+            vec![serde_json::from_value(json).unwrap()],
+            vec![],
+            99999,
+        );
+
+        txs_proofs
+            .verify()
+            .expect("Proofs without block hashes should verify");
+    }
+
     #[test]
     fn verify_invalid_proofs() {
         let set_proof = CardanoTransactionsSetProof::new(
             vec!["invalid1".to_string()],
+            vec!["block-hash".to_string()],
             MKProof::from_leaves(&["invalid2"]).unwrap(),
         );
         let txs_proofs = CardanoTransactionsProofsMessage::new(
@@ -282,11 +525,15 @@ mod tests {
         let set_proofs = vec![
             CardanoTransactionsSetProof::new(
                 vec!["tx-1".to_string()],
-                MKProof::from_leaves(&["tx-1"]).unwrap(),
+                vec!["block-hash-1".to_string()],
+                MKProof::from_leaves(&[CardanoTransaction::compute_leaf("tx-1", "block-hash-1")])
+                    .unwrap(),
             ),
             CardanoTransactionsSetProof::new(
                 vec!["tx-2".to_string()],
-                MKProof::from_leaves(&["tx-2"]).unwrap(),
+                vec!["block-hash-2".to_string()],
+                MKProof::from_leaves(&[CardanoTransaction::compute_leaf("tx-2", "block-hash-2")])
+                    .unwrap(),
             ),
         ];
         let txs_proofs = CardanoTransactionsProofsMessage::new(
@@ -306,17 +553,168 @@ mod tests {
         assert!(
             matches!(
                 error,
-                VerifyCardanoTransactionsProofsError::NonMatchingMerkleRoot { .. },
+                VerifyCardanoTransactionsProofsError::NonMatchingMerkleRoot,
             ),
             "Expected 'NonMatchingMerkleRoot' error but got '{:?}'",
             error
         );
     }
 
+    #[test]
+    fn verify_with_audit_of_invalid_proof_log_the_failing_proof() {
+        let set_proof = CardanoTransactionsSetProof::new(
+            vec!["invalid1".to_string()],
+            vec!["block-hash".to_string()],
+            MKProof::from_leaves(&["invalid2"]).unwrap(),
+        );
+        let message_part: CardanoTransactionsSetProofMessagePart = set_proof.try_into().unwrap();
+        let txs_proofs = CardanoTransactionsProofsMessage::new(
+            "whatever",
+            vec![message_part.clone()],
+            vec![],
+            99999,
+        );
+
+        let (result, audit_log) = txs_proofs.verify_with_audit();
+
+        assert!(
+            matches!(
+                result,
+                Err(VerifyCardanoTransactionsProofsError::InvalidSetProof { .. }),
+            ),
+            "Expected 'InvalidSetProof' error but got '{:?}'",
+            result
+        );
+        assert_eq!(1, audit_log.proofs_checked);
+        assert_eq!(Some(0), audit_log.first_failure_index);
+        assert_eq!(
+            Some(message_part.proof.into_bytes()),
+            audit_log.raw_proof_bytes
+        );
+    }
+
+    #[test]
+    fn verify_with_audit_of_valid_proofs_log_the_checked_proofs() {
+        let txs_proofs = CardanoTransactionsProofsMessage::new(
+            "whatever",
+            vec![CardanoTransactionsSetProof::dummy().try_into().unwrap()],
+            vec![],
+            99999,
+        );
+
+        let (result, audit_log) = txs_proofs.verify_with_audit();
+
+        result.expect("Valid txs proofs should verify itself");
+        assert_eq!(1, audit_log.proofs_checked);
+        assert_eq!(None, audit_log.first_failure_index);
+        assert_eq!(None, audit_log.raw_proof_bytes);
+    }
+
+    fn proofs_message_for_size_tests(
+        set_proof: CardanoTransactionsSetProof,
+    ) -> (usize, usize, CardanoTransactionsProofsMessage) {
+        let message_part: CardanoTransactionsSetProofMessagePart = set_proof.try_into().unwrap();
+        let nb_transactions = message_part.transactions_hashes.len();
+        let proof_size = message_part.proof.len();
+        let message =
+            CardanoTransactionsProofsMessage::new("whatever", vec![message_part], vec![], 99999);
+
+        (nb_transactions, proof_size, message)
+    }
+
+    #[test]
+    fn verify_proofs_at_size_limit_succeed() {
+        let (nb_transactions, proof_size, txs_proofs) =
+            proofs_message_for_size_tests(CardanoTransactionsSetProof::dummy());
+
+        txs_proofs
+            .with_max_proof_size(nb_transactions, proof_size)
+            .verify()
+            .expect("Txs proofs exactly at the size limits should verify itself");
+    }
+
+    #[test]
+    fn verify_proofs_with_too_many_transactions_fail() {
+        let (nb_transactions, proof_size, txs_proofs) =
+            proofs_message_for_size_tests(CardanoTransactionsSetProof::dummy());
+
+        let error = txs_proofs
+            .with_max_proof_size(nb_transactions - 1, proof_size)
+            .verify()
+            .expect_err("Txs proofs with too many transactions should fail to verify itself");
+
+        assert!(
+            matches!(
+                error,
+                VerifyCardanoTransactionsProofsError::ExceedsMaxSize {
+                    field: "certified_transactions",
+                    ..
+                },
+            ),
+            "Expected 'ExceedsMaxSize' error but got '{:?}'",
+            error
+        );
+    }
+
+    #[test]
+    fn verify_proofs_with_too_big_proof_fail() {
+        let (nb_transactions, proof_size, txs_proofs) =
+            proofs_message_for_size_tests(CardanoTransactionsSetProof::dummy());
+
+        let error = txs_proofs
+            .with_max_proof_size(nb_transactions, proof_size - 1)
+            .verify()
+            .expect_err("Txs proofs with a too big proof should fail to verify itself");
+
+        assert!(
+            matches!(
+                error,
+                VerifyCardanoTransactionsProofsError::ExceedsMaxSize {
+                    field: "proof",
+                    actual,
+                    max,
+                } if actual == proof_size && max == proof_size - 1,
+            ),
+            "Expected 'ExceedsMaxSize' error but got '{:?}'",
+            error
+        );
+    }
+
+    #[test]
+    fn size_limits_are_deserialized_with_the_message() {
+        let (nb_transactions, proof_size, txs_proofs) =
+            proofs_message_for_size_tests(CardanoTransactionsSetProof::dummy());
+        let txs_proofs = txs_proofs.with_max_proof_size(nb_transactions, proof_size - 1);
+
+        let json = serde_json::to_string(&txs_proofs).unwrap();
+        let deserialized: CardanoTransactionsProofsMessage = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(Some(nb_transactions), deserialized.max_transactions);
+        assert_eq!(Some(proof_size - 1), deserialized.max_proof_bytes);
+        deserialized
+            .validate_size()
+            .expect_err("The deserialized size limits should be enforced");
+    }
+
+    #[test]
+    fn message_without_size_limits_is_deserialized_unbounded() {
+        let (_, _, txs_proofs) =
+            proofs_message_for_size_tests(CardanoTransactionsSetProof::dummy());
+
+        let json = serde_json::to_string(&txs_proofs).unwrap();
+        let deserialized: CardanoTransactionsProofsMessage = serde_json::from_str(&json).unwrap();
+
+        assert!(!json.contains("max_transactions"));
+        assert_eq!(None, deserialized.max_transactions);
+        assert_eq!(None, deserialized.max_proof_bytes);
+    }
+
     #[cfg(feature = "fs")]
     mod fs_only {
         use crate::crypto_helper::{MKMap, MKMapNode};
-        use crate::entities::{BlockNumber, BlockRange, CardanoTransaction};
+        use crate::entities::{
+            BlockNumber, BlockRange, CardanoTransaction, CardanoTransactionLeafFormat,
+        };
         use crate::signable_builder::{
             CardanoTransactionsSignableBuilder, MockBlockRangeRootRetriever,
             MockTransactionsImporter, SignableBuilder,
@@ -330,8 +728,20 @@ mod tests {
         async fn verify_hashes_from_verified_cardano_transaction_and_from_signable_builder_are_equals(
         ) {
             let transactions = vec![
-                CardanoTransaction::new("tx-hash-123", 10, 1, "block_hash", 1),
-                CardanoTransaction::new("tx-hash-456", 20, 2, "block_hash", 1),
+                CardanoTransaction::new(
+                    "tx-hash-123",
+                    10,
+                    1,
+                    CardanoTransactionsSetProof::dummy_block_hash(10),
+                    1,
+                ),
+                CardanoTransaction::new(
+                    "tx-hash-456",
+                    20,
+                    2,
+                    CardanoTransactionsSetProof::dummy_block_hash(20),
+                    1,
+                ),
             ];
 
             assert_eq!(
@@ -393,7 +803,9 @@ mod tests {
                         transactions_imported.into_iter().map(|tx| {
                             (
                                 BlockRange::from_block_number(tx.block_number),
-                                MKMapNode::TreeNode(tx.transaction_hash.clone().into()),
+                                MKMapNode::TreeNode(
+                                    tx.leaf(CardanoTransactionLeafFormat::TransactionAndBlockHash),
+                                ),
                             )
                         }),
                     )
@@ -47,11 +47,13 @@ impl CertificatePendingMessage {
                     k: 5,
                     m: 100,
                     phi_f: 0.65,
+                    weighted: false,
                 },
                 next_protocol_parameters: ProtocolParameters {
                     k: 50,
                     m: 1000,
                     phi_f: 0.65,
+                    weighted: false,
                 },
                 signers: [SignerMessagePart::dummy()].to_vec(),
                 next_signers: [SignerMessagePart::dummy()].to_vec(),
@@ -82,11 +84,13 @@ mod tests {
                 k: 5,
                 m: 100,
                 phi_f: 0.65,
+                weighted: false,
             },
             next_protocol_parameters: ProtocolParameters {
                 k: 50,
                 m: 1000,
                 phi_f: 0.65,
+                weighted: false,
             },
             signers: vec![
                 SignerMessagePart {
@@ -25,11 +25,13 @@ impl EpochSettingsMessage {
                 k: 5,
                 m: 100,
                 phi_f: 0.65,
+                weighted: false,
             },
             next_protocol_parameters: ProtocolParameters {
                 k: 5,
                 m: 100,
                 phi_f: 0.65,
+                weighted: false,
             },
         }
     }
@@ -46,11 +48,13 @@ mod tests {
                 k: 5,
                 m: 100,
                 phi_f: 0.65,
+                weighted: false,
             },
             next_protocol_parameters: ProtocolParameters {
                 k: 50,
                 m: 1000,
                 phi_f: 0.65,
+                weighted: false,
             },
         }
     }
@@ -1,4 +1,4 @@
-use crate::entities::{HexEncodedKey, TransactionHash};
+use crate::entities::{BlockHash, HexEncodedKey, TransactionHash};
 use serde::{Deserialize, Serialize};
 
 #[cfg(target_family = "wasm")]
@@ -11,6 +11,13 @@ pub struct CardanoTransactionsSetProofMessagePart {
     /// Hashes of the certified transactions
     pub transactions_hashes: Vec<TransactionHash>,
 
+    /// Hashes of the blocks of the certified transactions, in the same order as the
+    /// transactions hashes.
+    ///
+    /// Absent from the proofs which leaves are computed from the transactions hashes only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_hashes: Vec<BlockHash>,
+
     /// Proof of the transactions
     pub proof: HexEncodedKey,
 }
@@ -22,7 +22,7 @@ pub use cardano_transaction_snapshot_list::{
     CardanoTransactionSnapshotListItemMessage, CardanoTransactionSnapshotListMessage,
 };
 pub use cardano_transactions_proof::{
-    CardanoTransactionsProofsMessage, VerifiedCardanoTransactions,
+    CardanoTransactionsProofsMessage, VerificationAuditLog, VerifiedCardanoTransactions,
     VerifyCardanoTransactionsProofsError,
 };
 pub use certificate::CertificateMessage;
@@ -104,7 +104,7 @@ impl SignableBuilder<BlockNumber> for CardanoTransactionsSignableBuilder {
 mod tests {
 
     use crate::{
-        entities::CardanoTransaction,
+        entities::{CardanoTransaction, CardanoTransactionLeafFormat},
         test_utils::{CardanoTransactionsBuilder, TestLogger},
     };
 
@@ -112,11 +112,21 @@ mod tests {
 
     fn compute_mk_map_from_transactions(
         transactions: Vec<CardanoTransaction>,
+    ) -> MKMap<BlockRange, MKMapNode<BlockRange>> {
+        compute_mk_map_from_transactions_leaves(
+            transactions,
+            CardanoTransactionLeafFormat::TransactionHash,
+        )
+    }
+
+    fn compute_mk_map_from_transactions_leaves(
+        transactions: Vec<CardanoTransaction>,
+        leaf_format: CardanoTransactionLeafFormat,
     ) -> MKMap<BlockRange, MKMapNode<BlockRange>> {
         MKMap::new_from_iter(transactions.iter().map(|tx| {
             (
                 BlockRange::from_block_number(tx.block_number),
-                MKMapNode::TreeNode(tx.transaction_hash.clone().into()),
+                MKMapNode::TreeNode(tx.leaf(leaf_format)),
             )
         }))
         .unwrap()
@@ -163,6 +173,57 @@ mod tests {
         assert_eq!(signable_expected, signable);
     }
 
+    #[tokio::test]
+    async fn test_compute_signable_depends_on_the_block_hash_of_the_transactions_with_their_leaf_format(
+    ) {
+        async fn compute_merkle_root(transactions: Vec<CardanoTransaction>) -> Option<String> {
+            let mut transaction_importer = MockTransactionsImporter::new();
+            transaction_importer
+                .expect_import()
+                .return_once(move |_| Ok(()));
+            let mut block_range_root_retriever = MockBlockRangeRootRetriever::new();
+            block_range_root_retriever
+                .expect_compute_merkle_map_from_block_range_roots()
+                .return_once(move |_| {
+                    Ok(compute_mk_map_from_transactions_leaves(
+                        transactions,
+                        CardanoTransactionLeafFormat::TransactionAndBlockHash,
+                    ))
+                });
+            let cardano_transactions_signable_builder = CardanoTransactionsSignableBuilder::new(
+                Arc::new(transaction_importer),
+                Arc::new(block_range_root_retriever),
+                TestLogger::stdout(),
+            );
+
+            cardano_transactions_signable_builder
+                .compute_protocol_message(50)
+                .await
+                .unwrap()
+                .get_message_part(&ProtocolMessagePartKey::CardanoTransactionsMerkleRoot)
+                .cloned()
+        }
+
+        let merkle_root = compute_merkle_root(vec![CardanoTransaction::new(
+            "tx-hash-123",
+            10,
+            40,
+            "block-hash-1",
+            1,
+        )])
+        .await;
+        let reorganised_merkle_root = compute_merkle_root(vec![CardanoTransaction::new(
+            "tx-hash-123",
+            10,
+            40,
+            "block-hash-2",
+            1,
+        )])
+        .await;
+
+        assert_ne!(merkle_root, reorganised_merkle_root);
+    }
+
     #[tokio::test]
     async fn test_compute_signable_with_no_block_range_root_return_error() {
         let block_number = 50;
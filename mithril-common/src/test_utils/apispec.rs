@@ -67,25 +67,25 @@ impl<'a> APISpec<'a> {
     }
 
     /// Sets the path to specify/check.
-    pub fn path(&'a mut self, path: &'a str) -> &mut APISpec {
+    pub fn path(&'a mut self, path: &'a str) -> &'a mut APISpec<'a> {
         self.path = Some(path);
         self
     }
 
     /// Sets the method to specify/check.
-    pub fn method(&'a mut self, method: &'a str) -> &mut APISpec {
+    pub fn method(&'a mut self, method: &'a str) -> &'a mut APISpec<'a> {
         self.method = Some(method);
         self
     }
 
     /// Sets the content type to specify/check, note that it defaults to "application/json".
-    pub fn content_type(&'a mut self, content_type: &'a str) -> &mut APISpec {
+    pub fn content_type(&'a mut self, content_type: &'a str) -> &'a mut APISpec<'a> {
         self.content_type = Some(content_type);
         self
     }
 
     /// Validates if a request is valid
-    fn validate_request(&'a self, request_body: &impl Serialize) -> Result<&APISpec, String> {
+    fn validate_request(&'a self, request_body: &impl Serialize) -> Result<&'a APISpec<'a>, String> {
         let path = self.path.unwrap();
         let method = self.method.unwrap().to_lowercase();
         let content_type = self.content_type.unwrap();
@@ -104,25 +104,28 @@ impl<'a> APISpec<'a> {
         &'a self,
         path: &str,
         operation_object: &Value,
-    ) -> Result<&APISpec, String> {
+    ) -> Result<&'a APISpec<'a>, String> {
         let fake_base_url = "http://0.0.0.1";
         let url = Url::parse(&format!("{}{}", fake_base_url, path)).unwrap();
 
-        check_query_parameter_limitations(&url, operation_object);
-
-        let mut query_pairs = url.query_pairs();
-        if let Some(parameter) = query_pairs.next() {
-            let spec_parameter = &operation_object["parameters"][0];
-            let spec_parameter_name = &spec_parameter["name"].as_str().unwrap();
-            let spec_parameter_in = &spec_parameter["in"].as_str().unwrap();
-            if spec_parameter_in.eq(&"query") && spec_parameter_name.eq(&parameter.0) {
-                Ok(self)
-            } else {
-                Err(format!("Unexpected query parameter '{}'", parameter.0))
+        let spec_query_parameters = operation_object["parameters"]
+            .as_array()
+            .map(|parameters| {
+                parameters
+                    .iter()
+                    .filter(|p| p["in"].eq("query"))
+                    .filter_map(|p| p["name"].as_str())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for (parameter_name, _) in url.query_pairs() {
+            if !spec_query_parameters.contains(&parameter_name.as_ref()) {
+                return Err(format!("Unexpected query parameter '{parameter_name}'"));
             }
-        } else {
-            Ok(self)
         }
+
+        Ok(self)
     }
 
     /// Validates if the status is the expected one
@@ -130,7 +133,7 @@ impl<'a> APISpec<'a> {
         &'a self,
         response: &Response<Bytes>,
         expected_status_code: &StatusCode,
-    ) -> Result<&APISpec, String> {
+    ) -> Result<&'a APISpec<'a>, String> {
         if expected_status_code.as_u16() != response.status().as_u16() {
             return Err(format!(
                 "expected status code {} but was {}",
@@ -143,7 +146,7 @@ impl<'a> APISpec<'a> {
     }
 
     /// Validates if a response is valid
-    fn validate_response(&'a self, response: &Response<Bytes>) -> Result<&APISpec, String> {
+    fn validate_response(&'a self, response: &Response<Bytes>) -> Result<&'a APISpec<'a>, String> {
         let body = response.body();
         let status = response.status();
 
@@ -207,7 +210,7 @@ impl<'a> APISpec<'a> {
     }
 
     /// Validates conformity of a value against a schema
-    fn validate_conformity(&'a self, value: &Value, schema: &Value) -> Result<&APISpec, String> {
+    fn validate_conformity(&'a self, value: &Value, schema: &Value) -> Result<&'a APISpec<'a>, String> {
         match schema {
             Null => match value {
                 Null => Ok(self),
@@ -305,25 +308,6 @@ impl<'a> APISpec<'a> {
     }
 }
 
-// TODO: For now, it verifies only one parameter,
-// should verify with multiple query parameters using an openapi.yaml file for test.
-fn check_query_parameter_limitations(url: &Url, operation_object: &Value) {
-    if url.query_pairs().count() >= 2 {
-        panic!("This method does not work with multiple parameters");
-    }
-
-    if let Some(parameters) = operation_object["parameters"].as_array() {
-        let len = parameters
-            .iter()
-            .filter(|p| p["in"].eq("query"))
-            .collect::<Vec<_>>()
-            .len();
-        if len >= 2 {
-            panic!("This method does not work with multiple parameters");
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -702,6 +686,18 @@ components:
         );
     }
 
+    #[test]
+    fn test_validate_query_parameters_with_multiple_parameters() {
+        let api_spec = APISpec::from_file(&APISpec::get_default_spec_file());
+        api_spec
+            .validate_query_parameters(
+                "/artifact/snapshots/manifest?from=5&to=20",
+                &api_spec.openapi["paths"]["/artifact/snapshots/manifest"]["get"],
+            )
+            .map(|_apispec| ())
+            .unwrap()
+    }
+
     #[test]
     fn test_verify_conformity_with_expected_status() {
         APISpec::verify_conformity(
@@ -53,6 +53,7 @@ mod utils {
     use slog_async::Async;
     use slog_term::{CompactFormat, PlainDecorator};
 
+    /// Builder of the loggers used by the tests
     pub struct TestLogger;
 
     #[cfg(test)]
@@ -64,10 +65,12 @@ mod utils {
             Logger::root(Arc::new(drain), slog::o!())
         }
 
+        /// Logger that writes to the test standard output
         pub fn stdout() -> Logger {
             Self::from_writer(slog_term::TestStdoutWriter)
         }
 
+        /// Logger that writes to the given file
         pub fn file(filepath: &std::path::Path) -> Logger {
             Self::from_writer(File::create(filepath).unwrap())
         }
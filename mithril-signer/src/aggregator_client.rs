@@ -68,6 +68,7 @@ pub enum AggregatorClientError {
 #[cfg(test)]
 /// convenient methods to error enum
 impl AggregatorClientError {
+    /// Check if the error is an API version mismatch
     pub fn is_api_version_mismatch(&self) -> bool {
         matches!(self, Self::ApiVersionMismatch(_))
     }
@@ -99,6 +100,14 @@ pub trait AggregatorClient: Sync + Send {
         signed_entity_type: &SignedEntityType,
         signatures: &SingleSignatures,
     ) -> Result<(), AggregatorClientError>;
+
+    /// Registers several single signatures of the same signed entity type with the aggregator
+    /// in one request, either all of them are registered or none.
+    async fn register_signatures_batch(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: &[SingleSignatures],
+    ) -> Result<(), AggregatorClientError>;
 }
 
 /// AggregatorHTTPClient is a http client for an aggregator
@@ -254,6 +263,12 @@ impl AggregatorClient for AggregatorHTTPClient {
         match response {
             Ok(response) => match response.status() {
                 StatusCode::CREATED => Ok(()),
+                // The aggregator already processed a registration with the same nonce, ie: this
+                // is the retry of a registration which response was lost
+                StatusCode::CONFLICT => {
+                    debug!("Signer registration already processed by the aggregator");
+                    Ok(())
+                }
                 StatusCode::PRECONDITION_FAILED => Err(self.handle_api_error(&response)),
                 StatusCode::BAD_REQUEST => Err(AggregatorClientError::RemoteServerLogical(
                     anyhow!("bad request: {}", response.text().await.unwrap_or_default()),
@@ -303,6 +318,48 @@ impl AggregatorClient for AggregatorHTTPClient {
             Err(err) => Err(AggregatorClientError::RemoteServerUnreachable(anyhow!(err))),
         }
     }
+
+    async fn register_signatures_batch(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: &[SingleSignatures],
+    ) -> Result<(), AggregatorClientError> {
+        debug!("Register a batch of {} signatures", signatures.len());
+        let url = format!("{}/register-signatures-batch", self.aggregator_endpoint);
+        let register_single_signature_messages = signatures
+            .iter()
+            .map(|signature| {
+                ToRegisterSignatureMessageAdapter::try_adapt((
+                    signed_entity_type.to_owned(),
+                    signature.to_owned(),
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AggregatorClientError::Adapter(anyhow!(e)))?;
+        let response = self
+            .prepare_request_builder(self.prepare_http_client()?.post(url.clone()))
+            .json(&register_single_signature_messages)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) => match response.status() {
+                StatusCode::CREATED => Ok(()),
+                StatusCode::PRECONDITION_FAILED => Err(self.handle_api_error(&response)),
+                StatusCode::BAD_REQUEST => Err(AggregatorClientError::RemoteServerLogical(
+                    anyhow!("bad request: {}", response.text().await.unwrap_or_default()),
+                )),
+                StatusCode::CONFLICT => Err(AggregatorClientError::RemoteServerLogical(anyhow!(
+                    "already registered single signatures"
+                ))),
+                _ => Err(AggregatorClientError::RemoteServerTechnical(anyhow!(
+                    "{}",
+                    response.text().await.unwrap_or_default()
+                ))),
+            },
+            Err(err) => Err(AggregatorClientError::RemoteServerUnreachable(anyhow!(err))),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -403,6 +460,15 @@ pub(crate) mod dumb {
         ) -> Result<(), AggregatorClientError> {
             Ok(())
         }
+
+        /// Registers a batch of single signatures with the aggregator
+        async fn register_signatures_batch(
+            &self,
+            _signed_entity_type: &SignedEntityType,
+            _signatures: &[SingleSignatures],
+        ) -> Result<(), AggregatorClientError> {
+            Ok(())
+        }
     }
 }
 
@@ -657,6 +723,30 @@ mod tests {
         register_signer.expect("unexpected error");
     }
 
+    #[tokio::test]
+    async fn test_register_signer_already_processed_409_is_ok() {
+        let epoch = Epoch(1);
+        let single_signers = fake_data::signers(1);
+        let single_signer = single_signers[0]
+            .clone()
+            .with_registration_nonce("nonce-1".to_string());
+        let (server, config, api_version_provider) = setup_test();
+        let _snapshots_mock = server.mock(|when, then| {
+            when.method(POST).path("/register-signer");
+            then.status(409);
+        });
+        let certificate_handler = AggregatorHTTPClient::new(
+            config.aggregator_endpoint,
+            config.relay_endpoint,
+            Arc::new(api_version_provider),
+            None,
+        );
+        let register_signer = certificate_handler
+            .register_signer(epoch, &single_signer)
+            .await;
+        register_signer.expect("a 409 should be a successful registration");
+    }
+
     #[tokio::test]
     async fn test_register_signer_ko_412() {
         let epoch = Epoch(1);
@@ -895,6 +985,71 @@ mod tests {
         };
     }
 
+    #[tokio::test]
+    async fn test_register_signatures_batch_ok_201() {
+        let single_signatures = vec![
+            fake_data::single_signatures((1..5).collect()),
+            fake_data::single_signatures((5..10).collect()),
+        ];
+        let expected_messages = single_signatures
+            .iter()
+            .map(|signature| {
+                ToRegisterSignatureMessageAdapter::try_adapt((
+                    SignedEntityType::dummy(),
+                    signature.to_owned(),
+                ))
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let (server, config, api_version_provider) = setup_test();
+        let _snapshots_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/register-signatures-batch")
+                .json_body(json!(expected_messages));
+            then.status(201);
+        });
+        let certificate_handler = AggregatorHTTPClient::new(
+            config.aggregator_endpoint,
+            config.relay_endpoint,
+            Arc::new(api_version_provider),
+            None,
+        );
+        certificate_handler
+            .register_signatures_batch(&SignedEntityType::dummy(), &single_signatures)
+            .await
+            .expect("unexpected error");
+    }
+
+    #[tokio::test]
+    async fn test_register_signatures_batch_ko_400() {
+        let single_signatures = vec![fake_data::single_signatures((1..5).collect())];
+        let (server, config, api_version_provider) = setup_test();
+        let _snapshots_mock = server.mock(|when, then| {
+            when.method(POST).path("/register-signatures-batch");
+            then.status(400).body(
+                serde_json::to_vec(&ClientError::new(
+                    "error".to_string(),
+                    "an error".to_string(),
+                ))
+                .unwrap(),
+            );
+        });
+        let certificate_handler = AggregatorHTTPClient::new(
+            config.aggregator_endpoint,
+            config.relay_endpoint,
+            Arc::new(api_version_provider),
+            None,
+        );
+        match certificate_handler
+            .register_signatures_batch(&SignedEntityType::dummy(), &single_signatures)
+            .await
+            .unwrap_err()
+        {
+            AggregatorClientError::RemoteServerLogical(_) => (),
+            e => panic!("Expected Aggregator::RemoteServerLogical error, got '{e:?}'."),
+        };
+    }
+
     #[tokio::test]
     async fn test_register_signatures_timeout() {
         let single_signatures = fake_data::single_signatures((1..5).collect());
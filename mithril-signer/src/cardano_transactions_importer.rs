@@ -8,7 +8,10 @@ use slog::{debug, Logger};
 
 use mithril_common::cardano_block_scanner::{BlockScanner, ChainScannedBlocks};
 use mithril_common::crypto_helper::{MKTree, MKTreeNode};
-use mithril_common::entities::{BlockNumber, BlockRange, CardanoTransaction, ChainPoint};
+use mithril_common::entities::{
+    BlockNumber, BlockRange, CardanoTransaction, CardanoTransactionLeafFormat, ChainPoint,
+    SlotNumber,
+};
 use mithril_common::signable_builder::TransactionsImporter;
 use mithril_common::StdResult;
 
@@ -33,6 +36,13 @@ pub trait TransactionStore: Send + Sync {
         range: Range<BlockNumber>,
     ) -> StdResult<Vec<CardanoTransaction>>;
 
+    /// Get transactions in an inclusive interval of slots
+    async fn get_transactions_in_slot_range(
+        &self,
+        from_slot: SlotNumber,
+        to_slot: SlotNumber,
+    ) -> StdResult<Vec<CardanoTransaction>>;
+
     /// Store list of block ranges with their corresponding merkle root
     async fn store_block_range_roots(
         &self,
@@ -55,6 +65,7 @@ pub struct CardanoTransactionsImporter {
     transaction_store: Arc<dyn TransactionStore>,
     logger: Logger,
     dirpath: PathBuf,
+    leaf_format: CardanoTransactionLeafFormat,
 }
 
 impl CardanoTransactionsImporter {
@@ -74,9 +85,17 @@ impl CardanoTransactionsImporter {
             transaction_store,
             logger,
             dirpath: dirpath.to_owned(),
+            leaf_format: CardanoTransactionLeafFormat::default(),
         }
     }
 
+    /// Set the format of the Merkle tree leaves of the new block ranges, without it the leaves
+    /// are computed from the transactions hashes only.
+    pub fn with_leaf_format(mut self, leaf_format: CardanoTransactionLeafFormat) -> Self {
+        self.leaf_format = leaf_format;
+        self
+    }
+
     async fn import_transactions(&self, up_to_beacon: BlockNumber) -> StdResult<()> {
         let from = self.transaction_store.get_highest_beacon().await?;
         self.parse_and_store_transactions_not_imported_yet(from, up_to_beacon)
@@ -145,6 +164,7 @@ impl CardanoTransactionsImporter {
             "start_block" => block_ranges.start(), "end_block" => block_ranges.end(),
         );
 
+        let leaf_format = self.leaf_format;
         let mut block_ranges_with_merkle_root: Vec<(BlockRange, MKTreeNode)> = vec![];
         for block_range in block_ranges {
             let transactions = self
@@ -156,7 +176,11 @@ impl CardanoTransactionsImporter {
                 continue;
             }
 
-            let merkle_root = MKTree::new(&transactions)?.compute_root()?;
+            let leaves: Vec<MKTreeNode> = transactions
+                .iter()
+                .map(|transaction| transaction.leaf(leaf_format))
+                .collect();
+            let merkle_root = MKTree::new(&leaves)?.compute_root()?;
             block_ranges_with_merkle_root.push((block_range, merkle_root));
 
             if block_ranges_with_merkle_root.len() >= 100 {
@@ -213,6 +237,7 @@ mod tests {
     }
 
     impl CardanoTransactionsImporter {
+        /// Create an importer with an empty database path and a test logger
         pub fn new_for_test(
             scanner: Arc<dyn BlockScanner>,
             transaction_store: Arc<dyn TransactionStore>,
@@ -368,6 +393,47 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn block_range_roots_leaves_follow_the_format_of_the_current_era() {
+        let connection = cardano_tx_db_connection().unwrap();
+        let repository = Arc::new(CardanoTransactionRepository::new(Arc::new(
+            SqliteConnectionPool::build_from_connection(connection),
+        )));
+        let blocks = build_blocks(0, BlockRange::LENGTH);
+        let transactions = into_transactions(&blocks);
+        repository
+            .store_transactions(transactions.clone())
+            .await
+            .unwrap();
+
+        let importer = CardanoTransactionsImporter::new_for_test(
+            Arc::new(MockBlockScannerImpl::new()),
+            repository.clone(),
+        )
+        .with_leaf_format(CardanoTransactionLeafFormat::TransactionAndBlockHash);
+
+        importer
+            .import_block_ranges()
+            .await
+            .expect("Transactions Importer should succeed");
+
+        let leaves: Vec<MKTreeNode> = transactions
+            .iter()
+            .map(|tx| tx.leaf(CardanoTransactionLeafFormat::TransactionAndBlockHash))
+            .collect();
+        let block_range_roots = repository.get_all_block_range_root().unwrap();
+        assert_eq!(
+            vec![(
+                BlockRange::from_block_number(0),
+                MKTree::new(&leaves).unwrap().compute_root().unwrap()
+            )],
+            block_range_roots
+                .into_iter()
+                .map(|r| (r.range, r.merkle_root))
+                .collect::<Vec<_>>()
+        );
+    }
+
     #[tokio::test]
     async fn if_all_block_ranges_computed_nothing_computed_and_stored() {
         let connection = cardano_tx_db_connection().unwrap();
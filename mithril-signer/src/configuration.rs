@@ -7,7 +7,7 @@ use std::{path::PathBuf, sync::Arc};
 use mithril_common::{
     chain_observer::ChainObserver,
     crypto_helper::tests_setup,
-    entities::{BlockNumber, PartyId},
+    entities::{BlockNumber, CardanoTransactionLeafFormat, PartyId},
     era::{
         adapters::{EraReaderAdapterBuilder, EraReaderAdapterType},
         EraReaderAdapter,
@@ -15,6 +15,8 @@ use mithril_common::{
     CardanoNetwork, StdResult,
 };
 
+use crate::{PoolId, PoolIdMapping};
+
 /// Client configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Documenter)]
 pub struct Configuration {
@@ -70,6 +72,11 @@ pub struct Configuration {
     /// Store retention limit. If set to None, no limit will be set.
     pub store_retention_limit: Option<usize>,
 
+    /// Maximum number of epochs between a pending certificate and the latest stake distribution
+    /// known by the signer for the signer to sign it. If set to None, the age of the stake
+    /// distribution is not checked.
+    pub max_stake_age: Option<u64>,
+
     /// File path to the KES secret key of the pool
     pub kes_secret_key_path: Option<PathBuf>,
 
@@ -113,6 +120,34 @@ pub struct Configuration {
     /// storage footprint of the signer by reducing the number of transactions stored on disk
     /// at any given time.
     pub transactions_import_block_chunk_size: BlockNumber,
+
+    /// Format of the Merkle tree leaves of the Cardano transactions of the new block ranges, it
+    /// must be the same for the signer and its aggregator.
+    #[example = "`transaction_hash` or `transaction_and_block_hash`"]
+    pub cardano_transactions_leaf_format: CardanoTransactionLeafFormat,
+
+    /// Stake pools signed by this signer, for operators running several Cardano nodes.
+    ///
+    /// If set, each pool is signed by its own signer with the `party_id`, the KES secret key and
+    /// the operational certificate of the pool, and stores its data in a sub directory of
+    /// [data_stores_directory][Self::data_stores_directory] named after its `pool_id`.
+    pub pools: Option<Vec<PoolConfiguration>>,
+}
+
+/// Configuration of a stake pool signed by a [MultiPoolSigner][crate::MultiPoolSigner]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolConfiguration {
+    /// Identifier of the stake pool
+    pub pool_id: PoolId,
+
+    /// Party Id of the stake pool
+    pub party_id: Option<PartyId>,
+
+    /// File path to the KES secret key of the pool
+    pub kes_secret_key_path: Option<PathBuf>,
+
+    /// File path to the operational certificate of the pool
+    pub operational_certificate_path: Option<PathBuf>,
 }
 
 impl Configuration {
@@ -135,6 +170,7 @@ impl Configuration {
             run_interval: 5000,
             data_stores_directory: PathBuf::new(),
             store_retention_limit: None,
+            max_stake_age: None,
             kes_secret_key_path: signer_temp_dir.as_ref().map(|dir| dir.join("kes.sk")),
             operational_certificate_path: signer_temp_dir
                 .as_ref()
@@ -149,9 +185,45 @@ impl Configuration {
             allow_unparsable_block: false,
             enable_transaction_pruning: false,
             transactions_import_block_chunk_size: 1000,
+            cardano_transactions_leaf_format: CardanoTransactionLeafFormat::TransactionHash,
+            pools: None,
         }
     }
 
+    /// Return the configuration of the signer of each stake pool of
+    /// [pools][Self::pools], empty if this signer signs a single pool.
+    pub fn get_pool_configurations(&self) -> Vec<(PoolId, Configuration)> {
+        self.pools
+            .iter()
+            .flatten()
+            .map(|pool| {
+                let configuration = Self {
+                    party_id: pool.party_id.clone(),
+                    kes_secret_key_path: pool.kes_secret_key_path.clone(),
+                    operational_certificate_path: pool.operational_certificate_path.clone(),
+                    data_stores_directory: self.data_stores_directory.join(&pool.pool_id),
+                    pools: None,
+                    ..self.clone()
+                };
+
+                (pool.pool_id.clone(), configuration)
+            })
+            .collect()
+    }
+
+    /// Return the party id of each stake pool of [pools][Self::pools] that has one.
+    pub fn get_pool_id_mapping(&self) -> PoolIdMapping {
+        self.pools
+            .iter()
+            .flatten()
+            .filter_map(|pool| {
+                pool.party_id
+                    .clone()
+                    .map(|party_id| (pool.pool_id.clone(), party_id))
+            })
+            .collect()
+    }
+
     /// Return the CardanoNetwork value from the configuration.
     pub fn get_network(&self) -> StdResult<CardanoNetwork> {
         CardanoNetwork::from_code(self.network.clone(), self.network_magic).with_context(|| {
@@ -221,6 +293,9 @@ pub struct DefaultConfiguration {
 
     /// Chunk size for importing transactions
     pub transactions_import_block_chunk_size: BlockNumber,
+
+    /// Cardano transactions leaf format
+    pub cardano_transactions_leaf_format: String,
 }
 
 impl DefaultConfiguration {
@@ -239,6 +314,7 @@ impl Default for DefaultConfiguration {
             preload_security_parameter: 3000,
             enable_transaction_pruning: true,
             transactions_import_block_chunk_size: 1500,
+            cardano_transactions_leaf_format: "transaction_hash".to_string(),
         }
     }
 }
@@ -290,6 +366,71 @@ impl Source for DefaultConfiguration {
             into_value(myself.transactions_import_block_chunk_size),
         );
 
+        result.insert(
+            "cardano_transactions_leaf_format".to_string(),
+            into_value(myself.cardano_transactions_leaf_format),
+        );
+
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(pool_id: &str, party_id: Option<&str>) -> PoolConfiguration {
+        PoolConfiguration {
+            pool_id: pool_id.to_string(),
+            party_id: party_id.map(|p| p.to_string()),
+            kes_secret_key_path: Some(PathBuf::from(format!("{pool_id}/kes.sk"))),
+            operational_certificate_path: Some(PathBuf::from(format!("{pool_id}/opcert.cert"))),
+        }
+    }
+
+    #[test]
+    fn a_single_pool_signer_has_no_pool_configurations() {
+        let config = Configuration::new_sample("party-1");
+
+        assert!(config.get_pool_configurations().is_empty());
+        assert!(config.get_pool_id_mapping().is_empty());
+    }
+
+    #[test]
+    fn each_pool_configuration_uses_the_keys_of_the_pool_and_its_own_stores() {
+        let config = Configuration {
+            data_stores_directory: PathBuf::from("stores"),
+            pools: Some(vec![pool("pool-a", Some("party-a")), pool("pool-b", None)]),
+            ..Configuration::new_sample("party-1")
+        };
+
+        let pool_configurations = config.get_pool_configurations();
+
+        assert_eq!(
+            vec!["pool-a".to_string(), "pool-b".to_string()],
+            pool_configurations
+                .iter()
+                .map(|(pool_id, _)| pool_id.clone())
+                .collect::<Vec<_>>()
+        );
+        let (_, pool_a_config) = &pool_configurations[0];
+        assert_eq!(Some("party-a".to_string()), pool_a_config.party_id);
+        assert_eq!(
+            Some(PathBuf::from("pool-a/kes.sk")),
+            pool_a_config.kes_secret_key_path
+        );
+        assert_eq!(
+            Some(PathBuf::from("pool-a/opcert.cert")),
+            pool_a_config.operational_certificate_path
+        );
+        assert_eq!(
+            PathBuf::from("stores").join("pool-a"),
+            pool_a_config.data_stores_directory
+        );
+        assert_eq!(None, pool_a_config.pools);
+        assert_eq!(
+            PoolIdMapping::from([("pool-a".to_string(), "party-a".to_string())]),
+            config.get_pool_id_mapping()
+        );
+    }
+}
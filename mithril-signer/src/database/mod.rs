@@ -4,6 +4,7 @@
 pub mod migration;
 pub mod repository;
 
+/// Database helpers for the tests
 #[cfg(test)]
 pub mod test_utils {
     use sqlite::ConnectionThreadSafe;
@@ -12,6 +13,7 @@ pub mod test_utils {
     use mithril_persistence::database::cardano_transaction_migration;
     use mithril_persistence::sqlite::{ConnectionBuilder, ConnectionOptions};
 
+    /// Open an in memory connection to a cardano transactions database
     pub fn cardano_tx_db_connection() -> StdResult<ConnectionThreadSafe> {
         let connection = ConnectionBuilder::open_memory()
             .with_options(&[ConnectionOptions::ForceDisableForeignKeys])
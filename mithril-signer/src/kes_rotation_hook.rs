@@ -0,0 +1,13 @@
+use mithril_common::crypto_helper::{KESPeriod, ProtocolInitializer};
+use mithril_common::StdResult;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Hook called when the KES period of the signer advances between two registrations.
+#[cfg_attr(test, automock)]
+pub trait KesRotationHook: Sync + Send {
+    /// Supply the [ProtocolInitializer], which verification key is signed with the KES key of
+    /// the `new_period`, to register with.
+    fn on_kes_rotation(&self, new_period: KESPeriod) -> StdResult<ProtocolInitializer>;
+}
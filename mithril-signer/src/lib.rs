@@ -10,8 +10,10 @@ mod aggregator_client;
 mod cardano_transactions_importer;
 mod configuration;
 pub mod database;
+mod kes_rotation_hook;
 mod message_adapters;
 pub mod metrics;
+mod nonce_generator;
 mod protocol_initializer_store;
 mod runtime;
 mod single_signer;
@@ -22,12 +24,16 @@ mod transactions_importer_with_pruner;
 pub use aggregator_client::dumb::DumbAggregatorClient;
 pub use aggregator_client::*;
 pub use cardano_transactions_importer::*;
-pub use configuration::{Configuration, DefaultConfiguration};
+pub use configuration::{Configuration, DefaultConfiguration, PoolConfiguration};
+pub use kes_rotation_hook::*;
 pub use message_adapters::{
     FromEpochSettingsAdapter, FromPendingCertificateMessageAdapter, ToRegisterSignerMessageAdapter,
 };
 pub use metrics::*;
-pub use protocol_initializer_store::{ProtocolInitializerStore, ProtocolInitializerStorer};
+pub use nonce_generator::*;
+pub use protocol_initializer_store::{
+    EncryptedKeyBundle, PendingRegistration, ProtocolInitializerStore, ProtocolInitializerStorer,
+};
 pub use runtime::*;
 pub use single_signer::*;
 pub use transactions_importer_by_chunk::*;
@@ -48,11 +54,13 @@ use tikv_jemallocator::Jemalloc;
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
+/// Tools for the tests
 #[cfg(test)]
 pub mod test_tools {
     use slog::Drain;
     use std::sync::Arc;
 
+    /// Logger that writes to the test standard output
     pub fn logger_for_tests() -> slog::Logger {
         let decorator = slog_term::PlainDecorator::new(slog_term::TestStdoutWriter);
         let drain = slog_term::CompactFormat::new(decorator).build().fuse();
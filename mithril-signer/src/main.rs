@@ -13,11 +13,12 @@ use tokio::{
     task::JoinSet,
 };
 
-use mithril_common::StdResult;
+use mithril_common::{cardano_transactions_preloader::CardanoTransactionsPreloader, StdResult};
 use mithril_doc::{Documenter, DocumenterDefault, GenerateDocCommands, StructDoc};
 use mithril_signer::{
-    Configuration, DefaultConfiguration, MetricsServer, ProductionServiceBuilder, ServiceBuilder,
-    SignerRunner, SignerState, StateMachine,
+    Configuration, DefaultConfiguration, InitializerRetryPolicy, MetricsServer, MetricsService,
+    MultiPoolSigner, ProductionServiceBuilder, RandomNonceGenerator, ServiceBuilder, SignerRunner,
+    SignerState, StateMachine,
 };
 
 /// CLI args
@@ -105,6 +106,51 @@ fn build_logger(min_level: Level) -> Logger {
     Logger::root(Arc::new(drain), o!())
 }
 
+/// Build the state machine of a signer with its own services.
+///
+/// The state machine records its metrics with the given metrics service, or with the one of its
+/// services if none is given.
+async fn build_state_machine(
+    config: &Configuration,
+    metrics_service: Option<Arc<MetricsService>>,
+    logger: Logger,
+) -> StdResult<(
+    StateMachine,
+    Arc<MetricsService>,
+    Arc<CardanoTransactionsPreloader>,
+)> {
+    let services = ProductionServiceBuilder::new(config)
+        .build()
+        .await
+        .with_context(|| "services initialization error")?;
+    let metrics_service = metrics_service.unwrap_or_else(|| services.metrics_service.clone());
+    let cardano_transaction_preloader = services.cardano_transactions_preloader.clone();
+
+    let mut runner = SignerRunner::new(config.clone(), services)
+        .with_initializer_retry_policy(InitializerRetryPolicy::new(
+            3,
+            Duration::from_millis(config.run_interval),
+        ))
+        .with_nonce_generator(Arc::new(RandomNonceGenerator::new()));
+    if let Some(max_stake_age) = config.max_stake_age {
+        runner = runner.with_max_stake_age(max_stake_age);
+    }
+
+    let state_machine = StateMachine::new(
+        SignerState::Init,
+        Box::new(runner),
+        Duration::from_millis(config.run_interval),
+        metrics_service.clone(),
+        logger,
+    );
+
+    Ok((
+        state_machine,
+        metrics_service,
+        cardano_transaction_preloader,
+    ))
+}
+
 #[derive(Subcommand, Debug, Clone)]
 enum SignerCommands {
     #[clap(alias("doc"), hide(true))]
@@ -158,33 +204,65 @@ async fn main() -> StdResult<()> {
         .try_deserialize()
         .with_context(|| "configuration deserialize error")?;
 
-    let services = ProductionServiceBuilder::new(&config)
-        .build()
-        .await
-        .with_context(|| "services initialization error")?;
-
-    let metrics_service = services.metrics_service.clone();
-    let cardano_transaction_preloader = services.cardano_transactions_preloader.clone();
+    let mut join_set = JoinSet::new();
+    let mut preloaders = vec![];
+    let pool_configurations = config.get_pool_configurations();
+    let metrics_service = if pool_configurations.is_empty() {
+        let (state_machine, metrics_service, cardano_transaction_preloader) =
+            build_state_machine(&config, None, slog_scope::logger()).await?;
+        preloaders.push(cardano_transaction_preloader);
+
+        debug!("Started"; "run_mode" => &args.run_mode, "config" => format!("{config:?}"));
+        join_set.spawn(async move {
+            state_machine
+                .run()
+                .await
+                .map_err(|e| anyhow!(e))
+                .map(|_| None)
+        });
 
-    debug!("Started"; "run_mode" => &args.run_mode, "config" => format!("{config:?}"));
+        metrics_service
+    } else {
+        // The state machines of all the pools record their metrics in the same service
+        let mut metrics_service = None;
+        let mut pool_signers = vec![];
+        for (pool_id, pool_config) in pool_configurations {
+            let (state_machine, pool_metrics_service, cardano_transaction_preloader) =
+                build_state_machine(
+                    &pool_config,
+                    metrics_service.clone(),
+                    slog_scope::logger().new(o!("pool_id" => pool_id.clone())),
+                )
+                .await
+                .with_context(|| {
+                    format!("initialization error of the signer of pool '{pool_id}'")
+                })?;
+            metrics_service = Some(pool_metrics_service);
+            preloaders.push(cardano_transaction_preloader);
+            pool_signers.push((pool_id, state_machine));
+        }
+        let multi_pool_signer = MultiPoolSigner::new(
+            pool_signers,
+            config.get_pool_id_mapping(),
+            slog_scope::logger(),
+        );
 
-    let state_machine = StateMachine::new(
-        SignerState::Init,
-        Box::new(SignerRunner::new(config.clone(), services)),
-        Duration::from_millis(config.run_interval),
-        metrics_service.clone(),
-    );
+        debug!("Started"; "run_mode" => &args.run_mode, "config" => format!("{config:?}"));
+        join_set.spawn(async move {
+            multi_pool_signer
+                .run()
+                .await
+                .map_err(|e| anyhow!(e))
+                .map(|_| None)
+        });
 
-    let mut join_set = JoinSet::new();
-    join_set.spawn(async move {
-        state_machine
-            .run()
-            .await
-            .map_err(|e| anyhow!(e))
-            .map(|_| None)
-    });
+        metrics_service.expect("there is at least one pool signer")
+    };
 
-    let preload_task = tokio::spawn(async move { cardano_transaction_preloader.preload().await });
+    let preload_tasks: Vec<_> = preloaders
+        .into_iter()
+        .map(|preloader| tokio::spawn(async move { preloader.preload().await }))
+        .collect();
 
     let (metrics_server_shutdown_tx, metrics_server_shutdown_rx) = oneshot::channel();
     if config.enable_metrics_server {
@@ -239,8 +317,10 @@ async fn main() -> StdResult<()> {
         .send(())
         .map_err(|e| anyhow!("Metrics server shutdown signal could not be sent: {e:?}"))?;
 
-    if !preload_task.is_finished() {
-        preload_task.abort();
+    for preload_task in preload_tasks {
+        if !preload_task.is_finished() {
+            preload_task.abort();
+        }
     }
 
     join_set.shutdown().await;
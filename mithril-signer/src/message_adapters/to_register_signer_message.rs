@@ -38,6 +38,7 @@ impl TryToMessageAdapter<(Epoch, Signer), RegisterSignerMessage>
                 None => None,
             },
             kes_period: signer.kes_period,
+            registration_nonce: signer.registration_nonce,
         };
 
         Ok(message)
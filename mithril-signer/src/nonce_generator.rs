@@ -0,0 +1,48 @@
+use rand_core::{OsRng, RngCore};
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Generate the nonces identifying the registration requests of the signer, so the aggregator
+/// can detect the retries of a registration it already processed.
+#[cfg_attr(test, automock)]
+pub trait NonceGenerator: Sync + Send {
+    /// Produce a value unique to a registration request
+    fn next_nonce(&self) -> String;
+}
+
+/// [NonceGenerator] producing random nonces of 128 bits, hex encoded
+#[derive(Debug, Default)]
+pub struct RandomNonceGenerator {}
+
+impl RandomNonceGenerator {
+    /// RandomNonceGenerator factory
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl NonceGenerator for RandomNonceGenerator {
+    fn next_nonce(&self) -> String {
+        let mut nonce = [0u8; 16];
+        OsRng.fill_bytes(&mut nonce);
+
+        hex::encode(nonce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_nonces_are_unique() {
+        let generator = RandomNonceGenerator::new();
+
+        let first_nonce = generator.next_nonce();
+        let second_nonce = generator.next_nonce();
+
+        assert_eq!(32, first_nonce.len());
+        assert_ne!(first_nonce, second_nonce);
+    }
+}
@@ -1,11 +1,37 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
-use mithril_common::{crypto_helper::ProtocolInitializer, entities::Epoch, StdResult};
-use mithril_persistence::store::{adapter::StoreAdapter, StorePruner};
+use mithril_common::{
+    crypto_helper::{ProtocolInitializer, ProtocolInitializerVersion},
+    entities::Epoch,
+    StdResult,
+};
+use mithril_persistence::store::{
+    adapter::{MemoryAdapter, StoreAdapter},
+    StorePruner,
+};
 
 type Adapter = Box<dyn StoreAdapter<Key = Epoch, Record = ProtocolInitializer>>;
 
+type PendingRegistrationAdapter = Box<dyn StoreAdapter<Key = Epoch, Record = PendingRegistration>>;
+
+/// Registration of the signer sent to the aggregator but not acknowledged yet.
+///
+/// It is kept until the registration succeeds so a retry sends the same nonce and keys, the
+/// aggregator can then recognize a registration it already processed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRegistration {
+    /// Nonce identifying the registration
+    pub nonce: String,
+
+    /// Protocol initializer holding the registered keys
+    pub protocol_initializer: ProtocolInitializer,
+}
+
 #[async_trait]
 /// Store the ProtocolInitializer used for each Epoch. This is useful because
 /// protocol parameters and stake distribution change over time.
@@ -28,10 +54,133 @@ pub trait ProtocolInitializerStorer: Sync + Send {
         &self,
         last: usize,
     ) -> StdResult<Vec<(Epoch, ProtocolInitializer)>>;
+
+    /// Save the registration of the given Epoch sent to the aggregator but not acknowledged yet.
+    async fn save_pending_registration(
+        &self,
+        epoch: Epoch,
+        pending_registration: PendingRegistration,
+    ) -> StdResult<()>;
+
+    /// Fetch the registration of the given Epoch not acknowledged yet, if any.
+    async fn get_pending_registration(
+        &self,
+        epoch: Epoch,
+    ) -> StdResult<Option<PendingRegistration>>;
+
+    /// Remove the registration of the given Epoch once acknowledged by the aggregator.
+    async fn remove_pending_registration(&self, epoch: Epoch) -> StdResult<()>;
+
+    /// Return the version of the saved protocol initializers with their Epoch, from the latest
+    /// Epoch.
+    ///
+    /// The versions change when the protocol parameters change, the initializers of the
+    /// previous versions are kept until pruned by the retention limit of the store.
+    async fn get_all_protocol_initializer_versions(
+        &self,
+    ) -> StdResult<Vec<(Epoch, ProtocolInitializerVersion)>> {
+        Ok(self
+            .get_last_protocol_initializer(usize::MAX)
+            .await?
+            .into_iter()
+            .map(|(epoch, protocol_initializer)| (epoch, protocol_initializer.version()))
+            .collect())
+    }
+
+    /// Export the signing keys of the given Epoch, the protocol initializer is encrypted with
+    /// AES-256-GCM so the bundle can be kept in cold storage.
+    async fn export_epoch_signing_keys(
+        &self,
+        epoch: Epoch,
+        encryption_key: &[u8; 32],
+    ) -> StdResult<EncryptedKeyBundle> {
+        let protocol_initializer = self
+            .get_protocol_initializer(epoch)
+            .await?
+            .ok_or_else(|| anyhow!("No protocol initializer found for epoch '{epoch}'"))?;
+        let plaintext = serde_json::to_vec(&protocol_initializer)
+            .with_context(|| "Could not serialize the protocol initializer")?;
+
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(encryption_key));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: &EncryptedKeyBundle::associated_data(epoch),
+                },
+            )
+            .map_err(|e| anyhow!("Could not encrypt the signing keys of epoch '{epoch}': {e}"))?;
+
+        Ok(EncryptedKeyBundle {
+            epoch,
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    /// Import the signing keys of an [EncryptedKeyBundle], restoring the protocol initializer
+    /// of its Epoch.
+    async fn import_epoch_signing_keys(
+        &self,
+        bundle: EncryptedKeyBundle,
+        decryption_key: &[u8; 32],
+    ) -> StdResult<()> {
+        let epoch = bundle.epoch;
+        if bundle.nonce.len() != EncryptedKeyBundle::NONCE_LENGTH {
+            return Err(anyhow!(
+                "Invalid nonce length '{}' in the key bundle of epoch '{epoch}'",
+                bundle.nonce.len()
+            ));
+        }
+
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(decryption_key));
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&bundle.nonce),
+                Payload {
+                    msg: &bundle.ciphertext,
+                    aad: &EncryptedKeyBundle::associated_data(epoch),
+                },
+            )
+            .map_err(|e| anyhow!("Could not decrypt the signing keys of epoch '{epoch}': {e}"))?;
+        let protocol_initializer: ProtocolInitializer = serde_json::from_slice(&plaintext)
+            .with_context(|| "Could not deserialize the protocol initializer")?;
+
+        self.save_protocol_initializer(epoch, protocol_initializer)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Signing keys of an Epoch encrypted with AES-256-GCM, made by
+/// [ProtocolInitializerStorer::export_epoch_signing_keys].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedKeyBundle {
+    /// Epoch of the signing keys
+    pub epoch: Epoch,
+
+    /// Nonce used to encrypt the signing keys
+    pub nonce: Vec<u8>,
+
+    /// Encrypted protocol initializer
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedKeyBundle {
+    const NONCE_LENGTH: usize = 12;
+
+    /// The epoch is authenticated with the keys so a bundle can't be restored for another epoch
+    fn associated_data(epoch: Epoch) -> [u8; 8] {
+        epoch.0.to_be_bytes()
+    }
 }
 /// Implementation of the ProtocolInitializerStorer
 pub struct ProtocolInitializerStore {
     adapter: RwLock<Adapter>,
+    pending_registration_adapter: RwLock<PendingRegistrationAdapter>,
     retention_limit: Option<usize>,
 }
 
@@ -40,9 +189,20 @@ impl ProtocolInitializerStore {
     pub fn new(adapter: Adapter, retention_limit: Option<usize>) -> Self {
         Self {
             adapter: RwLock::new(adapter),
+            pending_registration_adapter: RwLock::new(Box::new(MemoryAdapter::new(None).unwrap())),
             retention_limit,
         }
     }
+
+    /// Persist the pending registrations with the given adapter instead of keeping them in
+    /// memory.
+    pub fn with_pending_registration_adapter(
+        mut self,
+        pending_registration_adapter: PendingRegistrationAdapter,
+    ) -> Self {
+        self.pending_registration_adapter = RwLock::new(pending_registration_adapter);
+        self
+    }
 }
 
 #[async_trait]
@@ -95,6 +255,44 @@ impl ProtocolInitializerStorer for ProtocolInitializerStore {
 
         Ok(records)
     }
+
+    async fn save_pending_registration(
+        &self,
+        epoch: Epoch,
+        pending_registration: PendingRegistration,
+    ) -> StdResult<()> {
+        self.pending_registration_adapter
+            .write()
+            .await
+            .store_record(&epoch, &pending_registration)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_pending_registration(
+        &self,
+        epoch: Epoch,
+    ) -> StdResult<Option<PendingRegistration>> {
+        let record = self
+            .pending_registration_adapter
+            .read()
+            .await
+            .get_record(&epoch)
+            .await?;
+
+        Ok(record)
+    }
+
+    async fn remove_pending_registration(&self, epoch: Epoch) -> StdResult<()> {
+        self.pending_registration_adapter
+            .write()
+            .await
+            .remove(&epoch)
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -103,9 +301,12 @@ mod tests {
 
     use super::*;
 
-    use mithril_common::test_utils::fake_data;
+    use mithril_common::entities::{ProtocolMessage, ProtocolMessagePartKey, ProtocolParameters};
+    use mithril_common::test_utils::{fake_data, MithrilFixtureBuilder};
     use mithril_persistence::store::adapter::MemoryAdapter;
 
+    use crate::{MithrilSingleSigner, SingleSigner};
+
     use rand_chacha::ChaCha20Rng;
     use rand_core::SeedableRng;
 
@@ -213,4 +414,177 @@ mod tests {
             .unwrap()
             .is_none());
     }
+
+    #[tokio::test]
+    async fn protocol_initializers_of_previous_parameters_versions_are_retained() {
+        let store = init_store(0, None);
+        let parameters = [
+            ProtocolParameters::new(5, 100, 0.65),
+            ProtocolParameters::new(10, 200, 0.65),
+            ProtocolParameters::new(10, 200, 0.8),
+        ];
+        for (epoch, protocol_parameters) in (1..).zip(parameters.iter()) {
+            let mut rng = ChaCha20Rng::from_seed([epoch as u8; 32]);
+            let protocol_initializer = ProtocolInitializer::setup(
+                protocol_parameters.clone().into(),
+                None::<PathBuf>,
+                Some(0),
+                100,
+                &mut rng,
+            )
+            .unwrap();
+            store
+                .save_protocol_initializer(Epoch(epoch), protocol_initializer)
+                .await
+                .unwrap();
+        }
+
+        let versions = store.get_all_protocol_initializer_versions().await.unwrap();
+
+        assert_eq!(
+            vec![
+                (
+                    Epoch(3),
+                    ProtocolInitializerVersion::from_protocol_parameters(
+                        &parameters[2].clone().into()
+                    )
+                ),
+                (
+                    Epoch(2),
+                    ProtocolInitializerVersion::from_protocol_parameters(
+                        &parameters[1].clone().into()
+                    )
+                ),
+                (
+                    Epoch(1),
+                    ProtocolInitializerVersion::from_protocol_parameters(
+                        &parameters[0].clone().into()
+                    )
+                ),
+            ],
+            versions
+        );
+        assert_eq!(
+            3,
+            versions
+                .iter()
+                .map(|(_, version)| version)
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+        );
+        for epoch in 1..=3 {
+            assert!(store
+                .get_protocol_initializer(Epoch(epoch))
+                .await
+                .unwrap()
+                .is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn exported_signing_keys_can_sign_once_imported() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let signer = &fixture.signers_fixture()[0];
+        let store = init_store(0, None);
+        store
+            .save_protocol_initializer(Epoch(5), signer.protocol_initializer.clone())
+            .await
+            .unwrap();
+        let key = [7u8; 32];
+
+        let bundle = store
+            .export_epoch_signing_keys(Epoch(5), &key)
+            .await
+            .unwrap();
+        // The keys are restored in a new store, as if the in-memory copy was lost
+        let restored_store = init_store(0, None);
+        restored_store
+            .import_epoch_signing_keys(bundle, &key)
+            .await
+            .unwrap();
+
+        let protocol_initializer = restored_store
+            .get_protocol_initializer(Epoch(5))
+            .await
+            .unwrap()
+            .expect("the protocol initializer should be restored");
+        let mut protocol_message = ProtocolMessage::new();
+        protocol_message
+            .set_message_part(ProtocolMessagePartKey::SnapshotDigest, "digest".to_string());
+        let signature = MithrilSingleSigner::new(signer.party_id())
+            .compute_single_signatures(
+                &protocol_message,
+                &fixture.signers_with_stake(),
+                &protocol_initializer,
+            )
+            .unwrap();
+        assert!(signature.is_some());
+    }
+
+    #[tokio::test]
+    async fn import_signing_keys_fails_with_another_key_or_epoch() {
+        let store = init_store(1, None);
+        let bundle = store
+            .export_epoch_signing_keys(Epoch(1), &[7u8; 32])
+            .await
+            .unwrap();
+
+        store
+            .import_epoch_signing_keys(bundle.clone(), &[8u8; 32])
+            .await
+            .expect_err("import with another key should fail");
+        store
+            .import_epoch_signing_keys(
+                EncryptedKeyBundle {
+                    epoch: Epoch(2),
+                    ..bundle
+                },
+                &[7u8; 32],
+            )
+            .await
+            .expect_err("import for another epoch should fail");
+    }
+
+    #[tokio::test]
+    async fn export_signing_keys_fails_without_protocol_initializer() {
+        let store = init_store(1, None);
+
+        store
+            .export_epoch_signing_keys(Epoch(3), &[7u8; 32])
+            .await
+            .expect_err("export of an unknown epoch should fail");
+    }
+
+    #[tokio::test]
+    async fn pending_registration_is_kept_until_removed() {
+        let protocol_initializers = setup_protocol_initializers(1);
+        let store = init_store(0, None);
+        let pending_registration = PendingRegistration {
+            nonce: "nonce-1".to_string(),
+            protocol_initializer: protocol_initializers[0].1.clone(),
+        };
+
+        store
+            .save_pending_registration(Epoch(1), pending_registration)
+            .await
+            .unwrap();
+        let saved_registration = store
+            .get_pending_registration(Epoch(1))
+            .await
+            .unwrap()
+            .expect("a pending registration should be saved");
+        assert_eq!("nonce-1", saved_registration.nonce);
+        assert!(store
+            .get_pending_registration(Epoch(2))
+            .await
+            .unwrap()
+            .is_none());
+
+        store.remove_pending_registration(Epoch(1)).await.unwrap();
+        assert!(store
+            .get_pending_registration(Epoch(1))
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
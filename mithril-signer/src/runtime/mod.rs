@@ -1,9 +1,11 @@
 mod error;
+mod multi_pool_signer;
 mod runner;
 mod signer_services;
 mod state_machine;
 
 pub use error::*;
+pub use multi_pool_signer::*;
 pub use runner::*;
 pub use signer_services::*;
 pub use state_machine::*;
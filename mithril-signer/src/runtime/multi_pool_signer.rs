@@ -0,0 +1,363 @@
+use futures::future::{join_all, try_join_all};
+use slog::{o, warn, Logger};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use mithril_common::entities::PartyId;
+
+use super::{RuntimeError, SignerState, StateMachine};
+
+/// Identifier of a stake pool operated by a [MultiPoolSigner].
+pub type PoolId = String;
+
+/// Party id used by the signer of each stake pool operated by a [MultiPoolSigner].
+pub type PoolIdMapping = HashMap<PoolId, PartyId>;
+
+/// Signing status of a stake pool operated by a [MultiPoolSigner].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignerStatus {
+    /// The last cycle of the pool signer succeeded.
+    Active,
+
+    /// The last cycle of the pool signer failed before it was registered to the aggregator.
+    RegistrationFailed,
+
+    /// The last cycle of the pool signer failed after it was registered to the aggregator.
+    SigningFailed,
+}
+
+impl SignerStatus {
+    fn from_cycle(state: &SignerState, result: &Result<(), RuntimeError>) -> Self {
+        match (state, result) {
+            (_, Ok(())) => Self::Active,
+            (SignerState::Init | SignerState::Unregistered { .. }, Err(_)) => {
+                Self::RegistrationFailed
+            }
+            (SignerState::Registered { .. } | SignerState::Signed { .. }, Err(_)) => {
+                Self::SigningFailed
+            }
+        }
+    }
+}
+
+struct PoolSigner {
+    pool_id: PoolId,
+    state_machine: StateMachine,
+    status: RwLock<SignerStatus>,
+    logger: Logger,
+}
+
+impl PoolSigner {
+    fn record_cycle(&self, state: &SignerState, result: &Result<(), RuntimeError>) {
+        let status = SignerStatus::from_cycle(state, result);
+        if let Err(error) = result {
+            warn!(
+                self.logger,
+                "MULTI POOL SIGNER: pool cycle failed";
+                "status" => ?status,
+                "error" => %error
+            );
+        }
+
+        // A panic while holding the lock can't leave the status in an inconsistent state
+        *self.status.write().unwrap_or_else(|e| e.into_inner()) = status;
+    }
+
+    fn status(&self) -> SignerStatus {
+        *self.status.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Signer of a stake pool operator running several Cardano nodes, one per stake pool.
+///
+/// Each pool is signed independently by its own [StateMachine], so the failure of a pool signer
+/// doesn't prevent the other ones from signing. The state machines must be built with their own
+/// services, so they don't share a certificate handler nor their KES keys.
+pub struct MultiPoolSigner {
+    signers: Vec<PoolSigner>,
+}
+
+impl MultiPoolSigner {
+    /// Create a new MultiPoolSigner instance from the state machine of each pool.
+    pub fn new(
+        signers: Vec<(PoolId, StateMachine)>,
+        pool_id_mapping: PoolIdMapping,
+        logger: Logger,
+    ) -> Self {
+        let signers = signers
+            .into_iter()
+            .map(|(pool_id, state_machine)| {
+                let party_id = pool_id_mapping.get(&pool_id).cloned();
+                PoolSigner {
+                    logger: logger.new(o!("pool_id" => pool_id.clone(), "party_id" => party_id)),
+                    pool_id,
+                    state_machine,
+                    status: RwLock::new(SignerStatus::Active),
+                }
+            })
+            .collect();
+
+        Self { signers }
+    }
+
+    /// Launch the state machines of all the pools concurrently.
+    ///
+    /// The state machines only stop on a critical error: the first one stops all the pools and
+    /// is returned right away.
+    pub async fn run(&self) -> Result<(), RuntimeError> {
+        try_join_all(self.signers.iter().map(|signer| {
+            signer
+                .state_machine
+                .run_observed(|state, result| signer.record_cycle(state, result))
+        }))
+        .await
+        .map(|_| ())
+    }
+
+    /// Perform a cycle of the state machines of all the pools concurrently.
+    pub async fn cycle(&self) {
+        join_all(self.signers.iter().map(|signer| async move {
+            let state = signer.state_machine.get_state().await;
+            let result = signer.state_machine.cycle().await;
+            signer.record_cycle(&state, &result);
+        }))
+        .await;
+    }
+
+    /// Return the signing status of each pool, in the order the pools were given.
+    pub fn get_signing_status(&self) -> Vec<(PoolId, SignerStatus)> {
+        self.signers
+            .iter()
+            .map(|signer| (signer.pool_id.clone(), signer.status()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use mithril_common::crypto_helper::ProtocolInitializerError;
+    use mithril_common::entities::{CertificatePending, ProtocolMessage, TimePoint};
+    use mithril_common::test_utils::fake_data;
+
+    use crate::runtime::MockSignerRunner;
+    use crate::test_tools::logger_for_tests;
+    use crate::MetricsService;
+
+    use super::*;
+
+    fn init_state_machine(runner: MockSignerRunner) -> StateMachine {
+        StateMachine::new(
+            SignerState::Unregistered {
+                epoch: TimePoint::dummy().epoch,
+            },
+            Box::new(runner),
+            Duration::from_millis(100),
+            Arc::new(MetricsService::new().unwrap()),
+            logger_for_tests(),
+        )
+    }
+
+    fn registering_and_signing_runner() -> MockSignerRunner {
+        let certificate_pending = CertificatePending {
+            epoch: TimePoint::dummy().epoch,
+            ..fake_data::certificate_pending()
+        };
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_time_point()
+            .times(3)
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_get_epoch_settings()
+            .once()
+            .returning(|| Ok(Some(fake_data::epoch_settings())));
+        runner
+            .expect_update_stake_distribution()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_register_signer_to_aggregator()
+            .once()
+            .returning(|_, _| Ok(()));
+        runner
+            .expect_get_pending_certificate()
+            .once()
+            .returning(move || Ok(Some(certificate_pending.clone())));
+        runner.expect_can_i_sign().once().returning(|_| Ok(true));
+        runner
+            .expect_associate_signers_with_stake()
+            .times(2)
+            .returning(|_, _| Ok(fake_data::signers_with_stakes(4)));
+        runner
+            .expect_verify_pending_certificate()
+            .once()
+            .returning(|_, _, _| Ok(()));
+        runner
+            .expect_compute_message()
+            .once()
+            .returning(|_, _| Ok(ProtocolMessage::new()));
+        runner
+            .expect_compute_single_signature()
+            .once()
+            .returning(|_, _, _| Ok(Some(fake_data::single_signatures(vec![1, 5, 23]))));
+        runner
+            .expect_send_single_signature()
+            .once()
+            .returning(|_, _| Ok(()));
+
+        runner
+    }
+
+    fn failing_registration_runner() -> MockSignerRunner {
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_time_point()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_get_epoch_settings()
+            .returning(|| Ok(Some(fake_data::epoch_settings())));
+        runner
+            .expect_update_stake_distribution()
+            .returning(|_| Ok(()));
+        runner
+            .expect_register_signer_to_aggregator()
+            .returning(|_, _| Err(anyhow!("registration refused")));
+
+        runner
+    }
+
+    fn never_registered_runner() -> MockSignerRunner {
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_time_point()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner.expect_get_epoch_settings().returning(|| Ok(None));
+
+        runner
+    }
+
+    fn critical_registration_runner() -> MockSignerRunner {
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_time_point()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_get_epoch_settings()
+            .returning(|| Ok(Some(fake_data::epoch_settings())));
+        runner
+            .expect_update_stake_distribution()
+            .returning(|_| Ok(()));
+        runner
+            .expect_register_signer_to_aggregator()
+            .returning(|_, _| Err(ProtocolInitializerError::KesMismatch(5, 6).into()));
+
+        runner
+    }
+
+    #[test]
+    fn signer_status_from_cycle() {
+        let epoch = TimePoint::dummy().epoch;
+        let failure = || {
+            Err(RuntimeError::KeepState {
+                message: "failure".to_string(),
+                nested_error: None,
+            })
+        };
+
+        assert_eq!(
+            SignerStatus::Active,
+            SignerStatus::from_cycle(&SignerState::Registered { epoch }, &Ok(()))
+        );
+        assert_eq!(
+            SignerStatus::RegistrationFailed,
+            SignerStatus::from_cycle(&SignerState::Init, &failure())
+        );
+        assert_eq!(
+            SignerStatus::RegistrationFailed,
+            SignerStatus::from_cycle(&SignerState::Unregistered { epoch }, &failure())
+        );
+        assert_eq!(
+            SignerStatus::SigningFailed,
+            SignerStatus::from_cycle(&SignerState::Registered { epoch }, &failure())
+        );
+    }
+
+    #[tokio::test]
+    async fn a_pool_failing_to_register_does_not_prevent_the_other_pools_from_signing() {
+        let signer = MultiPoolSigner::new(
+            vec![
+                (
+                    "pool-a".to_string(),
+                    init_state_machine(registering_and_signing_runner()),
+                ),
+                (
+                    "pool-b".to_string(),
+                    init_state_machine(failing_registration_runner()),
+                ),
+            ],
+            PoolIdMapping::from([
+                ("pool-a".to_string(), "party-a".to_string()),
+                ("pool-b".to_string(), "party-b".to_string()),
+            ]),
+            logger_for_tests(),
+        );
+
+        // Unregistered → Registered, then Registered → Signed
+        signer.cycle().await;
+        signer.cycle().await;
+
+        assert_eq!(
+            vec![
+                ("pool-a".to_string(), SignerStatus::Active),
+                ("pool-b".to_string(), SignerStatus::RegistrationFailed),
+            ],
+            signer.get_signing_status()
+        );
+        assert!(signer.signers[0]
+            .state_machine
+            .get_state()
+            .await
+            .is_signed());
+        assert!(signer.signers[1]
+            .state_machine
+            .get_state()
+            .await
+            .is_unregistered());
+    }
+
+    #[tokio::test]
+    async fn run_stops_on_the_first_critical_error_without_waiting_for_the_other_pools() {
+        let signer = MultiPoolSigner::new(
+            vec![
+                (
+                    "pool-a".to_string(),
+                    init_state_machine(never_registered_runner()),
+                ),
+                (
+                    "pool-b".to_string(),
+                    init_state_machine(critical_registration_runner()),
+                ),
+            ],
+            PoolIdMapping::new(),
+            logger_for_tests(),
+        );
+
+        let error = tokio::time::timeout(Duration::from_secs(10), signer.run())
+            .await
+            .expect("run should stop as soon as a pool has a critical error")
+            .expect_err("run should return the critical error");
+
+        assert!(error.is_critical(), "unexpected error: {error:?}");
+        assert_eq!(
+            vec![
+                ("pool-a".to_string(), SignerStatus::Active),
+                ("pool-b".to_string(), SignerStatus::RegistrationFailed),
+            ],
+            signer.get_signing_status()
+        );
+    }
+}
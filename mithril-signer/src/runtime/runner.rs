@@ -1,12 +1,17 @@
 use anyhow::Context;
 use async_trait::async_trait;
 use slog_scope::{debug, info, trace, warn};
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
+use tokio::sync::RwLock;
 
 #[cfg(test)]
 use mockall::automock;
 
-use mithril_common::crypto_helper::{KESPeriod, OpCert, ProtocolOpCert, SerDeShelleyFileFormat};
+use mithril_common::crypto_helper::{
+    KESPeriod, OpCert, ProtocolInitializer, ProtocolOpCert, SerDeShelleyFileFormat,
+};
 use mithril_common::entities::{
     CertificatePending, Epoch, EpochSettings, PartyId, ProtocolMessage, ProtocolMessagePartKey,
     ProtocolParameters, SignedEntityType, Signer, SignerWithStake, SingleSignatures, TimePoint,
@@ -14,7 +19,10 @@ use mithril_common::entities::{
 use mithril_common::StdResult;
 use mithril_persistence::store::StakeStorer;
 
-use crate::{Configuration, MithrilProtocolInitializerBuilder};
+use crate::{
+    Configuration, KesRotationHook, MithrilProtocolInitializerBuilder, NonceGenerator,
+    PendingRegistration,
+};
 
 use super::signer_services::SignerServices;
 
@@ -51,6 +59,14 @@ pub trait Runner: Send + Sync {
         signers: &[Signer],
     ) -> StdResult<Vec<SignerWithStake>>;
 
+    /// Check that the pending certificate is consistent with the data observed locally.
+    async fn verify_pending_certificate(
+        &self,
+        pending_certificate: &CertificatePending,
+        current_epoch: Epoch,
+        signers: &[SignerWithStake],
+    ) -> StdResult<()>;
+
     /// Create the message to be signed with the single signature.
     async fn compute_message(
         &self,
@@ -73,6 +89,14 @@ pub trait Runner: Send + Sync {
         maybe_signature: Option<SingleSignatures>,
     ) -> StdResult<()>;
 
+    /// Send several single signatures of the same signed entity type to the aggregator, in one
+    /// batch if there are more than one of them.
+    async fn send_single_signatures(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: Vec<SingleSignatures>,
+    ) -> StdResult<()>;
+
     /// Read the current era and update the EraChecker.
     async fn update_era_checker(&self, epoch: Epoch) -> StdResult<()>;
 }
@@ -92,18 +116,169 @@ pub enum RunnerError {
     /// Parse file error
     #[error("File parse failed: {0}.")]
     FileParse(String),
+    /// The protocol initializer was still not available after all the attempts of the
+    /// [InitializerRetryPolicy].
+    #[error("No protocol initializer available after {attempts} attempts.")]
+    InitializerUnavailableAfterRetries {
+        /// Number of attempts made to get the protocol initializer
+        attempts: u32,
+    },
+    /// The latest stake distribution known by the signer is older than the
+    /// [maximum stake age][SignerRunner::with_max_stake_age] relative to the pending certificate.
+    #[error("Stake distribution of epoch {stake_epoch} is too old to sign a certificate of epoch {certificate_epoch}.")]
+    StakeDistributionTooOld {
+        /// Epoch of the pending certificate
+        certificate_epoch: Epoch,
+        /// Epoch of the latest stake distribution known by the signer
+        stake_epoch: Epoch,
+    },
+}
+
+/// Policy to retry getting a protocol initializer that is not available yet, ie: on the first
+/// startup of a signer, before its initializer is created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitializerRetryPolicy {
+    /// Number of retries after the first attempt
+    pub max_retries: u32,
+
+    /// Delay to wait before each retry
+    pub delay: Duration,
+}
+
+impl InitializerRetryPolicy {
+    /// InitializerRetryPolicy factory
+    pub fn new(max_retries: u32, delay: Duration) -> Self {
+        Self { max_retries, delay }
+    }
+}
+
+impl Default for InitializerRetryPolicy {
+    /// No retry
+    fn default() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
 }
 
 /// Controller methods for the Signer's state machine.
 pub struct SignerRunner {
     config: Configuration,
     services: SignerServices,
+    kes_rotation_hook: Option<Arc<dyn KesRotationHook>>,
+    last_registered_kes_period: RwLock<Option<KESPeriod>>,
+    initializer_retry_policy: InitializerRetryPolicy,
+    nonce_generator: Option<Arc<dyn NonceGenerator>>,
+    max_stake_age: Option<u64>,
 }
 
 impl SignerRunner {
     /// Create a new Runner instance.
     pub fn new(config: Configuration, services: SignerServices) -> Self {
-        Self { services, config }
+        Self {
+            services,
+            config,
+            kes_rotation_hook: None,
+            last_registered_kes_period: RwLock::new(None),
+            initializer_retry_policy: InitializerRetryPolicy::default(),
+            nonce_generator: None,
+            max_stake_age: None,
+        }
+    }
+
+    /// Set the generator of the nonces sent with each registration, so a registration request
+    /// delivered twice is only processed once by the aggregator.
+    ///
+    /// A new nonce is generated for each call to `register_signer_to_aggregator`, since each of
+    /// them registers a new verification key.
+    pub fn with_nonce_generator(mut self, nonce_generator: Arc<dyn NonceGenerator>) -> Self {
+        self.nonce_generator = Some(nonce_generator);
+        self
+    }
+
+    /// Set the policy used to retry getting a protocol initializer that is not available yet.
+    pub fn with_initializer_retry_policy(mut self, policy: InitializerRetryPolicy) -> Self {
+        self.initializer_retry_policy = policy;
+        self
+    }
+
+    /// Set the maximum number of epochs between a pending certificate and the latest stake
+    /// distribution known by the signer, the aggregator rejects the signatures computed with an
+    /// older stake distribution.
+    pub fn with_max_stake_age(mut self, epochs: u64) -> Self {
+        self.max_stake_age = Some(epochs);
+        self
+    }
+
+    /// Check that the latest stake distribution known by the signer is not older than the
+    /// maximum stake age relative to the given pending certificate.
+    async fn check_stake_freshness(
+        &self,
+        pending_certificate: &CertificatePending,
+    ) -> StdResult<()> {
+        let Some(max_stake_age) = self.max_stake_age else {
+            return Ok(());
+        };
+        let Some(stake_epoch) = self.services.stake_store.get_last_stakes_epoch().await? else {
+            return Ok(());
+        };
+
+        let certificate_epoch = pending_certificate.epoch;
+        if certificate_epoch.saturating_sub(*stake_epoch) > max_stake_age {
+            return Err(RunnerError::StakeDistributionTooOld {
+                certificate_epoch,
+                stake_epoch,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Set the hook supplying the protocol initializer when the KES period advances.
+    pub fn with_kes_rotation_hook(mut self, hook: Arc<dyn KesRotationHook>) -> Self {
+        self.kes_rotation_hook = Some(hook);
+        self
+    }
+
+    /// Check if the KES period advanced since the last registration of the signer.
+    async fn is_kes_rotation(&self, kes_period: Option<KESPeriod>) -> bool {
+        match (*self.last_registered_kes_period.read().await, kes_period) {
+            (Some(last_kes_period), Some(kes_period)) => kes_period > last_kes_period,
+            _ => false,
+        }
+    }
+
+    /// Get the protocol initializer of the given epoch, waiting for it according to the
+    /// [InitializerRetryPolicy] if it is not available yet.
+    async fn get_protocol_initializer_with_retry(
+        &self,
+        epoch: Epoch,
+    ) -> StdResult<ProtocolInitializer> {
+        let policy = self.initializer_retry_policy;
+        let max_attempts = policy.max_retries + 1;
+        for attempt in 1..=max_attempts {
+            if let Some(protocol_initializer) = self
+                .services
+                .protocol_initializer_store
+                .get_protocol_initializer(epoch)
+                .await?
+            {
+                return Ok(protocol_initializer);
+            }
+
+            if attempt < max_attempts {
+                warn!(
+                    " > NO protocol initializer found for epoch {epoch}, retrying in {:?}",
+                    policy.delay;
+                    "attempt" => attempt, "max_attempts" => max_attempts
+                );
+                tokio::time::sleep(policy.delay).await;
+            }
+        }
+
+        Err(RunnerError::InitializerUnavailableAfterRetries {
+            attempts: max_attempts,
+        })
+        .with_context(|| format!("Runner can not get protocol initializer at epoch {epoch}"))
     }
 }
 
@@ -187,19 +362,71 @@ impl Runner for SignerRunner {
             ),
             None => None,
         };
-        let protocol_initializer = MithrilProtocolInitializerBuilder::build(
-            stake,
-            protocol_parameters,
-            self.config.kes_secret_key_path.clone(),
-            kes_period,
-        )?;
-        let signer = Signer::new(
+        // A registration not acknowledged by the aggregator is sent again with the same nonce
+        // and keys, so the aggregator can recognize it if it was already processed
+        let pending_registration = match &self.nonce_generator {
+            Some(_) => {
+                self.services
+                    .protocol_initializer_store
+                    .get_pending_registration(epoch_offset_to_recording_epoch)
+                    .await?
+            }
+            None => None,
+        };
+        let (protocol_initializer, registration_nonce) = match pending_registration {
+            Some(pending_registration) => {
+                debug!("RUNNER: register_signer_to_aggregator: retrying pending registration"; "nonce" => &pending_registration.nonce);
+                (
+                    pending_registration.protocol_initializer,
+                    Some(pending_registration.nonce),
+                )
+            }
+            None => {
+                let protocol_initializer = match &self.kes_rotation_hook {
+                    Some(hook) if self.is_kes_rotation(kes_period).await => {
+                        let new_kes_period = kes_period.unwrap_or_default();
+                        info!("RUNNER: register_signer_to_aggregator: KES rotation detected"; "new_kes_period" => new_kes_period);
+                        hook.on_kes_rotation(new_kes_period).with_context(|| {
+                            format!("KES rotation hook failed for KES period {new_kes_period}")
+                        })?
+                    }
+                    _ => MithrilProtocolInitializerBuilder::build(
+                        stake,
+                        protocol_parameters,
+                        self.config.kes_secret_key_path.clone(),
+                        kes_period,
+                    )?,
+                };
+                let registration_nonce = match &self.nonce_generator {
+                    Some(nonce_generator) => {
+                        let nonce = nonce_generator.next_nonce();
+                        self.services
+                            .protocol_initializer_store
+                            .save_pending_registration(
+                                epoch_offset_to_recording_epoch,
+                                PendingRegistration {
+                                    nonce: nonce.clone(),
+                                    protocol_initializer: protocol_initializer.clone(),
+                                },
+                            )
+                            .await?;
+                        Some(nonce)
+                    }
+                    None => None,
+                };
+                (protocol_initializer, registration_nonce)
+            }
+        };
+        let mut signer = Signer::new(
             self.services.single_signer.get_party_id(),
             protocol_initializer.verification_key().into(),
             protocol_initializer.verification_key_signature(),
             protocol_operational_certificate,
             kes_period,
         );
+        if let Some(registration_nonce) = &registration_nonce {
+            signer = signer.with_registration_nonce(registration_nonce.clone());
+        }
         self.services
             .certificate_handler
             .register_signer(epoch_offset_to_recording_epoch, &signer)
@@ -208,6 +435,13 @@ impl Runner for SignerRunner {
             .protocol_initializer_store
             .save_protocol_initializer(epoch_offset_to_recording_epoch, protocol_initializer)
             .await?;
+        if registration_nonce.is_some() {
+            self.services
+                .protocol_initializer_store
+                .remove_pending_registration(epoch_offset_to_recording_epoch)
+                .await?;
+        }
+        *self.last_registered_kes_period.write().await = kes_period;
 
         Ok(())
     }
@@ -347,15 +581,8 @@ impl Runner for SignerRunner {
         let epoch = signed_entity_type.get_epoch();
         let next_signer_retrieval_epoch = epoch.offset_to_next_signer_retrieval_epoch();
         let next_protocol_initializer = self
-            .services
-            .protocol_initializer_store
-            .get_protocol_initializer(next_signer_retrieval_epoch)
-            .await?
-            .ok_or_else(|| {
-                RunnerError::NoValueError(format!(
-                    "protocol_initializer at epoch {next_signer_retrieval_epoch}"
-                ))
-            })?;
+            .get_protocol_initializer_with_retry(next_signer_retrieval_epoch)
+            .await?;
 
         let avk = self
             .services
@@ -367,6 +594,26 @@ impl Runner for SignerRunner {
         Ok(message)
     }
 
+    async fn verify_pending_certificate(
+        &self,
+        pending_certificate: &CertificatePending,
+        current_epoch: Epoch,
+        signers: &[SignerWithStake],
+    ) -> StdResult<()> {
+        debug!("RUNNER: verify_pending_certificate");
+        self.check_stake_freshness(pending_certificate).await?;
+
+        self.services
+            .single_signer
+            .verify_pending_certificate(pending_certificate, current_epoch, signers)
+            .with_context(|| {
+                format!(
+                    "Runner can not verify pending certificate for signed entity type: '{}'",
+                    pending_certificate.signed_entity_type
+                )
+            })
+    }
+
     async fn compute_single_signature(
         &self,
         epoch: Epoch,
@@ -377,15 +624,8 @@ impl Runner for SignerRunner {
 
         let signer_retrieval_epoch = epoch.offset_to_signer_retrieval_epoch()?;
         let protocol_initializer = self
-            .services
-            .protocol_initializer_store
-            .get_protocol_initializer(signer_retrieval_epoch)
-            .await?
-            .ok_or_else(|| {
-                RunnerError::NoValueError(format!(
-                    "protocol_initializer at epoch {signer_retrieval_epoch}"
-                ))
-            })?;
+            .get_protocol_initializer_with_retry(signer_retrieval_epoch)
+            .await?;
         let signature = self.services.single_signer.compute_single_signatures(
             message,
             signers,
@@ -410,20 +650,43 @@ impl Runner for SignerRunner {
     ) -> StdResult<()> {
         debug!("RUNNER: send_single_signature");
 
-        if let Some(single_signatures) = maybe_signature {
-            debug!(" > there is a single signature to send");
+        self.send_single_signatures(signed_entity_type, maybe_signature.into_iter().collect())
+            .await
+    }
 
-            self.services
-                .certificate_handler
-                .register_signatures(signed_entity_type, &single_signatures)
-                .await?;
+    async fn send_single_signatures(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: Vec<SingleSignatures>,
+    ) -> StdResult<()> {
+        debug!("RUNNER: send_single_signatures");
 
-            Ok(())
-        } else {
-            debug!(" > NO single signature to send, doing nothing");
+        match signatures.as_slice() {
+            [] => {
+                debug!(" > NO single signature to send, doing nothing");
+            }
+            [single_signatures] => {
+                debug!(" > there is a single signature to send");
 
-            Ok(())
+                self.services
+                    .certificate_handler
+                    .register_signatures(signed_entity_type, single_signatures)
+                    .await?;
+            }
+            _ => {
+                debug!(
+                    " > there are {} single signatures to send in a batch",
+                    signatures.len()
+                );
+
+                self.services
+                    .certificate_handler
+                    .register_signatures_batch(signed_entity_type, &signatures)
+                    .await?;
+            }
         }
+
+        Ok(())
     }
 
     async fn update_era_checker(&self, epoch: Epoch) -> StdResult<()> {
@@ -461,7 +724,9 @@ mod tests {
         cardano_block_scanner::DumbBlockScanner,
         cardano_transactions_preloader::CardanoTransactionsPreloader,
         chain_observer::{ChainObserver, FakeObserver},
-        crypto_helper::{MKMap, MKMapNode, MKTreeNode, ProtocolInitializer},
+        crypto_helper::{
+            MKMap, MKMapNode, MKTreeNode, ProtocolInitializer, ProtocolSignerVerificationKey,
+        },
         digesters::{DumbImmutableDigester, DumbImmutableFileObserver},
         entities::{BlockNumber, BlockRange, CardanoDbBeacon, Epoch, StakeDistribution},
         era::{adapters::EraReaderBootstrapAdapter, EraChecker, EraReader},
@@ -476,13 +741,14 @@ mod tests {
     };
     use mithril_persistence::store::adapter::{DumbStoreAdapter, MemoryAdapter};
     use mithril_persistence::store::{StakeStore, StakeStorer};
-    use mockall::mock;
-    use std::{path::Path, sync::Arc};
+    use mockall::{mock, predicate};
+    use std::path::Path;
 
     use crate::{
         metrics::MetricsService, AggregatorClient, CardanoTransactionsImporter,
-        DumbAggregatorClient, MithrilSingleSigner, MockAggregatorClient, MockTransactionStore,
-        ProtocolInitializerStore, SingleSigner,
+        DumbAggregatorClient, MithrilSingleSigner, MockAggregatorClient, MockKesRotationHook,
+        MockNonceGenerator, MockTransactionStore, ProtocolInitializerStore,
+        ProtocolInitializerStorer, SingleSigner,
     };
 
     use super::*;
@@ -498,6 +764,42 @@ mod tests {
         }
     }
 
+    mock! {
+        pub ProtocolInitializerStoreImpl { }
+
+        #[async_trait]
+        impl ProtocolInitializerStorer for ProtocolInitializerStoreImpl {
+            async fn save_protocol_initializer(
+                &self,
+                epoch: Epoch,
+                protocol_initializer: ProtocolInitializer,
+            ) -> StdResult<Option<ProtocolInitializer>>;
+
+            async fn get_protocol_initializer(
+                &self,
+                epoch: Epoch,
+            ) -> StdResult<Option<ProtocolInitializer>>;
+
+            async fn get_last_protocol_initializer(
+                &self,
+                last: usize,
+            ) -> StdResult<Vec<(Epoch, ProtocolInitializer)>>;
+
+            async fn save_pending_registration(
+                &self,
+                epoch: Epoch,
+                pending_registration: PendingRegistration,
+            ) -> StdResult<()>;
+
+            async fn get_pending_registration(
+                &self,
+                epoch: Epoch,
+            ) -> StdResult<Option<PendingRegistration>>;
+
+            async fn remove_pending_registration(&self, epoch: Epoch) -> StdResult<()>;
+        }
+    }
+
     mock! {
         pub BlockRangeRootRetrieverImpl { }
 
@@ -716,6 +1018,230 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_register_signer_to_aggregator_sends_a_registration_nonce() {
+        let mut services = init_services().await;
+        let certificate_handler = Arc::new(DumbAggregatorClient::default());
+        services.certificate_handler = certificate_handler.clone();
+        let chain_observer = Arc::new(FakeObserver::default());
+        services.chain_observer = chain_observer.clone();
+        let epoch = services
+            .ticker_service
+            .get_current_epoch()
+            .await
+            .unwrap()
+            .offset_to_recording_epoch();
+        let stakes = chain_observer
+            .get_current_stake_distribution()
+            .await
+            .unwrap()
+            .unwrap();
+        services
+            .stake_store
+            .save_stakes(epoch, stakes)
+            .await
+            .unwrap();
+        let mut nonce_generator = MockNonceGenerator::new();
+        nonce_generator
+            .expect_next_nonce()
+            .returning(|| "nonce-1".to_string())
+            .once();
+        let runner = init_runner(Some(services), None)
+            .await
+            .with_nonce_generator(Arc::new(nonce_generator));
+        let epoch = chain_observer
+            .current_time_point
+            .read()
+            .await
+            .clone()
+            .expect("chain_observer should have a current_time_point")
+            .epoch;
+        let pending_certificate = certificate_handler
+            .retrieve_pending_certificate()
+            .await
+            .unwrap()
+            .unwrap();
+
+        runner
+            .register_signer_to_aggregator(epoch, &pending_certificate.protocol_parameters)
+            .await
+            .expect("registering a signer to the aggregator should not fail");
+
+        let registered_signer = certificate_handler
+            .get_last_registered_signer()
+            .await
+            .unwrap();
+        assert_eq!(
+            Some("nonce-1".to_string()),
+            registered_signer.registration_nonce
+        );
+    }
+
+    #[tokio::test]
+    async fn test_register_signer_to_aggregator_retries_the_pending_registration() {
+        let mut services = init_services().await;
+        let certificate_handler = Arc::new(DumbAggregatorClient::default());
+        services.certificate_handler = certificate_handler.clone();
+        let chain_observer = Arc::new(FakeObserver::default());
+        services.chain_observer = chain_observer.clone();
+        let protocol_initializer_store = services.protocol_initializer_store.clone();
+        let recording_epoch = services
+            .ticker_service
+            .get_current_epoch()
+            .await
+            .unwrap()
+            .offset_to_recording_epoch();
+        let stakes = chain_observer
+            .get_current_stake_distribution()
+            .await
+            .unwrap()
+            .unwrap();
+        services
+            .stake_store
+            .save_stakes(recording_epoch, stakes)
+            .await
+            .unwrap();
+        let pending_certificate = certificate_handler
+            .retrieve_pending_certificate()
+            .await
+            .unwrap()
+            .unwrap();
+        let pending_protocol_initializer = MithrilProtocolInitializerBuilder::build(
+            &100,
+            &pending_certificate.protocol_parameters,
+            None,
+            None,
+        )
+        .unwrap();
+        protocol_initializer_store
+            .save_pending_registration(
+                recording_epoch,
+                PendingRegistration {
+                    nonce: "pending-nonce".to_string(),
+                    protocol_initializer: pending_protocol_initializer.clone(),
+                },
+            )
+            .await
+            .unwrap();
+        let mut nonce_generator = MockNonceGenerator::new();
+        nonce_generator.expect_next_nonce().never();
+        let runner = init_runner(Some(services), None)
+            .await
+            .with_nonce_generator(Arc::new(nonce_generator));
+        let epoch = chain_observer
+            .current_time_point
+            .read()
+            .await
+            .clone()
+            .expect("chain_observer should have a current_time_point")
+            .epoch;
+
+        runner
+            .register_signer_to_aggregator(epoch, &pending_certificate.protocol_parameters)
+            .await
+            .expect("registering a signer to the aggregator should not fail");
+
+        let registered_signer = certificate_handler
+            .get_last_registered_signer()
+            .await
+            .unwrap();
+        assert_eq!(
+            Some("pending-nonce".to_string()),
+            registered_signer.registration_nonce
+        );
+        assert_eq!(
+            pending_protocol_initializer.verification_key(),
+            registered_signer.verification_key.into()
+        );
+        assert!(protocol_initializer_store
+            .get_pending_registration(recording_epoch)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_register_signer_to_aggregator_after_kes_rotation_uses_the_hook_initializer() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(1).build();
+        let signer_fixture = fixture.signers_fixture()[0].clone();
+        let party_id = signer_fixture.party_id();
+        let protocol_parameters = fixture.protocol_parameters();
+        let start_kes_period = signer_fixture
+            .operational_certificate()
+            .expect("the fixture signer should have an operational certificate")
+            .start_kes_period as KESPeriod;
+        let epoch = Epoch(10);
+
+        let mut services = init_services().await;
+        let certificate_handler = Arc::new(DumbAggregatorClient::default());
+        services.certificate_handler = certificate_handler.clone();
+        services.single_signer = Arc::new(MithrilSingleSigner::new(party_id.clone()));
+        let chain_observer = Arc::new(FakeObserver::default());
+        chain_observer
+            .set_signers(fixture.signers_with_stake())
+            .await;
+        chain_observer
+            .set_current_kes_period(start_kes_period)
+            .await;
+        services.chain_observer = chain_observer.clone();
+        services.stake_store = Arc::new(StakeStore::new(
+            Box::new(MemoryAdapter::new(None).unwrap()),
+            None,
+        ));
+        for registration_epoch in [epoch, epoch + 1] {
+            services
+                .stake_store
+                .save_stakes(
+                    registration_epoch.offset_to_recording_epoch(),
+                    fixture.stake_distribution(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let rotated_protocol_initializer = MithrilProtocolInitializerBuilder::build(
+            &signer_fixture.signer_with_stake.stake,
+            &protocol_parameters,
+            None,
+            None,
+        )
+        .unwrap();
+        let rotated_verification_key: ProtocolSignerVerificationKey =
+            rotated_protocol_initializer.verification_key().into();
+        let mut kes_rotation_hook = MockKesRotationHook::new();
+        kes_rotation_hook
+            .expect_on_kes_rotation()
+            .with(predicate::eq(1))
+            .return_once(move |_| Ok(rotated_protocol_initializer))
+            .once();
+        let runner = init_runner(Some(services), Some(Configuration::new_sample(party_id)))
+            .await
+            .with_kes_rotation_hook(Arc::new(kes_rotation_hook));
+
+        runner
+            .register_signer_to_aggregator(epoch, &protocol_parameters)
+            .await
+            .expect("registering a signer to the aggregator should not fail");
+        let registered_signer = certificate_handler
+            .get_last_registered_signer()
+            .await
+            .unwrap();
+        assert_ne!(rotated_verification_key, registered_signer.verification_key);
+
+        chain_observer
+            .set_current_kes_period(start_kes_period + 1)
+            .await;
+        runner
+            .register_signer_to_aggregator(epoch + 1, &protocol_parameters)
+            .await
+            .expect("registering a signer after a KES rotation should not fail");
+        let registered_signer = certificate_handler
+            .get_last_registered_signer()
+            .await
+            .unwrap();
+        assert_eq!(rotated_verification_key, registered_signer.verification_key);
+    }
+
     #[tokio::test]
     async fn test_can_i_sign() {
         let mut pending_certificate = fake_data::certificate_pending();
@@ -895,6 +1421,125 @@ mod tests {
         assert_eq!(expected, single_signature);
     }
 
+    #[tokio::test]
+    async fn test_compute_single_signature_retries_until_protocol_initializer_is_available() {
+        let mut services = init_services().await;
+        let current_time_point = services
+            .ticker_service
+            .get_current_time_point()
+            .await
+            .expect("get_current_time_point should not fail");
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let signer_with_stake = fixture.signers_fixture()[0].signer_with_stake.clone();
+        let protocol_initializer = fixture.signers_fixture()[0].protocol_initializer.clone();
+        let single_signer = Arc::new(MithrilSingleSigner::new(
+            signer_with_stake.party_id.to_string(),
+        ));
+        services.single_signer = single_signer.clone();
+        let mut protocol_initializer_store = MockProtocolInitializerStoreImpl::new();
+        let mut sequence = mockall::Sequence::new();
+        protocol_initializer_store
+            .expect_get_protocol_initializer()
+            .times(2)
+            .in_sequence(&mut sequence)
+            .returning(|_| Ok(None));
+        let returned_protocol_initializer = protocol_initializer.clone();
+        protocol_initializer_store
+            .expect_get_protocol_initializer()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_once(move |_| Ok(Some(returned_protocol_initializer)));
+        services.protocol_initializer_store = Arc::new(protocol_initializer_store);
+        let signers = fixture.signers_with_stake();
+        let mut message = ProtocolMessage::new();
+        message.set_message_part(
+            ProtocolMessagePartKey::SnapshotDigest,
+            "a message".to_string(),
+        );
+
+        let expected = single_signer
+            .compute_single_signatures(&message, &signers, &protocol_initializer)
+            .expect("compute_single_signatures should not fail");
+
+        let runner = init_runner(Some(services), None)
+            .await
+            .with_initializer_retry_policy(InitializerRetryPolicy::new(
+                2,
+                Duration::from_millis(1),
+            ));
+        let single_signature = runner
+            .compute_single_signature(current_time_point.epoch, &message, &signers)
+            .await
+            .expect("compute_single_signature should not fail");
+        assert_eq!(expected, single_signature);
+    }
+
+    #[tokio::test]
+    async fn test_compute_single_signature_fails_when_protocol_initializer_is_unavailable_after_retries(
+    ) {
+        let mut services = init_services().await;
+        let current_time_point = services
+            .ticker_service
+            .get_current_time_point()
+            .await
+            .expect("get_current_time_point should not fail");
+        let mut protocol_initializer_store = MockProtocolInitializerStoreImpl::new();
+        protocol_initializer_store
+            .expect_get_protocol_initializer()
+            .times(3)
+            .returning(|_| Ok(None));
+        services.protocol_initializer_store = Arc::new(protocol_initializer_store);
+        let signers = fake_data::signers_with_stakes(2);
+
+        let runner = init_runner(Some(services), None)
+            .await
+            .with_initializer_retry_policy(InitializerRetryPolicy::new(
+                2,
+                Duration::from_millis(1),
+            ));
+        let error = runner
+            .compute_single_signature(current_time_point.epoch, &ProtocolMessage::new(), &signers)
+            .await
+            .expect_err("compute_single_signature should fail");
+
+        assert_eq!(
+            Some(&RunnerError::InitializerUnavailableAfterRetries { attempts: 3 }),
+            error.downcast_ref::<RunnerError>()
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_pending_certificate_fails_if_the_stake_distribution_is_too_old() {
+        let mut services = init_services().await;
+        services.stake_store = Arc::new(StakeStore::new(
+            Box::new(MemoryAdapter::<Epoch, StakeDistribution>::new(None).unwrap()),
+            None,
+        ));
+        services
+            .stake_store
+            .save_stakes(Epoch(7), StakeDistribution::from([("1".to_string(), 123)]))
+            .await
+            .unwrap();
+        let runner = init_runner(Some(services), None)
+            .await
+            .with_max_stake_age(2);
+        let mut pending_certificate = fake_data::certificate_pending();
+        pending_certificate.epoch = Epoch(10);
+
+        let error = runner
+            .verify_pending_certificate(&pending_certificate, Epoch(10), &[])
+            .await
+            .expect_err("verify_pending_certificate should fail");
+
+        assert_eq!(
+            Some(&RunnerError::StakeDistributionTooOld {
+                certificate_epoch: Epoch(10),
+                stake_epoch: Epoch(7),
+            }),
+            error.downcast_ref::<RunnerError>()
+        );
+    }
+
     #[tokio::test]
     async fn test_send_single_signature() {
         let mut services = init_services().await;
@@ -915,6 +1560,32 @@ mod tests {
             .expect("send_single_signature should not fail");
     }
 
+    #[tokio::test]
+    async fn test_send_single_signatures_in_one_batch() {
+        let signatures = vec![
+            fake_data::single_signatures(vec![2, 5, 12]),
+            fake_data::single_signatures(vec![3, 7]),
+        ];
+        let mut services = init_services().await;
+        let mut certificate_handler = MockAggregatorClient::new();
+        {
+            let signatures = signatures.clone();
+            certificate_handler
+                .expect_register_signatures_batch()
+                .withf(move |_, batch| batch == signatures)
+                .once()
+                .returning(|_, _| Ok(()));
+        }
+        certificate_handler.expect_register_signatures().never();
+        services.certificate_handler = Arc::new(certificate_handler);
+        let runner = init_runner(Some(services), None).await;
+
+        runner
+            .send_single_signatures(&SignedEntityType::dummy(), signatures)
+            .await
+            .expect("send_single_signatures should not fail");
+    }
+
     #[tokio::test]
     async fn test_update_era_checker() {
         let services = init_services().await;
@@ -209,13 +209,19 @@ impl<'a> ServiceBuilder for ProductionServiceBuilder<'a> {
         );
 
         let signed_entity_type_lock = Arc::new(SignedEntityTypeLock::default());
-        let protocol_initializer_store = Arc::new(ProtocolInitializerStore::new(
-            Box::new(SQLiteAdapter::new(
-                "protocol_initializer",
+        let protocol_initializer_store = Arc::new(
+            ProtocolInitializerStore::new(
+                Box::new(SQLiteAdapter::new(
+                    "protocol_initializer",
+                    sqlite_connection.clone(),
+                )?),
+                self.config.store_retention_limit,
+            )
+            .with_pending_registration_adapter(Box::new(SQLiteAdapter::new(
+                "pending_registration",
                 sqlite_connection.clone(),
-            )?),
-            self.config.store_retention_limit,
-        ));
+            )?)),
+        );
         let single_signer = Arc::new(MithrilSingleSigner::new(self.compute_protocol_party_id()?));
         let digester = Arc::new(CardanoImmutableDigester::new(
             self.build_digester_cache_provider().await?,
@@ -275,12 +281,15 @@ impl<'a> ServiceBuilder for ProductionServiceBuilder<'a> {
             // Rescan the last immutable when importing transactions, it may have been partially imported
             Some(1),
         ));
-        let transactions_importer = Arc::new(CardanoTransactionsImporter::new(
-            block_scanner,
-            transaction_store.clone(),
-            &self.config.db_directory,
-            slog_scope::logger(),
-        ));
+        let transactions_importer = Arc::new(
+            CardanoTransactionsImporter::new(
+                block_scanner,
+                transaction_store.clone(),
+                &self.config.db_directory,
+                slog_scope::logger(),
+            )
+            .with_leaf_format(self.config.cardano_transactions_leaf_format),
+        );
         // Wrap the transaction importer with decorator to prune the transactions after import
         let transactions_importer = Arc::new(TransactionsImporterWithPruner::new(
             self.config
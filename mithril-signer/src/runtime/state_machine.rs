@@ -1,4 +1,4 @@
-use slog_scope::{crit, debug, error, info};
+use slog::{crit, debug, error, info, Logger};
 use std::{fmt::Display, ops::Deref, sync::Arc, time::Duration};
 use tokio::{sync::Mutex, time::sleep};
 
@@ -91,6 +91,7 @@ pub struct StateMachine {
     runner: Box<dyn Runner>,
     state_sleep: Duration,
     metrics_service: Arc<MetricsService>,
+    logger: Logger,
 }
 
 impl StateMachine {
@@ -100,12 +101,14 @@ impl StateMachine {
         runner: Box<dyn Runner>,
         state_sleep: Duration,
         metrics_service: Arc<MetricsService>,
+        logger: Logger,
     ) -> Self {
         Self {
             state: Mutex::new(starting_state),
             runner,
             state_sleep,
             metrics_service,
+            logger,
         }
     }
 
@@ -116,20 +119,42 @@ impl StateMachine {
 
     /// Launch the state machine until an error occurs or it is interrupted.
     pub async fn run(&self) -> Result<(), RuntimeError> {
-        info!("STATE MACHINE: launching");
+        self.run_observed(|_, _| {}).await
+    }
+
+    /// Launch the state machine like [run][Self::run], calling the given observer after each
+    /// cycle with the state at the start of the cycle and the result of the cycle.
+    pub async fn run_observed<F>(&self, observer: F) -> Result<(), RuntimeError>
+    where
+        F: Fn(&SignerState, &Result<(), RuntimeError>),
+    {
+        info!(self.logger, "STATE MACHINE: launching");
 
         loop {
-            if let Err(e) = self.cycle().await {
+            let state = self.get_state().await;
+            let result = self.cycle().await;
+            observer(&state, &result);
+
+            if let Err(e) = result {
                 if e.is_critical() {
-                    crit!("{e}");
+                    crit!(
+                        self.logger,
+                        "STATE MACHINE: critical error";
+                        "error" => %e
+                    );
 
                     return Err(e);
                 } else {
-                    error!("{e}");
+                    error!(
+                        self.logger,
+                        "STATE MACHINE: cycle error";
+                        "error" => %e
+                    );
                 }
             }
 
             info!(
+                self.logger,
                 "… Cycle finished, Sleeping for {} ms",
                 self.state_sleep.as_millis()
             );
@@ -140,8 +165,11 @@ impl StateMachine {
     /// Perform a cycle of the state machine.
     pub async fn cycle(&self) -> Result<(), RuntimeError> {
         let mut state = self.state.lock().await;
-        info!("================================================================================");
-        info!("STATE MACHINE: new cycle: {}", *state);
+        info!(
+            self.logger,
+            "================================================================================"
+        );
+        info!(self.logger, "STATE MACHINE: new cycle: {}", *state);
 
         self.metrics_service
             .runtime_cycle_total_since_startup_counter_increment();
@@ -152,7 +180,11 @@ impl StateMachine {
             }
             SignerState::Unregistered { epoch } => {
                 if let Some(new_epoch) = self.has_epoch_changed(*epoch).await? {
-                    info!("→ Epoch has changed, transiting to UNREGISTERED");
+                    info!(
+                        self.logger,
+                        "→ Epoch has changed, transiting to UNREGISTERED";
+                        "beacon_epoch" => ?new_epoch
+                    );
                     *state = self
                         .transition_from_unregistered_to_unregistered(new_epoch)
                         .await?;
@@ -165,27 +197,32 @@ impl StateMachine {
                         nested_error: Some(e),
                     })?
                 {
-                    info!("→ Epoch settings found");
+                    info!(self.logger, "→ Epoch settings found");
                     if epoch_settings.epoch >= *epoch {
-                        info!("new Epoch found");
-                        info!(" ⋅ transiting to REGISTERED");
+                        info!(self.logger, "new Epoch found");
+                        info!(self.logger, " ⋅ transiting to REGISTERED");
                         *state = self
                             .transition_from_unregistered_to_registered(&epoch_settings)
                             .await?;
                     } else {
                         info!(
+                            self.logger,
                             " ⋅ Epoch settings found, but its epoch is behind the known epoch, waiting…";
                             "epoch_settings" => ?epoch_settings,
                             "known_epoch" => ?epoch,
                         );
                     }
                 } else {
-                    info!("→ No epoch settings found yet, waiting…");
+                    info!(self.logger, "→ No epoch settings found yet, waiting…");
                 }
             }
             SignerState::Registered { epoch } => {
                 if let Some(new_epoch) = self.has_epoch_changed(*epoch).await? {
-                    info!("→ Epoch has changed, transiting to UNREGISTERED");
+                    info!(
+                        self.logger,
+                        "→ Epoch has changed, transiting to UNREGISTERED";
+                        "beacon_epoch" => ?new_epoch
+                    );
                     *state = self
                         .transition_from_registered_to_unregistered(new_epoch)
                         .await?;
@@ -198,6 +235,7 @@ impl StateMachine {
                     })?
                 {
                     info!(
+                        self.logger,
                         " ⋅ Epoch has NOT changed but there is a pending certificate";
                         "pending_certificate" => ?pending_certificate
                     );
@@ -211,15 +249,21 @@ impl StateMachine {
                             nested_error: Some(e),
                         })?
                     {
-                        info!(" → we can sign this certificate, transiting to SIGNED");
+                        info!(
+                            self.logger,
+                            " → we can sign this certificate, transiting to SIGNED"
+                        );
                         *state = self
-                            .transition_from_registered_to_signed(&pending_certificate)
+                            .transition_from_registered_to_signed(&pending_certificate, *epoch)
                             .await?;
                     } else {
-                        info!(" ⋅ cannot sign this pending certificate, waiting…");
+                        info!(
+                            self.logger,
+                            " ⋅ cannot sign this pending certificate, waiting…"
+                        );
                     }
                 } else {
-                    info!(" ⋅ no pending certificate, waiting…");
+                    info!(self.logger, " ⋅ no pending certificate, waiting…");
                 }
             }
             SignerState::Signed {
@@ -227,7 +271,11 @@ impl StateMachine {
                 signed_entity_type,
             } => {
                 if let Some(new_epoch) = self.has_epoch_changed(*epoch).await? {
-                    info!(" → new Epoch detected, transiting to UNREGISTERED");
+                    info!(
+                        self.logger,
+                        " → new Epoch detected, transiting to UNREGISTERED";
+                        "beacon_epoch" => ?new_epoch
+                    );
                     *state = self
                         .transition_from_signed_to_unregistered(new_epoch)
                         .await?;
@@ -240,17 +288,24 @@ impl StateMachine {
                     })?
                 {
                     info!(
+                        self.logger,
                         " ⋅ Epoch has NOT changed but there is a pending certificate";
                         "pending_certificate" => ?pending_certificate
                     );
                     if pending_certificate.signed_entity_type == *signed_entity_type {
-                        info!(" ⋅ pending certificate has not changed, waiting…");
+                        info!(
+                            self.logger,
+                            " ⋅ pending certificate has not changed, waiting…"
+                        );
                     } else {
-                        info!(" → new pending certificate detected, transiting to REGISTERED");
+                        info!(
+                            self.logger,
+                            " → new pending certificate detected, transiting to REGISTERED"
+                        );
                         *state = self.transition_from_signed_to_registered(*epoch).await?;
                     }
                 } else {
-                    info!(" ⋅ no pending certificate, waiting…");
+                    info!(self.logger, " ⋅ no pending certificate, waiting…");
                 }
             }
         };
@@ -366,6 +421,7 @@ impl StateMachine {
     async fn transition_from_registered_to_signed(
         &self,
         pending_certificate: &CertificatePending,
+        observed_epoch: Epoch,
     ) -> Result<SignerState, RuntimeError> {
         let current_epoch = pending_certificate.epoch;
         let (retrieval_epoch, next_retrieval_epoch) = (
@@ -374,8 +430,9 @@ impl StateMachine {
         );
 
         debug!(
+            self.logger,
             " > transition_from_registered_to_signed";
-            "current_epoch" => ?current_epoch,
+            "beacon_epoch" => ?current_epoch,
             "retrieval_epoch" => ?retrieval_epoch,
             "next_retrieval_epoch" => ?next_retrieval_epoch,
         );
@@ -399,6 +456,13 @@ impl StateMachine {
                 message: format!("Could not associate next signers with stakes during 'registered → signed' phase (current epoch {current_epoch:?}, next retrieval epoch {next_retrieval_epoch:?})"),
                 nested_error: Some(e)
             })?;
+        self.runner
+            .verify_pending_certificate(pending_certificate, observed_epoch, &signers)
+            .await
+            .map_err(|e| RuntimeError::KeepState {
+                message: format!("Pending certificate is not consistent with local data during 'registered → signed' phase (current epoch {current_epoch:?}, observed epoch {observed_epoch:?})"),
+                nested_error: Some(e)
+            })?;
 
         let message = self
             .runner
@@ -416,6 +480,21 @@ impl StateMachine {
                 message: format!("Could not compute single signature during 'registered → signed' phase (current epoch {current_epoch:?})"),
                 nested_error: Some(e)
             })?;
+        match &single_signatures {
+            Some(signature) => debug!(
+                self.logger,
+                " > single signature computed";
+                "beacon_epoch" => ?current_epoch,
+                "party_id" => &signature.party_id,
+                "signature_count" => signature.won_indexes.len(),
+            ),
+            None => debug!(
+                self.logger,
+                " > no lottery won, no single signature to send";
+                "beacon_epoch" => ?current_epoch,
+                "signature_count" => 0,
+            ),
+        }
         self.runner.send_single_signature(&pending_certificate.signed_entity_type, single_signatures).await
             .map_err(|e| RuntimeError::KeepState {
                 message: format!("Could not send single signature during 'registered → signed' phase (current epoch {current_epoch:?})"),
@@ -466,17 +545,78 @@ mod tests {
     use mithril_common::entities::{CardanoDbBeacon, ChainPoint, Epoch, ProtocolMessage};
     use mithril_common::test_utils::fake_data;
 
+    use slog::{Drain, OwnedKVList, Record, KV};
+    use std::sync::Mutex as StdMutex;
+
     use crate::runtime::runner::MockSignerRunner;
+    use crate::test_tools::logger_for_tests;
 
     use super::*;
 
     fn init_state_machine(init_state: SignerState, runner: MockSignerRunner) -> StateMachine {
+        init_state_machine_with_logger(init_state, runner, logger_for_tests())
+    }
+
+    fn init_state_machine_with_logger(
+        init_state: SignerState,
+        runner: MockSignerRunner,
+        logger: Logger,
+    ) -> StateMachine {
         let metrics_service = Arc::new(MetricsService::new().unwrap());
         StateMachine {
             state: init_state.into(),
             runner: Box::new(runner),
             state_sleep: Duration::from_millis(100),
             metrics_service,
+            logger,
+        }
+    }
+
+    /// Log record keeping only the message and the key/value pairs of the record
+    #[derive(Debug, Default)]
+    struct RecordedLog {
+        message: String,
+        key_values: Vec<(String, String)>,
+    }
+
+    impl slog::Serializer for RecordedLog {
+        fn emit_arguments(&mut self, key: slog::Key, val: &std::fmt::Arguments) -> slog::Result {
+            self.key_values.push((key.to_string(), val.to_string()));
+            Ok(())
+        }
+    }
+
+    /// Drain keeping the records in memory
+    #[derive(Clone, Default)]
+    struct RecordingDrain {
+        records: Arc<StdMutex<Vec<RecordedLog>>>,
+    }
+
+    impl RecordingDrain {
+        fn find_record(&self, message: &str) -> Option<Vec<(String, String)>> {
+            self.records
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|record| record.message == message)
+                .map(|record| record.key_values.clone())
+        }
+    }
+
+    impl Drain for RecordingDrain {
+        type Ok = ();
+        type Err = slog::Never;
+
+        fn log(&self, record: &Record, values: &OwnedKVList) -> Result<(), slog::Never> {
+            let mut recorded_log = RecordedLog {
+                message: record.msg().to_string(),
+                ..RecordedLog::default()
+            };
+            record.kv().serialize(record, &mut recorded_log).unwrap();
+            values.serialize(record, &mut recorded_log).unwrap();
+            self.records.lock().unwrap().push(recorded_log);
+
+            Ok(())
         }
     }
 
@@ -679,6 +819,10 @@ mod tests {
             .expect_associate_signers_with_stake()
             .times(2)
             .returning(|_, _| Ok(fake_data::signers_with_stakes(4)));
+        runner
+            .expect_verify_pending_certificate()
+            .once()
+            .returning(|_, _, _| Ok(()));
         runner
             .expect_compute_single_signature()
             .once()
@@ -709,6 +853,69 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn registered_to_signed_logs_structured_key_values() {
+        let time_point = TimePoint {
+            immutable_file_number: 99,
+            epoch: Epoch(9),
+            chain_point: ChainPoint::dummy(),
+        };
+        let state = SignerState::Registered {
+            epoch: time_point.epoch,
+        };
+        let certificate_pending = CertificatePending {
+            epoch: time_point.epoch,
+            ..fake_data::certificate_pending()
+        };
+        let single_signatures = fake_data::single_signatures(vec![1, 5, 23]);
+        let party_id = single_signatures.party_id.clone();
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_time_point()
+            .once()
+            .returning(move || Ok(time_point.to_owned()));
+        runner
+            .expect_get_pending_certificate()
+            .once()
+            .returning(move || Ok(Some(certificate_pending.clone())));
+        runner.expect_can_i_sign().once().returning(|_| Ok(true));
+        runner
+            .expect_associate_signers_with_stake()
+            .times(2)
+            .returning(|_, _| Ok(fake_data::signers_with_stakes(4)));
+        runner
+            .expect_verify_pending_certificate()
+            .once()
+            .returning(|_, _, _| Ok(()));
+        runner
+            .expect_compute_single_signature()
+            .once()
+            .returning(move |_, _, _| Ok(Some(single_signatures.clone())));
+        runner
+            .expect_compute_message()
+            .once()
+            .returning(|_, _| Ok(ProtocolMessage::new()));
+        runner
+            .expect_send_single_signature()
+            .once()
+            .returning(|_, _| Ok(()));
+        let drain = RecordingDrain::default();
+        let logger = Logger::root(drain.clone(), slog::o!());
+
+        let state_machine = init_state_machine_with_logger(state, runner, logger);
+        state_machine
+            .cycle()
+            .await
+            .expect("Cycling the state machine should not fail");
+
+        let key_values = drain
+            .find_record(" > single signature computed")
+            .expect("the single signature computation should be logged");
+        assert!(key_values.contains(&("beacon_epoch".to_string(), "Epoch(9)".to_string())));
+        assert!(key_values.contains(&("party_id".to_string(), party_id)));
+        assert!(key_values.contains(&("signature_count".to_string(), "3".to_string())));
+    }
+
     #[tokio::test]
     async fn signed_to_registered() {
         let time_point = TimePoint {
@@ -6,7 +6,8 @@ use thiserror::Error;
 
 use mithril_common::crypto_helper::{KESPeriod, ProtocolInitializer};
 use mithril_common::entities::{
-    PartyId, ProtocolMessage, ProtocolParameters, SignerWithStake, SingleSignatures, Stake,
+    CertificatePending, Epoch, PartyId, ProtocolMessage, ProtocolParameters, SignerWithStake,
+    SingleSignatures, Stake,
 };
 use mithril_common::protocol::SignerBuilder;
 use mithril_common::{StdError, StdResult};
@@ -56,6 +57,16 @@ pub trait SingleSigner: Sync + Send {
         protocol_initializer: &ProtocolInitializer,
     ) -> StdResult<Option<String>>;
 
+    /// Check that a pending certificate is consistent with the data observed locally:
+    /// its epoch must be the current epoch, its protocol parameters must be acceptable and
+    /// at least one of its signers must have a non-zero stake.
+    fn verify_pending_certificate(
+        &self,
+        pending_certificate: &CertificatePending,
+        current_epoch: Epoch,
+        signers_with_stake: &[SignerWithStake],
+    ) -> Result<(), SingleSignerError>;
+
     /// Get party id
     fn get_party_id(&self) -> PartyId;
 }
@@ -74,6 +85,10 @@ pub enum SingleSignerError {
     /// Avk computation Error
     #[error("Aggregate verification key computation Error")]
     AggregateVerificationKeyComputationFailed(#[source] StdError),
+
+    /// The pending certificate is not consistent with the data observed locally
+    #[error("Invalid pending certificate: {0}")]
+    InvalidPendingCertificate(String),
 }
 
 /// Implementation of the SingleSigner.
@@ -86,6 +101,26 @@ impl MithrilSingleSigner {
     pub fn new(party_id: PartyId) -> Self {
         Self { party_id }
     }
+
+    fn verify_protocol_parameters(
+        name: &str,
+        protocol_parameters: &ProtocolParameters,
+    ) -> Result<(), SingleSignerError> {
+        let ProtocolParameters { k, m, phi_f, .. } = protocol_parameters;
+
+        if *k == 0 || *m == 0 || k > m {
+            return Err(SingleSignerError::InvalidPendingCertificate(format!(
+                "{name} protocol parameters must verify 0 < k <= m, got k={k} and m={m}"
+            )));
+        }
+        if !(*phi_f > 0.0 && *phi_f <= 1.0) {
+            return Err(SingleSignerError::InvalidPendingCertificate(format!(
+                "{name} protocol parameters must verify 0 < phi_f <= 1, got phi_f={phi_f}"
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 impl SingleSigner for MithrilSingleSigner {
@@ -159,6 +194,29 @@ impl SingleSigner for MithrilSingleSigner {
         Ok(Some(encoded_avk))
     }
 
+    fn verify_pending_certificate(
+        &self,
+        pending_certificate: &CertificatePending,
+        current_epoch: Epoch,
+        signers_with_stake: &[SignerWithStake],
+    ) -> Result<(), SingleSignerError> {
+        if pending_certificate.epoch != current_epoch {
+            return Err(SingleSignerError::InvalidPendingCertificate(format!(
+                "pending certificate epoch {} does not match the current epoch {current_epoch}",
+                pending_certificate.epoch
+            )));
+        }
+        Self::verify_protocol_parameters("current", &pending_certificate.protocol_parameters)?;
+        Self::verify_protocol_parameters("next", &pending_certificate.next_protocol_parameters)?;
+        if !signers_with_stake.iter().any(|s| s.stake > 0) {
+            return Err(SingleSignerError::InvalidPendingCertificate(
+                "no signer with a non-zero stake in the stake distribution".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Get party id
     fn get_party_id(&self) -> PartyId {
         self.party_id.clone()
@@ -170,8 +228,9 @@ mod tests {
     use super::*;
 
     use mithril_common::{
-        crypto_helper::ProtocolClerk, entities::ProtocolMessagePartKey,
-        test_utils::MithrilFixtureBuilder,
+        crypto_helper::ProtocolClerk,
+        entities::ProtocolMessagePartKey,
+        test_utils::{fake_data, MithrilFixtureBuilder},
     };
 
     #[test]
@@ -227,4 +286,77 @@ mod tests {
             .expect("compute aggregate verification signature should not fail")
             .expect("aggregate verification signature should not be empty");
     }
+
+    #[test]
+    fn verify_valid_pending_certificate() {
+        let pending_certificate = fake_data::certificate_pending();
+        let signers_with_stake = fake_data::signers_with_stakes(3);
+        let single_signer = MithrilSingleSigner::new(signers_with_stake[0].party_id.clone());
+
+        single_signer
+            .verify_pending_certificate(
+                &pending_certificate,
+                pending_certificate.epoch,
+                &signers_with_stake,
+            )
+            .expect("pending certificate should be valid");
+    }
+
+    #[test]
+    fn verify_pending_certificate_fails_with_an_empty_stake_distribution() {
+        let pending_certificate = fake_data::certificate_pending();
+        let single_signer = MithrilSingleSigner::new("party_id".to_string());
+
+        let error = single_signer
+            .verify_pending_certificate(&pending_certificate, pending_certificate.epoch, &[])
+            .expect_err("pending certificate should be invalid");
+
+        assert!(
+            matches!(error, SingleSignerError::InvalidPendingCertificate(_)),
+            "unexpected error: {error:?}"
+        );
+    }
+
+    #[test]
+    fn verify_pending_certificate_fails_with_an_unexpected_epoch() {
+        let pending_certificate = fake_data::certificate_pending();
+        let signers_with_stake = fake_data::signers_with_stakes(3);
+        let single_signer = MithrilSingleSigner::new(signers_with_stake[0].party_id.clone());
+
+        let error = single_signer
+            .verify_pending_certificate(
+                &pending_certificate,
+                pending_certificate.epoch + 1,
+                &signers_with_stake,
+            )
+            .expect_err("pending certificate should be invalid");
+
+        assert!(
+            matches!(error, SingleSignerError::InvalidPendingCertificate(_)),
+            "unexpected error: {error:?}"
+        );
+    }
+
+    #[test]
+    fn verify_pending_certificate_fails_with_invalid_protocol_parameters() {
+        let pending_certificate = CertificatePending {
+            protocol_parameters: ProtocolParameters::new(10, 5, 0.65),
+            ..fake_data::certificate_pending()
+        };
+        let signers_with_stake = fake_data::signers_with_stakes(3);
+        let single_signer = MithrilSingleSigner::new(signers_with_stake[0].party_id.clone());
+
+        let error = single_signer
+            .verify_pending_certificate(
+                &pending_certificate,
+                pending_certificate.epoch,
+                &signers_with_stake,
+            )
+            .expect_err("pending certificate should be invalid");
+
+        assert!(
+            matches!(error, SingleSignerError::InvalidPendingCertificate(_)),
+            "unexpected error: {error:?}"
+        );
+    }
 }
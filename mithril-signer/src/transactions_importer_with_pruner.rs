@@ -80,6 +80,7 @@ mod tests {
     }
 
     impl TransactionsImporterWithPruner {
+        /// Create an importer with a mocked pruner and importer, configured by the given closures
         pub fn new_with_mock<P, I>(
             number_of_blocks_to_keep: Option<BlockNumber>,
             transaction_pruner_mock_config: P,
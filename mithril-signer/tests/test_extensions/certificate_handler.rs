@@ -137,6 +137,15 @@ impl AggregatorClient for FakeAggregator {
     ) -> Result<(), AggregatorClientError> {
         Ok(())
     }
+
+    /// Registers a batch of single signatures with the aggregator
+    async fn register_signatures_batch(
+        &self,
+        _signed_entity_type: &SignedEntityType,
+        _signatures: &[SingleSignatures],
+    ) -> Result<(), AggregatorClientError> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -228,6 +228,7 @@ impl StateMachineTester {
             runner,
             Duration::from_secs(5),
             metrics_service.clone(),
+            slog_scope::logger(),
         );
 
         Ok(StateMachineTester {
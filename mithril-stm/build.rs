@@ -5,6 +5,8 @@ fn main() {
     let target_no_batch_verify_aggregates =
         target_arch.eq("wasm32") || env::var("STM_TEST_NO_BATCH_VERIFY_AGGREGATES").is_ok();
 
+    println!("cargo::rustc-check-cfg=cfg(feature, values(\"batch-verify-aggregates\"))");
+
     if !target_no_batch_verify_aggregates {
         println!("cargo:rustc-cfg=feature=\"batch-verify-aggregates\"");
     }
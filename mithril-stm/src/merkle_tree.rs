@@ -367,7 +367,7 @@ impl<D: Clone + Digest> MerkleTreeCommitmentBatchCompat<D> {
                         new_hashes.push(
                             D::new()
                                 .chain(&leaves[i])
-                                .chain(&D::digest([0u8]))
+                                .chain(D::digest([0u8]))
                                 .finalize()
                                 .to_vec(),
                         );
@@ -488,9 +488,9 @@ impl<D: Digest + FixedOutput> MerkleTree<D> {
     /// 2. Given an input vector `v = v1, . . .,vl`, if `v.len() == 1`, return `proof`, else, continue.
     /// 3. Map each `vi` to the corresponding number of the leaf (by adding the offset).
     /// 4. Initialise a new empty vector `p = []`. Next, iterate over each element `vi`
-    ///     a. Append the parent of `vi` to `p`
-    ///     b. Compute the sibling, `si` of `vi`
-    ///     c. If `si == v(i+1)` then do nothing, and skip step four for `v(i+1)`. Else append `si` to `proof`
+    ///    a. Append the parent of `vi` to `p`
+    ///    b. Compute the sibling, `si` of `vi`
+    ///    c. If `si == v(i+1)` then do nothing, and skip step four for `v(i+1)`. Else append `si` to `proof`
     /// 5. Iterate from step 2 with input vector `p`
     ///
     /// # Panics
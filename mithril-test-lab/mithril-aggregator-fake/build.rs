@@ -12,6 +12,12 @@ fn main() {
     let data_folder_path: &Path = Path::new("./default_data");
     let data = FakeAggregatorData::load_from_folder(data_folder_path);
     let generated_code = data.generate_code_for_all_data();
+    if let Err(errors) = FakeAggregatorData::verify_generated_code(&generated_code) {
+        panic!(
+            "Invalid code generated from the fake aggregator data:\n{}",
+            errors.join("\n")
+        );
+    }
     fs::write(dest_path, generated_code).unwrap();
 
     println!("cargo:rerun-if-changed=default_data/");
@@ -64,6 +64,7 @@ pub async fn update_protocol_parameters(aggregator: &mut Aggregator) -> StdResul
         k: 150,
         m: 210,
         phi_f: 0.80,
+        weighted: false,
     };
     info!(
         "> updating protocol parameters to {:?}...",
@@ -113,11 +113,7 @@ impl MithrilInfrastructure {
             chain_observer_type,
         })?;
 
-        aggregator.set_protocol_parameters(&ProtocolParameters {
-            k: 75,
-            m: 105,
-            phi_f: 0.95,
-        });
+        aggregator.set_protocol_parameters(&ProtocolParameters::new(75, 105, 0.95));
         if config.mithril_era_reader_adapter == "cardano-chain" {
             assertions::register_era_marker(&mut aggregator, &config.devnet, &config.mithril_era)
                 .await?;
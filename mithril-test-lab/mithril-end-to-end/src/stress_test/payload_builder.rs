@@ -42,6 +42,7 @@ pub fn generate_register_signer_message(
                 .operational_certificate
                 .map(|o| o.to_json_hex().unwrap()),
             kes_period: signer.kes_period,
+            registration_nonce: signer.registration_nonce,
         })
         .collect::<Vec<_>>()
 }
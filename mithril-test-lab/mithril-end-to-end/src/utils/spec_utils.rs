@@ -30,7 +30,6 @@ macro_rules! attempt {
 
 #[cfg(test)]
 mod tests {
-    use crate::attempt;
     use crate::utils::AttemptResult;
     use std::time::Duration;
     use tokio::time::Instant;